@@ -1,11 +1,15 @@
 //! PQC Performance Benchmarks under strict post-quantum mode.
 //!
 //! Measures: ML-DSA sign/verify, SHA3-256 hashing, block validation,
-//! RocksDB persistence, invalid flood rejection, and full node throughput.
+//! RocksDB persistence, invalid flood rejection, full node throughput, and
+//! batch (parallel, cached) signature verification.
 
-use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::sync::Arc;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 
 use rust_bc::crypto::hasher::{hash_with, HashAlgorithm};
+use rust_bc::identity::batch_verify::{BatchVerifier, SignatureCheck, VerifiedSignatureCache};
 use rust_bc::identity::pqc_policy::validate_signature_consistency;
 use rust_bc::identity::signing::{
     MlDsaSigningProvider, SigningAlgorithm, SigningProvider, SoftwareSigningProvider,
@@ -113,6 +117,13 @@ fn make_pqc_block(height: u64, signer: &MlDsaSigningProvider) -> Block {
         secondary_signature_algorithm: None,
         hash_algorithm: HashAlgorithm::Sha3_256,
         orderer_signature: None,
+        commit_qc: None,
+        next_validator_set: None,
+        evidence: Vec::new(),
+        config_tx: None,
+        last_config: 0,
+        state_root: [0u8; 32],
+        private_data_purges: vec![],
     }
 }
 
@@ -257,6 +268,50 @@ fn bench_full_node_throughput(c: &mut Criterion) {
     });
 }
 
+// ═══════════════════════════════════════════════════════════════════
+// 8. Batch signature verification (500-signature block)
+// ═══════════════════════════════════════════════════════════════════
+
+fn bench_batch_signature_verification(c: &mut Criterion) {
+    const SIGNATURES: usize = 500;
+    let signers: Vec<MlDsaSigningProvider> =
+        (0..8).map(|_| MlDsaSigningProvider::generate()).collect();
+    let checks: Vec<SignatureCheck> = (0..SIGNATURES)
+        .map(|i| {
+            let signer = &signers[i % signers.len()];
+            let payload = hash_with(HashAlgorithm::Sha3_256, format!("tx-{i}").as_bytes());
+            SignatureCheck::new(payload, signer.public_key(), signer.sign(&payload).unwrap())
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("mldsa65_batch_verify");
+    group.throughput(Throughput::Elements(SIGNATURES as u64));
+    group.sample_size(10);
+
+    group.bench_function("sequential", |b| {
+        b.iter(|| {
+            for check in &checks {
+                assert!(black_box(check).verify());
+            }
+        })
+    });
+
+    // Cache disabled: every signature is verified, spread over the rayon pool.
+    let uncached = BatchVerifier::new(Arc::new(VerifiedSignatureCache::new(0)));
+    group.bench_function("parallel", |b| {
+        b.iter(|| uncached.verify_batch(black_box(&checks)))
+    });
+
+    // Warm cache: signatures already checked at submission.
+    let cached = BatchVerifier::new(Arc::new(VerifiedSignatureCache::new(SIGNATURES)));
+    cached.verify_batch(&checks);
+    group.bench_function("parallel_cached", |b| {
+        b.iter(|| cached.verify_batch(black_box(&checks)))
+    });
+
+    group.finish();
+}
+
 // ═══════════════════════════════════════════════════════════════════
 
 criterion_group!(
@@ -270,5 +325,6 @@ criterion_group!(
     bench_rocksdb_write_read,
     bench_invalid_flood_rejection,
     bench_full_node_throughput,
+    bench_batch_signature_verification,
 );
 criterion_main!(benches);
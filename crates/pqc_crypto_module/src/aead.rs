@@ -0,0 +1,138 @@
+//! AES-256-GCM authenticated encryption (FIPS 197, SP 800-38D).
+//!
+//! Used for envelope encryption of data at rest: the data key is a 32-byte
+//! AES key, the ciphertext carries its 16-byte tag at the end.
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+
+use crate::approved_mode::require_approved;
+use crate::errors::CryptoError;
+
+/// AES-256 key length in bytes.
+pub const KEY_LEN: usize = 32;
+/// GCM nonce length in bytes.
+pub const NONCE_LEN: usize = 12;
+/// GCM tag length in bytes.
+pub const TAG_LEN: usize = 16;
+
+/// Encrypt `plaintext` under `key` and `nonce`, authenticating `aad`.
+/// Returns `ciphertext || tag`. Requires approved mode.
+pub fn seal(
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    require_approved()?;
+    seal_raw(key, nonce, aad, plaintext)
+}
+
+pub(crate) fn seal_raw(
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    let cipher = cipher(key, nonce)?;
+    cipher
+        .encrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .map_err(|_| CryptoError::EncryptionFailed)
+}
+
+/// Decrypt `ciphertext || tag` under `key` and `nonce`, checking `aad`.
+/// Requires approved mode.
+pub fn open(
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    require_approved()?;
+    open_raw(key, nonce, aad, ciphertext)
+}
+
+pub(crate) fn open_raw(
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    let cipher = cipher(key, nonce)?;
+    cipher
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad,
+            },
+        )
+        .map_err(|_| CryptoError::DecryptionFailed)
+}
+
+fn cipher(key: &[u8], nonce: &[u8]) -> Result<Aes256Gcm, CryptoError> {
+    if nonce.len() != NONCE_LEN {
+        return Err(CryptoError::InvalidKey(format!(
+            "AES-GCM nonce must be {NONCE_LEN} bytes"
+        )));
+    }
+    Aes256Gcm::new_from_slice(key)
+        .map_err(|_| CryptoError::InvalidKey(format!("AES-256 key must be {KEY_LEN} bytes")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approved_mode::{set_state, ModuleState};
+
+    #[test]
+    fn gcm_known_answer() {
+        // SP 800-38D test case 14: zero key, zero IV, one zero block.
+        let sealed = seal_raw(&[0u8; 32], &[0u8; 12], b"", &[0u8; 16]).unwrap();
+        assert_eq!(
+            hex::encode(sealed),
+            "cea7403d4d606b6e074ec5d3baf39d18d0d1c8a799996bf0265b98b5d48ab919"
+        );
+    }
+
+    #[test]
+    fn seal_open_roundtrip() {
+        let key = [7u8; KEY_LEN];
+        let nonce = [9u8; NONCE_LEN];
+        let ct = seal_raw(&key, &nonce, b"aad", b"hello").unwrap();
+        assert_eq!(ct.len(), 5 + TAG_LEN);
+        assert_eq!(open_raw(&key, &nonce, b"aad", &ct).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn open_rejects_tampering() {
+        let key = [7u8; KEY_LEN];
+        let nonce = [9u8; NONCE_LEN];
+        let mut ct = seal_raw(&key, &nonce, b"aad", b"hello").unwrap();
+        assert!(open_raw(&key, &nonce, b"other", &ct).is_err());
+        ct[0] ^= 1;
+        assert!(matches!(
+            open_raw(&key, &nonce, b"aad", &ct),
+            Err(CryptoError::DecryptionFailed)
+        ));
+    }
+
+    #[test]
+    fn bad_key_length_rejected() {
+        assert!(seal_raw(&[0u8; 16], &[0u8; 12], b"", b"x").is_err());
+        assert!(seal_raw(&[0u8; 32], &[0u8; 8], b"", b"x").is_err());
+    }
+
+    #[test]
+    fn aead_api_works_after_approved_mode() {
+        set_state(ModuleState::Approved);
+        let ct = seal(&[1u8; 32], &[2u8; 12], b"", b"data").unwrap();
+        assert_eq!(open(&[1u8; 32], &[2u8; 12], b"", &ct).unwrap(), b"data");
+    }
+}
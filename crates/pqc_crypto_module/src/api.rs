@@ -81,6 +81,26 @@ pub fn mlkem_decapsulate(
     crate::mlkem::decapsulate(private_key, ciphertext)
 }
 
+/// AES-256-GCM encryption. Returns `ciphertext || tag`.
+pub fn aes256gcm_seal(
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    crate::aead::seal(key, nonce, aad, plaintext)
+}
+
+/// AES-256-GCM decryption of `ciphertext || tag`.
+pub fn aes256gcm_open(
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    crate::aead::open(key, nonce, aad, ciphertext)
+}
+
 /// Generate cryptographically secure random bytes.
 pub fn random_bytes(n: usize) -> Result<Vec<u8>, CryptoError> {
     require_approved()?;
@@ -24,4 +24,8 @@ pub enum CryptoError {
     SerializationError(String),
     #[error("invalid key length for HMAC")]
     InvalidKeyLength,
+    #[error("AEAD encryption failed")]
+    EncryptionFailed,
+    #[error("AEAD decryption failed — wrong key or tampered ciphertext")]
+    DecryptionFailed,
 }
@@ -22,6 +22,7 @@
 //! let hash = api::sha3_256(b"data").unwrap();
 //! ```
 
+pub mod aead;
 pub mod api;
 pub mod approved_mode;
 pub mod errors;
@@ -2,6 +2,7 @@
 //!
 //! Run at module initialization before transitioning to Approved state.
 
+use crate::aead::{open_raw as aead_open_raw, seal_raw as aead_seal_raw};
 use crate::errors::CryptoError;
 use crate::hashing::sha3_256_raw;
 use crate::mldsa::{generate_keypair_raw, sign_message_raw, verify_signature_raw};
@@ -13,6 +14,7 @@ pub fn run_all() -> Result<(), CryptoError> {
     kat_sha3_256()?;
     kat_mldsa65()?;
     kat_mlkem()?;
+    kat_aes256gcm()?;
     test_rng()?;
     Ok(())
 }
@@ -97,6 +99,29 @@ fn kat_mlkem() -> Result<(), CryptoError> {
     Ok(())
 }
 
+fn kat_aes256gcm() -> Result<(), CryptoError> {
+    // SP 800-38D test case 14: zero key, zero IV, one zero block.
+    let sealed = aead_seal_raw(&[0u8; 32], &[0u8; 12], b"", &[0u8; 16])
+        .map_err(|e| CryptoError::SelfTestFailed(format!("AES-GCM seal: {e}")))?;
+    let expected = "cea7403d4d606b6e074ec5d3baf39d18d0d1c8a799996bf0265b98b5d48ab919";
+    if hex::encode(&sealed) != expected {
+        return Err(CryptoError::SelfTestFailed(
+            "AES-GCM KAT: ciphertext or tag mismatch".into(),
+        ));
+    }
+
+    // Corrupted tag must fail
+    let mut bad = sealed.clone();
+    bad[31] ^= 0x01;
+    if aead_open_raw(&[0u8; 32], &[0u8; 12], b"", &bad).is_ok() {
+        return Err(CryptoError::SelfTestFailed(
+            "AES-GCM KAT: corrupted tag was accepted".into(),
+        ));
+    }
+
+    Ok(())
+}
+
 fn test_rng() -> Result<(), CryptoError> {
     continuous_rng_test()?;
     Ok(())
@@ -1,5 +1,12 @@
 //! Standard ACL resource identifiers, mirroring Hyperledger Fabric's resource names.
 
+use crate::acl::AclProvider;
+use crate::storage::errors::StorageResult;
+
+/// Policy the query resources (`qscc/*` reads) are bound to by default,
+/// like Fabric's `/Channel/Application/Readers`.
+pub const READERS_POLICY: &str = "Readers";
+
 /// Well-known ACL resources.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AclResource {
@@ -18,6 +25,13 @@ pub enum AclResource {
     #[allow(dead_code)]
     PrivateDataWrite,
     #[allow(dead_code)]
+    PrivateDataPurge,
+    #[allow(dead_code)]
+    ChannelWriters,
+    QsccGetState,
+    QsccGetHistoryForKey,
+    QsccGetQueryResult,
+    #[allow(dead_code)]
     Custom(String),
 }
 
@@ -33,9 +47,44 @@ impl AclResource {
             Self::PeerDiscovery => "peer/Discovery",
             Self::PrivateDataRead => "peer/PrivateData.Read",
             Self::PrivateDataWrite => "peer/PrivateData.Write",
+            Self::PrivateDataPurge => "peer/PrivateData.Purge",
+            Self::ChannelWriters => "channel/Writers",
+            Self::QsccGetState => "qscc/GetState",
+            Self::QsccGetHistoryForKey => "qscc/GetHistoryForKey",
+            Self::QsccGetQueryResult => "qscc/GetQueryResult",
             Self::Custom(name) => name.as_str(),
         }
     }
+
+    /// Policy the resource is bound to when no ACL entry was set for it.
+    pub fn default_policy_ref(&self) -> Option<&'static str> {
+        match self {
+            Self::QsccGetState | Self::QsccGetHistoryForKey | Self::QsccGetQueryResult => {
+                Some(READERS_POLICY)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Bind each resource that has a default policy to it, keeping entries
+/// already set. Returns how many entries were added.
+pub fn install_default_acls(acl_provider: &dyn AclProvider) -> StorageResult<usize> {
+    let mut added = 0;
+    for resource in [
+        AclResource::QsccGetState,
+        AclResource::QsccGetHistoryForKey,
+        AclResource::QsccGetQueryResult,
+    ] {
+        let Some(policy_ref) = resource.default_policy_ref() else {
+            continue;
+        };
+        if acl_provider.get_acl(resource.resource_name())?.is_none() {
+            acl_provider.set_acl(resource.resource_name(), policy_ref)?;
+            added += 1;
+        }
+    }
+    Ok(added)
 }
 
 #[cfg(test)]
@@ -66,9 +115,44 @@ mod tests {
             AclResource::PrivateDataWrite.resource_name(),
             "peer/PrivateData.Write"
         );
+        assert_eq!(
+            AclResource::PrivateDataPurge.resource_name(),
+            "peer/PrivateData.Purge"
+        );
+        assert_eq!(
+            AclResource::ChannelWriters.resource_name(),
+            "channel/Writers"
+        );
+        assert_eq!(AclResource::QsccGetState.resource_name(), "qscc/GetState");
+        assert_eq!(
+            AclResource::QsccGetHistoryForKey.resource_name(),
+            "qscc/GetHistoryForKey"
+        );
+        assert_eq!(
+            AclResource::QsccGetQueryResult.resource_name(),
+            "qscc/GetQueryResult"
+        );
         assert_eq!(
             AclResource::Custom("my/Resource".to_string()).resource_name(),
             "my/Resource"
         );
     }
+
+    #[test]
+    fn default_acls_bind_query_resources_to_readers() {
+        use crate::acl::MemoryAclProvider;
+
+        let acl = MemoryAclProvider::new();
+        acl.set_acl("qscc/GetQueryResult", "AuditorsPolicy")
+            .unwrap();
+        assert_eq!(install_default_acls(&acl).unwrap(), 2);
+
+        let policy = |r: &str| acl.get_acl(r).unwrap().unwrap().policy_ref;
+        assert_eq!(policy("qscc/GetState"), READERS_POLICY);
+        assert_eq!(policy("qscc/GetHistoryForKey"), READERS_POLICY);
+        // Entries set by the operator are kept.
+        assert_eq!(policy("qscc/GetQueryResult"), "AuditorsPolicy");
+        assert_eq!(install_default_acls(&acl).unwrap(), 0);
+        assert!(acl.get_acl("peer/BlockEvents").unwrap().is_none());
+    }
 }
@@ -125,6 +125,24 @@ impl ApiError {
     }
 }
 
+/// Orderer admission refusals: unauthorised submitters get 403, replays
+/// 409, anything else about the transaction itself 400.
+impl From<crate::ordering::admission::AdmissionError> for ApiError {
+    fn from(e: crate::ordering::admission::AdmissionError) -> Self {
+        use crate::ordering::admission::AdmissionError;
+        match e {
+            AdmissionError::Forbidden(reason) => ApiError::Forbidden { reason },
+            AdmissionError::Duplicate(_) => ApiError::Conflict {
+                reason: e.to_string(),
+            },
+            _ => ApiError::ValidationError {
+                field: "transaction".to_string(),
+                reason: e.to_string(),
+            },
+        }
+    }
+}
+
 impl ResponseError for ApiError {
     fn error_response(&self) -> HttpResponse {
         let status = self.status_code();
@@ -175,6 +193,7 @@ fn required_role_for_resource(resource: &str) -> Option<crate::msp::MspRole> {
         | "peer/MSP.Admin"
         | "peer/Discovery.Admin"
         | "qscc/Snapshot.Admin"
+        | "peer/PrivateData.Purge"
         | "peer/ChannelConfig" => Some(MspRole::Admin),
         // Writer operations — Client or Peer role required
         "peer/Propose"
@@ -2,6 +2,7 @@
 
 pub mod acl;
 pub mod audit;
+pub mod backups;
 pub mod blocks;
 pub mod chain;
 pub mod chaincode;
@@ -11,6 +12,7 @@ pub mod compliance_auto;
 pub mod contact;
 pub mod credentials;
 pub mod discovery;
+pub mod encryption;
 pub mod events;
 #[cfg(feature = "evm")]
 pub mod evm;
@@ -19,6 +21,7 @@ pub mod gateway;
 pub mod governance;
 pub mod governance_entities;
 pub mod identity;
+pub mod indexes;
 pub mod intelligence;
 pub mod interop;
 pub mod legal_oracle;
@@ -32,6 +35,7 @@ pub mod proposals;
 pub mod registry;
 pub mod regulatory;
 pub mod snapshots;
+pub mod state;
 pub mod stress;
 pub mod tokenization;
 pub mod transactions;
@@ -0,0 +1,139 @@
+//! Admin backup endpoints:
+//!   POST /api/v1/admin/backups — take an online backup of the node's databases
+//!   GET  /api/v1/admin/backups — list the backups available for restore
+//!
+//! Restore runs offline with `bcctl restore`, never through the API.
+
+use std::sync::Arc;
+
+use actix_web::{get, post, web, HttpRequest, HttpResponse};
+
+use crate::api::errors::{enforce_acl, ApiError, ApiResponse, ApiResult};
+use crate::app_state::AppState;
+use crate::storage::backup::BackupProvider;
+
+fn backup_provider(state: &AppState) -> ApiResult<Arc<dyn BackupProvider>> {
+    state
+        .backup_provider
+        .clone()
+        .ok_or_else(|| ApiError::NotFound {
+            resource: "backups (requires STORAGE_BACKEND=rocksdb)".to_string(),
+        })
+}
+
+/// `POST /api/v1/admin/backups` — take a backup while the node keeps running.
+#[post("/admin/backups")]
+pub async fn create_backup(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+) -> ApiResult<HttpResponse> {
+    enforce_acl(
+        state.acl_provider.as_deref(),
+        state.policy_store.as_deref(),
+        "peer/Admin",
+        &req,
+    )?;
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    let provider = backup_provider(&state)?;
+
+    let backup = tokio::task::spawn_blocking(move || provider.create_backup())
+        .await
+        .map_err(|e| ApiError::InternalError {
+            reason: e.to_string(),
+        })?
+        .map_err(|e| ApiError::StorageError {
+            reason: e.to_string(),
+        })?;
+
+    Ok(HttpResponse::Created().json(ApiResponse::success(backup, trace_id)))
+}
+
+/// `GET /api/v1/admin/backups` — list backups, oldest first.
+#[get("/admin/backups")]
+pub async fn list_backups(req: HttpRequest, state: web::Data<AppState>) -> ApiResult<HttpResponse> {
+    enforce_acl(
+        state.acl_provider.as_deref(),
+        state.policy_store.as_deref(),
+        "peer/Admin",
+        &req,
+    )?;
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    let backups = backup_provider(&state)?
+        .list_backups()
+        .map_err(|e| ApiError::StorageError {
+            reason: e.to_string(),
+        })?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(backups, trace_id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+    use std::sync::Mutex;
+
+    use crate::storage::backup::NodeBackup;
+    use crate::storage::errors::StorageResult;
+
+    #[derive(Default)]
+    struct FakeBackups {
+        taken: Mutex<Vec<NodeBackup>>,
+    }
+
+    impl BackupProvider for FakeBackups {
+        fn create_backup(&self) -> StorageResult<NodeBackup> {
+            let mut taken = self.taken.lock().unwrap();
+            let backup = NodeBackup {
+                id: taken.len() as u64 + 1,
+                created_at: 0,
+                schema_version: 3,
+                latest_height: 4,
+                databases: vec![],
+            };
+            taken.push(backup.clone());
+            Ok(backup)
+        }
+
+        fn list_backups(&self) -> StorageResult<Vec<NodeBackup>> {
+            Ok(self.taken.lock().unwrap().clone())
+        }
+    }
+
+    #[actix_web::test]
+    async fn backups_are_taken_and_listed() {
+        std::env::set_var("ACL_MODE", "permissive");
+        let mut state = AppState::test_default();
+        state.backup_provider = Some(Arc::new(FakeBackups::default()));
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(state))
+                .service(create_backup)
+                .service(list_backups),
+        )
+        .await;
+
+        let req = test::TestRequest::post().uri("/admin/backups").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+
+        let req = test::TestRequest::get().uri("/admin/backups").to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["data"][0]["id"], 1);
+        assert_eq!(body["data"][0]["latest_height"], 4);
+    }
+
+    #[actix_web::test]
+    async fn backups_need_a_rocksdb_node() {
+        std::env::set_var("ACL_MODE", "permissive");
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(AppState::test_default()))
+                .service(create_backup),
+        )
+        .await;
+        let req = test::TestRequest::post().uri("/admin/backups").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 404);
+    }
+}
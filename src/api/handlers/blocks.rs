@@ -1,4 +1,5 @@
 use actix_web::{get, post, web, HttpRequest, HttpResponse};
+use serde::Serialize;
 
 use crate::api::errors::{ApiError, ApiResponse, ApiResult};
 use crate::api::handlers::channels::{
@@ -7,6 +8,54 @@ use crate::api::handlers::channels::{
 use crate::api::models::CreateBlockRequest;
 use crate::app_state::AppState;
 use crate::block_creation;
+use crate::storage::traits::{Block, BlockStore};
+
+/// A block as returned by the `/blocks` endpoints, flagged with its finality.
+#[derive(Debug, Serialize)]
+pub struct BlockView {
+    #[serde(flatten)]
+    pub block: Block,
+    /// `true` once the block can no longer be reorganized away.
+    pub finalized: bool,
+}
+
+/// Finality summary returned by `GET /api/v1/blocks/finalized`.
+#[derive(Debug, Serialize)]
+pub struct FinalityInfo {
+    /// Height of the latest block, `None` for an empty chain.
+    pub tip: Option<u64>,
+    /// Highest finalized height, `None` while nothing is final yet.
+    pub finalized_height: Option<u64>,
+    /// Depth rule in blocks (`MAX_REORG_DEPTH`).
+    pub depth: u64,
+    /// Highest height finalized by a BFT commit QC, if any.
+    pub qc_finalized_height: Option<u64>,
+}
+
+/// Attach the `finalized` flag to `blocks` using the node's finality rule.
+fn block_views(
+    state: &AppState,
+    store: &dyn BlockStore,
+    blocks: Vec<Block>,
+) -> ApiResult<Vec<BlockView>> {
+    let (tip, _) = state
+        .finality
+        .refresh(store)
+        .map_err(|e| ApiError::StorageError {
+            reason: e.to_string(),
+        })?;
+    Ok(blocks
+        .into_iter()
+        .map(|block| {
+            let finalized = state.finality.is_finalized(block.height, tip);
+            BlockView { block, finalized }
+        })
+        .collect())
+}
+
+fn block_view(state: &AppState, store: &dyn BlockStore, block: Block) -> ApiResult<BlockView> {
+    Ok(block_views(state, store, vec![block])?.remove(0))
+}
 
 /// POST /api/v1/blocks — crea un bloque (lógica en `block_creation::try_create_block`).
 #[post("")]
@@ -37,6 +86,7 @@ pub async fn list_blocks(state: web::Data<AppState>) -> ApiResult<HttpResponse>
         .map_err(|e| ApiError::StorageError {
             reason: e.to_string(),
         })?;
+    let blocks = block_views(&state, store.as_ref(), blocks)?;
     let body = ApiResponse::success(blocks, trace_id);
     Ok(HttpResponse::Ok().json(body))
 }
@@ -51,13 +101,37 @@ pub async fn get_block_by_index(
     let trace_id = uuid::Uuid::new_v4().to_string();
     let store = get_channel_store(&state, "default")?;
     match store.read_block(idx) {
-        Ok(block) => Ok(HttpResponse::Ok().json(ApiResponse::success(block, trace_id))),
+        Ok(block) => {
+            let block = block_view(&state, store.as_ref(), block)?;
+            Ok(HttpResponse::Ok().json(ApiResponse::success(block, trace_id)))
+        }
         Err(_) => Err(ApiError::NotFound {
             resource: format!("block index {idx}"),
         }),
     }
 }
 
+/// GET /api/v1/blocks/finalized — tip and highest finalized height.
+#[get("/finalized")]
+pub async fn finalized_height(state: web::Data<AppState>) -> ApiResult<HttpResponse> {
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    let store = get_channel_store(&state, "default")?;
+    let (tip, finalized_height) =
+        state
+            .finality
+            .refresh(store.as_ref())
+            .map_err(|e| ApiError::StorageError {
+                reason: e.to_string(),
+            })?;
+    let info = FinalityInfo {
+        tip,
+        finalized_height,
+        depth: state.finality.depth(),
+        qc_finalized_height: state.finality.qc_finalized_height(),
+    };
+    Ok(HttpResponse::Ok().json(ApiResponse::success(info, trace_id)))
+}
+
 /// GET /api/v1/blocks/{hash} — block by hash (legacy, not indexed in BlockStore).
 #[get("/{hash}")]
 pub async fn get_block_by_hash(
@@ -143,6 +217,7 @@ pub async fn explorer_list_blocks(
         .map_err(|e| ApiError::StorageError {
             reason: e.to_string(),
         })?;
+    let blocks = block_views(&state, store.as_ref(), blocks)?;
     let resp = crate::api::pagination::PaginatedResponse::new(blocks, total, &query);
     Ok(HttpResponse::Ok().json(ApiResponse::success(resp, trace_id)))
 }
@@ -156,8 +231,16 @@ pub async fn explorer_get_block(
     let height = *path;
     let trace_id = uuid::Uuid::new_v4().to_string();
     let store = get_channel_store(&state, "default")?;
-    match store.read_block(height) {
-        Ok(block) => Ok(HttpResponse::Ok().json(ApiResponse::success(block, trace_id))),
+    // Blocks pruned by the retention policy are served in full from the archive.
+    let archived = state
+        .block_archive
+        .as_ref()
+        .and_then(|archive| archive.read_block(store.as_ref(), height).ok());
+    match archived.map_or_else(|| store.read_block(height), Ok) {
+        Ok(block) => {
+            let block = block_view(&state, store.as_ref(), block)?;
+            Ok(HttpResponse::Ok().json(ApiResponse::success(block, trace_id)))
+        }
         Err(_) => Err(ApiError::NotFound {
             resource: format!("block height {height}"),
         }),
@@ -167,8 +250,8 @@ pub async fn explorer_get_block(
 #[cfg(test)]
 mod tests {
     use super::{
-        create_block, get_block_by_hash, get_block_by_index, list_blocks, store_get_block,
-        store_latest_height,
+        create_block, finalized_height, get_block_by_hash, get_block_by_index, list_blocks,
+        store_get_block, store_latest_height,
     };
 
     #[test]
@@ -178,6 +261,7 @@ mod tests {
             list_blocks,
             get_block_by_index,
             get_block_by_hash,
+            finalized_height,
             store_get_block,
             store_latest_height,
         );
@@ -31,6 +31,8 @@ pub struct InstallResponse {
     pub chaincode_id: String,
     pub version: String,
     pub size_bytes: usize,
+    /// Secondary indexes the package shipped and this node registered.
+    pub indexes: Vec<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -53,6 +55,8 @@ pub struct CommitResponse {
 ///
 /// Accepts raw Wasm bytes in the request body and stores them in the
 /// `chaincode_packages` column family keyed by `{chaincode_id}:{version}`.
+/// Indexes in the package's `rustbc.indexes` section are registered on the
+/// world state.
 #[post("/chaincode/install")]
 pub async fn install_chaincode(
     http_req: HttpRequest,
@@ -104,6 +108,12 @@ pub async fn install_chaincode(
         }
     }
 
+    let index_defs = crate::storage::index::indexes_from_package(&body, &query.chaincode_id)
+        .map_err(|e| ApiError::ValidationError {
+            field: "wasm_bytes".to_string(),
+            reason: e.to_string(),
+        })?;
+
     let store = state
         .chaincode_package_store
         .as_ref()
@@ -135,6 +145,33 @@ pub async fn install_chaincode(
         });
     }
 
+    let mut indexes = Vec::new();
+    match state.state_index.as_ref() {
+        Some(index_store) => {
+            for def in &index_defs {
+                let report =
+                    index_store
+                        .register_index(def)
+                        .map_err(|e| ApiError::StorageError {
+                            reason: e.to_string(),
+                        })?;
+                log::info!(
+                    "Index {} of chaincode {} registered ({} entries)",
+                    def.name,
+                    query.chaincode_id,
+                    report.entries
+                );
+                indexes.push(def.name.clone());
+            }
+        }
+        None if !index_defs.is_empty() => log::warn!(
+            "Chaincode {} ships {} index(es); ignored without STATE_DB=rocksdb",
+            query.chaincode_id,
+            index_defs.len()
+        ),
+        None => {}
+    }
+
     log::info!(
         "Chaincode {} v{} installed, sha256: {}",
         query.chaincode_id,
@@ -182,6 +219,7 @@ pub async fn install_chaincode(
         chaincode_id: query.chaincode_id.clone(),
         version: query.version.clone(),
         size_bytes: body.len(),
+        indexes,
     };
     Ok(HttpResponse::Ok().json(ApiResponse::success(response, trace_id)))
 }
@@ -7,11 +7,21 @@ use serde::{Deserialize, Serialize};
 
 use crate::api::errors::{enforce_acl, ApiError, ApiResponse, ApiResult};
 use crate::app_state::AppState;
-use crate::channel::config::{apply_config_update, ChannelConfig, ConfigTransaction};
+use crate::channel::config::{
+    apply_config_update, check_key_rotation_activation, ChannelConfig, ConfigTransaction,
+    ConfigUpdateType,
+};
+use crate::channel::delivery::{run_delivery_loop, ChannelCommitter};
 use crate::channel::genesis::create_genesis_block;
+use crate::channel::ledger::{config_history, ConfigValidator};
+use crate::endorsement::policy_store::PolicyStore;
+use crate::endorsement::registry::OrgRegistry;
 use crate::endorsement::{MemoryOrgRegistry, MemoryPolicyStore};
+use crate::ordering::OrderingBackend;
 use crate::storage::memory::MemoryStore;
+use crate::storage::state_tree::AuthenticatedWorldState;
 use crate::storage::traits::BlockStore;
+use crate::storage::world_state::{MemoryWorldState, WorldState};
 
 // ── Request / response types ──────────────────────────────────────────────────
 
@@ -104,6 +114,117 @@ pub fn get_channel_store(
         })
 }
 
+/// Return the authenticated world state of `channel_id`: the node's
+/// `authenticated_state` for `"default"`, the channel's own one otherwise,
+/// or `ApiError::NotFound`.
+pub fn get_channel_authenticated_state(
+    state: &AppState,
+    channel_id: &str,
+) -> Result<Arc<AuthenticatedWorldState>, ApiError> {
+    let found = if channel_id == "default" {
+        state.authenticated_state.clone()
+    } else {
+        state
+            .channel_authenticated_states
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(channel_id)
+            .cloned()
+    };
+    found.ok_or_else(|| ApiError::NotFound {
+        resource: format!("authenticated state of channel '{channel_id}'"),
+    })
+}
+
+/// Return the world state of `channel_id`: the node's `world_state` for
+/// `"default"`, the channel's own one otherwise, or `ApiError::NotFound`.
+pub fn get_channel_world_state(
+    state: &AppState,
+    channel_id: &str,
+) -> Result<Arc<dyn WorldState>, ApiError> {
+    if channel_id == "default" {
+        return state.world_state.clone().ok_or_else(|| ApiError::NotFound {
+            resource: "world_state".to_string(),
+        });
+    }
+    state
+        .channel_world_states
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(channel_id)
+        .cloned()
+        .ok_or_else(|| ApiError::NotFound {
+            resource: format!("world state of channel '{channel_id}'"),
+        })
+}
+
+/// Config history of `channel_id`, rebuilt from the channel ledger when it
+/// starts with a genesis config, otherwise the history kept in `AppState`.
+fn channel_config_history(state: &AppState, channel_id: &str) -> Option<Vec<ChannelConfig>> {
+    get_channel_store(state, channel_id)
+        .ok()
+        .and_then(|store| config_history(store.as_ref()).ok())
+        .or_else(|| {
+            state
+                .channel_configs
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .get(channel_id)
+                .cloned()
+        })
+}
+
+/// Validator for blocks committed to a channel ledger, over the node's
+/// org/policy stores or empty in-memory ones.
+fn config_validator(state: &AppState) -> ConfigValidator {
+    let policy_store: Arc<dyn PolicyStore> = state
+        .policy_store
+        .clone()
+        .unwrap_or_else(|| Arc::new(MemoryPolicyStore::new()));
+    let org_registry: Arc<dyn OrgRegistry> = state
+        .org_registry
+        .clone()
+        .unwrap_or_else(|| Arc::new(MemoryOrgRegistry::new()));
+    ConfigValidator::new(policy_store, org_registry)
+}
+
+/// Commit the blocks `backend` orders for `channel_id` to `store` in the
+/// background. Each committed config block extends the config history in
+/// `AppState::channel_configs`, and a committed rotation of this node's own
+/// key switches the block signer at its activation height.
+fn start_delivery(
+    state: &AppState,
+    channel_id: &str,
+    store: Arc<dyn BlockStore>,
+    backend: Arc<dyn OrderingBackend>,
+) {
+    let configs = state.channel_configs.clone();
+    let node_keys = state.node_keys.clone();
+    let signer_backend = backend.clone();
+    let id = channel_id.to_string();
+    let committer = ChannelCommitter::new(channel_id, store, backend, config_validator(state))
+        .on_config(move |tx, config| {
+            configs
+                .write()
+                .unwrap_or_else(|e| e.into_inner())
+                .entry(id.clone())
+                .or_default()
+                .push(config.clone());
+            let Some(keys) = &node_keys else {
+                return;
+            };
+            let mut keys = keys.lock().unwrap_or_else(|e| e.into_inner());
+            for update in &tx.updates {
+                if let ConfigUpdateType::RotateKey(rotation) = update {
+                    if let Some(signer) = keys.commit_rotation(rotation) {
+                        signer_backend.rotate_signer_at(signer, rotation.activation_height);
+                    }
+                }
+            }
+        });
+    actix_web::rt::spawn(run_delivery_loop(Arc::new(committer)));
+}
+
 // ── Handlers ──────────────────────────────────────────────────────────────────
 
 /// POST /api/v1/channels — crear un channel e instanciar su store.
@@ -155,8 +276,38 @@ pub async fn create_channel(
             reason: format!("failed to write genesis block for channel '{channel_id}': {e}"),
         })?;
 
-    map.insert(channel_id.clone(), new_store);
+    // Start the channel's own ordering group when this orderer hosts one.
+    let group = match state.ordering_groups.as_ref() {
+        Some(groups) => groups
+            .create_group(&channel_id, &genesis_config)
+            .map_err(|e| ApiError::InternalError {
+                reason: e.to_string(),
+            })?,
+        None => None,
+    };
+
+    let world_state = Arc::new(
+        AuthenticatedWorldState::new(Arc::new(MemoryWorldState::new()), 0).map_err(|e| {
+            ApiError::InternalError {
+                reason: e.to_string(),
+            }
+        })?,
+    );
+    map.insert(channel_id.clone(), new_store.clone());
     drop(map);
+    if let Some(group) = group {
+        start_delivery(&state, &channel_id, new_store, group.backend());
+    }
+    state
+        .channel_world_states
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(channel_id.clone(), world_state.clone());
+    state
+        .channel_authenticated_states
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(channel_id.clone(), world_state);
 
     // Seed config history with the genesis config.
     state
@@ -184,9 +335,11 @@ pub async fn create_channel(
 
 /// POST /api/v1/channels/{channel_id}/config — submit a config-update transaction.
 ///
-/// Validates signatures against the channel's current modification policy, applies
-/// the updates, and persists the new `ChannelConfig` version in `AppState::channel_configs`.
-/// Returns 200 with the resulting `ChannelConfig`.
+/// Checks signatures against the channel's current modification policy, then
+/// orders the transaction through the channel's ordering group. The config
+/// block it is cut into is validated again when the channel's delivery
+/// commits it, and only then takes effect. Returns 202 with the ledger
+/// transaction ID.
 #[post("/channels/{channel_id}/config")]
 pub async fn update_channel_config(
     http_req: HttpRequest,
@@ -205,7 +358,7 @@ pub async fn update_channel_config(
     let trace_id = uuid::Uuid::new_v4().to_string();
 
     // Channel must exist in the store map.
-    get_channel_store(&state, &channel_id)?;
+    let store = get_channel_store(&state, &channel_id)?;
 
     // Get current config (last entry in history).
     let current = {
@@ -223,40 +376,126 @@ pub async fn update_channel_config(
 
     // Validate config-tx signatures. Fall back to empty in-memory registries when
     // AppState does not carry live org/policy stores (e.g. in unit tests).
-    let fallback_policy_store = MemoryPolicyStore::new();
-    let fallback_org_registry = MemoryOrgRegistry::new();
-    let policy_store = state
+    let policy_store: Arc<dyn PolicyStore> = state
         .policy_store
-        .as_deref()
-        .unwrap_or(&fallback_policy_store);
-    let org_registry = state
+        .clone()
+        .unwrap_or_else(|| Arc::new(MemoryPolicyStore::new()));
+    let org_registry: Arc<dyn OrgRegistry> = state
         .org_registry
-        .as_deref()
-        .unwrap_or(&fallback_org_registry);
-
-    crate::channel::config::validate_config_tx(&tx, &current, policy_store, org_registry).map_err(
+        .clone()
+        .unwrap_or_else(|| Arc::new(MemoryOrgRegistry::new()));
+
+    crate::channel::config::validate_config_tx(
+        &tx,
+        &current,
+        policy_store.as_ref(),
+        org_registry.as_ref(),
+    )
+    .map_err(|e| ApiError::ValidationError {
+        field: "signatures".to_string(),
+        reason: e.to_string(),
+    })?;
+
+    apply_config_update(&current, &tx.updates).map_err(|e| ApiError::ValidationError {
+        field: "updates".to_string(),
+        reason: e.to_string(),
+    })?;
+    check_key_rotation_activation(&tx.updates, store.get_latest_height().unwrap_or(0)).map_err(
         |e| ApiError::ValidationError {
-            field: "signatures".to_string(),
+            field: "updates".to_string(),
             reason: e.to_string(),
         },
     )?;
 
-    // Apply updates and persist new version.
-    let new_config =
-        apply_config_update(&current, &tx.updates).map_err(|e| ApiError::ValidationError {
-            field: "updates".to_string(),
-            reason: e.to_string(),
+    // Order the transaction through the channel's own consensus; its
+    // delivery commits the config block.
+    let backend = state
+        .ordering_groups
+        .as_ref()
+        .and_then(|groups| groups.backend_for(&channel_id))
+        .ok_or_else(|| ApiError::ConsensusError {
+            reason: format!("no ordering group for channel '{channel_id}'"),
+        })?;
+    backend
+        .submit_config_tx(&tx)
+        .map_err(|e| ApiError::ConsensusError {
+            reason: format!("ordering config transaction failed: {e}"),
         })?;
 
-    state
-        .channel_configs
-        .write()
+    Ok(HttpResponse::Accepted().json(ApiResponse::success(
+        serde_json::json!({ "tx_id": tx.ledger_tx_id(), "status": "submitted" }),
+        trace_id,
+    )))
+}
+
+/// Body of `POST /channels/{channel_id}/config/key-rotation`.
+#[derive(Debug, Deserialize)]
+pub struct KeyRotationRequest {
+    /// First height signed with the new key.
+    pub activation_height: u64,
+    /// Blocks after activation during which the old key still verifies.
+    #[serde(default)]
+    pub grace_blocks: Option<u64>,
+}
+
+/// POST /api/v1/channels/{channel_id}/config/key-rotation — announce a
+/// rotation of this node's signing key.
+///
+/// Returns a `KeyRotation` signed by the current key and a fresh one. Once
+/// it is committed as a `RotateKey` update through
+/// `POST /channels/{channel_id}/config` (with the admins' signatures), this
+/// node signs with the new key from `activation_height` on. 400 if the node
+/// does not sign with an Ed25519 key or the height has already passed.
+#[post("/channels/{channel_id}/config/key-rotation")]
+pub async fn announce_key_rotation(
+    http_req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<KeyRotationRequest>,
+) -> ApiResult<HttpResponse> {
+    enforce_acl(
+        state.acl_provider.as_deref(),
+        state.policy_store.as_deref(),
+        "peer/ChannelConfig",
+        &http_req,
+    )?;
+    let channel_id = path.into_inner();
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    let store = get_channel_store(&state, &channel_id)?;
+
+    let keys = state
+        .node_keys
+        .as_ref()
+        .ok_or_else(|| ApiError::ValidationError {
+            field: "signing_key".to_string(),
+            reason: "this node does not sign with a rotatable Ed25519 key".to_string(),
+        })?;
+    let tip = store.get_latest_height().unwrap_or(0);
+    if body.activation_height <= tip {
+        return Err(ApiError::ValidationError {
+            field: "activation_height".to_string(),
+            reason: format!("must be above the channel height {tip}"),
+        });
+    }
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let rotation = keys
+        .lock()
         .unwrap_or_else(|e| e.into_inner())
-        .entry(channel_id)
-        .or_default()
-        .push(new_config.clone());
+        .announce_rotation(
+            now,
+            body.activation_height,
+            body.grace_blocks
+                .unwrap_or(crate::identity::key_rotation::DEFAULT_KEY_ROTATION_GRACE_BLOCKS),
+        )
+        .map_err(|e| ApiError::InternalError {
+            reason: format!("signing the key rotation failed: {e}"),
+        })?;
 
-    Ok(HttpResponse::Ok().json(ApiResponse::success(new_config, trace_id)))
+    Ok(HttpResponse::Ok().json(ApiResponse::success(rotation, trace_id)))
 }
 
 /// GET /api/v1/channels/{channel_id}/config — retorna la config actual del channel.
@@ -271,11 +510,7 @@ pub async fn get_channel_config(
     let channel_id = path.into_inner();
     let trace_id = uuid::Uuid::new_v4().to_string();
 
-    let config = state
-        .channel_configs
-        .read()
-        .unwrap_or_else(|e| e.into_inner())
-        .get(&channel_id)
+    let config = channel_config_history(&state, &channel_id)
         .and_then(|v| v.last().cloned())
         .ok_or_else(|| ApiError::NotFound {
             resource: format!("config for channel '{channel_id}'"),
@@ -296,13 +531,8 @@ pub async fn get_channel_config_history(
     let channel_id = path.into_inner();
     let trace_id = uuid::Uuid::new_v4().to_string();
 
-    let history = state
-        .channel_configs
-        .read()
-        .unwrap_or_else(|e| e.into_inner())
-        .get(&channel_id)
-        .cloned()
-        .ok_or_else(|| ApiError::NotFound {
+    let history =
+        channel_config_history(&state, &channel_id).ok_or_else(|| ApiError::NotFound {
             resource: format!("config history for channel '{channel_id}'"),
         })?;
 
@@ -404,6 +634,278 @@ mod tests {
         assert!(map.contains_key("ch-dup"));
     }
 
+    #[actix_web::test]
+    async fn create_channel_starts_its_ordering_group() {
+        use actix_web::{test, web, App};
+
+        use crate::ordering::groups::{GroupKind, OrderingGroups};
+
+        let dir = tempfile::tempdir().unwrap();
+        let groups = Arc::new(OrderingGroups::new(GroupKind::Solo, dir.path()));
+        let mut state = make_state(vec![("default", Arc::new(MemoryStore::new()))]);
+        state.ordering_groups = Some(groups.clone());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(state))
+                .service(web::scope("/api/v1").service(create_channel)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/channels")
+            .set_json(serde_json::json!({ "channel_id": "ch-busy" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+
+        assert!(
+            groups.get("ch-busy").is_some(),
+            "group created with the channel"
+        );
+        assert!(dir.path().join("ch-busy").is_dir());
+        assert!(groups.get("default").is_none());
+    }
+
+    #[actix_web::test]
+    async fn config_update_is_ordered_into_the_channel_ledger() {
+        use actix_web::{test, web, App};
+
+        use crate::channel::config::{ConfigTransaction, ConfigUpdateType};
+        use crate::endorsement::policy::EndorsementPolicy;
+        use crate::endorsement::policy_store::PolicyStore;
+        use crate::endorsement::MemoryPolicyStore;
+        use crate::ordering::groups::{GroupKind, OrderingGroups};
+
+        use super::{get_channel_config_history, update_channel_config};
+
+        let policies: Arc<dyn PolicyStore> = Arc::new(MemoryPolicyStore::new());
+        policies
+            .set_policy(
+                "channel/ch-cfg/mod_policy",
+                &EndorsementPolicy::AllOf(vec![]),
+            )
+            .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let mut state = make_state(vec![("default", Arc::new(MemoryStore::new()))]);
+        state.policy_store = Some(policies);
+        state.ordering_groups = Some(Arc::new(OrderingGroups::new(GroupKind::Solo, dir.path())));
+        let stores = state.store.clone();
+        let app = test::init_service(
+            App::new().app_data(web::Data::new(state)).service(
+                web::scope("/api/v1")
+                    .service(create_channel)
+                    .service(update_channel_config)
+                    .service(get_channel_config_history),
+            ),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/channels")
+            .set_json(serde_json::json!({ "channel_id": "ch-cfg" }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+        let tx = ConfigTransaction {
+            tx_id: "cfg-1".to_string(),
+            channel_id: "ch-cfg".to_string(),
+            updates: vec![ConfigUpdateType::SetBatchSize(42)],
+            signatures: vec![],
+            created_at: 0,
+        };
+        let req = test::TestRequest::post()
+            .uri("/api/v1/channels/ch-cfg/config")
+            .set_json(&tx)
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 202);
+
+        // The channel's delivery commits the config transaction to the
+        // ledger, in a block of its own.
+        let store = get_channel_store_from(&stores, "ch-cfg");
+        wait_for_height(store.as_ref(), 1).await;
+        let block = store.read_block(1).expect("config block committed");
+        assert_eq!(block.config_tx, Some(tx.clone()));
+        assert_eq!(block.transactions, vec![tx.ledger_tx_id()]);
+        assert_eq!(block.last_config, 1);
+
+        let req = test::TestRequest::get()
+            .uri("/api/v1/channels/ch-cfg/config/history")
+            .to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        let history = body["data"].as_array().expect("history");
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1]["batch_size"], 42);
+    }
+
+    #[actix_web::test]
+    async fn config_update_without_ordering_service_is_rejected() {
+        use actix_web::{test, web, App};
+
+        use super::update_channel_config;
+        use crate::channel::config::{ChannelConfig, ConfigTransaction};
+        use crate::endorsement::policy::EndorsementPolicy;
+
+        let state = make_state(vec![("ch1", Arc::new(MemoryStore::new()))]);
+        state.channel_configs.write().unwrap().insert(
+            "ch1".to_string(),
+            vec![ChannelConfig {
+                endorsement_policy: EndorsementPolicy::AllOf(vec![]),
+                ..ChannelConfig::default()
+            }],
+        );
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(state))
+                .service(web::scope("/api/v1").service(update_channel_config)),
+        )
+        .await;
+
+        let tx = ConfigTransaction {
+            tx_id: "cfg-1".to_string(),
+            channel_id: "ch1".to_string(),
+            updates: vec![],
+            signatures: vec![],
+            created_at: 0,
+        };
+        let req = test::TestRequest::post()
+            .uri("/api/v1/channels/ch1/config")
+            .set_json(&tx)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(!resp.status().is_success());
+    }
+
+    #[actix_web::test]
+    async fn committed_key_rotation_switches_the_block_signer() {
+        use actix_web::{test, web, App};
+
+        use crate::channel::config::{ConfigTransaction, ConfigUpdateType};
+        use crate::endorsement::policy::EndorsementPolicy;
+        use crate::endorsement::policy_store::PolicyStore;
+        use crate::endorsement::MemoryPolicyStore;
+        use crate::identity::key_rotation::KeyRotation;
+        use crate::identity::keys::{KeyManager, NodeKeys};
+        use crate::identity::signing::verify_with_public_key;
+        use crate::ordering::block_hash_for_signing;
+        use crate::ordering::groups::{GroupKind, OrderingGroups};
+        use crate::storage::traits::Transaction;
+
+        use super::{announce_key_rotation, update_channel_config};
+
+        std::env::set_var("ACL_MODE", "permissive");
+        let policies: Arc<dyn PolicyStore> = Arc::new(MemoryPolicyStore::new());
+        policies
+            .set_policy(
+                "channel/ch-keys/mod_policy",
+                &EndorsementPolicy::AllOf(vec![]),
+            )
+            .unwrap();
+        let keys = NodeKeys::new(KeyManager::new(1000));
+        let old_key = keys.signing_provider().public_key();
+        let dir = tempfile::tempdir().unwrap();
+        let groups = Arc::new(
+            OrderingGroups::new(GroupKind::Solo, dir.path())
+                .with_signing_provider(keys.signing_provider()),
+        );
+        let mut state = make_state(vec![("default", Arc::new(MemoryStore::new()))]);
+        state.policy_store = Some(policies);
+        state.ordering_groups = Some(groups.clone());
+        state.node_keys = Some(Arc::new(Mutex::new(keys)));
+        let stores = state.store.clone();
+        let app = test::init_service(
+            App::new().app_data(web::Data::new(state)).service(
+                web::scope("/api/v1")
+                    .service(create_channel)
+                    .service(update_channel_config)
+                    .service(announce_key_rotation),
+            ),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/channels")
+            .set_json(serde_json::json!({ "channel_id": "ch-keys" }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/channels/ch-keys/config/key-rotation")
+            .set_json(serde_json::json!({ "activation_height": 0 }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/channels/ch-keys/config/key-rotation")
+            .set_json(serde_json::json!({ "activation_height": 3, "grace_blocks": 1 }))
+            .to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        let rotation: KeyRotation = serde_json::from_value(body["data"].clone()).unwrap();
+        assert_eq!(rotation.old_public_key, old_key);
+        assert_eq!(rotation.verify(), Ok(()));
+
+        let tx = ConfigTransaction {
+            tx_id: "cfg-rotate".to_string(),
+            channel_id: "ch-keys".to_string(),
+            updates: vec![ConfigUpdateType::RotateKey(rotation.clone())],
+            signatures: vec![],
+            created_at: 0,
+        };
+        let req = test::TestRequest::post()
+            .uri("/api/v1/channels/ch-keys/config")
+            .set_json(&tx)
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 202);
+        let store = get_channel_store_from(&stores, "ch-keys");
+        wait_for_height(store.as_ref(), 1).await;
+
+        // Blocks below the activation height keep the old key.
+        let orderer = groups.backend_for("ch-keys").expect("channel group");
+        let signed_by = |block: crate::storage::traits::Block| {
+            let hash = block_hash_for_signing(&block);
+            let sig = block.orderer_signature.unwrap();
+            if verify_with_public_key(&old_key, &hash, &sig) {
+                "old"
+            } else if verify_with_public_key(&rotation.new_public_key, &hash, &sig) {
+                "new"
+            } else {
+                "none"
+            }
+        };
+        for height in [2, 3] {
+            let tx = Transaction {
+                id: format!("tx-{height}"),
+                block_height: 0,
+                timestamp: 0,
+                input_did: "did:bc:alice".to_string(),
+                output_recipient: "did:bc:bob".to_string(),
+                amount: 1,
+                state: "pending".to_string(),
+            };
+            orderer.submit_tx(&tx).unwrap();
+            wait_for_height(store.as_ref(), height).await;
+        }
+        assert_eq!(signed_by(store.read_block(2).unwrap()), "old");
+        assert_eq!(signed_by(store.read_block(3).unwrap()), "new");
+    }
+
+    /// Wait (up to 10s) for the channel's delivery to commit `height`.
+    async fn wait_for_height(store: &dyn BlockStore, height: u64) {
+        for _ in 0..200 {
+            if store.get_latest_height().unwrap_or(0) >= height {
+                return;
+            }
+            actix_web::rt::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        panic!("block {height} was not committed");
+    }
+
+    fn get_channel_store_from(
+        stores: &crate::app_state::StoreMap,
+        channel_id: &str,
+    ) -> Arc<dyn BlockStore> {
+        stores.read().unwrap()[channel_id].clone()
+    }
+
     // ── list_channels ─────────────────────────────────────────────────────────
 
     #[test]
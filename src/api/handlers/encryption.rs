@@ -0,0 +1,212 @@
+//! Admin storage encryption endpoints:
+//!   GET  /api/v1/admin/storage/encryption        — encryption coverage per scope
+//!   POST /api/v1/admin/storage/encryption/rotate — rotate data keys and re-encrypt
+//!
+//! Encryption itself is switched on at startup (`STORAGE_ENCRYPTION`); the
+//! KEK never leaves the node.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use actix_web::{get, post, web, HttpRequest, HttpResponse};
+use serde::Deserialize;
+
+use crate::api::errors::{enforce_acl, ApiError, ApiResponse, ApiResult};
+use crate::app_state::AppState;
+use crate::storage::encryption::{spawn_reencryption, EncryptedStore};
+use crate::storage::errors::StorageError;
+
+fn encrypted_store(state: &AppState) -> ApiResult<Arc<dyn EncryptedStore>> {
+    state
+        .storage_encryption
+        .clone()
+        .ok_or_else(|| ApiError::NotFound {
+            resource:
+                "storage encryption (requires STORAGE_ENCRYPTION and a persistent STORAGE_BACKEND)"
+                    .to_string(),
+        })
+}
+
+fn storage_error(e: StorageError) -> ApiError {
+    ApiError::StorageError {
+        reason: e.to_string(),
+    }
+}
+
+/// Body of `POST /admin/storage/encryption/rotate`.
+#[derive(Debug, Default, Deserialize)]
+pub struct RotateRequest {
+    /// Scope (column family or table) to rotate; every scope when absent.
+    pub scope: Option<String>,
+}
+
+/// `GET /api/v1/admin/storage/encryption` — how many values of each scope
+/// are sealed under the current key, an older key, or not at all.
+#[get("/admin/storage/encryption")]
+pub async fn get_encryption_coverage(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+) -> ApiResult<HttpResponse> {
+    enforce_acl(
+        state.acl_provider.as_deref(),
+        state.policy_store.as_deref(),
+        "peer/Admin",
+        &req,
+    )?;
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    let store = encrypted_store(&state)?;
+
+    let report = tokio::task::spawn_blocking(move || store.encryption_coverage())
+        .await
+        .map_err(|e| ApiError::InternalError {
+            reason: e.to_string(),
+        })?
+        .map_err(storage_error)?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(report, trace_id)))
+}
+
+/// `POST /api/v1/admin/storage/encryption/rotate` — wrap a new data key for
+/// one scope (or all of them) and re-encrypt the scope in the background.
+/// Returns the new key version per scope.
+#[post("/admin/storage/encryption/rotate")]
+pub async fn rotate_encryption_keys(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    body: Option<web::Json<RotateRequest>>,
+) -> ApiResult<HttpResponse> {
+    enforce_acl(
+        state.acl_provider.as_deref(),
+        state.policy_store.as_deref(),
+        "peer/Admin",
+        &req,
+    )?;
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    let store = encrypted_store(&state)?;
+    let scope = body.map(|b| b.into_inner().scope).unwrap_or_default();
+
+    let rotated = tokio::task::spawn_blocking(move || -> ApiResult<BTreeMap<String, u32>> {
+        let scopes = store.encrypted_scopes().map_err(storage_error)?;
+        let scopes = match scope {
+            Some(scope) if !scopes.contains(&scope) => {
+                return Err(ApiError::ValidationError {
+                    field: "scope".to_string(),
+                    reason: format!("{scope} is not an encrypted scope"),
+                })
+            }
+            Some(scope) => vec![scope],
+            None => scopes,
+        };
+        let mut rotated = BTreeMap::new();
+        for scope in scopes {
+            let version = store.rotate_data_key(&scope).map_err(storage_error)?;
+            rotated.insert(scope, version);
+        }
+        spawn_reencryption(store, rotated.keys().cloned().collect()).map_err(|e| {
+            ApiError::InternalError {
+                reason: e.to_string(),
+            }
+        })?;
+        Ok(rotated)
+    })
+    .await
+    .map_err(|e| ApiError::InternalError {
+        reason: e.to_string(),
+    })??;
+
+    crate::audit::emit_if_present(
+        &state.audit_store,
+        crate::audit::AuditAction::StorageKeyRotated,
+        req.headers()
+            .get("X-Org-Id")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("unknown"),
+        Some(
+            rotated
+                .iter()
+                .map(|(scope, version)| format!("{scope}=v{version}"))
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+    );
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(rotated, trace_id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+
+    #[cfg(feature = "redb-storage")]
+    use crate::storage::{
+        encryption::MlKemKek, redb_store::RedbBlockStore, world_state::WorldState,
+    };
+
+    #[cfg(feature = "redb-storage")]
+    #[actix_web::test]
+    async fn coverage_and_rotation() {
+        std::env::set_var("ACL_MODE", "permissive");
+        let dir = tempfile::TempDir::new().unwrap();
+        let store = Arc::new(RedbBlockStore::new(dir.path()).unwrap());
+        store
+            .enable_encryption(Arc::new(MlKemKek::generate().unwrap()))
+            .unwrap();
+        store.put("k", b"v").unwrap();
+        let mut state = AppState::test_default();
+        state.storage_encryption = Some(store.clone());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(state))
+                .service(get_encryption_coverage)
+                .service(rotate_encryption_keys),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/admin/storage/encryption")
+            .to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["data"]["enabled"], true);
+        let world_state = body["data"]["scopes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|s| s["scope"] == "world_state")
+            .unwrap()
+            .clone();
+        assert_eq!(world_state["key_version"], 1);
+        assert_eq!(world_state["current"], 1);
+
+        let req = test::TestRequest::post()
+            .uri("/admin/storage/encryption/rotate")
+            .set_json(serde_json::json!({"scope": "world_state"}))
+            .to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["data"]["world_state"], 2);
+        assert_eq!(store.get("k").unwrap().unwrap().data, b"v");
+
+        let req = test::TestRequest::post()
+            .uri("/admin/storage/encryption/rotate")
+            .set_json(serde_json::json!({"scope": "identities"}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+    }
+
+    #[actix_web::test]
+    async fn encryption_needs_an_encrypted_store() {
+        std::env::set_var("ACL_MODE", "permissive");
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(AppState::test_default()))
+                .service(get_encryption_coverage),
+        )
+        .await;
+        let req = test::TestRequest::get()
+            .uri("/admin/storage/encryption")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 404);
+    }
+}
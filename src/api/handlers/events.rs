@@ -86,7 +86,7 @@ fn event_passes_filter(event: &BlockEvent, filter: &WsFilter) -> bool {
             BlockEvent::BlockCommitted { channel_id, .. } => Some(channel_id.as_str()),
             BlockEvent::TransactionCommitted { channel_id, .. } => Some(channel_id.as_str()),
             BlockEvent::ChaincodeEvent { channel_id, .. } => Some(channel_id.as_str()),
-            // Security events are channel-agnostic — skip channel filter.
+            // Security and reorg events are channel-agnostic — skip channel filter.
             _ => None,
         };
         if let Some(ch) = event_channel {
@@ -477,6 +477,13 @@ mod tests {
             secondary_signature_algorithm: None,
             hash_algorithm: Default::default(),
             orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: Vec::new(),
+            config_tx: None,
+            last_config: 0,
+            state_root: [0u8; 32],
+            private_data_purges: vec![],
         }
     }
 
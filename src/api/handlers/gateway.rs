@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 use crate::api::errors::{enforce_acl, ApiError, ApiResponse, ApiResult};
 use crate::api::handlers::channels::enforce_channel_membership;
 use crate::app_state::AppState;
-use crate::gateway::TxResult;
+use crate::gateway::{GatewayError, TxResult};
 use crate::storage::traits::Transaction;
 
 // ── Request / response types ──────────────────────────────────────────────────
@@ -108,8 +108,15 @@ pub async fn gateway_submit(
     let result = gw
         .submit(&req.chaincode_id, &req.channel_id, tx)
         .await
-        .map_err(|e| ApiError::InternalError {
-            reason: e.to_string(),
+        .map_err(|e| match e {
+            GatewayError::TransactionTooLarge(reason) => ApiError::ValidationError {
+                field: "transaction".to_string(),
+                reason,
+            },
+            GatewayError::Rejected(e) => e.into(),
+            e => ApiError::InternalError {
+                reason: e.to_string(),
+            },
         })?;
 
     let trace_id = uuid::Uuid::new_v4().to_string();
@@ -236,4 +243,56 @@ mod tests {
         let resp = test::call_service(&app, req).await;
         assert_eq!(resp.status(), 400);
     }
+
+    #[actix_web::test]
+    async fn submit_returns_400_when_transaction_exceeds_absolute_max_bytes() {
+        let ordering = OrderingService::with_config(10, 500).with_block_size_limits(
+            crate::ordering::BlockSizeLimits {
+                preferred_max_bytes: 64,
+                absolute_max_bytes: 64,
+            },
+        );
+        let gw = Gateway::new(
+            Arc::new(MemoryOrgRegistry::new()),
+            Arc::new(MemoryPolicyStore::new()),
+            Arc::new(ordering),
+            Arc::new(MemoryStore::new()),
+        );
+        let app = test::init_service(
+            App::new()
+                .app_data(base_state(Some(Arc::new(gw))))
+                .service(web::scope("/api/v1").service(gateway_submit)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/gateway/submit")
+            .set_json(submit_body("tx-too-large"))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["error"]["field"], "transaction");
+    }
+
+    #[actix_web::test]
+    async fn submit_returns_409_when_transaction_is_replayed() {
+        let state = make_state_with_gateway();
+        let app = test::init_service(
+            App::new()
+                .app_data(state)
+                .service(web::scope("/api/v1").service(gateway_submit)),
+        )
+        .await;
+
+        for expected in [200, 409] {
+            let req = test::TestRequest::post()
+                .uri("/api/v1/gateway/submit")
+                .set_json(submit_body("tx-replayed"))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), expected);
+        }
+    }
 }
@@ -0,0 +1,218 @@
+//! Admin endpoints for world-state secondary indexes:
+//!   POST /api/v1/admin/indexes                — register and build an index
+//!   GET  /api/v1/admin/indexes                — list registered indexes
+//!   GET  /api/v1/admin/indexes/{name}/verify  — compare an index with the state
+//!   POST /api/v1/admin/indexes/{name}/rebuild — rebuild an index from the state
+//!
+//! Indexes shipped in chaincode packages are registered on install; these
+//! endpoints cover the rest. Rich queries use them via `POST /state/query`.
+
+use std::sync::Arc;
+
+use actix_web::{get, post, web, HttpRequest, HttpResponse};
+
+use crate::api::errors::{enforce_acl, ApiError, ApiResponse, ApiResult};
+use crate::app_state::AppState;
+use crate::storage::errors::StorageError;
+use crate::storage::index::{IndexDefinition, StateIndexStore};
+
+fn index_store(req: &HttpRequest, state: &AppState) -> ApiResult<Arc<dyn StateIndexStore>> {
+    enforce_acl(
+        state.acl_provider.as_deref(),
+        state.policy_store.as_deref(),
+        "peer/Admin",
+        req,
+    )?;
+    state.state_index.clone().ok_or_else(|| ApiError::NotFound {
+        resource: "state indexes (requires STATE_DB=rocksdb)".to_string(),
+    })
+}
+
+fn index_error(e: StorageError) -> ApiError {
+    match e {
+        StorageError::KeyNotFound(resource) => ApiError::NotFound { resource },
+        e => ApiError::StorageError {
+            reason: e.to_string(),
+        },
+    }
+}
+
+/// Run a build or scan off the async workers; both read the whole state.
+async fn blocking<T, F>(f: F) -> ApiResult<T>
+where
+    F: FnOnce() -> Result<T, StorageError> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| ApiError::InternalError {
+            reason: e.to_string(),
+        })?
+        .map_err(index_error)
+}
+
+/// `POST /api/v1/admin/indexes` — register `body` and build it from the
+/// current state. Registering an existing name replaces that index.
+#[post("/admin/indexes")]
+pub async fn register_index(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    body: web::Json<IndexDefinition>,
+) -> ApiResult<HttpResponse> {
+    let store = index_store(&req, &state)?;
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    let def = body.into_inner();
+    def.validate().map_err(|e| ApiError::ValidationError {
+        field: "index".to_string(),
+        reason: e.to_string(),
+    })?;
+
+    let report = blocking(move || store.register_index(&def)).await?;
+    Ok(HttpResponse::Created().json(ApiResponse::success(report, trace_id)))
+}
+
+/// `GET /api/v1/admin/indexes` — registered index definitions.
+#[get("/admin/indexes")]
+pub async fn list_indexes(req: HttpRequest, state: web::Data<AppState>) -> ApiResult<HttpResponse> {
+    let store = index_store(&req, &state)?;
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    let indexes = store.list_indexes().map_err(index_error)?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(indexes, trace_id)))
+}
+
+/// `GET /api/v1/admin/indexes/{name}/verify` — missing and stale entries.
+#[get("/admin/indexes/{name}/verify")]
+pub async fn verify_index(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> ApiResult<HttpResponse> {
+    let store = index_store(&req, &state)?;
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    let name = path.into_inner();
+    let report = blocking(move || store.verify_index(&name)).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(report, trace_id)))
+}
+
+/// `POST /api/v1/admin/indexes/{name}/rebuild` — drop and rebuild an index.
+#[post("/admin/indexes/{name}/rebuild")]
+pub async fn rebuild_index(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> ApiResult<HttpResponse> {
+    let store = index_store(&req, &state)?;
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    let name = path.into_inner();
+    let report = blocking(move || store.rebuild_index(&name)).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(report, trace_id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+    use std::sync::Mutex;
+
+    use crate::storage::errors::StorageResult;
+    use crate::storage::index::IndexReport;
+
+    #[derive(Default)]
+    struct FakeIndexes {
+        defs: Mutex<Vec<IndexDefinition>>,
+    }
+
+    fn report(name: &str) -> IndexReport {
+        IndexReport {
+            name: name.to_string(),
+            entries: 3,
+            missing: 0,
+            stale: 0,
+        }
+    }
+
+    impl StateIndexStore for FakeIndexes {
+        fn register_index(&self, def: &IndexDefinition) -> StorageResult<IndexReport> {
+            self.defs.lock().unwrap().push(def.clone());
+            Ok(report(&def.name))
+        }
+
+        fn list_indexes(&self) -> StorageResult<Vec<IndexDefinition>> {
+            Ok(self.defs.lock().unwrap().clone())
+        }
+
+        fn rebuild_index(&self, name: &str) -> StorageResult<IndexReport> {
+            self.verify_index(name)
+        }
+
+        fn verify_index(&self, name: &str) -> StorageResult<IndexReport> {
+            if self.defs.lock().unwrap().iter().any(|d| d.name == name) {
+                Ok(report(name))
+            } else {
+                Err(StorageError::KeyNotFound(format!("index {name}")))
+            }
+        }
+
+        fn scan_index(&self, _: &str, _: &[u8], _: &[u8]) -> StorageResult<Vec<String>> {
+            Ok(vec![])
+        }
+    }
+
+    #[actix_web::test]
+    async fn indexes_are_registered_listed_and_verified() {
+        std::env::set_var("ACL_MODE", "permissive");
+        let mut state = AppState::test_default();
+        state.state_index = Some(Arc::new(FakeIndexes::default()));
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(state))
+                .service(register_index)
+                .service(list_indexes)
+                .service(verify_index)
+                .service(rebuild_index),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/admin/indexes")
+            .set_json(IndexDefinition::new("by_owner", &["owner"]))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+
+        let req = test::TestRequest::post()
+            .uri("/admin/indexes")
+            .set_json(IndexDefinition::new("no good", &["owner"]))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+        let req = test::TestRequest::get().uri("/admin/indexes").to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["data"][0]["name"], "by_owner");
+        assert_eq!(body["data"][0]["fields"][0], "owner");
+
+        let req = test::TestRequest::get()
+            .uri("/admin/indexes/by_owner/verify")
+            .to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["data"]["entries"], 3);
+
+        let req = test::TestRequest::post()
+            .uri("/admin/indexes/missing/rebuild")
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 404);
+    }
+
+    #[actix_web::test]
+    async fn indexes_need_a_rocksdb_world_state() {
+        std::env::set_var("ACL_MODE", "permissive");
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(AppState::test_default()))
+                .service(list_indexes),
+        )
+        .await;
+        let req = test::TestRequest::get().uri("/admin/indexes").to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 404);
+    }
+}
@@ -1,8 +1,11 @@
-use actix_web::{get, put, web, HttpRequest, HttpResponse};
+use actix_web::{get, post, put, web, HttpRequest, HttpResponse};
 use serde::{Deserialize, Serialize};
 
 use crate::api::errors::{enforce_acl, ApiError, ApiResponse, ApiResult};
+use crate::api::handlers::channels::get_channel_store;
 use crate::app_state::AppState;
+use crate::endorsement::types::Endorsement;
+use crate::private_data::{PrivateDataError, PurgeRequest};
 
 // ── Request / response types ──────────────────────────────────────────────────
 
@@ -25,6 +28,28 @@ pub struct GetPrivateDataResponse {
     pub value: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurgePrivateDataBody {
+    /// Hex SHA-256 of the value, as returned by the PUT and recorded on-chain.
+    pub value_hash: String,
+    /// Ledger height the endorsers signed at.
+    pub height: u64,
+    /// Member endorsements over
+    /// `purge_payload_hash(collection, key, value_hash, height)`.
+    pub endorsements: Vec<Endorsement>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PurgePrivateDataResponse {
+    pub collection: String,
+    pub key: String,
+    pub value_hash: String,
+    /// Ledger ID of the purge transaction.
+    pub tx_id: String,
+    /// Height of the block that committed the purge.
+    pub block_height: u64,
+}
+
 // ── Helper: extract and validate org membership ───────────────────────────────
 
 /// Reads `X-Org-Id` from the request headers and verifies the org is a member
@@ -67,6 +92,40 @@ fn check_membership(
     Ok(org_id)
 }
 
+/// P2P addresses of the known peers whose org is a member of `collection_name`.
+fn member_peers(state: &AppState, collection_name: &str) -> Vec<String> {
+    let (Some(disc), Some(col)) = (
+        state.discovery_service.as_ref(),
+        state
+            .collection_registry
+            .as_ref()
+            .and_then(|reg| reg.get(collection_name)),
+    ) else {
+        return vec![];
+    };
+    disc.all_peers()
+        .into_iter()
+        .filter(|p| col.is_member(&p.org_id))
+        .map(|p| p.peer_address)
+        .collect()
+}
+
+/// Send `msg` to each of `peers` without waiting for the acks.
+fn disseminate(state: &AppState, peers: Vec<String>, msg: crate::network::Message) {
+    let Some(ref p2p_node) = state.node else {
+        return;
+    };
+    for peer_addr in peers {
+        let node = p2p_node.clone();
+        let msg = msg.clone();
+        tokio::spawn(async move {
+            let _ = node
+                .send_and_wait(&peer_addr, msg, std::time::Duration::from_secs(3))
+                .await;
+        });
+    }
+}
+
 // ── Handlers ──────────────────────────────────────────────────────────────────
 
 /// PUT /api/v1/private-data/{collection}/{key}
@@ -93,7 +152,7 @@ pub async fn put_private_data(
     let (collection, key) = path.into_inner();
     let trace_id = uuid::Uuid::new_v4().to_string();
 
-    check_membership(&req, &state, &collection)?;
+    let sender_org = check_membership(&req, &state, &collection)?;
 
     let store = state
         .private_data_store
@@ -104,60 +163,35 @@ pub async fn put_private_data(
 
     let value_bytes = body.value.as_bytes().to_vec();
 
+    // Record the collection's TTL so the entry expires after
+    // `blocks_to_live` blocks, on this peer and on the members it reaches.
+    let blocks_to_live = state
+        .collection_registry
+        .as_ref()
+        .and_then(|reg| reg.get(&collection))
+        .map_or(0, |col| col.blocks_to_live);
+    let height = get_channel_store(&state, "default")
+        .ok()
+        .and_then(|s| s.get_latest_height().ok())
+        .unwrap_or(0);
+
     let hash = store
-        .put_private_data(&collection, &key, &value_bytes)
+        .put_private_data_at(&collection, &key, &value_bytes, height, blocks_to_live)
         .map_err(|e| ApiError::StorageError {
             reason: e.to_string(),
         })?;
 
-    // Disseminate to member org peers via P2P.
-    let sender_org = req
-        .headers()
-        .get("X-Org-Id")
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("")
-        .to_string();
-
-    if let Some(ref p2p_node) = state.node {
-        // Find member peers from the discovery service.
-        let member_peers: Vec<String> = if let Some(ref disc) = state.discovery_service {
-            disc.all_peers()
-                .into_iter()
-                .filter(|p| {
-                    // Only send to peers whose org is a member of this collection.
-                    if let Some(ref reg) = state.collection_registry {
-                        if let Some(col) = reg.get(&collection) {
-                            return col.is_member(&p.org_id);
-                        }
-                    }
-                    false
-                })
-                .map(|p| p.peer_address)
-                .collect()
-        } else {
-            vec![]
-        };
-
-        let collection_clone = collection.clone();
-        let key_clone = key.clone();
-        let node = p2p_node.clone();
-
-        // Fire-and-forget: send to each member peer.
-        for peer_addr in member_peers {
-            let msg = crate::network::Message::PrivateDataPush {
-                collection: collection_clone.clone(),
-                key: key_clone.clone(),
-                value: value_bytes.clone(),
-                sender_org: sender_org.clone(),
-            };
-            let node = node.clone();
-            tokio::spawn(async move {
-                let _ = node
-                    .send_and_wait(&peer_addr, msg, std::time::Duration::from_secs(3))
-                    .await;
-            });
-        }
-    }
+    // Disseminate to member org peers via P2P (fire-and-forget).
+    disseminate(
+        &state,
+        member_peers(&state, &collection),
+        crate::network::Message::PrivateDataPush {
+            collection: collection.clone(),
+            key: key.clone(),
+            value: value_bytes,
+            sender_org,
+        },
+    );
 
     let response = PutPrivateDataResponse {
         collection,
@@ -208,6 +242,85 @@ pub async fn get_private_data(
     Ok(HttpResponse::Ok().json(ApiResponse::success(response, trace_id)))
 }
 
+/// POST /api/v1/private-data/{collection}/{key}/purge
+///
+/// Erase an entry from every member peer, like Fabric's `PurgePrivateData`.
+/// The body carries the value's on-chain hash, the ledger height the
+/// endorsers signed at, and endorsements from `required_peer_count` member
+/// orgs over `purge_payload_hash(collection, key, value_hash, height)`. The
+/// purge is checked here, then ordered as a transaction; each member peer
+/// verifies it again and erases the entry when it commits the block, and
+/// appends a `private_data_purged` audit record. The hash stays on-chain. A
+/// purge only commits within `PURGE_VALIDITY_BLOCKS` of its height.
+#[post("/private-data/{collection}/{key}/purge")]
+pub async fn purge_private_data(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<(String, String)>,
+    body: web::Json<PurgePrivateDataBody>,
+) -> ApiResult<HttpResponse> {
+    enforce_acl(
+        state.acl_provider.as_deref(),
+        state.policy_store.as_deref(),
+        "peer/PrivateData.Purge",
+        &req,
+    )?;
+    let (collection, key) = path.into_inner();
+    let trace_id = uuid::Uuid::new_v4().to_string();
+
+    check_membership(&req, &state, &collection)?;
+
+    let (Some(registry), Some(org_registry), Some(gw)) = (
+        state.collection_registry.as_deref(),
+        state.org_registry.as_deref(),
+        state.gateway.as_ref(),
+    ) else {
+        return Err(ApiError::NotFound {
+            resource: "private data purge (org registry or gateway)".to_string(),
+        });
+    };
+
+    let body = body.into_inner();
+    let request = PurgeRequest {
+        collection: collection.clone(),
+        key: key.clone(),
+        value_hash: body.value_hash,
+        height: body.height,
+        endorsements: body.endorsements,
+    };
+    let definition = registry
+        .get(&collection)
+        .ok_or_else(|| ApiError::NotFound {
+            resource: format!("collection '{collection}'"),
+        })?;
+    let next_height = gw.store.get_latest_height().unwrap_or(0) + 1;
+    request
+        .verify(&definition, org_registry)
+        .and_then(|_| request.check_commit_height(next_height))
+        .map_err(|e| match e {
+            PrivateDataError::PurgeRejected(reason) => ApiError::Forbidden { reason },
+            other => ApiError::ValidationError {
+                field: "collection".to_string(),
+                reason: other.to_string(),
+            },
+        })?;
+
+    let result = gw
+        .submit_purge(&request)
+        .map_err(|e| ApiError::InternalError {
+            reason: e.to_string(),
+        })?;
+
+    let response = PurgePrivateDataResponse {
+        collection,
+        key,
+        value_hash: request.value_hash,
+        tx_id: result.tx_id,
+        block_height: result.block_height,
+    };
+    Ok(HttpResponse::Ok().json(ApiResponse::success(response, trace_id)))
+}
+
 // ── Collection registration ──────────────────────────────────────────────────
 
 #[derive(Debug, Deserialize)]
@@ -439,4 +552,240 @@ mod tests {
         let resp = test::call_service(&app, req).await;
         assert_eq!(resp.status(), 404);
     }
+
+    #[actix_web::test]
+    async fn put_records_the_collection_ttl() {
+        let registry = Arc::new(MemoryCollectionRegistry::new());
+        registry
+            .register(make_collection("col1", &["org1"]))
+            .unwrap();
+        let store = Arc::new(MemoryPrivateDataStore::new());
+        let state = make_state(registry, store.clone() as Arc<dyn PrivateDataStore>);
+        let app = test::init_service(
+            App::new()
+                .app_data(state)
+                .service(web::scope("/api/v1").service(put_private_data)),
+        )
+        .await;
+
+        let req = test::TestRequest::put()
+            .uri("/api/v1/private-data/col1/k1")
+            .insert_header(("X-Org-Id", "org1"))
+            .set_json(PutPrivateDataBody {
+                value: "secret".to_string(),
+            })
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+        // Written at height 0 with blocks_to_live = 100.
+        store.purge_expired(99);
+        assert!(store.get_private_data("col1", "k1").unwrap().is_some());
+        store.purge_expired(100);
+        assert_eq!(store.get_private_data("col1", "k1").unwrap(), None);
+    }
+
+    // ── Purge tests ───────────────────────────────────────────────────────────
+
+    use crate::audit::{AuditAction, AuditStore, MemoryAuditStore};
+    use crate::endorsement::org::Organization;
+    use crate::endorsement::policy_store::MemoryPolicyStore;
+    use crate::endorsement::registry::{MemoryOrgRegistry, OrgRegistry};
+    use crate::gateway::Gateway;
+    use crate::ordering::service::OrderingService;
+    use crate::private_data::{purge_payload_hash, sha256, PurgeCommitter};
+    use crate::storage::MemoryStore;
+    use pqc_crypto_module::legacy::ed25519::{Signer, SigningKey};
+    use pqc_crypto_module::legacy::rng::OsRng;
+
+    /// `col1` of org1 and org2 (one endorsement needed) holding `k1`, plus
+    /// a signing key per org. Purges are ordered through a solo gateway
+    /// whose committer runs as org1.
+    fn purge_setup() -> (
+        web::Data<AppState>,
+        Arc<MemoryPrivateDataStore>,
+        Arc<MemoryAuditStore>,
+        Vec<(&'static str, SigningKey)>,
+    ) {
+        let registry = Arc::new(MemoryCollectionRegistry::new());
+        registry
+            .register(make_collection("col1", &["org1", "org2"]))
+            .unwrap();
+        let store = Arc::new(MemoryPrivateDataStore::new());
+        store.put_private_data("col1", "k1", b"payload").unwrap();
+        let orgs = Arc::new(MemoryOrgRegistry::new());
+        let mut keys = Vec::new();
+        for org_id in ["org1", "org2", "org3"] {
+            let sk = SigningKey::generate(&mut OsRng);
+            let org = Organization::new(
+                org_id,
+                format!("{org_id}MSP"),
+                vec![format!("did:bc:{org_id}:admin")],
+                vec![],
+                vec![sk.verifying_key().to_bytes()],
+            )
+            .unwrap();
+            orgs.register_org(&org).unwrap();
+            keys.push((org_id, sk));
+        }
+        let audit = Arc::new(MemoryAuditStore::new());
+        let mut gateway = Gateway::new(
+            orgs.clone(),
+            Arc::new(MemoryPolicyStore::new()),
+            Arc::new(OrderingService::with_config(10, 500)),
+            Arc::new(MemoryStore::new()),
+        );
+        gateway.purge_committer = Some(
+            PurgeCommitter::new(registry.clone(), orgs.clone(), store.clone(), "org1")
+                .with_audit_store(audit.clone()),
+        );
+
+        std::env::set_var("ACL_MODE", "permissive");
+        let mut state = AppState::test_default();
+        state.private_data_store = Some(store.clone());
+        state.collection_registry = Some(registry);
+        state.org_registry = Some(orgs);
+        state.audit_store = Some(audit.clone());
+        state.gateway = Some(Arc::new(gateway));
+        (web::Data::new(state), store, audit, keys)
+    }
+
+    fn purge_body(keys: &[(&str, SigningKey)], signer: &str, height: u64) -> PurgePrivateDataBody {
+        let value_hash = sha256(b"payload");
+        let payload = purge_payload_hash("col1", "k1", &value_hash, height);
+        let (org_id, sk) = keys.iter().find(|(org, _)| *org == signer).unwrap();
+        PurgePrivateDataBody {
+            value_hash: hex::encode(value_hash),
+            height,
+            endorsements: vec![Endorsement {
+                signer_did: format!("did:bc:{org_id}:admin"),
+                org_id: org_id.to_string(),
+                signature: sk.sign(&payload).to_bytes().to_vec(),
+                signature_algorithm: Default::default(),
+                payload_hash: payload,
+                timestamp: 0,
+            }],
+        }
+    }
+
+    #[actix_web::test]
+    async fn endorsed_purge_is_ordered_and_erases_entry_at_commit() {
+        let (state, store, audit, keys) = purge_setup();
+        let app = test::init_service(
+            App::new()
+                .app_data(state.clone())
+                .service(web::scope("/api/v1").service(purge_private_data)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/private-data/col1/k1/purge")
+            .insert_header(("X-Org-Id", "org1"))
+            .set_json(purge_body(&keys, "org2", 0))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["data"]["value_hash"], hex::encode(sha256(b"payload")));
+        assert_eq!(body["data"]["block_height"], 1);
+        let tx_id = body["data"]["tx_id"].as_str().unwrap().to_string();
+        assert!(tx_id.starts_with(crate::private_data::PURGE_TX_PREFIX));
+        assert_eq!(store.get_private_data("col1", "k1").unwrap(), None);
+
+        // The purge is on the ledger, in the block that applied it.
+        let gateway = state.gateway.as_ref().unwrap();
+        let block = gateway.store.read_block(1).unwrap();
+        assert_eq!(block.transactions, vec![tx_id]);
+        assert_eq!(block.private_data_purges.len(), 1);
+
+        let entries = audit
+            .query(None, None, None, Some(&AuditAction::PrivateDataPurged), 10)
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].org_id, "org1");
+        let meta: serde_json::Value =
+            serde_json::from_str(entries[0].metadata.as_deref().unwrap()).unwrap();
+        assert_eq!(meta["key"], "k1");
+        assert_eq!(meta["endorsing_orgs"], serde_json::json!(["org2"]));
+    }
+
+    #[actix_web::test]
+    async fn purge_endorsed_by_non_member_is_forbidden() {
+        let (state, store, audit, keys) = purge_setup();
+        let app = test::init_service(
+            App::new()
+                .app_data(state)
+                .service(web::scope("/api/v1").service(purge_private_data)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/private-data/col1/k1/purge")
+            .insert_header(("X-Org-Id", "org1"))
+            .set_json(purge_body(&keys, "org3", 0))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 403);
+        assert!(store.get_private_data("col1", "k1").unwrap().is_some());
+        assert!(audit.query(None, None, None, None, 10).unwrap().is_empty());
+    }
+
+    #[actix_web::test]
+    async fn purge_endorsed_at_a_stale_height_is_forbidden() {
+        let (state, store, _audit, keys) = purge_setup();
+        let gateway = state.gateway.clone().unwrap();
+        let app = test::init_service(
+            App::new()
+                .app_data(state)
+                .service(web::scope("/api/v1").service(purge_private_data)),
+        )
+        .await;
+
+        // Move the ledger past the purge's validity window.
+        for i in 0..=crate::private_data::PURGE_VALIDITY_BLOCKS {
+            let tx = crate::storage::traits::Transaction {
+                id: format!("tx-{i}"),
+                block_height: 0,
+                timestamp: 0,
+                input_did: "did:bc:alice".to_string(),
+                output_recipient: "did:bc:bob".to_string(),
+                amount: 1,
+                state: "pending".to_string(),
+            };
+            gateway.submit("", "", tx).await.unwrap();
+        }
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/private-data/col1/k1/purge")
+            .insert_header(("X-Org-Id", "org1"))
+            .set_json(purge_body(&keys, "org2", 0))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 403);
+        assert!(store.get_private_data("col1", "k1").unwrap().is_some());
+    }
+
+    #[actix_web::test]
+    async fn purge_requires_admin_role_and_membership() {
+        let (state, _store, _audit, keys) = purge_setup();
+        let app = test::init_service(
+            App::new()
+                .app_data(state)
+                .service(web::scope("/api/v1").service(purge_private_data)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/private-data/col1/k1/purge")
+            .insert_header(("X-Org-Id", "org1"))
+            .insert_header(("X-Msp-Role", "client"))
+            .set_json(purge_body(&keys, "org1", 0))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 403);
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/private-data/col1/k1/purge")
+            .insert_header(("X-Org-Id", "org3"))
+            .set_json(purge_body(&keys, "org1", 0))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 403);
+    }
 }
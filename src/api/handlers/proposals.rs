@@ -1,11 +1,15 @@
 //! POST /api/v1/proposals and POST /api/v1/transactions/submit
 
+use std::sync::Arc;
+
 use actix_web::{post, web, HttpRequest, HttpResponse};
 
 use crate::api::errors::{ApiError, ApiResponse, ApiResult};
 use crate::api::handlers::channels::channel_id_from_req;
 use crate::app_state::AppState;
-use crate::endorsement::validator::validate_endorsements;
+use crate::endorsement::validator::validate_endorsements_cached;
+use crate::ordering::OrderingBackend;
+use crate::storage::errors::StorageError;
 use crate::transaction::endorsed::EndorsedTransaction;
 use crate::transaction::proposal::{ProposalResponse, TransactionProposal};
 use crate::transaction::rwset::{KVRead, ReadWriteSet};
@@ -93,13 +97,16 @@ pub async fn submit_proposal(
 }
 
 /// POST /api/v1/transactions/submit — validate endorsements against policy,
-/// then forward the transaction to the ordering service.
+/// then forward the endorsed transaction to the channel's ordering service,
+/// whose admission checks the creator and endorsement signatures.
 #[post("/transactions/submit")]
 pub async fn submit_endorsed_transaction(
     state: web::Data<AppState>,
     body: web::Json<EndorsedTransaction>,
+    req: HttpRequest,
 ) -> ApiResult<HttpResponse> {
     let trace_id = uuid::Uuid::new_v4().to_string();
+    let channel_id = channel_id_from_req(&req);
     let endorsed = body.into_inner();
 
     // Validate endorsements against policy if both registry and policy_store are present.
@@ -108,23 +115,44 @@ pub async fn submit_endorsed_transaction(
     {
         let tx_id = &endorsed.proposal.tx.id;
         if let Ok(policy) = policy_store.get_policy(tx_id) {
-            validate_endorsements(&endorsed.endorsements, &policy, registry.as_ref(), None)
-                .map_err(|e| ApiError::ValidationError {
-                    field: "endorsements".to_string(),
-                    reason: e.to_string(),
-                })?;
+            validate_endorsements_cached(
+                &endorsed.endorsements,
+                &policy,
+                registry.as_ref(),
+                None,
+                &state.signature_verifier,
+            )
+            .map_err(|e| ApiError::ValidationError {
+                field: "endorsements".to_string(),
+                reason: e.to_string(),
+            })?;
         } // No policy registered for this tx — accept as-is.
     }
 
-    // Forward to ordering service via node if available.
-    if let Some(node) = &state.node {
-        if let Some(ordering) = &node.ordering_service {
-            ordering
-                .submit_tx(endorsed.proposal.tx.clone())
-                .map_err(|e| ApiError::InternalError {
-                    reason: e.to_string(),
-                })?;
-        }
+    // Forward to the channel's ordering service, else the node's.
+    let backend = state
+        .ordering_groups
+        .as_ref()
+        .and_then(|groups| groups.backend_for(channel_id))
+        .or_else(|| state.ordering_backend.clone())
+        .or_else(|| {
+            state
+                .node
+                .as_ref()
+                .and_then(|node| node.ordering_service.clone())
+                .map(|ordering| ordering as Arc<dyn OrderingBackend>)
+        });
+    if let Some(backend) = backend {
+        backend.submit_endorsed_tx(&endorsed).map_err(|e| match e {
+            StorageError::TransactionTooLarge { .. } => ApiError::ValidationError {
+                field: "proposal.tx".to_string(),
+                reason: e.to_string(),
+            },
+            StorageError::AdmissionRejected(e) => e.into(),
+            e => ApiError::InternalError {
+                reason: e.to_string(),
+            },
+        })?;
     }
 
     Ok(HttpResponse::Accepted().json(ApiResponse::success(
@@ -243,4 +271,84 @@ mod tests {
         let resp = test::call_service(&app, req).await;
         assert_eq!(resp.status(), 202);
     }
+
+    #[actix_web::test]
+    async fn post_transactions_submit_rejects_forged_endorsement() {
+        use crate::endorsement::org::Organization;
+        use crate::endorsement::registry::OrgRegistry;
+        use crate::identity::signing::{SigningProvider, SoftwareSigningProvider};
+        use crate::ordering::admission::{AdmissionChain, SignaturePolicy};
+        use crate::ordering::service::OrderingService;
+        use crate::ordering::OrderingBackend;
+
+        let org_key = ed25519_dalek::SigningKey::from_bytes(&[3u8; 32]);
+        let registry = Arc::new(MemoryOrgRegistry::new());
+        registry
+            .register_org(
+                &Organization::new(
+                    "org1",
+                    "org1",
+                    vec!["did:bc:org1".to_string()],
+                    vec![],
+                    vec![org_key.verifying_key().to_bytes()],
+                )
+                .unwrap(),
+            )
+            .unwrap();
+        let ordering = Arc::new(
+            OrderingService::new()
+                .with_admission(AdmissionChain::new().with_filter(SignaturePolicy::new(registry))),
+        );
+        let mut state = AppState::test_default();
+        state.ordering_backend = Some(ordering.clone() as Arc<dyn OrderingBackend>);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(state))
+                .service(web::scope("/api/v1").service(super::submit_endorsed_transaction)),
+        )
+        .await;
+
+        let endorsed_by = |id: &str, signer: &SoftwareSigningProvider| {
+            let mut proposal = TransactionProposal {
+                tx: sample_tx(id),
+                creator_did: String::new(),
+                creator_signature: Vec::new(),
+                signature_algorithm: Default::default(),
+                rwset: sample_rwset(),
+            };
+            proposal
+                .sign(&SoftwareSigningProvider::from_key(
+                    ed25519_dalek::SigningKey::from_bytes(&[4u8; 32]),
+                ))
+                .unwrap();
+            EndorsedTransaction {
+                proposal,
+                endorsements: vec![Endorsement {
+                    signer_did: "did:bc:org1".to_string(),
+                    org_id: "org1".to_string(),
+                    signature: signer.sign(&[7u8; 32]).unwrap(),
+                    signature_algorithm: Default::default(),
+                    payload_hash: [7u8; 32],
+                    timestamp: 0,
+                }],
+                rwset: sample_rwset(),
+            }
+        };
+        let submit = |endorsed: EndorsedTransaction| {
+            test::TestRequest::post()
+                .uri("/api/v1/transactions/submit")
+                .set_json(endorsed)
+                .to_request()
+        };
+
+        let forged = endorsed_by("tx-forged", &SoftwareSigningProvider::generate());
+        let resp = test::call_service(&app, submit(forged)).await;
+        assert!(resp.status().is_client_error(), "got {}", resp.status());
+        assert_eq!(ordering.pending_count(), 0);
+
+        let genuine = endorsed_by("tx-genuine", &SoftwareSigningProvider::from_key(org_key));
+        let resp = test::call_service(&app, submit(genuine)).await;
+        assert_eq!(resp.status(), 202);
+        assert_eq!(ordering.pending_count(), 1);
+    }
 }
@@ -1,10 +1,15 @@
-//! Snapshot API handlers: create, list, and download state snapshots.
+//! Snapshot API handlers: create, list, and download state snapshots, and
+//! serve the chunked bootstrap snapshots new peers join from.
 
 use actix_web::{get, post, web, HttpRequest, HttpResponse};
 
 use crate::api::errors::{enforce_acl, ApiError, ApiResponse, ApiResult};
+use crate::api::handlers::channels::get_channel_authenticated_state;
 use crate::app_state::AppState;
-use crate::storage::snapshot;
+use crate::endorsement::policy::EndorsementPolicy;
+use crate::endorsement::types::Endorsement;
+use crate::endorsement::validator::validate_endorsements;
+use crate::storage::{bootstrap, snapshot};
 
 /// `POST /api/v1/snapshots/{channel_id}` — trigger snapshot creation.
 #[post("/snapshots/{channel_id}")]
@@ -136,3 +141,151 @@ pub async fn download_snapshot(
         ))
         .body(content))
 }
+
+/// Base directory snapshots are written to and served from.
+fn snapshot_base_dir() -> std::path::PathBuf {
+    std::env::var("SNAPSHOT_DIR")
+        .unwrap_or_else(|_| "./data".to_string())
+        .into()
+}
+
+fn storage_error(e: crate::storage::errors::StorageError) -> ApiError {
+    match e {
+        crate::storage::errors::StorageError::KeyNotFound(resource) => {
+            ApiError::NotFound { resource }
+        }
+        e @ crate::storage::errors::StorageError::InvalidChannelId(_) => {
+            ApiError::ValidationError {
+                field: "channel_id".to_string(),
+                reason: e.to_string(),
+            }
+        }
+        e => ApiError::StorageError {
+            reason: e.to_string(),
+        },
+    }
+}
+
+/// `POST /api/v1/snapshots/{channel_id}/bootstrap` — take a chunked
+/// bootstrap snapshot at the current tip for new peers to join from.
+#[post("/snapshots/{channel_id}/bootstrap")]
+pub async fn create_bootstrap_snapshot(
+    req: HttpRequest,
+    path: web::Path<String>,
+    state: web::Data<AppState>,
+) -> ApiResult<HttpResponse> {
+    enforce_acl(
+        state.acl_provider.as_deref(),
+        state.policy_store.as_deref(),
+        "qscc/Snapshot.Admin",
+        &req,
+    )?;
+    let channel_id = path.into_inner();
+    let trace_id = uuid::Uuid::new_v4().to_string();
+
+    let store = {
+        let map = state.store.read().unwrap_or_else(|e| e.into_inner());
+        map.get(&channel_id).cloned()
+    }
+    .ok_or_else(|| ApiError::NotFound {
+        resource: format!("channel '{channel_id}'"),
+    })?;
+    let world_state = get_channel_authenticated_state(&state, &channel_id)?;
+
+    let manifest = bootstrap::create_bootstrap_snapshot(
+        store.as_ref(),
+        &world_state,
+        &channel_id,
+        &snapshot_base_dir(),
+    )
+    .map_err(storage_error)?;
+
+    Ok(HttpResponse::Created().json(ApiResponse::success(manifest, trace_id)))
+}
+
+/// `GET /api/v1/snapshots/{channel_id}/bootstrap/{height}` — snapshot manifest.
+#[get("/snapshots/{channel_id}/bootstrap/{height}")]
+pub async fn get_bootstrap_manifest(
+    path: web::Path<(String, u64)>,
+    _state: web::Data<AppState>,
+) -> ApiResult<HttpResponse> {
+    let (channel_id, height) = path.into_inner();
+    let trace_id = uuid::Uuid::new_v4().to_string();
+
+    let manifest = bootstrap::load_manifest(&snapshot_base_dir(), &channel_id, height)
+        .map_err(storage_error)?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(manifest, trace_id)))
+}
+
+/// `GET /api/v1/snapshots/{channel_id}/bootstrap/{height}/chunks/{index}` —
+/// download one compressed chunk.
+#[get("/snapshots/{channel_id}/bootstrap/{height}/chunks/{index}")]
+pub async fn download_bootstrap_chunk(
+    path: web::Path<(String, u64, u32)>,
+    _state: web::Data<AppState>,
+) -> ApiResult<HttpResponse> {
+    let (channel_id, height, index) = path.into_inner();
+
+    let content = bootstrap::read_chunk(&snapshot_base_dir(), &channel_id, height, index)
+        .map_err(storage_error)?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/octet-stream")
+        .body(content))
+}
+
+/// `POST /api/v1/snapshots/{channel_id}/bootstrap/{height}/signatures` —
+/// attach an org signature over the snapshot hash.
+///
+/// The signature must verify against a root key of the signing org; it
+/// replaces an earlier signature of the same org.
+#[post("/snapshots/{channel_id}/bootstrap/{height}/signatures")]
+pub async fn sign_bootstrap_snapshot(
+    req: HttpRequest,
+    path: web::Path<(String, u64)>,
+    body: web::Json<Endorsement>,
+    state: web::Data<AppState>,
+) -> ApiResult<HttpResponse> {
+    enforce_acl(
+        state.acl_provider.as_deref(),
+        state.policy_store.as_deref(),
+        "qscc/Snapshot.Admin",
+        &req,
+    )?;
+    let (channel_id, height) = path.into_inner();
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    let signature = body.into_inner();
+
+    let base_dir = snapshot_base_dir();
+    let mut manifest =
+        bootstrap::load_manifest(&base_dir, &channel_id, height).map_err(storage_error)?;
+    if signature.payload_hash != manifest.snapshot_hash() {
+        return Err(ApiError::ValidationError {
+            field: "payload_hash".to_string(),
+            reason: "does not match the snapshot hash".to_string(),
+        });
+    }
+    let registry = state
+        .org_registry
+        .as_deref()
+        .ok_or_else(|| ApiError::NotFound {
+            resource: "org_registry".to_string(),
+        })?;
+    validate_endorsements(
+        std::slice::from_ref(&signature),
+        &EndorsementPolicy::AnyOf(vec![signature.org_id.clone()]),
+        registry,
+        state.crl_store.as_deref(),
+    )
+    .map_err(|e| ApiError::ValidationError {
+        field: "signature".to_string(),
+        reason: e.to_string(),
+    })?;
+
+    manifest.signatures.retain(|s| s.org_id != signature.org_id);
+    manifest.signatures.push(signature);
+    bootstrap::save_manifest(&base_dir, &manifest).map_err(storage_error)?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(manifest, trace_id)))
+}
@@ -0,0 +1,608 @@
+//! World state endpoints:
+//!   GET /api/v1/state/{key}/proof?height=N — membership or non-membership
+//!   proof for `key` against the state root of block N (default: latest)
+//!   GET /api/v1/state/{key}                — current value of `key`
+//!   GET /api/v1/state?start=&end=&bookmark= — paginated key range
+//!   GET /api/v1/state/{key}/history        — every version of `key`
+//!   POST /api/v1/state/query               — values matching a JSON selector
+//!
+//! Lookups are scoped to the channel named by `X-Channel-Id` and checked
+//! against the `qscc/GetState`, `qscc/GetHistoryForKey` and
+//! `qscc/GetQueryResult` ACL resources.
+
+use actix_web::{get, post, web, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+
+use crate::api::errors::{enforce_acl, ApiError, ApiResponse, ApiResult};
+use crate::api::handlers::channels::{
+    channel_id_from_req, enforce_channel_membership, get_channel_authenticated_state,
+    get_channel_store, get_channel_world_state,
+};
+use crate::app_state::AppState;
+use crate::light_client::client::StateProof;
+use crate::storage::index::{self, Selector};
+use crate::storage::world_state::WorldState;
+use crate::storage::BlockStore;
+
+/// Default and maximum number of entries per range page.
+const DEFAULT_PAGE_SIZE: usize = 100;
+const MAX_PAGE_SIZE: usize = 1_000;
+/// Upper bound used when a range query gives no `end`.
+const RANGE_END_UNBOUNDED: &str = "\u{10ffff}";
+
+#[derive(Deserialize)]
+pub struct StateProofQuery {
+    /// Block height to prove against; the latest block when absent.
+    pub height: Option<u64>,
+}
+
+/// GET /api/v1/state/{key}/proof — state proof verifiable by a light client
+/// against the `state_root` of the block header at the requested height.
+///
+/// Heights older than the state tree's retention window, or whose block
+/// carries no state root for the channel's state, are not found.
+#[get("/state/{key}/proof")]
+pub async fn get_state_proof(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<StateProofQuery>,
+) -> ApiResult<HttpResponse> {
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    let key = path.into_inner();
+    let (store, _) = state_for(&state, &req, "qscc/GetState")?;
+    let tree = get_channel_authenticated_state(&state, channel_id_from_req(&req))?;
+
+    let tip = store.get_latest_height().unwrap_or(0);
+    let height = query.height.unwrap_or(tip);
+    let state_root = store.read_block(height).ok().map(|block| block.state_root);
+    let proof = tree
+        .prove(&key, height)
+        .filter(|proof| Some(proof.root) == state_root)
+        .ok_or_else(|| ApiError::NotFound {
+            resource: format!("state root at height {height}"),
+        })?;
+
+    let body = StateProof::from_tree_proof(&key, height, proof);
+    Ok(HttpResponse::Ok().json(ApiResponse::success(body, trace_id)))
+}
+
+/// A world-state entry as served by the lookup endpoints.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StateEntry {
+    pub key: String,
+    pub value: String,
+    pub version: u64,
+}
+
+impl StateEntry {
+    fn new(key: String, value: crate::storage::world_state::VersionedValue) -> Self {
+        Self {
+            key,
+            value: String::from_utf8_lossy(&value.data).into_owned(),
+            version: value.version,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StateRangePage {
+    pub entries: Vec<StateEntry>,
+    /// Key to pass as `bookmark` for the next page; `None` on the last page.
+    pub bookmark: Option<String>,
+}
+
+/// One version of a key, linked to the transaction and block that wrote it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StateHistoryEntry {
+    pub version: u64,
+    pub value: String,
+    pub is_delete: bool,
+    pub tx_id: String,
+    pub block_height: Option<u64>,
+    pub timestamp: u64,
+}
+
+#[derive(Deserialize)]
+pub struct StateRangeQuery {
+    #[serde(default)]
+    pub start: String,
+    pub end: Option<String>,
+    /// Resume from this key, as returned by the previous page.
+    pub bookmark: Option<String>,
+    pub page_size: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub struct StateQueryRequest {
+    /// Field paths mapped to a value or to `$eq`/`$gt`/`$gte`/`$lt`/`$lte`.
+    pub selector: serde_json::Value,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StateQueryResult {
+    pub entries: Vec<StateEntry>,
+    /// Secondary index that served the query; `None` after a full scan.
+    pub index: Option<String>,
+}
+
+/// Resolve the caller's channel, check access to `resource` on it, and
+/// return its block store and world state.
+fn state_for(
+    state: &AppState,
+    req: &HttpRequest,
+    resource: &str,
+) -> ApiResult<(
+    std::sync::Arc<dyn BlockStore>,
+    std::sync::Arc<dyn WorldState>,
+)> {
+    enforce_acl(
+        state.acl_provider.as_deref(),
+        state.policy_store.as_deref(),
+        resource,
+        req,
+    )?;
+    let channel_id = channel_id_from_req(req);
+    enforce_channel_membership(state, channel_id, req)?;
+    let store = get_channel_store(state, channel_id)?;
+    let world_state = get_channel_world_state(state, channel_id)?;
+    Ok((store, world_state))
+}
+
+fn storage_error(e: crate::storage::errors::StorageError) -> ApiError {
+    ApiError::StorageError {
+        reason: e.to_string(),
+    }
+}
+
+/// GET /api/v1/state — entries with `start <= key < end`, one page at a time.
+#[get("/state")]
+pub async fn get_state_range(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<StateRangeQuery>,
+) -> ApiResult<HttpResponse> {
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    let (_, world_state) = state_for(&state, &req, "qscc/GetState")?;
+
+    let query = query.into_inner();
+    let page_size = query
+        .page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let start = match query.bookmark {
+        Some(bookmark) if bookmark > query.start => bookmark,
+        _ => query.start,
+    };
+    let end = query.end.unwrap_or_else(|| RANGE_END_UNBOUNDED.to_string());
+
+    // One entry past the page tells whether there is a next one.
+    let mut entries = world_state
+        .get_range_page(&start, &end, page_size + 1)
+        .map_err(storage_error)?;
+    let bookmark = (entries.len() > page_size).then(|| entries[page_size].0.clone());
+    entries.truncate(page_size);
+
+    let page = StateRangePage {
+        entries: entries
+            .into_iter()
+            .map(|(key, value)| StateEntry::new(key, value))
+            .collect(),
+        bookmark,
+    };
+    Ok(HttpResponse::Ok().json(ApiResponse::success(page, trace_id)))
+}
+
+/// GET /api/v1/state/{key} — current value and version of `key`.
+#[get("/state/{key}")]
+pub async fn get_state_value(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> ApiResult<HttpResponse> {
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    let key = path.into_inner();
+    let (_, world_state) = state_for(&state, &req, "qscc/GetState")?;
+
+    let value = world_state
+        .get(&key)
+        .map_err(storage_error)?
+        .ok_or_else(|| ApiError::NotFound {
+            resource: format!("state key '{key}'"),
+        })?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(StateEntry::new(key, value), trace_id)))
+}
+
+/// GET /api/v1/state/{key}/history — every version of `key`, oldest first.
+///
+/// Entries recorded without a block height take it from the channel's
+/// transaction index when their tx id is known.
+#[get("/state/{key}/history")]
+pub async fn get_state_history(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> ApiResult<HttpResponse> {
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    let key = path.into_inner();
+    let (store, world_state) = state_for(&state, &req, "qscc/GetHistoryForKey")?;
+
+    let history: Vec<StateHistoryEntry> = world_state
+        .get_history(&key)
+        .map_err(storage_error)?
+        .into_iter()
+        .map(|entry| {
+            let block_height = entry.block_height.or_else(|| {
+                (!entry.tx_id.is_empty())
+                    .then(|| store.read_transaction(&entry.tx_id).ok())
+                    .flatten()
+                    .map(|tx| tx.block_height)
+            });
+            StateHistoryEntry {
+                version: entry.version,
+                value: String::from_utf8_lossy(&entry.data).into_owned(),
+                is_delete: entry.is_delete,
+                tx_id: entry.tx_id,
+                block_height,
+                timestamp: entry.timestamp,
+            }
+        })
+        .collect();
+    Ok(HttpResponse::Ok().json(ApiResponse::success(history, trace_id)))
+}
+
+/// POST /api/v1/state/query — values matching `selector`, through a
+/// secondary index when one covers it.
+#[post("/state/query")]
+pub async fn query_state(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    body: web::Json<StateQueryRequest>,
+) -> ApiResult<HttpResponse> {
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    let (_, world_state) = state_for(&state, &req, "qscc/GetQueryResult")?;
+
+    let selector = Selector::parse(&body.selector).map_err(|e| ApiError::ValidationError {
+        field: "selector".to_string(),
+        reason: e.to_string(),
+    })?;
+    let limit = body
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let outcome = index::query(
+        world_state.as_ref(),
+        state.state_index.as_deref(),
+        &selector,
+        limit,
+    )
+    .map_err(storage_error)?;
+
+    let result = StateQueryResult {
+        entries: outcome
+            .entries
+            .into_iter()
+            .map(|(key, value)| StateEntry::new(key, value))
+            .collect(),
+        index: outcome.index,
+    };
+    Ok(HttpResponse::Ok().json(ApiResponse::success(result, trace_id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+    use std::sync::Arc;
+
+    use crate::storage::state_tree::AuthenticatedWorldState;
+    use crate::storage::world_state::{MemoryWorldState, WorldState};
+
+    fn setup() -> (web::Data<AppState>, Arc<AuthenticatedWorldState>) {
+        std::env::set_var("ACL_MODE", "permissive");
+        let tree =
+            Arc::new(AuthenticatedWorldState::new(Arc::new(MemoryWorldState::new()), 0).unwrap());
+        let mut state = AppState::test_default();
+        state.world_state = Some(tree.clone());
+        state.authenticated_state = Some(tree.clone());
+        (web::Data::new(state), tree)
+    }
+
+    async fn fetch(state: web::Data<AppState>, uri: &str) -> (u16, serde_json::Value) {
+        fetch_on(state, uri, "default").await
+    }
+
+    async fn fetch_on(
+        state: web::Data<AppState>,
+        uri: &str,
+        channel: &str,
+    ) -> (u16, serde_json::Value) {
+        let app = test::init_service(
+            App::new()
+                .app_data(state)
+                .service(web::scope("/api/v1").service(get_state_proof)),
+        )
+        .await;
+        let req = test::TestRequest::get()
+            .uri(uri)
+            .insert_header(("X-Channel-Id", channel))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let status = resp.status().as_u16();
+        (status, test::read_body_json(resp).await)
+    }
+
+    /// Write a block at `height` carrying `state_root` to `channel`'s store.
+    fn write_block(state: &AppState, channel: &str, height: u64, state_root: [u8; 32]) {
+        let tx = crate::channel::config::ConfigTransaction {
+            tx_id: format!("tx-{height}"),
+            channel_id: channel.to_string(),
+            updates: vec![],
+            signatures: vec![],
+            created_at: 0,
+        };
+        let mut block = crate::channel::ledger::config_block(height, "orderer", tx);
+        block.state_root = state_root;
+        get_channel_store(state, channel)
+            .unwrap()
+            .write_block(&block)
+            .unwrap();
+    }
+
+    #[actix_web::test]
+    async fn proves_membership_and_absence_at_a_height() {
+        let (state, tree) = setup();
+        write_block(&state, "default", 0, tree.root_at(0).unwrap());
+        tree.put("asset:1", b"v1").unwrap();
+        let root1 = tree.commit(1);
+        write_block(&state, "default", 1, root1);
+        tree.put("asset:1", b"v2").unwrap();
+        let root2 = tree.commit(2);
+        write_block(&state, "default", 2, root2);
+
+        let (status, body) = fetch(state.clone(), "/api/v1/state/asset:1/proof?height=1").await;
+        assert_eq!(status, 200);
+        let proof: StateProof = serde_json::from_value(body["data"].clone()).unwrap();
+        assert!(proof.exists);
+        assert_eq!(proof.value, b"v1");
+        assert_eq!(proof.proof.root, root1);
+
+        let (status, body) = fetch(state.clone(), "/api/v1/state/asset:9/proof").await;
+        assert_eq!(status, 200);
+        let proof: StateProof = serde_json::from_value(body["data"].clone()).unwrap();
+        assert!(!proof.exists);
+        assert_eq!(proof.height, 2);
+    }
+
+    async fn lookup(
+        world_state: Arc<MemoryWorldState>,
+        uri: &str,
+        channel: Option<&str>,
+    ) -> (u16, serde_json::Value) {
+        std::env::set_var("ACL_MODE", "permissive");
+        let mut state = AppState::test_default();
+        state.world_state = Some(world_state);
+        let app = test::init_service(
+            App::new().app_data(web::Data::new(state)).service(
+                web::scope("/api/v1")
+                    .service(get_state_history)
+                    .service(get_state_value)
+                    .service(get_state_range),
+            ),
+        )
+        .await;
+        let mut req = test::TestRequest::get().uri(uri);
+        if let Some(channel) = channel {
+            req = req.insert_header(("X-Channel-Id", channel));
+        }
+        let resp = test::call_service(&app, req.to_request()).await;
+        let status = resp.status().as_u16();
+        (status, test::read_body_json(resp).await)
+    }
+
+    #[actix_web::test]
+    async fn returns_the_current_value_of_a_key() {
+        let ws = Arc::new(MemoryWorldState::new());
+        ws.put("asset:1", b"v1").unwrap();
+        ws.put("asset:1", b"v2").unwrap();
+
+        let (status, body) = lookup(ws.clone(), "/api/v1/state/asset:1", None).await;
+        assert_eq!(status, 200);
+        assert_eq!(body["data"]["value"], "v2");
+        assert_eq!(body["data"]["version"], 2);
+
+        let (status, _) = lookup(ws, "/api/v1/state/asset:9", None).await;
+        assert_eq!(status, 404);
+    }
+
+    #[actix_web::test]
+    async fn range_pages_follow_the_bookmark() {
+        let ws = Arc::new(MemoryWorldState::new());
+        for i in 1..=5 {
+            ws.put(&format!("a{i}"), b"x").unwrap();
+        }
+        ws.put("b1", b"x").unwrap();
+
+        let uri = "/api/v1/state?start=a&end=b&page_size=2";
+        let (status, body) = lookup(ws.clone(), uri, None).await;
+        assert_eq!(status, 200);
+        assert_eq!(body["data"]["entries"][0]["key"], "a1");
+        assert_eq!(body["data"]["entries"][1]["key"], "a2");
+        assert_eq!(body["data"]["bookmark"], "a3");
+
+        let (_, body) = lookup(ws.clone(), &format!("{uri}&bookmark=a5"), None).await;
+        assert_eq!(body["data"]["entries"][0]["key"], "a5");
+        assert_eq!(body["data"]["entries"].as_array().unwrap().len(), 1);
+        assert!(body["data"]["bookmark"].is_null());
+
+        let (_, body) = lookup(ws, "/api/v1/state", None).await;
+        assert_eq!(body["data"]["entries"].as_array().unwrap().len(), 6);
+    }
+
+    #[actix_web::test]
+    async fn history_links_versions_to_their_tx_and_block() {
+        let ws = Arc::new(MemoryWorldState::new());
+        ws.put_from_tx("asset:1", b"v1", "tx-1", 3).unwrap();
+        ws.put_from_tx("asset:1", b"v2", "tx-2", 5).unwrap();
+        ws.delete("asset:1").unwrap();
+
+        let (status, body) = lookup(ws, "/api/v1/state/asset:1/history", None).await;
+        assert_eq!(status, 200);
+        let history = body["data"].as_array().unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0]["tx_id"], "tx-1");
+        assert_eq!(history[0]["block_height"], 3);
+        assert_eq!(history[1]["value"], "v2");
+        assert_eq!(history[1]["block_height"], 5);
+        assert_eq!(history[2]["is_delete"], true);
+    }
+
+    async fn run_query(
+        world_state: Arc<MemoryWorldState>,
+        body: serde_json::Value,
+    ) -> (u16, serde_json::Value) {
+        std::env::set_var("ACL_MODE", "permissive");
+        let mut state = AppState::test_default();
+        state.world_state = Some(world_state);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(state))
+                .service(web::scope("/api/v1").service(query_state)),
+        )
+        .await;
+        let req = test::TestRequest::post()
+            .uri("/api/v1/state/query")
+            .set_json(body)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let status = resp.status().as_u16();
+        (status, test::read_body_json(resp).await)
+    }
+
+    #[actix_web::test]
+    async fn rich_query_returns_matching_values() {
+        let ws = Arc::new(MemoryWorldState::new());
+        ws.put("a1", br#"{"owner":"bob","size":3}"#).unwrap();
+        ws.put("a2", br#"{"owner":"bob","size":12}"#).unwrap();
+        ws.put("a3", br#"{"owner":"alice","size":7}"#).unwrap();
+
+        let selector = serde_json::json!({"owner": "bob", "size": {"$gt": 5}});
+        let (status, body) =
+            run_query(ws.clone(), serde_json::json!({ "selector": selector })).await;
+        assert_eq!(status, 200);
+        let entries = body["data"]["entries"].as_array().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0]["key"], "a2");
+        assert!(body["data"]["index"].is_null());
+
+        let bad = serde_json::json!({"selector": {"owner": {"$regex": "b"}}});
+        let (status, _) = run_query(ws, bad).await;
+        assert_eq!(status, 400);
+    }
+
+    #[actix_web::test]
+    async fn lookups_on_an_unknown_channel_are_not_found() {
+        let ws = Arc::new(MemoryWorldState::new());
+        ws.put("k", b"v").unwrap();
+        let (status, _) = lookup(ws, "/api/v1/state/k", Some("nope")).await;
+        assert_eq!(status, 404);
+    }
+
+    #[actix_web::test]
+    async fn lookups_read_the_requested_channel_state() {
+        std::env::set_var("ACL_MODE", "permissive");
+        let node_ws = Arc::new(MemoryWorldState::new());
+        node_ws.put("k", b"default").unwrap();
+        let channel_ws = Arc::new(MemoryWorldState::new());
+        channel_ws.put("k", b"ch1").unwrap();
+
+        let mut state = AppState::test_default();
+        state.world_state = Some(node_ws);
+        for channel in ["ch1", "ch2"] {
+            state.store.write().unwrap().insert(
+                channel.to_string(),
+                Arc::new(crate::storage::MemoryStore::new()),
+            );
+        }
+        state
+            .channel_world_states
+            .write()
+            .unwrap()
+            .insert("ch1".to_string(), channel_ws);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(state))
+                .service(web::scope("/api/v1").service(get_state_value)),
+        )
+        .await;
+
+        let get = |channel: &'static str| {
+            test::TestRequest::get()
+                .uri("/api/v1/state/k")
+                .insert_header(("X-Channel-Id", channel))
+                .to_request()
+        };
+        let resp = test::call_service(&app, get("ch1")).await;
+        assert_eq!(resp.status().as_u16(), 200);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["data"]["value"], "ch1");
+
+        let resp = test::call_service(&app, get("ch2")).await;
+        assert_eq!(resp.status().as_u16(), 404);
+    }
+
+    #[actix_web::test]
+    async fn height_beyond_the_tip_is_not_found() {
+        let (state, _tree) = setup();
+        let (status, _) = fetch(state, "/api/v1/state/k/proof?height=5").await;
+        assert_eq!(status, 404);
+    }
+
+    #[actix_web::test]
+    async fn proofs_resolve_the_requested_channel() {
+        let (state, tree) = setup();
+        tree.put("k", b"default").unwrap();
+        write_block(&state, "default", 0, tree.commit(0));
+
+        let channel_tree =
+            Arc::new(AuthenticatedWorldState::new(Arc::new(MemoryWorldState::new()), 0).unwrap());
+        channel_tree.put("k", b"ch1").unwrap();
+        state.store.write().unwrap().insert(
+            "ch1".to_string(),
+            Arc::new(crate::storage::MemoryStore::new()),
+        );
+        state
+            .channel_world_states
+            .write()
+            .unwrap()
+            .insert("ch1".to_string(), channel_tree.clone());
+        state
+            .channel_authenticated_states
+            .write()
+            .unwrap()
+            .insert("ch1".to_string(), channel_tree.clone());
+        write_block(&state, "ch1", 0, channel_tree.commit(0));
+
+        let (status, body) = fetch_on(state.clone(), "/api/v1/state/k/proof", "ch1").await;
+        assert_eq!(status, 200);
+        let proof: StateProof = serde_json::from_value(body["data"].clone()).unwrap();
+        assert_eq!(proof.value, b"ch1");
+        assert_eq!(proof.proof.root, channel_tree.root_at(0).unwrap());
+
+        let (status, _) = fetch_on(state, "/api/v1/state/k/proof", "nope").await;
+        assert_eq!(status, 404);
+    }
+
+    #[actix_web::test]
+    async fn heights_whose_block_carries_another_root_are_not_found() {
+        let (state, tree) = setup();
+        write_block(&state, "default", 0, tree.root_at(0).unwrap());
+        tree.put("k", b"v").unwrap();
+        tree.commit(1);
+        write_block(&state, "default", 1, [0u8; 32]);
+
+        let (status, _) = fetch(state, "/api/v1/state/k/proof?height=1").await;
+        assert_eq!(status, 404);
+    }
+}
@@ -3,11 +3,11 @@ use actix_web::{web, Scope};
 #[cfg(feature = "evm")]
 use crate::api::handlers::evm;
 use crate::api::handlers::{
-    acl, audit, blocks, chain, chaincode, channels, compliance, compliance_auto, contact,
-    credentials, discovery, events, forensic, gateway, governance, governance_entities, identity,
-    intelligence, interop, legal_oracle, msp, oracle, organizations, pentest, pin, private_data,
-    proposals, registry, regulatory, snapshots, stress, tokenization, transactions, utilities,
-    vault, zkp,
+    acl, audit, backups, blocks, chain, chaincode, channels, compliance, compliance_auto, contact,
+    credentials, discovery, encryption, events, forensic, gateway, governance, governance_entities, identity,
+    indexes, intelligence, interop, legal_oracle, msp, oracle, organizations, pentest, pin,
+    private_data, proposals, registry, regulatory, snapshots, state, stress, tokenization,
+    transactions, utilities, vault, zkp,
 };
 
 /// API routes configuration
@@ -121,6 +121,7 @@ impl ApiRoutes {
             .service(channels::create_channel)
             .service(channels::list_channels)
             .service(channels::update_channel_config)
+            .service(channels::announce_key_rotation)
             .service(channels::get_channel_config)
             .service(channels::get_channel_config_history)
             .service(msp::revoke_serial)
@@ -132,6 +133,7 @@ impl ApiRoutes {
         cfg.service(private_data::register_collection)
             .service(private_data::put_private_data)
             .service(private_data::get_private_data)
+            .service(private_data::purge_private_data)
             .service(chaincode::install_chaincode)
             .service(chaincode::approve_chaincode)
             .service(chaincode::commit_chaincode)
@@ -150,8 +152,25 @@ impl ApiRoutes {
             .service(snapshots::create_snapshot)
             .service(snapshots::list_snapshots)
             .service(snapshots::download_snapshot)
+            .service(snapshots::create_bootstrap_snapshot)
+            .service(snapshots::get_bootstrap_manifest)
+            .service(snapshots::download_bootstrap_chunk)
+            .service(snapshots::sign_bootstrap_snapshot)
+            .service(state::query_state)
+            .service(state::get_state_proof)
+            .service(state::get_state_history)
+            .service(state::get_state_value)
+            .service(state::get_state_range)
             .service(audit::list_audit_entries)
-            .service(audit::export_audit_csv);
+            .service(audit::export_audit_csv)
+            .service(backups::create_backup)
+            .service(backups::list_backups)
+            .service(encryption::get_encryption_coverage)
+            .service(encryption::rotate_encryption_keys)
+            .service(indexes::register_index)
+            .service(indexes::list_indexes)
+            .service(indexes::verify_index)
+            .service(indexes::rebuild_index);
         #[cfg(feature = "evm")]
         cfg.service(evm::evm_deploy)
             .service(evm::evm_call)
@@ -224,6 +243,7 @@ impl ApiRoutes {
             .service(blocks::create_block)
             .service(blocks::list_blocks)
             .service(blocks::get_block_by_index)
+            .service(blocks::finalized_height)
             .service(blocks::get_block_by_hash)
     }
 
@@ -296,6 +316,7 @@ impl ApiRoutes {
             .service(channels::create_channel)
             .service(channels::list_channels)
             .service(channels::update_channel_config)
+            .service(channels::announce_key_rotation)
             .service(channels::get_channel_config)
             .service(channels::get_channel_config_history)
     }
@@ -310,6 +331,7 @@ impl ApiRoutes {
         web::scope("")
             .service(private_data::put_private_data)
             .service(private_data::get_private_data)
+            .service(private_data::purge_private_data)
     }
 
     fn chaincode_routes() -> Scope {
@@ -350,6 +372,10 @@ impl ApiRoutes {
             .service(snapshots::create_snapshot)
             .service(snapshots::list_snapshots)
             .service(snapshots::download_snapshot)
+            .service(snapshots::create_bootstrap_snapshot)
+            .service(snapshots::get_bootstrap_manifest)
+            .service(snapshots::download_bootstrap_chunk)
+            .service(snapshots::sign_bootstrap_snapshot)
     }
 
     // utilities (health, version, openapi) registered as .route() in register()
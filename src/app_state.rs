@@ -1,6 +1,9 @@
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex, RwLock};
 pub type StoreMap = Arc<RwLock<HashMap<String, Arc<dyn BlockStore>>>>;
+pub type WorldStateMap =
+    Arc<RwLock<HashMap<String, Arc<dyn crate::storage::world_state::WorldState>>>>;
+pub type AuthenticatedStateMap = Arc<RwLock<HashMap<String, Arc<AuthenticatedWorldState>>>>;
 
 use crate::acl::AclProvider;
 use crate::airdrop::AirdropManager;
@@ -10,6 +13,7 @@ use crate::cache::BalanceCache;
 use crate::chaincode::{ChaincodeDefinitionStore, ChaincodePackageStore};
 use crate::channel::config::ChannelConfig;
 use crate::checkpoint::CheckpointManager;
+use crate::consensus::finality::FinalityTracker;
 use crate::discovery::service::DiscoveryService;
 use crate::endorsement::policy_store::PolicyStore;
 use crate::endorsement::registry::OrgRegistry;
@@ -18,16 +22,21 @@ use crate::gateway::Gateway;
 use crate::governance::params::ParamRegistry;
 use crate::governance::proposals::ProposalStore;
 use crate::governance::voting::VoteStore;
+use crate::identity::batch_verify::BatchVerifier;
 use crate::metrics::MetricsCollector;
 use crate::mining::MiningService;
 use crate::models::{Mempool, WalletManager};
 use crate::msp::CrlStore;
 use crate::network::Node;
+use crate::ordering::groups::OrderingGroups;
 use crate::ordering::OrderingBackend;
 use crate::pin::store::PinStore;
 use crate::private_data::{CollectionRegistry, PrivateDataStore};
 use crate::smart_contracts::ContractManager;
 use crate::staking::StakingManager;
+use crate::storage::archive::BlockArchive;
+use crate::storage::backup::BackupProvider;
+use crate::storage::state_tree::AuthenticatedWorldState;
 use crate::storage::traits::BlockStore;
 use crate::transaction_validation::TransactionValidator;
 
@@ -75,8 +84,25 @@ pub struct AppState {
     pub acl_provider: Option<Arc<dyn AclProvider>>,
     /// Ordering backend — solo (default) or raft.
     pub ordering_backend: Option<Arc<dyn OrderingBackend>>,
+    /// Per-channel ordering groups, created with each channel.
+    pub ordering_groups: Option<Arc<OrderingGroups>>,
     /// World state for snapshots and state queries.
     pub world_state: Option<Arc<dyn crate::storage::world_state::WorldState>>,
+    /// World states of the channels created at runtime via `POST /channels`.
+    /// The `"default"` channel uses `world_state`.
+    pub channel_world_states: WorldStateMap,
+    /// State tree over `world_state`, serving proofs against block state roots.
+    pub authenticated_state: Option<Arc<AuthenticatedWorldState>>,
+    /// State trees over `channel_world_states`, by channel.
+    pub channel_authenticated_states: AuthenticatedStateMap,
+    /// Cold archive of blocks pruned by the channel retention policy.
+    pub block_archive: Option<Arc<BlockArchive>>,
+    /// Online backups of the node's RocksDB databases.
+    pub backup_provider: Option<Arc<dyn BackupProvider>>,
+    /// Encryption at rest of the persistent store (`STORAGE_ENCRYPTION`).
+    pub storage_encryption: Option<Arc<dyn crate::storage::encryption::EncryptedStore>>,
+    /// Secondary indexes over `world_state` (`STATE_DB=rocksdb` only).
+    pub state_index: Option<Arc<dyn crate::storage::index::StateIndexStore>>,
     /// Audit trail — immutable log of all API requests.
     pub audit_store: Option<Arc<dyn crate::audit::AuditStore>>,
     /// Governance — proposal store.
@@ -101,11 +127,19 @@ pub struct AppState {
     pub mining_service: Option<Arc<MiningService>>,
     /// Node signing provider (Ed25519 or ML-DSA-65).
     pub signing_provider: Option<Arc<dyn crate::identity::signing::SigningProvider>>,
+    /// Keys behind an Ed25519 `signing_provider`, rotated through
+    /// `POST /channels/{id}/config/key-rotation`. `None` for ML-DSA-65.
+    pub node_keys: Option<Arc<Mutex<crate::identity::keys::NodeKeys>>>,
     /// New transaction pool backed by storage::Transaction.
     pub tx_pool: Arc<Mutex<crate::transaction::mempool::TransactionPool>>,
     /// HMAC secret for vault recovery blind indexing (NIST SP 800-185).
     /// Recovery is disabled when this is `None`.
     pub vault_recovery_secret: Option<Vec<u8>>,
+    /// Finality rule used to flag blocks as final in `/blocks` responses.
+    pub finality: Arc<FinalityTracker>,
+    /// Signature verifier whose cache is shared with block pre-verification,
+    /// so endorsements checked at submission are not checked again at commit.
+    pub signature_verifier: BatchVerifier,
 }
 
 impl AppState {
@@ -147,7 +181,15 @@ impl AppState {
             channel_configs: Arc::new(RwLock::new(HashMap::new())),
             acl_provider: None,
             ordering_backend: None,
+            ordering_groups: None,
             world_state: None,
+            channel_world_states: Arc::new(RwLock::new(HashMap::new())),
+            authenticated_state: None,
+            channel_authenticated_states: Arc::new(RwLock::new(HashMap::new())),
+            block_archive: None,
+            backup_provider: None,
+            storage_encryption: None,
+            state_index: None,
             audit_store: Some(Arc::new(crate::audit::MemoryAuditStore::new())),
             proposal_store: None,
             vote_store: None,
@@ -166,10 +208,13 @@ impl AppState {
             )),
             mining_service: None,
             signing_provider: None,
+            node_keys: None,
             tx_pool: Arc::new(Mutex::new(
                 crate::transaction::mempool::TransactionPool::new(),
             )),
             vault_recovery_secret: None,
+            finality: Arc::new(FinalityTracker::default()),
+            signature_verifier: BatchVerifier::default(),
         }
     }
 }
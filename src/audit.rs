@@ -47,6 +47,10 @@ pub enum AuditAction {
     ProposalSubmitted,
     /// A vote was cast on a proposal.
     ProposalVoted,
+    /// A private data entry was erased by an endorsed purge.
+    PrivateDataPurged,
+    /// A storage data key was rotated.
+    StorageKeyRotated,
 }
 
 impl std::fmt::Display for AuditAction {
@@ -76,6 +76,92 @@ enum Commands {
         #[arg(default_value = "all")]
         target: String,
     },
+    /// Take an online backup of the node's databases.
+    Backup {
+        /// List the available backups instead of taking one.
+        #[arg(long)]
+        list: bool,
+    },
+    /// Show how much of the node's storage is encrypted at rest.
+    Encryption {
+        /// Rotate the data keys (all scopes, or `--scope`) and re-encrypt
+        /// in the background.
+        #[arg(long)]
+        rotate: bool,
+        /// Column family or table to rotate.
+        #[arg(long, requires = "rotate")]
+        scope: Option<String>,
+    },
+    /// List the world-state secondary indexes of a node.
+    Indexes {
+        /// Also check each index against the state (missing/stale entries).
+        #[arg(long)]
+        verify: bool,
+    },
+    /// Query the node's SQL projection (its SQL_PROJECTION_PATH) read-only.
+    Sql {
+        /// SQLite file of the projection.
+        path: std::path::PathBuf,
+        /// SQL statement to run.
+        query: Option<String>,
+        /// Instead of querying, drop blocks from this height on; the node
+        /// projects them again on its next block.
+        #[arg(long, conflicts_with = "query")]
+        rebuild_from: Option<u64>,
+    },
+    /// Copy a stopped node's RocksDB data directory into a redb store
+    /// (for STORAGE_BACKEND=redb).
+    MigrateStorage {
+        /// RocksDB data directory (the node's STORAGE_PATH).
+        #[arg(long, default_value = "./data/blocks")]
+        from: std::path::PathBuf,
+        /// Directory of the new redb store.
+        #[arg(long)]
+        to: std::path::PathBuf,
+    },
+    /// Offline checks over a node's data directory.
+    Ledger {
+        #[command(subcommand)]
+        command: LedgerCommand,
+    },
+    /// Restore a stopped node's data directory to a backup.
+    Restore {
+        /// Backup id (see `bcctl backup --list`).
+        backup_id: u64,
+        /// Backup directory of the node (its BACKUP_DIR).
+        #[arg(long, default_value = "./backups")]
+        backup_dir: std::path::PathBuf,
+        /// Data directory of the node (its STORAGE_PATH).
+        #[arg(long, default_value = "./data/blocks")]
+        storage_path: std::path::PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum LedgerCommand {
+    /// Check the hash chain, merkle roots, signatures, tx index and world
+    /// state of a RocksDB data directory, and print a signed report.
+    Verify {
+        /// RocksDB data directory (the node's STORAGE_PATH), opened read-only.
+        #[arg(long, default_value = "./data/blocks")]
+        path: std::path::PathBuf,
+        /// Orderer identity (hex public key) to check orderer signatures
+        /// against; each block's proposer by default.
+        #[arg(long)]
+        orderer: Option<String>,
+        /// File holding the hex Ed25519 secret key to sign the report with;
+        /// a one-off key (SIGNING_ALGORITHM) otherwise.
+        #[arg(long)]
+        signing_key: Option<std::path::PathBuf>,
+        /// Also write the signed report as JSON to this file.
+        #[arg(long)]
+        out: Option<std::path::PathBuf>,
+        /// Archive base directory (the node's ARCHIVE_DIR) holding the
+        /// bodies of blocks pruned by retention. Its manifest must be signed
+        /// by a key in ARCHIVE_TRUSTED_KEYS.
+        #[arg(long)]
+        archive_dir: Option<std::path::PathBuf>,
+    },
 }
 
 fn port_for(node: &str) -> u16 {
@@ -434,6 +520,476 @@ fn cmd_restart(target: &str) {
     }
 }
 
+async fn cmd_backup(client: &Client, node: &str, list: bool, json: bool) {
+    let result = if list {
+        api_get(client, node, "admin/backups").await
+    } else {
+        api_post(client, node, "admin/backups", &Value::Null).await
+    };
+    match result {
+        Ok(resp) => {
+            if json {
+                print_json(&resp["data"]);
+                return;
+            }
+            if let Some(error) = resp["error"].as_str() {
+                eprintln!("Error: {error}");
+                return;
+            }
+            let backups = match resp["data"].as_array() {
+                Some(arr) => arr.clone(),
+                None => vec![resp["data"].clone()],
+            };
+            println!(
+                "{:<6} {:<12} {:<8} {:<10}",
+                "ID", "CREATED", "HEIGHT", "DATABASES"
+            );
+            for b in &backups {
+                println!(
+                    "{:<6} {:<12} {:<8} {:<10}",
+                    b["id"].as_u64().unwrap_or(0),
+                    b["created_at"].as_u64().unwrap_or(0),
+                    b["latest_height"].as_u64().unwrap_or(0),
+                    b["databases"].as_array().map(|d| d.len()).unwrap_or(0)
+                );
+            }
+        }
+        Err(e) => eprintln!("Error: {e}"),
+    }
+}
+
+async fn cmd_encryption(
+    client: &Client,
+    node: &str,
+    rotate: bool,
+    scope: Option<String>,
+    json: bool,
+) {
+    if rotate {
+        let body = match scope {
+            Some(scope) => serde_json::json!({ "scope": scope }),
+            None => Value::Null,
+        };
+        match api_post(client, node, "admin/storage/encryption/rotate", &body).await {
+            Ok(resp) => {
+                if json {
+                    print_json(&resp["data"]);
+                    return;
+                }
+                if let Some(error) = resp["error"].as_str() {
+                    eprintln!("Error: {error}");
+                    return;
+                }
+                println!("{:<24} {:<12}", "SCOPE", "KEY VERSION");
+                for (scope, version) in resp["data"].as_object().into_iter().flatten() {
+                    println!("{:<24} {:<12}", scope, version.as_u64().unwrap_or(0));
+                }
+                println!("Re-encryption runs in the background; check with `bcctl encryption`.");
+            }
+            Err(e) => eprintln!("Error: {e}"),
+        }
+        return;
+    }
+
+    let resp = match api_get(client, node, "admin/storage/encryption").await {
+        Ok(resp) => resp,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return;
+        }
+    };
+    if json {
+        print_json(&resp["data"]);
+        return;
+    }
+    if let Some(error) = resp["error"].as_str() {
+        eprintln!("Error: {error}");
+        return;
+    }
+    let report = &resp["data"];
+    println!(
+        "Encryption: {} (KEK {})",
+        if report["enabled"].as_bool().unwrap_or(false) {
+            "enabled"
+        } else {
+            "disabled"
+        },
+        report["kek_id"].as_str().unwrap_or("-")
+    );
+    println!(
+        "{:<24} {:<12} {:<10} {:<10} {:<10}",
+        "SCOPE", "KEY VERSION", "CURRENT", "STALE", "PLAINTEXT"
+    );
+    for s in report["scopes"].as_array().into_iter().flatten() {
+        println!(
+            "{:<24} {:<12} {:<10} {:<10} {:<10}",
+            s["scope"].as_str().unwrap_or("-"),
+            s["key_version"]
+                .as_u64()
+                .map_or("-".to_string(), |v| v.to_string()),
+            s["current"].as_u64().unwrap_or(0),
+            s["stale"].as_u64().unwrap_or(0),
+            s["plaintext"].as_u64().unwrap_or(0)
+        );
+    }
+    let pending: Vec<&str> = report["pending"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|p| p.as_str())
+        .collect();
+    if !pending.is_empty() {
+        println!("Re-encryption pending: {}", pending.join(", "));
+    }
+}
+
+async fn cmd_indexes(client: &Client, node: &str, verify: bool, json: bool) {
+    let resp = match api_get(client, node, "admin/indexes").await {
+        Ok(resp) => resp,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return;
+        }
+    };
+    if let Some(error) = resp["error"].as_str() {
+        eprintln!("Error: {error}");
+        return;
+    }
+    let indexes = resp["data"].as_array().cloned().unwrap_or_default();
+
+    let mut reports = Vec::new();
+    if verify {
+        for index in &indexes {
+            let name = index["name"].as_str().unwrap_or_default();
+            let report = api_get(client, node, &format!("admin/indexes/{name}/verify"))
+                .await
+                .map(|resp| resp["data"].clone())
+                .unwrap_or_else(|e| serde_json::json!({ "error": e }));
+            reports.push(report);
+        }
+    }
+
+    if json {
+        if verify {
+            print_json(&Value::Array(reports));
+        } else {
+            print_json(&Value::Array(indexes));
+        }
+        return;
+    }
+
+    println!("{:<24} {:<16} {:<32}", "NAME", "CHAINCODE", "FIELDS");
+    for index in &indexes {
+        let fields: Vec<&str> = index["fields"]
+            .as_array()
+            .map(|f| f.iter().filter_map(|v| v.as_str()).collect())
+            .unwrap_or_default();
+        println!(
+            "{:<24} {:<16} {:<32}",
+            index["name"].as_str().unwrap_or("-"),
+            index["chaincode_id"].as_str().unwrap_or("-"),
+            fields.join(",")
+        );
+    }
+    if verify {
+        println!();
+        println!(
+            "{:<24} {:<10} {:<10} {:<10} {:<6}",
+            "NAME", "ENTRIES", "MISSING", "STALE", "OK"
+        );
+        for r in &reports {
+            let missing = r["missing"].as_u64().unwrap_or(0);
+            let stale = r["stale"].as_u64().unwrap_or(0);
+            let ok = r.get("error").is_none() && missing == 0 && stale == 0;
+            println!(
+                "{:<24} {:<10} {:<10} {:<10} {:<6}",
+                r["name"].as_str().unwrap_or("-"),
+                r["entries"].as_u64().unwrap_or(0),
+                missing,
+                stale,
+                if ok { "yes" } else { "NO" }
+            );
+        }
+    }
+}
+
+#[cfg(feature = "rocksdb-storage")]
+fn cmd_restore(
+    backup_id: u64,
+    backup_dir: &std::path::Path,
+    storage_path: &std::path::Path,
+    json: bool,
+) {
+    match rust_bc::storage::backup::restore_backup(backup_dir, backup_id, storage_path) {
+        Ok(report) => {
+            if json {
+                print_json(&serde_json::to_value(&report).unwrap_or_default());
+            } else {
+                println!(
+                    "Restored backup {} into {} (height {}, schema v{})",
+                    report.backup.id,
+                    storage_path.display(),
+                    report.backup.latest_height,
+                    report.backup.schema_version
+                );
+                println!(
+                    "Hash chain verified: {} linked, {} unlinked blocks",
+                    report.chain.linked_blocks, report.chain.unlinked_blocks
+                );
+                if let Some(previous) = report.previous_data {
+                    println!("Previous data kept at {}", previous.display());
+                }
+            }
+        }
+        Err(e) => {
+            eprintln!("Restore failed, data directory left unchanged: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(all(feature = "rocksdb-storage", feature = "redb-storage"))]
+fn cmd_migrate_storage(from: &std::path::Path, to: &std::path::Path, json: bool) {
+    match rust_bc::storage::redb_store::migrate_from_rocksdb(from, to) {
+        Ok(report) => {
+            if json {
+                print_json(&serde_json::to_value(&report).unwrap_or_default());
+            } else {
+                for (table, entries) in &report.tables {
+                    println!("{table:<24} {entries}");
+                }
+                println!(
+                    "Migrated {} to {} (height {}); start the node with STORAGE_BACKEND=redb STORAGE_PATH={}",
+                    from.display(),
+                    to.display(),
+                    report.latest_height,
+                    to.display()
+                );
+            }
+        }
+        Err(e) => {
+            eprintln!("Migration failed: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(feature = "rocksdb-storage")]
+fn cmd_ledger_verify(
+    path: &std::path::Path,
+    orderer: Option<&str>,
+    signing_key: Option<&std::path::Path>,
+    out: Option<&std::path::Path>,
+    archive_dir: Option<&std::path::Path>,
+    json: bool,
+) {
+    use rust_bc::identity::signing::{
+        MlDsaSigningProvider, SigningProvider, SoftwareSigningProvider,
+    };
+    use rust_bc::storage::archive::{trusted_keys_from_env, BlockArchive};
+    use rust_bc::storage::verify::{LedgerVerifier, SignedLedgerReport};
+    use std::sync::Arc;
+
+    let signer: Arc<dyn SigningProvider> = match signing_key {
+        Some(file) => {
+            let seed = std::fs::read_to_string(file)
+                .ok()
+                .and_then(|s| hex::decode(s.trim()).ok())
+                .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok());
+            let Some(seed) = seed else {
+                eprintln!(
+                    "Error: {} must hold a hex Ed25519 secret key",
+                    file.display()
+                );
+                std::process::exit(1);
+            };
+            Arc::new(SoftwareSigningProvider::from_key(
+                ed25519_dalek::SigningKey::from_bytes(&seed),
+            ))
+        }
+        None => match std::env::var("SIGNING_ALGORITHM")
+            .unwrap_or_default()
+            .to_lowercase()
+            .as_str()
+        {
+            "ml-dsa-65" | "mldsa65" => Arc::new(MlDsaSigningProvider::generate()),
+            _ => Arc::new(SoftwareSigningProvider::generate()),
+        },
+    };
+    let archive = archive_dir.map(|dir| {
+        BlockArchive::open(dir, "default", signer.clone(), trusted_keys_from_env())
+            .unwrap_or_else(|e| {
+                eprintln!("Error: cannot open the archive at {}: {e}", dir.display());
+                std::process::exit(1);
+            })
+    });
+
+    let result = rust_bc::storage::encryption::kek_from_env()
+        .and_then(|kek| rust_bc::storage::RocksDbBlockStore::open_read_only(path, kek))
+        .and_then(|store| {
+            let store = Arc::new(store);
+            let mut verifier = LedgerVerifier::new(store.as_ref())
+                .with_state(store.as_ref())
+                .with_registry(store.clone());
+            if let Some(orderer) = orderer {
+                verifier = verifier.with_orderer(orderer);
+            }
+            if let Some(archive) = &archive {
+                verifier = verifier.with_archive(archive);
+            }
+            verifier.verify()
+        })
+        .and_then(|report| SignedLedgerReport::sign(report, signer.as_ref()));
+    let signed = match result {
+        Ok(signed) => signed,
+        Err(e) => {
+            eprintln!("Verification failed to run: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    if let Some(out) = out {
+        let encoded = serde_json::to_string_pretty(&signed).unwrap_or_default();
+        if let Err(e) = std::fs::write(out, encoded) {
+            eprintln!("Error: cannot write {}: {e}", out.display());
+            std::process::exit(1);
+        }
+    }
+    let report = &signed.report;
+    if json {
+        print_json(&serde_json::to_value(&signed).unwrap_or_default());
+    } else {
+        match report.latest_height {
+            Some(height) => println!(
+                "Ledger at {}: height {height}, tip {}",
+                path.display(),
+                report.tip_hash.as_deref().unwrap_or("-")
+            ),
+            None => println!("Ledger at {} is empty", path.display()),
+        }
+        println!(
+            "{:<16} {} ({} linked, {} unlinked)",
+            "blocks", report.blocks, report.linked_blocks, report.unlinked_blocks
+        );
+        println!(
+            "{:<16} {} checked",
+            "merkle roots", report.merkle_roots_checked
+        );
+        println!("{:<16} {} checked", "signatures", report.signatures_checked);
+        println!(
+            "{:<16} {} indexed, {} not stored",
+            "transactions", report.transactions_indexed, report.transactions_not_stored
+        );
+        println!(
+            "{:<16} {} keys verified, {} off-ledger",
+            "world state", report.state_keys_verified, report.state_keys_off_ledger
+        );
+        if let Some(through) = report.archived_through {
+            println!(
+                "{:<16} through {through}, {} blocks read back",
+                "archive", report.archived_blocks
+            );
+        }
+        if report.is_clean() {
+            println!("No findings.");
+        } else {
+            println!("\n{:<12} {:<8} DETAIL", "CHECK", "HEIGHT");
+            for finding in &report.findings {
+                let check = serde_json::to_value(finding.check).unwrap_or_default();
+                println!(
+                    "{:<12} {:<8} {}",
+                    check.as_str().unwrap_or("-"),
+                    finding.height.map_or("-".to_string(), |h| h.to_string()),
+                    finding.detail
+                );
+            }
+        }
+        println!("Report signed by {} ({})", signed.signer, signed.algorithm);
+    }
+    if !report.is_clean() {
+        std::process::exit(1);
+    }
+}
+
+#[cfg(feature = "sql-projection")]
+fn cmd_sql(path: &std::path::Path, query: Option<&str>, rebuild_from: Option<u64>, json: bool) {
+    use rust_bc::storage::projection::{query_read_only, ProjectionSink, SqliteProjection};
+
+    if let Some(height) = rebuild_from {
+        if !path.exists() {
+            eprintln!("Error: no projection at {}", path.display());
+            std::process::exit(1);
+        }
+        match SqliteProjection::open(path).and_then(|mut sink| sink.rewind(height)) {
+            Ok(()) => println!("Projection rewound to height {height}; the node rebuilds it from there on its next block"),
+            Err(e) => {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let Some(query) = query else {
+        eprintln!("Error: give a query or --rebuild-from");
+        std::process::exit(1);
+    };
+    let result = match query_read_only(path, query) {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    };
+    if json {
+        print_json(&serde_json::to_value(&result).unwrap_or_default());
+        return;
+    }
+    println!("{}", result.columns.join("\t"));
+    for row in &result.rows {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|v| match v {
+                Value::String(s) => s.clone(),
+                Value::Null => "NULL".to_string(),
+                v => v.to_string(),
+            })
+            .collect();
+        println!("{}", cells.join("\t"));
+    }
+}
+
+#[cfg(not(feature = "sql-projection"))]
+fn cmd_sql(_: &std::path::Path, _: Option<&str>, _: Option<u64>, _: bool) {
+    eprintln!("Error: bcctl was built without the sql-projection feature");
+    std::process::exit(1);
+}
+
+#[cfg(not(all(feature = "rocksdb-storage", feature = "redb-storage")))]
+fn cmd_migrate_storage(_: &std::path::Path, _: &std::path::Path, _: bool) {
+    eprintln!("Error: bcctl was built without the rocksdb-storage and redb-storage features");
+    std::process::exit(1);
+}
+
+#[cfg(not(feature = "rocksdb-storage"))]
+fn cmd_ledger_verify(
+    _: &std::path::Path,
+    _: Option<&str>,
+    _: Option<&std::path::Path>,
+    _: Option<&std::path::Path>,
+    _: Option<&std::path::Path>,
+    _: bool,
+) {
+    eprintln!("Error: bcctl was built without the rocksdb-storage feature");
+    std::process::exit(1);
+}
+
+#[cfg(not(feature = "rocksdb-storage"))]
+fn cmd_restore(_: u64, _: &std::path::Path, _: &std::path::Path, _: bool) {
+    eprintln!("Error: bcctl was built without the rocksdb-storage feature");
+    std::process::exit(1);
+}
+
 // ── Main ─────────────────────────────────────────────────────────────────────
 
 #[tokio::main]
@@ -459,5 +1015,38 @@ async fn main() {
         Commands::Env => cmd_env().await,
         Commands::Logs { target, lines } => cmd_logs(&target, lines),
         Commands::Restart { target } => cmd_restart(&target),
+        Commands::Backup { list } => cmd_backup(&client, &cli.node, list, json).await,
+        Commands::Encryption { rotate, scope } => {
+            cmd_encryption(&client, &cli.node, rotate, scope, json).await
+        }
+        Commands::Indexes { verify } => cmd_indexes(&client, &cli.node, verify, json).await,
+        Commands::Sql {
+            path,
+            query,
+            rebuild_from,
+        } => cmd_sql(&path, query.as_deref(), rebuild_from, json),
+        Commands::MigrateStorage { from, to } => cmd_migrate_storage(&from, &to, json),
+        Commands::Ledger {
+            command:
+                LedgerCommand::Verify {
+                    path,
+                    orderer,
+                    signing_key,
+                    out,
+                    archive_dir,
+                },
+        } => cmd_ledger_verify(
+            &path,
+            orderer.as_deref(),
+            signing_key.as_deref(),
+            out.as_deref(),
+            archive_dir.as_deref(),
+            json,
+        ),
+        Commands::Restore {
+            backup_id,
+            backup_dir,
+            storage_path,
+        } => cmd_restore(backup_id, &backup_dir, &storage_path, json),
     }
 }
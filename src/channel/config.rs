@@ -9,6 +9,7 @@ use crate::endorsement::policy_store::PolicyStore;
 use crate::endorsement::registry::OrgRegistry;
 use crate::endorsement::types::Endorsement;
 use crate::endorsement::validator::{validate_endorsements, EndorsementError};
+use crate::identity::key_rotation::{KeyRotation, KeySchedule};
 
 /// Errors that can occur when processing channel configuration.
 #[derive(Debug, thiserror::Error, PartialEq)]
@@ -25,6 +26,38 @@ pub enum ChannelError {
     PolicyNotFound(String),
     #[error("endorsement validation failed: {0}")]
     EndorsementFailed(String),
+    #[error("consenter already exists: {0}")]
+    ConsenterAlreadyExists(u64),
+    #[error("consenter not found: {0}")]
+    ConsenterNotFound(u64),
+    #[error("consenter {0} is already a voter")]
+    ConsenterAlreadyVoter(u64),
+    #[error("invalid config block at height {height}: {reason}")]
+    InvalidConfigBlock { height: u64, reason: String },
+    #[error("config history unavailable: {0}")]
+    ConfigHistory(String),
+    #[error("ledger write failed: {0}")]
+    Storage(String),
+    #[error("invalid block size limits: preferred {preferred} bytes, absolute {absolute} bytes")]
+    InvalidBlockSizeLimits { preferred: usize, absolute: usize },
+    #[error("invalid key rotation: {0}")]
+    InvalidKeyRotation(String),
+}
+
+/// Default soft cap on the transaction bytes batched into one block.
+pub const DEFAULT_PREFERRED_MAX_BYTES: usize = 1024 * 1024;
+
+/// Default hard cap on the transaction bytes of one block. Kept under the
+/// 4 MB default of `P2P_RESPONSE_BUFFER_BYTES` so a full block still fits in
+/// one P2P response alongside its signatures.
+pub const DEFAULT_ABSOLUTE_MAX_BYTES: usize = 3 * 1024 * 1024;
+
+fn default_preferred_max_bytes() -> usize {
+    DEFAULT_PREFERRED_MAX_BYTES
+}
+
+fn default_absolute_max_bytes() -> usize {
+    DEFAULT_ABSOLUTE_MAX_BYTES
 }
 
 /// Data retention policy for a channel.
@@ -47,6 +80,18 @@ pub struct RetentionPolicy {
     pub transaction_retention_secs: u64,
 }
 
+/// An orderer participating in the channel's Raft consenter set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Consenter {
+    /// Raft node ID of the orderer.
+    pub raft_id: u64,
+    /// P2P address (`host:port`) the orderer listens on.
+    pub address: String,
+    /// `true` while the orderer is a non-voting learner catching up on the log.
+    #[serde(default)]
+    pub learner: bool,
+}
+
 /// Full governance configuration of a channel.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ChannelConfig {
@@ -64,11 +109,32 @@ pub struct ChannelConfig {
     pub batch_size: usize,
     /// Maximum time (ms) the orderer waits before cutting a block.
     pub batch_timeout_ms: u64,
+    /// Transaction bytes at which the orderer cuts a block early. A single
+    /// transaction larger than this is cut into a block of its own.
+    #[serde(default = "default_preferred_max_bytes")]
+    pub preferred_max_bytes: usize,
+    /// Largest transaction (and block payload) the orderer accepts, in bytes.
+    #[serde(default = "default_absolute_max_bytes")]
+    pub absolute_max_bytes: usize,
     /// Anchor peers per org: org_id → list of "host:port" addresses.
     pub anchor_peers: HashMap<String, Vec<String>>,
     /// Data retention policy for this channel.
     #[serde(default)]
     pub retention_policy: RetentionPolicy,
+    /// Raft consenters (orderers) serving this channel.
+    #[serde(default)]
+    pub consenters: Vec<Consenter>,
+    /// Committed validator and orderer signing key rotations, in order.
+    #[serde(default)]
+    pub key_rotations: Vec<KeyRotation>,
+}
+
+impl ChannelConfig {
+    /// Key schedule built from the committed rotations. Each rotation was
+    /// checked when its config update applied, so replaying cannot fail.
+    pub fn key_schedule(&self) -> KeySchedule {
+        KeySchedule::from_rotations(&self.key_rotations).unwrap_or_default()
+    }
 }
 
 impl Default for ChannelConfig {
@@ -81,8 +147,12 @@ impl Default for ChannelConfig {
             acls: HashMap::new(),
             batch_size: 100,
             batch_timeout_ms: 2000,
+            preferred_max_bytes: DEFAULT_PREFERRED_MAX_BYTES,
+            absolute_max_bytes: DEFAULT_ABSOLUTE_MAX_BYTES,
             anchor_peers: HashMap::new(),
             retention_policy: RetentionPolicy::default(),
+            consenters: Vec::new(),
+            key_rotations: Vec::new(),
         }
     }
 }
@@ -100,11 +170,26 @@ pub enum ConfigUpdateType {
     },
     SetBatchSize(usize),
     SetBatchTimeout(u64),
+    /// Set the preferred and absolute block byte limits together; the
+    /// preferred limit must be non-zero and not above the absolute one.
+    SetMaxBytes {
+        preferred_max_bytes: usize,
+        absolute_max_bytes: usize,
+    },
     SetAnchorPeer {
         org_id: String,
         peer_address: String,
     },
     SetRetention(RetentionPolicy),
+    /// Add an orderer to the consenter set as a non-voting learner.
+    AddConsenter(Consenter),
+    /// Promote a caught-up learner to a voting consenter.
+    PromoteConsenter(u64),
+    /// Remove an orderer (voter or learner) from the consenter set.
+    RemoveConsenter(u64),
+    /// Announce a validator or orderer signing key rotation, signed by both
+    /// the old and the new key.
+    RotateKey(KeyRotation),
 }
 
 /// Apply a slice of [`ConfigUpdateType`] changes to `config`, returning a new
@@ -147,6 +232,19 @@ pub fn apply_config_update(
             ConfigUpdateType::SetBatchTimeout(ms) => {
                 next.batch_timeout_ms = *ms;
             }
+            ConfigUpdateType::SetMaxBytes {
+                preferred_max_bytes,
+                absolute_max_bytes,
+            } => {
+                if *preferred_max_bytes == 0 || preferred_max_bytes > absolute_max_bytes {
+                    return Err(ChannelError::InvalidBlockSizeLimits {
+                        preferred: *preferred_max_bytes,
+                        absolute: *absolute_max_bytes,
+                    });
+                }
+                next.preferred_max_bytes = *preferred_max_bytes;
+                next.absolute_max_bytes = *absolute_max_bytes;
+            }
             ConfigUpdateType::SetAnchorPeer {
                 org_id,
                 peer_address,
@@ -159,6 +257,44 @@ pub fn apply_config_update(
             ConfigUpdateType::SetRetention(policy) => {
                 next.retention_policy = policy.clone();
             }
+            ConfigUpdateType::AddConsenter(consenter) => {
+                if next
+                    .consenters
+                    .iter()
+                    .any(|c| c.raft_id == consenter.raft_id)
+                {
+                    return Err(ChannelError::ConsenterAlreadyExists(consenter.raft_id));
+                }
+                next.consenters.push(Consenter {
+                    learner: true,
+                    ..consenter.clone()
+                });
+            }
+            ConfigUpdateType::PromoteConsenter(id) => {
+                let consenter = next
+                    .consenters
+                    .iter_mut()
+                    .find(|c| c.raft_id == *id)
+                    .ok_or(ChannelError::ConsenterNotFound(*id))?;
+                if !consenter.learner {
+                    return Err(ChannelError::ConsenterAlreadyVoter(*id));
+                }
+                consenter.learner = false;
+            }
+            ConfigUpdateType::RemoveConsenter(id) => {
+                let pos = next
+                    .consenters
+                    .iter()
+                    .position(|c| c.raft_id == *id)
+                    .ok_or(ChannelError::ConsenterNotFound(*id))?;
+                next.consenters.remove(pos);
+            }
+            ConfigUpdateType::RotateKey(rotation) => {
+                next.key_schedule()
+                    .check(rotation)
+                    .map_err(|e| ChannelError::InvalidKeyRotation(e.to_string()))?;
+                next.key_rotations.push(rotation.clone());
+            }
         }
     }
     next.version += 1;
@@ -186,6 +322,22 @@ pub fn validate_config_tx(
         .map_err(|e: EndorsementError| ChannelError::EndorsementFailed(e.to_string()))
 }
 
+/// Reject key rotations in `updates` that would activate at or below
+/// `current_height`, the latest committed block of the channel.
+pub fn check_key_rotation_activation(
+    updates: &[ConfigUpdateType],
+    current_height: u64,
+) -> Result<(), ChannelError> {
+    for update in updates {
+        if let ConfigUpdateType::RotateKey(rotation) = update {
+            rotation
+                .check_activation(current_height)
+                .map_err(|e| ChannelError::InvalidKeyRotation(e.to_string()))?;
+        }
+    }
+    Ok(())
+}
+
 /// A signed proposal to apply one or more [`ConfigUpdateType`] changes to a channel.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ConfigTransaction {
@@ -201,6 +353,22 @@ pub struct ConfigTransaction {
     pub created_at: u64,
 }
 
+/// Prefix of the ledger transaction ID of a config transaction.
+pub const CONFIG_TX_PREFIX: &str = "config-";
+
+impl ConfigTransaction {
+    /// ID under which the transaction is listed in its config block:
+    /// [`CONFIG_TX_PREFIX`] followed by the hex SHA-256 of its JSON, so the
+    /// block's merkle root commits to the full transaction.
+    pub fn ledger_tx_id(&self) -> String {
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        format!(
+            "{CONFIG_TX_PREFIX}{}",
+            hex::encode(crate::private_data::sha256(&json))
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -214,11 +382,19 @@ mod tests {
             acls: HashMap::from([("peer/ChaincodeInvoke".to_string(), "OrgPolicy".to_string())]),
             batch_size: 50,
             batch_timeout_ms: 1000,
+            preferred_max_bytes: 512 * 1024,
+            absolute_max_bytes: 2 * 1024 * 1024,
             anchor_peers: HashMap::from([(
                 "org1".to_string(),
                 vec!["peer0.org1:7051".to_string()],
             )]),
             retention_policy: RetentionPolicy::default(),
+            consenters: vec![Consenter {
+                raft_id: 1,
+                address: "orderer1:8087".to_string(),
+                learner: false,
+            }],
+            key_rotations: Vec::new(),
         }
     }
 
@@ -354,6 +530,18 @@ mod tests {
         assert_eq!(tx, restored);
     }
 
+    #[test]
+    fn ledger_tx_id_commits_to_the_transaction() {
+        let tx = sample_config_tx();
+        let id = tx.ledger_tx_id();
+        assert!(id.starts_with(CONFIG_TX_PREFIX));
+        assert_eq!(id, sample_config_tx().ledger_tx_id());
+
+        let mut changed = tx.clone();
+        changed.updates.push(ConfigUpdateType::SetBatchTimeout(10));
+        assert_ne!(changed.ledger_tx_id(), id);
+    }
+
     #[test]
     fn config_transaction_empty_updates_allowed() {
         let tx = ConfigTransaction {
@@ -479,6 +667,46 @@ mod tests {
         }"#;
         let cfg: ChannelConfig = serde_json::from_str(json).expect("deserialize");
         assert_eq!(cfg.retention_policy, RetentionPolicy::default());
+        assert_eq!(cfg.preferred_max_bytes, DEFAULT_PREFERRED_MAX_BYTES);
+        assert_eq!(cfg.absolute_max_bytes, DEFAULT_ABSOLUTE_MAX_BYTES);
+    }
+
+    #[test]
+    fn apply_set_max_bytes_updates_both_limits() {
+        let cfg = sample();
+        let next = apply_config_update(
+            &cfg,
+            &[ConfigUpdateType::SetMaxBytes {
+                preferred_max_bytes: 256 * 1024,
+                absolute_max_bytes: 1024 * 1024,
+            }],
+        )
+        .expect("apply");
+        assert_eq!(next.preferred_max_bytes, 256 * 1024);
+        assert_eq!(next.absolute_max_bytes, 1024 * 1024);
+        assert_eq!(next.version, cfg.version + 1);
+    }
+
+    #[test]
+    fn apply_set_max_bytes_rejects_preferred_above_absolute() {
+        let cfg = sample();
+        for (preferred, absolute) in [(2048, 1024), (0, 1024)] {
+            let err = apply_config_update(
+                &cfg,
+                &[ConfigUpdateType::SetMaxBytes {
+                    preferred_max_bytes: preferred,
+                    absolute_max_bytes: absolute,
+                }],
+            )
+            .unwrap_err();
+            assert_eq!(
+                err,
+                ChannelError::InvalidBlockSizeLimits {
+                    preferred,
+                    absolute
+                }
+            );
+        }
     }
 
     #[test]
@@ -597,4 +825,155 @@ mod tests {
 
         assert!(validate_config_tx(&tx, &cfg, &policy_store, &reg).is_err());
     }
+
+    // ── Consenter membership tests ───────────────────────────────────────────
+
+    fn consenter(raft_id: u64) -> Consenter {
+        Consenter {
+            raft_id,
+            address: format!("orderer{raft_id}:8087"),
+            learner: false,
+        }
+    }
+
+    #[test]
+    fn config_update_add_consenter_roundtrip() {
+        let u = ConfigUpdateType::AddConsenter(consenter(4));
+        assert_eq!(roundtrip(u.clone()), u);
+    }
+
+    #[test]
+    fn apply_add_consenter_starts_as_learner() {
+        let cfg = sample();
+        let next = apply_config_update(&cfg, &[ConfigUpdateType::AddConsenter(consenter(4))])
+            .expect("apply");
+        let added = next.consenters.iter().find(|c| c.raft_id == 4).unwrap();
+        assert!(added.learner);
+        assert_eq!(added.address, "orderer4:8087");
+    }
+
+    #[test]
+    fn apply_promote_consenter_clears_learner_flag() {
+        let cfg = sample();
+        let next = apply_config_update(
+            &cfg,
+            &[
+                ConfigUpdateType::AddConsenter(consenter(4)),
+                ConfigUpdateType::PromoteConsenter(4),
+            ],
+        )
+        .expect("apply");
+        assert!(
+            !next
+                .consenters
+                .iter()
+                .find(|c| c.raft_id == 4)
+                .unwrap()
+                .learner
+        );
+    }
+
+    #[test]
+    fn apply_promote_voter_returns_error() {
+        let cfg = sample();
+        let err = apply_config_update(&cfg, &[ConfigUpdateType::PromoteConsenter(1)]).unwrap_err();
+        assert_eq!(err, ChannelError::ConsenterAlreadyVoter(1));
+    }
+
+    #[test]
+    fn apply_add_duplicate_consenter_returns_error() {
+        let cfg = sample();
+        let err =
+            apply_config_update(&cfg, &[ConfigUpdateType::AddConsenter(consenter(1))]).unwrap_err();
+        assert_eq!(err, ChannelError::ConsenterAlreadyExists(1));
+    }
+
+    #[test]
+    fn apply_remove_consenter() {
+        let cfg = sample();
+        let next =
+            apply_config_update(&cfg, &[ConfigUpdateType::RemoveConsenter(1)]).expect("apply");
+        assert!(next.consenters.is_empty());
+
+        let err = apply_config_update(&cfg, &[ConfigUpdateType::RemoveConsenter(9)]).unwrap_err();
+        assert_eq!(err, ChannelError::ConsenterNotFound(9));
+    }
+
+    // ── key rotation ─────────────────────────────────────────────────────────
+
+    fn rotation(
+        old: &crate::identity::signing::SoftwareSigningProvider,
+        new: &crate::identity::signing::SoftwareSigningProvider,
+        activation_height: u64,
+    ) -> KeyRotation {
+        use crate::identity::signing::SigningProvider;
+        KeyRotation::signed(
+            hex::encode(old.public_key()),
+            old,
+            new,
+            activation_height,
+            10,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn apply_rotate_key_records_rotation() {
+        use crate::identity::signing::{SigningProvider, SoftwareSigningProvider};
+        let (old, new) = (
+            SoftwareSigningProvider::generate(),
+            SoftwareSigningProvider::generate(),
+        );
+        let r = rotation(&old, &new, 20);
+        let next = apply_config_update(&sample(), &[ConfigUpdateType::RotateKey(r.clone())])
+            .expect("apply");
+        assert_eq!(next.key_rotations, vec![r.clone()]);
+        assert_eq!(
+            next.key_schedule().current_key(&r.subject),
+            Some(new.public_key())
+        );
+        assert_eq!(
+            roundtrip(ConfigUpdateType::RotateKey(r.clone())),
+            ConfigUpdateType::RotateKey(r)
+        );
+    }
+
+    #[test]
+    fn apply_rotate_key_rejects_bad_signature_and_replay() {
+        use crate::identity::signing::SoftwareSigningProvider;
+        let (old, new) = (
+            SoftwareSigningProvider::generate(),
+            SoftwareSigningProvider::generate(),
+        );
+        let mut forged = rotation(&old, &new, 20);
+        forged.new_signature[0] ^= 0xFF;
+        assert!(matches!(
+            apply_config_update(&sample(), &[ConfigUpdateType::RotateKey(forged)]),
+            Err(ChannelError::InvalidKeyRotation(_))
+        ));
+
+        // Replaying the same rotation no longer replaces the current key.
+        let r = rotation(&old, &new, 20);
+        let next = apply_config_update(&sample(), &[ConfigUpdateType::RotateKey(r.clone())])
+            .expect("apply");
+        assert!(matches!(
+            apply_config_update(&next, &[ConfigUpdateType::RotateKey(r)]),
+            Err(ChannelError::InvalidKeyRotation(_))
+        ));
+    }
+
+    #[test]
+    fn key_rotation_must_activate_after_current_height() {
+        use crate::identity::signing::SoftwareSigningProvider;
+        let (old, new) = (
+            SoftwareSigningProvider::generate(),
+            SoftwareSigningProvider::generate(),
+        );
+        let updates = [ConfigUpdateType::RotateKey(rotation(&old, &new, 20))];
+        assert_eq!(check_key_rotation_activation(&updates, 19), Ok(()));
+        assert!(matches!(
+            check_key_rotation_activation(&updates, 20),
+            Err(ChannelError::InvalidKeyRotation(_))
+        ));
+    }
 }
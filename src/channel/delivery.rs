@@ -0,0 +1,188 @@
+//! Delivery of ordered blocks to a channel ledger.
+//!
+//! A channel's ordering group cuts its blocks; the [`ChannelCommitter`]
+//! takes them in order, checks them with the [`ConfigValidator`] and writes
+//! them to the channel ledger. A committed config block is then applied on
+//! the node: the ordering backend follows its consenter and batch updates,
+//! and the committer's hook handles the rest (cached config history, signer
+//! rotation). Config transactions take effect here and nowhere else.
+
+use std::sync::Arc;
+
+use crate::channel::config::{ChannelConfig, ChannelError, ConfigTransaction};
+use crate::channel::ledger::ConfigValidator;
+use crate::ordering::OrderingBackend;
+use crate::storage::traits::BlockStore;
+
+/// How often the delivery loop asks the ordering backend for new blocks.
+pub const DELIVERY_INTERVAL_MS: u64 = 100;
+
+/// Called with the transaction and resulting config of every config block
+/// once it is committed.
+pub type ConfigCommitHook = Box<dyn Fn(&ConfigTransaction, &ChannelConfig) + Send + Sync>;
+
+/// Commits the blocks a channel's ordering backend cuts to its ledger.
+pub struct ChannelCommitter {
+    channel_id: String,
+    store: Arc<dyn BlockStore>,
+    backend: Arc<dyn OrderingBackend>,
+    validator: ConfigValidator,
+    on_config: Option<ConfigCommitHook>,
+}
+
+impl ChannelCommitter {
+    pub fn new(
+        channel_id: impl Into<String>,
+        store: Arc<dyn BlockStore>,
+        backend: Arc<dyn OrderingBackend>,
+        validator: ConfigValidator,
+    ) -> Self {
+        Self {
+            channel_id: channel_id.into(),
+            store,
+            backend,
+            validator,
+            on_config: None,
+        }
+    }
+
+    /// Run `hook` after each committed config block.
+    pub fn on_config(
+        mut self,
+        hook: impl Fn(&ConfigTransaction, &ChannelConfig) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_config = Some(Box::new(hook));
+        self
+    }
+
+    /// Cut and commit every block the backend has ready. Returns how many
+    /// blocks were committed; a block failing validation is dropped and
+    /// ends the round with its error.
+    pub fn deliver(&self) -> Result<usize, ChannelError> {
+        let mut committed = 0;
+        loop {
+            let height = self.store.get_latest_height().unwrap_or(0) + 1;
+            let Some(block) = self
+                .backend
+                .cut_block(height, "orderer")
+                .map_err(|e| ChannelError::Storage(e.to_string()))?
+            else {
+                return Ok(committed);
+            };
+            let (block, config) = self.validator.commit(self.store.as_ref(), block)?;
+            committed += 1;
+            if let (Some(tx), Some(config)) = (&block.config_tx, config) {
+                log::info!(
+                    "Channel '{}' committed config block {} (version {})",
+                    self.channel_id,
+                    block.height,
+                    config.version
+                );
+                self.backend
+                    .apply_config_updates(&tx.updates)
+                    .map_err(|e| ChannelError::Storage(e.to_string()))?;
+                if let Some(hook) = &self.on_config {
+                    hook(tx, &config);
+                }
+            }
+        }
+    }
+}
+
+/// Deliver the channel's blocks every [`DELIVERY_INTERVAL_MS`], for as long
+/// as the node runs.
+pub async fn run_delivery_loop(committer: Arc<ChannelCommitter>) {
+    let interval = tokio::time::Duration::from_millis(DELIVERY_INTERVAL_MS);
+    loop {
+        tokio::time::sleep(interval).await;
+        // Cutting may wait for consensus (BFT), so it runs off the runtime.
+        let round = committer.clone();
+        match tokio::task::spawn_blocking(move || round.deliver()).await {
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => log::warn!("Channel '{}' delivery: {e}", committer.channel_id),
+            Err(e) => log::error!(
+                "Channel '{}' delivery task failed: {e}",
+                committer.channel_id
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channel::config::ConfigUpdateType;
+    use crate::channel::genesis::create_genesis_block;
+    use crate::endorsement::policy::EndorsementPolicy;
+    use crate::endorsement::{MemoryOrgRegistry, MemoryPolicyStore};
+    use crate::ordering::admission::AdmissionChain;
+    use crate::ordering::service::OrderingService;
+    use crate::storage::traits::Transaction;
+    use crate::storage::MemoryStore;
+    use std::sync::Mutex;
+
+    fn make_tx(id: &str) -> Transaction {
+        Transaction {
+            id: id.to_string(),
+            block_height: 0,
+            timestamp: 0,
+            input_did: "did:bc:alice".to_string(),
+            output_recipient: "did:bc:bob".to_string(),
+            amount: 1,
+            state: "pending".to_string(),
+        }
+    }
+
+    #[test]
+    fn config_blocks_take_effect_when_they_are_committed() {
+        let config = ChannelConfig {
+            endorsement_policy: EndorsementPolicy::AllOf(vec![]),
+            ..ChannelConfig::default()
+        };
+        let store: Arc<dyn BlockStore> = Arc::new(MemoryStore::new());
+        store
+            .write_block(&create_genesis_block("ch1", &config))
+            .unwrap();
+        let backend =
+            Arc::new(OrderingService::with_config(100, 2000).with_admission(AdmissionChain::new()));
+        let applied = Arc::new(Mutex::new(Vec::new()));
+        let seen = applied.clone();
+        let committer = ChannelCommitter::new(
+            "ch1",
+            store.clone(),
+            backend.clone(),
+            ConfigValidator::new(
+                Arc::new(MemoryPolicyStore::new()),
+                Arc::new(MemoryOrgRegistry::new()),
+            ),
+        )
+        .on_config(move |tx, config| {
+            seen.lock()
+                .unwrap()
+                .push((tx.tx_id.clone(), config.batch_size))
+        });
+
+        backend.submit_tx(make_tx("tx-a")).unwrap();
+        backend
+            .submit_config_tx(ConfigTransaction {
+                tx_id: "cfg-1".to_string(),
+                channel_id: "ch1".to_string(),
+                updates: vec![ConfigUpdateType::SetBatchSize(1)],
+                signatures: vec![],
+                created_at: 0,
+            })
+            .unwrap();
+        // Submitting orders the transaction; nothing is applied yet.
+        assert_eq!(backend.max_batch_size(), 100);
+        assert!(applied.lock().unwrap().is_empty());
+
+        assert_eq!(committer.deliver().unwrap(), 2);
+        assert_eq!(store.get_latest_height().unwrap(), 2);
+        assert!(store.read_block(1).unwrap().config_tx.is_some());
+        assert_eq!(store.read_block(2).unwrap().last_config, 1);
+        assert_eq!(backend.max_batch_size(), 1);
+        assert_eq!(*applied.lock().unwrap(), vec![("cfg-1".to_string(), 1)]);
+
+        assert_eq!(committer.deliver().unwrap(), 0);
+    }
+}
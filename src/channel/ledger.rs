@@ -0,0 +1,394 @@
+//! Channel config in the ledger.
+//!
+//! A [`ConfigTransaction`] is ordered like any other transaction but always
+//! travels alone in a *config block*. Every block records in `last_config`
+//! the height of the latest config block at or below it, so a peer can
+//! rebuild the channel's config history from its ledger alone: genesis
+//! config from block 0, then the config blocks reached by following the
+//! pointers back from the tip.
+
+use std::sync::Arc;
+
+use crate::channel::config::{
+    apply_config_update, validate_config_tx, ChannelConfig, ChannelError, ConfigTransaction,
+    CONFIG_TX_PREFIX,
+};
+use crate::endorsement::policy_store::PolicyStore;
+use crate::endorsement::registry::OrgRegistry;
+use crate::storage::traits::{Block, BlockStore};
+
+/// Check that `block` is well formed with respect to config transactions:
+/// a config block lists exactly its config transaction's ledger ID, and any
+/// other block lists no config transaction ID at all.
+pub fn check_config_block_shape(block: &Block) -> Result<(), ChannelError> {
+    let invalid = |reason: &str| ChannelError::InvalidConfigBlock {
+        height: block.height,
+        reason: reason.to_string(),
+    };
+    match &block.config_tx {
+        Some(tx) => {
+            if block.transactions != [tx.ledger_tx_id()] {
+                return Err(invalid(
+                    "a config block must list only its config transaction",
+                ));
+            }
+        }
+        None => {
+            if block
+                .transactions
+                .iter()
+                .any(|id| id.starts_with(CONFIG_TX_PREFIX))
+            {
+                return Err(invalid(
+                    "config transaction ID without a config transaction",
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The block at the tip of `store`, if any.
+fn tip(store: &dyn BlockStore) -> Option<Block> {
+    let height = store.get_latest_height().ok()?;
+    store.read_block(height).ok()
+}
+
+/// Config stored in the channel genesis block (block 0).
+pub fn genesis_config(store: &dyn BlockStore) -> Result<ChannelConfig, ChannelError> {
+    let genesis = store
+        .read_block(0)
+        .map_err(|e| ChannelError::ConfigHistory(format!("no genesis block: {e}")))?;
+    genesis
+        .transactions
+        .first()
+        .and_then(|json| serde_json::from_str(json).ok())
+        .ok_or_else(|| ChannelError::ConfigHistory("genesis block carries no config".to_string()))
+}
+
+/// Every config version of the channel, genesis first, rebuilt from the
+/// ledger in `store` by following the `last_config` pointers.
+///
+/// Committed config blocks were validated when they were committed, so their
+/// updates are replayed without checking signatures again.
+pub fn config_history(store: &dyn BlockStore) -> Result<Vec<ChannelConfig>, ChannelError> {
+    let mut history = vec![genesis_config(store)?];
+
+    let mut config_txs = Vec::new();
+    let mut pointer = tip(store).map(|b| b.last_config).unwrap_or(0);
+    while pointer > 0 {
+        let read = |height: u64| {
+            store
+                .read_block(height)
+                .map_err(|e| ChannelError::ConfigHistory(format!("block {height}: {e}")))
+        };
+        let tx = read(pointer)?.config_tx.ok_or_else(|| {
+            ChannelError::ConfigHistory(format!("block {pointer} is not a config block"))
+        })?;
+        config_txs.push(tx);
+        let previous = read(pointer - 1)?.last_config;
+        if previous >= pointer {
+            return Err(ChannelError::ConfigHistory(format!(
+                "block {} points forward to config block {previous}",
+                pointer - 1
+            )));
+        }
+        pointer = previous;
+    }
+
+    for tx in config_txs.iter().rev() {
+        let current = history.last().expect("history starts with genesis");
+        history.push(apply_config_update(current, &tx.updates)?);
+    }
+    Ok(history)
+}
+
+/// Current config of the channel according to its ledger.
+#[allow(dead_code)]
+pub fn current_config(store: &dyn BlockStore) -> Result<ChannelConfig, ChannelError> {
+    config_history(store).map(|mut history| history.pop().expect("history is never empty"))
+}
+
+/// Validates blocks against the channel ledger before they are committed.
+#[derive(Clone)]
+pub struct ConfigValidator {
+    policy_store: Arc<dyn PolicyStore>,
+    org_registry: Arc<dyn OrgRegistry>,
+}
+
+impl ConfigValidator {
+    pub fn new(policy_store: Arc<dyn PolicyStore>, org_registry: Arc<dyn OrgRegistry>) -> Self {
+        Self {
+            policy_store,
+            org_registry,
+        }
+    }
+
+    /// Check `block` for commit on top of `store`: its shape, the
+    /// `last_config` pointer the orderer signed, and for a config block the
+    /// transaction against the channel's modification policy and current
+    /// config (both read from the ledger). Returns the new config when
+    /// `block` is a config block.
+    pub fn prepare_commit(
+        &self,
+        store: &dyn BlockStore,
+        block: &Block,
+    ) -> Result<Option<ChannelConfig>, ChannelError> {
+        check_config_block_shape(block)?;
+        let height = block.height;
+        let invalid = |reason: String| ChannelError::InvalidConfigBlock { height, reason };
+        let expected = match block.config_tx {
+            Some(_) => height,
+            None => tip(store).map(|b| b.last_config).unwrap_or(0),
+        };
+        if block.last_config != expected {
+            return Err(invalid(format!(
+                "last_config points at block {} instead of {expected}",
+                block.last_config
+            )));
+        }
+        let Some(tx) = &block.config_tx else {
+            return Ok(None);
+        };
+
+        let current = config_history(store)?
+            .pop()
+            .expect("history is never empty");
+        if let Some(channel_id) = channel_of(store) {
+            if tx.channel_id != channel_id {
+                return Err(invalid(format!(
+                    "config transaction targets channel '{}', not '{channel_id}'",
+                    tx.channel_id
+                )));
+            }
+        }
+        validate_config_tx(
+            tx,
+            &current,
+            self.policy_store.as_ref(),
+            self.org_registry.as_ref(),
+        )
+        .map_err(|e| invalid(e.to_string()))?;
+        let next =
+            apply_config_update(&current, &tx.updates).map_err(|e| invalid(e.to_string()))?;
+        Ok(Some(next))
+    }
+
+    /// [`prepare_commit`](Self::prepare_commit) `block`, then write it to
+    /// `store`.
+    pub fn commit(
+        &self,
+        store: &dyn BlockStore,
+        block: Block,
+    ) -> Result<(Block, Option<ChannelConfig>), ChannelError> {
+        let config = self.prepare_commit(store, &block)?;
+        store
+            .write_block(&block)
+            .map_err(|e| ChannelError::Storage(e.to_string()))?;
+        Ok((block, config))
+    }
+}
+
+/// Channel named by the genesis block's proposer (`genesis:<channel_id>`).
+fn channel_of(store: &dyn BlockStore) -> Option<String> {
+    let genesis = store.read_block(0).ok()?;
+    genesis
+        .proposer
+        .strip_prefix("genesis:")
+        .map(str::to_string)
+}
+
+/// Build a config block at `height` for `tx`. Ordering backends cut config
+/// transactions into blocks of their own with this layout.
+pub fn config_block(height: u64, proposer: &str, tx: ConfigTransaction) -> Block {
+    Block {
+        height,
+        timestamp: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        parent_hash: [0u8; 32],
+        merkle_root: [0u8; 32],
+        transactions: vec![tx.ledger_tx_id()],
+        proposer: proposer.to_string(),
+        signature: vec![0u8; 64],
+        signature_algorithm: Default::default(),
+        endorsements: vec![],
+        secondary_signature: None,
+        secondary_signature_algorithm: None,
+        hash_algorithm: Default::default(),
+        orderer_signature: None,
+        commit_qc: None,
+        next_validator_set: None,
+        evidence: Vec::new(),
+        config_tx: Some(tx),
+        last_config: height,
+        state_root: [0u8; 32],
+        private_data_purges: vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channel::config::ConfigUpdateType;
+    use crate::channel::genesis::create_genesis_block;
+    use crate::endorsement::policy::EndorsementPolicy;
+    use crate::endorsement::{MemoryOrgRegistry, MemoryPolicyStore};
+    use crate::storage::MemoryStore;
+
+    fn open_config() -> ChannelConfig {
+        // AllOf([]) is satisfied without signatures.
+        ChannelConfig {
+            endorsement_policy: EndorsementPolicy::AllOf(vec![]),
+            ..ChannelConfig::default()
+        }
+    }
+
+    fn channel_store(config: &ChannelConfig) -> MemoryStore {
+        let store = MemoryStore::new();
+        store
+            .write_block(&create_genesis_block("ch1", config))
+            .unwrap();
+        store
+    }
+
+    fn validator() -> ConfigValidator {
+        ConfigValidator::new(
+            Arc::new(MemoryPolicyStore::new()),
+            Arc::new(MemoryOrgRegistry::new()),
+        )
+    }
+
+    fn config_tx(id: &str, updates: Vec<ConfigUpdateType>) -> ConfigTransaction {
+        ConfigTransaction {
+            tx_id: id.to_string(),
+            channel_id: "ch1".to_string(),
+            updates,
+            signatures: vec![],
+            created_at: 0,
+        }
+    }
+
+    fn tx_block(height: u64, last_config: u64, tx_id: &str) -> Block {
+        let mut block = config_block(height, "orderer", config_tx("unused", vec![]));
+        block.config_tx = None;
+        block.transactions = vec![tx_id.to_string()];
+        block.last_config = last_config;
+        block
+    }
+
+    #[test]
+    fn blocks_point_at_the_latest_config_block() {
+        let store = channel_store(&open_config());
+        let v = validator();
+
+        v.commit(&store, tx_block(1, 0, "tx-a")).unwrap();
+
+        let tx = config_tx("cfg-1", vec![ConfigUpdateType::SetBatchSize(7)]);
+        let (b2, config) = v.commit(&store, config_block(2, "orderer", tx)).unwrap();
+        assert_eq!(b2.last_config, 2);
+        assert_eq!(config.unwrap().batch_size, 7);
+
+        // The pointer is signed by the orderer; a stale one is rejected.
+        assert!(matches!(
+            v.commit(&store, tx_block(3, 0, "tx-b")),
+            Err(ChannelError::InvalidConfigBlock { height: 3, .. })
+        ));
+        let (b3, config) = v.commit(&store, tx_block(3, 2, "tx-b")).unwrap();
+        assert_eq!(b3.last_config, 2);
+        assert!(config.is_none());
+    }
+
+    #[test]
+    fn history_is_rebuilt_from_the_ledger_alone() {
+        let store = channel_store(&open_config());
+        let v = validator();
+        v.commit(&store, tx_block(1, 0, "tx-a")).unwrap();
+        v.commit(
+            &store,
+            config_block(
+                2,
+                "orderer",
+                config_tx("cfg-1", vec![ConfigUpdateType::AddOrg("org2".into())]),
+            ),
+        )
+        .unwrap();
+        v.commit(&store, tx_block(3, 2, "tx-b")).unwrap();
+        v.commit(
+            &store,
+            config_block(
+                4,
+                "orderer",
+                config_tx("cfg-2", vec![ConfigUpdateType::SetBatchTimeout(50)]),
+            ),
+        )
+        .unwrap();
+        v.commit(&store, tx_block(5, 4, "tx-c")).unwrap();
+
+        // A joining peer copies the blocks and nothing else.
+        let joined = MemoryStore::new();
+        for height in 0..=5 {
+            joined
+                .write_block(&store.read_block(height).unwrap())
+                .unwrap();
+        }
+        let history = config_history(&joined).unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0], open_config());
+        assert_eq!(history[1].member_orgs, vec!["org2".to_string()]);
+        assert_eq!(history[2].batch_timeout_ms, 50);
+        assert_eq!(history[2].version, 2);
+        assert_eq!(current_config(&joined).unwrap(), history[2]);
+    }
+
+    #[test]
+    fn config_block_failing_the_modification_policy_is_rejected() {
+        let config = ChannelConfig {
+            endorsement_policy: EndorsementPolicy::AllOf(vec!["org1".to_string()]),
+            ..ChannelConfig::default()
+        };
+        let store = channel_store(&config);
+        let tx = config_tx("cfg-1", vec![ConfigUpdateType::SetBatchSize(7)]);
+
+        let err = validator()
+            .commit(&store, config_block(1, "orderer", tx))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ChannelError::InvalidConfigBlock { height: 1, .. }
+        ));
+        assert!(store.read_block(1).is_err());
+    }
+
+    #[test]
+    fn config_tx_for_another_channel_is_rejected() {
+        let store = channel_store(&open_config());
+        let mut tx = config_tx("cfg-1", vec![ConfigUpdateType::SetBatchSize(7)]);
+        tx.channel_id = "ch2".to_string();
+        assert!(validator()
+            .commit(&store, config_block(1, "orderer", tx))
+            .is_err());
+    }
+
+    #[test]
+    fn config_block_must_carry_only_its_transaction() {
+        let tx = config_tx("cfg-1", vec![ConfigUpdateType::SetBatchSize(7)]);
+        let mut block = config_block(1, "orderer", tx.clone());
+        block.transactions.push("tx-a".to_string());
+        assert!(check_config_block_shape(&block).is_err());
+
+        let mut plain = tx_block(1, 0, "tx-a");
+        plain.transactions.push(tx.ledger_tx_id());
+        assert!(check_config_block_shape(&plain).is_err());
+    }
+
+    #[test]
+    fn history_without_genesis_config_is_unavailable() {
+        let store = MemoryStore::new();
+        assert!(matches!(
+            config_history(&store),
+            Err(ChannelError::ConfigHistory(_))
+        ));
+    }
+}
@@ -1,7 +1,9 @@
 //! Channel model — a logical ledger scope with its own member orgs and endorsement policy.
 
 pub mod config;
+pub mod delivery;
 pub mod genesis;
+pub mod ledger;
 pub mod registry;
 pub mod store;
 
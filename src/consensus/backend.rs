@@ -19,14 +19,23 @@ pub enum ConsensusMode {
 }
 
 impl ConsensusMode {
-    /// Read from the `CONSENSUS_MODE` environment variable.
+    /// Read from the `CONSENSUS_MODE` environment variable, falling back to
+    /// `ORDERING_BACKEND=bft` (the switch that starts the BFT orderer).
     /// Defaults to `Raft` for backward compatibility.
     pub fn from_env() -> Self {
-        match std::env::var("CONSENSUS_MODE")
-            .unwrap_or_default()
-            .to_lowercase()
-            .as_str()
-        {
+        Self::from_settings(
+            std::env::var("CONSENSUS_MODE").ok().as_deref(),
+            std::env::var("ORDERING_BACKEND").ok().as_deref(),
+        )
+    }
+
+    /// Resolve the mode from `CONSENSUS_MODE` and `ORDERING_BACKEND` values.
+    pub fn from_settings(consensus_mode: Option<&str>, ordering_backend: Option<&str>) -> Self {
+        let setting = consensus_mode
+            .filter(|v| !v.is_empty())
+            .or(ordering_backend)
+            .unwrap_or_default();
+        match setting.to_lowercase().as_str() {
             "bft" => ConsensusMode::Bft,
             _ => ConsensusMode::Raft,
         }
@@ -74,6 +83,26 @@ mod tests {
         assert_ne!(ConsensusMode::Raft, ConsensusMode::Bft);
     }
 
+    #[test]
+    fn ordering_backend_bft_selects_bft_mode() {
+        assert_eq!(
+            ConsensusMode::from_settings(None, Some("bft")),
+            ConsensusMode::Bft
+        );
+        assert_eq!(
+            ConsensusMode::from_settings(Some("raft"), Some("bft")),
+            ConsensusMode::Raft
+        );
+        assert_eq!(
+            ConsensusMode::from_settings(None, Some("solo")),
+            ConsensusMode::Raft
+        );
+        assert_eq!(
+            ConsensusMode::from_settings(None, None),
+            ConsensusMode::Raft
+        );
+    }
+
     #[test]
     fn consensus_mode_serialization_roundtrip() {
         let json = serde_json::to_string(&ConsensusMode::Bft).unwrap();
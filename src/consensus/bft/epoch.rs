@@ -0,0 +1,346 @@
+//! Epoch-based BFT validator sets.
+//!
+//! The validator set is fixed for an epoch of `epoch_length` blocks. The
+//! last block of each epoch carries the [`ValidatorSet`] for the next one,
+//! computed from [`StakingManager`] stakes through DPoS committee selection.
+//! Because the set is covered by the block hash, it is certified by the
+//! block's commit QC; QCs for later blocks are then checked against the set
+//! of the epoch they belong to ([`ValidatorSetHistory`]).
+
+use std::collections::BTreeMap;
+
+use pqc_crypto_module::legacy::sha256::{Digest, Sha256};
+use serde::{Deserialize, Serialize};
+
+use super::quorum::{QuorumValidator, SignatureVerifier, MIN_BFT_VALIDATORS};
+use super::types::{BftPhase, QcError, QuorumCertificate};
+use crate::consensus::dpos::{select_committee, DposConfig, ValidatorStake};
+use crate::staking::StakingManager;
+
+/// Validator set for one epoch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidatorSet {
+    /// Epoch number (0 = genesis set).
+    pub epoch: u64,
+    /// First block height validated by this set.
+    pub start_height: u64,
+    /// Validator IDs, ordered by stake descending (leader rotation order).
+    pub validators: Vec<String>,
+}
+
+impl ValidatorSet {
+    /// Digest bound into the hash of the block that commits this set.
+    pub fn digest(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(self.epoch.to_le_bytes());
+        hasher.update(self.start_height.to_le_bytes());
+        for v in &self.validators {
+            hasher.update((v.len() as u64).to_le_bytes());
+            hasher.update(v.as_bytes());
+        }
+        hasher.finalize().into()
+    }
+}
+
+/// Epoch schedule and committee selection settings.
+#[derive(Debug, Clone, Default)]
+pub struct EpochConfig {
+    /// Blocks per epoch. `0` disables reconfiguration.
+    pub epoch_length: u64,
+    /// DPoS committee selection parameters.
+    pub dpos: DposConfig,
+}
+
+impl EpochConfig {
+    /// Read from `BFT_EPOCH_LENGTH` (blocks, default 0 = fixed set),
+    /// `BFT_MAX_VALIDATORS` and `BFT_MIN_STAKE`.
+    pub fn from_env() -> Self {
+        let defaults = DposConfig::default();
+        let var = |name: &str| std::env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        Self {
+            epoch_length: var("BFT_EPOCH_LENGTH").unwrap_or(0),
+            dpos: DposConfig {
+                max_validators: var("BFT_MAX_VALIDATORS")
+                    .map(|v| v as usize)
+                    .unwrap_or(defaults.max_validators),
+                min_stake: var("BFT_MIN_STAKE").unwrap_or(defaults.min_stake),
+            },
+        }
+    }
+
+    /// Epoch a block height belongs to (height 1 is the first block).
+    pub fn epoch_for_height(&self, height: u64) -> u64 {
+        if self.epoch_length == 0 || height == 0 {
+            return 0;
+        }
+        (height - 1) / self.epoch_length
+    }
+
+    /// Whether `height` is the last block of its epoch, i.e. the block that
+    /// commits the next validator set.
+    pub fn is_epoch_boundary(&self, height: u64) -> bool {
+        self.epoch_length > 0 && height > 0 && height.is_multiple_of(self.epoch_length)
+    }
+}
+
+/// Compute the committee for `epoch` (starting at `start_height`) from the
+/// stakes in `staking`.
+///
+/// Stakers join the BFT committee under their validator ID (hex public
+/// key). If fewer than [`MIN_BFT_VALIDATORS`] are eligible, the `current`
+/// validators carry over so the chain never loses fault tolerance.
+pub fn committee_from_staking(
+    staking: &StakingManager,
+    config: &DposConfig,
+    epoch: u64,
+    start_height: u64,
+    current: &[String],
+) -> ValidatorSet {
+    let candidates: Vec<ValidatorStake> = staking
+        .get_active_validators()
+        .into_iter()
+        .map(|v| ValidatorStake {
+            address: v.address,
+            stake: v.staked_amount,
+            active: v.is_active,
+        })
+        .collect();
+    let committee = select_committee(&candidates, config, epoch);
+    let validators = if committee.size() >= MIN_BFT_VALIDATORS {
+        committee.members.into_iter().map(|m| m.address).collect()
+    } else {
+        log::warn!(
+            "epoch {epoch}: only {} eligible stakers, keeping the current {} validators",
+            committee.size(),
+            current.len()
+        );
+        current.to_vec()
+    };
+    ValidatorSet {
+        epoch,
+        start_height,
+        validators,
+    }
+}
+
+/// Errors from epoch validator-set handling.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum EpochError {
+    #[error("no validator set known for height {0}")]
+    UnknownHeight(u64),
+    #[error("validator set for epoch {got} does not follow epoch {expected}")]
+    OutOfOrder { expected: u64, got: u64 },
+    #[error("expected a Commit QC, got {0:?}")]
+    NotCommitQc(BftPhase),
+    #[error("invalid QC for epoch {epoch}: {source}")]
+    InvalidQc {
+        epoch: u64,
+        #[source]
+        source: QcError,
+    },
+}
+
+/// Validator sets by epoch, as handed off through committed blocks.
+#[derive(Debug, Clone, Default)]
+pub struct ValidatorSetHistory {
+    /// Keyed by `start_height`.
+    sets: BTreeMap<u64, ValidatorSet>,
+}
+
+impl ValidatorSetHistory {
+    /// History starting from the genesis validator set.
+    pub fn new(genesis: Vec<String>) -> Self {
+        let mut sets = BTreeMap::new();
+        sets.insert(
+            0,
+            ValidatorSet {
+                epoch: 0,
+                start_height: 0,
+                validators: genesis,
+            },
+        );
+        Self { sets }
+    }
+
+    /// The most recent validator set.
+    pub fn latest(&self) -> Option<&ValidatorSet> {
+        self.sets.values().next_back()
+    }
+
+    /// The validator set that validates the block at `height`.
+    pub fn set_for_height(&self, height: u64) -> Option<&ValidatorSet> {
+        self.sets.range(..=height).next_back().map(|(_, s)| s)
+    }
+
+    /// Record the set for the next epoch. Re-recording a known set is a no-op.
+    pub fn record(&mut self, set: ValidatorSet) -> Result<(), EpochError> {
+        if self.sets.get(&set.start_height) == Some(&set) {
+            return Ok(());
+        }
+        let expected = self.latest().map(|s| s.epoch + 1).unwrap_or(0);
+        let after_latest = self
+            .latest()
+            .is_none_or(|s| set.start_height > s.start_height);
+        if set.epoch != expected || !after_latest {
+            return Err(EpochError::OutOfOrder {
+                expected,
+                got: set.epoch,
+            });
+        }
+        self.sets.insert(set.start_height, set);
+        Ok(())
+    }
+
+    /// Validate a commit QC for the block at `height` against the validator
+    /// set of that block's epoch.
+    pub fn validate_commit_qc<V: SignatureVerifier>(
+        &self,
+        qc: &QuorumCertificate,
+        height: u64,
+        verifier: V,
+    ) -> Result<(), EpochError> {
+        if qc.phase != BftPhase::Commit {
+            return Err(EpochError::NotCommitQc(qc.phase));
+        }
+        let set = self
+            .set_for_height(height)
+            .ok_or(EpochError::UnknownHeight(height))?;
+        QuorumValidator::new(set.validators.clone(), verifier)
+            .validate_qc(qc)
+            .map_err(|source| EpochError::InvalidQc {
+                epoch: set.epoch,
+                source,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::bft::quorum::AcceptAllVerifier;
+    use crate::consensus::bft::types::VoteMessage;
+
+    fn ids(prefix: &str, n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("{prefix}{i}")).collect()
+    }
+
+    fn commit_qc(voters: &[String]) -> QuorumCertificate {
+        QuorumCertificate {
+            block_hash: [1u8; 32],
+            round: 0,
+            phase: BftPhase::Commit,
+            votes: voters
+                .iter()
+                .map(|v| VoteMessage {
+                    block_hash: [1u8; 32],
+                    round: 0,
+                    phase: BftPhase::Commit,
+                    voter_id: v.clone(),
+                    signature: vec![1u8; 64],
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn epoch_schedule() {
+        let config = EpochConfig {
+            epoch_length: 10,
+            ..Default::default()
+        };
+        assert_eq!(config.epoch_for_height(1), 0);
+        assert_eq!(config.epoch_for_height(10), 0);
+        assert_eq!(config.epoch_for_height(11), 1);
+        assert!(config.is_epoch_boundary(10));
+        assert!(!config.is_epoch_boundary(11));
+        assert!(!EpochConfig::default().is_epoch_boundary(10));
+    }
+
+    #[test]
+    fn committee_follows_stake() {
+        let staking = StakingManager::new(Some(1000), None, None);
+        for (i, stake) in [5000u64, 4000, 3000, 2000, 1500].iter().enumerate() {
+            staking.stake(&format!("v{i}"), *stake, true).unwrap();
+        }
+        let config = DposConfig {
+            max_validators: 4,
+            min_stake: 1000,
+        };
+        let set = committee_from_staking(&staking, &config, 1, 11, &[]);
+        assert_eq!(set.validators, vec!["v0", "v1", "v2", "v3"]);
+        assert_eq!((set.epoch, set.start_height), (1, 11));
+    }
+
+    #[test]
+    fn too_few_stakers_keeps_current_set() {
+        let staking = StakingManager::new(Some(1000), None, None);
+        staking.stake("v0", 5000, true).unwrap();
+        let current = ids("old", 4);
+        let set = committee_from_staking(&staking, &DposConfig::default(), 1, 11, &current);
+        assert_eq!(set.validators, current);
+    }
+
+    #[test]
+    fn qcs_are_checked_against_their_epoch() {
+        let mut history = ValidatorSetHistory::new(ids("a", 4));
+        history
+            .record(ValidatorSet {
+                epoch: 1,
+                start_height: 11,
+                validators: ids("b", 4),
+            })
+            .unwrap();
+
+        let old_qc = commit_qc(&ids("a", 3));
+        let new_qc = commit_qc(&ids("b", 3));
+        assert!(history
+            .validate_commit_qc(&old_qc, 10, AcceptAllVerifier)
+            .is_ok());
+        assert!(history
+            .validate_commit_qc(&old_qc, 11, AcceptAllVerifier)
+            .is_err());
+        assert!(history
+            .validate_commit_qc(&new_qc, 11, AcceptAllVerifier)
+            .is_ok());
+        assert!(history
+            .validate_commit_qc(&new_qc, 5, AcceptAllVerifier)
+            .is_err());
+    }
+
+    #[test]
+    fn sets_must_be_recorded_in_order() {
+        let mut history = ValidatorSetHistory::new(ids("a", 4));
+        let skip = ValidatorSet {
+            epoch: 2,
+            start_height: 21,
+            validators: ids("c", 4),
+        };
+        assert_eq!(
+            history.record(skip),
+            Err(EpochError::OutOfOrder {
+                expected: 1,
+                got: 2
+            })
+        );
+        let next = ValidatorSet {
+            epoch: 1,
+            start_height: 11,
+            validators: ids("b", 4),
+        };
+        history.record(next.clone()).unwrap();
+        history.record(next).unwrap();
+        assert_eq!(history.latest().unwrap().epoch, 1);
+    }
+
+    #[test]
+    fn digest_depends_on_members() {
+        let a = ValidatorSet {
+            epoch: 1,
+            start_height: 11,
+            validators: ids("a", 4),
+        };
+        let mut b = a.clone();
+        b.validators[3] = "x".into();
+        assert_ne!(a.digest(), b.digest());
+    }
+}
@@ -3,6 +3,7 @@
 //! Implements a HotStuff-inspired BFT layer on top of the existing DAG consensus.
 //! Raft remains available as an alternative backend for permissioned deployments.
 
+pub mod epoch;
 pub mod quorum;
 pub mod round;
 pub mod round_manager;
@@ -2,11 +2,13 @@
 //!
 //! Verifies that a [`QuorumCertificate`] contains enough valid, distinct votes
 //! to satisfy the BFT threshold: `2f + 1` out of `n` total validators, where
-//! `f = (n - 1) / 3`.
+//! `f = (n - 1) / 3`. Timeout certificates are validated the same way.
 
 use std::collections::HashSet;
 
-use super::types::{QcError, QuorumCertificate, VoteMessage};
+use super::types::{
+    BftPhase, QcError, QuorumCertificate, TimeoutCertificate, TimeoutMessage, VoteMessage,
+};
 
 /// Minimum number of validators required for Byzantine fault tolerance.
 /// With n < 4, f = 0 and the network has zero Byzantine tolerance.
@@ -99,14 +101,35 @@ impl<V: SignatureVerifier> QuorumValidator<V> {
     /// Validate a full quorum certificate.
     ///
     /// Checks:
-    /// 1. No duplicate voters
-    /// 2. All voters are known validators
-    /// 3. All signatures are valid
-    /// 4. Number of valid, distinct votes >= `quorum_threshold()`
+    /// 1. Every vote is for the QC's `(phase, block_hash, round)`
+    /// 2. No duplicate voters
+    /// 3. All voters are known validators
+    /// 4. All signatures are valid
+    /// 5. Number of valid, distinct votes >= `quorum_threshold()`
     pub fn validate_qc(&self, qc: &QuorumCertificate) -> Result<(), QcError> {
         let mut seen = HashSet::new();
 
         for vote in &qc.votes {
+            // A deserialized QC may bundle votes signed for something else.
+            if vote.phase != qc.phase {
+                return Err(QcError::MismatchedPhase {
+                    expected: qc.phase,
+                    got: vote.phase,
+                });
+            }
+            if vote.block_hash != qc.block_hash {
+                return Err(QcError::MismatchedBlockHash {
+                    expected: qc.block_hash,
+                    got: vote.block_hash,
+                });
+            }
+            if vote.round != qc.round {
+                return Err(QcError::MismatchedRound {
+                    expected: qc.round,
+                    got: vote.round,
+                });
+            }
+
             // Duplicate check.
             if !seen.insert(&vote.voter_id) {
                 return Err(QcError::DuplicateVoter(vote.voter_id.clone()));
@@ -127,6 +150,58 @@ impl<V: SignatureVerifier> QuorumValidator<V> {
 
         Ok(())
     }
+
+    /// Validate a single timeout: known voter, valid signature, and (if
+    /// present) a valid Prepare QC no newer than the timed-out round.
+    pub fn validate_timeout(&self, timeout: &TimeoutMessage) -> Result<(), QcError> {
+        if !self.validators.contains(&timeout.voter_id) {
+            return Err(QcError::UnknownVoter(timeout.voter_id.clone()));
+        }
+        if !self
+            .verifier
+            .verify(&timeout.voter_id, &timeout.payload(), &timeout.signature)
+        {
+            return Err(QcError::InvalidSignature(timeout.voter_id.clone()));
+        }
+        if let Some(qc) = &timeout.high_qc {
+            if qc.phase != BftPhase::Prepare
+                || qc.round > timeout.round
+                || self.validate_qc(qc).is_err()
+            {
+                return Err(QcError::InvalidHighQc(timeout.voter_id.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate a timeout certificate: `>= quorum_threshold()` valid,
+    /// distinct timeouts, all for the certificate's round.
+    pub fn validate_tc(&self, tc: &TimeoutCertificate) -> Result<(), QcError> {
+        let mut seen = HashSet::new();
+
+        for timeout in &tc.timeouts {
+            if timeout.round != tc.round {
+                return Err(QcError::MismatchedRound {
+                    expected: tc.round,
+                    got: timeout.round,
+                });
+            }
+            if !seen.insert(&timeout.voter_id) {
+                return Err(QcError::DuplicateVoter(timeout.voter_id.clone()));
+            }
+            self.validate_timeout(timeout)?;
+        }
+
+        let threshold = self.quorum_threshold();
+        if seen.len() < threshold {
+            return Err(QcError::InsufficientVotes {
+                needed: threshold,
+                have: seen.len(),
+            });
+        }
+
+        Ok(())
+    }
 }
 
 /// Test-only signature verifier that accepts any non-empty signature.
@@ -158,7 +233,7 @@ impl SignatureVerifier for RejectVoterVerifier {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::consensus::bft::types::{BftPhase, VoteMessage};
+    use crate::consensus::bft::types::{BftPhase, TimeoutMessage, VoteMessage};
 
     fn validators(n: usize) -> Vec<String> {
         (0..n).map(|i| format!("v{i}")).collect()
@@ -408,4 +483,125 @@ mod tests {
         let qc = QuorumCertificate::new(BftPhase::Prepare, block_hash(1), 0, votes).unwrap();
         assert!(qv.validate_qc(&qc).is_err());
     }
+
+    #[test]
+    fn validate_qc_rejects_votes_for_another_block() {
+        let qv = QuorumValidator::new(validators(4), AcceptAllVerifier);
+        let mut qc = QuorumCertificate::new(
+            BftPhase::Prepare,
+            block_hash(1),
+            0,
+            vec![vote(1, 0, "v0"), vote(1, 0, "v1"), vote(1, 0, "v2")],
+        )
+        .unwrap();
+        // Relabel the certificate without re-collecting votes.
+        qc.block_hash = block_hash(2);
+        assert!(matches!(
+            qv.validate_qc(&qc),
+            Err(QcError::MismatchedBlockHash { .. })
+        ));
+        qc.block_hash = block_hash(1);
+        qc.round = 4;
+        assert!(matches!(
+            qv.validate_qc(&qc),
+            Err(QcError::MismatchedRound { .. })
+        ));
+    }
+
+    // --- timeout certificates ---
+
+    fn timeout(round: u64, voter: &str, high_qc: Option<QuorumCertificate>) -> TimeoutMessage {
+        TimeoutMessage {
+            round,
+            voter_id: voter.to_string(),
+            high_qc,
+            signature: vec![1u8; 64],
+        }
+    }
+
+    fn prepare_qc(hash_id: u8, round: u64) -> QuorumCertificate {
+        let votes = (0..3)
+            .map(|i| vote(hash_id, round, &format!("v{i}")))
+            .collect();
+        QuorumCertificate::new(BftPhase::Prepare, block_hash(hash_id), round, votes).unwrap()
+    }
+
+    #[test]
+    fn validate_tc_accepts_quorum_of_timeouts() {
+        let qv = QuorumValidator::new(validators(4), AcceptAllVerifier);
+        let tc = TimeoutCertificate {
+            round: 3,
+            timeouts: vec![
+                timeout(3, "v0", None),
+                timeout(3, "v1", Some(prepare_qc(1, 2))),
+                timeout(3, "v2", None),
+            ],
+        };
+        assert!(qv.validate_tc(&tc).is_ok());
+    }
+
+    #[test]
+    fn validate_tc_rejects_too_few_or_mixed_rounds() {
+        let qv = QuorumValidator::new(validators(4), AcceptAllVerifier);
+        let short = TimeoutCertificate {
+            round: 3,
+            timeouts: vec![timeout(3, "v0", None), timeout(3, "v1", None)],
+        };
+        assert!(matches!(
+            qv.validate_tc(&short),
+            Err(QcError::InsufficientVotes { needed: 3, have: 2 })
+        ));
+
+        let mixed = TimeoutCertificate {
+            round: 3,
+            timeouts: vec![
+                timeout(3, "v0", None),
+                timeout(2, "v1", None),
+                timeout(3, "v2", None),
+            ],
+        };
+        assert!(matches!(
+            qv.validate_tc(&mixed),
+            Err(QcError::MismatchedRound { .. })
+        ));
+
+        let dup = TimeoutCertificate {
+            round: 3,
+            timeouts: vec![
+                timeout(3, "v0", None),
+                timeout(3, "v0", None),
+                timeout(3, "v2", None),
+            ],
+        };
+        assert!(matches!(
+            qv.validate_tc(&dup),
+            Err(QcError::DuplicateVoter(_))
+        ));
+    }
+
+    #[test]
+    fn validate_timeout_rejects_bad_high_qc() {
+        let qv = QuorumValidator::new(validators(4), AcceptAllVerifier);
+        // QC from after the round that timed out cannot be a high QC.
+        let later_round = timeout(3, "v0", Some(prepare_qc(1, 4)));
+        assert!(matches!(
+            qv.validate_timeout(&later_round),
+            Err(QcError::InvalidHighQc(_))
+        ));
+        // Under-signed QC.
+        let mut weak = prepare_qc(1, 1);
+        weak.votes.truncate(2);
+        assert!(matches!(
+            qv.validate_timeout(&timeout(3, "v0", Some(weak))),
+            Err(QcError::InvalidHighQc(_))
+        ));
+        let unsigned = TimeoutMessage {
+            signature: vec![],
+            ..timeout(3, "v0", None)
+        };
+        assert!(matches!(
+            qv.validate_timeout(&unsigned),
+            Err(QcError::InvalidSignature(_))
+        ));
+    }
 }
@@ -4,6 +4,11 @@
 //! The state machine is event-driven and synchronous: callers feed events
 //! (proposals, votes, timeouts) and receive actions (broadcast, send vote,
 //! commit block) to execute externally.
+//!
+//! Safety across rounds is enforced by [`SafetyRules`], which a round inherits
+//! from its predecessor: a validator that saw a PreCommit QC is *locked* on
+//! that block and only votes for another block at the same height when the
+//! proposal is justified by a Prepare QC from a later round.
 
 use super::quorum::{QuorumValidator, SignatureVerifier};
 use super::types::{BftPhase, QuorumCertificate, VoteMessage};
@@ -18,6 +23,9 @@ pub enum RoundEvent {
     Proposal {
         block_hash: [u8; 32],
         leader_id: String,
+        /// Highest Prepare QC the leader knows; when present the proposal
+        /// must be for that QC's block.
+        justify: Option<QuorumCertificate>,
     },
     /// Received a vote from a validator.
     Vote(VoteMessage),
@@ -64,6 +72,70 @@ pub enum RoundState {
     Failed,
 }
 
+/// Voting state carried from round to round while a height is in progress.
+///
+/// Implements the HotStuff rules:
+/// - `high_qc`: highest Prepare QC seen; reported in timeouts and used by a
+///   leader to justify its proposal.
+/// - `locked_qc`: PreCommit QC this node voted Commit on. Only a proposal for
+///   the locked block, or one justified by a Prepare QC from a later round,
+///   is safe to vote for.
+///
+/// Reset once a block is decided at the height.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SafetyRules {
+    high_qc: Option<QuorumCertificate>,
+    locked_qc: Option<QuorumCertificate>,
+}
+
+impl SafetyRules {
+    /// Highest Prepare QC seen.
+    pub fn high_qc(&self) -> Option<&QuorumCertificate> {
+        self.high_qc.as_ref()
+    }
+
+    /// The QC this node is locked on.
+    pub fn locked_qc(&self) -> Option<&QuorumCertificate> {
+        self.locked_qc.as_ref()
+    }
+
+    /// Whether voting for `block_hash`, justified by `justify`, is safe.
+    ///
+    /// The proposal must be for the justifying QC's block, and either be the
+    /// locked block or carry a justification newer than the lock.
+    pub fn is_safe(&self, block_hash: &[u8; 32], justify: Option<&QuorumCertificate>) -> bool {
+        if justify.is_some_and(|qc| qc.block_hash != *block_hash) {
+            return false;
+        }
+        match &self.locked_qc {
+            None => true,
+            Some(lock) => {
+                lock.block_hash == *block_hash || justify.is_some_and(|qc| qc.round > lock.round)
+            }
+        }
+    }
+
+    /// Record a Prepare QC if it is higher than the current one.
+    pub fn update_high_qc(&mut self, qc: QuorumCertificate) {
+        if qc.phase == BftPhase::Prepare && self.high_qc.as_ref().is_none_or(|h| qc.round > h.round)
+        {
+            self.high_qc = Some(qc);
+        }
+    }
+
+    /// Lock on a PreCommit QC.
+    pub fn lock(&mut self, qc: QuorumCertificate) {
+        if self.locked_qc.as_ref().is_none_or(|l| qc.round >= l.round) {
+            self.locked_qc = Some(qc);
+        }
+    }
+
+    /// Forget all QCs (a block was committed at this height).
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
 /// Helper: create an unsigned vote stub for this node.
 /// The caller must sign it before sending over the network.
 fn make_vote(phase: BftPhase, block_hash: [u8; 32], round: u64, node_id: &str) -> VoteMessage {
@@ -95,6 +167,7 @@ pub struct BftRound<V: SignatureVerifier> {
     commit_collector: Option<VoteCollector<V>>,
     validators: Vec<String>,
     verifier: V,
+    safety: SafetyRules,
 }
 
 impl<V: SignatureVerifier + Clone> BftRound<V> {
@@ -126,9 +199,31 @@ impl<V: SignatureVerifier + Clone> BftRound<V> {
             commit_collector: None,
             validators,
             verifier,
+            safety: SafetyRules::default(),
         }
     }
 
+    /// Continue with the safety state of a previous round.
+    pub fn with_safety(mut self, safety: SafetyRules) -> Self {
+        self.safety = safety;
+        self
+    }
+
+    /// Safety state (locks and highest QC).
+    pub fn safety(&self) -> &SafetyRules {
+        &self.safety
+    }
+
+    /// Mutable safety state.
+    pub fn safety_mut(&mut self) -> &mut SafetyRules {
+        &mut self.safety
+    }
+
+    /// Consume the round, keeping its safety state for the next one.
+    pub fn into_safety(self) -> SafetyRules {
+        self.safety
+    }
+
     /// Current round number.
     pub fn round(&self) -> u64 {
         self.round
@@ -171,7 +266,8 @@ impl<V: SignatureVerifier + Clone> BftRound<V> {
             RoundEvent::Proposal {
                 block_hash,
                 leader_id,
-            } => self.handle_proposal(block_hash, &leader_id),
+                justify,
+            } => self.handle_proposal(block_hash, &leader_id, justify),
             RoundEvent::Vote(vote) => self.handle_vote(vote),
             RoundEvent::Timeout => self.handle_timeout(),
         }
@@ -181,6 +277,11 @@ impl<V: SignatureVerifier + Clone> BftRound<V> {
         if self.state != RoundState::AwaitingProposal || !self.is_leader() {
             return RoundAction::None;
         }
+        // The leader justifies with its highest QC, so it must re-propose
+        // that block.
+        if !self.safety.is_safe(&block_hash, self.safety.high_qc()) {
+            return RoundAction::None;
+        }
 
         self.block_hash = Some(block_hash);
         self.state = RoundState::Preparing;
@@ -189,10 +290,26 @@ impl<V: SignatureVerifier + Clone> BftRound<V> {
         RoundAction::BroadcastProposal { block_hash }
     }
 
-    fn handle_proposal(&mut self, block_hash: [u8; 32], leader_id: &str) -> RoundAction {
+    fn handle_proposal(
+        &mut self,
+        block_hash: [u8; 32],
+        leader_id: &str,
+        justify: Option<QuorumCertificate>,
+    ) -> RoundAction {
         if self.state != RoundState::AwaitingProposal || leader_id != self.leader_id {
             return RoundAction::None;
         }
+        if let Some(qc) = &justify {
+            if !self.is_valid_justify(qc) {
+                return RoundAction::None;
+            }
+        }
+        if !self.safety.is_safe(&block_hash, justify.as_ref()) {
+            return RoundAction::None;
+        }
+        if let Some(qc) = justify {
+            self.safety.update_high_qc(qc);
+        }
 
         self.block_hash = Some(block_hash);
         self.state = RoundState::Preparing;
@@ -206,6 +323,15 @@ impl<V: SignatureVerifier + Clone> BftRound<V> {
         ))
     }
 
+    /// A justification must be a valid Prepare QC from an earlier round.
+    fn is_valid_justify(&self, qc: &QuorumCertificate) -> bool {
+        qc.phase == BftPhase::Prepare
+            && qc.round < self.round
+            && QuorumValidator::new(self.validators.clone(), self.verifier.clone())
+                .validate_qc(qc)
+                .is_ok()
+    }
+
     fn handle_vote(&mut self, vote: VoteMessage) -> RoundAction {
         let bh = match self.block_hash {
             Some(h) => h,
@@ -247,6 +373,7 @@ impl<V: SignatureVerifier + Clone> BftRound<V> {
     ) -> RoundAction {
         match completed_phase {
             BftPhase::Prepare => {
+                self.safety.update_high_qc(qc.clone());
                 self.prepare_qc = Some(qc.clone());
                 self.state = RoundState::PreCommitting;
                 self.ensure_collector(BftPhase::PreCommit, bh);
@@ -256,6 +383,8 @@ impl<V: SignatureVerifier + Clone> BftRound<V> {
                 }
             }
             BftPhase::PreCommit => {
+                // Voting Commit from here on: lock on the block.
+                self.safety.lock(qc.clone());
                 self.precommit_qc = Some(qc.clone());
                 self.state = RoundState::Committing;
                 self.ensure_collector(BftPhase::Commit, bh);
@@ -265,6 +394,8 @@ impl<V: SignatureVerifier + Clone> BftRound<V> {
                 }
             }
             BftPhase::Commit => {
+                // The height is settled; locks no longer apply.
+                self.safety.reset();
                 self.commit_qc = Some(qc.clone());
                 self.state = RoundState::Decided;
                 RoundAction::Decide {
@@ -397,6 +528,7 @@ mod tests {
         let action = r.process(RoundEvent::Proposal {
             block_hash: block_hash(1),
             leader_id: "v0".into(),
+            justify: None,
         });
         match action {
             RoundAction::SendVote(vote) => {
@@ -415,6 +547,7 @@ mod tests {
         let action = r.process(RoundEvent::Proposal {
             block_hash: block_hash(1),
             leader_id: "v2".into(),
+            justify: None,
         });
         assert_eq!(action, RoundAction::None);
     }
@@ -617,4 +750,137 @@ mod tests {
         assert_eq!(action, RoundAction::None);
         assert_eq!(r.block_hash(), Some(block_hash(1)));
     }
+
+    // --- locking and justification ---
+
+    fn qc(phase: BftPhase, hash_id: u8, round: u64) -> QuorumCertificate {
+        let votes = ["v0", "v1", "v2"]
+            .iter()
+            .map(|v| make_test_vote(phase, hash_id, round, v))
+            .collect();
+        QuorumCertificate::new(phase, block_hash(hash_id), round, votes).unwrap()
+    }
+
+    /// Follower state after round 0 locked it on block 1.
+    fn locked_on_block_1() -> SafetyRules {
+        let mut r = follower_round(0, "v1", "v0");
+        r.process(RoundEvent::Proposal {
+            block_hash: block_hash(1),
+            leader_id: "v0".into(),
+            justify: None,
+        });
+        for phase in [BftPhase::Prepare, BftPhase::PreCommit] {
+            for voter in ["v0", "v1", "v2"] {
+                r.process(RoundEvent::Vote(make_test_vote(phase, 1, 0, voter)));
+            }
+        }
+        assert_eq!(r.state(), RoundState::Committing);
+        r.process(RoundEvent::Timeout);
+        r.into_safety()
+    }
+
+    #[test]
+    fn precommit_qc_locks_and_prepare_qc_raises_high_qc() {
+        let safety = locked_on_block_1();
+        assert_eq!(safety.locked_qc().unwrap().block_hash, block_hash(1));
+        assert_eq!(safety.high_qc().unwrap().phase, BftPhase::Prepare);
+        assert_eq!(safety.high_qc().unwrap().round, 0);
+    }
+
+    #[test]
+    fn locked_node_rejects_conflicting_unjustified_proposal() {
+        let mut r = leader_round(1, "v1").with_safety(locked_on_block_1());
+        let mut r2 = follower_round(1, "v2", "v1").with_safety(locked_on_block_1());
+        // Fresh block 2 without justification.
+        let action = r2.process(RoundEvent::Proposal {
+            block_hash: block_hash(2),
+            leader_id: "v1".into(),
+            justify: None,
+        });
+        assert_eq!(action, RoundAction::None);
+        assert_eq!(r2.state(), RoundState::AwaitingProposal);
+        // Even as leader, the node must re-propose its locked block.
+        assert_eq!(
+            r.process(RoundEvent::StartAsLeader {
+                block_hash: block_hash(2)
+            }),
+            RoundAction::None
+        );
+        assert!(matches!(
+            r.process(RoundEvent::StartAsLeader {
+                block_hash: block_hash(1)
+            }),
+            RoundAction::BroadcastProposal { .. }
+        ));
+    }
+
+    #[test]
+    fn locked_node_votes_for_locked_block_again() {
+        let mut r = follower_round(1, "v2", "v1").with_safety(locked_on_block_1());
+        let action = r.process(RoundEvent::Proposal {
+            block_hash: block_hash(1),
+            leader_id: "v1".into(),
+            justify: Some(qc(BftPhase::Prepare, 1, 0)),
+        });
+        assert!(matches!(action, RoundAction::SendVote(_)));
+    }
+
+    #[test]
+    fn newer_justification_unlocks() {
+        let mut r = follower_round(3, "v2", "v3").with_safety(locked_on_block_1());
+        // A Prepare QC for block 2 from round 2 > lock round 0.
+        let action = r.process(RoundEvent::Proposal {
+            block_hash: block_hash(2),
+            leader_id: "v3".into(),
+            justify: Some(qc(BftPhase::Prepare, 2, 2)),
+        });
+        assert!(matches!(action, RoundAction::SendVote(_)));
+        assert_eq!(r.safety().high_qc().unwrap().round, 2);
+    }
+
+    #[test]
+    fn invalid_justifications_are_rejected() {
+        let cases = [
+            // Justifies another block.
+            (2, qc(BftPhase::Prepare, 3, 2)),
+            // Not a Prepare QC.
+            (2, qc(BftPhase::PreCommit, 2, 2)),
+            // Not from an earlier round.
+            (2, qc(BftPhase::Prepare, 2, 3)),
+        ];
+        for (hash_id, justify) in cases {
+            let mut r = follower_round(3, "v2", "v3").with_safety(locked_on_block_1());
+            let action = r.process(RoundEvent::Proposal {
+                block_hash: block_hash(hash_id),
+                leader_id: "v3".into(),
+                justify: Some(justify),
+            });
+            assert_eq!(action, RoundAction::None);
+        }
+        // Under-signed QC.
+        let mut weak = qc(BftPhase::Prepare, 2, 2);
+        weak.votes.truncate(2);
+        let mut r = follower_round(3, "v2", "v3");
+        let action = r.process(RoundEvent::Proposal {
+            block_hash: block_hash(2),
+            leader_id: "v3".into(),
+            justify: Some(weak),
+        });
+        assert_eq!(action, RoundAction::None);
+    }
+
+    #[test]
+    fn decide_clears_locks() {
+        let mut r = leader_round(0, "v0");
+        r.process(RoundEvent::StartAsLeader {
+            block_hash: block_hash(1),
+        });
+        for phase in [BftPhase::Prepare, BftPhase::PreCommit, BftPhase::Commit] {
+            for voter in ["v0", "v1", "v2"] {
+                r.process(RoundEvent::Vote(make_test_vote(phase, 1, 0, voter)));
+            }
+        }
+        assert_eq!(r.state(), RoundState::Decided);
+        assert_eq!(r.safety(), &SafetyRules::default());
+    }
 }
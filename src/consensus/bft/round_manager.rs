@@ -4,11 +4,21 @@
 //! Sits above [`BftRound`] and handles:
 //! - Round-robin leader election from the validator set
 //! - Timeout detection and view change (advance to next round with new leader)
+//! - Aggregating signed timeouts into [`TimeoutCertificate`]s; a round is
+//!   only left on a certificate, and `f + 1` timeouts make a node join in
+//! - Carrying [`SafetyRules`] (lock and highest QC) from round to round
 //! - Tracking the highest committed QC for chain continuity
 
-use super::quorum::SignatureVerifier;
-use super::round::{BftRound, RoundAction, RoundEvent, RoundState};
-use super::types::QuorumCertificate;
+use std::collections::BTreeMap;
+
+use super::quorum::{QuorumValidator, SignatureVerifier};
+use super::round::{BftRound, RoundAction, RoundEvent, RoundState, SafetyRules};
+use super::types::{QuorumCertificate, TimeoutCertificate, TimeoutMessage};
+use super::vote_collector::{TimeoutCollector, TimeoutResult};
+
+/// How many rounds ahead of the current one timeouts are collected; bounds
+/// the collectors a faulty validator can open with far-future timeouts.
+pub const MAX_TIMEOUT_LOOKAHEAD: u64 = 256;
 
 /// Configuration for the round manager.
 #[derive(Debug, Clone)]
@@ -40,6 +50,9 @@ pub enum ManagerAction {
         leader_id: String,
         timeout_ms: u64,
     },
+    /// Sign and broadcast this timeout (our timer fired, or `f + 1`
+    /// validators already timed out the round).
+    SendTimeout(TimeoutMessage),
     /// No action.
     None,
 }
@@ -58,6 +71,12 @@ pub struct RoundManager<V: SignatureVerifier + Clone> {
     consecutive_timeouts: u32,
     /// Highest committed QC seen so far.
     highest_commit_qc: Option<QuorumCertificate>,
+    /// Safety state while no round is active (otherwise held by `current`).
+    safety: SafetyRules,
+    /// Timeouts being collected, by round.
+    timeouts: BTreeMap<u64, TimeoutCollector<V>>,
+    /// Certificate that started the current round, if it was a timeout.
+    last_tc: Option<TimeoutCertificate>,
 }
 
 impl<V: SignatureVerifier + Clone> RoundManager<V> {
@@ -77,9 +96,24 @@ impl<V: SignatureVerifier + Clone> RoundManager<V> {
             current: None,
             consecutive_timeouts: 0,
             highest_commit_qc: None,
+            safety: SafetyRules::default(),
+            timeouts: BTreeMap::new(),
+            last_tc: None,
         }
     }
 
+    /// The validator set rounds are run with.
+    pub fn validators(&self) -> &[String] {
+        &self.validators
+    }
+
+    /// Replace the validator set (epoch handoff). Leader rotation and vote
+    /// validation use the new set from the next round started.
+    pub fn set_validators(&mut self, validators: Vec<String>) {
+        self.validators = validators;
+        self.timeouts.clear();
+    }
+
     /// Current round number.
     pub fn current_round(&self) -> u64 {
         self.current_round
@@ -120,6 +154,37 @@ impl<V: SignatureVerifier + Clone> RoundManager<V> {
         self.current.as_ref().map(|r| r.state())
     }
 
+    /// Lock and highest QC for the height in progress.
+    pub fn safety(&self) -> &SafetyRules {
+        self.current.as_ref().map_or(&self.safety, |r| r.safety())
+    }
+
+    fn safety_mut(&mut self) -> &mut SafetyRules {
+        match self.current.as_mut() {
+            Some(r) => r.safety_mut(),
+            None => &mut self.safety,
+        }
+    }
+
+    /// Adopt a Prepare QC (e.g. from a timeout certificate) as the highest
+    /// QC if it is newer. Callers only pass QCs for the height in progress.
+    pub fn update_high_qc(&mut self, qc: QuorumCertificate) {
+        self.safety_mut().update_high_qc(qc);
+    }
+
+    /// Drop locks once a block at the height in progress was committed by
+    /// other means (e.g. block sync).
+    pub fn reset_safety(&mut self) {
+        self.safety_mut().reset();
+    }
+
+    /// The timeout certificate that started the current round, if any.
+    pub fn last_timeout_certificate(&self) -> Option<&TimeoutCertificate> {
+        self.last_tc
+            .as_ref()
+            .filter(|tc| tc.round + 1 == self.current_round)
+    }
+
     /// Start or advance to a specific round.
     ///
     /// Creates a new `BftRound`, selects the leader via round-robin,
@@ -129,14 +194,20 @@ impl<V: SignatureVerifier + Clone> RoundManager<V> {
         self.current_round = round;
         let leader = self.leader_for_round(round).to_string();
 
+        let safety = match self.current.take() {
+            Some(previous) => previous.into_safety(),
+            None => std::mem::take(&mut self.safety),
+        };
         let bft_round = BftRound::new(
             round,
             self.node_id.clone(),
             leader.clone(),
             self.validators.clone(),
             self.verifier.clone(),
-        );
+        )
+        .with_safety(safety);
         self.current = Some(bft_round);
+        self.timeouts = self.timeouts.split_off(&round);
 
         ManagerAction::NewRound {
             round,
@@ -168,10 +239,12 @@ impl<V: SignatureVerifier + Clone> RoundManager<V> {
         ManagerAction::Round(action)
     }
 
-    /// Handle a timeout for the current round.
+    /// Handle a timeout for the current round without a certificate.
     ///
     /// Increments the backoff counter and advances to the next round with
     /// a new leader. Returns `NewRound` so the caller can reset their timer.
+    /// Networked validators use [`local_timeout`](Self::local_timeout) and
+    /// only advance once a [`TimeoutCertificate`] forms.
     pub fn on_timeout(&mut self) -> ManagerAction {
         // Notify the current round of the timeout.
         if let Some(ref mut r) = self.current {
@@ -183,6 +256,73 @@ impl<V: SignatureVerifier + Clone> RoundManager<V> {
         self.start_round(next_round)
     }
 
+    /// This node's timer for the current round fired: stop voting in it and
+    /// return the (unsigned) timeout to broadcast. The caller signs it and
+    /// feeds it back through [`process_timeout`](Self::process_timeout).
+    pub fn local_timeout(&mut self) -> ManagerAction {
+        if let Some(ref mut r) = self.current {
+            r.process(RoundEvent::Timeout);
+        }
+        ManagerAction::SendTimeout(TimeoutMessage {
+            round: self.current_round,
+            voter_id: self.node_id.clone(),
+            high_qc: self.safety().high_qc().cloned(),
+            signature: Vec::new(),
+        })
+    }
+
+    /// Count a signed timeout (ours or a peer's).
+    ///
+    /// Returns `NewRound` once `2f + 1` timeouts for a round at or above the
+    /// current one form a certificate, or `SendTimeout` when `f + 1`
+    /// validators timed out a round this node has not yet given up on (at
+    /// least one honest node did, so joining keeps rounds in sync).
+    pub fn process_timeout(&mut self, timeout: TimeoutMessage) -> ManagerAction {
+        let round = timeout.round;
+        if round < self.current_round || round > self.current_round + MAX_TIMEOUT_LOOKAHEAD {
+            return ManagerAction::None;
+        }
+        let collector = self.timeouts.entry(round).or_insert_with(|| {
+            TimeoutCollector::new(
+                round,
+                QuorumValidator::new(self.validators.clone(), self.verifier.clone()),
+            )
+        });
+        match collector.add_timeout(timeout) {
+            TimeoutResult::CertificateFormed { tc } => self.advance_with_certificate(tc),
+            TimeoutResult::Pending { count } => {
+                if count < collector.join_threshold() || collector.contains(&self.node_id) {
+                    return ManagerAction::None;
+                }
+                if round > self.current_round {
+                    self.start_round(round);
+                }
+                self.local_timeout()
+            }
+            TimeoutResult::Rejected { .. } => ManagerAction::None,
+        }
+    }
+
+    /// Adopt a timeout certificate received from a peer. Returns `NewRound`
+    /// if it is valid and lets this node move past its current round.
+    pub fn on_timeout_certificate(&mut self, tc: TimeoutCertificate) -> ManagerAction {
+        if tc.round < self.current_round
+            || QuorumValidator::new(self.validators.clone(), self.verifier.clone())
+                .validate_tc(&tc)
+                .is_err()
+        {
+            return ManagerAction::None;
+        }
+        self.advance_with_certificate(tc)
+    }
+
+    fn advance_with_certificate(&mut self, tc: TimeoutCertificate) -> ManagerAction {
+        self.consecutive_timeouts += 1;
+        let next_round = tc.round + 1;
+        self.last_tc = Some(tc);
+        self.start_round(next_round)
+    }
+
     /// Advance to the next round after a successful Decide.
     ///
     /// Resets timeout backoff since progress was made.
@@ -241,6 +381,18 @@ mod tests {
         assert_eq!(m.leader_for_round(4), "v0"); // wraps
     }
 
+    #[test]
+    fn new_validator_set_applies_from_next_round() {
+        let mut m = manager("v0");
+        m.start();
+        let next: Vec<String> = (0..4).map(|i| format!("w{i}")).collect();
+        m.set_validators(next.clone());
+        assert_eq!(m.validators(), next.as_slice());
+        m.advance_after_decide();
+        assert_eq!(m.current_leader(), "w1");
+        assert!(!m.is_current_leader());
+    }
+
     #[test]
     fn start_returns_new_round_action() {
         let mut m = manager("v0");
@@ -357,6 +509,7 @@ mod tests {
         let action = m.process_event(RoundEvent::Proposal {
             block_hash: block_hash(1),
             leader_id: "v0".into(),
+            justify: None,
         });
         match action {
             ManagerAction::Round(RoundAction::SendVote(vote)) => {
@@ -394,4 +547,140 @@ mod tests {
             }
         }
     }
+
+    // --- timeout certificates ---
+
+    fn signed(mut timeout: TimeoutMessage) -> TimeoutMessage {
+        timeout.signature = vec![1u8; 64];
+        timeout
+    }
+
+    fn peer_timeout(round: u64, voter: &str) -> TimeoutMessage {
+        signed(TimeoutMessage {
+            round,
+            voter_id: voter.to_string(),
+            high_qc: None,
+            signature: vec![],
+        })
+    }
+
+    #[test]
+    fn local_timeout_fails_round_without_advancing() {
+        let mut m = manager("v0");
+        m.start();
+        let action = m.local_timeout();
+        match action {
+            ManagerAction::SendTimeout(t) => {
+                assert_eq!(t.round, 0);
+                assert_eq!(t.voter_id, "v0");
+                assert!(t.signature.is_empty());
+            }
+            other => panic!("expected SendTimeout, got {other:?}"),
+        }
+        assert_eq!(m.current_round(), 0);
+        assert_eq!(m.round_state(), Some(RoundState::Failed));
+    }
+
+    #[test]
+    fn quorum_of_timeouts_advances_round() {
+        let mut m = manager("v0");
+        m.start();
+        let ManagerAction::SendTimeout(own) = m.local_timeout() else {
+            panic!("expected SendTimeout");
+        };
+        assert_eq!(m.process_timeout(signed(own)), ManagerAction::None);
+        assert_eq!(
+            m.process_timeout(peer_timeout(0, "v1")),
+            ManagerAction::None
+        );
+        let action = m.process_timeout(peer_timeout(0, "v2"));
+        assert!(matches!(action, ManagerAction::NewRound { round: 1, .. }));
+        assert_eq!(m.last_timeout_certificate().unwrap().round, 0);
+        assert_eq!(m.current_timeout_ms(), 6000);
+    }
+
+    #[test]
+    fn f_plus_one_timeouts_make_node_join() {
+        let mut m = manager("v0");
+        m.start();
+        assert_eq!(
+            m.process_timeout(peer_timeout(0, "v1")),
+            ManagerAction::None
+        );
+        // Second timeout: f + 1 = 2 for n = 4.
+        let action = m.process_timeout(peer_timeout(0, "v2"));
+        assert!(matches!(action, ManagerAction::SendTimeout(ref t) if t.round == 0));
+        assert_eq!(m.round_state(), Some(RoundState::Failed));
+    }
+
+    #[test]
+    fn lagging_node_jumps_to_timed_out_round() {
+        let mut m = manager("v0");
+        m.start();
+        m.process_timeout(peer_timeout(5, "v1"));
+        let action = m.process_timeout(peer_timeout(5, "v2"));
+        assert!(matches!(action, ManagerAction::SendTimeout(ref t) if t.round == 5));
+        assert_eq!(m.current_round(), 5);
+    }
+
+    #[test]
+    fn stale_or_invalid_certificates_are_ignored() {
+        let mut m = manager("v0");
+        m.start_round(3);
+        let stale = TimeoutCertificate {
+            round: 1,
+            timeouts: ["v0", "v1", "v2"]
+                .iter()
+                .map(|v| peer_timeout(1, v))
+                .collect(),
+        };
+        assert_eq!(m.on_timeout_certificate(stale), ManagerAction::None);
+        let short = TimeoutCertificate {
+            round: 3,
+            timeouts: vec![peer_timeout(3, "v1"), peer_timeout(3, "v2")],
+        };
+        assert_eq!(m.on_timeout_certificate(short), ManagerAction::None);
+        let good = TimeoutCertificate {
+            round: 4,
+            timeouts: ["v1", "v2", "v3"]
+                .iter()
+                .map(|v| peer_timeout(4, v))
+                .collect(),
+        };
+        assert!(matches!(
+            m.on_timeout_certificate(good),
+            ManagerAction::NewRound { round: 5, .. }
+        ));
+    }
+
+    #[test]
+    fn lock_survives_round_change() {
+        let mut m = manager("v1");
+        m.start();
+        m.process_event(RoundEvent::Proposal {
+            block_hash: block_hash(1),
+            leader_id: "v0".into(),
+            justify: None,
+        });
+        for phase in [BftPhase::Prepare, BftPhase::PreCommit] {
+            for voter in &["v0", "v1", "v2"] {
+                m.process_event(RoundEvent::Vote(make_vote(phase, 1, 0, voter)));
+            }
+        }
+        assert!(m.safety().locked_qc().is_some());
+        m.on_timeout();
+        assert_eq!(m.current_round(), 1);
+        assert_eq!(
+            m.safety().locked_qc().unwrap().block_hash,
+            block_hash(1),
+            "lock carried into the next round"
+        );
+        // Round 1 leader (v1 = this node) must re-propose the locked block.
+        let action = m.process_event(RoundEvent::StartAsLeader {
+            block_hash: block_hash(2),
+        });
+        assert_eq!(action, ManagerAction::Round(RoundAction::None));
+        m.reset_safety();
+        assert!(m.safety().locked_qc().is_none());
+    }
 }
@@ -131,6 +131,70 @@ impl QuorumCertificate {
     }
 }
 
+/// Domain-separation byte for timeout signatures (distinct from every
+/// [`BftPhase::as_byte`] value).
+pub const TIMEOUT_DOMAIN: u8 = 0x10;
+
+/// A validator's signed statement that `round` timed out, carrying the
+/// highest Prepare QC it holds so the next leader can justify its proposal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeoutMessage {
+    /// The round that timed out.
+    pub round: u64,
+    /// Validator identity.
+    pub voter_id: String,
+    /// Highest Prepare QC this validator has seen for the height in progress.
+    pub high_qc: Option<QuorumCertificate>,
+    /// Signature over `(TIMEOUT_DOMAIN || round || high_qc round)`.
+    pub signature: Vec<u8>,
+}
+
+impl TimeoutMessage {
+    /// Canonical bytes to sign: `TIMEOUT_DOMAIN || round_le || has_qc ||
+    /// high_qc_round_le`.
+    pub fn signing_payload(round: u64, high_qc_round: Option<u64>) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(18);
+        payload.push(TIMEOUT_DOMAIN);
+        payload.extend_from_slice(&round.to_le_bytes());
+        payload.push(high_qc_round.is_some() as u8);
+        payload.extend_from_slice(&high_qc_round.unwrap_or(0).to_le_bytes());
+        payload
+    }
+
+    /// Return the signing payload for this timeout.
+    pub fn payload(&self) -> Vec<u8> {
+        Self::signing_payload(self.round, self.high_qc.as_ref().map(|qc| qc.round))
+    }
+}
+
+/// Proof that `2f + 1` validators timed out in `round`, allowing everyone to
+/// move to `round + 1`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeoutCertificate {
+    /// The round that timed out.
+    pub round: u64,
+    /// Signed timeouts forming the quorum.
+    pub timeouts: Vec<TimeoutMessage>,
+}
+
+impl TimeoutCertificate {
+    /// The highest Prepare QC reported by any signer. A leader proposing
+    /// after this certificate must re-propose that QC's block.
+    pub fn high_qc(&self) -> Option<&QuorumCertificate> {
+        self.timeouts
+            .iter()
+            .filter_map(|t| t.high_qc.as_ref())
+            .max_by_key(|qc| qc.round)
+    }
+
+    /// Number of distinct signers.
+    pub fn voter_count(&self) -> usize {
+        let seen: std::collections::HashSet<&String> =
+            self.timeouts.iter().map(|t| &t.voter_id).collect();
+        seen.len()
+    }
+}
+
 /// Errors when constructing or validating a [`QuorumCertificate`].
 #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
 pub enum QcError {
@@ -150,6 +214,8 @@ pub enum QcError {
     InvalidSignature(String),
     #[error("insufficient validators for BFT: need >= {min}, have {have}")]
     InsufficientValidators { min: usize, have: usize },
+    #[error("invalid high QC in timeout from {0}")]
+    InvalidHighQc(String),
 }
 
 #[cfg(test)]
@@ -267,6 +333,50 @@ mod tests {
         assert_eq!(qc.voter_count(), 2);
     }
 
+    // --- timeouts ---
+
+    fn timeout(round: u64, voter: &str, high_qc_round: Option<u64>) -> TimeoutMessage {
+        TimeoutMessage {
+            round,
+            voter_id: voter.to_string(),
+            high_qc: high_qc_round.map(|r| QuorumCertificate {
+                block_hash: block_hash(r as u8),
+                round: r,
+                phase: BftPhase::Prepare,
+                votes: vec![],
+            }),
+            signature: vec![1u8; 64],
+        }
+    }
+
+    #[test]
+    fn timeout_payload_binds_high_qc_round() {
+        let none = timeout(5, "alice", None).payload();
+        let zero = timeout(5, "alice", Some(0)).payload();
+        let three = timeout(5, "alice", Some(3)).payload();
+        assert_ne!(none, zero);
+        assert_ne!(zero, three);
+        assert_eq!(none[0], TIMEOUT_DOMAIN);
+        assert_ne!(
+            none,
+            VoteMessage::signing_payload(BftPhase::Prepare, &block_hash(0), 5)
+        );
+    }
+
+    #[test]
+    fn tc_high_qc_is_highest_reported() {
+        let tc = TimeoutCertificate {
+            round: 7,
+            timeouts: vec![
+                timeout(7, "alice", Some(2)),
+                timeout(7, "bob", None),
+                timeout(7, "carol", Some(5)),
+            ],
+        };
+        assert_eq!(tc.high_qc().unwrap().round, 5);
+        assert_eq!(tc.voter_count(), 3);
+    }
+
     // --- BftPhase domain separation ---
 
     #[test]
@@ -2,11 +2,15 @@
 //!
 //! Collects individual [`VoteMessage`]s for a specific `(phase, round, block_hash)`
 //! and signals when quorum (`2f + 1`) is reached, producing a [`QuorumCertificate`].
+//! [`TimeoutCollector`] does the same for [`TimeoutMessage`]s of one round,
+//! producing a [`TimeoutCertificate`].
 
 use std::collections::HashMap;
 
 use super::quorum::{QuorumValidator, SignatureVerifier};
-use super::types::{BftPhase, QcError, QuorumCertificate, VoteMessage};
+use super::types::{
+    BftPhase, QcError, QuorumCertificate, TimeoutCertificate, TimeoutMessage, VoteMessage,
+};
 
 /// Result of adding a vote to the collector.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -146,6 +150,99 @@ impl<V: SignatureVerifier> VoteCollector<V> {
     }
 }
 
+/// Result of adding a timeout to a [`TimeoutCollector`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimeoutResult {
+    /// Timeout accepted, no certificate yet. `count` = valid timeouts so far.
+    Pending { count: usize },
+    /// Timeout accepted and `2f + 1` reached — returns the formed certificate.
+    CertificateFormed { tc: TimeoutCertificate },
+    /// Timeout was rejected (reason in the error).
+    Rejected { reason: QcError },
+}
+
+/// Accumulates timeouts for a single round.
+pub struct TimeoutCollector<V: SignatureVerifier> {
+    round: u64,
+    /// Accepted timeouts keyed by voter_id to prevent duplicates.
+    timeouts: HashMap<String, TimeoutMessage>,
+    quorum_validator: QuorumValidator<V>,
+    complete: bool,
+}
+
+impl<V: SignatureVerifier> TimeoutCollector<V> {
+    /// Create a new collector for `round`.
+    pub fn new(round: u64, quorum_validator: QuorumValidator<V>) -> Self {
+        Self {
+            round,
+            timeouts: HashMap::new(),
+            quorum_validator,
+            complete: false,
+        }
+    }
+
+    /// Add a timeout. Checks round, duplicates, membership, signature and
+    /// the attached high QC.
+    pub fn add_timeout(&mut self, timeout: TimeoutMessage) -> TimeoutResult {
+        if self.complete {
+            return TimeoutResult::Rejected {
+                reason: QcError::DuplicateVoter("certificate already formed".into()),
+            };
+        }
+        if timeout.round != self.round {
+            return TimeoutResult::Rejected {
+                reason: QcError::MismatchedRound {
+                    expected: self.round,
+                    got: timeout.round,
+                },
+            };
+        }
+        if self.timeouts.contains_key(&timeout.voter_id) {
+            return TimeoutResult::Rejected {
+                reason: QcError::DuplicateVoter(timeout.voter_id.clone()),
+            };
+        }
+        if let Err(e) = self.quorum_validator.validate_timeout(&timeout) {
+            return TimeoutResult::Rejected { reason: e };
+        }
+
+        self.timeouts.insert(timeout.voter_id.clone(), timeout);
+
+        let count = self.timeouts.len();
+        if count >= self.quorum_validator.quorum_threshold() {
+            self.complete = true;
+            TimeoutResult::CertificateFormed {
+                tc: TimeoutCertificate {
+                    round: self.round,
+                    timeouts: self.timeouts.values().cloned().collect(),
+                },
+            }
+        } else {
+            TimeoutResult::Pending { count }
+        }
+    }
+
+    /// Current number of accepted timeouts.
+    pub fn timeout_count(&self) -> usize {
+        self.timeouts.len()
+    }
+
+    /// Whether `voter_id` has timed out in this round.
+    pub fn contains(&self, voter_id: &str) -> bool {
+        self.timeouts.contains_key(voter_id)
+    }
+
+    /// Whether a certificate was formed.
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Timeouts needed before an honest node must have timed out (`f + 1`).
+    pub fn join_threshold(&self) -> usize {
+        self.quorum_validator.max_faulty() + 1
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -330,4 +427,48 @@ mod tests {
             other => panic!("expected QuorumReached, got {other:?}"),
         }
     }
+
+    // --- timeout collection ---
+
+    fn make_timeout(round: u64, voter: &str) -> TimeoutMessage {
+        TimeoutMessage {
+            round,
+            voter_id: voter.to_string(),
+            high_qc: None,
+            signature: vec![1u8; 64],
+        }
+    }
+
+    #[test]
+    fn timeouts_form_certificate_at_quorum() {
+        let qv = QuorumValidator::new(validators(4), AcceptAllVerifier);
+        let mut c = TimeoutCollector::new(2, qv);
+        assert_eq!(c.join_threshold(), 2);
+        assert!(matches!(
+            c.add_timeout(make_timeout(2, "v0")),
+            TimeoutResult::Pending { count: 1 }
+        ));
+        assert!(matches!(
+            c.add_timeout(make_timeout(2, "v0")),
+            TimeoutResult::Rejected {
+                reason: QcError::DuplicateVoter(_)
+            }
+        ));
+        assert!(matches!(
+            c.add_timeout(make_timeout(3, "v1")),
+            TimeoutResult::Rejected {
+                reason: QcError::MismatchedRound { .. }
+            }
+        ));
+        c.add_timeout(make_timeout(2, "v1"));
+        match c.add_timeout(make_timeout(2, "v2")) {
+            TimeoutResult::CertificateFormed { tc } => {
+                assert_eq!(tc.round, 2);
+                assert_eq!(tc.voter_count(), 3);
+            }
+            other => panic!("expected CertificateFormed, got {other:?}"),
+        }
+        assert!(c.is_complete());
+        assert!(c.contains("v1"));
+    }
 }
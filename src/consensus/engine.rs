@@ -3,8 +3,11 @@
 //! `ConsensusEngine` ties together the DAG, fork-choice rule, and slot
 //! scheduler.  It is the only type callers need to interact with.
 
+use std::sync::Arc;
+
 use crate::consensus::{
     dag::{Dag, DagBlock},
+    finality::FinalityTracker,
     fork_choice::{ForkChoice, ForkChoiceRule},
     scheduler::SlotScheduler,
     validator::{BlockValidator, ValidityResult},
@@ -13,6 +16,7 @@ use crate::consensus::{
 use crate::endorsement::policy_store::PolicyStore;
 use crate::endorsement::registry::OrgRegistry;
 use crate::endorsement::validator::validate_endorsements;
+use crate::events::{BlockEvent, EventBus};
 use crate::storage::traits::BlockStore;
 
 /// Errors returned by the consensus engine.
@@ -62,6 +66,10 @@ pub struct ConsensusEngine {
     /// When set, blocks must carry a valid CommitQC to be accepted.
     /// Uses a boxed trait-object verifier so the engine is not generic over V.
     bft_quorum_validator: Option<crate::consensus::bft::quorum::QuorumValidator<BoxedVerifier>>,
+    /// Finality rule applied to the canonical chain.
+    finality: FinalityTracker,
+    /// When set, canonical-chain switches are published as [`BlockEvent::Reorg`].
+    event_bus: Option<Arc<EventBus>>,
 }
 
 /// Type-erased signature verifier so `ConsensusEngine` stays non-generic.
@@ -107,6 +115,8 @@ impl ConsensusEngine {
             policy_store: None,
             org_registry: None,
             bft_quorum_validator: None,
+            finality: FinalityTracker::default(),
+            event_bus: None,
         }
     }
 
@@ -153,6 +163,22 @@ impl ConsensusEngine {
         self
     }
 
+    #[allow(dead_code)]
+    /// Replace the finality rule (default: depth
+    /// [`DEFAULT_FINALITY_DEPTH`](crate::consensus::finality::DEFAULT_FINALITY_DEPTH)).
+    pub fn with_finality(mut self, finality: FinalityTracker) -> Self {
+        self.finality = finality;
+        self
+    }
+
+    #[allow(dead_code)]
+    /// Publish a [`BlockEvent::Reorg`] on `bus` whenever the canonical chain
+    /// switches branches.
+    pub fn with_event_bus(mut self, bus: Arc<EventBus>) -> Self {
+        self.event_bus = Some(bus);
+        self
+    }
+
     // --- mutations ---
 
     #[allow(dead_code)]
@@ -226,9 +252,16 @@ impl ConsensusEngine {
         }
 
         let hash = block.hash;
+        let before = self.canonical_chain();
         self.dag
             .add_block(block.clone())
             .map_err(ConsensusError::DagError)?;
+        let after = self.canonical_chain();
+        self.detect_reorg(&before, &after);
+        if after.last() == Some(&hash) {
+            self.finality
+                .observe(block.height, block.commit_qc.is_some());
+        }
 
         if let Some(store) = &self.store {
             let storage_block = crate::storage::traits::Block {
@@ -245,6 +278,13 @@ impl ConsensusEngine {
                 secondary_signature_algorithm: block.secondary_signature_algorithm,
                 hash_algorithm: Default::default(),
                 orderer_signature: None,
+                commit_qc: None,
+                next_validator_set: None,
+                evidence: Vec::new(),
+                config_tx: None,
+                last_config: 0,
+                state_root: [0u8; 32],
+                private_data_purges: vec![],
             };
             store
                 .write_block(&storage_block)
@@ -254,6 +294,23 @@ impl ConsensusEngine {
         Ok(hash)
     }
 
+    /// Publish a reorg when `before`'s tip is no longer on the `after` chain.
+    fn detect_reorg(&self, before: &[[u8; 32]], after: &[[u8; 32]]) {
+        let common = before.iter().zip(after).take_while(|(a, b)| a == b).count();
+        let depth = (before.len() - common) as u64;
+        let (Some(from), Some(to)) = (before.last(), after.last()) else {
+            return;
+        };
+        if depth == 0 {
+            return;
+        }
+        let (from, to) = (hex::encode(from), hex::encode(to));
+        log::warn!("canonical chain reorg of depth {depth}: {from} -> {to}");
+        if let Some(bus) = &self.event_bus {
+            bus.publish(BlockEvent::Reorg { from, to, depth });
+        }
+    }
+
     // --- accessors ---
 
     #[allow(dead_code)]
@@ -272,6 +329,30 @@ impl ConsensusEngine {
             .next_back()
     }
 
+    #[allow(dead_code)]
+    /// Highest finalized height on the canonical chain, or `None` while
+    /// nothing is final yet.  Uses the depth rule, or the latest canonical
+    /// block carrying a commit QC when that is higher.
+    pub fn finalized_height(&self) -> Option<u64> {
+        let tip = self
+            .canonical_tip()
+            .and_then(|h| self.dag.get_block(&h))
+            .map(|b| b.height);
+        self.finality.finalized_height(tip)
+    }
+
+    #[allow(dead_code)]
+    /// Whether `hash` is on the canonical chain at or below the finalized height.
+    pub fn is_finalized(&self, hash: &[u8; 32]) -> bool {
+        let Some(finalized) = self.finalized_height() else {
+            return false;
+        };
+        self.dag
+            .get_block(hash)
+            .is_some_and(|b| b.height <= finalized)
+            && self.canonical_chain().contains(hash)
+    }
+
     #[allow(dead_code)]
     /// Total number of blocks in the DAG (including stale branches).
     pub fn block_count(&self) -> u64 {
@@ -406,6 +487,45 @@ mod tests {
         ));
     }
 
+    // --- finality / reorg ---
+
+    #[test]
+    fn depth_rule_finalizes_buried_blocks() {
+        let mut e = engine().with_finality(FinalityTracker::new(2));
+        e.accept_block(valid_block(1, 0, 0)).unwrap();
+        e.accept_block(valid_block(2, 1, 1)).unwrap();
+        assert_eq!(e.finalized_height(), None);
+        e.accept_block(valid_block(3, 2, 2)).unwrap();
+        assert_eq!(e.finalized_height(), Some(0));
+        assert!(e.is_finalized(&mk(1)));
+        assert!(!e.is_finalized(&mk(2)));
+    }
+
+    #[tokio::test]
+    async fn branch_switch_publishes_reorg_event() {
+        let bus = Arc::new(EventBus::new());
+        let mut rx = bus.subscribe();
+        let mut e = engine().with_event_bus(bus.clone());
+        e.accept_block(valid_block(1, 0, 0)).unwrap();
+        e.accept_block(valid_block(2, 1, 1)).unwrap();
+        e.accept_block(valid_block(3, 2, 2)).unwrap();
+        // Competing branch 4 → 5 → 6 from genesis overtakes 2 → 3.
+        e.accept_block(valid_block(4, 1, 1)).unwrap();
+        e.accept_block(valid_block(5, 4, 2)).unwrap();
+        assert!(rx.try_recv().is_err(), "tie keeps the current branch");
+        e.accept_block(valid_block(6, 5, 3)).unwrap();
+
+        assert_eq!(e.canonical_tip(), Some(mk(6)));
+        assert_eq!(
+            rx.recv().await.unwrap(),
+            BlockEvent::Reorg {
+                from: hex::encode(mk(3)),
+                to: hex::encode(mk(6)),
+                depth: 2,
+            }
+        );
+    }
+
     // --- canonical_tip / canonical_chain ---
 
     #[test]
@@ -594,6 +714,20 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn commit_qc_finalizes_block_immediately() {
+        let mut e = bft_engine();
+        e.accept_block(valid_block(1, 0, 0)).unwrap();
+        assert_eq!(e.finalized_height(), None);
+
+        let mut block = valid_block(2, 1, 1);
+        block.commit_qc = Some(make_commit_qc(mk(2)));
+        e.accept_block(block).unwrap();
+        assert_eq!(e.finalized_height(), Some(1));
+        assert!(e.is_finalized(&mk(1)));
+        assert!(e.is_finalized(&mk(2)));
+    }
+
     #[test]
     fn bft_engine_rejects_qc_with_wrong_block_hash() {
         let mut e = bft_engine();
@@ -1,15 +1,33 @@
-//! Equivocation detection for block proposals.
+//! Equivocation detection for block proposals and BFT votes.
 //!
 //! Detects when a proposer signs two different valid blocks for the same
 //! consensus position (height, slot). This is a Byzantine fault — not
 //! cryptographic forgery — and must be handled by penalizing the proposer.
+//!
+//! For BFT consensus the detector also tracks votes: two signed votes from
+//! one validator for the same round and phase but different blocks form an
+//! [`EquivocationEvidence`] that any node can verify. Evidence is gossiped
+//! as [`SignedEvidence`] and committed in blocks as an evidence transaction
+//! (see [`EquivocationEvidence::tx_id`]).
 
 use std::collections::{HashMap, HashSet};
 
+use pqc_crypto_module::legacy::sha256::{Digest, Sha256};
 use serde::{Deserialize, Serialize};
 
+use crate::consensus::bft::quorum::SignatureVerifier;
+use crate::consensus::bft::types::{BftPhase, VoteMessage};
 use crate::identity::signing::SigningAlgorithm;
 
+/// Domain-separation byte for a reporter's signature over gossiped evidence
+/// (distinct from vote phases and [`TIMEOUT_DOMAIN`]).
+///
+/// [`TIMEOUT_DOMAIN`]: crate::consensus::bft::types::TIMEOUT_DOMAIN
+pub const EVIDENCE_DOMAIN: u8 = 0x20;
+
+/// Transaction-ID prefix of evidence transactions in a block.
+pub const EVIDENCE_TX_PREFIX: &str = "evidence-";
+
 /// Uniquely identifies a consensus position for equivocation detection.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ConsensusPosition {
@@ -39,11 +57,147 @@ impl EquivocationProof {
     }
 }
 
+/// Why a piece of equivocation evidence was rejected.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum EvidenceError {
+    #[error("votes differ in voter, round or phase")]
+    Unrelated,
+    #[error("votes are for the same block")]
+    SameBlock,
+    #[error("invalid signature from {0}")]
+    InvalidSignature(String),
+}
+
+/// Proof that a BFT validator voted for two different blocks in the same
+/// round and phase. A leader votes Prepare for its own proposal, so a
+/// leader proposing two blocks is caught here as well.
+///
+/// The votes are kept in block-hash order, so the same pair of votes always
+/// yields the same evidence (and [`hash`](Self::hash)).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EquivocationEvidence {
+    pub vote_a: VoteMessage,
+    pub vote_b: VoteMessage,
+}
+
+impl EquivocationEvidence {
+    /// Pair two votes. `None` unless they come from the same validator for
+    /// the same round and phase and name different blocks. Signatures are
+    /// not checked here; see [`verify`](Self::verify).
+    pub fn from_votes(a: VoteMessage, b: VoteMessage) -> Option<Self> {
+        if a.voter_id != b.voter_id
+            || a.round != b.round
+            || a.phase != b.phase
+            || a.block_hash == b.block_hash
+        {
+            return None;
+        }
+        let (vote_a, vote_b) = if a.block_hash < b.block_hash {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        Some(Self { vote_a, vote_b })
+    }
+
+    /// The validator that equivocated.
+    pub fn offender(&self) -> &str {
+        &self.vote_a.voter_id
+    }
+
+    /// The round both votes were cast in.
+    pub fn round(&self) -> u64 {
+        self.vote_a.round
+    }
+
+    /// Check that the votes conflict and are both signed by the offender.
+    pub fn verify(&self, verifier: &impl SignatureVerifier) -> Result<(), EvidenceError> {
+        let (a, b) = (&self.vote_a, &self.vote_b);
+        if a.voter_id != b.voter_id || a.round != b.round || a.phase != b.phase {
+            return Err(EvidenceError::Unrelated);
+        }
+        if a.block_hash >= b.block_hash {
+            // Equal hashes are no conflict; reversed order is not canonical.
+            return Err(EvidenceError::SameBlock);
+        }
+        for vote in [a, b] {
+            if !verifier.verify(&vote.voter_id, &vote.payload(), &vote.signature) {
+                return Err(EvidenceError::InvalidSignature(vote.voter_id.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Identifies the offense — the offender and round — independently of
+    /// which conflicting votes prove it. The penalty ledger records each
+    /// offense once.
+    pub fn offense_id(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update([EVIDENCE_DOMAIN]);
+        hasher.update(self.vote_a.round.to_le_bytes());
+        hasher.update(self.vote_a.voter_id.as_bytes());
+        hasher.finalize().into()
+    }
+
+    /// Content hash of the evidence (both votes and signatures).
+    pub fn hash(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update([EVIDENCE_DOMAIN]);
+        for vote in [&self.vote_a, &self.vote_b] {
+            hasher.update(vote.payload());
+            hasher.update((vote.voter_id.len() as u64).to_le_bytes());
+            hasher.update(vote.voter_id.as_bytes());
+            hasher.update((vote.signature.len() as u64).to_le_bytes());
+            hasher.update(&vote.signature);
+        }
+        hasher.finalize().into()
+    }
+
+    /// ID of the evidence transaction carrying this evidence in a block.
+    pub fn tx_id(&self) -> String {
+        format!("{EVIDENCE_TX_PREFIX}{}", hex::encode(self.hash()))
+    }
+}
+
+/// Evidence as gossiped between validators, signed by the reporting node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedEvidence {
+    pub evidence: EquivocationEvidence,
+    /// Validator ID of the node that detected the equivocation.
+    pub reporter: String,
+    /// Signature over `(EVIDENCE_DOMAIN || evidence hash)`.
+    pub signature: Vec<u8>,
+}
+
+impl SignedEvidence {
+    /// Canonical bytes the reporter signs: `EVIDENCE_DOMAIN || hash`.
+    pub fn signing_payload(evidence: &EquivocationEvidence) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(33);
+        payload.push(EVIDENCE_DOMAIN);
+        payload.extend_from_slice(&evidence.hash());
+        payload
+    }
+
+    /// Check the reporter's signature and the evidence itself.
+    pub fn verify(&self, verifier: &impl SignatureVerifier) -> Result<(), EvidenceError> {
+        if !verifier.verify(
+            &self.reporter,
+            &Self::signing_payload(&self.evidence),
+            &self.signature,
+        ) {
+            return Err(EvidenceError::InvalidSignature(self.reporter.clone()));
+        }
+        self.evidence.verify(verifier)
+    }
+}
+
 /// Tracks proposals per consensus position and detects equivocation.
 #[derive(Default)]
 pub struct EquivocationDetector {
     /// Maps (proposer, height, slot) → (block_hash, signature).
     seen: HashMap<ConsensusPosition, ([u8; 32], Vec<u8>)>,
+    /// First vote seen per (voter, round, phase).
+    votes: HashMap<(String, u64, BftPhase), VoteMessage>,
     /// Collected equivocation proofs.
     proofs: Vec<EquivocationProof>,
     /// Set of proof hashes for deduplication.
@@ -56,6 +210,7 @@ impl EquivocationDetector {
     pub fn new() -> Self {
         Self {
             seen: HashMap::new(),
+            votes: HashMap::new(),
             proofs: Vec::new(),
             proof_hashes: HashSet::new(),
             penalized: HashSet::new(),
@@ -118,6 +273,36 @@ impl EquivocationDetector {
         }
     }
 
+    /// Check a BFT vote for equivocation.
+    ///
+    /// Returns evidence if `vote` conflicts with the first vote seen from the
+    /// same validator for its round and phase. Signatures are not checked:
+    /// callers verify the evidence and, if the remembered vote turns out to
+    /// be forged, replace it with [`record_vote`](Self::record_vote).
+    pub fn check_vote(&mut self, vote: &VoteMessage) -> Option<EquivocationEvidence> {
+        let key = (vote.voter_id.clone(), vote.round, vote.phase);
+        match self.votes.get(&key) {
+            Some(first) => EquivocationEvidence::from_votes(first.clone(), vote.clone()),
+            None => {
+                self.votes.insert(key, vote.clone());
+                None
+            }
+        }
+    }
+
+    /// Remember `vote` as the reference vote for its round and phase.
+    pub fn record_vote(&mut self, vote: &VoteMessage) {
+        self.votes.insert(
+            (vote.voter_id.clone(), vote.round, vote.phase),
+            vote.clone(),
+        );
+    }
+
+    /// Forget votes from rounds before `round`.
+    pub fn prune_votes(&mut self, round: u64) {
+        self.votes.retain(|(_, r, _), _| *r >= round);
+    }
+
     /// Check if a proposer is penalized (quarantined).
     pub fn is_penalized(&self, proposer: &str) -> bool {
         self.penalized.contains(proposer)
@@ -324,4 +509,105 @@ mod tests {
         assert!(!det.receive_proof(&proof), "second receive should be dedup");
         assert_eq!(det.proofs().len(), 1);
     }
+
+    /// Test verifier: a signature is valid if it equals `voter || payload`.
+    struct ConcatVerifier;
+
+    impl SignatureVerifier for ConcatVerifier {
+        fn verify(&self, voter_id: &str, payload: &[u8], signature: &[u8]) -> bool {
+            signature == [voter_id.as_bytes(), payload].concat()
+        }
+    }
+
+    fn vote(voter: &str, round: u64, block: u8) -> VoteMessage {
+        let block_hash = [block; 32];
+        let payload = VoteMessage::signing_payload(BftPhase::Prepare, &block_hash, round);
+        VoteMessage {
+            block_hash,
+            round,
+            phase: BftPhase::Prepare,
+            voter_id: voter.to_string(),
+            signature: [voter.as_bytes(), &payload].concat(),
+        }
+    }
+
+    #[test]
+    fn double_vote_produces_verifiable_evidence() {
+        let mut det = EquivocationDetector::new();
+        assert!(det.check_vote(&vote("v1", 4, 2)).is_none());
+        assert!(det.check_vote(&vote("v1", 4, 2)).is_none(), "duplicate");
+        assert!(det.check_vote(&vote("v1", 5, 3)).is_none(), "other round");
+        assert!(det.check_vote(&vote("v2", 4, 3)).is_none(), "other voter");
+
+        let evidence = det.check_vote(&vote("v1", 4, 1)).expect("double vote");
+        assert_eq!(evidence.offender(), "v1");
+        assert_eq!(evidence.round(), 4);
+        assert_eq!(evidence.vote_a.block_hash, [1; 32], "canonical order");
+        assert_eq!(evidence.verify(&ConcatVerifier), Ok(()));
+        assert!(evidence.tx_id().starts_with(EVIDENCE_TX_PREFIX));
+    }
+
+    #[test]
+    fn evidence_is_independent_of_vote_order() {
+        let a = EquivocationEvidence::from_votes(vote("v1", 0, 1), vote("v1", 0, 2)).unwrap();
+        let b = EquivocationEvidence::from_votes(vote("v1", 0, 2), vote("v1", 0, 1)).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a.hash(), b.hash());
+        let other_pair =
+            EquivocationEvidence::from_votes(vote("v1", 0, 1), vote("v1", 0, 3)).unwrap();
+        assert_ne!(a.hash(), other_pair.hash());
+        assert_eq!(a.offense_id(), other_pair.offense_id(), "same offense");
+        assert!(EquivocationEvidence::from_votes(vote("v1", 0, 1), vote("v1", 0, 1)).is_none());
+        assert!(EquivocationEvidence::from_votes(vote("v1", 0, 1), vote("v2", 0, 2)).is_none());
+    }
+
+    #[test]
+    fn evidence_with_bad_signature_or_mismatch_is_rejected() {
+        let good = EquivocationEvidence::from_votes(vote("v1", 0, 1), vote("v1", 0, 2)).unwrap();
+
+        let mut forged = good.clone();
+        forged.vote_b.signature = vec![0; 8];
+        assert_eq!(
+            forged.verify(&ConcatVerifier),
+            Err(EvidenceError::InvalidSignature("v1".into()))
+        );
+
+        let mut unrelated = good.clone();
+        unrelated.vote_b = vote("v1", 1, 2);
+        assert_eq!(
+            unrelated.verify(&ConcatVerifier),
+            Err(EvidenceError::Unrelated)
+        );
+
+        let mut swapped = good.clone();
+        std::mem::swap(&mut swapped.vote_a, &mut swapped.vote_b);
+        assert_eq!(
+            swapped.verify(&ConcatVerifier),
+            Err(EvidenceError::SameBlock)
+        );
+    }
+
+    #[test]
+    fn signed_evidence_checks_reporter_signature() {
+        let evidence =
+            EquivocationEvidence::from_votes(vote("v1", 0, 1), vote("v1", 0, 2)).unwrap();
+        let payload = SignedEvidence::signing_payload(&evidence);
+        assert_eq!(payload[0], EVIDENCE_DOMAIN);
+        let mut signed = SignedEvidence {
+            evidence,
+            reporter: "v3".to_string(),
+            signature: [b"v3".as_slice(), &payload].concat(),
+        };
+        assert_eq!(signed.verify(&ConcatVerifier), Ok(()));
+        signed.reporter = "v2".to_string();
+        assert!(signed.verify(&ConcatVerifier).is_err());
+    }
+
+    #[test]
+    fn pruned_votes_are_forgotten() {
+        let mut det = EquivocationDetector::new();
+        det.check_vote(&vote("v1", 1, 1));
+        det.prune_votes(2);
+        assert!(det.check_vote(&vote("v1", 1, 2)).is_none());
+    }
 }
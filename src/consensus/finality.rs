@@ -0,0 +1,181 @@
+//! Finality tracking.
+//!
+//! A block is final once the canonical chain can no longer switch away from
+//! it.  Two rules are supported and combined, taking the higher height:
+//!
+//! - **Depth rule** — a block buried at least `depth` blocks below the tip is
+//!   final.  `depth` defaults to `MAX_REORG_DEPTH`, the deepest reorg the node
+//!   will follow.
+//! - **QC rule** — a block carrying a BFT commit quorum certificate is final
+//!   immediately, together with all of its ancestors.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::storage::errors::StorageResult;
+use crate::storage::traits::BlockStore;
+
+/// Default depth rule, matching the `MAX_REORG_DEPTH` default in `main.rs`.
+pub const DEFAULT_FINALITY_DEPTH: u64 = 2000;
+
+/// Tracks the highest finalized height of a chain.
+///
+/// ```rust
+/// use rust_bc::consensus::finality::FinalityTracker;
+/// let tracker = FinalityTracker::new(6);
+/// assert_eq!(tracker.finalized_height(Some(10)), Some(4));
+/// tracker.observe(8, true);
+/// assert_eq!(tracker.finalized_height(Some(10)), Some(8));
+/// ```
+#[derive(Debug)]
+pub struct FinalityTracker {
+    depth: u64,
+    /// Highest height seen with a commit QC, plus one (`0` = none seen).
+    qc_height: AtomicU64,
+}
+
+impl FinalityTracker {
+    /// Create a tracker that applies the depth rule with `depth`.
+    pub fn new(depth: u64) -> Self {
+        Self {
+            depth,
+            qc_height: AtomicU64::new(0),
+        }
+    }
+
+    /// Depth used by the depth rule.
+    pub fn depth(&self) -> u64 {
+        self.depth
+    }
+
+    /// Record a committed block.  Blocks with a commit QC raise the
+    /// QC-finalized height; others only matter through the depth rule.
+    pub fn observe(&self, height: u64, has_commit_qc: bool) {
+        if has_commit_qc {
+            self.qc_height.fetch_max(height + 1, Ordering::Relaxed);
+        }
+    }
+
+    /// Highest height finalized by a commit QC, if any was observed.
+    pub fn qc_finalized_height(&self) -> Option<u64> {
+        self.qc_height.load(Ordering::Relaxed).checked_sub(1)
+    }
+
+    /// Highest finalized height for a chain whose tip is at `tip`
+    /// (`None` for an empty chain).
+    pub fn finalized_height(&self, tip: Option<u64>) -> Option<u64> {
+        let tip = tip?;
+        let by_depth = tip.checked_sub(self.depth);
+        let by_qc = self.qc_finalized_height().map(|h| h.min(tip));
+        by_depth.max(by_qc)
+    }
+
+    /// Whether the block at `height` is final for a chain tipped at `tip`.
+    pub fn is_finalized(&self, height: u64, tip: Option<u64>) -> bool {
+        self.finalized_height(tip).is_some_and(|f| height <= f)
+    }
+
+    /// Refresh from `store` and return its tip and finalized height.
+    ///
+    /// Only the tip block is read: BFT-ordered chains carry a commit QC on
+    /// every block, so a QC on the tip finalizes the whole chain.
+    pub fn refresh(&self, store: &dyn BlockStore) -> StorageResult<(Option<u64>, Option<u64>)> {
+        let latest = store.get_latest_height()?;
+        let tip = match store.read_block(latest) {
+            Ok(block) => {
+                self.observe(block.height, block.commit_qc.is_some());
+                Some(block.height)
+            }
+            Err(_) => None,
+        };
+        Ok((tip, self.finalized_height(tip)))
+    }
+}
+
+impl Default for FinalityTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_FINALITY_DEPTH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::memory::MemoryStore;
+    use crate::storage::traits::Block;
+
+    fn block(height: u64, with_qc: bool) -> Block {
+        use crate::consensus::bft::types::{BftPhase, QuorumCertificate, VoteMessage};
+        let hash = [height as u8; 32];
+        let commit_qc = with_qc.then(|| {
+            let votes = (0..3)
+                .map(|i| VoteMessage {
+                    block_hash: hash,
+                    round: 0,
+                    phase: BftPhase::Commit,
+                    voter_id: format!("v{i}"),
+                    signature: vec![1u8; 64],
+                })
+                .collect();
+            QuorumCertificate::new(BftPhase::Commit, hash, 0, votes).unwrap()
+        });
+        Block {
+            height,
+            timestamp: 0,
+            parent_hash: [0u8; 32],
+            merkle_root: hash,
+            transactions: vec![],
+            proposer: "p".into(),
+            signature: vec![1u8; 64],
+            signature_algorithm: Default::default(),
+            endorsements: vec![],
+            secondary_signature: None,
+            secondary_signature_algorithm: None,
+            hash_algorithm: Default::default(),
+            orderer_signature: None,
+            commit_qc,
+            next_validator_set: None,
+            evidence: Vec::new(),
+            config_tx: None,
+            last_config: 0,
+            state_root: [0u8; 32],
+            private_data_purges: vec![],
+        }
+    }
+
+    #[test]
+    fn depth_rule_finalizes_buried_blocks() {
+        let t = FinalityTracker::new(3);
+        assert_eq!(t.finalized_height(None), None);
+        assert_eq!(t.finalized_height(Some(2)), None);
+        assert_eq!(t.finalized_height(Some(3)), Some(0));
+        assert_eq!(t.finalized_height(Some(10)), Some(7));
+        assert!(t.is_finalized(7, Some(10)));
+        assert!(!t.is_finalized(8, Some(10)));
+    }
+
+    #[test]
+    fn qc_rule_finalizes_immediately() {
+        let t = FinalityTracker::new(100);
+        t.observe(5, false);
+        assert_eq!(t.finalized_height(Some(5)), None);
+        t.observe(5, true);
+        assert_eq!(t.finalized_height(Some(5)), Some(5));
+        // An older QC never lowers the finalized height.
+        t.observe(2, true);
+        assert_eq!(t.qc_finalized_height(), Some(5));
+    }
+
+    #[test]
+    fn refresh_reads_tip_qc_from_store() {
+        let store = MemoryStore::new();
+        let t = FinalityTracker::new(10);
+        assert_eq!(t.refresh(&store).unwrap(), (None, None));
+
+        store.write_block(&block(0, false)).unwrap();
+        store.write_block(&block(1, false)).unwrap();
+        assert_eq!(t.refresh(&store).unwrap(), (Some(1), None));
+
+        store.write_block(&block(2, true)).unwrap();
+        assert_eq!(t.refresh(&store).unwrap(), (Some(2), Some(2)));
+    }
+}
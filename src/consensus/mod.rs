@@ -13,6 +13,7 @@ pub mod dag;
 pub mod dpos;
 pub mod engine;
 pub mod equivocation;
+pub mod finality;
 pub mod fork_choice;
 pub mod scheduler;
 pub mod slashing;
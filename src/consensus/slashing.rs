@@ -83,6 +83,15 @@ impl Default for PenaltyPolicy {
     }
 }
 
+impl PenaltyPolicy {
+    /// Stake to slash from `stake`: `slash_percent_bps` of it, at least
+    /// `min_slash_amount`, never more than the stake itself.
+    pub fn slash_amount(&self, stake: u64) -> u64 {
+        let amount = stake as u128 * self.slash_percent_bps as u128 / 10_000;
+        amount.max(self.min_slash_amount).min(stake as u128) as u64
+    }
+}
+
 /// Manages validator penalties with deterministic expiration.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PenaltyManager {
@@ -215,6 +224,19 @@ mod tests {
         assert_eq!(p.reputation_penalty, -100);
     }
 
+    #[test]
+    fn slash_amount_is_clamped() {
+        let p = PenaltyPolicy::default();
+        assert_eq!(p.slash_amount(10_000), 500);
+        assert_eq!(p.slash_amount(10), 1, "minimum applies");
+        assert_eq!(p.slash_amount(0), 0, "never more than the stake");
+        let all = PenaltyPolicy {
+            slash_percent_bps: 20_000,
+            ..PenaltyPolicy::default()
+        };
+        assert_eq!(all.slash_amount(u64::MAX), u64::MAX);
+    }
+
     #[test]
     fn penalty_record_status_at() {
         let r = PenaltyRecord {
@@ -7,6 +7,7 @@ use super::key_policy::KeyEndorsementStore;
 use super::policy::EndorsementPolicy;
 use super::registry::OrgRegistry;
 use super::types::Endorsement;
+use crate::identity::batch_verify::{BatchVerifier, SignatureCheck};
 use crate::msp::CrlStore;
 
 /// Errors produced during endorsement verification
@@ -42,6 +43,12 @@ pub fn verify_endorsement(e: &Endorsement, public_key: &[u8; 32]) -> Result<(),
         .map_err(|_| EndorsementError::VerificationFailed)
 }
 
+/// The signature check of endorsement `e` under root key `public_key`, for
+/// [`BatchVerifier`].
+pub fn endorsement_check(e: &Endorsement, public_key: &[u8; 32]) -> SignatureCheck {
+    SignatureCheck::new(e.payload_hash, public_key.to_vec(), e.signature.clone())
+}
+
 /// Validate a set of endorsements against a policy and an org registry.
 ///
 /// Steps:
@@ -56,6 +63,29 @@ pub fn validate_endorsements(
     policy: &EndorsementPolicy,
     registry: &dyn OrgRegistry,
     crl_store: Option<&dyn CrlStore>,
+) -> Result<(), EndorsementError> {
+    validate_endorsements_with(endorsements, policy, registry, crl_store, None)
+}
+
+/// Like [`validate_endorsements`], verifying signatures through `verifier`
+/// so they land in its cache and are not verified again when the block
+/// carrying them is pre-verified.
+pub fn validate_endorsements_cached(
+    endorsements: &[Endorsement],
+    policy: &EndorsementPolicy,
+    registry: &dyn OrgRegistry,
+    crl_store: Option<&dyn CrlStore>,
+    verifier: &BatchVerifier,
+) -> Result<(), EndorsementError> {
+    validate_endorsements_with(endorsements, policy, registry, crl_store, Some(verifier))
+}
+
+fn validate_endorsements_with(
+    endorsements: &[Endorsement],
+    policy: &EndorsementPolicy,
+    registry: &dyn OrgRegistry,
+    crl_store: Option<&dyn CrlStore>,
+    verifier: Option<&BatchVerifier>,
 ) -> Result<(), EndorsementError> {
     let mut valid_orgs: Vec<&str> = Vec::new();
 
@@ -77,10 +107,10 @@ pub fn validate_endorsements(
             .map_err(|_| EndorsementError::OrgNotFound(e.org_id.clone()))?;
 
         // Find the root key that verifies this endorsement.
-        let verified_pk = org
-            .root_public_keys
-            .iter()
-            .find(|pk| verify_endorsement(e, pk).is_ok());
+        let verified_pk = org.root_public_keys.iter().find(|pk| match verifier {
+            Some(v) => v.verify(&endorsement_check(e, pk)),
+            None => verify_endorsement(e, pk).is_ok(),
+        });
 
         let Some(pk) = verified_pk else { continue };
 
@@ -298,6 +328,33 @@ mod tests {
         assert!(validate_endorsements(&endorsements, &policy, &reg, None).is_ok());
     }
 
+    #[test]
+    fn validate_endorsements_cached_records_verified_signatures() {
+        let (sk1, pk1) = make_keypair();
+        let (sk2, pk2) = make_keypair();
+        let payload = [6u8; 32];
+        let reg = setup_registry_with_orgs(&[("org1", pk1), ("org2", pk2)]);
+        let endorsements = vec![
+            make_endorsement(&sk1, payload, "org1"),
+            make_endorsement(&sk2, payload, "org2"),
+        ];
+        let policy = EndorsementPolicy::AllOf(vec!["org1".into(), "org2".into()]);
+
+        let verifier = BatchVerifier::default();
+        assert!(
+            validate_endorsements_cached(&endorsements, &policy, &reg, None, &verifier).is_ok()
+        );
+        assert_eq!(verifier.cache().len(), 2);
+        assert_eq!(
+            verifier.verify_batch(&[
+                endorsement_check(&endorsements[0], &pk1),
+                endorsement_check(&endorsements[1], &pk2),
+            ]),
+            vec![true, true]
+        );
+        assert_eq!(verifier.cache().stats().hits, 2);
+    }
+
     #[test]
     fn validate_endorsements_fail_too_few() {
         let (sk1, pk1) = make_keypair();
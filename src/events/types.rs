@@ -26,6 +26,13 @@ pub enum BlockEvent {
         event_name: String,
         payload: Vec<u8>,
     },
+    /// The canonical chain switched branches. `from`/`to` are the hex hashes
+    /// of the old and new tips; `depth` is how many blocks were rolled back.
+    Reorg {
+        from: String,
+        to: String,
+        depth: u64,
+    },
 
     // ── Security events (for CSIRT/SIEM integration) ─────────────────────────
     /// An ACL check denied a request.
@@ -114,6 +121,19 @@ mod tests {
         assert_eq!(roundtrip(&event), event);
     }
 
+    #[test]
+    fn reorg_roundtrip() {
+        let event = BlockEvent::Reorg {
+            from: "aa".to_string(),
+            to: "bb".to_string(),
+            depth: 2,
+        };
+        assert_eq!(roundtrip(&event), event);
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains("\"type\":\"reorg\""));
+        assert!(!event.is_security_event());
+    }
+
     #[test]
     fn block_committed_json_contains_type_tag() {
         let event = BlockEvent::BlockCommitted {
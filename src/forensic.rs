@@ -221,6 +221,12 @@ impl ForensicEngine {
                     None,
                     Severity::Info,
                 ),
+                BlockEvent::Reorg { from, to, depth } => (
+                    "reorg".to_string(),
+                    format!("Reorg of depth {depth}: {from} → {to}"),
+                    None,
+                    Severity::Warning,
+                ),
             };
 
             timeline.push(TimelineEntry {
@@ -605,6 +611,13 @@ mod tests {
             secondary_signature_algorithm: None,
             hash_algorithm: Default::default(),
             orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: Vec::new(),
+            config_tx: None,
+            last_config: 0,
+            state_root: [0u8; 32],
+            private_data_purges: vec![],
         }
     }
 
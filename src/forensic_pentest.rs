@@ -141,6 +141,13 @@ fn test_block_tampering() -> PentestResult {
         secondary_signature_algorithm: None,
         hash_algorithm: Default::default(),
         orderer_signature: None,
+        commit_qc: None,
+        next_validator_set: None,
+        evidence: Vec::new(),
+        config_tx: None,
+        last_config: 0,
+        state_root: [0u8; 32],
+        private_data_purges: vec![],
     };
 
     // Compute original hash
@@ -237,6 +244,13 @@ fn test_replay_attack() -> PentestResult {
         secondary_signature_algorithm: None,
         hash_algorithm: Default::default(),
         orderer_signature: None,
+        commit_qc: None,
+        next_validator_set: None,
+        evidence: Vec::new(),
+        config_tx: None,
+        last_config: 0,
+        state_root: [0u8; 32],
+        private_data_purges: vec![],
     };
     store.write_block(&block).unwrap();
 
@@ -705,6 +719,13 @@ fn test_state_rollback() -> PentestResult {
             secondary_signature_algorithm: None,
             hash_algorithm: Default::default(),
             orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: Vec::new(),
+            config_tx: None,
+            last_config: 0,
+            state_root: [0u8; 32],
+            private_data_purges: vec![],
         };
         store.write_block(&block).unwrap();
     }
@@ -724,6 +745,13 @@ fn test_state_rollback() -> PentestResult {
         secondary_signature_algorithm: None,
         hash_algorithm: Default::default(),
         orderer_signature: None,
+        commit_qc: None,
+        next_validator_set: None,
+        evidence: Vec::new(),
+        config_tx: None,
+        last_config: 0,
+        state_root: [0u8; 32],
+        private_data_purges: vec![],
     };
 
     let overwrite_result = store.write_block(&tampered_block);
@@ -5,6 +5,7 @@ use std::sync::Arc;
 use thiserror::Error;
 
 use crate::chaincode::executor::WasmExecutor;
+use crate::channel::ledger::ConfigValidator;
 use crate::discovery::service::DiscoveryError;
 use crate::discovery::service::DiscoveryService;
 use crate::endorsement::key_policy::KeyEndorsementStore;
@@ -14,12 +15,18 @@ use crate::endorsement::registry::OrgRegistry;
 use crate::endorsement::types::Endorsement;
 use crate::events::types::BlockEvent;
 use crate::events::EventBus;
+use crate::identity::signing::SigningProvider;
 use crate::network::{Message, Node};
+use crate::ordering::admission::AdmissionError;
+use crate::private_data::{PurgeCommitter, PurgeRequest};
+use crate::storage::errors::StorageError;
+use crate::storage::state_tree::AuthenticatedWorldState;
 use crate::storage::traits::{BlockStore, Transaction};
-use crate::storage::world_state::WorldState;
+use crate::storage::world_state::{StateWrite, WorldState};
 use crate::transaction::endorsed::EndorsedTransaction;
 use crate::transaction::executor;
 use crate::transaction::mvcc;
+use crate::transaction::proposal::TransactionProposal;
 use crate::transaction::rwset::ReadWriteSet;
 
 /// Timeout for individual peer endorsement requests.
@@ -36,6 +43,24 @@ pub enum GatewayError {
     Storage(String),
     #[error("chaincode simulation failed: {0}")]
     Simulation(String),
+    #[error("block rejected by config validation: {0}")]
+    Config(String),
+    #[error("transaction rejected by the orderer: {0}")]
+    TransactionTooLarge(String),
+    #[error("transaction rejected by the orderer: {0}")]
+    Rejected(AdmissionError),
+}
+
+/// Map an ordering submit failure, keeping admission refusals apart so
+/// callers can report them as client errors.
+fn submit_error(e: StorageError) -> GatewayError {
+    match e {
+        StorageError::TransactionTooLarge { .. } => {
+            GatewayError::TransactionTooLarge(e.to_string())
+        }
+        StorageError::AdmissionRejected(e) => GatewayError::Rejected(e),
+        other => GatewayError::Ordering(other.to_string()),
+    }
 }
 
 /// Result returned after a transaction is fully committed.
@@ -91,6 +116,18 @@ pub struct Gateway {
     pub key_endorsement_store: Option<Arc<dyn KeyEndorsementStore>>,
     /// P2P node handle for sending endorsement requests to remote peers.
     pub p2p_node: Option<Arc<Node>>,
+    /// Per-channel ordering groups; channels without a group use
+    /// `ordering_service`.
+    pub ordering_groups: Option<Arc<crate::ordering::groups::OrderingGroups>>,
+    /// Authenticated view of `world_state`. When set, every committed block
+    /// gets the state root after its writes sealed into `state_root`.
+    pub authenticated_state: Option<Arc<AuthenticatedWorldState>>,
+    /// Key the gateway signs the proposals it sends for endorsement with,
+    /// as their creator.
+    pub signing_provider: Option<Arc<dyn SigningProvider>>,
+    /// Applies the private data purges of each block committed through
+    /// this gateway.
+    pub purge_committer: Option<PurgeCommitter>,
 }
 
 impl Gateway {
@@ -111,6 +148,10 @@ impl Gateway {
             world_state: None,
             key_endorsement_store: None,
             p2p_node: None,
+            ordering_groups: None,
+            authenticated_state: None,
+            signing_provider: None,
+            purge_committer: None,
         }
     }
 
@@ -134,6 +175,10 @@ impl Gateway {
             world_state: None,
             key_endorsement_store: None,
             p2p_node: None,
+            ordering_groups: None,
+            authenticated_state: None,
+            signing_provider: None,
+            purge_committer: None,
         }
     }
 
@@ -157,6 +202,10 @@ impl Gateway {
             world_state: None,
             key_endorsement_store: None,
             p2p_node: None,
+            ordering_groups: None,
+            authenticated_state: None,
+            signing_provider: None,
+            purge_committer: None,
         }
     }
 
@@ -179,6 +228,111 @@ impl Gateway {
         self
     }
 
+    /// Use `state` as the world state and stamp its root into every block
+    /// committed through this gateway.
+    pub fn with_authenticated_state(mut self, state: Arc<AuthenticatedWorldState>) -> Self {
+        self.world_state = Some(state.clone());
+        self.authenticated_state = Some(state);
+        self
+    }
+
+    /// Sign endorsement proposals as their creator with `signer`.
+    pub fn with_signing_provider(mut self, signer: Arc<dyn SigningProvider>) -> Self {
+        self.signing_provider = Some(signer);
+        self
+    }
+
+    /// Ordering backend for `channel_id`: its channel group when this node
+    /// hosts one, the node-wide ordering service otherwise.
+    fn ordering_for(&self, channel_id: &str) -> Arc<dyn crate::ordering::OrderingBackend> {
+        self.ordering_groups
+            .as_ref()
+            .and_then(|groups| groups.backend_for(channel_id))
+            .unwrap_or_else(|| self.ordering_service.clone())
+    }
+
+    /// Check a cut block, including its `last_config` pointer, against the
+    /// ledger's channel config before it is written.
+    fn prepare_commit(&self, block: &crate::storage::traits::Block) -> Result<(), GatewayError> {
+        ConfigValidator::new(self.policy_store.clone(), self.org_registry.clone())
+            .prepare_commit(self.store.as_ref(), block)
+            .map(|_| ())
+            .map_err(|e| GatewayError::Config(e.to_string()))
+    }
+
+    /// Commit `block` with its transactions and state writes through the
+    /// world state, so a store holding both commits them in one batch.
+    /// With an authenticated state, `ordering` seals the resulting state
+    /// root into the block before it is written. The block's private data
+    /// purges are applied once it is written.
+    fn commit(
+        &self,
+        ordering: &dyn crate::ordering::OrderingBackend,
+        block: &mut crate::storage::traits::Block,
+        txs: &[Transaction],
+        writes: &[StateWrite],
+    ) -> Result<(), GatewayError> {
+        let store = self.store.as_ref();
+        match (&self.authenticated_state, &self.world_state) {
+            (Some(state), _) => {
+                ordering.seal_block(block, state.root_with(writes));
+                state.commit_block(store, block, txs, writes)
+            }
+            (None, Some(ws)) => ws.commit_block_to(store, block, txs, writes),
+            (None, None) => store
+                .write_block(block)
+                .and_then(|()| txs.iter().try_for_each(|tx| store.write_transaction(tx))),
+        }
+        .map_err(|e| GatewayError::Storage(e.to_string()))?;
+        if let Some(purges) = &self.purge_committer {
+            purges.commit(block);
+        }
+        Ok(())
+    }
+
+    /// Order an endorsed private data purge through the node-wide ordering
+    /// service and commit the block carrying it. This node applies the
+    /// purge at commit, like every peer the block is broadcast to.
+    pub fn submit_purge(&self, request: &PurgeRequest) -> Result<TxResult, GatewayError> {
+        let ordering = self.ordering_service.as_ref();
+        ordering.submit_purge_tx(request).map_err(submit_error)?;
+
+        let next_height = self.store.get_latest_height().unwrap_or(0) + 1;
+        let mut block = ordering
+            .cut_block(next_height, "gateway")
+            .map_err(|e| GatewayError::Ordering(e.to_string()))?
+            .ok_or_else(|| GatewayError::Ordering("cut_block returned no block".to_string()))?;
+        self.prepare_commit(&block)?;
+
+        let indexed_txs: Vec<Transaction> = block
+            .transactions
+            .iter()
+            .map(|id| Transaction {
+                id: id.clone(),
+                block_height: block.height,
+                timestamp: block.timestamp,
+                input_did: String::new(),
+                output_recipient: String::new(),
+                amount: 0,
+                state: "committed".to_string(),
+            })
+            .collect();
+        self.commit(ordering, &mut block, &indexed_txs, &[])?;
+
+        if let Some(ref p2p) = self.p2p_node {
+            let p2p = p2p.clone();
+            let block_clone = block.clone();
+            tokio::spawn(async move {
+                p2p.broadcast_ordered_block(&block_clone).await;
+            });
+        }
+        Ok(TxResult {
+            tx_id: request.ledger_tx_id(),
+            block_height: block.height,
+            valid: true,
+        })
+    }
+
     /// Submit a transaction through the full endorse → order → commit pipeline.
     ///
     /// Steps (single-node implementation):
@@ -203,13 +357,19 @@ impl Gateway {
         //   A) Multi-peer: p2p_node + discovery → collect remote endorsements
         //   B) Local simulation: wasm_executor + world_state → simulate locally
         //   C) Policy-only: self_endorse check against org registry
+        //
+        // Path A yields an endorsed transaction, ordered with its envelope.
+        let mut endorsed = None;
         let simulation_rwset = if self.p2p_node.is_some()
             && self.discovery_service.is_some()
             && !channel_id.is_empty()
         {
             // Path A: multi-peer endorsement via P2P
-            let (rwset, _endorsements) =
-                self.collect_endorsements(chaincode_id, channel_id).await?;
+            let etx = self
+                .collect_endorsements(chaincode_id, channel_id, &tx)
+                .await?;
+            let rwset = etx.rwset.clone();
+            endorsed = Some(etx);
             Some(rwset)
         } else if let (Some(svc), false) = (&self.discovery_service, channel_id.is_empty()) {
             // Discovery available but no P2P node: check policy locally, then simulate.
@@ -251,56 +411,83 @@ impl Gateway {
             }
         };
 
-        // ── Step 2: enqueue in ordering service ───────────────────────────────
+        // ── Step 2: enqueue in the channel's ordering service ─────────────────
         let tx_id = tx.id.clone();
-        self.ordering_service
-            .submit_tx(&tx)
-            .map_err(|e| GatewayError::Ordering(e.to_string()))?;
+        let ordering = self.ordering_for(channel_id);
+        match &endorsed {
+            Some(etx) => ordering.submit_endorsed_tx(etx),
+            None => ordering.submit_tx(&tx),
+        }
+        .map_err(submit_error)?;
 
         // ── Step 3: cut block and commit to store ─────────────────────────────
         let next_height = self.store.get_latest_height().unwrap_or(0) + 1;
 
-        let block = self
-            .ordering_service
+        let mut block = ordering
             .cut_block(next_height, "gateway")
             .map_err(|e| GatewayError::Ordering(e.to_string()))?
             .ok_or_else(|| GatewayError::Ordering("cut_block returned no block".to_string()))?;
+        self.prepare_commit(&block)?;
 
         let block_height = block.height;
 
-        self.store
-            .write_block(&block)
-            .map_err(|e| GatewayError::Storage(e.to_string()))?;
+        // ── Step 3.1: MVCC validate the write-set against world state ───────
+        let (tx_valid, writes) =
+            if let (Some(ref rwset), Some(ref ws)) = (&simulation_rwset, &self.world_state) {
+                match mvcc::validate_rwset(rwset, ws.as_ref()) {
+                    Ok(()) => {
+                        let writes: Vec<StateWrite> = rwset
+                            .writes
+                            .iter()
+                            .map(|write| StateWrite::put(&tx_id, &write.key, &write.value))
+                            .collect();
+                        (true, writes)
+                    }
+                    Err(_conflict) => {
+                        // Fabric behavior: block is persisted, but TX writes are NOT applied.
+                        (false, Vec::new())
+                    }
+                }
+            } else {
+                // No simulation rwset — treat as valid (non-Wasm transactions).
+                (true, Vec::new())
+            };
 
-        // ── Step 3.1: index transactions by tx_id ─────────────────────────────
+        // ── Step 3.2: index transactions by tx_id ─────────────────────────────
         // Index the submitted tx with full metadata. Other batched txs get a
         // minimal record (we don't have their original input/output in scope).
-        for tx_id_in_block in &block.transactions {
-            let indexed_tx = if *tx_id_in_block == tx_id {
-                Transaction {
-                    id: tx_id_in_block.clone(),
-                    block_height,
-                    timestamp: block.timestamp,
-                    input_did: tx.input_did.clone(),
-                    output_recipient: tx.output_recipient.clone(),
-                    amount: tx.amount,
-                    state: "committed".to_string(),
-                }
-            } else {
-                Transaction {
-                    id: tx_id_in_block.clone(),
-                    block_height,
-                    timestamp: block.timestamp,
-                    input_did: String::new(),
-                    output_recipient: String::new(),
-                    amount: 0,
-                    state: "committed".to_string(),
+        let indexed_txs: Vec<Transaction> = block
+            .transactions
+            .iter()
+            .map(|tx_id_in_block| {
+                if *tx_id_in_block == tx_id {
+                    Transaction {
+                        id: tx_id_in_block.clone(),
+                        block_height,
+                        timestamp: block.timestamp,
+                        input_did: tx.input_did.clone(),
+                        output_recipient: tx.output_recipient.clone(),
+                        amount: tx.amount,
+                        state: "committed".to_string(),
+                    }
+                } else {
+                    Transaction {
+                        id: tx_id_in_block.clone(),
+                        block_height,
+                        timestamp: block.timestamp,
+                        input_did: String::new(),
+                        output_recipient: String::new(),
+                        amount: 0,
+                        state: "committed".to_string(),
+                    }
                 }
-            };
-            let _ = self.store.write_transaction(&indexed_tx);
-        }
+            })
+            .collect();
+
+        // Block, transactions and state writes land together.
+        self.commit(ordering.as_ref(), &mut block, &indexed_txs, &writes)?;
 
-        // ── Step 3.25: broadcast committed block to peers ───────────────────
+        // ── Step 3.3: broadcast committed block to peers ────────────────────
         if let Some(ref p2p) = self.p2p_node {
             let p2p = p2p.clone();
             let block_clone = block.clone();
@@ -309,26 +496,6 @@ impl Gateway {
             });
         }
 
-        // ── Step 3.5: MVCC validate + apply write-set to world state ────────
-        let tx_valid =
-            if let (Some(ref rwset), Some(ref ws)) = (&simulation_rwset, &self.world_state) {
-                match mvcc::validate_rwset(rwset, ws.as_ref()) {
-                    Ok(()) => {
-                        for write in &rwset.writes {
-                            let _ = ws.put(&write.key, &write.value);
-                        }
-                        true
-                    }
-                    Err(_conflict) => {
-                        // Fabric behavior: block is persisted, but TX writes are NOT applied.
-                        false
-                    }
-                }
-            } else {
-                // No simulation rwset — treat as valid (non-Wasm transactions).
-                true
-            };
-
         // ── Step 4: emit events ───────────────────────────────────────────────
         if let Some(ref bus) = self.event_bus {
             bus.publish(BlockEvent::BlockCommitted {
@@ -372,30 +539,26 @@ impl Gateway {
             GatewayError::Ordering("world_state required for parallel commit".into())
         })?;
 
-        // 1. Submit all txs to ordering service.
+        // 1. Submit all txs to the channel's ordering service.
+        let ordering = self.ordering_for(channel_id);
         for etx in endorsed_txs {
-            self.ordering_service
-                .submit_tx(&etx.proposal.tx)
-                .map_err(|e| GatewayError::Ordering(e.to_string()))?;
+            ordering.submit_endorsed_tx(etx).map_err(submit_error)?;
         }
 
         // 2. Cut a block from the ordering service.
         let next_height = self.store.get_latest_height().unwrap_or(0) + 1;
-        let block = self
-            .ordering_service
+        let mut block = ordering
             .cut_block(next_height, "gateway")
             .map_err(|e| GatewayError::Ordering(e.to_string()))?
             .ok_or_else(|| GatewayError::Ordering("cut_block returned no block".into()))?;
+        self.prepare_commit(&block)?;
 
         let block_height = block.height;
 
-        // 3. Persist the block.
-        self.store
-            .write_block(&block)
-            .map_err(|e| GatewayError::Storage(e.to_string()))?;
-
-        // 4. Execute txs in wave-parallel order.
-        let exec_result = executor::execute_block_parallel(endorsed_txs, ws.as_ref());
+        // 3. Plan txs in wave-parallel order, then commit the block with
+        //    the writes of the valid ones.
+        let (exec_result, writes) = executor::plan_block_parallel(endorsed_txs, ws.as_ref());
+        self.commit(ordering.as_ref(), &mut block, &[], &writes)?;
 
         // 5. Emit events.
         if let Some(ref bus) = self.event_bus {
@@ -427,14 +590,17 @@ impl Gateway {
     /// Collect endorsements from remote peers for a transaction proposal.
     ///
     /// 1. Query discovery for required endorsers.
-    /// 2. Send `ProposalRequest` to each peer via P2P (`send_and_wait`).
+    /// 2. Sign a proposal for `tx` and send it as a `ProposalRequest` to each
+    ///    peer via P2P (`send_and_wait`).
     /// 3. Validate all rwsets match (deterministic execution guarantee).
-    /// 4. Return the shared rwset and collected endorsements.
+    /// 4. Return the endorsed transaction: the proposal, the shared rwset and
+    ///    the collected endorsements.
     async fn collect_endorsements(
         &self,
         chaincode_id: &str,
         channel_id: &str,
-    ) -> Result<(ReadWriteSet, Vec<Endorsement>), GatewayError> {
+        tx: &Transaction,
+    ) -> Result<EndorsedTransaction, GatewayError> {
         let discovery = self.discovery_service.as_ref().ok_or_else(|| {
             GatewayError::PolicyNotSatisfied(
                 "no discovery service for multi-peer endorsement".into(),
@@ -449,6 +615,19 @@ impl Gateway {
             .endorsement_plan(chaincode_id, channel_id)
             .map_err(|e| GatewayError::PolicyNotSatisfied(e.to_string()))?;
 
+        let mut proposal = TransactionProposal {
+            tx: tx.clone(),
+            creator_did: String::new(),
+            creator_signature: Vec::new(),
+            signature_algorithm: Default::default(),
+            rwset: ReadWriteSet::default(),
+        };
+        if let Some(signer) = &self.signing_provider {
+            proposal
+                .sign(signer.as_ref())
+                .map_err(|e| GatewayError::Ordering(format!("signing proposal failed: {e}")))?;
+        }
+
         // Send ProposalRequest to each endorser and collect responses.
         let mut rwsets: Vec<ReadWriteSet> = Vec::with_capacity(endorsers.len());
         let mut endorsements: Vec<Endorsement> = Vec::with_capacity(endorsers.len());
@@ -460,21 +639,7 @@ impl Gateway {
                 chaincode_id: chaincode_id.to_string(),
                 function: "invoke".to_string(),
                 channel_id: channel_id.to_string(),
-                proposal: crate::transaction::proposal::TransactionProposal {
-                    tx: crate::storage::traits::Transaction {
-                        id: String::new(),
-                        block_height: 0,
-                        timestamp: 0,
-                        input_did: String::new(),
-                        output_recipient: String::new(),
-                        amount: 0,
-                        state: String::new(),
-                    },
-                    creator_did: String::new(),
-                    creator_signature: vec![0u8; 64],
-                    signature_algorithm: Default::default(),
-                    rwset: ReadWriteSet::default(),
-                },
+                proposal: proposal.clone(),
             };
 
             let response = p2p
@@ -514,8 +679,11 @@ impl Gateway {
             }
         }
 
-        let shared_rwset = rwsets.into_iter().next().unwrap_or_default();
-        Ok((shared_rwset, endorsements))
+        Ok(EndorsedTransaction {
+            proposal,
+            endorsements,
+            rwset: rwsets.into_iter().next().unwrap_or_default(),
+        })
     }
 
     /// Validate key-level endorsement policies for every write key in `rwset`.
@@ -594,6 +762,7 @@ mod tests {
     use crate::endorsement::policy::EndorsementPolicy;
     use crate::endorsement::policy_store::MemoryPolicyStore;
     use crate::endorsement::registry::MemoryOrgRegistry;
+    use crate::ordering::admission::AdmissionChain;
     use crate::ordering::service::OrderingService;
     use crate::storage::memory::MemoryStore;
 
@@ -706,6 +875,26 @@ mod tests {
         assert!(matches!(err, GatewayError::PolicyNotSatisfied(_)));
     }
 
+    #[tokio::test]
+    async fn submit_orders_through_the_channel_group() {
+        use crate::ordering::groups::{GroupKind, OrderingGroups};
+
+        let dir = tempfile::tempdir().unwrap();
+        let groups = Arc::new(OrderingGroups::new(GroupKind::Solo, dir.path()));
+        groups
+            .create_group("ch1", &crate::channel::config::ChannelConfig::default())
+            .unwrap();
+        let mut gw = make_gateway();
+        gw.ordering_groups = Some(groups);
+        // Pending work of the node-wide orderer is not cut into ch1's block.
+        gw.ordering_service.submit_tx(&make_tx("tx-other")).unwrap();
+
+        let result = gw.submit("cc", "ch1", make_tx("tx-ch1")).await.unwrap();
+        let block = gw.store.read_block(result.block_height).unwrap();
+        assert_eq!(block.transactions, vec!["tx-ch1".to_string()]);
+        assert_eq!(gw.ordering_service.pending_count(), 1);
+    }
+
     #[tokio::test]
     async fn multiple_submits_produce_sequential_block_heights() {
         let gw = make_gateway();
@@ -1126,7 +1315,9 @@ mod tests {
         let gw = Gateway::new(
             Arc::new(MemoryOrgRegistry::new()),
             Arc::new(MemoryPolicyStore::new()),
-            Arc::new(OrderingService::with_config(1000, 5000)),
+            Arc::new(
+                OrderingService::with_config(1000, 5000).with_admission(AdmissionChain::new()),
+            ),
             Arc::new(MemoryStore::new()),
         );
         let gw = Gateway {
@@ -1136,6 +1327,23 @@ mod tests {
         (gw, ws)
     }
 
+    #[test]
+    fn parallel_commit_runs_admission_on_the_endorsed_envelopes() {
+        let ws = Arc::new(crate::storage::MemoryWorldState::new());
+        let gw = Gateway {
+            world_state: Some(ws),
+            ..make_gateway()
+        };
+
+        // Zeroed creator and endorsement signatures.
+        let txs = vec![make_endorsed("tx1", &[], &[("a", b"a1")])];
+        assert!(matches!(
+            gw.commit_block_parallel("ch1", &txs),
+            Err(GatewayError::Rejected(AdmissionError::InvalidSignature(_)))
+        ));
+        assert_eq!(gw.store.get_latest_height().unwrap_or(0), 0);
+    }
+
     #[test]
     fn parallel_commit_independent_txs_all_committed() {
         let (gw, ws) = gateway_with_world_state();
@@ -1198,4 +1406,65 @@ mod tests {
         assert!((result.parallelism_ratio - 4.0).abs() < f64::EPSILON);
         assert_eq!(result.block_height, 1);
     }
+
+    #[test]
+    fn committed_block_carries_the_state_root_of_its_writes() {
+        let inner = Arc::new(crate::storage::MemoryWorldState::new());
+        inner.put("a", b"v1").unwrap();
+        let state = Arc::new(AuthenticatedWorldState::new(inner, 0).unwrap());
+        let gw = Gateway::new(
+            Arc::new(MemoryOrgRegistry::new()),
+            Arc::new(MemoryPolicyStore::new()),
+            Arc::new(
+                OrderingService::with_config(1000, 5000).with_admission(AdmissionChain::new()),
+            ),
+            Arc::new(MemoryStore::new()),
+        )
+        .with_authenticated_state(state.clone());
+
+        let txs = vec![make_endorsed("tx1", &[("a", 1)], &[("a", b"a2")])];
+        let result = gw.commit_block_parallel("ch1", &txs).unwrap();
+
+        let block = gw.store.read_block(result.block_height).unwrap();
+        assert_ne!(block.state_root, [0u8; 32]);
+        assert_eq!(state.root_at(result.block_height), Some(block.state_root));
+        let proof = state.prove("a", result.block_height).unwrap();
+        assert_eq!(proof.value, Some(b"a2".to_vec()));
+        assert_eq!(proof.root, block.state_root);
+    }
+
+    #[cfg(feature = "rocksdb-storage")]
+    #[test]
+    fn a_crash_after_a_gateway_commit_reopens_with_block_and_state_together() {
+        use crate::storage::RocksDbBlockStore;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let (height, state_root) = {
+            let db = Arc::new(RocksDbBlockStore::new(dir.path()).unwrap());
+            db.put("a", b"v1").unwrap();
+            let state = Arc::new(AuthenticatedWorldState::new(db.clone(), 0).unwrap());
+            let gw = Gateway::new(
+                Arc::new(MemoryOrgRegistry::new()),
+                Arc::new(MemoryPolicyStore::new()),
+                Arc::new(OrderingService::with_config(1000, 5000)),
+                db.clone(),
+            )
+            .with_authenticated_state(state);
+
+            let txs = vec![make_endorsed("tx1", &[("a", 1)], &[("a", b"a2")])];
+            let result = gw.commit_block_parallel("ch1", &txs).unwrap();
+            let block = db.read_block(result.block_height).unwrap();
+            // The node dies here, without any shutdown.
+            (result.block_height, block.state_root)
+        };
+
+        let db = Arc::new(RocksDbBlockStore::new(dir.path()).unwrap());
+        assert!(db.recover_state().unwrap().is_clean());
+        assert_eq!(db.state_savepoint().unwrap(), Some(height));
+        let record = db.block_write_set(height).unwrap().unwrap();
+        assert_eq!(record.writes[0].tx_id, "tx1");
+        assert_eq!(db.world_state_get("a").unwrap().unwrap().data, b"a2");
+        let rebuilt = AuthenticatedWorldState::new(db, height).unwrap();
+        assert_eq!(rebuilt.root_at(height), Some(state_root));
+    }
 }
@@ -0,0 +1,284 @@
+//! Parallel signature verification with a cache of verified signatures.
+//!
+//! [`BatchVerifier`] checks a batch of [`SignatureCheck`]s on the rayon
+//! thread pool. Every signature that verifies is remembered in a bounded
+//! [`VerifiedSignatureCache`], keyed by a digest of the payload, public key
+//! and signature, so a signature checked once — e.g. an endorsement checked
+//! when the transaction was submitted — is not checked again when its block
+//! commits. Only successes are cached: a bad signature is re-checked every
+//! time it shows up.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use pqc_crypto_module::legacy::sha256::{Digest, Sha256};
+use rayon::prelude::*;
+
+use crate::identity::signing::verify_with_public_key;
+
+/// Default number of verified signatures remembered.
+pub const DEFAULT_VERIFY_CACHE_CAPACITY: usize = 65_536;
+
+/// Domain separator of the cache key digest.
+const CACHE_KEY_DOMAIN: &[u8] = b"rust-bc/verified-signature/v1";
+
+/// One signature to verify: `signature` over `payload` by `public_key`
+/// (Ed25519 or ML-DSA-65, told apart by key length).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureCheck {
+    pub payload: Vec<u8>,
+    pub public_key: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+impl SignatureCheck {
+    pub fn new(
+        payload: impl Into<Vec<u8>>,
+        public_key: impl Into<Vec<u8>>,
+        signature: impl Into<Vec<u8>>,
+    ) -> Self {
+        Self {
+            payload: payload.into(),
+            public_key: public_key.into(),
+            signature: signature.into(),
+        }
+    }
+
+    /// Digest identifying the (payload, key, signature) triple.
+    pub fn cache_key(&self) -> [u8; 32] {
+        let mut h = Sha256::new();
+        h.update(CACHE_KEY_DOMAIN);
+        for part in [&self.payload, &self.public_key, &self.signature] {
+            h.update((part.len() as u64).to_le_bytes());
+            h.update(part);
+        }
+        h.finalize().into()
+    }
+
+    /// Verify without consulting any cache.
+    pub fn verify(&self) -> bool {
+        verify_with_public_key(&self.public_key, &self.payload, &self.signature)
+    }
+}
+
+/// Hit/miss counters of a [`VerifiedSignatureCache`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct VerifyCacheStats {
+    pub entries: usize,
+    pub capacity: usize,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+#[derive(Debug, Default)]
+struct CacheEntries {
+    keys: HashSet<[u8; 32]>,
+    order: VecDeque<[u8; 32]>,
+}
+
+/// Bounded set of signatures known to verify; the oldest entry is evicted
+/// once `capacity` is reached.
+#[derive(Debug)]
+pub struct VerifiedSignatureCache {
+    capacity: usize,
+    entries: Mutex<CacheEntries>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl Default for VerifiedSignatureCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_VERIFY_CACHE_CAPACITY)
+    }
+}
+
+impl VerifiedSignatureCache {
+    /// Cache remembering up to `capacity` signatures (0 disables caching).
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(CacheEntries::default()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Capacity from `SIG_VERIFY_CACHE_SIZE` (default 65 536).
+    pub fn from_env() -> Self {
+        Self::new(
+            std::env::var("SIG_VERIFY_CACHE_SIZE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_VERIFY_CACHE_CAPACITY),
+        )
+    }
+
+    /// Whether the triple behind `key` was verified before.
+    pub fn contains(&self, key: &[u8; 32]) -> bool {
+        let found = self
+            .entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .keys
+            .contains(key);
+        let counter = if found { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        found
+    }
+
+    /// Remember a verified triple.
+    pub fn insert(&self, key: [u8; 32]) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if !entries.keys.insert(key) {
+            return;
+        }
+        entries.order.push_back(key);
+        while entries.order.len() > self.capacity {
+            if let Some(old) = entries.order.pop_front() {
+                entries.keys.remove(&old);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .order
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn stats(&self) -> VerifyCacheStats {
+        VerifyCacheStats {
+            entries: self.len(),
+            capacity: self.capacity,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Verifies signatures in parallel, skipping those already in its cache.
+#[derive(Debug, Clone, Default)]
+pub struct BatchVerifier {
+    cache: Arc<VerifiedSignatureCache>,
+}
+
+impl BatchVerifier {
+    /// Verifier sharing `cache` with other verification paths.
+    pub fn new(cache: Arc<VerifiedSignatureCache>) -> Self {
+        Self { cache }
+    }
+
+    pub fn cache(&self) -> &Arc<VerifiedSignatureCache> {
+        &self.cache
+    }
+
+    /// Verify one signature, through the cache.
+    pub fn verify(&self, check: &SignatureCheck) -> bool {
+        let key = check.cache_key();
+        if self.cache.contains(&key) {
+            return true;
+        }
+        let ok = check.verify();
+        if ok {
+            self.cache.insert(key);
+        }
+        ok
+    }
+
+    /// Verify `checks` in parallel and return one result per check, in
+    /// order. Duplicate triples in the batch are verified once.
+    pub fn verify_batch(&self, checks: &[SignatureCheck]) -> Vec<bool> {
+        let keys: Vec<[u8; 32]> = checks.par_iter().map(SignatureCheck::cache_key).collect();
+        let mut pending: HashMap<[u8; 32], &SignatureCheck> = HashMap::new();
+        for (key, check) in keys.iter().zip(checks) {
+            if !pending.contains_key(key) && !self.cache.contains(key) {
+                pending.insert(*key, check);
+            }
+        }
+        let failed: HashSet<[u8; 32]> = pending
+            .into_par_iter()
+            .filter_map(|(key, check)| {
+                if check.verify() {
+                    self.cache.insert(key);
+                    None
+                } else {
+                    Some(key)
+                }
+            })
+            .collect();
+        keys.iter().map(|key| !failed.contains(key)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identity::signing::{SigningProvider, SoftwareSigningProvider};
+
+    fn signed(provider: &SoftwareSigningProvider, payload: &[u8]) -> SignatureCheck {
+        SignatureCheck::new(
+            payload,
+            provider.public_key(),
+            provider.sign(payload).unwrap(),
+        )
+    }
+
+    #[test]
+    fn batch_results_follow_input_order() {
+        let p = SoftwareSigningProvider::generate();
+        let good = signed(&p, b"a");
+        let mut bad = signed(&p, b"b");
+        bad.payload = b"tampered".to_vec();
+        let verifier = BatchVerifier::default();
+        assert_eq!(
+            verifier.verify_batch(&[good.clone(), bad.clone(), good.clone()]),
+            vec![true, false, true]
+        );
+        // Only the valid triple is remembered.
+        assert_eq!(verifier.cache().len(), 1);
+        assert!(!verifier.verify(&bad));
+    }
+
+    #[test]
+    fn cached_signature_is_not_verified_again() {
+        let p = SoftwareSigningProvider::generate();
+        let check = signed(&p, b"endorsement");
+        let cache = Arc::new(VerifiedSignatureCache::new(8));
+        assert!(BatchVerifier::new(cache.clone()).verify(&check));
+        let misses = cache.stats().misses;
+
+        assert_eq!(
+            BatchVerifier::new(cache.clone()).verify_batch(&[check]),
+            vec![true]
+        );
+        let stats = cache.stats();
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, misses);
+    }
+
+    #[test]
+    fn cache_evicts_oldest_entries() {
+        let cache = VerifiedSignatureCache::new(2);
+        cache.insert([1; 32]);
+        cache.insert([2; 32]);
+        cache.insert([2; 32]);
+        cache.insert([3; 32]);
+        assert_eq!(cache.len(), 2);
+        assert!(!cache.contains(&[1; 32]));
+        assert!(cache.contains(&[3; 32]));
+
+        let disabled = VerifiedSignatureCache::new(0);
+        disabled.insert([1; 32]);
+        assert!(disabled.is_empty());
+    }
+}
@@ -1,7 +1,8 @@
 //! DID (Decentralized Identifier) document model
 //!
 //! Implements W3C-compatible DIDs with rust-bc specific format:
-//! did:bc:<pubkey_hash>
+//! did:bc:<pubkey_hash>. Endorsers and transaction creators name their
+//! signing key directly as did:key:<hex_pubkey>.
 
 use hex;
 use pqc_crypto_module::legacy::sha256::{Digest, Sha256};
@@ -87,6 +88,16 @@ impl DidDocument {
         Self::create_did(&pubkey_hash)
     }
 
+    /// The `did:key` DID carrying `pubkey` itself: `did:key:<hex_pubkey>`.
+    pub fn did_key(pubkey: &[u8]) -> String {
+        format!("did:key:{}", hex::encode(pubkey))
+    }
+
+    /// Public key carried by a `did:key` DID, `None` for other DIDs.
+    pub fn did_key_public_key(did: &str) -> Option<Vec<u8>> {
+        hex::decode(did.strip_prefix("did:key:")?).ok()
+    }
+
     #[allow(dead_code)]
     /// Add a credential to this DID
     pub fn add_credential(&mut self, credential_id: String) {
@@ -8,6 +8,8 @@
 //! - `HSM_SLOT_ID` — PKCS#11 slot identifier
 //! - `HSM_PIN` — user PIN for the slot
 //! - `HSM_KEY_LABEL` — label of the Ed25519 signing key object
+//! - `HSM_KEK_LABEL` — label of the storage key-encryption key object
+//!   (`STORAGE_ENCRYPTION=hsm`, default `storage-kek`)
 
 use thiserror::Error;
 
@@ -42,6 +44,8 @@ pub struct HsmConfig {
     pub pin: String,
     #[allow(dead_code)]
     pub key_label: String,
+    #[allow(dead_code)]
+    pub kek_label: String,
 }
 
 impl HsmConfig {
@@ -57,6 +61,7 @@ impl HsmConfig {
                 .unwrap_or(0),
             pin: std::env::var("HSM_PIN").map_err(|_| HsmError::AuthFailed)?,
             key_label: std::env::var("HSM_KEY_LABEL").unwrap_or_else(|_| "ed25519-key".into()),
+            kek_label: std::env::var("HSM_KEK_LABEL").unwrap_or_else(|_| "storage-kek".into()),
         })
     }
 }
@@ -125,6 +130,7 @@ impl HsmSigningProvider {
                 slot_id,
                 pin: pin.to_string(),
                 key_label: key_label.to_string(),
+                kek_label: String::new(),
             },
         })
     }
@@ -182,6 +188,89 @@ impl super::signing::SigningProvider for HsmSigningProvider {
     }
 }
 
+/// Storage key-encryption key held in the HSM.
+///
+/// PKCS#11 2.40 has no ML-KEM mechanism, so the ML-KEM-768 private key is a
+/// private, non-modifiable data object (`CKO_DATA`, `CKA_PRIVATE`) labelled
+/// `HSM_KEK_LABEL`, read after login and kept only in locked memory. The
+/// token's PIN and access control guard it instead of a file on the host.
+pub struct HsmKek {
+    inner: crate::storage::encryption::MlKemKek,
+}
+
+impl HsmKek {
+    /// Log in and read the KEK object.
+    #[cfg(not(feature = "hsm"))]
+    pub fn new(_config: &HsmConfig) -> Result<Self, HsmError> {
+        Err(HsmError::NotEnabled)
+    }
+
+    #[cfg(feature = "hsm")]
+    pub fn new(config: &HsmConfig) -> Result<Self, HsmError> {
+        use cryptoki::context::{CInitializeArgs, Pkcs11};
+        use cryptoki::object::{Attribute, AttributeType, ObjectClass};
+
+        let ctx = Pkcs11::new(&config.pkcs11_lib)
+            .map_err(|e| HsmError::LibraryNotFound(e.to_string()))?;
+        ctx.initialize(CInitializeArgs::OsThreads)
+            .map_err(|e| HsmError::LibraryNotFound(e.to_string()))?;
+        let slot = ctx
+            .get_slots_with_token()
+            .map_err(|_| HsmError::SlotNotFound(config.slot_id))?
+            .into_iter()
+            .find(|s| s.id() == config.slot_id)
+            .ok_or(HsmError::SlotNotFound(config.slot_id))?;
+        let session = ctx
+            .open_ro_session(slot)
+            .map_err(|_| HsmError::AuthFailed)?;
+        session
+            .login(cryptoki::session::UserType::User, Some(&config.pin))
+            .map_err(|_| HsmError::AuthFailed)?;
+
+        let object = session
+            .find_objects(&[
+                Attribute::Class(ObjectClass::DATA),
+                Attribute::Label(config.kek_label.as_bytes().to_vec()),
+            ])
+            .map_err(|e| HsmError::KeyNotFound(e.to_string()))?
+            .into_iter()
+            .next()
+            .ok_or_else(|| HsmError::KeyNotFound(config.kek_label.clone()))?;
+        let value = session
+            .get_attributes(object, &[AttributeType::Value])
+            .map_err(|e| HsmError::KeyNotFound(e.to_string()))?
+            .into_iter()
+            .find_map(|attr| match attr {
+                Attribute::Value(bytes) => Some(bytes),
+                _ => None,
+            })
+            .ok_or_else(|| HsmError::KeyNotFound(config.kek_label.clone()))?;
+
+        let inner = crate::storage::encryption::MlKemKek::from_private_key(
+            pqc_crypto_module::types::MlKemPrivateKey(value),
+        )
+        .map_err(|e| HsmError::KeyNotFound(e.to_string()))?;
+        Ok(Self { inner })
+    }
+}
+
+impl crate::storage::encryption::KeyEncryptionKey for HsmKek {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    fn public_key(&self) -> &pqc_crypto_module::types::MlKemPublicKey {
+        self.inner.public_key()
+    }
+
+    fn decapsulate(
+        &self,
+        ciphertext: &pqc_crypto_module::types::MlKemCiphertext,
+    ) -> crate::storage::errors::StorageResult<pqc_crypto_module::types::MlKemSharedSecret> {
+        self.inner.decapsulate(ciphertext)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -193,6 +282,7 @@ mod tests {
             slot_id: 0,
             pin: "1234".into(),
             key_label: "mykey".into(),
+            kek_label: "storage-kek".into(),
         };
         assert_eq!(cfg.slot_id, 0);
         assert_eq!(cfg.key_label, "mykey");
@@ -207,4 +297,19 @@ mod tests {
             assert!(matches!(result, Err(HsmError::NotEnabled)));
         }
     }
+
+    #[test]
+    fn hsm_kek_not_enabled_without_feature() {
+        #[cfg(not(feature = "hsm"))]
+        {
+            let cfg = HsmConfig {
+                pkcs11_lib: "/lib.so".into(),
+                slot_id: 0,
+                pin: "pin".into(),
+                key_label: "label".into(),
+                kek_label: "storage-kek".into(),
+            };
+            assert!(matches!(HsmKek::new(&cfg), Err(HsmError::NotEnabled)));
+        }
+    }
 }
@@ -0,0 +1,399 @@
+//! On-chain signing key rotation for validators and orderers.
+//!
+//! A [`KeyRotation`] announces that `subject` (a validator or orderer ID —
+//! the hex public key it joined with) replaces its current key with a new
+//! one from `activation_height`. It is signed by both keys, so neither a
+//! thief of the new key nor of the old key alone can announce it. The old
+//! key keeps verifying for `grace_blocks` after activation, so blocks and
+//! votes in flight around the switch still check; after that it is retired
+//! for good.
+//!
+//! [`KeySchedule`] folds the committed rotations into a per-height key
+//! lookup, and [`ScheduledKeyVerifier`] plugs it into the BFT
+//! [`SignatureVerifier`] interface.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use pqc_crypto_module::legacy::sha256::{Digest, Sha256};
+use serde::{Deserialize, Serialize};
+
+use crate::consensus::bft::quorum::SignatureVerifier;
+use crate::identity::signing::{verify_with_public_key, SigningError, SigningProvider};
+
+/// Blocks an old key keeps verifying after its successor activates, unless
+/// the rotation says otherwise.
+pub const DEFAULT_KEY_ROTATION_GRACE_BLOCKS: u64 = 100;
+
+/// Domain separator of the rotation signing payload.
+const ROTATION_DOMAIN: &[u8] = b"rust-bc/key-rotation/v1";
+
+/// Errors raised when recording a [`KeyRotation`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum KeyRotationError {
+    #[error("rotation for '{0}' is not signed by the old key")]
+    InvalidOldSignature(String),
+    #[error("rotation for '{0}' is not signed by the new key")]
+    InvalidNewSignature(String),
+    #[error("rotation for '{0}' does not replace its current key")]
+    NotCurrentKey(String),
+    #[error("rotation for '{0}' keeps the same key")]
+    SameKey(String),
+    #[error("rotation for '{subject}' activates at {activation}, not after {after}")]
+    ActivationTooEarly {
+        subject: String,
+        activation: u64,
+        after: u64,
+    },
+}
+
+/// Announcement that `subject` switches signing keys at `activation_height`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyRotation {
+    /// Validator or orderer ID whose key rotates.
+    pub subject: String,
+    /// Key being retired (must be the subject's current key).
+    pub old_public_key: Vec<u8>,
+    /// Key taking over.
+    pub new_public_key: Vec<u8>,
+    /// First height signed with the new key.
+    pub activation_height: u64,
+    /// Blocks after activation during which the old key still verifies.
+    #[serde(default = "default_grace_blocks")]
+    pub grace_blocks: u64,
+    /// Signature of [`KeyRotation::signing_payload`] by the old key.
+    pub old_signature: Vec<u8>,
+    /// Signature of [`KeyRotation::signing_payload`] by the new key.
+    pub new_signature: Vec<u8>,
+}
+
+fn default_grace_blocks() -> u64 {
+    DEFAULT_KEY_ROTATION_GRACE_BLOCKS
+}
+
+impl KeyRotation {
+    /// Build a rotation of `subject` from `old` to `new`, signed by both.
+    pub fn signed(
+        subject: impl Into<String>,
+        old: &dyn SigningProvider,
+        new: &dyn SigningProvider,
+        activation_height: u64,
+        grace_blocks: u64,
+    ) -> Result<Self, SigningError> {
+        let mut rotation = Self {
+            subject: subject.into(),
+            old_public_key: old.public_key(),
+            new_public_key: new.public_key(),
+            activation_height,
+            grace_blocks,
+            old_signature: Vec::new(),
+            new_signature: Vec::new(),
+        };
+        let payload = rotation.signing_payload();
+        rotation.old_signature = old.sign(&payload)?;
+        rotation.new_signature = new.sign(&payload)?;
+        Ok(rotation)
+    }
+
+    /// Digest both keys sign: subject, keys, activation height and grace.
+    pub fn signing_payload(&self) -> [u8; 32] {
+        let mut h = Sha256::new();
+        h.update(ROTATION_DOMAIN);
+        for part in [
+            self.subject.as_bytes(),
+            &self.old_public_key,
+            &self.new_public_key,
+        ] {
+            h.update((part.len() as u64).to_le_bytes());
+            h.update(part);
+        }
+        h.update(self.activation_height.to_le_bytes());
+        h.update(self.grace_blocks.to_le_bytes());
+        h.finalize().into()
+    }
+
+    /// Check both signatures and that the key actually changes.
+    pub fn verify(&self) -> Result<(), KeyRotationError> {
+        if self.old_public_key == self.new_public_key {
+            return Err(KeyRotationError::SameKey(self.subject.clone()));
+        }
+        let payload = self.signing_payload();
+        if !verify_with_public_key(&self.old_public_key, &payload, &self.old_signature) {
+            return Err(KeyRotationError::InvalidOldSignature(self.subject.clone()));
+        }
+        if !verify_with_public_key(&self.new_public_key, &payload, &self.new_signature) {
+            return Err(KeyRotationError::InvalidNewSignature(self.subject.clone()));
+        }
+        Ok(())
+    }
+
+    /// Reject a rotation that would activate at or below `current_height`:
+    /// blocks already committed must keep verifying with the keys they
+    /// were signed with.
+    pub fn check_activation(&self, current_height: u64) -> Result<(), KeyRotationError> {
+        if self.activation_height <= current_height {
+            return Err(KeyRotationError::ActivationTooEarly {
+                subject: self.subject.clone(),
+                activation: self.activation_height,
+                after: current_height,
+            });
+        }
+        Ok(())
+    }
+
+    /// First height at which the old key no longer verifies.
+    pub fn old_key_expires_at(&self) -> u64 {
+        self.activation_height.saturating_add(self.grace_blocks)
+    }
+}
+
+/// Committed key rotations, per subject in activation order.
+///
+/// A subject without rotations is verified with its ID decoded as a hex
+/// public key, which is how validator IDs are formed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeySchedule {
+    rotations: BTreeMap<String, Vec<KeyRotation>>,
+}
+
+/// A [`KeySchedule`] shared between a node and the verifiers it hands out.
+pub type SharedKeySchedule = Arc<RwLock<KeySchedule>>;
+
+impl KeySchedule {
+    /// Empty schedule: every subject verifies with its own ID.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replay committed rotations in order.
+    pub fn from_rotations<'a>(
+        rotations: impl IntoIterator<Item = &'a KeyRotation>,
+    ) -> Result<Self, KeyRotationError> {
+        let mut schedule = Self::new();
+        for rotation in rotations {
+            schedule.record(rotation.clone())?;
+        }
+        Ok(schedule)
+    }
+
+    /// The key `subject` signs with once all recorded rotations are active.
+    pub fn current_key(&self, subject: &str) -> Option<Vec<u8>> {
+        match self.rotations.get(subject).and_then(|r| r.last()) {
+            Some(last) => Some(last.new_public_key.clone()),
+            None => hex::decode(subject).ok(),
+        }
+    }
+
+    /// Rotations recorded for `subject`, oldest first.
+    pub fn rotations(&self, subject: &str) -> &[KeyRotation] {
+        self.rotations.get(subject).map_or(&[], Vec::as_slice)
+    }
+
+    /// Check that `rotation` may follow the recorded ones: both signatures
+    /// verify, it replaces the current key, and it activates after the
+    /// previous rotation of the same subject.
+    pub fn check(&self, rotation: &KeyRotation) -> Result<(), KeyRotationError> {
+        rotation.verify()?;
+        if self.current_key(&rotation.subject).as_ref() != Some(&rotation.old_public_key) {
+            return Err(KeyRotationError::NotCurrentKey(rotation.subject.clone()));
+        }
+        if let Some(prev) = self.rotations(&rotation.subject).last() {
+            if rotation.activation_height <= prev.activation_height {
+                return Err(KeyRotationError::ActivationTooEarly {
+                    subject: rotation.subject.clone(),
+                    activation: rotation.activation_height,
+                    after: prev.activation_height,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// [`check`](Self::check) a rotation and record it.
+    pub fn record(&mut self, rotation: KeyRotation) -> Result<(), KeyRotationError> {
+        self.check(&rotation)?;
+        self.rotations
+            .entry(rotation.subject.clone())
+            .or_default()
+            .push(rotation);
+        Ok(())
+    }
+
+    /// Keys that verify signatures of `subject` at `height`: the key active
+    /// at that height, plus its predecessor while in its grace window.
+    pub fn keys_at(&self, subject: &str, height: u64) -> Vec<Vec<u8>> {
+        let mut keys = Vec::new();
+        let mut key = hex::decode(subject).ok();
+        let mut active_from = 0;
+        for rotation in self.rotations(subject) {
+            if let Some(k) = key.take() {
+                if height >= active_from && height < rotation.old_key_expires_at() {
+                    keys.push(k);
+                }
+            }
+            key = Some(rotation.new_public_key.clone());
+            active_from = rotation.activation_height;
+        }
+        if let Some(k) = key {
+            if height >= active_from {
+                keys.push(k);
+            }
+        }
+        keys
+    }
+
+    /// Whether `signature` over `payload` verifies for `subject` at `height`.
+    pub fn verify(&self, subject: &str, height: u64, payload: &[u8], signature: &[u8]) -> bool {
+        self.keys_at(subject, height)
+            .iter()
+            .any(|key| verify_with_public_key(key, payload, signature))
+    }
+}
+
+/// [`SignatureVerifier`] that resolves voter keys through a shared
+/// [`KeySchedule`] at a given height.
+///
+/// Clones share the height, so a node can move every verifier it handed to
+/// its round manager forward with [`set_height`](Self::set_height).
+#[derive(Debug, Clone)]
+pub struct ScheduledKeyVerifier {
+    schedule: SharedKeySchedule,
+    height: Arc<AtomicU64>,
+}
+
+impl ScheduledKeyVerifier {
+    /// Verifier over `schedule` at `height`.
+    pub fn at(schedule: SharedKeySchedule, height: u64) -> Self {
+        Self {
+            schedule,
+            height: Arc::new(AtomicU64::new(height)),
+        }
+    }
+
+    /// Move this verifier and its clones to `height`.
+    pub fn set_height(&self, height: u64) {
+        self.height.store(height, Ordering::Relaxed);
+    }
+
+    /// Height keys are currently selected for.
+    pub fn height(&self) -> u64 {
+        self.height.load(Ordering::Relaxed)
+    }
+
+    /// The schedule keys are resolved from.
+    pub fn schedule(&self) -> &SharedKeySchedule {
+        &self.schedule
+    }
+}
+
+impl SignatureVerifier for ScheduledKeyVerifier {
+    fn verify(&self, voter_id: &str, payload: &[u8], signature: &[u8]) -> bool {
+        self.schedule
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .verify(voter_id, self.height(), payload, signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identity::signing::SoftwareSigningProvider;
+
+    fn provider() -> SoftwareSigningProvider {
+        SoftwareSigningProvider::generate()
+    }
+
+    fn id(p: &dyn SigningProvider) -> String {
+        hex::encode(p.public_key())
+    }
+
+    #[test]
+    fn rotation_is_signed_by_both_keys() {
+        let (old, new) = (provider(), provider());
+        let rotation = KeyRotation::signed(id(&old), &old, &new, 10, 5).unwrap();
+        assert_eq!(rotation.verify(), Ok(()));
+
+        let mut forged = rotation.clone();
+        forged.activation_height = 11;
+        assert!(forged.verify().is_err());
+        assert!(rotation.check_activation(9).is_ok());
+        assert!(rotation.check_activation(10).is_err());
+
+        let mut swapped = rotation;
+        swapped.old_signature = swapped.new_signature.clone();
+        assert_eq!(
+            swapped.verify(),
+            Err(KeyRotationError::InvalidOldSignature(id(&old)))
+        );
+    }
+
+    #[test]
+    fn keys_are_selected_by_height_with_grace_window() {
+        let (old, new) = (provider(), provider());
+        let subject = id(&old);
+        let mut schedule = KeySchedule::new();
+        schedule
+            .record(KeyRotation::signed(subject.clone(), &old, &new, 10, 5).unwrap())
+            .unwrap();
+
+        let payload = b"block";
+        let old_sig = old.sign(payload).unwrap();
+        let new_sig = new.sign(payload).unwrap();
+
+        assert!(schedule.verify(&subject, 9, payload, &old_sig));
+        assert!(!schedule.verify(&subject, 9, payload, &new_sig));
+        // Both keys verify during the grace window.
+        assert!(schedule.verify(&subject, 12, payload, &old_sig));
+        assert!(schedule.verify(&subject, 12, payload, &new_sig));
+        assert!(schedule.verify(&subject, 14, payload, &old_sig));
+        // The retired key stops verifying after it.
+        assert!(!schedule.verify(&subject, 15, payload, &old_sig));
+        assert!(schedule.verify(&subject, 15, payload, &new_sig));
+    }
+
+    #[test]
+    fn record_rejects_stale_or_out_of_order_rotations() {
+        let (k0, k1, k2) = (provider(), provider(), provider());
+        let subject = id(&k0);
+        let mut schedule = KeySchedule::new();
+        schedule
+            .record(KeyRotation::signed(subject.clone(), &k0, &k1, 10, 0).unwrap())
+            .unwrap();
+
+        // k0 is no longer current.
+        let stale = KeyRotation::signed(subject.clone(), &k0, &k2, 20, 0).unwrap();
+        assert_eq!(
+            schedule.record(stale),
+            Err(KeyRotationError::NotCurrentKey(subject.clone()))
+        );
+        // Activation must come after the previous one.
+        let early = KeyRotation::signed(subject.clone(), &k1, &k2, 10, 0).unwrap();
+        assert!(matches!(
+            schedule.record(early),
+            Err(KeyRotationError::ActivationTooEarly { .. })
+        ));
+        let next = KeyRotation::signed(subject.clone(), &k1, &k2, 20, 0).unwrap();
+        schedule.record(next).unwrap();
+        assert_eq!(schedule.current_key(&subject), Some(k2.public_key()));
+        assert_eq!(schedule.keys_at(&subject, 15), vec![k1.public_key()]);
+    }
+
+    #[test]
+    fn scheduled_verifier_clones_share_height() {
+        let (old, new) = (provider(), provider());
+        let subject = id(&old);
+        let schedule =
+            KeySchedule::from_rotations(&[
+                KeyRotation::signed(subject.clone(), &old, &new, 10, 0).unwrap()
+            ])
+            .unwrap();
+        let verifier = ScheduledKeyVerifier::at(Arc::new(RwLock::new(schedule)), 1);
+        let clone = verifier.clone();
+        let sig = new.sign(b"vote").unwrap();
+        assert!(!clone.verify(&subject, b"vote", &sig));
+        verifier.set_height(10);
+        assert!(clone.verify(&subject, b"vote", &sig));
+    }
+}
@@ -1,9 +1,15 @@
 //! Key management for identity system
 //!
-//! Supports Ed25519 keypair generation, storage, and rotation
+//! Supports Ed25519 keypair generation, storage, and rotation. A node's own
+//! signing key rotates through [`NodeKeys`]: the rotation is announced
+//! on-chain first and the new key is only used once that is committed.
 
 use pqc_crypto_module::legacy::ed25519::{Signer, SigningKey, Verifier, VerifyingKey};
 use pqc_crypto_module::legacy::rng::OsRng;
+use std::sync::Arc;
+
+use crate::identity::key_rotation::KeyRotation;
+use crate::identity::signing::{SigningError, SigningProvider, SoftwareSigningProvider};
 
 /// Public key information
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -30,8 +36,8 @@ pub struct KeyPair {
     pub created_at: u64,
 }
 
-#[allow(dead_code)]
 /// Key manager for identity
+#[derive(Clone)]
 pub struct KeyManager {
     /// Active keypair
     active_key: KeyPair,
@@ -40,7 +46,6 @@ pub struct KeyManager {
 }
 
 impl KeyManager {
-    #[allow(dead_code)]
     /// Create a new key manager with generated keypair
     pub fn new(timestamp: u64) -> Self {
         let signing_key = SigningKey::generate(&mut OsRng);
@@ -59,7 +64,6 @@ impl KeyManager {
         }
     }
 
-    #[allow(dead_code)]
     /// Get the current public key
     pub fn public_key(&self) -> [u8; 32] {
         self.active_key.public_key
@@ -79,7 +83,6 @@ impl KeyManager {
         keys
     }
 
-    #[allow(dead_code)]
     /// Rotate to a new keypair
     pub fn rotate_key(&mut self, timestamp: u64) {
         // Archive current key as retired
@@ -103,12 +106,34 @@ impl KeyManager {
         };
     }
 
+    /// Rotate to a new keypair and return the on-chain announcement of the
+    /// switch, signed by both the old and the new key.
+    pub fn rotate_key_announced(
+        &mut self,
+        subject: &str,
+        timestamp: u64,
+        activation_height: u64,
+        grace_blocks: u64,
+    ) -> Result<KeyRotation, SigningError> {
+        let old = SoftwareSigningProvider::from_key(self.active_key.signing_key.clone());
+        self.rotate_key(timestamp);
+        let new = SoftwareSigningProvider::from_key(self.active_key.signing_key.clone());
+        KeyRotation::signed(subject, &old, &new, activation_height, grace_blocks)
+    }
+
     #[allow(dead_code)]
     /// Get the active signing key for creating signatures
     pub fn signing_key(&self) -> &SigningKey {
         &self.active_key.signing_key
     }
 
+    /// A signing provider over the active key.
+    pub fn signing_provider(&self) -> Arc<dyn SigningProvider> {
+        Arc::new(SoftwareSigningProvider::from_key(
+            self.active_key.signing_key.clone(),
+        ))
+    }
+
     #[allow(dead_code)]
     /// Sign data with the active key
     pub fn sign(&self, data: &[u8]) -> [u8; 64] {
@@ -116,7 +141,6 @@ impl KeyManager {
         signature.to_bytes()
     }
 
-    #[allow(dead_code)]
     /// Verify a signature with the active key
     pub fn verify(&self, data: &[u8], signature: &[u8; 64]) -> bool {
         let verifying_key = self.active_key.signing_key.verifying_key();
@@ -129,26 +153,19 @@ impl KeyManager {
     }
 
     #[allow(dead_code)]
-    /// Verify a signature with any available key (including retired)
-    pub fn verify_with_any_key(&self, data: &[u8], signature: &[u8; 64]) -> bool {
-        // Try active key first
+    /// Verify a signature with the active key, or with a retired key whose
+    /// retirement is less than `grace_secs` before `now`.
+    pub fn verify_at(&self, data: &[u8], signature: &[u8; 64], now: u64, grace_secs: u64) -> bool {
         if self.verify(data, signature) {
             return true;
         }
-
-        // Try retired keys
-        for retired_key in &self.retired_keys {
-            if let Ok(verifying_key) = VerifyingKey::from_bytes(&retired_key.public_key) {
-                use pqc_crypto_module::legacy::ed25519::Signature;
-                if let Ok(sig) = Signature::from_slice(signature) {
-                    if verifying_key.verify(data, &sig).is_ok() {
-                        return true;
-                    }
-                }
-            }
-        }
-
-        false
+        self.retired_keys
+            .iter()
+            .filter(|k| {
+                k.expires_at
+                    .is_some_and(|retired| now < retired.saturating_add(grace_secs))
+            })
+            .any(|k| verify_with_key(&k.public_key, data, signature))
     }
 
     #[allow(dead_code)]
@@ -164,6 +181,74 @@ impl KeyManager {
     }
 }
 
+/// This node's signing keys, and a rotation announced but not committed yet.
+pub struct NodeKeys {
+    /// Validator or orderer ID the node joined with.
+    subject: String,
+    current: KeyManager,
+    pending: Option<(KeyRotation, KeyManager)>,
+}
+
+impl NodeKeys {
+    /// Keys of a node identified by the hex public key of `keys`.
+    pub fn new(keys: KeyManager) -> Self {
+        Self {
+            subject: hex::encode(keys.public_key()),
+            current: keys,
+            pending: None,
+        }
+    }
+
+    /// Validator or orderer ID the rotations are announced for.
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    /// Signing provider over the key in use.
+    pub fn signing_provider(&self) -> Arc<dyn SigningProvider> {
+        self.current.signing_provider()
+    }
+
+    /// Announce a switch to a fresh key at `activation_height`. The key in
+    /// use does not change until [`commit_rotation`](Self::commit_rotation);
+    /// a later announcement replaces this one.
+    pub fn announce_rotation(
+        &mut self,
+        timestamp: u64,
+        activation_height: u64,
+        grace_blocks: u64,
+    ) -> Result<KeyRotation, SigningError> {
+        let mut next = self.current.clone();
+        let rotation =
+            next.rotate_key_announced(&self.subject, timestamp, activation_height, grace_blocks)?;
+        self.pending = Some((rotation.clone(), next));
+        Ok(rotation)
+    }
+
+    /// Adopt the announced key once `rotation` is committed. Returns the
+    /// signer for blocks from its activation height, `None` if `rotation`
+    /// is not the pending announcement.
+    pub fn commit_rotation(&mut self, rotation: &KeyRotation) -> Option<Arc<dyn SigningProvider>> {
+        if self.pending.as_ref().map(|(r, _)| r) != Some(rotation) {
+            return None;
+        }
+        let (_, next) = self.pending.take()?;
+        self.current = next;
+        Some(self.current.signing_provider())
+    }
+}
+
+/// Verify an Ed25519 signature against raw public key bytes.
+fn verify_with_key(public_key: &[u8; 32], data: &[u8], signature: &[u8; 64]) -> bool {
+    use pqc_crypto_module::legacy::ed25519::Signature;
+    let Ok(verifying_key) = VerifyingKey::from_bytes(public_key) else {
+        return false;
+    };
+    Signature::from_slice(signature)
+        .map(|sig| verifying_key.verify(data, &sig).is_ok())
+        .unwrap_or(false)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -208,8 +293,8 @@ mod tests {
         let data = b"test message";
         let signature = km.sign(data);
         km.rotate_key(2000);
-        // Verify with any key should still work
-        assert!(km.verify_with_any_key(data, &signature));
+        // The retired key still verifies within its grace window
+        assert!(km.verify_at(data, &signature, 2000, 60));
     }
 
     #[test]
@@ -232,6 +317,54 @@ mod tests {
         assert_eq!(signature.len(), 64); // Ed25519 signature is 64 bytes
     }
 
+    #[test]
+    fn retired_key_stops_verifying_after_grace() {
+        let mut km = KeyManager::new(1000);
+        let data = b"test message";
+        let signature = km.sign(data);
+        km.rotate_key(2000);
+        assert!(km.verify_at(data, &signature, 2050, 100));
+        assert!(!km.verify_at(data, &signature, 2100, 100));
+        // The active key has no expiry.
+        let fresh = km.sign(data);
+        assert!(km.verify_at(data, &fresh, u64::MAX, 0));
+    }
+
+    #[test]
+    fn announced_rotation_is_signed_by_both_keys() {
+        let mut km = KeyManager::new(1000);
+        let old = km.public_key();
+        let rotation = km
+            .rotate_key_announced(&hex::encode(old), 2000, 50, 10)
+            .unwrap();
+        assert_eq!(rotation.old_public_key, old.to_vec());
+        assert_eq!(rotation.new_public_key, km.public_key().to_vec());
+        assert_eq!(rotation.verify(), Ok(()));
+    }
+
+    #[test]
+    fn node_keys_switch_once_the_announcement_commits() {
+        let mut keys = NodeKeys::new(KeyManager::new(1000));
+        let subject = keys.subject().to_string();
+        let before = keys.signing_provider().public_key();
+
+        let stale = keys.announce_rotation(2000, 40, 10).unwrap();
+        let rotation = keys.announce_rotation(2000, 50, 10).unwrap();
+        assert_eq!(rotation.subject, subject);
+        assert_eq!(rotation.old_public_key, before);
+        assert_eq!(keys.signing_provider().public_key(), before);
+
+        assert!(keys.commit_rotation(&stale).is_none());
+        let signer = keys.commit_rotation(&rotation).unwrap();
+        assert_eq!(signer.public_key(), rotation.new_public_key);
+        assert_eq!(
+            keys.signing_provider().public_key(),
+            rotation.new_public_key
+        );
+        assert_eq!(keys.subject(), subject);
+        assert!(keys.commit_rotation(&rotation).is_none());
+    }
+
     #[test]
     fn test_multiple_rotations() {
         let mut km = KeyManager::new(1000);
@@ -5,9 +5,11 @@
 //! - Key derivation and rotation
 //! - Signature generation and verification
 
+pub mod batch_verify;
 pub mod did;
 pub mod dual_signing;
 pub mod hsm;
+pub mod key_rotation;
 pub mod keys;
 pub mod pqc_policy;
 pub mod signing;
@@ -66,6 +66,38 @@ pub trait SigningProvider: Send + Sync {
     fn verify(&self, data: &[u8], sig: &[u8]) -> Result<bool, SigningError>;
 }
 
+/// Verify a signature against a raw public key.
+///
+/// Ed25519 keys are 32 bytes; anything else is treated as ML-DSA-65.
+pub fn verify_with_public_key(public_key: &[u8], payload: &[u8], signature: &[u8]) -> bool {
+    if public_key.len() == 32 {
+        use pqc_crypto_module::legacy::ed25519::{Signature, Verifier, VerifyingKey};
+        let Ok(pk) = <[u8; 32]>::try_from(public_key) else {
+            return false;
+        };
+        let Ok(vk) = VerifyingKey::from_bytes(&pk) else {
+            return false;
+        };
+        let Ok(sig) = Signature::try_from(signature) else {
+            return false;
+        };
+        return vk.verify(payload, &sig).is_ok();
+    }
+
+    use pqc_crypto_module::legacy::mldsa_raw::{DetachedSignature, PublicKey};
+    let Ok(pk) = pqc_crypto_module::legacy::mldsa_raw::mldsa65::PublicKey::from_bytes(public_key)
+    else {
+        return false;
+    };
+    let Ok(sig) =
+        pqc_crypto_module::legacy::mldsa_raw::mldsa65::DetachedSignature::from_bytes(signature)
+    else {
+        return false;
+    };
+    pqc_crypto_module::legacy::mldsa_raw::mldsa65::verify_detached_signature(&sig, payload, &pk)
+        .is_ok()
+}
+
 /// Software-based signing provider using in-memory Ed25519 keys.
 ///
 /// The inner `SigningKey` implements `ZeroizeOnDrop` — key material is
@@ -7,8 +7,13 @@
 //! 3. Verify the proof against the synced header's state_root
 
 use crate::bridge::verifier;
-use crate::consensus::bft::quorum::{QuorumValidator, SignatureVerifier};
+use crate::consensus::bft::epoch::{ValidatorSet, ValidatorSetHistory};
+use crate::consensus::bft::quorum::SignatureVerifier;
 use crate::consensus::bft::types::BftPhase;
+use crate::identity::key_rotation::{
+    KeyRotation, KeyRotationError, KeySchedule, ScheduledKeyVerifier, SharedKeySchedule,
+};
+use crate::storage::state_tree::{self, StateLeaf, StateTreeProof};
 
 use super::header::{BlockHeader, HeaderChain, HeaderError};
 
@@ -25,6 +30,49 @@ pub struct StateProof {
     pub height: u64,
     /// Merkle inclusion proof against the block's state_root.
     pub proof: crate::bridge::types::InclusionProof,
+    /// How `proof` is laid out; proofs without the field are `Merkle`.
+    #[serde(default)]
+    pub scheme: ProofScheme,
+    /// For a non-existence proof ending at another key's leaf, that leaf.
+    /// `None` when the key's slot in the state tree is empty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub neighbor: Option<StateLeaf>,
+}
+
+/// Layout of the Merkle proof in a [`StateProof`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProofScheme {
+    /// Binary Merkle tree over `key || value` leaves (`key` alone for a
+    /// non-existence proof).
+    #[default]
+    Merkle,
+    /// Sparse Merkle tree of the authenticated world state; `merkle_path`
+    /// holds the sibling hashes from the key's leaf up to the root.
+    SparseMerkle,
+}
+
+impl StateProof {
+    /// Wrap a state tree proof for `key` against the state after block
+    /// `height`.
+    pub fn from_tree_proof(key: &str, height: u64, proof: StateTreeProof) -> Self {
+        let leaf_index = proof.leaf_index(key);
+        Self {
+            key: key.to_string(),
+            exists: proof.value.is_some(),
+            value: proof.value.unwrap_or_default(),
+            height,
+            proof: crate::bridge::types::InclusionProof {
+                merkle_path: proof.siblings,
+                leaf_index,
+                root: proof.root,
+                block_hash: [0u8; 32],
+                block_height: height,
+            },
+            scheme: ProofScheme::SparseMerkle,
+            neighbor: proof.neighbor,
+        }
+    }
 }
 
 /// Light client errors.
@@ -45,8 +93,13 @@ pub enum LightClientError {
 /// A light client that tracks headers and verifies state proofs.
 pub struct LightClient<V: SignatureVerifier + Clone> {
     chain: HeaderChain,
-    /// BFT quorum validator for verifying commit QCs on headers.
-    quorum_validator: Option<QuorumValidator<V>>,
+    /// Verifier for commit QC vote signatures; `None` disables BFT checks.
+    verifier: Option<V>,
+    /// Validator set per epoch, following handoffs in synced headers.
+    validator_sets: ValidatorSetHistory,
+    /// Committed validator key rotations; when set, QC votes are verified
+    /// with the key each validator held at the header's height.
+    keys: Option<SharedKeySchedule>,
 }
 
 impl<V: SignatureVerifier + Clone> Default for LightClient<V> {
@@ -60,26 +113,50 @@ impl<V: SignatureVerifier + Clone> LightClient<V> {
     pub fn new() -> Self {
         Self {
             chain: HeaderChain::new(),
-            quorum_validator: None,
+            verifier: None,
+            validator_sets: ValidatorSetHistory::default(),
+            keys: None,
         }
     }
 
-    /// Create a light client with BFT header verification.
+    /// Create a light client with BFT header verification, starting from
+    /// the genesis validator set.
     pub fn with_bft(validators: Vec<String>, verifier: V) -> Self {
         Self {
             chain: HeaderChain::new(),
-            quorum_validator: Some(QuorumValidator::new(validators, verifier)),
+            verifier: Some(verifier),
+            validator_sets: ValidatorSetHistory::new(validators),
+            keys: None,
         }
     }
 
+    /// Verify QC votes by height against `schedule` instead of the plain
+    /// verifier, so rotated validator keys are followed and retired keys
+    /// stop certifying headers after their grace window.
+    pub fn with_key_schedule(mut self, schedule: KeySchedule) -> Self {
+        self.keys = Some(std::sync::Arc::new(std::sync::RwLock::new(schedule)));
+        self
+    }
+
+    /// Follow a validator key rotation read from a committed config block.
+    pub fn record_key_rotation(&mut self, rotation: KeyRotation) -> Result<(), KeyRotationError> {
+        self.keys
+            .get_or_insert_with(Default::default)
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .record(rotation)
+    }
+
     /// Sync a header from a full node.
     ///
     /// Validates hash integrity, parent linkage, and (if BFT enabled)
-    /// the commit QC on non-genesis headers.
+    /// the commit QC on non-genesis headers against the validator set of
+    /// the header's epoch. A header carrying `next_validator_set` hands
+    /// off to that set for the following headers.
     pub fn sync_header(&mut self, header: BlockHeader) -> Result<(), LightClientError> {
         // BFT verification: non-genesis headers must have a valid CommitQC.
         if header.height > 0 {
-            if let Some(ref qv) = self.quorum_validator {
+            if let Some(ref verifier) = self.verifier {
                 let qc = header
                     .commit_qc
                     .as_ref()
@@ -98,15 +175,37 @@ impl<V: SignatureVerifier + Clone> LightClient<V> {
                     ));
                 }
 
-                qv.validate_qc(qc)
-                    .map_err(|e| LightClientError::BftFailed(e.to_string()))?;
+                match &self.keys {
+                    Some(keys) => self.validator_sets.validate_commit_qc(
+                        qc,
+                        header.height,
+                        ScheduledKeyVerifier::at(keys.clone(), header.height),
+                    ),
+                    None => {
+                        self.validator_sets
+                            .validate_commit_qc(qc, header.height, verifier.clone())
+                    }
+                }
+                .map_err(|e| LightClientError::BftFailed(e.to_string()))?;
             }
         }
 
+        let mut validator_sets = self.validator_sets.clone();
+        if let (Some(set), Some(_)) = (&header.next_validator_set, &self.verifier) {
+            validator_sets
+                .record(set.clone())
+                .map_err(|e| LightClientError::BftFailed(e.to_string()))?;
+        }
         self.chain.append(header)?;
+        self.validator_sets = validator_sets;
         Ok(())
     }
 
+    /// Validator set that validates headers at `height`.
+    pub fn validator_set_for_height(&self, height: u64) -> Option<&ValidatorSet> {
+        self.validator_sets.set_for_height(height)
+    }
+
     /// Verify a state proof against a synced header.
     ///
     /// Checks that the Merkle proof roots to the state_root of the
@@ -125,18 +224,29 @@ impl<V: SignatureVerifier + Clone> LightClient<V> {
             )));
         }
 
-        // Verify the Merkle proof.
-        let leaf_data = if proof.exists {
-            // Leaf = key || value
-            let mut data = proof.key.as_bytes().to_vec();
-            data.extend_from_slice(&proof.value);
-            data
-        } else {
-            // Non-existence proof: leaf is just the key.
-            proof.key.as_bytes().to_vec()
+        let valid = match proof.scheme {
+            ProofScheme::Merkle => {
+                let leaf_data = if proof.exists {
+                    // Leaf = key || value
+                    let mut data = proof.key.as_bytes().to_vec();
+                    data.extend_from_slice(&proof.value);
+                    data
+                } else {
+                    // Non-existence proof: leaf is just the key.
+                    proof.key.as_bytes().to_vec()
+                };
+                verifier::verify_merkle_proof(&leaf_data, &proof.proof)
+            }
+            // Non-existence proofs end at an empty slot or at the leaf of
+            // another key on the same path.
+            ProofScheme::SparseMerkle => state_tree::verify_proof(
+                &proof.key,
+                proof.exists.then_some(proof.value.as_slice()),
+                proof.neighbor.as_ref(),
+                &proof.proof.merkle_path,
+                &proof.proof.root,
+            ),
         };
-
-        let valid = verifier::verify_merkle_proof(&leaf_data, &proof.proof);
         if !valid {
             return Err(LightClientError::ProofFailed(proof.key.clone()));
         }
@@ -166,6 +276,7 @@ mod tests {
     use crate::bridge::types::InclusionProof;
     use crate::bridge::verifier::build_merkle_tree;
     use crate::consensus::bft::types::{BftPhase, QuorumCertificate, VoteMessage};
+    use crate::storage::state_tree::StateTree;
 
     #[derive(Clone)]
     struct TestVerifier;
@@ -186,18 +297,23 @@ mod tests {
             proposer: "v0".into(),
             tx_count: 0,
             commit_qc: None,
+            next_validator_set: None,
         };
         h.hash = h.compute_hash();
         h
     }
 
     fn make_qc(block_hash: [u8; 32]) -> QuorumCertificate {
+        qc_from(block_hash, "v")
+    }
+
+    fn qc_from(block_hash: [u8; 32], prefix: &str) -> QuorumCertificate {
         let votes: Vec<VoteMessage> = (0..3)
             .map(|i| VoteMessage {
                 block_hash,
                 round: 0,
                 phase: BftPhase::Commit,
-                voter_id: format!("v{i}"),
+                voter_id: format!("{prefix}{i}"),
                 signature: vec![1u8; 64],
             })
             .collect();
@@ -215,6 +331,7 @@ mod tests {
             proposer: format!("v{}", height % 4),
             tx_count: 1,
             commit_qc: None,
+            next_validator_set: None,
         };
         h.hash = h.compute_hash();
         h.commit_qc = Some(make_qc(h.hash));
@@ -281,21 +398,90 @@ mod tests {
         assert!(matches!(err, LightClientError::BftFailed(_)));
     }
 
-    // --- state proof verification ---
+    #[test]
+    fn sync_with_bft_follows_validator_set_handoff() {
+        let mut lc = LightClient::with_bft(validators(), TestVerifier);
+        let g = genesis();
+        lc.sync_header(g.clone()).unwrap();
+
+        // Block 1 hands off to the "w" validators from height 2.
+        let mut c1 = child_with_qc(&g, 1, [10u8; 32]);
+        c1.next_validator_set = Some(ValidatorSet {
+            epoch: 1,
+            start_height: 2,
+            validators: (0..4).map(|i| format!("w{i}")).collect(),
+        });
+        c1.hash = c1.compute_hash();
+        c1.commit_qc = Some(make_qc(c1.hash));
+        lc.sync_header(c1.clone()).unwrap();
+        assert_eq!(lc.validator_set_for_height(2).unwrap().epoch, 1);
+
+        // A QC from the old set no longer certifies height 2.
+        let stale = child_with_qc(&c1, 2, [11u8; 32]);
+        let err = lc.sync_header(stale.clone()).unwrap_err();
+        assert!(matches!(err, LightClientError::BftFailed(_)));
+
+        let mut c2 = stale;
+        c2.commit_qc = Some(qc_from(c2.hash, "w"));
+        lc.sync_header(c2).unwrap();
+        assert_eq!(lc.synced_height(), Some(2));
+    }
 
     #[test]
-    fn verify_valid_state_proof() {
-        let mut lc: LightClient<TestVerifier> = LightClient::new();
+    fn sync_with_key_schedule_follows_rotation() {
+        use crate::identity::signing::{SigningProvider, SoftwareSigningProvider};
+        use crate::ordering::bft_node::PublicKeyVerifier;
 
-        // Build a state with one key-value pair.
-        let key = "balance:alice";
-        let value = b"1000";
-        let leaf_data = [key.as_bytes(), value.as_slice()].concat();
+        let signers: Vec<SoftwareSigningProvider> = (0..4)
+            .map(|_| SoftwareSigningProvider::generate())
+            .collect();
+        let ids: Vec<String> = signers
+            .iter()
+            .map(|s| hex::encode(s.public_key()))
+            .collect();
+        let rotated = SoftwareSigningProvider::generate();
+        let rotation = KeyRotation::signed(&ids[0], &signers[0], &rotated, 2, 0).unwrap();
+        let signed_qc = |hash: [u8; 32], first: &SoftwareSigningProvider| {
+            let votes = (0..3)
+                .map(|i| {
+                    let signer = if i == 0 { first } else { &signers[i] };
+                    let payload = VoteMessage::signing_payload(BftPhase::Commit, &hash, 0);
+                    VoteMessage {
+                        block_hash: hash,
+                        round: 0,
+                        phase: BftPhase::Commit,
+                        voter_id: ids[i].clone(),
+                        signature: signer.sign(&payload).unwrap(),
+                    }
+                })
+                .collect();
+            QuorumCertificate::new(BftPhase::Commit, hash, 0, votes).unwrap()
+        };
 
-        let (root, proofs) = build_merkle_tree(&[&leaf_data]);
-        let state_root = root.unwrap();
+        let mut lc = LightClient::with_bft(ids.clone(), PublicKeyVerifier);
+        lc.record_key_rotation(rotation).unwrap();
+        let g = genesis();
+        lc.sync_header(g.clone()).unwrap();
 
-        // Create genesis with this state_root.
+        // Height 1 still verifies with the old key.
+        let mut c1 = child_with_qc(&g, 1, [10u8; 32]);
+        c1.commit_qc = Some(signed_qc(c1.hash, &signers[0]));
+        lc.sync_header(c1.clone()).unwrap();
+
+        // From height 2 the old key is retired (no grace window).
+        let mut c2 = child_with_qc(&c1, 2, [11u8; 32]);
+        c2.commit_qc = Some(signed_qc(c2.hash, &signers[0]));
+        let err = lc.sync_header(c2.clone()).unwrap_err();
+        assert!(matches!(err, LightClientError::BftFailed(_)));
+
+        c2.commit_qc = Some(signed_qc(c2.hash, &rotated));
+        lc.sync_header(c2).unwrap();
+        assert_eq!(lc.synced_height(), Some(2));
+    }
+
+    // --- state proof verification ---
+
+    fn genesis_with_state_root(state_root: [u8; 32]) -> BlockHeader {
         let mut g = BlockHeader {
             height: 0,
             hash: [0u8; 32],
@@ -306,9 +492,26 @@ mod tests {
             proposer: "v0".into(),
             tx_count: 0,
             commit_qc: None,
+            next_validator_set: None,
         };
         g.hash = g.compute_hash();
-        lc.sync_header(g).unwrap();
+        g
+    }
+
+    #[test]
+    fn verify_valid_state_proof() {
+        let mut lc: LightClient<TestVerifier> = LightClient::new();
+
+        // Build a state with one key-value pair.
+        let key = "balance:alice";
+        let value = b"1000";
+        let leaf_data = [key.as_bytes(), value.as_slice()].concat();
+
+        let (root, proofs) = build_merkle_tree(&[&leaf_data]);
+
+        // Create genesis with this state_root.
+        lc.sync_header(genesis_with_state_root(root.unwrap()))
+            .unwrap();
 
         let state_proof = StateProof {
             key: key.to_string(),
@@ -316,6 +519,8 @@ mod tests {
             exists: true,
             height: 0,
             proof: proofs[0].clone(),
+            scheme: ProofScheme::Merkle,
+            neighbor: None,
         };
 
         let exists = lc.verify_state_proof(&state_proof).unwrap();
@@ -328,20 +533,8 @@ mod tests {
 
         let leaf_data = b"balance:alice1000";
         let (root, proofs) = build_merkle_tree(&[leaf_data.as_slice()]);
-
-        let mut g = BlockHeader {
-            height: 0,
-            hash: [0u8; 32],
-            parent_hash: [0u8; 32],
-            tx_merkle_root: [0u8; 32],
-            state_root: root.unwrap(),
-            timestamp: 1000,
-            proposer: "v0".into(),
-            tx_count: 0,
-            commit_qc: None,
-        };
-        g.hash = g.compute_hash();
-        lc.sync_header(g).unwrap();
+        lc.sync_header(genesis_with_state_root(root.unwrap()))
+            .unwrap();
 
         // Proof for wrong value.
         let state_proof = StateProof {
@@ -350,12 +543,89 @@ mod tests {
             exists: true,
             height: 0,
             proof: proofs[0].clone(),
+            scheme: ProofScheme::Merkle,
+            neighbor: None,
         };
 
         let err = lc.verify_state_proof(&state_proof).unwrap_err();
         assert!(matches!(err, LightClientError::ProofFailed(_)));
     }
 
+    #[test]
+    fn verify_valid_sparse_state_proof() {
+        let mut lc: LightClient<TestVerifier> = LightClient::new();
+
+        // Build a state with one key-value pair.
+        let key = "balance:alice";
+        let value = b"1000";
+        let tree = StateTree::new().insert(key, value);
+
+        // Create genesis with this state_root.
+        lc.sync_header(genesis_with_state_root(tree.root()))
+            .unwrap();
+
+        let state_proof = StateProof::from_tree_proof(key, 0, tree.prove(key));
+        assert_eq!(state_proof.value, value.to_vec());
+
+        let exists = lc.verify_state_proof(&state_proof).unwrap();
+        assert!(exists);
+    }
+
+    #[test]
+    fn verify_invalid_sparse_state_proof() {
+        let mut lc: LightClient<TestVerifier> = LightClient::new();
+
+        let tree = StateTree::new().insert("balance:alice", b"1000");
+        lc.sync_header(genesis_with_state_root(tree.root()))
+            .unwrap();
+
+        // Proof for wrong value.
+        let mut state_proof =
+            StateProof::from_tree_proof("balance:alice", 0, tree.prove("balance:alice"));
+        state_proof.value = b"9999".to_vec(); // Wrong value.
+
+        let err = lc.verify_state_proof(&state_proof).unwrap_err();
+        assert!(matches!(err, LightClientError::ProofFailed(_)));
+    }
+
+    #[test]
+    fn verify_non_existence_proof() {
+        let mut lc: LightClient<TestVerifier> = LightClient::new();
+
+        let tree = (0..20).fold(StateTree::new(), |tree, i| {
+            tree.insert(&format!("balance:{i}"), b"1")
+        });
+        lc.sync_header(genesis_with_state_root(tree.root()))
+            .unwrap();
+
+        for i in 0..20 {
+            let key = format!("absent:{i}");
+            let proof = StateProof::from_tree_proof(&key, 0, tree.prove(&key));
+            assert!(!proof.exists);
+            assert!(!lc.verify_state_proof(&proof).unwrap());
+        }
+
+        // A present key cannot be passed off as absent.
+        let mut forged = StateProof::from_tree_proof("balance:3", 0, tree.prove("balance:3"));
+        forged.exists = false;
+        forged.value.clear();
+        assert!(matches!(
+            lc.verify_state_proof(&forged).unwrap_err(),
+            LightClientError::ProofFailed(_)
+        ));
+    }
+
+    #[test]
+    fn state_proof_serde_roundtrip() {
+        let tree = StateTree::new().insert("a", b"1").insert("b", b"2");
+        let proof = StateProof::from_tree_proof("c", 0, tree.prove("c"));
+        let json = serde_json::to_string(&proof).unwrap();
+        let back: StateProof = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.scheme, ProofScheme::SparseMerkle);
+        assert_eq!(back.neighbor, proof.neighbor);
+        assert_eq!(back.proof.merkle_path, proof.proof.merkle_path);
+    }
+
     #[test]
     fn verify_proof_wrong_height() {
         let lc: LightClient<TestVerifier> = LightClient::new();
@@ -371,6 +641,8 @@ mod tests {
                 block_hash: [0u8; 32],
                 block_height: 99,
             },
+            scheme: ProofScheme::Merkle,
+            neighbor: None,
         };
         let err = lc.verify_state_proof(&proof).unwrap_err();
         assert!(matches!(err, LightClientError::HeightNotFound(99)));
@@ -393,6 +665,8 @@ mod tests {
                 block_hash: [0u8; 32],
                 block_height: 0,
             },
+            scheme: ProofScheme::Merkle,
+            neighbor: None,
         };
         let err = lc.verify_state_proof(&proof).unwrap_err();
         assert!(matches!(err, LightClientError::ProofFailed(_)));
@@ -6,6 +6,7 @@
 use pqc_crypto_module::legacy::sha256::{Digest, Sha256};
 use serde::{Deserialize, Serialize};
 
+use crate::consensus::bft::epoch::ValidatorSet;
 use crate::consensus::bft::types::QuorumCertificate;
 
 /// Compact block header for light client verification.
@@ -33,12 +34,17 @@ pub struct BlockHeader {
     /// BFT commit QC (proves 2f+1 validators agreed on this block).
     /// `None` for genesis or pre-BFT blocks.
     pub commit_qc: Option<QuorumCertificate>,
+    /// Validator set for the next BFT epoch, carried by the last block of
+    /// an epoch. Covered by the header hash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_validator_set: Option<ValidatorSet>,
 }
 
 impl BlockHeader {
     /// Compute the canonical hash of this header.
     ///
-    /// Hash = SHA-256(height || parent_hash || tx_merkle_root || state_root || timestamp || proposer)
+    /// Hash = SHA-256(height || parent_hash || tx_merkle_root || state_root || timestamp || proposer
+    /// [|| next_validator_set digest])
     pub fn compute_hash(&self) -> [u8; 32] {
         let mut hasher = Sha256::new();
         hasher.update(self.height.to_le_bytes());
@@ -47,6 +53,9 @@ impl BlockHeader {
         hasher.update(self.state_root);
         hasher.update(self.timestamp.to_le_bytes());
         hasher.update(self.proposer.as_bytes());
+        if let Some(set) = &self.next_validator_set {
+            hasher.update(set.digest());
+        }
         hasher.finalize().into()
     }
 
@@ -131,6 +140,7 @@ impl HeaderChain {
 }
 
 /// Errors from header operations.
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
 pub enum HeaderError {
     #[error("invalid header hash at height {height}")]
@@ -156,6 +166,7 @@ mod tests {
             proposer: "v0".into(),
             tx_count: 0,
             commit_qc: None,
+            next_validator_set: None,
         };
         h.hash = h.compute_hash();
         h
@@ -172,6 +183,7 @@ mod tests {
             proposer: format!("v{}", height % 4),
             tx_count: height as u32,
             commit_qc: None,
+            next_validator_set: None,
         };
         h.hash = h.compute_hash();
         h
@@ -273,6 +285,7 @@ mod tests {
             proposer: "v1".into(),
             tx_count: 0,
             commit_qc: None,
+            next_validator_set: None,
         };
         bad_child.hash = bad_child.compute_hash();
 
@@ -8,6 +8,7 @@ mod audit;
 mod billing;
 mod block_creation;
 mod blockchain;
+mod bridge;
 mod cache;
 mod chaincode;
 mod channel;
@@ -27,6 +28,7 @@ mod governance;
 mod identity;
 mod intelligence;
 mod legal_oracle;
+mod light_client;
 mod metrics;
 mod middleware;
 mod mining;
@@ -69,7 +71,7 @@ use staking::StakingManager;
 use std::env;
 use std::net::SocketAddr;
 use std::sync::{Arc, Mutex, RwLock};
-#[cfg(feature = "rocksdb-storage")]
+#[cfg(any(feature = "rocksdb-storage", feature = "redb-storage"))]
 use storage::BlockStore;
 #[cfg(feature = "rocksdb-storage")]
 use storage::RocksDbBlockStore;
@@ -398,7 +400,10 @@ async fn async_main_inner() -> std::io::Result<()> {
     log::info!("Cryptographic self-tests passed (Ed25519, ML-DSA-65, SHA-256, SHA3-256)");
     log::info!("Hash algorithm: {hash_algo}");
 
-    let signing_provider: Arc<dyn crate::identity::signing::SigningProvider> = {
+    let (signing_provider, node_keys): (
+        Arc<dyn crate::identity::signing::SigningProvider>,
+        Option<Arc<Mutex<crate::identity::keys::NodeKeys>>>,
+    ) = {
         use crate::identity::signing::SigningProvider as _;
         let algo = std::env::var("SIGNING_ALGORITHM").unwrap_or_default();
         match algo.to_lowercase().as_str() {
@@ -408,15 +413,23 @@ async fn async_main_inner() -> std::io::Result<()> {
                     "Signing algorithm: ML-DSA-65 (FIPS 204, post-quantum) | pubkey_len={} bytes",
                     provider.public_key().len()
                 );
-                Arc::new(provider)
+                (Arc::new(provider), None)
             }
             "" | "ed25519" => {
-                let provider = crate::identity::signing::SoftwareSigningProvider::generate();
+                let keys = crate::identity::keys::NodeKeys::new(
+                    crate::identity::keys::KeyManager::new(
+                        std::time::SystemTime::now()
+                            .duration_since(std::time::UNIX_EPOCH)
+                            .unwrap_or_default()
+                            .as_secs(),
+                    ),
+                );
+                let provider = keys.signing_provider();
                 log::info!(
                     "Signing algorithm: Ed25519 | pubkey_len={} bytes",
                     provider.public_key().len()
                 );
-                Arc::new(provider)
+                (provider, Some(Arc::new(Mutex::new(keys))))
             }
             other => {
                 panic!(
@@ -426,11 +439,254 @@ async fn async_main_inner() -> std::io::Result<()> {
         }
     };
 
+    // Initialize scaffold services — use RocksDB-backed impls when STORAGE_BACKEND=rocksdb.
+    let storage_backend_env = env::var("STORAGE_BACKEND").unwrap_or_default();
+    // STORAGE_ENCRYPTION=file|hsm: seal the ledger, state, vault, audit log and
+    // private data of the persistent store under ML-KEM-wrapped data keys.
+    let storage_kek = match storage::encryption::kek_from_env() {
+        Ok(kek) => kek,
+        Err(e) => {
+            log::error!("Storage encryption key unavailable: {e}");
+            return Err(std::io::Error::other(format!("storage KEK: {e}")));
+        }
+    };
+    #[cfg(feature = "rocksdb-storage")]
+    let shared_rocksdb: Option<Arc<RocksDbBlockStore>> = if storage_backend_env == "rocksdb" {
+        let path = env::var("STORAGE_PATH").unwrap_or_else(|_| "./data/blocks".to_string());
+        match RocksDbBlockStore::new(&path) {
+            Ok(store) => {
+                if let Some(ref kek) = storage_kek {
+                    if let Err(e) = store.enable_encryption(kek.clone()) {
+                        log::error!("Storage encryption failed to start: {e}");
+                        return Err(std::io::Error::other(format!("storage encryption: {e}")));
+                    }
+                }
+                // Run schema migrations before serving requests.
+                match storage::migrations::run_pending(&store) {
+                    Ok(n) if n > 0 => log::info!("{n} schema migration(s) applied"),
+                    Err(e) => {
+                        log::error!("Schema migration failed: {e}");
+                        return Err(std::io::Error::other(format!("migration failed: {e}")));
+                    }
+                    _ => {}
+                }
+                // Reconcile the world-state savepoint with the block height.
+                match store.recover_state() {
+                    Ok(report) if !report.is_clean() => log::warn!(
+                        "Recovered ledger state: {} block(s) replayed, {} truncated, {} rolled back; tip now {:?}",
+                        report.replayed,
+                        report.truncated,
+                        report.rolled_back,
+                        report.block_height
+                    ),
+                    Err(e) => {
+                        log::error!("Ledger state recovery failed: {e}");
+                        return Err(std::io::Error::other(format!("state recovery failed: {e}")));
+                    }
+                    _ => {}
+                }
+                log::info!("Storage backend: RocksDB at {path}");
+                Some(Arc::new(store))
+            }
+            Err(e) => {
+                log::error!("STORAGE_BACKEND=rocksdb but failed to open RocksDB at {path}: {e}");
+                return Err(std::io::Error::other(format!(
+                    "RocksDB failed to open at {path}: {e}"
+                )));
+            }
+        }
+    } else {
+        None
+    };
+    #[cfg(not(feature = "rocksdb-storage"))]
+    let shared_rocksdb: Option<Arc<storage::MemoryStore>> = {
+        let _ = &storage_backend_env;
+        if storage_backend_env == "rocksdb" {
+            log::warn!("STORAGE_BACKEND=rocksdb but 'rocksdb-storage' feature not compiled. Using memory store.");
+        }
+        None
+    };
+    // STORAGE_BACKEND=redb: the same persistent services on redb, a pure-Rust
+    // embedded store (`bcctl migrate-storage` converts a RocksDB directory).
+    #[cfg(feature = "redb-storage")]
+    let shared_redb: Option<Arc<storage::RedbBlockStore>> = if storage_backend_env == "redb" {
+        let path = env::var("STORAGE_PATH").unwrap_or_else(|_| "./data/blocks".to_string());
+        match storage::RedbBlockStore::new(&path) {
+            Ok(store) => {
+                if let Some(ref kek) = storage_kek {
+                    if let Err(e) = store.enable_encryption(kek.clone()) {
+                        log::error!("Storage encryption failed to start: {e}");
+                        return Err(std::io::Error::other(format!("storage encryption: {e}")));
+                    }
+                }
+                log::info!("Storage backend: redb at {path}");
+                Some(Arc::new(store))
+            }
+            Err(e) => {
+                log::error!("STORAGE_BACKEND=redb but failed to open redb at {path}: {e}");
+                return Err(std::io::Error::other(format!(
+                    "redb failed to open at {path}: {e}"
+                )));
+            }
+        }
+    } else {
+        None
+    };
+    #[cfg(not(feature = "redb-storage"))]
+    let shared_redb: Option<Arc<storage::MemoryStore>> = {
+        if storage_backend_env == "redb" {
+            log::warn!(
+                "STORAGE_BACKEND=redb but 'redb-storage' feature not compiled. Using memory store."
+            );
+        }
+        None
+    };
+    // The first persistent store that was opened (mapped through `$found`),
+    // else `$fallback`.
+    #[cfg(any(feature = "rocksdb-storage", feature = "redb-storage"))]
+    macro_rules! persistent_or {
+        ($fallback:expr) => {
+            persistent_or!(db => db.clone(), $fallback)
+        };
+        ($db:ident => $found:expr, $fallback:expr) => {
+            'store: {
+                #[cfg(feature = "rocksdb-storage")]
+                if let Some(ref $db) = shared_rocksdb {
+                    break 'store $found;
+                }
+                #[cfg(feature = "redb-storage")]
+                if let Some(ref $db) = shared_redb {
+                    break 'store $found;
+                }
+                $fallback
+            }
+        };
+    }
+    #[cfg(not(any(feature = "rocksdb-storage", feature = "redb-storage")))]
+    macro_rules! persistent_or {
+        ($fallback:expr) => {
+            $fallback
+        };
+        ($db:ident => $found:expr, $fallback:expr) => {
+            $fallback
+        };
+    }
+    if storage_kek.is_some() && shared_rocksdb.is_none() && shared_redb.is_none() {
+        log::warn!("STORAGE_ENCRYPTION is set but the in-memory store is not encrypted");
+    }
+
+    // Values written before encryption was enabled, or under a rotated key,
+    // are re-encrypted in the background; the markers survive a restart.
+    let storage_encryption: Option<Arc<dyn storage::encryption::EncryptedStore>> =
+        if storage_kek.is_none() {
+            None
+        } else {
+            persistent_or!(db => Some(db.clone()), None)
+        };
+    if let Some(ref store) = storage_encryption {
+        match store.pending_reencryption() {
+            Ok(pending) if !pending.is_empty() => {
+                log::info!("Re-encrypting {} storage scope(s) in the background", pending.len());
+                if let Err(e) = storage::encryption::spawn_reencryption(store.clone(), pending) {
+                    log::error!("Failed to start storage re-encryption: {e}");
+                }
+            }
+            Err(e) => log::error!("Failed to read pending storage re-encryption: {e}"),
+            _ => {}
+        }
+    }
+
+    // Attach persistent store to TransactionValidator for replay prevention
+    #[cfg(feature = "rocksdb-storage")]
+    if let Some(ref db) = shared_rocksdb {
+        let mut tv = transaction_validator
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Ok(entries) = db.load_seen_txs() {
+            let count = entries.len();
+            for (tx_id, ts) in entries {
+                tv.seen_transaction_ids.insert(tx_id, ts);
+            }
+            if count > 0 {
+                log::info!("Loaded {count} seen transaction IDs from RocksDB");
+            }
+        }
+        tv.store = Some(db.clone());
+    }
+    #[cfg(feature = "redb-storage")]
+    if let Some(ref db) = shared_redb {
+        let mut tv = transaction_validator
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Ok(entries) = db.load_seen_txs() {
+            let count = entries.len();
+            for (tx_id, ts) in entries {
+                tv.seen_transaction_ids.insert(tx_id, ts);
+            }
+            if count > 0 {
+                log::info!("Loaded {count} seen transaction IDs from redb");
+            }
+        }
+        tv.store = Some(db.clone());
+    }
+
+    if shared_rocksdb.is_some() {
+        log::info!("Services: persistent (RocksDB) — orgs, policies, ACLs, CRL, chaincode, collections, private data, seen tx IDs");
+    } else if shared_redb.is_some() {
+        log::info!("Services: persistent (redb) — orgs, policies, ACLs, CRL, chaincode, collections, private data, seen tx IDs");
+    } else {
+        log::info!("Services: in-memory — data will be lost on restart");
+    }
+
+    let org_registry: Arc<dyn crate::endorsement::registry::OrgRegistry> = persistent_or!(
+        Arc::new(crate::endorsement::registry::MemoryOrgRegistry::new())
+    );
+    let policy_store: Arc<dyn crate::endorsement::policy_store::PolicyStore> = persistent_or!(
+        Arc::new(crate::endorsement::policy_store::MemoryPolicyStore::new())
+    );
+    let acl_provider: Arc<dyn crate::acl::AclProvider> =
+        persistent_or!(Arc::new(crate::acl::MemoryAclProvider::new()));
+    // Bind the query resources to their default policy ("Readers") unless
+    // an operator already set them.
+    match crate::acl::resources::install_default_acls(acl_provider.as_ref()) {
+        Ok(0) => {}
+        Ok(n) => log::info!(
+            "Installed {n} default ACL entries (policy '{}')",
+            crate::acl::resources::READERS_POLICY
+        ),
+        Err(e) => log::warn!("Could not install default ACL entries: {e}"),
+    }
+
+    // Orderer admission control: well-formedness, size, signature policy,
+    // writers ACL ("channel/Writers") and replay window.
+    let admission = ordering::admission::AdmissionChain::standard_with_acl(
+        org_registry.clone(),
+        acl_provider.clone(),
+        policy_store.clone(),
+    );
+
     // Ordering backend: "raft" or "solo" (default)
     //
     // When ORDERING_BACKEND=raft, also reads:
     //   RAFT_NODE_ID  — this node's raft ID (default: 1)
     //   RAFT_PEERS    — comma-separated `id:host:port` (e.g. "1:orderer1:8087,2:orderer2:8087")
+    //   RAFT_JOIN     — "true" when this orderer joins an existing cluster as a
+    //                   learner added by a consenter config update (default: false)
+    //   RAFT_COMPACT_MAX_ENTRIES / RAFT_COMPACT_MAX_BYTES — log compaction thresholds
+    //
+    // When ORDERING_BACKEND=bft, also reads:
+    //   BFT_PEERS            — comma-separated `pubkey_hex@host:port` for every
+    //                          validator, this node included (its ID is the hex
+    //                          public key of the node's signing provider)
+    //   BFT_ROUND_TIMEOUT_MS — base round timeout before a view change (default: 3000)
+    //   BFT_EPOCH_LENGTH     — blocks per validator-set epoch; at each boundary the
+    //                          committee is recomputed from staking (default: 0 = fixed)
+    //   BFT_MAX_VALIDATORS / BFT_MIN_STAKE — DPoS committee size and stake floor
+    //
+    // ORDERING_GROUPS=true additionally runs one group of the same backend per
+    // channel created through the API, logging under STORAGE_PATH/orderer/<channel>.
+    let mut shared_bft_service: Option<Arc<ordering::bft_service::BftOrderingService>> = None;
+    let mut bft_peers: Vec<ordering::bft_transport::BftPeer> = Vec::new();
     #[cfg(feature = "raft-ordering")]
     let mut shared_raft_node: Option<Arc<Mutex<crate::ordering::raft_node::RaftNode>>> = None;
     #[cfg(feature = "raft-ordering")]
@@ -450,7 +706,11 @@ async fn async_main_inner() -> std::io::Result<()> {
                 let parsed_map = crate::ordering::raft_transport::parse_raft_peers(&peer_map_raw);
                 let raft_voter_ids: Vec<u64> = parsed_map.keys().copied().collect();
 
-                let voters = if raft_voter_ids.is_empty() {
+                let joining = env::var("RAFT_JOIN").map(|v| v == "true").unwrap_or(false);
+                let voters = if joining {
+                    // Membership arrives with the leader's snapshot.
+                    Vec::new()
+                } else if raft_voter_ids.is_empty() {
                     vec![raft_id]
                 } else {
                     raft_voter_ids
@@ -472,10 +732,22 @@ async fn async_main_inner() -> std::io::Result<()> {
                         log::info!(
                             "Ordering backend: Raft (node_id={raft_id}, peers={peer_map_raw})"
                         );
-                        let svc = svc.with_signing_provider(signing_provider.clone());
+                        let svc = svc
+                            .with_signing_provider(signing_provider.clone())
+                            .with_compaction_policy(
+                                crate::ordering::raft_storage::CompactionPolicy::from_env(),
+                            )
+                            .with_block_size_limits(ordering::BlockSizeLimits::from_env())
+                            .with_admission(admission.clone());
                         let raft_arc = svc.raft_node.clone();
                         shared_raft_node = Some(raft_arc.clone());
-                        raft_peer_map = Some(Arc::new(Mutex::new(parsed_map)));
+                        let peer_map = Arc::new(Mutex::new(parsed_map));
+                        // Committed consenter changes keep the transport's map current.
+                        raft_arc
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .set_peer_map(peer_map.clone());
+                        raft_peer_map = Some(peer_map);
 
                         // Use the same RaftOrderingService for the gateway —
                         // shares the RaftNode with the tick loop and P2P handler.
@@ -487,103 +759,69 @@ async fn async_main_inner() -> std::io::Result<()> {
                         );
                         Some(Arc::new(
                             ordering::service::OrderingService::new()
-                                .with_signing_provider(signing_provider.clone()),
+                                .with_signing_provider(signing_provider.clone())
+                                .with_admission(admission.clone()),
                         ))
                     }
                 }
             }
+            "bft" => {
+                let node_id = ordering::bft_node::validator_id(signing_provider.as_ref());
+                bft_peers = ordering::bft_transport::parse_bft_peers(
+                    &env::var("BFT_PEERS").unwrap_or_default(),
+                );
+                let mut validators: Vec<String> =
+                    bft_peers.iter().map(|p| p.validator_id.clone()).collect();
+                if !validators.contains(&node_id) {
+                    validators.push(node_id.clone());
+                }
+                if validators.len() < crate::consensus::bft::quorum::MIN_BFT_VALIDATORS {
+                    log::warn!(
+                        "BFT ordering with {} validators tolerates no faults (need >= {})",
+                        validators.len(),
+                        crate::consensus::bft::quorum::MIN_BFT_VALIDATORS
+                    );
+                }
+                let config = crate::consensus::bft::round_manager::RoundManagerConfig {
+                    base_timeout_ms: env::var("BFT_ROUND_TIMEOUT_MS")
+                        .ok()
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or(3000),
+                    ..Default::default()
+                };
+                log::info!(
+                    "Ordering backend: BFT (node_id={node_id}, validators={})",
+                    validators.len()
+                );
+                let svc = Arc::new(
+                    ordering::bft_service::BftOrderingService::new(
+                        signing_provider.clone(),
+                        validators,
+                        config,
+                        100,
+                    )
+                    .with_epochs(
+                        crate::consensus::bft::epoch::EpochConfig::from_env(),
+                        staking_manager.clone(),
+                    )
+                    .with_block_size_limits(ordering::BlockSizeLimits::from_env())
+                    .with_admission(admission.clone()),
+                );
+                shared_bft_service = Some(svc.clone());
+                Some(svc)
+            }
             _ => {
                 log::info!("Ordering backend: Solo");
                 Some(Arc::new(
                     ordering::service::OrderingService::new()
-                        .with_signing_provider(signing_provider.clone()),
+                        .with_signing_provider(signing_provider.clone())
+                        .with_metrics(metrics_collector.clone())
+                        .with_admission(admission.clone()),
                 ))
             }
         }
     };
 
-    // Initialize scaffold services — use RocksDB-backed impls when STORAGE_BACKEND=rocksdb.
-    let storage_backend_env = env::var("STORAGE_BACKEND").unwrap_or_default();
-    #[cfg(feature = "rocksdb-storage")]
-    let shared_rocksdb: Option<Arc<RocksDbBlockStore>> = if storage_backend_env == "rocksdb" {
-        let path = env::var("STORAGE_PATH").unwrap_or_else(|_| "./data/blocks".to_string());
-        match RocksDbBlockStore::new(&path) {
-            Ok(store) => {
-                // Run schema migrations before serving requests.
-                match storage::migrations::run_pending(&store) {
-                    Ok(n) if n > 0 => log::info!("{n} schema migration(s) applied"),
-                    Err(e) => {
-                        log::error!("Schema migration failed: {e}");
-                        return Err(std::io::Error::other(format!("migration failed: {e}")));
-                    }
-                    _ => {}
-                }
-                log::info!("Storage backend: RocksDB at {path}");
-                Some(Arc::new(store))
-            }
-            Err(e) => {
-                log::error!("STORAGE_BACKEND=rocksdb but failed to open RocksDB at {path}: {e}");
-                return Err(std::io::Error::other(format!(
-                    "RocksDB failed to open at {path}: {e}"
-                )));
-            }
-        }
-    } else {
-        None
-    };
-    #[cfg(not(feature = "rocksdb-storage"))]
-    let shared_rocksdb: Option<Arc<storage::MemoryStore>> = {
-        let _ = &storage_backend_env;
-        if storage_backend_env == "rocksdb" {
-            log::warn!("STORAGE_BACKEND=rocksdb but 'rocksdb-storage' feature not compiled. Using memory store.");
-        }
-        None
-    };
-
-    // Attach persistent store to TransactionValidator for replay prevention
-    #[cfg(feature = "rocksdb-storage")]
-    if let Some(ref db) = shared_rocksdb {
-        let mut tv = transaction_validator
-            .lock()
-            .unwrap_or_else(|e| e.into_inner());
-        if let Ok(entries) = db.load_seen_txs() {
-            let count = entries.len();
-            for (tx_id, ts) in entries {
-                tv.seen_transaction_ids.insert(tx_id, ts);
-            }
-            if count > 0 {
-                log::info!("Loaded {count} seen transaction IDs from RocksDB");
-            }
-        }
-        tv.store = Some(db.clone());
-    }
-
-    if shared_rocksdb.is_some() {
-        log::info!("Services: persistent (RocksDB) — orgs, policies, ACLs, CRL, chaincode, collections, private data, seen tx IDs");
-    } else {
-        log::info!("Services: in-memory — data will be lost on restart");
-    }
-
-    let org_registry: Arc<dyn crate::endorsement::registry::OrgRegistry> = {
-        #[cfg(feature = "rocksdb-storage")]
-        if let Some(ref db) = shared_rocksdb {
-            db.clone()
-        } else {
-            Arc::new(crate::endorsement::registry::MemoryOrgRegistry::new())
-        }
-        #[cfg(not(feature = "rocksdb-storage"))]
-        Arc::new(crate::endorsement::registry::MemoryOrgRegistry::new())
-    };
-    let policy_store: Arc<dyn crate::endorsement::policy_store::PolicyStore> = {
-        #[cfg(feature = "rocksdb-storage")]
-        if let Some(ref db) = shared_rocksdb {
-            db.clone()
-        } else {
-            Arc::new(crate::endorsement::policy_store::MemoryPolicyStore::new())
-        }
-        #[cfg(not(feature = "rocksdb-storage"))]
-        Arc::new(crate::endorsement::policy_store::MemoryPolicyStore::new())
-    };
     let discovery_service = Arc::new(
         crate::discovery::service::DiscoveryService::new(
             org_registry.clone(),
@@ -591,9 +829,44 @@ async fn async_main_inner() -> std::io::Result<()> {
         )
         .with_metrics(metrics_collector.clone()),
     );
+    // STATE_DB=rocksdb keeps the world state in the RocksDB block store, which
+    // also maintains its secondary indexes (rich queries, /admin/indexes).
+    #[allow(unused_mut)]
+    let mut state_index: Option<Arc<dyn storage::index::StateIndexStore>> = None;
     let world_state: Arc<dyn storage::world_state::WorldState> = {
         let state_db = env::var("STATE_DB").unwrap_or_default();
-        if state_db == "couchdb" {
+        if state_db == "rocksdb" {
+            #[cfg(feature = "rocksdb-storage")]
+            if let Some(ref db) = shared_rocksdb {
+                log::info!("World state backend: RocksDB (with secondary indexes)");
+                state_index = Some(db.clone());
+                db.clone()
+            } else {
+                log::warn!(
+                    "STATE_DB=rocksdb needs STORAGE_BACKEND=rocksdb. Using MemoryWorldState."
+                );
+                Arc::new(storage::MemoryWorldState::new())
+            }
+            #[cfg(not(feature = "rocksdb-storage"))]
+            {
+                log::warn!("STATE_DB=rocksdb but 'rocksdb-storage' feature not compiled. Using MemoryWorldState.");
+                Arc::new(storage::MemoryWorldState::new())
+            }
+        } else if state_db == "redb" {
+            #[cfg(feature = "redb-storage")]
+            if let Some(ref db) = shared_redb {
+                log::info!("World state backend: redb");
+                db.clone()
+            } else {
+                log::warn!("STATE_DB=redb needs STORAGE_BACKEND=redb. Using MemoryWorldState.");
+                Arc::new(storage::MemoryWorldState::new())
+            }
+            #[cfg(not(feature = "redb-storage"))]
+            {
+                log::warn!("STATE_DB=redb but 'redb-storage' feature not compiled. Using MemoryWorldState.");
+                Arc::new(storage::MemoryWorldState::new())
+            }
+        } else if state_db == "couchdb" {
             let couchdb_url =
                 env::var("COUCHDB_URL").unwrap_or_else(|_| "http://localhost:5984".to_string());
             let couchdb_db = env::var("COUCHDB_DB").unwrap_or_else(|_| "world_state".to_string());
@@ -614,42 +887,83 @@ async fn async_main_inner() -> std::io::Result<()> {
             Arc::new(storage::MemoryWorldState::new())
         }
     };
-    let chaincode_package_store: Arc<dyn crate::chaincode::ChaincodePackageStore> = {
-        #[cfg(feature = "rocksdb-storage")]
-        if let Some(ref db) = shared_rocksdb {
-            db.clone()
-        } else {
-            Arc::new(crate::chaincode::MemoryChaincodePackageStore::new())
-        }
-        #[cfg(not(feature = "rocksdb-storage"))]
+    let chaincode_package_store: Arc<dyn crate::chaincode::ChaincodePackageStore> = persistent_or!(
         Arc::new(crate::chaincode::MemoryChaincodePackageStore::new())
-    };
+    );
     let ordering_service_for_gateway: Arc<dyn ordering::OrderingBackend> =
         ordering_backend.clone().unwrap_or_else(|| {
             Arc::new(
                 ordering::service::OrderingService::new()
-                    .with_signing_provider(signing_provider.clone()),
+                    .with_signing_provider(signing_provider.clone())
+                    .with_admission(admission.clone()),
             )
         });
-    let gateway_store: Arc<dyn storage::BlockStore> = {
-        #[cfg(feature = "rocksdb-storage")]
-        if let Some(ref db) = shared_rocksdb {
-            db.clone()
-        } else {
-            Arc::new(storage::MemoryStore::new())
+    let gateway_store: Arc<dyn storage::BlockStore> =
+        persistent_or!(Arc::new(storage::MemoryStore::new()));
+    // Blocks cut from here on point at the ledger's latest config block.
+    if let Ok(tip) = gateway_store.read_block(gateway_store.get_latest_height().unwrap_or(0)) {
+        ordering_service_for_gateway.resume_last_config(tip.last_config);
+    }
+    // Authenticated world state: blocks committed through the gateway carry
+    // the resulting state root, and `/state/{key}/proof` proves against it
+    // for the last STATE_PROOF_RETENTION_BLOCKS heights.
+    let authenticated_state = Arc::new(
+        storage::state_tree::AuthenticatedWorldState::new(
+            world_state,
+            gateway_store.get_latest_height().unwrap_or(0),
+        )
+        .expect("FATAL: failed to build the world state tree — node cannot start")
+        .with_retention(
+            env::var("STATE_PROOF_RETENTION_BLOCKS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(storage::state_tree::DEFAULT_RETENTION_BLOCKS),
+        ),
+    );
+    let world_state: Arc<dyn storage::world_state::WorldState> = authenticated_state.clone();
+    // Cold archive for blocks pruned by the default channel's retention policy.
+    // The node key is regenerated on every start, so manifests signed before
+    // a restart are only accepted from the keys in ARCHIVE_TRUSTED_KEYS. A
+    // manifest that fails verification disables pruning rather than the node.
+    let block_archive = {
+        let base_dir = env::var("ARCHIVE_DIR").unwrap_or_else(|_| "./data".to_string());
+        match storage::archive::BlockArchive::open(
+            std::path::Path::new(&base_dir),
+            "default",
+            signing_provider.clone(),
+            storage::archive::trusted_keys_from_env(),
+        ) {
+            Ok(archive) => {
+                log::info!(
+                    "Block archive manifests signed with {}",
+                    hex::encode(signing_provider.public_key())
+                );
+                Some(Arc::new(archive))
+            }
+            Err(e) => {
+                log::error!("Block archive at {base_dir} unavailable, retention disabled: {e}");
+                None
+            }
         }
-        #[cfg(not(feature = "rocksdb-storage"))]
-        Arc::new(storage::MemoryStore::new())
     };
     let mut gateway = crate::gateway::Gateway::new(
         org_registry.clone(),
         policy_store.clone(),
         ordering_service_for_gateway,
         gateway_store.clone(),
-    );
-    gateway.world_state = Some(world_state.clone());
+    )
+    .with_authenticated_state(authenticated_state.clone())
+    .with_signing_provider(signing_provider.clone());
     gateway.discovery_service = Some(discovery_service.clone());
     gateway.p2p_node = Some(node_arc.clone());
+    // Per-channel ordering groups (ORDERING_GROUPS=true): every channel created
+    // through the API gets a consenter instance of its own.
+    let ordering_groups = ordering::groups::OrderingGroups::from_env(signing_provider.clone())
+        .map(|groups| Arc::new(groups.with_admission(admission.clone())));
+    if ordering_groups.is_some() {
+        log::info!("Ordering groups: one consenter instance per channel");
+    }
+    gateway.ordering_groups = ordering_groups.clone();
     let event_bus = Arc::new(events::EventBus::new());
     gateway.event_bus = Some(event_bus.clone());
 
@@ -659,6 +973,26 @@ async fn async_main_inner() -> std::io::Result<()> {
         events::webhook::spawn_webhook_notifier(webhook_config, webhook_rx);
     }
 
+    // SQL projection (SQL_PROJECTION_PATH): blocks, transactions, state changes
+    // and chaincode events in an SQLite file for analytics.
+    if let Ok(path) = env::var("SQL_PROJECTION_PATH") {
+        #[cfg(feature = "sql-projection")]
+        match storage::projection::SqliteProjection::open(std::path::Path::new(&path)) {
+            Ok(sink) => {
+                log::info!("SQL projection: {path}");
+                let projector = storage::projection::Projector::new(
+                    gateway_store.clone(),
+                    world_state.clone(),
+                    sink,
+                );
+                storage::projection::spawn_projector(projector, event_bus.subscribe());
+            }
+            Err(e) => log::error!("SQL projection at {path} unavailable: {e}"),
+        }
+        #[cfg(not(feature = "sql-projection"))]
+        log::warn!("SQL_PROJECTION_PATH={path} but 'sql-projection' feature not compiled");
+    }
+
     // Wire endorsement resources into the P2P server node so it can handle
     // ProposalRequest messages (simulate chaincode + sign rwset).
     node_for_server.chaincode_store = Some(chaincode_package_store.clone());
@@ -667,34 +1001,97 @@ async fn async_main_inner() -> std::io::Result<()> {
     // Wire the gateway store into the server node for pull-based state sync
     // (StateRequest handler reads blocks from this store).
     node_for_server.store = Some(gateway_store.clone());
+    // Route channel-scoped consensus messages to their ordering group.
+    node_for_server.ordering_groups = ordering_groups.clone();
+    // Validate config blocks (and check `last_config`) before ordered blocks commit.
+    node_for_server.config_validator = Some(crate::channel::ledger::ConfigValidator::new(
+        policy_store.clone(),
+        org_registry.clone(),
+    ));
+    // Pre-verify the signatures of ordered blocks in parallel before they
+    // commit, sharing the verified-signature cache with the submit path.
+    let signature_verifier = crate::identity::batch_verify::BatchVerifier::new(Arc::new(
+        crate::identity::batch_verify::VerifiedSignatureCache::from_env(),
+    ));
+    let block_preverifier =
+        crate::ordering::preverify::BlockPreverifier::new(signature_verifier.cache().clone())
+            .with_registry(org_registry.clone());
+    if let Ok(history) = crate::channel::ledger::config_history(gateway_store.as_ref()) {
+        if let Some(config) = history.last() {
+            *block_preverifier
+                .key_schedule()
+                .write()
+                .unwrap_or_else(|e| e.into_inner()) = config.key_schedule();
+        }
+    }
+    node_for_server.block_preverifier = Some(Arc::new(block_preverifier));
     // Wire Raft node into the P2P server for RaftMessage handling.
     #[cfg(feature = "raft-ordering")]
     if let Some(ref raft) = shared_raft_node {
         node_for_server.raft_node = Some(raft.clone());
     }
-    // Wire private data resources for PrivateDataPush handling.
-    let private_data_store: Arc<dyn crate::private_data::PrivateDataStore> = {
-        #[cfg(feature = "rocksdb-storage")]
-        if let Some(ref db) = shared_rocksdb {
-            db.clone()
-        } else {
-            Arc::new(crate::private_data::MemoryPrivateDataStore::new())
+    // Wire the BFT validator into the P2P server and continue its chain
+    // from the local ledger tip.
+    if let Some(ref bft) = shared_bft_service {
+        node_for_server.bft_node = Some(bft.clone());
+        let tip = gateway_store.get_latest_height().unwrap_or(0);
+        // Replay validator-set handoffs committed at epoch boundaries.
+        let epoch_length = crate::consensus::bft::epoch::EpochConfig::from_env().epoch_length;
+        if epoch_length > 0 {
+            for height in (epoch_length..=tip).step_by(epoch_length as usize) {
+                if let Ok(Some(set)) = gateway_store
+                    .read_block(height)
+                    .map(|b| b.next_validator_set)
+                {
+                    bft.restore_validator_set(set);
+                }
+            }
         }
-        #[cfg(not(feature = "rocksdb-storage"))]
-        Arc::new(crate::private_data::MemoryPrivateDataStore::new())
-    };
-    let collection_registry: Arc<dyn crate::private_data::CollectionRegistry> = {
-        #[cfg(feature = "rocksdb-storage")]
-        if let Some(ref db) = shared_rocksdb {
-            db.clone()
-        } else {
-            Arc::new(crate::private_data::MemoryCollectionRegistry::new())
+        // Rebuild the penalty ledger from committed evidence so an offense
+        // is never slashed twice.
+        for height in 1..=tip {
+            if let Ok(block) = gateway_store.read_block(height) {
+                if !block.evidence.is_empty() {
+                    bft.restore_evidence(&block);
+                }
+            }
+        }
+        // Verify votes with the signing keys rotated in the committed config.
+        if let Some(config) = crate::channel::ledger::config_history(gateway_store.as_ref())
+            .ok()
+            .and_then(|history| history.last().cloned())
+        {
+            bft.set_key_schedule(config.key_schedule());
         }
-        #[cfg(not(feature = "rocksdb-storage"))]
+        if let Ok(block) = gateway_store.read_block(tip) {
+            bft.set_ledger_tip(tip, ordering::block_hash_for_signing(&block));
+            // Never vote again in a round this validator may have voted in.
+            if let Some(qc) = &block.commit_qc {
+                bft.resume_after_round(qc.round);
+            }
+        }
+        bft.set_event_bus(event_bus.clone());
+    }
+    // Wire private data resources for PrivateDataPush handling.
+    let private_data_store: Arc<dyn crate::private_data::PrivateDataStore> =
+        persistent_or!(Arc::new(crate::private_data::MemoryPrivateDataStore::new()));
+    let collection_registry: Arc<dyn crate::private_data::CollectionRegistry> = persistent_or!(
         Arc::new(crate::private_data::MemoryCollectionRegistry::new())
-    };
+    );
+    let audit_store: Arc<dyn crate::audit::AuditStore> =
+        persistent_or!(Arc::new(crate::audit::MemoryAuditStore::new()));
     node_for_server.private_data_store = Some(private_data_store.clone());
     node_for_server.collection_registry = Some(collection_registry.clone());
+    // Applying and recording the endorsed private data purges of committed blocks.
+    let purge_committer = crate::private_data::PurgeCommitter::new(
+        collection_registry.clone(),
+        org_registry.clone(),
+        private_data_store.clone(),
+        node_for_server.org_id.clone(),
+    )
+    .with_audit_store(audit_store.clone());
+    node_for_server.purge_committer = Some(purge_committer.clone());
+    gateway.purge_committer = Some(purge_committer);
 
     // Hydrate governance stores from persistent storage
     let proposal_store = {
@@ -733,6 +1130,36 @@ async fn async_main_inner() -> std::io::Result<()> {
         vs
     };
 
+    // Online backups (POST /admin/backups) of the RocksDB block store, Raft
+    // log and channel stores into BACKUP_DIR; `bcctl restore` restores them.
+    #[cfg(feature = "rocksdb-storage")]
+    let backup_provider: Option<Arc<dyn storage::backup::BackupProvider>> =
+        shared_rocksdb.as_ref().map(|db| {
+            #[allow(unused_mut)]
+            let mut backups = storage::backup::RocksDbBackups::new(
+                db.clone(),
+                env::var("STORAGE_PATH").unwrap_or_else(|_| "./data/blocks".to_string()),
+                env::var("BACKUP_DIR").unwrap_or_else(|_| "./backups".to_string()),
+            );
+            #[cfg(feature = "raft-ordering")]
+            if let Some(raft_log) = shared_raft_node
+                .as_ref()
+                .and_then(|raft| raft.lock().unwrap_or_else(|e| e.into_inner()).persistent_storage())
+            {
+                backups = backups.with_source("raft", raft_log);
+            }
+            Arc::new(backups) as Arc<dyn storage::backup::BackupProvider>
+        });
+    #[cfg(not(feature = "rocksdb-storage"))]
+    let backup_provider: Option<Arc<dyn storage::backup::BackupProvider>> = None;
+
+    // SNAPSHOT_BOOTSTRAP=true: a peer with an empty ledger joins from a
+    // bootstrap snapshot served by its peers instead of replaying every block.
+    let snapshot_join = env::var("SNAPSHOT_BOOTSTRAP")
+        .map(|v| v == "true")
+        .unwrap_or(false)
+        && !gateway_store.block_exists(0).unwrap_or(true);
+
     let app_state = AppState {
         blockchain: blockchain_arc.clone(),
         wallet_manager: wallet_manager_arc.clone(),
@@ -751,7 +1178,8 @@ async fn async_main_inner() -> std::io::Result<()> {
             // operate on the same data.
             let default_store: Arc<dyn storage::BlockStore> = gateway_store.clone();
             // Write genesis block for the default channel if store is empty.
-            if !default_store.block_exists(0).unwrap_or(true) {
+            // A peer joining from a snapshot gets it from the snapshot instead.
+            if !snapshot_join && !default_store.block_exists(0).unwrap_or(true) {
                 let genesis_config = crate::channel::config::ChannelConfig::default();
                 let genesis =
                     crate::channel::genesis::create_genesis_block("default", &genesis_config);
@@ -765,58 +1193,37 @@ async fn async_main_inner() -> std::io::Result<()> {
             store_map.insert("default".to_string(), default_store);
             std::sync::Arc::new(std::sync::RwLock::new(store_map))
         },
-        org_registry: Some(org_registry),
+        org_registry: Some(org_registry.clone()),
         policy_store: Some(policy_store),
-        crl_store: Some({
-            #[cfg(feature = "rocksdb-storage")]
-            if let Some(ref db) = shared_rocksdb {
-                db.clone() as Arc<dyn crate::msp::CrlStore>
-            } else {
-                Arc::new(crate::msp::MemoryCrlStore::new()) as Arc<dyn crate::msp::CrlStore>
-            }
-            #[cfg(not(feature = "rocksdb-storage"))]
-            {
-                Arc::new(crate::msp::MemoryCrlStore::new()) as Arc<dyn crate::msp::CrlStore>
-            }
-        }),
+        crl_store: Some(persistent_or!(Arc::new(crate::msp::MemoryCrlStore::new()))),
         private_data_store: Some(private_data_store.clone()),
         collection_registry: Some(collection_registry.clone()),
         chaincode_package_store: Some(chaincode_package_store.clone()),
-        chaincode_definition_store: Some({
-            #[cfg(feature = "rocksdb-storage")]
-            if let Some(ref db) = shared_rocksdb {
-                db.clone() as Arc<dyn crate::chaincode::ChaincodeDefinitionStore>
-            } else {
-                Arc::new(crate::chaincode::MemoryChaincodeDefinitionStore::new())
-                    as Arc<dyn crate::chaincode::ChaincodeDefinitionStore>
-            }
-            #[cfg(not(feature = "rocksdb-storage"))]
-            {
-                Arc::new(crate::chaincode::MemoryChaincodeDefinitionStore::new())
-                    as Arc<dyn crate::chaincode::ChaincodeDefinitionStore>
-            }
-        }),
+        chaincode_definition_store: Some(persistent_or!(Arc::new(
+            crate::chaincode::MemoryChaincodeDefinitionStore::new()
+        ))),
         gateway: Some(Arc::new(gateway)),
         discovery_service: Some(discovery_service),
         event_bus: event_bus.clone(),
         channel_configs: std::sync::Arc::new(std::sync::RwLock::new(
             std::collections::HashMap::new(),
         )),
-        acl_provider: Some({
-            #[cfg(feature = "rocksdb-storage")]
-            if let Some(ref db) = shared_rocksdb {
-                db.clone() as Arc<dyn crate::acl::AclProvider>
-            } else {
-                Arc::new(crate::acl::MemoryAclProvider::new()) as Arc<dyn crate::acl::AclProvider>
-            }
-            #[cfg(not(feature = "rocksdb-storage"))]
-            {
-                Arc::new(crate::acl::MemoryAclProvider::new()) as Arc<dyn crate::acl::AclProvider>
-            }
-        }),
+        acl_provider: Some(acl_provider.clone()),
         ordering_backend,
+        ordering_groups: ordering_groups.clone(),
         world_state: Some(world_state.clone()),
-        audit_store: Some(Arc::new(crate::audit::MemoryAuditStore::new())),
+        channel_world_states: std::sync::Arc::new(std::sync::RwLock::new(
+            std::collections::HashMap::new(),
+        )),
+        authenticated_state: Some(authenticated_state.clone()),
+        channel_authenticated_states: std::sync::Arc::new(std::sync::RwLock::new(
+            std::collections::HashMap::new(),
+        )),
+        block_archive: block_archive.clone(),
+        backup_provider,
+        storage_encryption,
+        state_index,
+        audit_store: Some(audit_store.clone()),
         proposal_store: Some(proposal_store),
         vote_store: Some(vote_store),
         param_registry: Some({
@@ -848,6 +1255,7 @@ async fn async_main_inner() -> std::io::Result<()> {
                 .with_signer(signing_provider.clone()),
         )),
         signing_provider: Some(signing_provider.clone()),
+        node_keys,
         tx_pool: Arc::new(std::sync::Mutex::new(
             transaction::mempool::TransactionPool::new(),
         )),
@@ -855,6 +1263,10 @@ async fn async_main_inner() -> std::io::Result<()> {
             .ok()
             .filter(|s| !s.is_empty())
             .map(|s| hex::decode(s.as_str()).unwrap_or_else(|_| s.into_bytes())),
+        finality: Arc::new(crate::consensus::finality::FinalityTracker::new(
+            max_reorg_depth,
+        )),
+        signature_verifier,
     };
 
     // Telemetry adapter: polls external APIs and ingests into Asset Registry.
@@ -904,8 +1316,58 @@ async fn async_main_inner() -> std::io::Result<()> {
     let bootstrap_nodes_clone = bootstrap_nodes.clone();
 
     // Start pull-based state sync loop (catches up from peers with higher block height).
-    let pull_sync_handle =
-        node_for_server.start_pull_sync_loop(crate::network::gossip::PULL_INTERVAL_MS);
+    // A snapshot join installs the snapshot first, so sync starts above it.
+    let pull_sync_handle = if snapshot_join {
+        let node = node_for_server.clone();
+        let registry = org_registry.clone();
+        let crl_store = app_state.crl_store.clone();
+        let state = authenticated_state.clone();
+        let min_signatures = env::var("SNAPSHOT_MIN_ORG_SIGNATURES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(1);
+        tokio::spawn(async move {
+            let mut retry = tokio::time::interval(tokio::time::Duration::from_secs(10));
+            loop {
+                retry.tick().await;
+                let orgs = registry
+                    .list_orgs()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|org| org.org_id)
+                    .collect();
+                let policy = crate::endorsement::policy::EndorsementPolicy::NOutOf {
+                    n: min_signatures,
+                    orgs,
+                };
+                match node
+                    .join_from_snapshot(
+                        "default",
+                        &policy,
+                        registry.as_ref(),
+                        crl_store.as_deref(),
+                        &state,
+                    )
+                    .await
+                {
+                    Ok(height) => {
+                        log::info!("Snapshot bootstrap complete at height {height}");
+                        break;
+                    }
+                    Err(e) => log::warn!("Snapshot bootstrap failed, retrying: {e}"),
+                }
+            }
+            let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(
+                crate::network::gossip::PULL_INTERVAL_MS,
+            ));
+            loop {
+                interval.tick().await;
+                node.pull_missing_blocks().await;
+            }
+        })
+    } else {
+        node_for_server.start_pull_sync_loop(crate::network::gossip::PULL_INTERVAL_MS)
+    };
 
     // Start Raft tick loop if raft backend is configured.
     #[cfg(feature = "raft-ordering")]
@@ -915,10 +1377,28 @@ async fn async_main_inner() -> std::io::Result<()> {
             peer_map.clone(),
             node_arc.clone(),
             100, // tick every 100ms
+            None,
         );
         log::info!("Raft tick loop started (100ms interval)");
     }
 
+    // Channel groups tick and talk to their peers from here on.
+    if let Some(ref groups) = ordering_groups {
+        groups.start_transport(node_arc.clone());
+    }
+
+    // Start the BFT round loop if the bft backend is configured.
+    if let Some(ref bft) = shared_bft_service {
+        let _bft_handle = crate::ordering::bft_transport::start_bft_loop(
+            bft.clone(),
+            bft_peers.clone(),
+            node_arc.clone(),
+            50, // tick every 50ms
+            None,
+        );
+        log::info!("BFT round loop started (50ms interval)");
+    }
+
     // Private data TTL purge loop — expires entries whose blocks_to_live window has closed.
     {
         let pd_store = private_data_store.clone();
@@ -935,6 +1415,42 @@ async fn async_main_inner() -> std::io::Result<()> {
         });
     }
 
+    // Retention loop — moves blocks past the channel RetentionPolicy into the
+    // cold archive, leaving their headers in the block store.
+    if let Some(archive) = block_archive.clone() {
+        let gw_store = gateway_store.clone();
+        let interval_secs: u64 = env::var("RETENTION_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(300);
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(tokio::time::Duration::from_secs(interval_secs));
+            loop {
+                interval.tick().await;
+                let policy = crate::channel::ledger::current_config(gw_store.as_ref())
+                    .map(|config| config.retention_policy)
+                    .unwrap_or_default();
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs();
+                match storage::archive::enforce_retention(gw_store.as_ref(), &archive, &policy, now)
+                {
+                    Ok(Some(segment)) => log::info!(
+                        "Archived blocks {}..={} ({} txs) to {}",
+                        segment.first_height,
+                        segment.last_height,
+                        segment.tx_count,
+                        segment.file
+                    ),
+                    Ok(None) => {}
+                    Err(e) => log::warn!("Retention pass failed: {e}"),
+                }
+            }
+        });
+    }
+
     let server_handle = tokio::spawn(async move {
         if let Err(e) = node_for_server.start_server(p2p_port).await {
             eprintln!("Error en servidor P2P: {e}");
@@ -1,4 +1,6 @@
-use prometheus::{Histogram, HistogramOpts, IntCounter, IntGauge, Opts, Registry, TextEncoder};
+use prometheus::{
+    Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, Opts, Registry, TextEncoder,
+};
 use std::sync::Arc;
 
 /// Central Prometheus metrics collector.
@@ -54,6 +56,12 @@ pub struct MetricsCollector {
     // ── Ordering (Phase 12.2.1) ───────────────────────────────────────────────
     /// Total ordered blocks cut by the ordering service.
     pub ordering_blocks_cut_total: IntCounter,
+    /// Histogram of transaction payload bytes per cut block.
+    pub ordering_block_size_bytes: Histogram,
+    /// Transactions rejected at submit for exceeding the absolute block size.
+    pub ordering_txs_oversized_total: IntCounter,
+    /// Transactions refused by the orderer's admission filters, by reason.
+    pub ordering_txs_rejected_total: IntCounterVec,
 
     // ── MVCC (Phase 12.2.1) ───────────────────────────────────────────────────
     #[allow(dead_code)]
@@ -228,6 +236,41 @@ impl MetricsCollector {
             .register(Box::new(ordering_blocks_cut_total.clone()))
             .expect("register failed");
 
+        let ordering_block_size_bytes = Histogram::with_opts(
+            HistogramOpts::new(
+                "ordering_block_size_bytes",
+                "Transaction payload bytes per cut block",
+            )
+            .buckets(vec![
+                1024.0, 16384.0, 65536.0, 262144.0, 524288.0, 1048576.0, 2097152.0, 4194304.0,
+            ]),
+        )
+        .expect("metric creation failed");
+        registry
+            .register(Box::new(ordering_block_size_bytes.clone()))
+            .expect("register failed");
+
+        let ordering_txs_oversized_total = IntCounter::with_opts(Opts::new(
+            "ordering_txs_oversized_total",
+            "Transactions rejected for exceeding the absolute block size",
+        ))
+        .expect("metric creation failed");
+        registry
+            .register(Box::new(ordering_txs_oversized_total.clone()))
+            .expect("register failed");
+
+        let ordering_txs_rejected_total = IntCounterVec::new(
+            Opts::new(
+                "ordering_txs_rejected_total",
+                "Transactions refused by orderer admission control",
+            ),
+            &["reason"],
+        )
+        .expect("metric creation failed");
+        registry
+            .register(Box::new(ordering_txs_rejected_total.clone()))
+            .expect("register failed");
+
         // ── MVCC ──────────────────────────────────────────────────────────────
         let mvcc_conflicts_total = IntCounter::with_opts(Opts::new(
             "mvcc_conflicts_total",
@@ -276,6 +319,9 @@ impl MetricsCollector {
             gossip_blocks_gossiped,
             endorsement_validations_total,
             ordering_blocks_cut_total,
+            ordering_block_size_bytes,
+            ordering_txs_oversized_total,
+            ordering_txs_rejected_total,
             mvcc_conflicts_total,
             event_subscriptions_active,
             discovery_peers_registered,
@@ -364,6 +410,25 @@ impl MetricsCollector {
         self.ordering_blocks_cut_total.inc();
     }
 
+    /// Observe the transaction payload bytes of a cut block.
+    pub fn record_ordering_block_size(&self, bytes: usize) {
+        self.ordering_block_size_bytes.observe(bytes as f64);
+    }
+
+    /// Increment when a transaction is rejected for exceeding the absolute
+    /// block size.
+    pub fn record_ordering_tx_oversized(&self) {
+        self.ordering_txs_oversized_total.inc();
+    }
+
+    /// Increment when admission control refuses a transaction; `reason` is
+    /// the filter's short label (e.g. `duplicate`, `forbidden`).
+    pub fn record_ordering_tx_rejected(&self, reason: &str) {
+        self.ordering_txs_rejected_total
+            .with_label_values(&[reason])
+            .inc();
+    }
+
     // ── MVCC helpers ──────────────────────────────────────────────────────────
 
     #[allow(dead_code)]
@@ -462,6 +527,29 @@ mod tests {
         assert!(output.contains("ordering_blocks_cut_total 2"));
     }
 
+    #[test]
+    fn ordering_block_size_histogram_and_oversized_counter() {
+        let m = MetricsCollector::new();
+        m.record_ordering_block_size(2048);
+        m.record_ordering_block_size(700_000);
+        m.record_ordering_tx_oversized();
+        let output = m.collect_metrics();
+        assert!(output.contains("ordering_block_size_bytes_count 2"));
+        assert!(output.contains("ordering_block_size_bytes_sum 702048"));
+        assert!(output.contains("ordering_txs_oversized_total 1"));
+    }
+
+    #[test]
+    fn ordering_rejections_are_counted_by_reason() {
+        let m = MetricsCollector::new();
+        m.record_ordering_tx_rejected("duplicate");
+        m.record_ordering_tx_rejected("duplicate");
+        m.record_ordering_tx_rejected("forbidden");
+        let output = m.collect_metrics();
+        assert!(output.contains("ordering_txs_rejected_total{reason=\"duplicate\"} 2"));
+        assert!(output.contains("ordering_txs_rejected_total{reason=\"forbidden\"} 1"));
+    }
+
     #[test]
     fn mvcc_conflicts_counter() {
         let m = MetricsCollector::new();
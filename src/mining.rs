@@ -127,6 +127,13 @@ impl MiningService {
             secondary_signature_algorithm: None,
             hash_algorithm: HashAlgorithm::default(),
             orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: Vec::new(),
+            config_tx: None,
+            last_config: 0,
+            state_root: [0u8; 32],
+            private_data_purges: vec![],
         };
 
         // Write block and transactions
@@ -9,6 +9,17 @@ type RaftNodeHandle = Option<Arc<Mutex<crate::ordering::raft_node::RaftNode>>>;
 #[cfg(not(feature = "raft-ordering"))]
 type RaftNodeHandle = Option<Arc<()>>; // Arc<()> is not Copy, so .clone() is valid
 
+// BFT ordering service handle (HotStuff validators only).
+type BftNodeHandle = Option<Arc<crate::ordering::bft_service::BftOrderingService>>;
+// Channel-scoped ordering groups hosted by this orderer.
+type OrderingGroupsHandle = Option<Arc<crate::ordering::groups::OrderingGroups>>;
+// Validates config blocks and checks `last_config` before ordered blocks commit.
+type ConfigValidatorHandle = Option<crate::channel::ledger::ConfigValidator>;
+// Verifies block signatures in parallel before ordered blocks commit.
+type BlockPreverifierHandle = Option<Arc<crate::ordering::preverify::BlockPreverifier>>;
+// Applies the private data purges of committed blocks.
+type PurgeCommitterHandle = Option<crate::private_data::PurgeCommitter>;
+
 // Standard library
 use std::collections::{HashMap, HashSet};
 use std::net::SocketAddr;
@@ -32,8 +43,8 @@ use crate::transaction_validation::TransactionValidator;
 
 // ── Configurable buffer sizes (env var override) ─────────────────────────────
 
-/// Buffer for `send_and_wait` P2P responses.  Default 256 KB.
-fn p2p_response_buffer_size() -> usize {
+/// Buffer for `send_and_wait` P2P responses.  Default 4 MB.
+pub(crate) fn p2p_response_buffer_size() -> usize {
     std::env::var("P2P_RESPONSE_BUFFER_BYTES")
         .ok()
         .and_then(|v| v.parse().ok())
@@ -56,6 +67,13 @@ fn p2p_sync_buffer_size() -> usize {
         .unwrap_or(4 * 1024 * 1024)
 }
 
+/// Directory bootstrap snapshots are served from (`SNAPSHOT_DIR`).
+fn snapshot_base_dir() -> std::path::PathBuf {
+    std::env::var("SNAPSHOT_DIR")
+        .unwrap_or_else(|_| "./data".to_string())
+        .into()
+}
+
 /// Abstracts over plain TCP and TLS peer streams.
 trait AsyncStream: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static {}
 impl<T: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static> AsyncStream for T {}
@@ -91,6 +109,8 @@ pub enum Message {
     UpdateContract(SmartContract),
     /// Peer sends an endorsed transaction to the orderer.
     SubmitTransaction(crate::storage::traits::Transaction),
+    /// Peer sends a channel config transaction to the orderer.
+    SubmitConfigTransaction(crate::channel::config::ConfigTransaction),
     /// Orderer broadcasts an ordered block to peers.
     OrderedBlock(crate::storage::traits::Block),
     /// Raft consensus message (protobuf-serialized via prost).
@@ -105,6 +125,25 @@ pub enum Message {
     StateResponse {
         blocks: Vec<crate::storage::traits::Block>,
     },
+    /// Snapshot bootstrap: ask for the latest bootstrap snapshot of a channel.
+    SnapshotManifestRequest {
+        channel_id: String,
+    },
+    /// Snapshot bootstrap: the manifest, or `None` when the peer has none.
+    SnapshotManifestResponse {
+        manifest: Option<Box<crate::storage::bootstrap::SnapshotManifest>>,
+    },
+    /// Snapshot bootstrap: ask for one chunk of the snapshot at `height`.
+    SnapshotChunkRequest {
+        channel_id: String,
+        height: u64,
+        index: u32,
+    },
+    /// Snapshot bootstrap: compressed chunk bytes, checked against the manifest.
+    SnapshotChunkResponse {
+        index: u32,
+        data: Vec<u8>,
+    },
     /// Endorsement request: peer simulates chaincode and returns a signed rwset.
     ProposalRequest {
         /// Unique ID to correlate request with response.
@@ -158,12 +197,17 @@ pub enum Message {
         leader_id: String,
         /// Serialized block data (the full proposed block).
         block_data: Vec<u8>,
+        /// Highest Prepare QC the leader knows; the proposal re-proposes its
+        /// block. `None` for a fresh block.
+        #[serde(default)]
+        justify: Option<crate::consensus::bft::types::QuorumCertificate>,
     },
     /// Validator vote for a BFT phase (Prepare / PreCommit / Commit).
     BftVote(crate::consensus::bft::types::VoteMessage),
     /// A formed quorum certificate broadcast to peers.
     BftQuorumCertificate(crate::consensus::bft::types::QuorumCertificate),
     /// View change: validator signals round timeout and proposes advancing.
+    /// `2f + 1` of these for a round form a timeout certificate.
     BftViewChange {
         /// The round that timed out.
         timed_out_round: u64,
@@ -171,8 +215,26 @@ pub enum Message {
         new_round: u64,
         /// Validator sending the view change.
         voter_id: String,
-        /// Highest commit QC this validator has seen (proof of progress).
+        /// Highest Prepare QC this validator has seen for the height in
+        /// progress (justifies the next leader's proposal).
         highest_qc: Option<crate::consensus::bft::types::QuorumCertificate>,
+        /// Signature over the timeout payload (see `TimeoutMessage`).
+        #[serde(default)]
+        signature: Vec<u8>,
+    },
+    /// Timeout certificate letting lagging validators enter the next round.
+    BftTimeoutCertificate(crate::consensus::bft::types::TimeoutCertificate),
+    /// Signed evidence that a validator equivocated, for inclusion in a block.
+    BftEvidence(crate::consensus::equivocation::SignedEvidence),
+    /// A transaction gossiped by the validator that admitted it.
+    BftTransaction(crate::ordering::bft_node::GossipedTransaction),
+
+    // ── Channel-scoped ordering ─────────────────────────────────────────────
+    /// A Raft or BFT message of one channel's ordering group, delivered to
+    /// that group instead of the node-wide orderer.
+    ChannelOrdering {
+        channel_id: String,
+        message: Box<Message>,
     },
 }
 
@@ -236,10 +298,20 @@ pub struct Node {
     pub signing_provider: Option<Arc<dyn crate::identity::signing::SigningProvider>>,
     /// Raft node for delivering inbound consensus messages.
     pub raft_node: RaftNodeHandle,
+    /// BFT ordering service for delivering inbound HotStuff messages.
+    pub bft_node: BftNodeHandle,
+    /// Per-channel ordering groups for delivering `ChannelOrdering` messages.
+    pub ordering_groups: OrderingGroupsHandle,
+    /// Config-block validator applied to ordered blocks before they commit.
+    pub config_validator: ConfigValidatorHandle,
+    /// Signature pre-verification applied to ordered blocks before they commit.
+    pub block_preverifier: BlockPreverifierHandle,
     /// Private data store for receiving replicated private data from peers.
     pub private_data_store: Option<Arc<dyn crate::private_data::PrivateDataStore>>,
     /// Collection registry for validating membership on private data push.
     pub collection_registry: Option<Arc<dyn crate::private_data::CollectionRegistry>>,
+    /// Private data purges applied as ordered blocks commit.
+    pub purge_committer: PurgeCommitterHandle,
     #[allow(dead_code)]
     /// Monotonically increasing alive sequence counter.
     pub alive_sequence: Arc<Mutex<u64>>,
@@ -296,6 +368,42 @@ async fn open_peer_stream(
     }
 }
 
+/// Write an ordered block to the peer ledger. With a pre-verifier, its
+/// signatures are checked first. With a config validator, a config block is
+/// validated against the ledger and every block's `last_config` pointer is
+/// checked. Blocks failing either check are dropped. Once written, the
+/// block's private data purges are applied.
+fn commit_ordered_block(
+    store: &dyn crate::storage::traits::BlockStore,
+    validator: Option<&crate::channel::ledger::ConfigValidator>,
+    preverifier: Option<&crate::ordering::preverify::BlockPreverifier>,
+    purges: Option<&crate::private_data::PurgeCommitter>,
+    block: crate::storage::traits::Block,
+) -> bool {
+    if let Some(preverifier) = preverifier {
+        if let Err(e) = preverifier.preverify(&block) {
+            log::warn!("Ordered block {} rejected: {e}", block.height);
+            return false;
+        }
+    }
+    if let Some(validator) = validator {
+        if let Err(e) = validator.prepare_commit(store, &block) {
+            log::warn!("Ordered block {} rejected: {e}", block.height);
+            return false;
+        }
+    }
+    if store.write_block(&block).is_err() {
+        return false;
+    }
+    if let Some(preverifier) = preverifier {
+        preverifier.observe_committed(&block);
+    }
+    if let Some(purges) = purges {
+        purges.commit(&block);
+    }
+    true
+}
+
 /// Background task that re-gossips accepted blocks to up to `GOSSIP_FANOUT` random peers,
 /// excluding the peer that originally sent the block.
 async fn gossip_loop(
@@ -435,8 +543,13 @@ impl Node {
             world_state: None,
             signing_provider: None,
             raft_node: None,
+            bft_node: None,
+            ordering_groups: None,
+            config_validator: None,
+            block_preverifier: None,
             private_data_store: None,
             collection_registry: None,
+            purge_committer: None,
         }
     }
 
@@ -520,27 +633,37 @@ impl Node {
             .await
             .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?;
 
-        // Read response with timeout
-        let mut buf = vec![0u8; p2p_response_buffer_size()];
-        let n = tokio::time::timeout(timeout, stream.read(&mut buf))
-            .await
-            .map_err(|_| -> Box<dyn std::error::Error + Send + Sync> {
-                Box::new(std::io::Error::new(
-                    std::io::ErrorKind::TimedOut,
-                    format!("no response from {peer_address} within {timeout:?}"),
-                ))
-            })?
-            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?;
-
-        if n == 0 {
-            return Err(Box::new(std::io::Error::new(
-                std::io::ErrorKind::UnexpectedEof,
-                format!("peer {peer_address} closed connection without responding"),
-            )));
-        }
-
-        let response: Message = serde_json::from_slice(&buf[..n])
-            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?;
+        // Read the response with timeout. Large responses (snapshot chunks)
+        // arrive over several reads; keep reading until the JSON is complete.
+        let deadline = tokio::time::Instant::now() + timeout;
+        let limit = p2p_response_buffer_size();
+        let mut buf = Vec::new();
+        let mut read_buf = vec![0u8; 64 * 1024];
+        let response: Message = loop {
+            let n = tokio::time::timeout_at(deadline, stream.read(&mut read_buf))
+                .await
+                .map_err(|_| -> Box<dyn std::error::Error + Send + Sync> {
+                    Box::new(std::io::Error::new(
+                        std::io::ErrorKind::TimedOut,
+                        format!("no response from {peer_address} within {timeout:?}"),
+                    ))
+                })?
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?;
+
+            if n == 0 && buf.is_empty() {
+                return Err(Box::new(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    format!("peer {peer_address} closed connection without responding"),
+                )));
+            }
+            buf.extend_from_slice(&read_buf[..n]);
+
+            match serde_json::from_slice(&buf) {
+                Ok(message) => break message,
+                Err(e) if e.is_eof() && n > 0 && buf.len() < limit => continue,
+                Err(e) => return Err(Box::new(e)),
+            }
+        };
 
         Ok(response)
     }
@@ -578,8 +701,13 @@ impl Node {
         let signing_provider = self.signing_provider.clone();
         let node_org_id = self.org_id.clone();
         let raft_node = self.raft_node.clone();
+        let bft_node = self.bft_node.clone();
+        let ordering_groups = self.ordering_groups.clone();
+        let config_validator = self.config_validator.clone();
+        let block_preverifier = self.block_preverifier.clone();
         let private_data_store = self.private_data_store.clone();
         let collection_registry = self.collection_registry.clone();
+        let purge_committer = self.purge_committer.clone();
         let net_security = self.network_security.clone();
 
         // Push-gossip channel: newly accepted blocks are sent here and forwarded
@@ -629,8 +757,13 @@ impl Node {
                     let signing_provider_clone = signing_provider.clone();
                     let node_org_id_clone = node_org_id.clone();
                     let raft_node_clone = raft_node.clone();
+                    let bft_node_clone = bft_node.clone();
+                    let ordering_groups_clone = ordering_groups.clone();
+                    let config_validator_clone = config_validator.clone();
+                    let block_preverifier_clone = block_preverifier.clone();
                     let private_data_store_clone = private_data_store.clone();
                     let collection_registry_clone = collection_registry.clone();
+                    let purge_committer_clone = purge_committer.clone();
                     let net_security_clone = net_security.clone();
 
                     tokio::spawn(async move {
@@ -671,8 +804,13 @@ impl Node {
                             signing_provider_clone,
                             node_org_id_clone,
                             raft_node_clone,
+                            bft_node_clone,
+                            ordering_groups_clone,
+                            config_validator_clone,
+                            block_preverifier_clone,
                             private_data_store_clone,
                             collection_registry_clone,
+                            purge_committer_clone,
                             net_security_clone,
                         )
                         .await
@@ -717,8 +855,13 @@ impl Node {
         signing_provider: Option<Arc<dyn crate::identity::signing::SigningProvider>>,
         node_org_id: String,
         raft_node: RaftNodeHandle,
+        bft_node: BftNodeHandle,
+        ordering_groups: OrderingGroupsHandle,
+        config_validator: ConfigValidatorHandle,
+        block_preverifier: BlockPreverifierHandle,
         private_data_store: Option<Arc<dyn crate::private_data::PrivateDataStore>>,
         collection_registry: Option<Arc<dyn crate::private_data::CollectionRegistry>>,
+        purge_committer: PurgeCommitterHandle,
         net_security: Arc<Mutex<NetworkSecurityManager>>,
     ) -> Result<(), Box<dyn std::error::Error>> {
         let peer_addr_str = format!("{}:{}", peer_addr.ip(), peer_addr.port());
@@ -833,8 +976,13 @@ impl Node {
                     signing_provider.clone(),
                     &node_org_id,
                     raft_node.clone(),
+                    bft_node.clone(),
+                    ordering_groups.clone(),
+                    config_validator.clone(),
+                    block_preverifier.clone(),
                     private_data_store.clone(),
                     collection_registry.clone(),
+                    purge_committer.clone(),
                 )
                 .await?;
 
@@ -898,8 +1046,13 @@ impl Node {
         signing_provider: Option<Arc<dyn crate::identity::signing::SigningProvider>>,
         node_org_id: &str,
         _raft_node: RaftNodeHandle,
+        bft_node: BftNodeHandle,
+        ordering_groups: OrderingGroupsHandle,
+        config_validator: ConfigValidatorHandle,
+        block_preverifier: BlockPreverifierHandle,
         private_data_store: Option<Arc<dyn crate::private_data::PrivateDataStore>>,
         collection_registry: Option<Arc<dyn crate::private_data::CollectionRegistry>>,
+        purge_committer: PurgeCommitterHandle,
     ) -> Result<Option<Message>, Box<dyn std::error::Error>> {
         match message {
             Message::Ping => Ok(Some(Message::Pong)),
@@ -1453,19 +1606,47 @@ impl Node {
 
             Message::SubmitTransaction(tx) => {
                 if matches!(role, NodeRole::Orderer | NodeRole::PeerAndOrderer) {
-                    if let Some(svc) = &ordering_service {
+                    // BFT validators share a mempool: the local validator
+                    // admits the tx and gossips it, signed, to the others.
+                    if let Some(bft) = &bft_node {
+                        if let Err(e) = bft.submit_tx(&tx) {
+                            log::warn!("BFT: transaction {} refused: {e}", tx.id);
+                        }
+                    } else if let Some(svc) = &ordering_service {
                         let _ = svc.submit_tx(tx);
                     }
                 }
                 Ok(None)
             }
 
+            Message::SubmitConfigTransaction(tx) => {
+                if matches!(role, NodeRole::Orderer | NodeRole::PeerAndOrderer) {
+                    if let Some(bft) = &bft_node {
+                        bft.handle_message(Message::SubmitConfigTransaction(tx));
+                    } else if let Some(svc) = &ordering_service {
+                        let _ = svc.submit_config_tx(tx);
+                    }
+                }
+                Ok(None)
+            }
+
             Message::OrderedBlock(block) => {
                 if matches!(role, NodeRole::Peer | NodeRole::PeerAndOrderer) {
                     if let Some(s) = &store {
-                        let _ = s.write_block(&block);
+                        commit_ordered_block(
+                            s.as_ref(),
+                            config_validator.as_ref(),
+                            block_preverifier.as_deref(),
+                            purge_committer.as_ref(),
+                            block.clone(),
+                        );
                     }
                 }
+                // A validator that missed a decision continues from the
+                // committed block (checked against its commit QC).
+                if let Some(bft) = &bft_node {
+                    bft.observe_block(&block);
+                }
                 Ok(None)
             }
 
@@ -1536,6 +1717,32 @@ impl Node {
                 Ok(None)
             }
 
+            Message::SnapshotManifestRequest { channel_id } => {
+                let manifest =
+                    crate::storage::bootstrap::latest_manifest(&snapshot_base_dir(), &channel_id);
+                Ok(Some(Message::SnapshotManifestResponse {
+                    manifest: manifest.map(Box::new),
+                }))
+            }
+
+            Message::SnapshotChunkRequest {
+                channel_id,
+                height,
+                index,
+            } => Ok(crate::storage::bootstrap::read_chunk(
+                &snapshot_base_dir(),
+                &channel_id,
+                height,
+                index,
+            )
+            .ok()
+            .map(|data| Message::SnapshotChunkResponse { index, data })),
+
+            Message::SnapshotManifestResponse { .. } | Message::SnapshotChunkResponse { .. } => {
+                // Read directly by join_from_snapshot() on the request stream.
+                Ok(None)
+            }
+
             Message::ProposalRequest {
                 request_id,
                 chaincode_id,
@@ -1616,7 +1823,7 @@ impl Node {
 
                 // 4. Build endorsement
                 let pub_key = signer.public_key();
-                let signer_did = format!("did:key:{}", hex::encode(&pub_key));
+                let signer_did = crate::identity::did::DidDocument::did_key(&pub_key);
                 let timestamp = std::time::SystemTime::now()
                     .duration_since(std::time::UNIX_EPOCH)
                     .unwrap_or_default()
@@ -1658,7 +1865,17 @@ impl Node {
                 {
                     if let Some(col) = reg.get(&collection) {
                         if col.is_member(&sender_org) {
-                            match pd_store.put_private_data(&collection, &key, &value) {
+                            let height = store
+                                .as_ref()
+                                .and_then(|s| s.get_latest_height().ok())
+                                .unwrap_or(0);
+                            match pd_store.put_private_data_at(
+                                &collection,
+                                &key,
+                                &value,
+                                height,
+                                col.blocks_to_live,
+                            ) {
                                 Ok(_hash) => true,
                                 Err(e) => {
                                     eprintln!(
@@ -1697,15 +1914,34 @@ impl Node {
                 Ok(None)
             }
 
-            // BFT consensus messages — handled by the BFT round manager
-            // outside of the generic message handler. Log and ignore here.
-            Message::BftProposal { .. }
+            // BFT consensus messages — delivered to the local validator.
+            // Replies travel as separate broadcasts from the BFT loop.
+            msg @ (Message::BftProposal { .. }
             | Message::BftVote(_)
             | Message::BftQuorumCertificate(_)
-            | Message::BftViewChange { .. } => {
-                log::debug!(
-                    "BFT message received in generic handler — ignored (handled by BFT subsystem)"
-                );
+            | Message::BftViewChange { .. }
+            | Message::BftTimeoutCertificate(_)
+            | Message::BftEvidence(_)
+            | Message::BftTransaction(_)) => {
+                match &bft_node {
+                    Some(bft) => {
+                        bft.handle_message(msg);
+                    }
+                    None => log::debug!("BFT message received but BFT ordering is not enabled"),
+                }
+                Ok(None)
+            }
+
+            Message::ChannelOrdering {
+                channel_id,
+                message,
+            } => {
+                let delivered = ordering_groups
+                    .as_ref()
+                    .is_some_and(|groups| groups.deliver(&channel_id, *message));
+                if !delivered {
+                    log::debug!("ordering message for channel '{channel_id}' has no local group");
+                }
                 Ok(None)
             }
         }
@@ -3004,61 +3240,206 @@ impl Node {
                 tokio::time::interval(tokio::time::Duration::from_millis(pull_interval_ms));
             loop {
                 interval.tick().await;
+                node.pull_missing_blocks().await;
+            }
+        })
+    }
 
-                let store = match &node.store {
-                    Some(s) => s.clone(),
-                    None => continue,
-                };
+    /// Ask each peer once for the blocks above the local height and write
+    /// them to the store. Returns the number of blocks written.
+    pub async fn pull_missing_blocks(&self) -> usize {
+        let store = match &self.store {
+            Some(s) => s.clone(),
+            None => return 0,
+        };
 
-                // Determine local height.
-                let local_height = store.get_latest_height().unwrap_or(0);
+        let peers: Vec<String> = {
+            let g = self.peers.lock().unwrap_or_else(|e| e.into_inner());
+            g.iter().cloned().collect()
+        };
 
-                let peers: Vec<String> = {
-                    let g = node.peers.lock().unwrap_or_else(|e| e.into_inner());
-                    g.iter().cloned().collect()
-                };
+        let mut written = 0;
+        for peer_addr in &peers {
+            // Determine local height (may have advanced with the previous peer).
+            let local_height = store.get_latest_height().unwrap_or(0);
 
-                for peer_addr in &peers {
-                    // Send StateRequest.
-                    let req = Message::StateRequest {
-                        from_height: local_height + 1,
-                    };
-                    let Ok(json) = serde_json::to_string(&req) else {
-                        continue;
-                    };
+            // Send StateRequest.
+            let req = Message::StateRequest {
+                from_height: local_height + 1,
+            };
+            let Ok(json) = serde_json::to_string(&req) else {
+                continue;
+            };
 
-                    let mut stream = match node.open_stream(peer_addr).await {
-                        Ok(s) => s,
-                        Err(_) => continue,
-                    };
+            let mut stream = match self.open_stream(peer_addr).await {
+                Ok(s) => s,
+                Err(_) => continue,
+            };
 
-                    if tokio::io::AsyncWriteExt::write_all(&mut stream, json.as_bytes())
-                        .await
-                        .is_err()
-                    {
-                        continue;
-                    }
+            if tokio::io::AsyncWriteExt::write_all(&mut stream, json.as_bytes())
+                .await
+                .is_err()
+            {
+                continue;
+            }
 
-                    // Read response.
-                    let mut buf = vec![0u8; p2p_sync_buffer_size()];
-                    let n = match tokio::io::AsyncReadExt::read(&mut stream, &mut buf).await {
-                        Ok(n) if n > 0 => n,
-                        _ => continue,
-                    };
+            // Read response.
+            let mut buf = vec![0u8; p2p_sync_buffer_size()];
+            let n = match tokio::io::AsyncReadExt::read(&mut stream, &mut buf).await {
+                Ok(n) if n > 0 => n,
+                _ => continue,
+            };
 
-                    let resp: Message = match serde_json::from_slice(&buf[..n]) {
-                        Ok(m) => m,
-                        Err(_) => continue,
-                    };
+            let resp: Message = match serde_json::from_slice(&buf[..n]) {
+                Ok(m) => m,
+                Err(_) => continue,
+            };
 
-                    if let Message::StateResponse { blocks } = resp {
-                        for block in blocks.into_iter().take(gossip::STATE_BATCH_SIZE) {
-                            let _ = store.write_block(&block);
-                        }
+            if let Message::StateResponse { blocks } = resp {
+                let blocks: Vec<crate::storage::traits::Block> =
+                    blocks.into_iter().take(gossip::STATE_BATCH_SIZE).collect();
+                // Verify the signatures of the whole batch in parallel up
+                // front; each commit below then finds them in the cache. A
+                // block failing here is checked again on its own, after any
+                // key rotation committed earlier in the batch.
+                if let Some(p) = &self.block_preverifier {
+                    p.preverify_all(&blocks);
+                }
+                for block in blocks {
+                    if commit_ordered_block(
+                        store.as_ref(),
+                        self.config_validator.as_ref(),
+                        self.block_preverifier.as_deref(),
+                        self.purge_committer.as_ref(),
+                        block,
+                    ) {
+                        written += 1;
                     }
                 }
             }
-        })
+        }
+        written
+    }
+
+    /// Join the network from a bootstrap snapshot instead of replaying the
+    /// ledger.
+    ///
+    /// Asks every peer for its latest snapshot of `channel_id`, keeps the
+    /// manifests whose org signatures satisfy `policy`, and downloads the
+    /// chunks of the highest one from the peers offering it. The state is
+    /// installed into `state` only once every chunk matches its manifest
+    /// hash and the rebuilt tree matches the `state_root` of block `H`.
+    /// Returns `H`; pull sync then continues from `H + 1`.
+    pub async fn join_from_snapshot(
+        &self,
+        channel_id: &str,
+        policy: &crate::endorsement::policy::EndorsementPolicy,
+        registry: &dyn crate::endorsement::registry::OrgRegistry,
+        crl_store: Option<&dyn crate::msp::CrlStore>,
+        state: &crate::storage::state_tree::AuthenticatedWorldState,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        use crate::storage::bootstrap::{decode_chunk, install_snapshot, SnapshotManifest};
+
+        let store = self.store.clone().ok_or("no block store configured")?;
+        let peers: Vec<String> = {
+            let g = self.peers.lock().unwrap_or_else(|e| e.into_inner());
+            g.iter().cloned().collect()
+        };
+        let timeout = std::time::Duration::from_secs(30);
+
+        // Trusted manifests, and the peers offering each snapshot hash.
+        let mut offers: Vec<(SnapshotManifest, Vec<String>)> = Vec::new();
+        for peer in &peers {
+            let request = Message::SnapshotManifestRequest {
+                channel_id: channel_id.to_string(),
+            };
+            let Ok(Message::SnapshotManifestResponse {
+                manifest: Some(manifest),
+            }) = self.send_and_wait(peer, request, timeout).await
+            else {
+                continue;
+            };
+            if manifest.channel_id != channel_id {
+                continue;
+            }
+            if let Err(e) = manifest.verify() {
+                log::warn!("Snapshot manifest from {peer} rejected: {e}");
+                continue;
+            }
+            if let Err(e) = manifest.check_signatures(policy, registry, crl_store) {
+                log::warn!("Snapshot manifest from {peer} rejected: {e}");
+                continue;
+            }
+            let hash = manifest.snapshot_hash();
+            match offers.iter_mut().find(|(m, _)| m.snapshot_hash() == hash) {
+                Some((_, sources)) => sources.push(peer.clone()),
+                None => offers.push((*manifest, vec![peer.clone()])),
+            }
+        }
+        let (manifest, sources) = offers
+            .into_iter()
+            .max_by_key(|(m, sources)| (m.height, sources.len()))
+            .ok_or_else(|| format!("no peer offers a trusted snapshot of '{channel_id}'"))?;
+
+        // Spread the chunks over the sources; fall back to the others when
+        // one fails or serves bytes that do not match the manifest.
+        let mut chunks = Vec::with_capacity(manifest.chunks.len());
+        for info in &manifest.chunks {
+            let mut fetched = None;
+            for offset in 0..sources.len() {
+                let peer = &sources[(info.index as usize + offset) % sources.len()];
+                let request = Message::SnapshotChunkRequest {
+                    channel_id: channel_id.to_string(),
+                    height: manifest.height,
+                    index: info.index,
+                };
+                if let Ok(Message::SnapshotChunkResponse { index, data }) =
+                    self.send_and_wait(peer, request, timeout).await
+                {
+                    if index == info.index && decode_chunk(&manifest, index, &data).is_ok() {
+                        fetched = Some(data);
+                        break;
+                    }
+                    log::warn!("Snapshot chunk {} from {peer} rejected", info.index);
+                }
+            }
+            chunks.push(
+                fetched.ok_or_else(|| {
+                    format!("no peer served a valid snapshot chunk {}", info.index)
+                })?,
+            );
+        }
+
+        install_snapshot(&manifest, &chunks, store.as_ref(), state)?;
+        if let Some(p) = &self.block_preverifier {
+            for block in manifest.config_blocks.iter().chain([&manifest.header]) {
+                p.observe_committed(block);
+            }
+        }
+        log::info!(
+            "Joined channel '{channel_id}' from a snapshot at height {} ({} entries)",
+            manifest.height,
+            manifest.entry_count
+        );
+        Ok(manifest.height)
+    }
+
+    /// Pull blocks from peers until the local ledger reaches `target_height`
+    /// or a full pass over the peers makes no progress.
+    ///
+    /// Used by an orderer that installed a Raft snapshot pointing at a block
+    /// height it has not reached: the blocks come from peers instead of a
+    /// replay of the compacted Raft log. Returns the height reached.
+    pub async fn sync_blocks_to(&self, target_height: u64) -> u64 {
+        let Some(store) = self.store.clone() else {
+            return 0;
+        };
+        loop {
+            let height = store.get_latest_height().unwrap_or(0);
+            if height >= target_height || self.pull_missing_blocks().await == 0 {
+                return store.get_latest_height().unwrap_or(0);
+            }
+        }
     }
 }
 
@@ -3241,8 +3622,13 @@ mod tests {
             None,      // signing_provider
             "default", // node_org_id
             None,      // raft_node
+            None,      // bft_node
+            None,      // ordering_groups
+            None,      // config_validator
+            None,      // block_preverifier
             None,      // private_data_store
             None,      // collection_registry
+            None,      // purge_committer
         )
         .await
         .unwrap();
@@ -3272,6 +3658,13 @@ mod tests {
             secondary_signature_algorithm: None,
             hash_algorithm: Default::default(),
             orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: Vec::new(),
+            config_tx: None,
+            last_config: 0,
+            state_root: [0u8; 32],
+            private_data_purges: vec![],
         };
 
         Node::process_message(
@@ -3298,8 +3691,13 @@ mod tests {
             None,      // signing_provider
             "default", // node_org_id
             None,      // raft_node
+            None,      // bft_node
+            None,      // ordering_groups
+            None,      // config_validator
+            None,      // block_preverifier
             None,      // private_data_store
             None,      // collection_registry
+            None,      // purge_committer
         )
         .await
         .unwrap();
@@ -3362,8 +3760,13 @@ mod tests {
             None,      // signing_provider
             "default", // node_org_id
             None,      // raft_node
+            None,      // bft_node
+            None,      // ordering_groups
+            None,      // config_validator
+            None,      // block_preverifier
             None,      // private_data_store
             None,      // collection_registry
+            None,      // purge_committer
         )
         .await
         .unwrap();
@@ -3391,6 +3794,13 @@ mod tests {
             secondary_signature_algorithm: None,
             hash_algorithm: Default::default(),
             orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: Vec::new(),
+            config_tx: None,
+            last_config: 0,
+            state_root: [0u8; 32],
+            private_data_purges: vec![],
         };
         let msg = Message::OrderedBlock(block);
         let json = serde_json::to_string(&msg).unwrap();
@@ -3444,6 +3854,13 @@ mod tests {
             secondary_signature_algorithm: None,
             hash_algorithm: Default::default(),
             orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: Vec::new(),
+            config_tx: None,
+            last_config: 0,
+            state_root: [0u8; 32],
+            private_data_purges: vec![],
         };
         let msg = Message::StateResponse {
             blocks: vec![block],
@@ -3458,4 +3875,80 @@ mod tests {
             panic!("expected StateResponse");
         }
     }
+
+    #[test]
+    fn snapshot_chunk_messages_serde_roundtrip() {
+        let msg = Message::SnapshotChunkRequest {
+            channel_id: "ch1".to_string(),
+            height: 9,
+            index: 3,
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        let decoded: Message = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            decoded,
+            Message::SnapshotChunkRequest {
+                height: 9,
+                index: 3,
+                ..
+            }
+        ));
+
+        let msg = Message::SnapshotManifestResponse { manifest: None };
+        let json = serde_json::to_string(&msg).unwrap();
+        let decoded: Message = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            decoded,
+            Message::SnapshotManifestResponse { manifest: None }
+        ));
+    }
+
+    #[tokio::test]
+    async fn send_and_wait_reads_a_response_split_over_several_writes() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let response = serde_json::to_vec(&Message::SnapshotChunkResponse {
+            index: 1,
+            data: vec![7u8; 200_000],
+        })
+        .unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut req = vec![0u8; 1024];
+            let _ = socket.read(&mut req).await.unwrap();
+            let (head, tail) = response.split_at(response.len() / 2);
+            socket.write_all(head).await.unwrap();
+            socket.flush().await.unwrap();
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            socket.write_all(tail).await.unwrap();
+        });
+
+        let node = Node::new(
+            addr,
+            Arc::new(Mutex::new(crate::blockchain::Blockchain::new(1))),
+            None,
+            None,
+            None,
+            None,
+        );
+        let reply = node
+            .send_and_wait(
+                &addr.to_string(),
+                Message::SnapshotChunkRequest {
+                    channel_id: "ch1".to_string(),
+                    height: 1,
+                    index: 1,
+                },
+                std::time::Duration::from_secs(5),
+            )
+            .await
+            .unwrap();
+        match reply {
+            Message::SnapshotChunkResponse { index, data } => {
+                assert_eq!(index, 1);
+                assert_eq!(data.len(), 200_000);
+            }
+            other => panic!("expected SnapshotChunkResponse, got {other:?}"),
+        }
+    }
 }
@@ -0,0 +1,816 @@
+//! Admission control for transactions submitted to the orderer.
+//!
+//! Every transaction passes an [`AdmissionChain`] before it enters a batch,
+//! so malformed, unsigned, oversized, unauthorised or replayed envelopes are
+//! turned away at submit instead of taking up block space until commit. The
+//! standard chain runs, in order:
+//!
+//! 1. [`WellFormed`] — non-empty, bounded transaction ID outside the
+//!    reserved evidence/config namespaces; endorsed envelopes name a creator.
+//! 2. [`SizeLimit`] — the channel's absolute block size.
+//! 3. [`SignaturePolicy`] — creator and endorsement signatures are present,
+//!    match their declared algorithm, satisfy `REQUIRE_PQC_SIGNATURES` and
+//!    verify: the creator's over its proposal under the key its DID names,
+//!    each endorsement under a root key of its org.
+//! 4. [`WritersAcl`] (when an ACL provider is attached) — the endorsing orgs
+//!    satisfy the policy bound to [`WRITERS_RESOURCE`].
+//! 5. [`ReplayWindow`] — the transaction ID was not admitted within the
+//!    replay window.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use thiserror::Error;
+
+use crate::acl::{check_access, AclError, AclProvider};
+use crate::channel::config::CONFIG_TX_PREFIX;
+use crate::consensus::equivocation::EVIDENCE_TX_PREFIX;
+use crate::endorsement::policy_store::PolicyStore;
+use crate::endorsement::registry::{MemoryOrgRegistry, OrgRegistry};
+use crate::endorsement::types::Endorsement;
+use crate::endorsement::validator::verify_endorsement;
+use crate::identity::did::DidDocument;
+use crate::identity::pqc_policy::{enforce_pqc, validate_signature_consistency};
+use crate::identity::signing::{verify_with_public_key, SigningAlgorithm};
+use crate::ordering::{tx_size_bytes, BlockSizeLimits};
+use crate::private_data::PURGE_TX_PREFIX;
+use crate::storage::errors::StorageError;
+use crate::storage::traits::Transaction;
+use crate::transaction::endorsed::EndorsedTransaction;
+
+/// ACL resource whose policy decides who may submit transactions
+/// ([`crate::acl::resources::AclResource::ChannelWriters`]).
+pub const WRITERS_RESOURCE: &str = "channel/Writers";
+
+/// Longest transaction ID the orderer admits.
+pub const MAX_TX_ID_LEN: usize = 256;
+
+/// Default replay window, in seconds.
+pub const DEFAULT_REPLAY_WINDOW_SECS: u64 = 300;
+
+/// Why a transaction was refused admission.
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum AdmissionError {
+    #[error("malformed transaction: {0}")]
+    Malformed(String),
+    #[error("transaction {tx_id} is {size} bytes, above the {limit}-byte limit")]
+    TooLarge {
+        tx_id: String,
+        size: usize,
+        limit: usize,
+    },
+    #[error("invalid signature: {0}")]
+    InvalidSignature(String),
+    #[error("submitter not allowed to write: {0}")]
+    Forbidden(String),
+    #[error("transaction {0} already submitted within the replay window")]
+    Duplicate(String),
+}
+
+impl AdmissionError {
+    /// Short label used as the `reason` of the rejection metric.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Self::Malformed(_) => "malformed",
+            Self::TooLarge { .. } => "too_large",
+            Self::InvalidSignature(_) => "invalid_signature",
+            Self::Forbidden(_) => "forbidden",
+            Self::Duplicate(_) => "duplicate",
+        }
+    }
+}
+
+impl From<AdmissionError> for StorageError {
+    fn from(e: AdmissionError) -> Self {
+        match e {
+            AdmissionError::TooLarge { tx_id, size, limit } => {
+                StorageError::TransactionTooLarge { tx_id, size, limit }
+            }
+            other => StorageError::AdmissionRejected(other),
+        }
+    }
+}
+
+/// Creator identity and signature of an endorsed envelope.
+#[derive(Debug, Clone, Copy)]
+pub struct Creator<'a> {
+    pub did: &'a str,
+    pub signature: &'a [u8],
+    pub algorithm: SigningAlgorithm,
+    /// What the signature covers:
+    /// [`TransactionProposal::signing_payload`](crate::transaction::proposal::TransactionProposal::signing_payload).
+    pub payload: [u8; 32],
+}
+
+/// A transaction as seen by the admission filters.
+#[derive(Debug, Clone, Copy)]
+pub struct Envelope<'a> {
+    pub tx: &'a Transaction,
+    /// `None` for plain transactions submitted without a proposal.
+    pub creator: Option<Creator<'a>>,
+    pub endorsements: &'a [Endorsement],
+}
+
+impl<'a> Envelope<'a> {
+    /// Envelope of a plain transaction (no creator, no endorsements).
+    pub fn plain(tx: &'a Transaction) -> Self {
+        Self {
+            tx,
+            creator: None,
+            endorsements: &[],
+        }
+    }
+
+    /// Envelope of an endorsed transaction.
+    pub fn endorsed(etx: &'a EndorsedTransaction) -> Self {
+        Self {
+            tx: &etx.proposal.tx,
+            creator: Some(Creator {
+                did: &etx.proposal.creator_did,
+                signature: &etx.proposal.creator_signature,
+                algorithm: etx.proposal.signature_algorithm,
+                payload: etx.proposal.signing_payload(),
+            }),
+            endorsements: &etx.endorsements,
+        }
+    }
+
+    /// Orgs vouching for the transaction: its distinct endorsing orgs.
+    pub fn orgs(&self) -> Vec<&'a str> {
+        let mut orgs: Vec<&str> = self
+            .endorsements
+            .iter()
+            .map(|e| e.org_id.as_str())
+            .collect();
+        orgs.sort_unstable();
+        orgs.dedup();
+        orgs
+    }
+}
+
+/// Per-submission state the filters check against.
+#[derive(Debug, Clone, Copy)]
+pub struct AdmissionContext {
+    /// Current block byte limits of the ordering service.
+    pub limits: BlockSizeLimits,
+    /// Submission time, seconds since the Unix epoch.
+    pub now_secs: u64,
+}
+
+impl AdmissionContext {
+    /// Context for a submission happening now.
+    pub fn now(limits: BlockSizeLimits) -> Self {
+        Self {
+            limits,
+            now_secs: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        }
+    }
+}
+
+/// One admission check.
+pub trait AdmissionFilter: Send + Sync {
+    fn check(&self, envelope: &Envelope<'_>, ctx: &AdmissionContext) -> Result<(), AdmissionError>;
+
+    /// A fresh copy for another channel's chain, for filters that remember
+    /// what they admitted; `None` shares this filter.
+    fn for_channel(&self) -> Option<Arc<dyn AdmissionFilter>> {
+        None
+    }
+}
+
+/// Ordered list of filters; the first rejection wins.
+#[derive(Clone, Default)]
+pub struct AdmissionChain {
+    filters: Vec<Arc<dyn AdmissionFilter>>,
+}
+
+impl AdmissionChain {
+    /// An empty chain admitting everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The standard chain without an ACL check; the replay window comes
+    /// from `ORDERING_REPLAY_WINDOW_SECS` (default 300, `0` disables it).
+    ///
+    /// It knows no signer keys, so only plain transactions get through.
+    pub fn standard() -> Self {
+        Self::standard_chain(Arc::new(MemoryOrgRegistry::new()), None)
+    }
+
+    /// The standard chain verifying signatures against the orgs in
+    /// `org_registry`, with a [`WritersAcl`] check.
+    pub fn standard_with_acl(
+        org_registry: Arc<dyn OrgRegistry>,
+        acl_provider: Arc<dyn AclProvider>,
+        policy_store: Arc<dyn PolicyStore>,
+    ) -> Self {
+        Self::standard_chain(
+            org_registry,
+            Some(WritersAcl::new(acl_provider, policy_store)),
+        )
+    }
+
+    fn standard_chain(org_registry: Arc<dyn OrgRegistry>, acl: Option<WritersAcl>) -> Self {
+        let mut chain = Self::new()
+            .with_filter(WellFormed)
+            .with_filter(SizeLimit)
+            .with_filter(SignaturePolicy::new(org_registry));
+        if let Some(acl) = acl {
+            chain = chain.with_filter(acl);
+        }
+        let window = std::env::var("ORDERING_REPLAY_WINDOW_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_REPLAY_WINDOW_SECS);
+        // Last, so rejected transactions are not remembered as seen.
+        if window > 0 {
+            chain = chain.with_filter(ReplayWindow::new(window));
+        }
+        chain
+    }
+
+    /// Append a filter to the chain.
+    pub fn with_filter(mut self, filter: impl AdmissionFilter + 'static) -> Self {
+        self.filters.push(Arc::new(filter));
+        self
+    }
+
+    /// The same checks for another channel: filters with memory (such as
+    /// the replay window) start empty, the rest are shared.
+    pub fn for_channel(&self) -> Self {
+        Self {
+            filters: self
+                .filters
+                .iter()
+                .map(|filter| filter.for_channel().unwrap_or_else(|| filter.clone()))
+                .collect(),
+        }
+    }
+
+    /// Run every filter in order.
+    pub fn admit(
+        &self,
+        envelope: &Envelope<'_>,
+        ctx: &AdmissionContext,
+    ) -> Result<(), AdmissionError> {
+        self.filters
+            .iter()
+            .try_for_each(|filter| filter.check(envelope, ctx))
+    }
+}
+
+/// Rejects envelopes that cannot be ordered meaningfully.
+pub struct WellFormed;
+
+impl AdmissionFilter for WellFormed {
+    fn check(
+        &self,
+        envelope: &Envelope<'_>,
+        _ctx: &AdmissionContext,
+    ) -> Result<(), AdmissionError> {
+        let id = &envelope.tx.id;
+        if id.trim().is_empty() {
+            return Err(AdmissionError::Malformed("empty transaction id".into()));
+        }
+        if id.len() > MAX_TX_ID_LEN {
+            return Err(AdmissionError::Malformed(format!(
+                "transaction id longer than {MAX_TX_ID_LEN} bytes"
+            )));
+        }
+        if id.chars().any(char::is_control) {
+            return Err(AdmissionError::Malformed(
+                "control characters in transaction id".into(),
+            ));
+        }
+        // Evidence, config and purge transactions only enter blocks through
+        // their own paths.
+        if id.starts_with(EVIDENCE_TX_PREFIX)
+            || id.starts_with(CONFIG_TX_PREFIX)
+            || id.starts_with(PURGE_TX_PREFIX)
+        {
+            return Err(AdmissionError::Malformed(format!(
+                "transaction id {id} uses a reserved prefix"
+            )));
+        }
+        if let Some(creator) = envelope.creator {
+            if creator.did.is_empty() {
+                return Err(AdmissionError::Malformed("empty creator DID".into()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Rejects transactions above the absolute block size.
+pub struct SizeLimit;
+
+impl AdmissionFilter for SizeLimit {
+    fn check(&self, envelope: &Envelope<'_>, ctx: &AdmissionContext) -> Result<(), AdmissionError> {
+        let size = tx_size_bytes(envelope.tx);
+        if size > ctx.limits.absolute_max_bytes {
+            return Err(AdmissionError::TooLarge {
+                tx_id: envelope.tx.id.clone(),
+                size,
+                limit: ctx.limits.absolute_max_bytes,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Applies the node's PQC signature policy to the creator and endorsement
+/// signatures and verifies them. Endorsed envelopes must be signed by their
+/// creator.
+///
+/// An endorsement must verify under a root key of its org. The creator
+/// signs its proposal's signing payload with the key its DID names: the key
+/// carried by a `did:key` DID, or for a `did:bc` DID the registered org root
+/// key it is derived from.
+pub struct SignaturePolicy {
+    org_registry: Arc<dyn OrgRegistry>,
+}
+
+impl SignaturePolicy {
+    pub fn new(org_registry: Arc<dyn OrgRegistry>) -> Self {
+        Self { org_registry }
+    }
+
+    fn check_creator(&self, tx: &Transaction, creator: &Creator<'_>) -> Result<(), AdmissionError> {
+        let verified = match DidDocument::did_key_public_key(creator.did) {
+            Some(pk) => verify_with_public_key(&pk, &creator.payload, creator.signature),
+            None => self
+                .org_registry
+                .list_orgs()
+                .unwrap_or_default()
+                .iter()
+                .flat_map(|org| &org.root_public_keys)
+                .filter(|pk| DidDocument::from_public_key(pk.as_slice()) == creator.did)
+                .any(|pk| verify_with_public_key(pk, &creator.payload, creator.signature)),
+        };
+        if verified {
+            Ok(())
+        } else {
+            Err(AdmissionError::InvalidSignature(format!(
+                "creator signature of transaction {} does not verify for {}",
+                tx.id, creator.did
+            )))
+        }
+    }
+
+    fn check_endorsement(&self, e: &Endorsement) -> Result<(), AdmissionError> {
+        let org = self.org_registry.get_org(&e.org_id).map_err(|_| {
+            AdmissionError::InvalidSignature(format!("unknown endorsing org {}", e.org_id))
+        })?;
+        if org
+            .root_public_keys
+            .iter()
+            .any(|pk| verify_endorsement(e, pk).is_ok())
+        {
+            Ok(())
+        } else {
+            Err(AdmissionError::InvalidSignature(format!(
+                "endorsement by {} does not verify under a key of org {}",
+                e.signer_did, e.org_id
+            )))
+        }
+    }
+}
+
+impl AdmissionFilter for SignaturePolicy {
+    fn check(
+        &self,
+        envelope: &Envelope<'_>,
+        _ctx: &AdmissionContext,
+    ) -> Result<(), AdmissionError> {
+        if let Some(creator) = envelope.creator {
+            if creator.signature.is_empty() {
+                return Err(AdmissionError::InvalidSignature(format!(
+                    "transaction {} is not signed by its creator",
+                    envelope.tx.id
+                )));
+            }
+            validate_signature_consistency(
+                creator.algorithm,
+                creator.signature,
+                "creator signature",
+            )
+            .and_then(|()| enforce_pqc(creator.algorithm, "creator signature"))
+            .map_err(AdmissionError::InvalidSignature)?;
+            self.check_creator(envelope.tx, &creator)?;
+        }
+        for e in envelope.endorsements {
+            validate_signature_consistency(
+                e.signature_algorithm,
+                &e.signature,
+                "endorsement signature",
+            )
+            .and_then(|()| enforce_pqc(e.signature_algorithm, "endorsement signature"))
+            .map_err(AdmissionError::InvalidSignature)?;
+            self.check_endorsement(e)?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks the endorsing orgs against the policy bound to
+/// [`WRITERS_RESOURCE`]. Without a writers ACL every submitter is admitted.
+pub struct WritersAcl {
+    acl_provider: Arc<dyn AclProvider>,
+    policy_store: Arc<dyn PolicyStore>,
+}
+
+impl WritersAcl {
+    pub fn new(acl_provider: Arc<dyn AclProvider>, policy_store: Arc<dyn PolicyStore>) -> Self {
+        Self {
+            acl_provider,
+            policy_store,
+        }
+    }
+}
+
+impl AdmissionFilter for WritersAcl {
+    fn check(
+        &self,
+        envelope: &Envelope<'_>,
+        _ctx: &AdmissionContext,
+    ) -> Result<(), AdmissionError> {
+        match check_access(
+            self.acl_provider.as_ref(),
+            self.policy_store.as_ref(),
+            WRITERS_RESOURCE,
+            &envelope.orgs(),
+        ) {
+            Ok(()) | Err(AclError::NotDefined(_)) => Ok(()),
+            Err(e) => Err(AdmissionError::Forbidden(e.to_string())),
+        }
+    }
+}
+
+/// Rejects a transaction ID admitted less than `window_secs` ago.
+pub struct ReplayWindow {
+    window_secs: u64,
+    seen: Mutex<SeenIds>,
+}
+
+#[derive(Default)]
+struct SeenIds {
+    admitted_at: HashMap<String, u64>,
+    order: VecDeque<(u64, String)>,
+}
+
+impl ReplayWindow {
+    pub fn new(window_secs: u64) -> Self {
+        Self {
+            window_secs,
+            seen: Mutex::new(SeenIds::default()),
+        }
+    }
+}
+
+impl AdmissionFilter for ReplayWindow {
+    fn check(&self, envelope: &Envelope<'_>, ctx: &AdmissionContext) -> Result<(), AdmissionError> {
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+        while let Some((at, _)) = seen.order.front() {
+            if at.saturating_add(self.window_secs) > ctx.now_secs {
+                break;
+            }
+            if let Some((at, id)) = seen.order.pop_front() {
+                if seen.admitted_at.get(&id) == Some(&at) {
+                    seen.admitted_at.remove(&id);
+                }
+            }
+        }
+
+        let id = &envelope.tx.id;
+        if seen.admitted_at.contains_key(id) {
+            return Err(AdmissionError::Duplicate(id.clone()));
+        }
+        seen.admitted_at.insert(id.clone(), ctx.now_secs);
+        seen.order.push_back((ctx.now_secs, id.clone()));
+        Ok(())
+    }
+
+    fn for_channel(&self) -> Option<Arc<dyn AdmissionFilter>> {
+        Some(Arc::new(Self::new(self.window_secs)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::acl::MemoryAclProvider;
+    use crate::endorsement::org::Organization;
+    use crate::endorsement::policy::EndorsementPolicy;
+    use crate::endorsement::policy_store::MemoryPolicyStore;
+    use crate::identity::signing::SoftwareSigningProvider;
+    use crate::transaction::proposal::TransactionProposal;
+    use crate::transaction::rwset::ReadWriteSet;
+    use pqc_crypto_module::legacy::ed25519::{Signer, SigningKey};
+
+    fn make_tx(id: &str) -> Transaction {
+        Transaction {
+            id: id.to_string(),
+            block_height: 0,
+            timestamp: 0,
+            input_did: "did:bc:alice".to_string(),
+            output_recipient: "did:bc:bob".to_string(),
+            amount: 1,
+            state: "pending".to_string(),
+        }
+    }
+
+    /// Root key of `org1` or `org2`.
+    fn org_key(org: &str) -> SigningKey {
+        SigningKey::from_bytes(&[if org == "org1" { 1 } else { 2 }; 32])
+    }
+
+    /// Creator key, named by a `did:key` DID.
+    fn creator_key() -> SigningKey {
+        SigningKey::from_bytes(&[9u8; 32])
+    }
+
+    /// A `did:bc` creator key, registered as a second root key of `org1`.
+    fn registered_creator_key() -> SigningKey {
+        SigningKey::from_bytes(&[8u8; 32])
+    }
+
+    fn registry() -> Arc<MemoryOrgRegistry> {
+        let registry = Arc::new(MemoryOrgRegistry::new());
+        for org in ["org1", "org2"] {
+            let mut keys = vec![org_key(org).verifying_key().to_bytes()];
+            if org == "org1" {
+                keys.push(registered_creator_key().verifying_key().to_bytes());
+            }
+            let org =
+                Organization::new(org, org, vec![format!("did:bc:{org}")], vec![], keys).unwrap();
+            registry.register_org(&org).unwrap();
+        }
+        registry
+    }
+
+    fn endorsed(id: &str, orgs: &[&str]) -> EndorsedTransaction {
+        let mut proposal = TransactionProposal {
+            tx: make_tx(id),
+            creator_did: String::new(),
+            creator_signature: Vec::new(),
+            signature_algorithm: SigningAlgorithm::Ed25519,
+            rwset: ReadWriteSet::default(),
+        };
+        proposal
+            .sign(&SoftwareSigningProvider::from_key(creator_key()))
+            .unwrap();
+        EndorsedTransaction {
+            proposal,
+            endorsements: orgs
+                .iter()
+                .map(|org| Endorsement {
+                    signer_did: format!("did:bc:{org}"),
+                    org_id: org.to_string(),
+                    signature: org_key(org).sign(&[0u8; 32]).to_bytes().to_vec(),
+                    signature_algorithm: SigningAlgorithm::Ed25519,
+                    payload_hash: [0u8; 32],
+                    timestamp: 0,
+                })
+                .collect(),
+            rwset: ReadWriteSet::default(),
+        }
+    }
+
+    fn ctx(now_secs: u64) -> AdmissionContext {
+        AdmissionContext {
+            limits: BlockSizeLimits::default(),
+            now_secs,
+        }
+    }
+
+    #[test]
+    fn well_formed_rejects_empty_reserved_and_unnamed() {
+        let chain = AdmissionChain::new().with_filter(WellFormed);
+        for id in ["", "  ", "evidence-1", "config-abc", "bad\nid"] {
+            let err = chain.admit(&Envelope::plain(&make_tx(id)), &ctx(0));
+            assert!(
+                matches!(err, Err(AdmissionError::Malformed(_))),
+                "id {id:?} admitted"
+            );
+        }
+        let long = make_tx(&"x".repeat(MAX_TX_ID_LEN + 1));
+        assert!(chain.admit(&Envelope::plain(&long), &ctx(0)).is_err());
+
+        let mut etx = endorsed("tx-1", &["org1"]);
+        etx.proposal.creator_did.clear();
+        assert!(matches!(
+            chain.admit(&Envelope::endorsed(&etx), &ctx(0)),
+            Err(AdmissionError::Malformed(_))
+        ));
+        assert!(chain
+            .admit(&Envelope::plain(&make_tx("tx-1")), &ctx(0))
+            .is_ok());
+    }
+
+    #[test]
+    fn size_limit_uses_context_limits() {
+        let chain = AdmissionChain::new().with_filter(SizeLimit);
+        let tx = make_tx("tx-1");
+        let size = tx_size_bytes(&tx);
+        let mut context = ctx(0);
+        context.limits.absolute_max_bytes = size;
+        assert!(chain.admit(&Envelope::plain(&tx), &context).is_ok());
+        context.limits.absolute_max_bytes = size - 1;
+        assert_eq!(
+            chain.admit(&Envelope::plain(&tx), &context),
+            Err(AdmissionError::TooLarge {
+                tx_id: "tx-1".to_string(),
+                size,
+                limit: size - 1,
+            })
+        );
+    }
+
+    #[test]
+    fn signature_policy_rejects_unsigned_and_mismatched_signatures() {
+        let chain = AdmissionChain::new().with_filter(SignaturePolicy::new(registry()));
+        assert!(chain
+            .admit(&Envelope::endorsed(&endorsed("tx-1", &["org1"])), &ctx(0))
+            .is_ok());
+
+        let mut unsigned = endorsed("tx-2", &["org1"]);
+        unsigned.proposal.creator_signature.clear();
+        assert!(matches!(
+            chain.admit(&Envelope::endorsed(&unsigned), &ctx(0)),
+            Err(AdmissionError::InvalidSignature(_))
+        ));
+
+        // Declares ML-DSA-65 but carries an Ed25519-sized signature.
+        let mut forged = endorsed("tx-3", &["org1"]);
+        forged.endorsements[0].signature_algorithm = SigningAlgorithm::MlDsa65;
+        let err = chain
+            .admit(&Envelope::endorsed(&forged), &ctx(0))
+            .unwrap_err();
+        assert_eq!(err.reason(), "invalid_signature");
+    }
+
+    #[test]
+    fn signature_policy_verifies_signatures_against_registered_keys() {
+        let chain = AdmissionChain::new().with_filter(SignaturePolicy::new(registry()));
+
+        // Well-sized signatures that were never produced by the signer.
+        let mut creator_forged = endorsed("tx-1", &["org1"]);
+        creator_forged.proposal.creator_signature = vec![1u8; 64];
+        assert!(matches!(
+            chain.admit(&Envelope::endorsed(&creator_forged), &ctx(0)),
+            Err(AdmissionError::InvalidSignature(_))
+        ));
+        let mut endorsement_forged = endorsed("tx-2", &["org1"]);
+        endorsement_forged.endorsements[0].signature = vec![2u8; 64];
+        assert!(matches!(
+            chain.admit(&Envelope::endorsed(&endorsement_forged), &ctx(0)),
+            Err(AdmissionError::InvalidSignature(_))
+        ));
+
+        // Signed for another transaction.
+        let mut replayed = endorsed("tx-3", &["org1"]);
+        replayed.proposal.creator_signature = endorsed("tx-4", &[]).proposal.creator_signature;
+        assert!(chain
+            .admit(&Envelope::endorsed(&replayed), &ctx(0))
+            .is_err());
+
+        // A creator DID whose key is not registered.
+        let mut stranger = endorsed("tx-5", &["org1"]);
+        stranger.proposal.creator_did = "did:bc:mallory".to_string();
+        assert!(chain
+            .admit(&Envelope::endorsed(&stranger), &ctx(0))
+            .is_err());
+
+        // A did:key naming another key than the one that signed.
+        let mut impostor = endorsed("tx-5b", &["org1"]);
+        impostor.proposal.creator_did =
+            DidDocument::did_key(&org_key("org1").verifying_key().to_bytes());
+        assert!(chain
+            .admit(&Envelope::endorsed(&impostor), &ctx(0))
+            .is_err());
+
+        // A did:bc creator signs with its registered root key.
+        let registered = registered_creator_key();
+        let mut bc_creator = endorsed("tx-5c", &["org1"]);
+        bc_creator.proposal.creator_did =
+            DidDocument::from_public_key(&registered.verifying_key().to_bytes());
+        bc_creator.proposal.creator_signature = registered
+            .sign(&bc_creator.proposal.signing_payload())
+            .to_bytes()
+            .to_vec();
+        assert!(chain
+            .admit(&Envelope::endorsed(&bc_creator), &ctx(0))
+            .is_ok());
+
+        // An endorsement signed with org1's key but claimed for org2.
+        let mut wrong_org = endorsed("tx-6", &["org1"]);
+        wrong_org.endorsements[0].org_id = "org2".to_string();
+        assert!(chain
+            .admit(&Envelope::endorsed(&wrong_org), &ctx(0))
+            .is_err());
+
+        // Without registered keys nothing signed is admitted.
+        let standard = AdmissionChain::standard();
+        assert!(standard
+            .admit(&Envelope::endorsed(&endorsed("tx-7", &["org1"])), &ctx(0))
+            .is_err());
+        assert!(standard
+            .admit(&Envelope::plain(&make_tx("tx-8")), &ctx(0))
+            .is_ok());
+    }
+
+    #[test]
+    fn writers_acl_checks_endorsing_orgs() {
+        let acl = Arc::new(MemoryAclProvider::new());
+        let policies = Arc::new(MemoryPolicyStore::new());
+        let chain =
+            AdmissionChain::new().with_filter(WritersAcl::new(acl.clone(), policies.clone()));
+
+        // No writers ACL yet: everyone may submit.
+        assert!(chain
+            .admit(&Envelope::plain(&make_tx("tx-0")), &ctx(0))
+            .is_ok());
+
+        policies
+            .set_policy(
+                "WritersPolicy",
+                &EndorsementPolicy::AnyOf(vec!["org1".into()]),
+            )
+            .unwrap();
+        acl.set_acl(WRITERS_RESOURCE, "WritersPolicy").unwrap();
+
+        assert!(chain
+            .admit(&Envelope::endorsed(&endorsed("tx-1", &["org1"])), &ctx(0))
+            .is_ok());
+        assert!(matches!(
+            chain.admit(&Envelope::endorsed(&endorsed("tx-2", &["org2"])), &ctx(0)),
+            Err(AdmissionError::Forbidden(_))
+        ));
+        assert!(matches!(
+            chain.admit(&Envelope::plain(&make_tx("tx-3")), &ctx(0)),
+            Err(AdmissionError::Forbidden(_))
+        ));
+    }
+
+    #[test]
+    fn replay_window_rejects_duplicates_until_it_expires() {
+        let chain = AdmissionChain::new().with_filter(ReplayWindow::new(60));
+        let tx = make_tx("tx-1");
+        assert!(chain.admit(&Envelope::plain(&tx), &ctx(1_000)).is_ok());
+        assert_eq!(
+            chain.admit(&Envelope::plain(&tx), &ctx(1_030)),
+            Err(AdmissionError::Duplicate("tx-1".to_string()))
+        );
+        assert!(chain
+            .admit(&Envelope::plain(&make_tx("tx-2")), &ctx(1_030))
+            .is_ok());
+        assert!(chain.admit(&Envelope::plain(&tx), &ctx(1_061)).is_ok());
+    }
+
+    #[test]
+    fn each_channel_gets_its_own_replay_window() {
+        let node = AdmissionChain::new().with_filter(ReplayWindow::new(60));
+        let ch1 = node.for_channel();
+        let ch2 = node.for_channel();
+        let tx = make_tx("tx-1");
+        for chain in [&node, &ch1, &ch2] {
+            assert!(chain.admit(&Envelope::plain(&tx), &ctx(1_000)).is_ok());
+        }
+        assert_eq!(
+            ch1.admit(&Envelope::plain(&tx), &ctx(1_010)),
+            Err(AdmissionError::Duplicate("tx-1".to_string()))
+        );
+    }
+
+    #[test]
+    fn standard_with_acl_keeps_replay_window_last() {
+        let acl = Arc::new(MemoryAclProvider::new());
+        let policies = Arc::new(MemoryPolicyStore::new());
+        policies
+            .set_policy(
+                "WritersPolicy",
+                &EndorsementPolicy::AnyOf(vec!["org1".into()]),
+            )
+            .unwrap();
+        acl.set_acl(WRITERS_RESOURCE, "WritersPolicy").unwrap();
+        let chain = AdmissionChain::standard_with_acl(registry(), acl, policies);
+
+        // A forbidden submission is not remembered, so a later authorised
+        // one with the same ID is admitted.
+        let forbidden = endorsed("tx-1", &["org2"]);
+        assert!(matches!(
+            chain.admit(&Envelope::endorsed(&forbidden), &ctx(0)),
+            Err(AdmissionError::Forbidden(_))
+        ));
+        let allowed = endorsed("tx-1", &["org1"]);
+        assert!(chain.admit(&Envelope::endorsed(&allowed), &ctx(0)).is_ok());
+        assert!(matches!(
+            chain.admit(&Envelope::endorsed(&allowed), &ctx(0)),
+            Err(AdmissionError::Duplicate(_))
+        ));
+    }
+}
@@ -0,0 +1,1849 @@
+//! BFT ordering node — drives HotStuff rounds from network messages.
+//!
+//! [`BftNode`] is a synchronous state machine on top of [`RoundManager`]:
+//! callers feed it transactions, P2P messages and clock ticks, and drain the
+//! messages it wants broadcast with [`BftNode::take_outbound`]. Votes are
+//! broadcast to every validator, so each node forms quorum certificates on
+//! its own and decides independently.
+//!
+//! Validator identities are hex-encoded public keys; votes are verified
+//! against them with [`PublicKeyVerifier`], or against the key the
+//! validator holds at the current height once it has rotated keys (see
+//! [`KeySchedule`]). With an [`EpochConfig`], the last
+//! block of every epoch carries the next validator set (computed from
+//! staking) and the node switches sets once that block is decided.
+//!
+//! A stalled round ends with a timeout certificate: each validator whose
+//! timer fires broadcasts a signed `BftViewChange` carrying its highest
+//! Prepare QC, and `2f + 1` of them move everyone to the next round. The
+//! next leader re-proposes the block of the highest reported QC, which is
+//! what locked validators require before voting again.
+//!
+//! Votes are also checked for equivocation: a validator signing two blocks
+//! in one round is reported as signed evidence (`BftEvidence`), which the
+//! next leader commits in a block as an evidence transaction. Every node
+//! verifies that evidence and, once the block is committed, records the
+//! penalty and slashes the offender's stake in block order.
+//!
+//! Transactions are gossiped as `BftTransaction`s signed by the validator
+//! that admitted them; gossip from anyone outside the validator set is
+//! dropped. Channel config transactions share the gossip path but
+//! wait in a pool of their own; a leader with a pending config transaction
+//! proposes it alone, as a config block.
+
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::channel::config::{ConfigTransaction, CONFIG_TX_PREFIX};
+use crate::channel::ledger::{check_config_block_shape, config_block};
+use crate::consensus::bft::epoch::{
+    committee_from_staking, EpochConfig, ValidatorSet, ValidatorSetHistory,
+};
+use crate::consensus::bft::quorum::{QuorumValidator, SignatureVerifier};
+use crate::consensus::bft::round::{RoundAction, RoundEvent, RoundState};
+use crate::consensus::bft::round_manager::{ManagerAction, RoundManager, RoundManagerConfig};
+use crate::consensus::bft::types::{
+    BftPhase, QuorumCertificate, TimeoutCertificate, TimeoutMessage, VoteMessage,
+};
+use crate::consensus::equivocation::{
+    EquivocationDetector, EquivocationEvidence, SignedEvidence, EVIDENCE_TX_PREFIX,
+};
+use crate::consensus::slashing::{PenaltyManager, PenaltyPolicy};
+use crate::events::{BlockEvent, EventBus};
+use crate::identity::key_rotation::{
+    KeyRotation, KeyRotationError, KeySchedule, ScheduledKeyVerifier, SharedKeySchedule,
+};
+use crate::identity::signing::{verify_with_public_key, SigningProvider};
+use crate::network::Message;
+use crate::ordering::{tx_size_bytes, BlockSizeLimits};
+use crate::staking::StakingManager;
+use crate::storage::traits::{Block, Transaction};
+use crate::transaction::endorsed::EndorsedTransaction;
+
+/// Maximum number of decided blocks kept for `cut_block` before the oldest
+/// are dropped (non-gateway validators never drain their queue).
+pub const MAX_DECIDED_BACKLOG: usize = 1024;
+/// Maximum number of out-of-order votes buffered for later phases/rounds.
+const MAX_PENDING_VOTES: usize = 4096;
+/// Maximum number of proposals buffered for future rounds.
+const MAX_FUTURE_PROPOSALS: usize = 64;
+/// How many rounds ahead a proposal may be buffered.
+const MAX_ROUND_LOOKAHEAD: u64 = 8;
+/// Maximum number of proposed blocks kept for re-proposal at one height.
+const MAX_CANDIDATE_BLOCKS: usize = 64;
+/// Number of transaction IDs remembered to suppress gossip loops.
+const SEEN_TX_CAPACITY: usize = 100_000;
+/// Verified evidence waiting for inclusion.
+const MAX_PENDING_EVIDENCE: usize = 256;
+/// Evidence transactions per block.
+const MAX_BLOCK_EVIDENCE: usize = 16;
+
+/// Domain-separation byte for gossiped transactions (distinct from the
+/// vote, timeout and evidence domains).
+pub const TX_GOSSIP_DOMAIN: u8 = 0x30;
+
+/// A transaction as gossiped between validators, signed by the validator
+/// that admitted it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GossipedTransaction {
+    pub tx: Transaction,
+    /// Validator ID of the node that admitted the transaction.
+    pub sender: String,
+    /// Signature over `(TX_GOSSIP_DOMAIN || SHA-256 of the transaction)`.
+    pub signature: Vec<u8>,
+    /// The endorsed envelope `tx` was submitted in, so receivers can re-run
+    /// creator and endorsement admission. `None` for plain transactions.
+    #[serde(default)]
+    pub endorsed: Option<EndorsedTransaction>,
+}
+
+impl GossipedTransaction {
+    /// Canonical bytes the sender signs: `TX_GOSSIP_DOMAIN || hash`.
+    pub fn signing_payload(tx: &Transaction) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(33);
+        payload.push(TX_GOSSIP_DOMAIN);
+        payload.extend_from_slice(&Sha256::digest(serde_json::to_vec(tx).unwrap_or_default()));
+        payload
+    }
+}
+
+/// Verifies BFT votes whose `voter_id` is the hex-encoded public key of the
+/// validator (Ed25519 or ML-DSA-65).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PublicKeyVerifier;
+
+impl SignatureVerifier for PublicKeyVerifier {
+    fn verify(&self, voter_id: &str, payload: &[u8], signature: &[u8]) -> bool {
+        match hex::decode(voter_id) {
+            Ok(pk) => verify_with_public_key(&pk, payload, signature),
+            Err(_) => false,
+        }
+    }
+}
+
+/// Validator identity for a signing provider: its hex-encoded public key.
+pub fn validator_id(provider: &dyn SigningProvider) -> String {
+    hex::encode(provider.public_key())
+}
+
+/// Merkle root over the transaction IDs of a block.
+pub fn tx_merkle_root(tx_ids: &[String]) -> [u8; 32] {
+    let leaves: Vec<&[u8]> = tx_ids.iter().map(|id| id.as_bytes()).collect();
+    crate::bridge::verifier::build_merkle_tree(&leaves)
+        .0
+        .unwrap_or_default()
+}
+
+/// Validate a commit QC against a validator set.
+pub fn validate_commit_qc(qc: &QuorumCertificate, validators: &[String]) -> bool {
+    validate_commit_qc_with(qc, validators, PublicKeyVerifier)
+}
+
+/// Validate a commit QC against a validator set, resolving voter keys with
+/// `verifier` (e.g. a [`ScheduledKeyVerifier`] at the block's height).
+pub fn validate_commit_qc_with<V: SignatureVerifier>(
+    qc: &QuorumCertificate,
+    validators: &[String],
+    verifier: V,
+) -> bool {
+    qc.phase == BftPhase::Commit
+        && QuorumValidator::new(validators.to_vec(), verifier)
+            .validate_qc(qc)
+            .is_ok()
+}
+
+/// Whether `block` hashes to `block_hash`, commits to its transaction list,
+/// and is signed by `leader_id`.
+fn is_signed_by(
+    block: &Block,
+    block_hash: &[u8; 32],
+    leader_id: &str,
+    verifier: &impl SignatureVerifier,
+) -> bool {
+    block.proposer == leader_id
+        && block.merkle_root == tx_merkle_root(&block.transactions)
+        && super::block_hash_for_signing(block) == *block_hash
+        && verifier.verify(leader_id, block_hash, &block.signature)
+}
+
+/// A proposal received for a round this node has not reached yet.
+struct FutureProposal {
+    block_hash: [u8; 32],
+    leader_id: String,
+    block: Block,
+    justify: Option<QuorumCertificate>,
+}
+
+/// A BFT ordering participant.
+pub struct BftNode {
+    node_id: String,
+    /// Validator set of the current epoch.
+    validators: Vec<String>,
+    /// Validator sets of every epoch seen, for checking QCs of synced blocks.
+    epochs: ValidatorSetHistory,
+    epoch_config: EpochConfig,
+    /// Stake source for computing the next epoch's committee.
+    staking: Option<Arc<StakingManager>>,
+    signer: Arc<dyn SigningProvider>,
+    /// Signer taking over at the given height after a key rotation.
+    pending_signer: Option<(u64, Arc<dyn SigningProvider>)>,
+    /// Committed key rotations of validators.
+    keys: SharedKeySchedule,
+    /// Resolves validator keys at `next_height`; the round manager holds a
+    /// clone sharing the height.
+    verifier: ScheduledKeyVerifier,
+    manager: RoundManager<ScheduledKeyVerifier>,
+    max_batch_size: usize,
+    block_limits: BlockSizeLimits,
+    /// Transactions waiting to be proposed, in arrival order.
+    mempool: VecDeque<Transaction>,
+    /// Config transactions waiting to be proposed, each in its own block.
+    config_pool: VecDeque<ConfigTransaction>,
+    seen_txs: HashSet<String>,
+    seen_order: VecDeque<String>,
+    /// Blocks proposed in the current round, by block hash.
+    proposals: BTreeMap<[u8; 32], Block>,
+    /// Signed proposals for later rounds, by `(round, leader_id)`: the leader
+    /// schedule of a later round may belong to the next epoch, so the leader
+    /// is checked once the round starts.
+    future_proposals: BTreeMap<(u64, String), FutureProposal>,
+    /// Votes that arrived before this node could use them.
+    pending_votes: Vec<VoteMessage>,
+    /// Blocks proposed at the current height in any round, so a leader can
+    /// re-propose the block of the highest Prepare QC.
+    candidates: VecDeque<([u8; 32], Block)>,
+    /// Decided blocks (with commit QC) not yet handed to `cut_block`.
+    decided: VecDeque<Block>,
+    next_height: u64,
+    parent_hash: [u8; 32],
+    /// Latest config block of the chain, for the `last_config` of proposals.
+    last_config: super::LastConfig,
+    now_ms: u64,
+    round_started_ms: u64,
+    outbox: Vec<Message>,
+    /// Round the first `start` enters.
+    first_round: u64,
+    /// Double-vote tracking over the votes of recent rounds.
+    equivocation: EquivocationDetector,
+    /// Verified evidence not yet committed, in arrival order.
+    evidence_pool: Vec<EquivocationEvidence>,
+    /// Penalties applied from committed evidence.
+    penalties: PenaltyManager,
+    penalty_policy: PenaltyPolicy,
+    event_bus: Option<Arc<EventBus>>,
+}
+
+impl BftNode {
+    /// Create a node signing with `signer` among `validators` (hex public
+    /// keys, including this node's own).
+    pub fn new(
+        signer: Arc<dyn SigningProvider>,
+        validators: Vec<String>,
+        config: RoundManagerConfig,
+        max_batch_size: usize,
+    ) -> Self {
+        let node_id = validator_id(signer.as_ref());
+        let keys: SharedKeySchedule = Default::default();
+        let verifier = ScheduledKeyVerifier::at(keys.clone(), 1);
+        let manager = RoundManager::new(
+            node_id.clone(),
+            validators.clone(),
+            verifier.clone(),
+            config,
+        );
+        Self {
+            node_id,
+            epochs: ValidatorSetHistory::new(validators.clone()),
+            validators,
+            epoch_config: EpochConfig::default(),
+            staking: None,
+            signer,
+            pending_signer: None,
+            keys,
+            verifier,
+            manager,
+            max_batch_size: max_batch_size.max(1),
+            block_limits: BlockSizeLimits::default(),
+            mempool: VecDeque::new(),
+            config_pool: VecDeque::new(),
+            seen_txs: HashSet::new(),
+            seen_order: VecDeque::new(),
+            proposals: BTreeMap::new(),
+            future_proposals: BTreeMap::new(),
+            pending_votes: Vec::new(),
+            candidates: VecDeque::new(),
+            decided: VecDeque::new(),
+            next_height: 1,
+            parent_hash: [0u8; 32],
+            last_config: super::LastConfig::default(),
+            now_ms: 0,
+            round_started_ms: 0,
+            outbox: Vec::new(),
+            first_round: 0,
+            equivocation: EquivocationDetector::new(),
+            evidence_pool: Vec::new(),
+            penalties: PenaltyManager::new(),
+            penalty_policy: PenaltyPolicy::default(),
+            event_bus: None,
+        }
+    }
+
+    /// Reconfigure the validator set every `config.epoch_length` blocks from
+    /// the stakes in `staking` (without a stake source the set carries over).
+    pub fn set_epochs(&mut self, config: EpochConfig, staking: Option<Arc<StakingManager>>) {
+        self.epoch_config = config;
+        self.staking = staking;
+    }
+
+    /// Cap the number of mempool transactions in the next proposals.
+    pub fn set_max_batch_size(&mut self, max_batch_size: usize) {
+        self.max_batch_size = max_batch_size.max(1);
+    }
+
+    /// Byte limits for the next proposals and for admitting transactions.
+    pub fn set_block_size_limits(&mut self, limits: BlockSizeLimits) {
+        self.block_limits = limits;
+    }
+
+    /// Current block byte limits.
+    pub fn block_size_limits(&self) -> BlockSizeLimits {
+        self.block_limits
+    }
+
+    /// Publish detected equivocations and applied slashes on `bus`.
+    pub fn set_event_bus(&mut self, bus: Arc<EventBus>) {
+        self.event_bus = Some(bus);
+    }
+
+    /// Record a committed key rotation of a validator. Votes and proposals
+    /// of that validator verify with the new key from the activation height,
+    /// and with the old one only until its grace window ends.
+    pub fn record_key_rotation(&mut self, rotation: KeyRotation) -> Result<(), KeyRotationError> {
+        self.keys
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .record(rotation)
+    }
+
+    /// Replay the key rotations committed in the channel config.
+    pub fn set_key_schedule(&mut self, schedule: KeySchedule) {
+        *self.keys.write().unwrap_or_else(|e| e.into_inner()) = schedule;
+    }
+
+    /// Committed key rotations this node verifies with.
+    pub fn key_schedule(&self) -> KeySchedule {
+        self.keys.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Sign with `signer` from `activation_height` on, after this node's own
+    /// key rotation was committed. The validator ID does not change.
+    pub fn rotate_signer_at(&mut self, signer: Arc<dyn SigningProvider>, activation_height: u64) {
+        self.pending_signer = Some((activation_height, signer));
+        self.activate_pending_signer();
+    }
+
+    fn activate_pending_signer(&mut self) {
+        if self
+            .pending_signer
+            .as_ref()
+            .is_some_and(|(at, _)| self.next_height >= *at)
+        {
+            if let Some((at, signer)) = self.pending_signer.take() {
+                log::info!(
+                    "BFT node {} signs with its rotated key from height {at}",
+                    self.node_id
+                );
+                self.signer = signer;
+            }
+        }
+    }
+
+    /// Penalties applied from committed evidence.
+    pub fn penalties(&self) -> &PenaltyManager {
+        &self.penalties
+    }
+
+    /// Start no earlier than the round after `round`, the last round this
+    /// validator may have voted in before a restart. Evidence is keyed by
+    /// round, so voting again in an old round would look like equivocation.
+    pub fn resume_after_round(&mut self, round: u64) {
+        self.first_round = self.first_round.max(round + 1);
+    }
+
+    /// Validator sets handed off so far.
+    pub fn validator_sets(&self) -> &ValidatorSetHistory {
+        &self.epochs
+    }
+
+    /// This node's validator ID.
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    /// The current epoch's validator set.
+    pub fn validators(&self) -> &[String] {
+        &self.validators
+    }
+
+    /// Current BFT round.
+    pub fn current_round(&self) -> u64 {
+        self.manager.current_round()
+    }
+
+    /// Height the next decided block will have.
+    pub fn next_height(&self) -> u64 {
+        self.next_height
+    }
+
+    /// Highest commit QC this node has formed or accepted.
+    pub fn highest_commit_qc(&self) -> Option<QuorumCertificate> {
+        self.manager.highest_commit_qc().cloned()
+    }
+
+    /// Continue from a ledger tip pointing at config block `height`.
+    pub fn resume_last_config(&mut self, height: u64) {
+        self.last_config.resume(height);
+    }
+
+    /// Continue the chain from an existing ledger tip.
+    pub fn set_ledger_tip(&mut self, height: u64, block_hash: [u8; 32]) {
+        if height + 1 > self.next_height {
+            self.advance_height(height, block_hash);
+        }
+    }
+
+    /// Move past the block at `height`: locks and candidates of that height
+    /// no longer apply.
+    fn advance_height(&mut self, height: u64, block_hash: [u8; 32]) {
+        self.next_height = height + 1;
+        self.parent_hash = block_hash;
+        self.candidates.clear();
+        self.manager.reset_safety();
+        self.verifier.set_height(self.next_height);
+        self.activate_pending_signer();
+    }
+
+    /// Start the first round (round 0 unless resumed).
+    pub fn start(&mut self, now_ms: u64) {
+        self.now_ms = now_ms;
+        self.manager.start_round(self.first_round);
+        self.round_started_ms = now_ms;
+        self.maybe_propose();
+    }
+
+    /// Transactions waiting to be proposed.
+    pub fn mempool_len(&self) -> usize {
+        self.mempool.len()
+    }
+
+    /// Config transactions waiting to be proposed.
+    pub fn config_pool_len(&self) -> usize {
+        self.config_pool.len()
+    }
+
+    /// Number of decided blocks waiting for `cut_block`.
+    pub fn decided_len(&self) -> usize {
+        self.decided.len()
+    }
+
+    /// Take the oldest decided block.
+    pub fn pop_decided(&mut self) -> Option<Block> {
+        self.decided.pop_front()
+    }
+
+    /// Drain the messages to broadcast to the other validators.
+    pub fn take_outbound(&mut self) -> Vec<Message> {
+        std::mem::take(&mut self.outbox)
+    }
+
+    /// Accept a locally submitted transaction and gossip it to the other
+    /// validators so whichever node leads can propose it.
+    pub fn submit_tx(&mut self, tx: Transaction) {
+        self.submit(tx, None);
+    }
+
+    /// Accept a locally submitted endorsed transaction and gossip it with
+    /// its envelope; only the inner transaction enters the mempool.
+    pub fn submit_endorsed_tx(&mut self, etx: EndorsedTransaction) {
+        self.submit(etx.proposal.tx.clone(), Some(etx));
+    }
+
+    fn submit(&mut self, tx: Transaction, endorsed: Option<EndorsedTransaction>) {
+        if self.remember_tx(&tx.id) {
+            match self.signer.sign(&GossipedTransaction::signing_payload(&tx)) {
+                Ok(signature) => self
+                    .outbox
+                    .push(Message::BftTransaction(GossipedTransaction {
+                        tx: tx.clone(),
+                        sender: self.node_id.clone(),
+                        signature,
+                        endorsed,
+                    })),
+                Err(e) => log::error!("BFT transaction signing failed: {e}"),
+            }
+            self.mempool.push_back(tx);
+            self.maybe_propose();
+        }
+    }
+
+    /// Check that `gossip` comes from a validator of the current epoch,
+    /// carries its signature and, if endorsed, that the envelope wraps the
+    /// gossiped transaction.
+    pub fn verify_gossip(&self, gossip: &GossipedTransaction) -> Result<(), String> {
+        if !self.validators.contains(&gossip.sender) {
+            return Err(format!("sender {} is not a validator", gossip.sender));
+        }
+        if !self.verifier.verify(
+            &gossip.sender,
+            &GossipedTransaction::signing_payload(&gossip.tx),
+            &gossip.signature,
+        ) {
+            return Err(format!("invalid signature from {}", gossip.sender));
+        }
+        if let Some(etx) = &gossip.endorsed {
+            if serde_json::to_vec(&etx.proposal.tx).ok() != serde_json::to_vec(&gossip.tx).ok() {
+                return Err(format!(
+                    "endorsed envelope from {} does not wrap transaction {}",
+                    gossip.sender, gossip.tx.id
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Accept a transaction gossiped by another validator. Transactions
+    /// above the absolute block size are dropped. Callers check the sender
+    /// with [`verify_gossip`](Self::verify_gossip) and run admission first.
+    pub fn on_transaction(&mut self, tx: Transaction) {
+        if let Err(e) = self.block_limits.check_tx(&tx) {
+            log::warn!("BFT: dropping gossiped transaction: {e}");
+            return;
+        }
+        if self.remember_tx(&tx.id) {
+            self.mempool.push_back(tx);
+            self.maybe_propose();
+        }
+    }
+
+    /// Accept a locally submitted config transaction and gossip it.
+    pub fn submit_config_tx(&mut self, tx: ConfigTransaction) {
+        if self.remember_id(&tx.ledger_tx_id()) {
+            self.outbox
+                .push(Message::SubmitConfigTransaction(tx.clone()));
+            self.config_pool.push_back(tx);
+            self.maybe_propose();
+        }
+    }
+
+    /// Accept a config transaction gossiped by another validator.
+    pub fn on_config_transaction(&mut self, tx: ConfigTransaction) {
+        if self.remember_id(&tx.ledger_tx_id()) {
+            self.config_pool.push_back(tx);
+            self.maybe_propose();
+        }
+    }
+
+    fn remember_tx(&mut self, id: &str) -> bool {
+        // Evidence and config transactions only enter blocks through their
+        // own pools; purges are not ordered by BFT.
+        if id.starts_with(EVIDENCE_TX_PREFIX)
+            || id.starts_with(CONFIG_TX_PREFIX)
+            || id.starts_with(crate::private_data::PURGE_TX_PREFIX)
+        {
+            return false;
+        }
+        self.remember_id(id)
+    }
+
+    fn remember_id(&mut self, id: &str) -> bool {
+        if !self.seen_txs.insert(id.to_string()) {
+            return false;
+        }
+        self.seen_order.push_back(id.to_string());
+        if self.seen_order.len() > SEEN_TX_CAPACITY {
+            if let Some(old) = self.seen_order.pop_front() {
+                self.seen_txs.remove(&old);
+            }
+        }
+        true
+    }
+
+    /// Advance the clock. Times out the current round once a proposal is
+    /// overdue (and re-broadcasts the timeout every period until a timeout
+    /// certificate forms); an idle node (nothing to order, nothing in
+    /// flight) never times out.
+    pub fn tick(&mut self, now_ms: u64) {
+        self.now_ms = now_ms;
+        // Non-members follow the chain but never drive view changes.
+        let idle = (self.mempool.is_empty()
+            && self.evidence_pool.is_empty()
+            && self.config_pool.is_empty()
+            && self.manager.round_state() == Some(RoundState::AwaitingProposal))
+            || !self.validators.contains(&self.node_id);
+        if idle {
+            self.round_started_ms = now_ms;
+            return;
+        }
+        if now_ms.saturating_sub(self.round_started_ms) < self.manager.current_timeout_ms() {
+            self.maybe_propose();
+            return;
+        }
+
+        log::warn!(
+            "BFT round {} timed out (leader {})",
+            self.manager.current_round(),
+            self.manager.current_leader()
+        );
+        self.round_started_ms = now_ms;
+        let action = self.manager.local_timeout();
+        self.handle_action(action);
+    }
+
+    /// Dispatch a BFT-related P2P message. Returns `false` for messages this
+    /// node does not handle.
+    pub fn handle_message(&mut self, msg: Message) -> bool {
+        match msg {
+            Message::BftProposal {
+                round,
+                block_hash,
+                leader_id,
+                block_data,
+                justify,
+            } => self.on_proposal(round, block_hash, leader_id, block_data, justify),
+            Message::BftVote(vote) => self.on_vote(vote),
+            Message::BftQuorumCertificate(qc) => self.on_commit_qc(qc),
+            Message::BftViewChange {
+                timed_out_round,
+                new_round: _,
+                voter_id,
+                highest_qc,
+                signature,
+            } => self.on_timeout(TimeoutMessage {
+                round: timed_out_round,
+                voter_id,
+                high_qc: highest_qc,
+                signature,
+            }),
+            Message::BftTimeoutCertificate(tc) => self.on_timeout_certificate(tc),
+            Message::BftEvidence(signed) => self.on_evidence(signed),
+            Message::BftTransaction(gossip) => match self.verify_gossip(&gossip) {
+                Ok(()) => self.on_transaction(gossip.tx),
+                Err(e) => log::warn!("BFT: dropping gossiped transaction: {e}"),
+            },
+            Message::SubmitConfigTransaction(tx) => self.on_config_transaction(tx),
+            _ => return false,
+        }
+        true
+    }
+
+    fn on_proposal(
+        &mut self,
+        round: u64,
+        block_hash: [u8; 32],
+        leader_id: String,
+        block_data: Vec<u8>,
+        justify: Option<QuorumCertificate>,
+    ) {
+        let current = self.manager.current_round();
+        if round < current || round > current + MAX_ROUND_LOOKAHEAD {
+            return;
+        }
+        let block: Block = match serde_json::from_slice(&block_data) {
+            Ok(b) => b,
+            Err(e) => {
+                log::warn!("BFT proposal for round {round} has undecodable block: {e}");
+                return;
+            }
+        };
+        if !is_signed_by(&block, &block_hash, &leader_id, &self.verifier) {
+            log::warn!("BFT proposal for round {round} is not signed by leader {leader_id}");
+            return;
+        }
+        if round > current {
+            if self.future_proposals.len() < MAX_FUTURE_PROPOSALS {
+                self.future_proposals
+                    .entry((round, leader_id.clone()))
+                    .or_insert(FutureProposal {
+                        block_hash,
+                        leader_id,
+                        block,
+                        justify,
+                    });
+            }
+            return;
+        }
+        self.accept_proposal(block_hash, leader_id, block, justify);
+    }
+
+    /// Vote for a leader-signed proposal of the current round if it extends
+    /// our chain tip and passes the locking rule.
+    fn accept_proposal(
+        &mut self,
+        block_hash: [u8; 32],
+        leader_id: String,
+        block: Block,
+        justify: Option<QuorumCertificate>,
+    ) {
+        let round = self.manager.current_round();
+        if leader_id != self.manager.leader_for_round(round) {
+            log::warn!("BFT proposal for round {round} from non-leader {leader_id}");
+            return;
+        }
+        if block.height != self.next_height
+            || block.parent_hash != self.parent_hash
+            || block.transactions.is_empty()
+            || block.next_validator_set != self.expected_handoff(block.height)
+            || block.last_config != self.last_config.expected(&block)
+            || !self.evidence_is_valid(&block)
+            || check_config_block_shape(&block).is_err()
+        {
+            log::warn!(
+                "BFT proposal rejected (height {}, expected {})",
+                block.height,
+                self.next_height
+            );
+            return;
+        }
+        self.remember_candidate(block_hash, &block);
+        self.proposals.insert(block_hash, block);
+        let action = self.manager.process_event(RoundEvent::Proposal {
+            block_hash,
+            leader_id,
+            justify,
+        });
+        self.handle_action(action);
+        self.replay_pending_votes();
+    }
+
+    fn on_vote(&mut self, vote: VoteMessage) {
+        let current = self.manager.current_round();
+        // Votes for later rounds may come from the next epoch's validators.
+        if vote.round < current
+            || (vote.round == current && !self.validators.contains(&vote.voter_id))
+        {
+            return;
+        }
+        if vote.round <= current + MAX_ROUND_LOOKAHEAD && self.validators.contains(&vote.voter_id) {
+            self.check_equivocation(&vote);
+        }
+        if vote.round > current || !self.vote_applies_now(&vote) {
+            self.buffer_vote(vote);
+            return;
+        }
+        let action = self.manager.process_event(RoundEvent::Vote(vote));
+        self.handle_action(action);
+    }
+
+    /// Report `vote` if it conflicts with an earlier vote of its validator.
+    fn check_equivocation(&mut self, vote: &VoteMessage) {
+        let Some(evidence) = self.equivocation.check_vote(vote) else {
+            return;
+        };
+        if evidence.verify(&self.verifier).is_ok() {
+            self.report_evidence(evidence);
+        } else if self
+            .verifier
+            .verify(&vote.voter_id, &vote.payload(), &vote.signature)
+        {
+            // The vote remembered first was forged; compare against this one.
+            self.equivocation.record_vote(vote);
+        }
+    }
+
+    /// Pool evidence found locally and gossip it, signed, to the validators.
+    fn report_evidence(&mut self, evidence: EquivocationEvidence) {
+        if !self.pool_evidence(evidence.clone()) {
+            return;
+        }
+        log::warn!(
+            "BFT validator {} equivocated in round {}",
+            evidence.offender(),
+            evidence.round()
+        );
+        self.publish(BlockEvent::EquivocationDetected {
+            proposer: evidence.offender().to_string(),
+            height: self.next_height,
+            slot: evidence.round(),
+        });
+        let signature = match self
+            .signer
+            .sign(&SignedEvidence::signing_payload(&evidence))
+        {
+            Ok(sig) => sig,
+            Err(e) => {
+                log::error!("BFT evidence signing failed: {e}");
+                return;
+            }
+        };
+        self.outbox.push(Message::BftEvidence(SignedEvidence {
+            evidence,
+            reporter: self.node_id.clone(),
+            signature,
+        }));
+        self.maybe_propose();
+    }
+
+    /// Pool evidence reported by another validator.
+    fn on_evidence(&mut self, signed: SignedEvidence) {
+        if !self.validators.contains(&signed.reporter) {
+            return;
+        }
+        if let Err(e) = signed.verify(&self.verifier) {
+            log::warn!("BFT evidence from {} rejected: {e}", signed.reporter);
+            return;
+        }
+        if self.pool_evidence(signed.evidence) {
+            self.maybe_propose();
+        }
+    }
+
+    /// Add verified evidence to the pool unless its offense is already
+    /// pooled or penalized. Returns whether it was added.
+    fn pool_evidence(&mut self, evidence: EquivocationEvidence) -> bool {
+        let offense = evidence.offense_id();
+        if self.penalties.is_proof_processed(&offense)
+            || self.evidence_pool.len() >= MAX_PENDING_EVIDENCE
+            || self.evidence_pool.iter().any(|e| e.offense_id() == offense)
+        {
+            return false;
+        }
+        self.evidence_pool.push(evidence);
+        true
+    }
+
+    /// Whether the evidence in `block` verifies, names offenses not yet
+    /// penalized (each once), and matches the block's evidence transaction
+    /// IDs one to one.
+    fn evidence_is_valid(&self, block: &Block) -> bool {
+        if block.evidence.len() > MAX_BLOCK_EVIDENCE {
+            return false;
+        }
+        let mut offenses = HashSet::new();
+        let mut tx_ids = HashSet::new();
+        for evidence in &block.evidence {
+            let offense = evidence.offense_id();
+            if evidence.verify(&self.verifier).is_err()
+                || self.penalties.is_proof_processed(&offense)
+                || !offenses.insert(offense)
+            {
+                return false;
+            }
+            tx_ids.insert(evidence.tx_id());
+        }
+        let listed: Vec<&String> = block
+            .transactions
+            .iter()
+            .filter(|id| id.starts_with(EVIDENCE_TX_PREFIX))
+            .collect();
+        listed.len() == tx_ids.len() && listed.iter().all(|id| tx_ids.contains(*id))
+    }
+
+    /// Apply the evidence committed by `block`: record each offense once,
+    /// slash the offender's stake by the penalty policy, and (if
+    /// `announce`) publish `ValidatorSlashed`. Nodes apply the same blocks
+    /// in the same order, so their penalty ledgers and stakes agree.
+    fn apply_evidence(&mut self, block: &Block, announce: bool) {
+        let verifier = ScheduledKeyVerifier::at(self.keys.clone(), block.height);
+        for evidence in &block.evidence {
+            if evidence.verify(&verifier).is_err() {
+                continue;
+            }
+            let offender = evidence.offender().to_string();
+            if self
+                .penalties
+                .penalize_equivocation(
+                    &offender,
+                    evidence.offense_id(),
+                    block.height,
+                    &self.penalty_policy,
+                )
+                .is_none()
+            {
+                continue;
+            }
+            let slashed = self
+                .staking
+                .as_ref()
+                .and_then(|staking| staking.slash_for_evidence(&offender, &self.penalty_policy))
+                .unwrap_or(0);
+            if announce {
+                log::warn!(
+                    "BFT validator {offender} slashed {slashed} at height {} for equivocating in round {}",
+                    block.height,
+                    evidence.round()
+                );
+                self.publish(BlockEvent::ValidatorSlashed {
+                    validator: offender,
+                    reason: format!("equivocation in round {}", evidence.round()),
+                    penalty_height: block.height,
+                });
+            }
+        }
+        let penalties = &self.penalties;
+        self.evidence_pool
+            .retain(|e| !penalties.is_proof_processed(&e.offense_id()));
+    }
+
+    /// Re-apply the evidence of a block read back from the ledger (startup),
+    /// without announcing it again.
+    pub fn restore_evidence(&mut self, block: &Block) {
+        self.apply_evidence(block, false);
+    }
+
+    fn publish(&self, event: BlockEvent) {
+        if let Some(bus) = &self.event_bus {
+            bus.publish(event);
+        }
+    }
+
+    /// Whether `vote` belongs to the phase the current round is collecting.
+    fn vote_applies_now(&self, vote: &VoteMessage) -> bool {
+        let expected = match self.manager.round_state() {
+            Some(RoundState::Preparing) => BftPhase::Prepare,
+            Some(RoundState::PreCommitting) => BftPhase::PreCommit,
+            Some(RoundState::Committing) => BftPhase::Commit,
+            _ => return false,
+        };
+        vote.phase == expected
+    }
+
+    fn buffer_vote(&mut self, vote: VoteMessage) {
+        let stale = match self.manager.round_state() {
+            Some(RoundState::PreCommitting) => {
+                vote.round == self.manager.current_round() && vote.phase == BftPhase::Prepare
+            }
+            Some(RoundState::Committing) => {
+                vote.round == self.manager.current_round() && vote.phase != BftPhase::Commit
+            }
+            Some(RoundState::Decided) | Some(RoundState::Failed) => {
+                vote.round == self.manager.current_round()
+            }
+            _ => false,
+        };
+        if !stale && self.pending_votes.len() < MAX_PENDING_VOTES {
+            self.pending_votes.push(vote);
+        }
+    }
+
+    fn replay_pending_votes(&mut self) {
+        let current = self.manager.current_round();
+        let pending = std::mem::take(&mut self.pending_votes);
+        let (now, later): (Vec<_>, Vec<_>) = pending.into_iter().partition(|v| v.round == current);
+        self.pending_votes = later;
+        for vote in now {
+            self.on_vote(vote);
+        }
+    }
+
+    fn on_commit_qc(&mut self, qc: QuorumCertificate) {
+        if qc.round < self.manager.current_round()
+            || !validate_commit_qc_with(&qc, &self.validators, self.verifier.clone())
+        {
+            return;
+        }
+        if qc.round == self.manager.current_round() && self.proposals.contains_key(&qc.block_hash) {
+            self.decide(qc.block_hash, qc);
+            return;
+        }
+        // We missed the proposal: skip to the next round and pick the block
+        // up from the ledger once it arrives.
+        log::warn!(
+            "BFT commit QC for round {} without local proposal; waiting for block sync",
+            qc.round
+        );
+        self.manager.start_round(qc.round + 1);
+        self.enter_round();
+    }
+
+    /// Count a signed timeout from a validator of the current epoch.
+    fn on_timeout(&mut self, timeout: TimeoutMessage) {
+        if !self.validators.contains(&timeout.voter_id) {
+            return;
+        }
+        let action = self.manager.process_timeout(timeout);
+        self.handle_action(action);
+    }
+
+    fn on_timeout_certificate(&mut self, tc: TimeoutCertificate) {
+        let action = self.manager.on_timeout_certificate(tc);
+        self.handle_action(action);
+    }
+
+    /// Sign a timeout, broadcast it as a view change, and count it locally.
+    fn send_timeout(&mut self, mut timeout: TimeoutMessage) {
+        timeout.signature = match self.signer.sign(&timeout.payload()) {
+            Ok(sig) => sig,
+            Err(e) => {
+                log::error!("BFT timeout signing failed: {e}");
+                return;
+            }
+        };
+        self.outbox.push(Message::BftViewChange {
+            timed_out_round: timeout.round,
+            new_round: timeout.round + 1,
+            voter_id: timeout.voter_id.clone(),
+            highest_qc: timeout.high_qc.clone(),
+            signature: timeout.signature.clone(),
+        });
+        self.on_timeout(timeout);
+    }
+
+    /// A timeout certificate moved us to a new round: take over its highest
+    /// QC if we hold that block, and as leader pass the certificate on so
+    /// lagging validators can follow.
+    fn on_new_round_from_certificate(&mut self) {
+        if let Some(tc) = self.manager.last_timeout_certificate().cloned() {
+            if let Some(qc) = tc.high_qc() {
+                if self.candidate(&qc.block_hash).is_some() {
+                    self.manager.update_high_qc(qc.clone());
+                }
+            }
+            if self.manager.is_current_leader() {
+                self.outbox.push(Message::BftTimeoutCertificate(tc));
+            }
+        }
+        self.enter_round();
+    }
+
+    /// Record a block committed elsewhere (e.g. received through block sync)
+    /// so this node continues the chain from it.
+    pub fn observe_block(&mut self, block: &Block) {
+        if block.height < self.next_height {
+            return;
+        }
+        let Some(qc) = block.commit_qc.as_ref() else {
+            return;
+        };
+        let hash = super::block_hash_for_signing(block);
+        if qc.block_hash != hash
+            || self
+                .epochs
+                .validate_commit_qc(
+                    qc,
+                    block.height,
+                    ScheduledKeyVerifier::at(self.keys.clone(), block.height),
+                )
+                .is_err()
+        {
+            return;
+        }
+        self.advance_height(block.height, hash);
+        self.last_config.resume(block.last_config);
+        self.drop_committed_txs(&block.transactions);
+        self.apply_evidence(block, true);
+        let handoff = block.next_validator_set.is_some();
+        if let Some(set) = block.next_validator_set.clone() {
+            self.adopt_validator_set(set);
+        }
+        // Restart the round on a handoff so it runs with the new set.
+        let current = self.manager.current_round();
+        if handoff || qc.round >= current {
+            self.manager.start_round(current.max(qc.round + 1));
+            self.enter_round();
+        }
+    }
+
+    fn handle_action(&mut self, action: ManagerAction) {
+        match action {
+            ManagerAction::Round(action) => self.handle_round_action(action),
+            ManagerAction::SendTimeout(timeout) => self.send_timeout(timeout),
+            // Only timeout certificates start rounds through the manager's
+            // actions; other round changes call `enter_round` directly.
+            ManagerAction::NewRound { .. } => self.on_new_round_from_certificate(),
+            ManagerAction::None => {}
+        }
+    }
+
+    fn handle_round_action(&mut self, action: RoundAction) {
+        match action {
+            RoundAction::BroadcastProposal { block_hash } => {
+                let Some(block) = self.proposals.get(&block_hash) else {
+                    return;
+                };
+                let Ok(block_data) = serde_json::to_vec(block) else {
+                    return;
+                };
+                self.outbox.push(Message::BftProposal {
+                    round: self.manager.current_round(),
+                    block_hash,
+                    leader_id: self.node_id.clone(),
+                    block_data,
+                    justify: self.manager.safety().high_qc().cloned(),
+                });
+                // The leader votes for its own proposal.
+                self.cast_vote(BftPhase::Prepare, block_hash);
+            }
+            RoundAction::SendVote(vote) => self.cast_vote(vote.phase, vote.block_hash),
+            RoundAction::PhaseComplete { phase, qc } => {
+                let next = match phase {
+                    BftPhase::Prepare => BftPhase::PreCommit,
+                    BftPhase::PreCommit => BftPhase::Commit,
+                    _ => return,
+                };
+                self.cast_vote(next, qc.block_hash);
+                self.replay_pending_votes();
+            }
+            RoundAction::Decide {
+                block_hash,
+                commit_qc,
+                ..
+            } => {
+                self.outbox
+                    .push(Message::BftQuorumCertificate(commit_qc.clone()));
+                self.decide(block_hash, commit_qc);
+            }
+            RoundAction::None => {}
+        }
+    }
+
+    /// Sign a vote, broadcast it, and count it locally.
+    fn cast_vote(&mut self, phase: BftPhase, block_hash: [u8; 32]) {
+        let round = self.manager.current_round();
+        let payload = VoteMessage::signing_payload(phase, &block_hash, round);
+        let signature = match self.signer.sign(&payload) {
+            Ok(sig) => sig,
+            Err(e) => {
+                log::error!("BFT vote signing failed: {e}");
+                return;
+            }
+        };
+        let vote = VoteMessage {
+            block_hash,
+            round,
+            phase,
+            voter_id: self.node_id.clone(),
+            signature,
+        };
+        self.outbox.push(Message::BftVote(vote.clone()));
+        self.on_vote(vote);
+    }
+
+    fn decide(&mut self, block_hash: [u8; 32], commit_qc: QuorumCertificate) {
+        let Some(mut block) = self.proposals.remove(&block_hash) else {
+            return;
+        };
+        log::info!(
+            "BFT decided block {} in round {} ({} txs, {} commit votes)",
+            block.height,
+            commit_qc.round,
+            block.transactions.len(),
+            commit_qc.votes.len()
+        );
+        block.commit_qc = Some(commit_qc);
+        self.advance_height(block.height, block_hash);
+        self.last_config.resume(block.last_config);
+        self.drop_committed_txs(&block.transactions);
+        self.apply_evidence(&block, true);
+        let handoff = block.next_validator_set.clone();
+        self.decided.push_back(block);
+        if self.decided.len() > MAX_DECIDED_BACKLOG {
+            self.decided.pop_front();
+        }
+        if let Some(set) = handoff {
+            self.adopt_validator_set(set);
+        }
+        self.manager.advance_after_decide();
+        self.enter_round();
+    }
+
+    /// The validator set the block at `height` must hand off: `Some` on the
+    /// last block of an epoch, `None` otherwise.
+    fn expected_handoff(&self, height: u64) -> Option<ValidatorSet> {
+        if !self.epoch_config.is_epoch_boundary(height) {
+            return None;
+        }
+        let epoch = self.epoch_config.epoch_for_height(height) + 1;
+        Some(match &self.staking {
+            Some(staking) => committee_from_staking(
+                staking,
+                &self.epoch_config.dpos,
+                epoch,
+                height + 1,
+                &self.validators,
+            ),
+            None => ValidatorSet {
+                epoch,
+                start_height: height + 1,
+                validators: self.validators.clone(),
+            },
+        })
+    }
+
+    /// Switch to a committed validator set. Rounds started from now on use it.
+    pub fn adopt_validator_set(&mut self, set: ValidatorSet) {
+        if let Err(e) = self.epochs.record(set.clone()) {
+            log::warn!("BFT validator set handoff rejected: {e}");
+            return;
+        }
+        if set.validators != self.validators {
+            log::info!(
+                "BFT epoch {} from height {}: {} validators{}",
+                set.epoch,
+                set.start_height,
+                set.validators.len(),
+                if set.validators.contains(&self.node_id) {
+                    ""
+                } else {
+                    " (this node is not a member)"
+                }
+            );
+        }
+        self.validators = set.validators.clone();
+        self.manager.set_validators(set.validators);
+    }
+
+    fn drop_committed_txs(&mut self, tx_ids: &[String]) {
+        let committed: HashSet<&String> = tx_ids.iter().collect();
+        self.mempool.retain(|tx| !committed.contains(&tx.id));
+        self.config_pool
+            .retain(|tx| !committed.contains(&tx.ledger_tx_id()));
+    }
+
+    /// Reset per-round state after the round number changed.
+    fn enter_round(&mut self) {
+        let current = self.manager.current_round();
+        self.round_started_ms = self.now_ms;
+        self.proposals.clear();
+        self.pending_votes.retain(|v| v.round >= current);
+        self.equivocation.prune_votes(current);
+        self.future_proposals = self.future_proposals.split_off(&(current, String::new()));
+        let leader = self.manager.current_leader().to_string();
+        if let Some(p) = self.future_proposals.remove(&(current, leader)) {
+            self.accept_proposal(p.block_hash, p.leader_id, p.block, p.justify);
+        }
+        self.replay_pending_votes();
+        self.maybe_propose();
+    }
+
+    fn remember_candidate(&mut self, block_hash: [u8; 32], block: &Block) {
+        if self.candidate(&block_hash).is_some() {
+            return;
+        }
+        self.candidates.push_back((block_hash, block.clone()));
+        if self.candidates.len() > MAX_CANDIDATE_BLOCKS {
+            self.candidates.pop_front();
+        }
+    }
+
+    fn candidate(&self, block_hash: &[u8; 32]) -> Option<&Block> {
+        self.candidates
+            .iter()
+            .find(|(hash, _)| hash == block_hash)
+            .map(|(_, block)| block)
+    }
+
+    /// Propose a block if this node leads the current round and has work.
+    ///
+    /// With a Prepare QC at this height the leader must re-propose that QC's
+    /// block (re-signed as its own); otherwise it cuts a fresh batch.
+    fn maybe_propose(&mut self) {
+        if !self.manager.is_current_leader()
+            || self.manager.round_state() != Some(RoundState::AwaitingProposal)
+        {
+            return;
+        }
+        let mut block = match self.manager.safety().high_qc() {
+            Some(qc) => match self.candidate(&qc.block_hash) {
+                Some(block) => block.clone(),
+                None => {
+                    log::debug!(
+                        "BFT round {}: block of the highest QC is unknown, cannot propose",
+                        self.manager.current_round()
+                    );
+                    return;
+                }
+            },
+            None if !self.config_pool.is_empty() => self.config_proposal(),
+            None if self.mempool.is_empty() && self.evidence_pool.is_empty() => return,
+            None => {
+                let evidence: Vec<EquivocationEvidence> = self
+                    .evidence_pool
+                    .iter()
+                    .take(MAX_BLOCK_EVIDENCE)
+                    .cloned()
+                    .collect();
+                let (count, _) = self
+                    .block_limits
+                    .batch_len(self.mempool.iter().map(tx_size_bytes), self.max_batch_size);
+                let tx_ids: Vec<String> = evidence
+                    .iter()
+                    .map(EquivocationEvidence::tx_id)
+                    .chain(self.mempool.iter().take(count).map(|tx| tx.id.clone()))
+                    .collect();
+                Block {
+                    height: self.next_height,
+                    timestamp: self.now_ms / 1000,
+                    parent_hash: self.parent_hash,
+                    merkle_root: tx_merkle_root(&tx_ids),
+                    transactions: tx_ids,
+                    proposer: self.node_id.clone(),
+                    signature: vec![0u8; 64],
+                    signature_algorithm: Default::default(),
+                    endorsements: vec![],
+                    secondary_signature: None,
+                    secondary_signature_algorithm: None,
+                    hash_algorithm: Default::default(),
+                    orderer_signature: None,
+                    commit_qc: None,
+                    next_validator_set: self.expected_handoff(self.next_height),
+                    evidence,
+                    config_tx: None,
+                    last_config: 0,
+                    state_root: [0u8; 32],
+                    private_data_purges: vec![],
+                }
+            }
+        };
+        // The proposer is not part of the block hash, so a re-proposed block
+        // keeps its hash under the new leader's signature.
+        block.proposer = self.node_id.clone();
+        block.last_config = self.last_config.expected(&block);
+        super::sign_block_with_provider(&mut block, self.signer.as_ref());
+        let block_hash = super::block_hash_for_signing(&block);
+        self.remember_candidate(block_hash, &block);
+        self.proposals.insert(block_hash, block);
+        let action = self
+            .manager
+            .process_event(RoundEvent::StartAsLeader { block_hash });
+        self.handle_action(action);
+        self.replay_pending_votes();
+    }
+
+    /// A config block for the oldest pending config transaction.
+    fn config_proposal(&self) -> Block {
+        let tx = self
+            .config_pool
+            .front()
+            .cloned()
+            .expect("config pool is not empty");
+        let mut block = config_block(self.next_height, &self.node_id, tx);
+        block.timestamp = self.now_ms / 1000;
+        block.parent_hash = self.parent_hash;
+        block.merkle_root = tx_merkle_root(&block.transactions);
+        block.next_validator_set = self.expected_handoff(self.next_height);
+        block
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identity::signing::SoftwareSigningProvider;
+
+    fn make_tx(id: &str) -> Transaction {
+        Transaction {
+            id: id.to_string(),
+            block_height: 0,
+            timestamp: 0,
+            input_did: "did:example:alice".to_string(),
+            output_recipient: "did:example:bob".to_string(),
+            amount: 1,
+            state: "pending".to_string(),
+        }
+    }
+
+    fn cluster(n: usize) -> Vec<BftNode> {
+        cluster_of(
+            (0..n)
+                .map(|_| Arc::new(SoftwareSigningProvider::generate()) as Arc<dyn SigningProvider>)
+                .collect(),
+        )
+    }
+
+    fn cluster_of(signers: Vec<Arc<dyn SigningProvider>>) -> Vec<BftNode> {
+        let validators: Vec<String> = signers.iter().map(|s| validator_id(s.as_ref())).collect();
+        let config = RoundManagerConfig {
+            base_timeout_ms: 100,
+            max_timeout_ms: 1_000,
+        };
+        let mut nodes: Vec<BftNode> = signers
+            .into_iter()
+            .map(|s| BftNode::new(s, validators.clone(), config.clone(), 10))
+            .collect();
+        for node in nodes.iter_mut() {
+            node.start(0);
+        }
+        nodes
+    }
+
+    /// Deliver every outbound message to every other node until quiet.
+    fn route(nodes: &mut [BftNode]) {
+        loop {
+            let mut sent = false;
+            for i in 0..nodes.len() {
+                for msg in nodes[i].take_outbound() {
+                    sent = true;
+                    for (j, node) in nodes.iter_mut().enumerate() {
+                        if i != j {
+                            node.handle_message(msg.clone());
+                        }
+                    }
+                }
+            }
+            if !sent {
+                return;
+            }
+        }
+    }
+
+    #[test]
+    fn public_key_verifier_checks_ed25519_votes() {
+        let signer = SoftwareSigningProvider::generate();
+        let payload = VoteMessage::signing_payload(BftPhase::Prepare, &[7u8; 32], 3);
+        let sig = signer.sign(&payload).unwrap();
+        let id = validator_id(&signer);
+        assert!(PublicKeyVerifier.verify(&id, &payload, &sig));
+        assert!(!PublicKeyVerifier.verify(&id, &payload, &[0u8; 64]));
+        assert!(!PublicKeyVerifier.verify("not-hex", &payload, &sig));
+    }
+
+    #[test]
+    fn four_nodes_decide_block_with_commit_qc() {
+        let mut nodes = cluster(4);
+        nodes[1].submit_tx(make_tx("tx-1"));
+        route(&mut nodes);
+
+        let validators = nodes[0].validators().to_vec();
+        let mut hashes = HashSet::new();
+        for node in nodes.iter_mut() {
+            let block = node.pop_decided().expect("block decided");
+            assert_eq!(block.height, 1);
+            assert_eq!(block.transactions, vec!["tx-1".to_string()]);
+            let qc = block.commit_qc.as_ref().unwrap();
+            assert!(validate_commit_qc(qc, &validators));
+            hashes.insert(crate::ordering::block_hash_for_signing(&block));
+            assert_eq!(node.mempool_len(), 0);
+            assert_eq!(node.current_round(), 1);
+        }
+        assert_eq!(hashes.len(), 1);
+    }
+
+    #[test]
+    fn config_tx_is_decided_in_a_block_of_its_own() {
+        use crate::channel::config::ConfigUpdateType;
+
+        let mut nodes = cluster(4);
+        let config_tx = ConfigTransaction {
+            tx_id: "cfg-1".to_string(),
+            channel_id: "ch1".to_string(),
+            updates: vec![ConfigUpdateType::SetBatchSize(5)],
+            signatures: vec![],
+            created_at: 0,
+        };
+        nodes[2].submit_config_tx(config_tx.clone());
+        nodes[1].submit_tx(make_tx("tx-1"));
+        route(&mut nodes);
+
+        for node in nodes.iter_mut() {
+            let mut blocks = Vec::new();
+            while let Some(block) = node.pop_decided() {
+                blocks.push(block);
+            }
+            assert_eq!(blocks.len(), 2);
+            let config = blocks
+                .iter()
+                .find(|b| b.config_tx.is_some())
+                .expect("config block decided");
+            assert_eq!(config.transactions, vec![config_tx.ledger_tx_id()]);
+            assert!(blocks
+                .iter()
+                .any(|b| b.transactions == vec!["tx-1".to_string()]));
+            assert_eq!(node.config_pool_len(), 0);
+        }
+    }
+
+    #[test]
+    fn consecutive_blocks_chain_parent_hashes() {
+        let mut nodes = cluster(4);
+        for i in 0..3 {
+            nodes[i].submit_tx(make_tx(&format!("tx-{i}")));
+            route(&mut nodes);
+        }
+        let blocks: Vec<Block> = std::iter::from_fn(|| nodes[2].pop_decided()).collect();
+        assert_eq!(blocks.len(), 3);
+        for pair in blocks.windows(2) {
+            assert_eq!(pair[1].height, pair[0].height + 1);
+            assert_eq!(
+                pair[1].parent_hash,
+                crate::ordering::block_hash_for_signing(&pair[0])
+            );
+        }
+    }
+
+    #[test]
+    fn proposal_from_non_leader_is_ignored() {
+        let mut nodes = cluster(4);
+        // Node 1 is not the leader of round 0; its forged proposal gets no votes.
+        let mut block = Block {
+            height: 1,
+            timestamp: 0,
+            parent_hash: [0u8; 32],
+            merkle_root: tx_merkle_root(&["tx-x".to_string()]),
+            transactions: vec!["tx-x".to_string()],
+            proposer: nodes[1].node_id().to_string(),
+            signature: vec![],
+            signature_algorithm: Default::default(),
+            endorsements: vec![],
+            secondary_signature: None,
+            secondary_signature_algorithm: None,
+            hash_algorithm: Default::default(),
+            orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: Vec::new(),
+            config_tx: None,
+            last_config: 0,
+            state_root: [0u8; 32],
+            private_data_purges: vec![],
+        };
+        block.signature = vec![1u8; 64];
+        let msg = Message::BftProposal {
+            round: 0,
+            block_hash: crate::ordering::block_hash_for_signing(&block),
+            leader_id: nodes[1].node_id().to_string(),
+            block_data: serde_json::to_vec(&block).unwrap(),
+            justify: None,
+        };
+        nodes[2].handle_message(msg);
+        assert!(nodes[2].take_outbound().is_empty());
+    }
+
+    #[test]
+    fn gossiped_transactions_are_accepted_only_from_validators() {
+        let mut nodes = cluster(4);
+        let outsider = SoftwareSigningProvider::generate();
+        let tx = make_tx("tx-gossip");
+        let signed_by = |signer: &dyn SigningProvider| GossipedTransaction {
+            tx: tx.clone(),
+            sender: validator_id(signer),
+            signature: signer
+                .sign(&GossipedTransaction::signing_payload(&tx))
+                .unwrap(),
+            endorsed: None,
+        };
+
+        nodes[1].handle_message(Message::BftTransaction(signed_by(&outsider)));
+        assert_eq!(nodes[1].mempool_len(), 0);
+
+        let mut forged = signed_by(&outsider);
+        forged.sender = nodes[2].node_id().to_string();
+        assert!(nodes[1].verify_gossip(&forged).is_err());
+        nodes[1].handle_message(Message::BftTransaction(forged));
+        assert_eq!(nodes[1].mempool_len(), 0);
+
+        nodes[2].submit_tx(tx.clone());
+        let gossip = nodes[2]
+            .take_outbound()
+            .into_iter()
+            .find_map(|msg| match msg {
+                Message::BftTransaction(g) => Some(g),
+                _ => None,
+            })
+            .expect("transaction gossiped");
+        assert_eq!(gossip.sender, nodes[2].node_id());
+        nodes[1].handle_message(Message::BftTransaction(gossip));
+        assert_eq!(nodes[1].mempool_len(), 1);
+    }
+
+    #[test]
+    fn endorsed_gossip_must_wrap_the_gossiped_transaction() {
+        use crate::transaction::proposal::TransactionProposal;
+        use crate::transaction::rwset::ReadWriteSet;
+
+        let mut nodes = cluster(4);
+        let etx = EndorsedTransaction {
+            proposal: TransactionProposal {
+                tx: make_tx("tx-endorsed"),
+                creator_did: String::new(),
+                creator_signature: Vec::new(),
+                signature_algorithm: Default::default(),
+                rwset: ReadWriteSet::default(),
+            },
+            endorsements: Vec::new(),
+            rwset: ReadWriteSet::default(),
+        };
+        nodes[2].submit_endorsed_tx(etx);
+        let gossip = nodes[2]
+            .take_outbound()
+            .into_iter()
+            .find_map(|msg| match msg {
+                Message::BftTransaction(g) => Some(g),
+                _ => None,
+            })
+            .expect("transaction gossiped");
+        assert!(gossip.endorsed.is_some());
+        assert!(nodes[1].verify_gossip(&gossip).is_ok());
+
+        let mut swapped = gossip;
+        swapped.endorsed.as_mut().unwrap().proposal.tx = make_tx("tx-other");
+        assert!(nodes[1].verify_gossip(&swapped).is_err());
+    }
+
+    #[test]
+    fn silent_leader_times_out_and_next_leader_proposes() {
+        let mut nodes = cluster(4);
+        // Round 0 leader is node 0; it never hears about the transaction
+        // and never speaks.
+        let tx = make_tx("tx-late");
+        for node in nodes.iter_mut().skip(1) {
+            node.on_transaction(tx.clone());
+        }
+        let mut now = 0;
+        while nodes[1].decided_len() == 0 {
+            now += 50;
+            assert!(now < 5_000, "no block decided after leader failure");
+            for node in nodes.iter_mut().skip(1) {
+                node.tick(now);
+            }
+            let (_, rest) = nodes.split_at_mut(1);
+            route(rest);
+        }
+        let block = nodes[1].pop_decided().unwrap();
+        assert_eq!(block.transactions, vec!["tx-late".to_string()]);
+        assert!(block.commit_qc.unwrap().round >= 1);
+    }
+
+    #[test]
+    fn epoch_boundary_hands_off_to_staked_committee() {
+        let signers: Vec<Arc<dyn SigningProvider>> = (0..5)
+            .map(|_| Arc::new(SoftwareSigningProvider::generate()) as Arc<dyn SigningProvider>)
+            .collect();
+        let ids: Vec<String> = signers.iter().map(|s| validator_id(s.as_ref())).collect();
+        // Validator 0 does not stake; newcomer 4 does.
+        let staking = Arc::new(StakingManager::new(Some(1000), None, None));
+        for id in &ids[1..] {
+            staking.stake(id, 5000, true).unwrap();
+        }
+        let epochs = EpochConfig {
+            epoch_length: 2,
+            ..Default::default()
+        };
+        let mut nodes: Vec<BftNode> = signers
+            .into_iter()
+            .map(|s| {
+                let mut node =
+                    BftNode::new(s, ids[..4].to_vec(), RoundManagerConfig::default(), 10);
+                node.set_epochs(epochs.clone(), Some(staking.clone()));
+                node.start(0);
+                node
+            })
+            .collect();
+
+        for i in 0..4 {
+            nodes[1].submit_tx(make_tx(&format!("tx-{i}")));
+            route(&mut nodes);
+        }
+
+        let blocks: Vec<Block> = std::iter::from_fn(|| nodes[4].pop_decided()).collect();
+        assert_eq!(blocks.len(), 4);
+        let handoff = blocks[1]
+            .next_validator_set
+            .as_ref()
+            .expect("handoff block");
+        assert_eq!((handoff.epoch, handoff.start_height), (1, 3));
+        let mut expected = ids[1..].to_vec();
+        expected.sort();
+        assert_eq!(handoff.validators, expected);
+        assert!(blocks[0].next_validator_set.is_none());
+
+        for block in &blocks {
+            let qc = block.commit_qc.as_ref().unwrap();
+            let voters: HashSet<&str> = qc.votes.iter().map(|v| v.voter_id.as_str()).collect();
+            if block.height <= 2 {
+                assert!(!voters.contains(ids[4].as_str()));
+            } else {
+                assert!(!voters.contains(ids[0].as_str()));
+            }
+            assert!(nodes[4]
+                .validator_sets()
+                .validate_commit_qc(qc, block.height, PublicKeyVerifier)
+                .is_ok());
+        }
+        assert_eq!(nodes[0].validators(), expected.as_slice());
+    }
+
+    #[test]
+    fn idle_node_does_not_time_out() {
+        let mut nodes = cluster(4);
+        nodes[0].tick(60_000);
+        assert_eq!(nodes[0].current_round(), 0);
+        assert!(nodes[0].take_outbound().is_empty());
+    }
+
+    #[test]
+    fn double_vote_is_committed_as_evidence_and_slashed() {
+        let mut nodes = cluster(4);
+        let stakes: Vec<Arc<StakingManager>> = nodes
+            .iter()
+            .map(|_| {
+                let staking = Arc::new(StakingManager::new(Some(1000), None, None));
+                for node in &nodes {
+                    staking.stake(node.node_id(), 10_000, true).unwrap();
+                }
+                staking
+            })
+            .collect();
+        for (node, staking) in nodes.iter_mut().zip(&stakes) {
+            node.set_epochs(EpochConfig::default(), Some(staking.clone()));
+        }
+        let bus = Arc::new(EventBus::new());
+        let mut events = bus.subscribe();
+        nodes[1].set_event_bus(bus);
+
+        // Validator 3 signs a Prepare vote for a block nobody proposed, then
+        // votes for the real proposal of the same round.
+        let offender = nodes[3].node_id().to_string();
+        let payload = VoteMessage::signing_payload(BftPhase::Prepare, &[0xbb; 32], 0);
+        let conflicting = VoteMessage {
+            block_hash: [0xbb; 32],
+            round: 0,
+            phase: BftPhase::Prepare,
+            voter_id: offender.clone(),
+            signature: nodes[3].signer.sign(&payload).unwrap(),
+        };
+        nodes[1].handle_message(Message::BftVote(conflicting));
+        nodes[1].submit_tx(make_tx("tx-1"));
+        route(&mut nodes);
+
+        for (node, staking) in nodes.iter_mut().zip(&stakes) {
+            let blocks: Vec<Block> = std::iter::from_fn(|| node.pop_decided()).collect();
+            assert_eq!(blocks.len(), 2);
+            let evidence = &blocks[1].evidence;
+            assert_eq!(evidence.len(), 1);
+            assert_eq!(evidence[0].offender(), offender);
+            assert_eq!(blocks[1].transactions, vec![evidence[0].tx_id()]);
+
+            assert!(node.penalties().is_active_penalty(&offender, 2));
+            assert_eq!(node.penalties().total_records(), 1);
+            assert_eq!(
+                staking.get_validator(&offender).unwrap().staked_amount,
+                9_500
+            );
+            assert!(node.evidence_pool.is_empty());
+        }
+        assert!(matches!(
+            events.try_recv(),
+            Ok(BlockEvent::EquivocationDetected { slot: 0, .. })
+        ));
+        match events.try_recv() {
+            Ok(BlockEvent::ValidatorSlashed {
+                validator,
+                penalty_height,
+                ..
+            }) => assert_eq!((validator, penalty_height), (offender, 2)),
+            other => panic!("expected ValidatorSlashed, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn proposal_with_unlisted_evidence_is_rejected() {
+        let mut nodes = cluster(4);
+        let offender = nodes[3].node_id().to_string();
+        let votes: Vec<VoteMessage> = [[1u8; 32], [2u8; 32]]
+            .iter()
+            .map(|hash| VoteMessage {
+                block_hash: *hash,
+                round: 0,
+                phase: BftPhase::Prepare,
+                voter_id: offender.clone(),
+                signature: nodes[3]
+                    .signer
+                    .sign(&VoteMessage::signing_payload(BftPhase::Prepare, hash, 0))
+                    .unwrap(),
+            })
+            .collect();
+        let evidence =
+            EquivocationEvidence::from_votes(votes[0].clone(), votes[1].clone()).unwrap();
+        let mut block = Block {
+            height: 1,
+            timestamp: 0,
+            parent_hash: [0u8; 32],
+            merkle_root: tx_merkle_root(&["tx-x".to_string()]),
+            transactions: vec!["tx-x".to_string()],
+            proposer: nodes[0].node_id().to_string(),
+            signature: vec![],
+            signature_algorithm: Default::default(),
+            endorsements: vec![],
+            secondary_signature: None,
+            secondary_signature_algorithm: None,
+            hash_algorithm: Default::default(),
+            orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: vec![evidence],
+            config_tx: None,
+            last_config: 0,
+            state_root: [0u8; 32],
+            private_data_purges: vec![],
+        };
+        crate::ordering::sign_block_with_provider(&mut block, nodes[0].signer.as_ref());
+        let msg = Message::BftProposal {
+            round: 0,
+            block_hash: crate::ordering::block_hash_for_signing(&block),
+            leader_id: nodes[0].node_id().to_string(),
+            block_data: serde_json::to_vec(&block).unwrap(),
+            justify: None,
+        };
+        nodes[2].handle_message(msg);
+        assert!(nodes[2].take_outbound().is_empty());
+    }
+
+    #[test]
+    fn resumed_node_starts_after_committed_round() {
+        let mut nodes = cluster(4);
+        nodes[0].resume_after_round(6);
+        nodes[0].start(0);
+        assert_eq!(nodes[0].current_round(), 7);
+    }
+
+    #[test]
+    fn rotated_validator_key_signs_from_activation_height() {
+        let signers: Vec<Arc<dyn SigningProvider>> = (0..4)
+            .map(|_| Arc::new(SoftwareSigningProvider::generate()) as Arc<dyn SigningProvider>)
+            .collect();
+        let old = signers[0].clone();
+        let new: Arc<dyn SigningProvider> = Arc::new(SoftwareSigningProvider::generate());
+        let id = validator_id(old.as_ref());
+        let rotation = KeyRotation::signed(&id, old.as_ref(), new.as_ref(), 2, 0).unwrap();
+
+        let mut nodes = cluster_of(signers);
+        for node in nodes.iter_mut() {
+            node.record_key_rotation(rotation.clone()).unwrap();
+        }
+        nodes[0].rotate_signer_at(new.clone(), 2);
+        assert!(nodes[1].record_key_rotation(rotation).is_err());
+
+        for i in 0..4 {
+            nodes[i].submit_tx(make_tx(&format!("tx-{i}")));
+            route(&mut nodes);
+        }
+        let blocks: Vec<Block> = std::iter::from_fn(|| nodes[3].pop_decided()).collect();
+        assert_eq!(blocks.len(), 4);
+        assert_eq!(nodes[0].node_id(), id);
+
+        let validators = nodes[3].validators().to_vec();
+        let mut rotated_votes = 0;
+        for block in &blocks {
+            let qc = block.commit_qc.as_ref().unwrap();
+            let verifier = ScheduledKeyVerifier::at(nodes[3].keys.clone(), block.height);
+            assert!(validate_commit_qc_with(qc, &validators, verifier));
+            for vote in qc.votes.iter().filter(|v| v.voter_id == id) {
+                let key = if block.height >= 2 { &new } else { &old };
+                assert!(verify_with_public_key(
+                    &key.public_key(),
+                    &vote.payload(),
+                    &vote.signature
+                ));
+                if block.height >= 2 {
+                    rotated_votes += 1;
+                    // Grace 0: the retired key no longer counts.
+                    assert!(!PublicKeyVerifier.verify(&id, &vote.payload(), &vote.signature));
+                }
+            }
+        }
+        assert!(rotated_votes > 0);
+    }
+}
@@ -0,0 +1,471 @@
+//! Ordering service backed by HotStuff BFT consensus.
+
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::channel::config::{ConfigTransaction, ConfigUpdateType};
+use crate::consensus::backend::{ConsensusBackend, ConsensusMode};
+use crate::consensus::bft::epoch::{EpochConfig, ValidatorSet};
+use crate::consensus::bft::round_manager::RoundManagerConfig;
+use crate::consensus::bft::types::QuorumCertificate;
+use crate::consensus::slashing::PenaltyManager;
+use crate::events::EventBus;
+use crate::identity::key_rotation::KeySchedule;
+use crate::identity::signing::SigningProvider;
+use crate::network::Message;
+use crate::ordering::admission::{AdmissionChain, AdmissionContext, Envelope};
+use crate::ordering::bft_node::BftNode;
+use crate::ordering::BlockSizeLimits;
+use crate::staking::StakingManager;
+use crate::storage::errors::{StorageError, StorageResult};
+use crate::storage::traits::{Block, Transaction};
+use crate::transaction::endorsed::EndorsedTransaction;
+
+/// Default time `cut_block` waits for a round to decide.
+pub const DEFAULT_COMMIT_WAIT_MS: u64 = 5_000;
+
+/// Milliseconds since the Unix epoch.
+pub fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Ordering service backed by a BFT validator set.
+///
+/// Wraps a [`BftNode`]: `submit_tx` adds to the shared mempool (gossiped to
+/// the other validators), and `cut_block` hands out blocks once a commit QC
+/// has been formed for them. Consensus messages flow through
+/// [`handle_message`](Self::handle_message) and
+/// [`take_outbound`](Self::take_outbound), driven by the BFT transport loop.
+pub struct BftOrderingService {
+    pub(crate) node: Arc<Mutex<BftNode>>,
+    decided: Condvar,
+    commit_wait: Duration,
+    admission: AdmissionChain,
+}
+
+impl BftOrderingService {
+    pub fn new(
+        signer: Arc<dyn SigningProvider>,
+        validators: Vec<String>,
+        config: RoundManagerConfig,
+        max_batch_size: usize,
+    ) -> Self {
+        let node = BftNode::new(signer, validators, config, max_batch_size);
+        Self {
+            node: Arc::new(Mutex::new(node)),
+            decided: Condvar::new(),
+            commit_wait: Duration::from_millis(DEFAULT_COMMIT_WAIT_MS),
+            admission: AdmissionChain::standard(),
+        }
+    }
+
+    /// Maximum time `cut_block` blocks waiting for a decided block.
+    pub fn with_commit_wait(mut self, wait: Duration) -> Self {
+        self.commit_wait = wait;
+        self
+    }
+
+    /// Replace the admission filters run on every locally submitted
+    /// transaction (default: [`AdmissionChain::standard`]).
+    pub fn with_admission(mut self, admission: AdmissionChain) -> Self {
+        self.admission = admission;
+        self
+    }
+
+    /// Set the preferred and absolute block byte limits.
+    pub fn with_block_size_limits(self, limits: BlockSizeLimits) -> Self {
+        self.lock().set_block_size_limits(limits);
+        self
+    }
+
+    /// Recompute the validator set from `staking` every epoch.
+    pub fn with_epochs(self, config: EpochConfig, staking: Arc<StakingManager>) -> Self {
+        self.lock().set_epochs(config, Some(staking));
+        self
+    }
+
+    /// Re-apply a validator set handoff read back from the ledger (startup).
+    pub fn restore_validator_set(&self, set: ValidatorSet) {
+        self.lock().adopt_validator_set(set);
+    }
+
+    /// Re-apply the key rotations of the committed channel config (startup).
+    pub fn set_key_schedule(&self, schedule: KeySchedule) {
+        self.lock().set_key_schedule(schedule);
+    }
+
+    /// Sign with `signer` from `activation_height` on, once this validator's
+    /// own key rotation is committed.
+    pub fn rotate_signer_at(&self, signer: Arc<dyn SigningProvider>, activation_height: u64) {
+        self.lock().rotate_signer_at(signer, activation_height);
+    }
+
+    /// Continue the chain from the local ledger tip.
+    pub fn set_ledger_tip(&self, height: u64, block_hash: [u8; 32]) {
+        self.lock().set_ledger_tip(height, block_hash);
+    }
+
+    /// Resume after the round that committed the ledger tip (startup).
+    pub fn resume_after_round(&self, round: u64) {
+        self.lock().resume_after_round(round);
+    }
+
+    /// Re-apply the equivocation evidence of a committed block (startup).
+    pub fn restore_evidence(&self, block: &Block) {
+        self.lock().restore_evidence(block);
+    }
+
+    /// Publish equivocation and slashing events on `bus`.
+    pub fn set_event_bus(&self, bus: Arc<EventBus>) {
+        self.lock().set_event_bus(bus);
+    }
+
+    /// Penalties applied from committed equivocation evidence.
+    pub fn penalties(&self) -> PenaltyManager {
+        self.lock().penalties().clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BftNode> {
+        self.node.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// This validator's ID (hex public key).
+    pub fn node_id(&self) -> String {
+        self.lock().node_id().to_string()
+    }
+
+    /// The validator set.
+    pub fn validators(&self) -> Vec<String> {
+        self.lock().validators().to_vec()
+    }
+
+    /// Current BFT round.
+    pub fn current_round(&self) -> u64 {
+        self.lock().current_round()
+    }
+
+    /// Start the first round at `now_ms`.
+    pub fn start(&self, now_ms: u64) {
+        self.lock().start(now_ms);
+    }
+
+    /// Advance the clock (round timeouts / view changes).
+    pub fn tick(&self, now_ms: u64) {
+        let decided = {
+            let mut node = self.lock();
+            node.tick(now_ms);
+            node.decided_len()
+        };
+        if decided > 0 {
+            self.decided.notify_all();
+        }
+    }
+
+    /// Feed a P2P message. Returns `false` if it is not a BFT message.
+    /// Gossiped transactions go through admission control once their
+    /// sender checks out, like locally submitted ones.
+    pub fn handle_message(&self, msg: Message) -> bool {
+        let (handled, decided) = {
+            let mut node = self.lock();
+            let handled = match msg {
+                Message::BftTransaction(gossip) => {
+                    let admitted = node.verify_gossip(&gossip).and_then(|()| {
+                        let envelope = match &gossip.endorsed {
+                            Some(etx) => Envelope::endorsed(etx),
+                            None => Envelope::plain(&gossip.tx),
+                        };
+                        self.admission
+                            .admit(&envelope, &AdmissionContext::now(node.block_size_limits()))
+                            .map_err(|e| e.to_string())
+                    });
+                    match admitted {
+                        Ok(()) => node.on_transaction(gossip.tx),
+                        Err(e) => log::warn!("BFT: dropping gossiped transaction: {e}"),
+                    }
+                    true
+                }
+                msg => node.handle_message(msg),
+            };
+            (handled, node.decided_len())
+        };
+        if decided > 0 {
+            self.decided.notify_all();
+        }
+        handled
+    }
+
+    /// Adopt a block committed by the validator set (e.g. from block sync).
+    pub fn observe_block(&self, block: &Block) {
+        self.lock().observe_block(block);
+    }
+
+    /// Drain the messages to broadcast to the other validators.
+    pub fn take_outbound(&self) -> Vec<Message> {
+        self.lock().take_outbound()
+    }
+
+    /// Add a transaction to the mempool and gossip it to the validators.
+    /// Transactions refused by admission control are not gossiped.
+    pub fn submit_tx(&self, tx: &Transaction) -> StorageResult<()> {
+        let mut node = self.lock();
+        self.admission.admit(
+            &Envelope::plain(tx),
+            &AdmissionContext::now(node.block_size_limits()),
+        )?;
+        node.submit_tx(tx.clone());
+        Ok(())
+    }
+
+    /// Admit an endorsed transaction and gossip it with its envelope so
+    /// every validator re-checks the creator and endorsements.
+    pub fn submit_endorsed_tx(&self, etx: &EndorsedTransaction) -> StorageResult<()> {
+        let mut node = self.lock();
+        self.admission.admit(
+            &Envelope::endorsed(etx),
+            &AdmissionContext::now(node.block_size_limits()),
+        )?;
+        node.submit_endorsed_tx(etx.clone());
+        Ok(())
+    }
+
+    /// Add a config transaction to the config pool and gossip it.
+    pub fn submit_config_tx(&self, tx: &ConfigTransaction) -> StorageResult<()> {
+        self.lock().submit_config_tx(tx.clone());
+        Ok(())
+    }
+
+    /// Transactions (config transactions included) waiting to be proposed.
+    pub fn pending_count(&self) -> usize {
+        let node = self.lock();
+        node.mempool_len() + node.config_pool_len()
+    }
+
+    /// Highest commit QC formed or accepted by this validator.
+    pub fn highest_qc(&self) -> Option<QuorumCertificate> {
+        self.lock().highest_commit_qc()
+    }
+
+    /// Return the next decided block at or above `height`, waiting up to the
+    /// commit wait for the validators to decide one. The block keeps the
+    /// height, proposer and signature it was decided with; `proposer` is
+    /// ignored. Returns `None` if nothing was decided in time.
+    pub fn cut_block(&self, height: u64, _proposer: &str) -> StorageResult<Option<Block>> {
+        let deadline = Instant::now() + self.commit_wait;
+        let mut node = self.lock();
+        loop {
+            while let Some(block) = node.pop_decided() {
+                // Blocks below `height` already reached the ledger via sync.
+                if block.height >= height {
+                    return Ok(Some(block));
+                }
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(None);
+            }
+            node = self
+                .decided
+                .wait_timeout(node, deadline - now)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
+}
+
+impl super::OrderingBackend for BftOrderingService {
+    fn submit_tx(&self, tx: &Transaction) -> StorageResult<()> {
+        self.submit_tx(tx)
+    }
+
+    fn submit_endorsed_tx(&self, etx: &EndorsedTransaction) -> StorageResult<()> {
+        self.submit_endorsed_tx(etx)
+    }
+
+    fn cut_block(&self, height: u64, proposer: &str) -> StorageResult<Option<Block>> {
+        self.cut_block(height, proposer)
+    }
+
+    fn pending_count(&self) -> usize {
+        self.pending_count()
+    }
+
+    /// Decided blocks are final: their commit QC and the next block's
+    /// `parent_hash` cover the signing hash, so they keep the state root
+    /// they were proposed with.
+    fn seal_block(&self, _block: &mut Block, _state_root: [u8; 32]) -> bool {
+        false
+    }
+
+    fn resume_last_config(&self, height: u64) {
+        self.lock().resume_last_config(height);
+    }
+
+    /// Batch size and max-bytes updates cap the proposals of the following
+    /// rounds.
+    fn apply_config_updates(&self, updates: &[ConfigUpdateType]) -> StorageResult<()> {
+        let mut node = self.lock();
+        for update in updates {
+            match update {
+                ConfigUpdateType::SetBatchSize(size) => node.set_max_batch_size(*size),
+                ConfigUpdateType::SetMaxBytes { .. } => {
+                    let mut limits = node.block_size_limits();
+                    limits.apply(update);
+                    node.set_block_size_limits(limits);
+                }
+                ConfigUpdateType::RotateKey(rotation) => node
+                    .record_key_rotation(rotation.clone())
+                    .map_err(|e| StorageError::Other(e.to_string()))?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn submit_config_tx(&self, tx: &ConfigTransaction) -> StorageResult<()> {
+        self.submit_config_tx(tx)
+    }
+
+    fn rotate_signer_at(&self, signer: Arc<dyn SigningProvider>, activation_height: u64) {
+        self.rotate_signer_at(signer, activation_height);
+    }
+}
+
+impl ConsensusBackend for BftOrderingService {
+    fn submit_tx(&self, tx: &Transaction) -> StorageResult<()> {
+        self.submit_tx(tx)
+    }
+
+    fn cut_block(&self, height: u64, proposer: &str) -> StorageResult<Option<Block>> {
+        self.cut_block(height, proposer)
+    }
+
+    fn pending_count(&self) -> usize {
+        self.pending_count()
+    }
+
+    fn mode(&self) -> ConsensusMode {
+        ConsensusMode::Bft
+    }
+
+    fn highest_qc(&self) -> Option<QuorumCertificate> {
+        self.highest_qc()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identity::signing::SoftwareSigningProvider;
+    use crate::ordering::bft_node::validator_id;
+
+    fn make_tx(id: &str) -> Transaction {
+        Transaction {
+            id: id.to_string(),
+            block_height: 0,
+            timestamp: 0,
+            input_did: "did:bc:alice".to_string(),
+            output_recipient: "did:bc:bob".to_string(),
+            amount: 1,
+            state: "pending".to_string(),
+        }
+    }
+
+    fn services(n: usize) -> Vec<BftOrderingService> {
+        let signers: Vec<Arc<dyn SigningProvider>> = (0..n)
+            .map(|_| Arc::new(SoftwareSigningProvider::generate()) as Arc<dyn SigningProvider>)
+            .collect();
+        let validators: Vec<String> = signers.iter().map(|s| validator_id(s.as_ref())).collect();
+        signers
+            .into_iter()
+            .map(|s| {
+                let svc = BftOrderingService::new(
+                    s,
+                    validators.clone(),
+                    RoundManagerConfig::default(),
+                    100,
+                )
+                .with_commit_wait(Duration::from_millis(10));
+                svc.start(0);
+                svc
+            })
+            .collect()
+    }
+
+    fn route(svcs: &[BftOrderingService]) {
+        loop {
+            let mut sent = false;
+            for (i, svc) in svcs.iter().enumerate() {
+                for msg in svc.take_outbound() {
+                    sent = true;
+                    for (j, other) in svcs.iter().enumerate() {
+                        if i != j {
+                            other.handle_message(msg.clone());
+                        }
+                    }
+                }
+            }
+            if !sent {
+                return;
+            }
+        }
+    }
+
+    #[test]
+    fn cut_block_returns_none_until_decided() {
+        let svcs = services(4);
+        svcs[0].submit_tx(&make_tx("tx-1")).unwrap();
+        assert!(svcs[0].cut_block(1, "gateway").unwrap().is_none());
+
+        route(&svcs);
+        let block = svcs[0].cut_block(1, "gateway").unwrap().unwrap();
+        assert_eq!(block.height, 1);
+        assert!(block.commit_qc.is_some());
+        assert_eq!(svcs[0].highest_qc(), block.commit_qc);
+        assert_eq!(svcs[0].pending_count(), 0);
+    }
+
+    #[test]
+    fn cut_block_skips_blocks_already_in_ledger() {
+        let svcs = services(4);
+        for i in 0..2 {
+            svcs[0].submit_tx(&make_tx(&format!("tx-{i}"))).unwrap();
+            route(&svcs);
+        }
+        let block = svcs[1].cut_block(2, "gateway").unwrap().unwrap();
+        assert_eq!(block.height, 2);
+        assert!(svcs[1].cut_block(3, "gateway").unwrap().is_none());
+    }
+
+    #[test]
+    fn gossiped_transactions_go_through_admission() {
+        use crate::ordering::bft_node::GossipedTransaction;
+
+        let peer = SoftwareSigningProvider::generate();
+        let local: Arc<dyn SigningProvider> = Arc::new(SoftwareSigningProvider::generate());
+        let validators = vec![validator_id(&peer), validator_id(local.as_ref())];
+        let svc = BftOrderingService::new(local, validators, RoundManagerConfig::default(), 100);
+        let gossip = |tx: Transaction| GossipedTransaction {
+            signature: peer
+                .sign(&GossipedTransaction::signing_payload(&tx))
+                .unwrap(),
+            sender: validator_id(&peer),
+            tx,
+            endorsed: None,
+        };
+
+        assert!(svc.handle_message(Message::BftTransaction(gossip(make_tx("tx\u{7}")))));
+        assert_eq!(svc.pending_count(), 0);
+
+        svc.handle_message(Message::BftTransaction(gossip(make_tx("tx-1"))));
+        assert_eq!(svc.pending_count(), 1);
+    }
+
+    #[test]
+    fn backend_reports_bft_mode() {
+        let svcs = services(4);
+        assert_eq!(ConsensusBackend::mode(&svcs[0]), ConsensusMode::Bft);
+    }
+}
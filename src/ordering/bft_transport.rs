@@ -0,0 +1,110 @@
+//! BFT network transport — bridges `BftOrderingService` messages with the P2P layer.
+
+use std::sync::Arc;
+
+use crate::ordering::bft_service::{now_ms, BftOrderingService};
+
+/// A validator in the BFT set: its ID (hex public key), P2P address and,
+/// if given, the orderer org it belongs to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BftPeer {
+    pub validator_id: String,
+    pub address: String,
+    pub org: Option<String>,
+}
+
+/// Parse a `BFT_PEERS` string.
+///
+/// Format: `"{pubkey_hex}@orderer1:8087,{pubkey_hex}@orderer2:8087,..."`,
+/// each entry optionally prefixed with its orderer org as `{org}/`.
+/// The list includes this node; entries without an `@` or with an ID that
+/// is not hex are skipped.
+pub fn parse_bft_peers(s: &str) -> Vec<BftPeer> {
+    s.split(',')
+        .filter_map(|entry| {
+            let (id, addr) = entry.trim().split_once('@')?;
+            let (org, id) = match id.split_once('/') {
+                Some((org, id)) if !org.is_empty() => (Some(org.to_string()), id),
+                Some(_) => return None,
+                None => (None, id),
+            };
+            if id.is_empty() || addr.is_empty() || hex::decode(id).is_err() {
+                return None;
+            }
+            Some(BftPeer {
+                validator_id: id.to_lowercase(),
+                address: addr.to_string(),
+                org,
+            })
+        })
+        .collect()
+}
+
+/// Spawn a background loop that ticks the BFT service every `tick_ms` and
+/// broadcasts its outbound consensus messages to the other validators via
+/// `Node::send_and_wait` (fire-and-forget — we ignore the response).
+/// Messages of a channel-scoped group (`channel_id` set) travel wrapped in
+/// `Message::ChannelOrdering`.
+pub fn start_bft_loop(
+    service: Arc<BftOrderingService>,
+    peers: Vec<BftPeer>,
+    p2p_node: Arc<crate::network::Node>,
+    tick_ms: u64,
+    channel_id: Option<String>,
+) -> tokio::task::JoinHandle<()> {
+    let own_id = service.node_id();
+    let targets: Vec<String> = peers
+        .into_iter()
+        .filter(|p| p.validator_id != own_id)
+        .map(|p| p.address)
+        .collect();
+    tokio::spawn(async move {
+        service.start(now_ms());
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(tick_ms));
+        loop {
+            interval.tick().await;
+
+            service.tick(now_ms());
+            for msg in service.take_outbound() {
+                let msg = crate::ordering::groups::scoped_message(channel_id.as_deref(), msg);
+                for addr in &targets {
+                    let node = p2p_node.clone();
+                    let addr = addr.clone();
+                    let msg = msg.clone();
+                    tokio::spawn(async move {
+                        let _ = node
+                            .send_and_wait(&addr, msg, std::time::Duration::from_secs(2))
+                            .await;
+                    });
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_bft_peers_reads_id_and_address() {
+        let peers = parse_bft_peers(
+            "AB01@orderer1:8087, OrdererB/cd02@orderer2:8087,,bad,zz@x:1,/ef03@x:1",
+        );
+        assert_eq!(
+            peers,
+            vec![
+                BftPeer {
+                    validator_id: "ab01".to_string(),
+                    address: "orderer1:8087".to_string(),
+                    org: None,
+                },
+                BftPeer {
+                    validator_id: "cd02".to_string(),
+                    address: "orderer2:8087".to_string(),
+                    org: Some("OrdererB".to_string()),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,661 @@
+//! Channel-scoped ordering groups.
+//!
+//! An orderer can host one consenter instance per channel so that a busy
+//! channel does not slow down the others. Each group is a solo, Raft or BFT
+//! backend of its own, created together with the channel, with its own log
+//! directory under `<base_dir>/<channel_id>`. It follows the channel's
+//! `orderer_orgs`, `batch_size` and `batch_timeout_ms`.
+//!
+//! Group consensus traffic travels as [`Message::ChannelOrdering`] and is
+//! routed back to the group by [`OrderingGroups::deliver`].
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock, RwLock};
+
+use thiserror::Error;
+
+use crate::channel::config::ChannelConfig;
+use crate::consensus::bft::round_manager::RoundManagerConfig;
+use crate::identity::signing::SigningProvider;
+use crate::network::{Message, Node};
+use crate::ordering::admission::AdmissionChain;
+use crate::ordering::bft_service::BftOrderingService;
+use crate::ordering::bft_transport::BftPeer;
+use crate::ordering::service::OrderingService;
+use crate::ordering::{BlockSizeLimits, OrderingBackend};
+
+/// Tick interval of a channel's Raft group.
+#[cfg(feature = "raft-ordering")]
+const RAFT_TICK_MS: u64 = 100;
+/// Tick interval of a channel's BFT group.
+const BFT_TICK_MS: u64 = 50;
+
+/// Errors produced when creating an ordering group.
+#[derive(Debug, Error)]
+pub enum GroupError {
+    #[error("ordering group for channel '{0}' already exists")]
+    AlreadyExists(String),
+    #[error("cannot create log directory {path}: {reason}")]
+    LogDir { path: String, reason: String },
+    #[error("cannot start consenter for channel '{channel_id}': {reason}")]
+    Consenter { channel_id: String, reason: String },
+}
+
+/// Consensus protocol of the groups an orderer hosts.
+#[derive(Debug, Clone)]
+pub enum GroupKind {
+    /// Single-orderer batching.
+    Solo,
+    /// One Raft cluster per channel. The channel's consenters define the
+    /// cluster; without consenters, the `peers` of the channel's orderer
+    /// orgs (all of them without `orderer_orgs`) and this node.
+    #[cfg(feature = "raft-ordering")]
+    Raft {
+        raft_id: u64,
+        peers: HashMap<u64, String>,
+        /// Orderer org of each peer that declares one.
+        peer_orgs: HashMap<u64, String>,
+    },
+    /// One BFT validator set per channel: the `peers` of the channel's
+    /// orderer orgs (all of them without `orderer_orgs`) and this node.
+    Bft {
+        peers: Vec<BftPeer>,
+        round: RoundManagerConfig,
+    },
+}
+
+enum GroupBackend {
+    Solo(Arc<OrderingService>),
+    #[cfg(feature = "raft-ordering")]
+    Raft {
+        service: Arc<crate::ordering::raft_service::RaftOrderingService>,
+        peer_map: crate::ordering::raft_transport::PeerMap,
+    },
+    Bft {
+        service: Arc<BftOrderingService>,
+        peers: Vec<BftPeer>,
+    },
+}
+
+/// The consenter instance ordering one channel.
+pub struct OrderingGroup {
+    channel_id: String,
+    backend: GroupBackend,
+}
+
+impl OrderingGroup {
+    /// The group's backend, used by the gateway to order the channel's txs.
+    pub fn backend(&self) -> Arc<dyn OrderingBackend> {
+        match &self.backend {
+            GroupBackend::Solo(service) => service.clone(),
+            #[cfg(feature = "raft-ordering")]
+            GroupBackend::Raft { service, .. } => service.clone(),
+            GroupBackend::Bft { service, .. } => service.clone(),
+        }
+    }
+
+    /// Feed a consensus message addressed to this channel. Returns `false`
+    /// if the message does not belong to the group's protocol.
+    pub fn deliver(&self, msg: Message) -> bool {
+        match (&self.backend, msg) {
+            #[cfg(feature = "raft-ordering")]
+            (GroupBackend::Raft { service, .. }, Message::RaftMessage(data)) => {
+                match crate::ordering::raft_transport::decode_raft_msg(&data) {
+                    Ok(raft_msg) => {
+                        let mut node = service.raft_node.lock().unwrap_or_else(|e| e.into_inner());
+                        if let Err(e) = node.step(raft_msg) {
+                            log::warn!("channel '{}' raft step error: {e}", self.channel_id);
+                        }
+                    }
+                    Err(e) => {
+                        log::warn!("channel '{}' raft decode error: {e}", self.channel_id);
+                    }
+                }
+                true
+            }
+            (GroupBackend::Bft { service, .. }, msg) => service.handle_message(msg),
+            _ => false,
+        }
+    }
+
+    /// Spawn the loop that ticks the group and sends its messages to peers.
+    fn start_transport(&self, p2p_node: Arc<Node>) {
+        match &self.backend {
+            GroupBackend::Solo(_) => {}
+            #[cfg(feature = "raft-ordering")]
+            GroupBackend::Raft { service, peer_map } => {
+                crate::ordering::raft_transport::start_raft_tick_loop(
+                    service.raft_node.clone(),
+                    peer_map.clone(),
+                    p2p_node,
+                    RAFT_TICK_MS,
+                    Some(self.channel_id.clone()),
+                );
+            }
+            GroupBackend::Bft { service, peers } => {
+                crate::ordering::bft_transport::start_bft_loop(
+                    service.clone(),
+                    peers.clone(),
+                    p2p_node,
+                    BFT_TICK_MS,
+                    Some(self.channel_id.clone()),
+                );
+            }
+        }
+    }
+}
+
+/// Registry of the ordering groups hosted by this orderer, keyed by channel.
+pub struct OrderingGroups {
+    kind: GroupKind,
+    base_dir: PathBuf,
+    orderer_org: Option<String>,
+    signing_provider: Option<Arc<dyn SigningProvider>>,
+    admission: Option<AdmissionChain>,
+    groups: RwLock<HashMap<String, Arc<OrderingGroup>>>,
+    p2p_node: OnceLock<Arc<Node>>,
+}
+
+impl OrderingGroups {
+    /// Groups of protocol `kind` keeping their logs under `base_dir`.
+    pub fn new(kind: GroupKind, base_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            kind,
+            base_dir: base_dir.into(),
+            orderer_org: None,
+            signing_provider: None,
+            admission: None,
+            groups: RwLock::new(HashMap::new()),
+            p2p_node: OnceLock::new(),
+        }
+    }
+
+    /// Only host channels that list `org` in their `orderer_orgs`.
+    pub fn with_orderer_org(mut self, org: impl Into<String>) -> Self {
+        self.orderer_org = Some(org.into());
+        self
+    }
+
+    /// Sign cut blocks (and BFT votes) with `provider`.
+    pub fn with_signing_provider(mut self, provider: Arc<dyn SigningProvider>) -> Self {
+        self.signing_provider = Some(provider);
+        self
+    }
+
+    /// Run `admission` on the transactions submitted to every group, each
+    /// group with a replay window of its own (default:
+    /// [`AdmissionChain::standard`] per group).
+    pub fn with_admission(mut self, admission: AdmissionChain) -> Self {
+        self.admission = Some(admission);
+        self
+    }
+
+    /// Build from the environment when `ORDERING_GROUPS=true`:
+    /// - `ORDERING_BACKEND` picks the protocol of every group, reusing
+    ///   `RAFT_NODE_ID` / `RAFT_PEERS` or `BFT_PEERS` / `BFT_ROUND_TIMEOUT_MS`
+    /// - `ORG_ID` is the orderer org matched against `orderer_orgs`, as are
+    ///   the `{org}/` prefixes of `RAFT_PEERS` and `BFT_PEERS` entries
+    /// - logs live under `STORAGE_PATH/orderer/<channel_id>`
+    pub fn from_env(signing_provider: Arc<dyn SigningProvider>) -> Option<Self> {
+        if std::env::var("ORDERING_GROUPS")
+            .map(|v| v != "true")
+            .unwrap_or(true)
+        {
+            return None;
+        }
+        let kind = match std::env::var("ORDERING_BACKEND")
+            .unwrap_or_default()
+            .as_str()
+        {
+            #[cfg(feature = "raft-ordering")]
+            "raft" => {
+                let raft_peers = std::env::var("RAFT_PEERS").unwrap_or_default();
+                GroupKind::Raft {
+                    raft_id: std::env::var("RAFT_NODE_ID")
+                        .ok()
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or(1),
+                    peers: crate::ordering::raft_transport::parse_raft_peers(&raft_peers),
+                    peer_orgs: crate::ordering::raft_transport::parse_raft_peer_orgs(&raft_peers),
+                }
+            }
+            "bft" => GroupKind::Bft {
+                peers: crate::ordering::bft_transport::parse_bft_peers(
+                    &std::env::var("BFT_PEERS").unwrap_or_default(),
+                ),
+                round: RoundManagerConfig {
+                    base_timeout_ms: std::env::var("BFT_ROUND_TIMEOUT_MS")
+                        .ok()
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or(3000),
+                    ..Default::default()
+                },
+            },
+            _ => GroupKind::Solo,
+        };
+        let base_dir = PathBuf::from(
+            std::env::var("STORAGE_PATH").unwrap_or_else(|_| "./data/blocks".to_string()),
+        )
+        .join("orderer");
+        let mut groups = Self::new(kind, base_dir).with_signing_provider(signing_provider);
+        if let Ok(org) = std::env::var("ORG_ID") {
+            groups = groups.with_orderer_org(org);
+        }
+        Some(groups)
+    }
+
+    /// Whether this orderer takes part in ordering a channel with `config`.
+    /// A channel without `orderer_orgs` is ordered by every orderer.
+    pub fn hosts(&self, config: &ChannelConfig) -> bool {
+        match &self.orderer_org {
+            Some(org) => config.orderer_orgs.is_empty() || config.orderer_orgs.contains(org),
+            None => true,
+        }
+    }
+
+    /// Create the ordering group of a new channel. Returns `Ok(None)` when
+    /// this orderer is not one of the channel's orderers.
+    pub fn create_group(
+        &self,
+        channel_id: &str,
+        config: &ChannelConfig,
+    ) -> Result<Option<Arc<OrderingGroup>>, GroupError> {
+        let mut groups = self.groups.write().unwrap_or_else(|e| e.into_inner());
+        if groups.contains_key(channel_id) {
+            return Err(GroupError::AlreadyExists(channel_id.to_string()));
+        }
+        if !self.hosts(config) {
+            return Ok(None);
+        }
+
+        let log_dir = self.base_dir.join(channel_id);
+        std::fs::create_dir_all(&log_dir).map_err(|e| GroupError::LogDir {
+            path: log_dir.display().to_string(),
+            reason: e.to_string(),
+        })?;
+        let Some(backend) = self.build_backend(channel_id, config, &log_dir)? else {
+            return Ok(None);
+        };
+
+        let group = Arc::new(OrderingGroup {
+            channel_id: channel_id.to_string(),
+            backend,
+        });
+        if let Some(p2p_node) = self.p2p_node.get() {
+            group.start_transport(p2p_node.clone());
+        }
+        groups.insert(channel_id.to_string(), group.clone());
+        log::info!("Ordering group created for channel '{channel_id}'");
+        Ok(Some(group))
+    }
+
+    fn build_backend(
+        &self,
+        channel_id: &str,
+        config: &ChannelConfig,
+        #[allow(unused_variables)] log_dir: &Path,
+    ) -> Result<Option<GroupBackend>, GroupError> {
+        let consenter_error = |reason: String| GroupError::Consenter {
+            channel_id: channel_id.to_string(),
+            reason,
+        };
+        match &self.kind {
+            GroupKind::Solo => {
+                let mut service =
+                    OrderingService::with_config(config.batch_size, config.batch_timeout_ms)
+                        .with_block_size_limits(BlockSizeLimits::from_config(config));
+                if let Some(provider) = &self.signing_provider {
+                    service = service.with_signing_provider(provider.clone());
+                }
+                if let Some(admission) = &self.admission {
+                    service = service.with_admission(admission.for_channel());
+                }
+                Ok(Some(GroupBackend::Solo(Arc::new(service))))
+            }
+            #[cfg(feature = "raft-ordering")]
+            GroupKind::Raft {
+                raft_id,
+                peers,
+                peer_orgs,
+            } => {
+                // The channel's consenters define its cluster. Without them,
+                // its orderer orgs pick the cluster from `peers`; a peer
+                // without an org only joins channels open to every orderer.
+                let peers: HashMap<u64, String> = if config.consenters.is_empty() {
+                    peers
+                        .iter()
+                        .filter(|(id, _)| {
+                            config.orderer_orgs.is_empty()
+                                || peer_orgs
+                                    .get(*id)
+                                    .is_some_and(|org| config.orderer_orgs.contains(org))
+                        })
+                        .map(|(id, addr)| (*id, addr.clone()))
+                        .collect()
+                } else {
+                    config
+                        .consenters
+                        .iter()
+                        .map(|c| (c.raft_id, c.address.clone()))
+                        .collect()
+                };
+                if !config.consenters.is_empty() && !peers.contains_key(raft_id) {
+                    return Ok(None);
+                }
+                let mut voters: Vec<u64> = if config.consenters.is_empty() {
+                    peers.keys().copied().collect()
+                } else {
+                    config
+                        .consenters
+                        .iter()
+                        .filter(|c| !c.learner)
+                        .map(|c| c.raft_id)
+                        .collect()
+                };
+                // An orderer hosting a channel without consenters votes in it
+                // even if its own `RAFT_PEERS` entry names no org.
+                if voters.is_empty() || (config.consenters.is_empty() && !voters.contains(raft_id))
+                {
+                    voters.push(*raft_id);
+                }
+                let mut service =
+                    crate::ordering::raft_service::RaftOrderingService::new_persistent(
+                        *raft_id,
+                        voters,
+                        config.batch_size,
+                        config.batch_timeout_ms,
+                        &log_dir.join("raft"),
+                    )
+                    .map_err(|e| consenter_error(e.to_string()))?
+                    .with_compaction_policy(
+                        crate::ordering::raft_storage::CompactionPolicy::from_env(),
+                    )
+                    .with_block_size_limits(BlockSizeLimits::from_config(config));
+                if let Some(provider) = &self.signing_provider {
+                    service = service.with_signing_provider(provider.clone());
+                }
+                if let Some(admission) = &self.admission {
+                    service = service.with_admission(admission.for_channel());
+                }
+                let peer_map = Arc::new(std::sync::Mutex::new(peers));
+                service
+                    .raft_node
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .set_peer_map(peer_map.clone());
+                Ok(Some(GroupBackend::Raft {
+                    service: Arc::new(service),
+                    peer_map,
+                }))
+            }
+            GroupKind::Bft { peers, round } => {
+                let signer = self
+                    .signing_provider
+                    .clone()
+                    .ok_or_else(|| consenter_error("BFT groups need a signing provider".into()))?;
+                // The channel's orderer orgs pick its validators; a peer
+                // without an org only joins channels open to every orderer.
+                let peers: Vec<BftPeer> = peers
+                    .iter()
+                    .filter(|p| {
+                        config.orderer_orgs.is_empty()
+                            || p.org
+                                .as_ref()
+                                .is_some_and(|org| config.orderer_orgs.contains(org))
+                    })
+                    .cloned()
+                    .collect();
+                let node_id = crate::ordering::bft_node::validator_id(signer.as_ref());
+                let mut validators: Vec<String> =
+                    peers.iter().map(|p| p.validator_id.clone()).collect();
+                if !validators.contains(&node_id) {
+                    validators.push(node_id);
+                }
+                let mut service =
+                    BftOrderingService::new(signer, validators, round.clone(), config.batch_size)
+                        .with_block_size_limits(BlockSizeLimits::from_config(config));
+                if let Some(admission) = &self.admission {
+                    service = service.with_admission(admission.for_channel());
+                }
+                Ok(Some(GroupBackend::Bft {
+                    service: Arc::new(service),
+                    peers,
+                }))
+            }
+        }
+    }
+
+    /// The group ordering `channel_id`, if this orderer hosts one.
+    pub fn get(&self, channel_id: &str) -> Option<Arc<OrderingGroup>> {
+        self.groups
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(channel_id)
+            .cloned()
+    }
+
+    /// Backend of the group ordering `channel_id`.
+    pub fn backend_for(&self, channel_id: &str) -> Option<Arc<dyn OrderingBackend>> {
+        self.get(channel_id).map(|group| group.backend())
+    }
+
+    /// Route an inbound [`Message::ChannelOrdering`] payload to its group.
+    /// Returns `false` when no local group orders `channel_id`.
+    pub fn deliver(&self, channel_id: &str, msg: Message) -> bool {
+        match self.get(channel_id) {
+            Some(group) => group.deliver(msg),
+            None => false,
+        }
+    }
+
+    /// Attach the P2P node and start the transport loops of every group,
+    /// including the ones created from now on. Later calls are ignored.
+    pub fn start_transport(&self, p2p_node: Arc<Node>) {
+        if self.p2p_node.set(p2p_node.clone()).is_err() {
+            return;
+        }
+        let groups = self.groups.read().unwrap_or_else(|e| e.into_inner());
+        for group in groups.values() {
+            group.start_transport(p2p_node.clone());
+        }
+    }
+}
+
+/// Wrap a consensus message of `channel_id`'s group for the P2P layer;
+/// node-wide messages (`None`) are sent as they are.
+pub fn scoped_message(channel_id: Option<&str>, msg: Message) -> Message {
+    match channel_id {
+        Some(channel_id) => Message::ChannelOrdering {
+            channel_id: channel_id.to_string(),
+            message: Box::new(msg),
+        },
+        None => msg,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::traits::Transaction;
+
+    fn make_tx(id: &str) -> Transaction {
+        Transaction {
+            id: id.to_string(),
+            block_height: 0,
+            timestamp: 0,
+            input_did: "did:bc:alice".to_string(),
+            output_recipient: "did:bc:bob".to_string(),
+            amount: 1,
+            state: "pending".to_string(),
+        }
+    }
+
+    fn config(batch_size: usize, orderer_orgs: &[&str]) -> ChannelConfig {
+        ChannelConfig {
+            batch_size,
+            orderer_orgs: orderer_orgs.iter().map(|o| o.to_string()).collect(),
+            ..ChannelConfig::default()
+        }
+    }
+
+    #[test]
+    fn each_channel_gets_its_own_group_and_log_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let groups = OrderingGroups::new(GroupKind::Solo, dir.path());
+
+        let a = groups
+            .create_group("ch-a", &config(10, &[]))
+            .unwrap()
+            .unwrap();
+        let b = groups
+            .create_group("ch-b", &config(10, &[]))
+            .unwrap()
+            .unwrap();
+        assert!(dir.path().join("ch-a").is_dir());
+        assert!(dir.path().join("ch-b").is_dir());
+
+        // Transactions of one channel never queue behind another's.
+        a.backend().submit_tx(&make_tx("tx-a")).unwrap();
+        assert_eq!(a.backend().pending_count(), 1);
+        assert_eq!(b.backend().pending_count(), 0);
+    }
+
+    #[test]
+    fn group_follows_channel_batch_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let groups = OrderingGroups::new(GroupKind::Solo, dir.path());
+        groups.create_group("ch", &config(2, &[])).unwrap();
+
+        let backend = groups.backend_for("ch").unwrap();
+        for i in 0..5 {
+            backend.submit_tx(&make_tx(&format!("tx{i}"))).unwrap();
+        }
+        assert_eq!(
+            backend
+                .cut_block(1, "o")
+                .unwrap()
+                .unwrap()
+                .transactions
+                .len(),
+            2
+        );
+
+        backend
+            .apply_config_updates(&[crate::channel::config::ConfigUpdateType::SetBatchSize(3)])
+            .unwrap();
+        assert_eq!(
+            backend
+                .cut_block(2, "o")
+                .unwrap()
+                .unwrap()
+                .transactions
+                .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn only_hosts_channels_listing_its_orderer_org() {
+        let dir = tempfile::tempdir().unwrap();
+        let groups = OrderingGroups::new(GroupKind::Solo, dir.path()).with_orderer_org("OrdererA");
+
+        assert!(groups
+            .create_group("theirs", &config(10, &["OrdererB"]))
+            .unwrap()
+            .is_none());
+        assert!(groups
+            .create_group("ours", &config(10, &["OrdererA", "OrdererB"]))
+            .unwrap()
+            .is_some());
+        assert!(groups
+            .create_group("open", &config(10, &[]))
+            .unwrap()
+            .is_some());
+        assert!(groups.get("theirs").is_none());
+        assert!(!dir.path().join("theirs").exists());
+    }
+
+    #[test]
+    fn bft_validators_follow_the_channel_orderer_orgs() {
+        let signer: Arc<dyn SigningProvider> =
+            Arc::new(crate::identity::signing::SoftwareSigningProvider::generate());
+        let own_id = crate::ordering::bft_node::validator_id(signer.as_ref());
+        let peers = crate::ordering::bft_transport::parse_bft_peers(&format!(
+            "OrdererA/{own_id}@a:8087,OrdererA/aa01@a2:8087,OrdererB/bb01@b:8087,cc01@c:8087"
+        ));
+        let dir = tempfile::tempdir().unwrap();
+        let groups = OrderingGroups::new(
+            GroupKind::Bft {
+                peers,
+                round: RoundManagerConfig::default(),
+            },
+            dir.path(),
+        )
+        .with_orderer_org("OrdererA")
+        .with_signing_provider(signer);
+
+        let validators = |channel_id: &str, orderer_orgs: &[&str]| {
+            let group = groups
+                .create_group(channel_id, &config(10, orderer_orgs))
+                .unwrap()
+                .unwrap();
+            match &group.backend {
+                GroupBackend::Bft { service, .. } => {
+                    let mut ids = service.validators();
+                    ids.sort();
+                    ids
+                }
+                _ => panic!("expected a BFT group"),
+            }
+        };
+        let mut only_a = vec![own_id.clone(), "aa01".to_string()];
+        only_a.sort();
+        assert_eq!(validators("ch-a", &["OrdererA"]), only_a);
+
+        let mut both = vec![own_id.clone(), "aa01".to_string(), "bb01".to_string()];
+        both.sort();
+        assert_eq!(validators("ch-ab", &["OrdererA", "OrdererB"]), both);
+
+        let mut open = vec![own_id, "aa01".into(), "bb01".into(), "cc01".into()];
+        open.sort();
+        assert_eq!(validators("ch-open", &[]), open);
+    }
+
+    #[test]
+    fn duplicate_group_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let groups = OrderingGroups::new(GroupKind::Solo, dir.path());
+        groups.create_group("ch", &config(10, &[])).unwrap();
+        assert!(matches!(
+            groups.create_group("ch", &config(10, &[])),
+            Err(GroupError::AlreadyExists(_))
+        ));
+    }
+
+    #[test]
+    fn messages_for_unknown_channels_are_not_delivered() {
+        let dir = tempfile::tempdir().unwrap();
+        let groups = OrderingGroups::new(GroupKind::Solo, dir.path());
+        groups.create_group("ch", &config(10, &[])).unwrap();
+
+        assert!(!groups.deliver("other", Message::Ping));
+        // A solo group takes no consensus messages.
+        assert!(!groups.deliver("ch", Message::Ping));
+    }
+
+    #[test]
+    fn scoped_message_wraps_group_traffic_only() {
+        match scoped_message(Some("ch"), Message::Ping) {
+            Message::ChannelOrdering {
+                channel_id,
+                message,
+            } => {
+                assert_eq!(channel_id, "ch");
+                assert!(matches!(*message, Message::Ping));
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+        assert!(matches!(scoped_message(None, Message::Ping), Message::Ping));
+    }
+}
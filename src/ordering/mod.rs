@@ -1,3 +1,9 @@
+pub mod admission;
+pub mod bft_node;
+pub mod bft_service;
+pub mod bft_transport;
+pub mod groups;
+pub mod preverify;
 #[cfg(feature = "raft-ordering")]
 pub mod raft_node;
 #[cfg(feature = "raft-ordering")]
@@ -9,19 +15,34 @@ pub mod raft_transport;
 pub mod service;
 
 use std::str::FromStr;
-
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::channel::config::{
+    ChannelConfig, ConfigTransaction, ConfigUpdateType, DEFAULT_ABSOLUTE_MAX_BYTES,
+    DEFAULT_PREFERRED_MAX_BYTES,
+};
+use crate::identity::key_rotation::KeySchedule;
 use crate::identity::signing::SigningProvider;
-use crate::storage::errors::StorageResult;
+use crate::storage::errors::{StorageError, StorageResult};
 use crate::storage::traits::{Block, Transaction};
+use crate::transaction::endorsed::EndorsedTransaction;
 use pqc_crypto_module::legacy::ed25519::Signer;
 use pqc_crypto_module::legacy::sha256::{Digest, Sha256};
 
-/// Compute a block hash for orderer signing: `sha256(height || parent_hash || merkle_root)`.
+/// Compute a block hash for orderer signing:
+/// `sha256(height || parent_hash || merkle_root || state_root || last_config)`,
+/// followed by the digest of the next validator set on epoch-boundary blocks.
 pub fn block_hash_for_signing(block: &Block) -> [u8; 32] {
     let mut hasher = Sha256::new();
     hasher.update(block.height.to_le_bytes());
     hasher.update(block.parent_hash);
     hasher.update(block.merkle_root);
+    hasher.update(block.state_root);
+    hasher.update(block.last_config.to_le_bytes());
+    if let Some(set) = &block.next_validator_set {
+        hasher.update(set.digest());
+    }
     hasher.finalize().into()
 }
 
@@ -84,6 +105,32 @@ pub fn verify_orderer_signature(
         .map_err(|e| format!("invalid orderer signature: {e}"))
 }
 
+#[allow(dead_code)]
+/// Verify a block's orderer signature with the key `orderer_id` held at the
+/// block's height, following committed key rotations.
+///
+/// Same results as [`verify_orderer_signature`]; a key retired by a
+/// rotation only verifies blocks inside its grace window.
+pub fn verify_orderer_signature_at(
+    block: &Block,
+    orderer_id: &str,
+    keys: &KeySchedule,
+) -> Result<bool, String> {
+    let sig = match &block.orderer_signature {
+        None => return Ok(false),
+        Some(s) => s,
+    };
+    let hash = block_hash_for_signing(block);
+    if keys.verify(orderer_id, block.height, &hash, sig) {
+        Ok(true)
+    } else {
+        Err(format!(
+            "invalid orderer signature: no key of '{orderer_id}' verifies at height {}",
+            block.height
+        ))
+    }
+}
+
 #[allow(dead_code)]
 /// Verify a block's signature using a `SigningProvider`.
 ///
@@ -111,12 +158,194 @@ pub fn verify_block_signature(
         .map_err(|e| format!("block signature verification failed: {e}"))
 }
 
+/// Byte limits applied when batching transactions into a block.
+///
+/// A block is cut once adding the next transaction would push its payload
+/// past `preferred_max_bytes`; a transaction above `absolute_max_bytes` is
+/// never ordered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockSizeLimits {
+    pub preferred_max_bytes: usize,
+    pub absolute_max_bytes: usize,
+}
+
+impl Default for BlockSizeLimits {
+    fn default() -> Self {
+        Self {
+            preferred_max_bytes: DEFAULT_PREFERRED_MAX_BYTES,
+            absolute_max_bytes: DEFAULT_ABSOLUTE_MAX_BYTES,
+        }
+    }
+}
+
+impl BlockSizeLimits {
+    /// Read the limits from env, falling back to the channel defaults:
+    /// - `ORDERING_PREFERRED_MAX_BYTES` (default 1 MB)
+    /// - `ORDERING_ABSOLUTE_MAX_BYTES` (default 3 MB)
+    ///
+    /// Warns when the absolute limit does not fit in `P2P_RESPONSE_BUFFER_BYTES`.
+    pub fn from_env() -> Self {
+        let read = |name: &str, default: usize| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default)
+        };
+        let absolute_max_bytes = read("ORDERING_ABSOLUTE_MAX_BYTES", DEFAULT_ABSOLUTE_MAX_BYTES);
+        let limits = Self {
+            preferred_max_bytes: read("ORDERING_PREFERRED_MAX_BYTES", DEFAULT_PREFERRED_MAX_BYTES)
+                .clamp(1, absolute_max_bytes.max(1)),
+            absolute_max_bytes,
+        };
+        let buffer = crate::network::p2p_response_buffer_size();
+        if limits.absolute_max_bytes >= buffer {
+            log::warn!(
+                "ORDERING_ABSOLUTE_MAX_BYTES ({}) does not fit in P2P_RESPONSE_BUFFER_BYTES ({buffer}); \
+                 full blocks will be truncated in transit",
+                limits.absolute_max_bytes
+            );
+        }
+        limits
+    }
+
+    /// Limits configured on a channel.
+    pub fn from_config(config: &ChannelConfig) -> Self {
+        Self {
+            preferred_max_bytes: config.preferred_max_bytes,
+            absolute_max_bytes: config.absolute_max_bytes,
+        }
+    }
+
+    /// Apply a `SetMaxBytes` update; other updates leave the limits as is.
+    pub fn apply(&mut self, update: &ConfigUpdateType) {
+        if let ConfigUpdateType::SetMaxBytes {
+            preferred_max_bytes,
+            absolute_max_bytes,
+        } = update
+        {
+            self.preferred_max_bytes = *preferred_max_bytes;
+            self.absolute_max_bytes = *absolute_max_bytes;
+        }
+    }
+
+    /// Size of `tx` as ordered, or `TransactionTooLarge` when it exceeds the
+    /// absolute limit.
+    pub fn check_tx(&self, tx: &Transaction) -> StorageResult<usize> {
+        let size = tx_size_bytes(tx);
+        if size > self.absolute_max_bytes {
+            return Err(StorageError::TransactionTooLarge {
+                tx_id: tx.id.clone(),
+                size,
+                limit: self.absolute_max_bytes,
+            });
+        }
+        Ok(size)
+    }
+
+    /// How many of the leading transactions with `sizes` go into the next
+    /// block, together with their total bytes. At most `max_count` are taken;
+    /// the batch ends before the transaction that would cross
+    /// `preferred_max_bytes`, but always holds at least one.
+    pub fn batch_len(
+        &self,
+        sizes: impl IntoIterator<Item = usize>,
+        max_count: usize,
+    ) -> (usize, usize) {
+        let (mut count, mut bytes) = (0, 0);
+        for size in sizes.into_iter().take(max_count) {
+            if count > 0 && bytes + size > self.preferred_max_bytes {
+                break;
+            }
+            count += 1;
+            bytes += size;
+        }
+        (count, bytes)
+    }
+}
+
+/// Height of the latest config block an orderer has cut. Blocks carry it as
+/// `last_config` from the moment they are cut, so the orderer signature
+/// covers the pointer; a config block points at itself.
+#[derive(Debug, Default)]
+pub struct LastConfig(AtomicU64);
+
+impl LastConfig {
+    /// The `last_config` pointer `block` must carry on top of the blocks
+    /// cut so far.
+    pub fn expected(&self, block: &Block) -> u64 {
+        if block.config_tx.is_some() {
+            block.height
+        } else {
+            self.0.load(Ordering::SeqCst)
+        }
+    }
+
+    /// Stamp a freshly cut `block` with its `last_config` pointer.
+    pub fn stamp(&self, block: &mut Block) {
+        block.last_config = self.expected(block);
+        self.resume(block.last_config);
+    }
+
+    /// Continue after a block (cut here or elsewhere) pointing at `height`.
+    pub fn resume(&self, height: u64) {
+        self.0.fetch_max(height, Ordering::SeqCst);
+    }
+}
+
+/// Serialized size of a transaction, as submitted to the orderer.
+pub fn tx_size_bytes(tx: &Transaction) -> usize {
+    serde_json::to_vec(tx).map(|bytes| bytes.len()).unwrap_or(0)
+}
+
 /// Common interface for ordering backends (solo batching vs Raft consensus).
 pub trait OrderingBackend: Send + Sync {
     fn submit_tx(&self, tx: &Transaction) -> StorageResult<()>;
+    /// Order an endorsed transaction. Admission checks its creator and
+    /// endorsement signatures, and the endorsing orgs against the writers
+    /// ACL, before its transaction is enqueued.
+    fn submit_endorsed_tx(&self, etx: &EndorsedTransaction) -> StorageResult<()>;
     fn cut_block(&self, height: u64, proposer: &str) -> StorageResult<Option<Block>>;
     #[allow(dead_code)]
     fn pending_count(&self) -> usize;
+
+    /// Stamp the `state_root` the committer computed for a cut `block` and
+    /// sign it again, so the signature covers the root. Backends whose
+    /// blocks are final once cut leave `block` unchanged and return `false`.
+    fn seal_block(&self, block: &mut Block, state_root: [u8; 32]) -> bool;
+
+    /// Continue from a ledger whose tip points at config block `height`:
+    /// blocks cut from now on carry it as `last_config` until the next
+    /// config block.
+    fn resume_last_config(&self, height: u64);
+
+    /// React to a committed channel config update. Backends with dynamic
+    /// membership (Raft) turn consenter changes into membership changes;
+    /// the default is a no-op.
+    fn apply_config_updates(&self, _updates: &[ConfigUpdateType]) -> StorageResult<()> {
+        Ok(())
+    }
+
+    /// Order a channel config transaction. It is cut into a config block of
+    /// its own (see [`crate::channel::ledger`]); peers validate it when they
+    /// commit that block.
+    fn submit_config_tx(&self, _tx: &ConfigTransaction) -> StorageResult<()> {
+        Err(StorageError::Other(
+            "ordering backend does not order config transactions".to_string(),
+        ))
+    }
+
+    /// Order an endorsed private data purge. It is listed in a regular
+    /// block under its ledger ID; member peers apply it when they commit
+    /// that block.
+    fn submit_purge_tx(&self, _request: &crate::private_data::PurgeRequest) -> StorageResult<()> {
+        Err(StorageError::Other(
+            "ordering backend does not order private data purges".to_string(),
+        ))
+    }
+
+    /// Sign with `signer` from `activation_height` on, once a rotation of
+    /// this node's own key is committed. The default is a no-op.
+    fn rotate_signer_at(&self, _signer: Arc<dyn SigningProvider>, _activation_height: u64) {}
 }
 
 /// Role of this node in the network.
@@ -175,6 +404,42 @@ mod tests {
         assert!("invalid".parse::<NodeRole>().is_err());
     }
 
+    #[test]
+    fn batch_len_stops_before_crossing_preferred_bytes() {
+        let limits = BlockSizeLimits {
+            preferred_max_bytes: 100,
+            absolute_max_bytes: 500,
+        };
+        assert_eq!(limits.batch_len([40, 40, 40], 10), (2, 80));
+        assert_eq!(limits.batch_len([40, 60, 1], 10), (2, 100));
+        assert_eq!(limits.batch_len([10, 10, 10], 2), (2, 20));
+        // An oversized (but admissible) transaction is batched alone.
+        assert_eq!(limits.batch_len([300, 10], 10), (1, 300));
+        assert_eq!(limits.batch_len([10, 300], 10), (1, 10));
+        assert_eq!(limits.batch_len([], 10), (0, 0));
+    }
+
+    #[test]
+    fn check_tx_rejects_transactions_above_absolute_limit() {
+        let tx = make_tx("tx-big");
+        let size = tx_size_bytes(&tx);
+        let fits = BlockSizeLimits {
+            preferred_max_bytes: 1,
+            absolute_max_bytes: size,
+        };
+        assert_eq!(fits.check_tx(&tx).unwrap(), size);
+
+        let too_small = BlockSizeLimits {
+            preferred_max_bytes: 1,
+            absolute_max_bytes: size - 1,
+        };
+        assert!(matches!(
+            too_small.check_tx(&tx),
+            Err(StorageError::TransactionTooLarge { size: s, limit, .. })
+                if s == size && limit == size - 1
+        ));
+    }
+
     fn make_tx(id: &str) -> Transaction {
         Transaction {
             id: id.to_string(),
@@ -253,6 +518,58 @@ mod tests {
         assert_eq!(verify_orderer_signature(&block, &verifying), Ok(true));
     }
 
+    #[test]
+    fn sealed_state_root_is_covered_by_the_orderer_signature() {
+        use pqc_crypto_module::legacy::ed25519::{SigningKey, VerifyingKey};
+
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let verifying = VerifyingKey::from(&key);
+
+        let svc = service::OrderingService::with_config(100, 2000).with_signing_key(key);
+        svc.submit_tx(make_tx("tx1").clone()).unwrap();
+        let mut block = svc.cut_block(1, "orderer").unwrap().unwrap();
+
+        assert!(svc.seal_block(&mut block, [4u8; 32]));
+        assert_eq!(block.state_root, [4u8; 32]);
+        assert_eq!(verify_orderer_signature(&block, &verifying), Ok(true));
+
+        // Swapping the root afterwards breaks the signature.
+        block.state_root = [5u8; 32];
+        assert!(verify_orderer_signature(&block, &verifying).is_err());
+    }
+
+    #[test]
+    fn blocks_are_cut_pointing_at_the_latest_config_block() {
+        use pqc_crypto_module::legacy::ed25519::{SigningKey, VerifyingKey};
+
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let verifying = VerifyingKey::from(&key);
+        let svc = service::OrderingService::with_config(100, 2000).with_signing_key(key);
+        svc.resume_last_config(3);
+
+        svc.submit_tx(make_tx("tx1")).unwrap();
+        assert_eq!(svc.cut_block(5, "orderer").unwrap().unwrap().last_config, 3);
+
+        svc.submit_config_tx(ConfigTransaction {
+            tx_id: "cfg-1".to_string(),
+            channel_id: "ch1".to_string(),
+            updates: vec![],
+            signatures: vec![],
+            created_at: 0,
+        })
+        .unwrap();
+        assert_eq!(svc.cut_block(6, "orderer").unwrap().unwrap().last_config, 6);
+
+        svc.submit_tx(make_tx("tx2")).unwrap();
+        let mut block = svc.cut_block(7, "orderer").unwrap().unwrap();
+        assert_eq!(block.last_config, 6);
+        assert_eq!(verify_orderer_signature(&block, &verifying), Ok(true));
+
+        // The signature covers the pointer.
+        block.last_config = 3;
+        assert!(verify_orderer_signature(&block, &verifying).is_err());
+    }
+
     #[test]
     fn verify_invalid_orderer_signature_rejects() {
         use pqc_crypto_module::legacy::ed25519::{SigningKey, VerifyingKey};
@@ -282,4 +599,38 @@ mod tests {
 
         assert_eq!(verify_orderer_signature(&block, &verifying), Ok(false));
     }
+
+    #[test]
+    fn verify_orderer_signature_at_follows_key_rotation() {
+        use crate::identity::key_rotation::KeyRotation;
+        use crate::identity::signing::SoftwareSigningProvider;
+        use std::sync::Arc;
+
+        let old = Arc::new(SoftwareSigningProvider::generate());
+        let new = Arc::new(SoftwareSigningProvider::generate());
+        let id = hex::encode(old.public_key());
+        let rotation = KeyRotation::signed(&id, old.as_ref(), new.as_ref(), 3, 1).unwrap();
+        let keys = KeySchedule::from_rotations([&rotation]).unwrap();
+
+        let svc =
+            service::OrderingService::with_config(100, 2000).with_signing_provider(old.clone());
+        svc.schedule_signer_rotation(new, 3);
+        let mut blocks = Vec::new();
+        for height in 1..=4 {
+            svc.submit_tx(make_tx(&format!("tx{height}"))).unwrap();
+            blocks.push(svc.cut_block(height, "orderer").unwrap().unwrap());
+        }
+        for block in &blocks {
+            assert_eq!(verify_orderer_signature_at(block, &id, &keys), Ok(true));
+        }
+
+        // The old key still verifies inside the grace window, not after it.
+        let mut stale = blocks[0].clone();
+        stale.height = 3;
+        sign_block_with_provider(&mut stale, old.as_ref());
+        assert_eq!(verify_orderer_signature_at(&stale, &id, &keys), Ok(true));
+        stale.height = 4;
+        sign_block_with_provider(&mut stale, old.as_ref());
+        assert!(verify_orderer_signature_at(&stale, &id, &keys).is_err());
+    }
 }
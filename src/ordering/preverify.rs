@@ -0,0 +1,415 @@
+//! Signature pre-verification of ordered blocks before they commit.
+//!
+//! Blocks delivered over P2P carry a proposer signature, usually an orderer
+//! signature, and the endorsements of their transactions. Checking them one
+//! by one on the commit path stalls it on large blocks, ML-DSA ones in
+//! particular. [`BlockPreverifier`] collects every signature of a batch of
+//! blocks and verifies them in parallel through a [`BatchVerifier`], whose
+//! cache also holds the endorsements already checked at submission.
+//!
+//! A signature is only checked when its key can be resolved: the proposer
+//! and the orderer through the channel's
+//! [`KeySchedule`](crate::identity::key_rotation::KeySchedule) (a hex public key
+//! ID, following rotations), endorsements through the org registry. Legacy
+//! blocks without such identities pass unchanged.
+
+use std::sync::Arc;
+
+use crate::channel::config::ConfigUpdateType;
+use crate::endorsement::registry::OrgRegistry;
+use crate::endorsement::validator::endorsement_check;
+use crate::identity::batch_verify::{BatchVerifier, SignatureCheck, VerifiedSignatureCache};
+use crate::identity::key_rotation::SharedKeySchedule;
+use crate::storage::traits::Block;
+
+/// Public key sizes a hex identity must decode to (Ed25519, ML-DSA-65);
+/// other hex-looking names are not identities.
+pub(crate) const PUBLIC_KEY_SIZES: [usize; 2] = [32, 1952];
+
+/// A block signature that failed pre-verification.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PreverifyError {
+    #[error("block {height}: invalid proposer signature from '{proposer}'")]
+    ProposerSignature { height: u64, proposer: String },
+    #[error("block {height}: invalid orderer signature from '{orderer}'")]
+    OrdererSignature { height: u64, orderer: String },
+    #[error("block {height}: endorsement {index} from org '{org_id}' does not verify")]
+    Endorsement {
+        height: u64,
+        index: usize,
+        org_id: String,
+    },
+}
+
+/// One signature requirement of a block: satisfied when any of its
+/// candidate checks (one per possible key) verifies.
+struct Requirement {
+    block: usize,
+    error: PreverifyError,
+    candidates: std::ops::Range<usize>,
+}
+
+/// Verifies the signatures of ordered blocks in parallel.
+#[derive(Clone, Default)]
+pub struct BlockPreverifier {
+    verifier: BatchVerifier,
+    keys: SharedKeySchedule,
+    orderer_id: Option<String>,
+    registry: Option<Arc<dyn OrgRegistry>>,
+}
+
+impl BlockPreverifier {
+    /// Pre-verifier sharing `cache` with the submission path.
+    pub fn new(cache: Arc<VerifiedSignatureCache>) -> Self {
+        Self {
+            verifier: BatchVerifier::new(cache),
+            ..Self::default()
+        }
+    }
+
+    /// Resolve proposer and orderer keys through `keys`, following key
+    /// rotations committed in the channel config.
+    pub fn with_key_schedule(mut self, keys: SharedKeySchedule) -> Self {
+        self.keys = keys;
+        self
+    }
+
+    /// Check `orderer_signature` against the keys of `orderer_id`.
+    pub fn with_orderer(mut self, orderer_id: impl Into<String>) -> Self {
+        self.orderer_id = Some(orderer_id.into());
+        self
+    }
+
+    /// Check endorsements against the root keys of their orgs.
+    pub fn with_registry(mut self, registry: Arc<dyn OrgRegistry>) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    /// Key rotations proposer and orderer keys are resolved through.
+    pub fn key_schedule(&self) -> &SharedKeySchedule {
+        &self.keys
+    }
+
+    pub fn verifier(&self) -> &BatchVerifier {
+        &self.verifier
+    }
+
+    /// Follow the key rotations carried by a committed config block, so
+    /// later blocks signed with the new keys verify.
+    pub fn observe_committed(&self, block: &Block) {
+        let Some(tx) = &block.config_tx else {
+            return;
+        };
+        let mut keys = self.keys.write().unwrap_or_else(|e| e.into_inner());
+        for update in &tx.updates {
+            if let ConfigUpdateType::RotateKey(rotation) = update {
+                if let Err(e) = keys.record(rotation.clone()) {
+                    log::warn!("Key rotation in block {} ignored: {e}", block.height);
+                }
+            }
+        }
+    }
+
+    /// Pre-verify one block.
+    pub fn preverify(&self, block: &Block) -> Result<(), PreverifyError> {
+        self.preverify_all(std::slice::from_ref(block))
+            .pop()
+            .unwrap_or(Ok(()))
+    }
+
+    /// Pre-verify `blocks` as one parallel batch; one result per block.
+    pub fn preverify_all(&self, blocks: &[Block]) -> Vec<Result<(), PreverifyError>> {
+        let mut checks = Vec::new();
+        let mut requirements = Vec::new();
+        for (i, block) in blocks.iter().enumerate() {
+            self.collect(i, block, &mut checks, &mut requirements);
+        }
+        let verified = self.verifier.verify_batch(&checks);
+
+        let mut results: Vec<Result<(), PreverifyError>> = vec![Ok(()); blocks.len()];
+        for req in requirements {
+            if results[req.block].is_ok() && !verified[req.candidates].iter().any(|ok| *ok) {
+                results[req.block] = Err(req.error);
+            }
+        }
+        results
+    }
+
+    /// Check every signature of `block` and list all that fail, rather
+    /// than only the first; also returns how many were checked.
+    pub fn signature_failures(&self, block: &Block) -> (usize, Vec<PreverifyError>) {
+        let mut checks = Vec::new();
+        let mut requirements = Vec::new();
+        self.collect(0, block, &mut checks, &mut requirements);
+        let verified = self.verifier.verify_batch(&checks);
+        let checked = requirements.len();
+        let failures = requirements
+            .into_iter()
+            .filter(|req| !verified[req.candidates.clone()].iter().any(|ok| *ok))
+            .map(|req| req.error)
+            .collect();
+        (checked, failures)
+    }
+
+    fn collect(
+        &self,
+        index: usize,
+        block: &Block,
+        checks: &mut Vec<SignatureCheck>,
+        requirements: &mut Vec<Requirement>,
+    ) {
+        let mut require = |keys: Vec<Vec<u8>>, payload: &[u8], sig: &[u8], error| {
+            let start = checks.len();
+            checks.extend(
+                keys.into_iter()
+                    .map(|key| SignatureCheck::new(payload, key, sig)),
+            );
+            requirements.push(Requirement {
+                block: index,
+                error,
+                candidates: start..checks.len(),
+            });
+        };
+
+        let hash = super::block_hash_for_signing(block);
+        let (proposer_keys, orderer_keys) = {
+            let keys = self.keys.read().unwrap_or_else(|e| e.into_inner());
+            let keys_at = |id: &str| -> Vec<Vec<u8>> {
+                keys.keys_at(id, block.height)
+                    .into_iter()
+                    .filter(|k| PUBLIC_KEY_SIZES.contains(&k.len()))
+                    .collect()
+            };
+            (
+                keys_at(&block.proposer),
+                self.orderer_id.as_deref().map(keys_at),
+            )
+        };
+        if !proposer_keys.is_empty() {
+            require(
+                proposer_keys,
+                &hash,
+                &block.signature,
+                PreverifyError::ProposerSignature {
+                    height: block.height,
+                    proposer: block.proposer.clone(),
+                },
+            );
+        }
+        if let (Some(sig), Some(keys), Some(orderer)) = (
+            &block.orderer_signature,
+            orderer_keys,
+            self.orderer_id.as_ref(),
+        ) {
+            require(
+                keys,
+                &hash,
+                sig,
+                PreverifyError::OrdererSignature {
+                    height: block.height,
+                    orderer: orderer.clone(),
+                },
+            );
+        }
+
+        let Some(registry) = &self.registry else {
+            return;
+        };
+        for (i, e) in block.endorsements.iter().enumerate() {
+            let roots = registry
+                .get_org(&e.org_id)
+                .map(|org| org.root_public_keys)
+                .unwrap_or_default();
+            let start = checks.len();
+            checks.extend(roots.iter().map(|pk| endorsement_check(e, pk)));
+            requirements.push(Requirement {
+                block: index,
+                error: PreverifyError::Endorsement {
+                    height: block.height,
+                    index: i,
+                    org_id: e.org_id.clone(),
+                },
+                candidates: start..checks.len(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::endorsement::org::Organization;
+    use crate::endorsement::registry::MemoryOrgRegistry;
+    use crate::endorsement::types::Endorsement;
+    use crate::identity::signing::{SigningProvider, SoftwareSigningProvider};
+    use pqc_crypto_module::legacy::ed25519::{Signer, SigningKey};
+
+    fn block(height: u64, proposer: &dyn SigningProvider) -> Block {
+        let mut block = Block {
+            height,
+            timestamp: 0,
+            parent_hash: [0u8; 32],
+            merkle_root: [height as u8; 32],
+            transactions: vec![format!("tx-{height}")],
+            proposer: hex::encode(proposer.public_key()),
+            signature: vec![],
+            signature_algorithm: Default::default(),
+            endorsements: vec![],
+            secondary_signature: None,
+            secondary_signature_algorithm: None,
+            hash_algorithm: Default::default(),
+            orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: Vec::new(),
+            config_tx: None,
+            last_config: 0,
+            state_root: [0u8; 32],
+            private_data_purges: vec![],
+        };
+        super::super::sign_block_with_provider(&mut block, proposer);
+        block
+    }
+
+    fn endorsement(key: &SigningKey, org_id: &str) -> Endorsement {
+        let payload_hash = [9u8; 32];
+        Endorsement {
+            signer_did: format!("did:bc:{org_id}"),
+            org_id: org_id.to_string(),
+            signature: key.sign(&payload_hash).to_bytes().to_vec(),
+            signature_algorithm: Default::default(),
+            payload_hash,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn batch_flags_only_the_tampered_block() {
+        let proposer = SoftwareSigningProvider::generate();
+        let orderer_id = hex::encode(proposer.public_key());
+        let mut blocks: Vec<Block> = (1..=4).map(|h| block(h, &proposer)).collect();
+        blocks[2].signature[0] ^= 0xFF;
+        blocks[3].orderer_signature.as_mut().unwrap()[0] ^= 0xFF;
+
+        let pre = BlockPreverifier::default().with_orderer(&orderer_id);
+        let results = pre.preverify_all(&blocks);
+        assert!(results[0].is_ok() && results[1].is_ok());
+        assert!(matches!(
+            results[2],
+            Err(PreverifyError::ProposerSignature { height: 3, .. })
+        ));
+        assert!(matches!(
+            results[3],
+            Err(PreverifyError::OrdererSignature { height: 4, .. })
+        ));
+    }
+
+    #[test]
+    fn signature_failures_lists_every_bad_signature() {
+        let proposer = SoftwareSigningProvider::generate();
+        let org_key = SigningKey::from_bytes(&[7u8; 32]);
+        let registry = MemoryOrgRegistry::new();
+        let org = Organization::new(
+            "org1",
+            "Org1MSP",
+            vec!["did:bc:admin".into()],
+            vec![],
+            vec![org_key.verifying_key().to_bytes()],
+        )
+        .unwrap();
+        registry.register_org(&org).unwrap();
+        let mut b = block(1, &proposer);
+        b.endorsements = vec![endorsement(&org_key, "org1"), endorsement(&org_key, "org1")];
+        let pre = BlockPreverifier::default()
+            .with_orderer(hex::encode(proposer.public_key()))
+            .with_registry(Arc::new(registry));
+        assert_eq!(pre.signature_failures(&b), (4, vec![]));
+
+        b.endorsements[1].signature[0] ^= 0xFF;
+        b.orderer_signature.as_mut().unwrap()[0] ^= 0xFF;
+        let (checked, failures) = pre.signature_failures(&b);
+        assert_eq!(checked, 4);
+        assert!(matches!(
+            failures[..],
+            [
+                PreverifyError::OrdererSignature { height: 1, .. },
+                PreverifyError::Endorsement { index: 1, .. }
+            ]
+        ));
+    }
+
+    #[test]
+    fn committed_rotation_switches_proposer_key() {
+        use crate::channel::config::ConfigTransaction;
+        use crate::identity::key_rotation::KeyRotation;
+
+        let old = SoftwareSigningProvider::generate();
+        let new = SoftwareSigningProvider::generate();
+        let id = hex::encode(old.public_key());
+        let rotation = KeyRotation::signed(&id, &old, &new, 3, 0).unwrap();
+        let mut config_block = block(1, &old);
+        config_block.config_tx = Some(ConfigTransaction {
+            tx_id: "cfg-1".to_string(),
+            channel_id: "ch1".to_string(),
+            updates: vec![ConfigUpdateType::RotateKey(rotation)],
+            signatures: vec![],
+            created_at: 0,
+        });
+
+        let mut rotated = block(3, &new);
+        rotated.proposer = id.clone();
+        super::super::sign_block_with_provider(&mut rotated, &new);
+        let pre = BlockPreverifier::default();
+        assert!(pre.preverify(&rotated).is_err());
+        pre.observe_committed(&config_block);
+        assert_eq!(pre.preverify(&rotated), Ok(()));
+        // The old key is retired at the activation height (no grace).
+        let mut stale = block(3, &old);
+        stale.proposer = id;
+        assert!(pre.preverify(&stale).is_err());
+    }
+
+    #[test]
+    fn legacy_proposer_is_not_checked() {
+        let mut b = block(1, &SoftwareSigningProvider::generate());
+        b.signature = vec![0u8; 64];
+        for proposer in ["orderer", "abcd"] {
+            b.proposer = proposer.to_string();
+            assert_eq!(BlockPreverifier::default().preverify(&b), Ok(()));
+        }
+    }
+
+    #[test]
+    fn endorsements_verify_against_org_roots_and_hit_the_cache() {
+        let key = SigningKey::from_bytes(&[3u8; 32]);
+        let registry = MemoryOrgRegistry::new();
+        let org = Organization::new(
+            "org1",
+            "Org1MSP",
+            vec!["did:bc:admin".into()],
+            vec![],
+            vec![key.verifying_key().to_bytes()],
+        )
+        .unwrap();
+        registry.register_org(&org).unwrap();
+
+        let cache = Arc::new(VerifiedSignatureCache::new(16));
+        let pre = BlockPreverifier::new(cache.clone()).with_registry(Arc::new(registry));
+        let mut b = block(1, &SoftwareSigningProvider::generate());
+        b.endorsements = vec![endorsement(&key, "org1")];
+        assert_eq!(pre.preverify(&b), Ok(()));
+        assert_eq!(pre.preverify(&b), Ok(()));
+        assert_eq!(cache.stats().hits, 2);
+
+        b.endorsements.push(endorsement(&key, "org2"));
+        assert_eq!(
+            pre.preverify(&b),
+            Err(PreverifyError::Endorsement {
+                height: 1,
+                index: 1,
+                org_id: "org2".into()
+            })
+        );
+    }
+}
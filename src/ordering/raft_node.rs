@@ -1,11 +1,19 @@
 //! Raft ordering node — wraps `RawNode<MemStorage>` from the tikv raft crate.
 
+use prost::Message as ProstMessage;
 use raft::prelude::*;
 use raft::storage::MemStorage;
 use raft::StateRole;
+use std::collections::VecDeque;
 use std::path::Path;
 
-use super::raft_storage::RocksDbRaftStorage;
+use super::raft_storage::{CompactionPolicy, RocksDbRaftStorage};
+use super::raft_transport::PeerMap;
+use crate::channel::config::ConfigUpdateType;
+
+/// Maximum number of log entries a learner may trail the leader by and still
+/// be considered caught up for promotion to voter.
+pub const MAX_LEARNER_LAG: u64 = 10;
 
 /// Errors produced by [`RaftNode`].
 #[derive(Debug, thiserror::Error)]
@@ -16,6 +24,118 @@ pub enum RaftError {
     Step(String),
 }
 
+/// A change to the Raft voter/learner set, derived from a channel config update.
+///
+/// Changes are queued on every orderer and proposed one at a time by whichever
+/// node is leader; a change is dropped from the queue once the matching
+/// `ConfChange` entry is applied (or the conf state already reflects it).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MembershipChange {
+    /// Add `id` as a non-voting learner reachable at `address`.
+    AddLearner { id: u64, address: String },
+    /// Promote learner `id` to voter once it has caught up.
+    Promote(u64),
+    /// Remove `id` from the cluster.
+    Remove(u64),
+}
+
+impl MembershipChange {
+    /// Translate a channel config update into a membership change, if it is one.
+    pub fn from_config_update(update: &ConfigUpdateType) -> Option<Self> {
+        match update {
+            ConfigUpdateType::AddConsenter(c) => Some(MembershipChange::AddLearner {
+                id: c.raft_id,
+                address: c.address.clone(),
+            }),
+            ConfigUpdateType::PromoteConsenter(id) => Some(MembershipChange::Promote(*id)),
+            ConfigUpdateType::RemoveConsenter(id) => Some(MembershipChange::Remove(*id)),
+            _ => None,
+        }
+    }
+
+    /// Raft node ID this change targets.
+    pub fn node_id(&self) -> u64 {
+        match self {
+            MembershipChange::AddLearner { id, .. } => *id,
+            MembershipChange::Promote(id) | MembershipChange::Remove(id) => *id,
+        }
+    }
+
+    /// Build the `ConfChange` proposal. The learner address travels in
+    /// `context` so every node can update its peer map when the change applies.
+    fn to_conf_change(&self) -> ConfChange {
+        let (change_type, context) = match self {
+            MembershipChange::AddLearner { address, .. } => {
+                (ConfChangeType::AddLearnerNode, address.clone().into_bytes())
+            }
+            MembershipChange::Promote(_) => (ConfChangeType::AddNode, Vec::new()),
+            MembershipChange::Remove(_) => (ConfChangeType::RemoveNode, Vec::new()),
+        };
+        ConfChange {
+            change_type: change_type as i32,
+            node_id: self.node_id(),
+            context: context.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Whether an applied `ConfChange` corresponds to this queued change.
+    fn matches(&self, cc: &ConfChange) -> bool {
+        self.to_conf_change().change_type == cc.change_type && self.node_id() == cc.node_id
+    }
+
+    /// Whether `cs` already reflects this change (nothing left to propose).
+    fn is_reflected_in(&self, cs: &ConfState) -> bool {
+        match self {
+            MembershipChange::AddLearner { id, .. } => {
+                cs.voters.contains(id) || cs.learners.contains(id)
+            }
+            MembershipChange::Promote(id) => cs.voters.contains(id),
+            MembershipChange::Remove(id) => !cs.voters.contains(id) && !cs.learners.contains(id),
+        }
+    }
+}
+
+/// Application payload carried in Raft snapshots.
+///
+/// Orderers never ship ledger contents through Raft: a snapshot only records
+/// the block height the ledger had reached, and a node that installs it
+/// fetches the missing blocks from peers through the regular state sync.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub struct RaftSnapshotData {
+    /// Height of the last block cut from entries covered by the snapshot.
+    pub block_height: u64,
+    /// Index of the last log entry included in that block; the snapshot
+    /// never covers entries past it.
+    #[serde(default)]
+    pub log_index: u64,
+}
+
+impl RaftSnapshotData {
+    /// Serialize into the snapshot `data` field.
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(self).unwrap_or_default()
+    }
+
+    /// Parse a snapshot `data` field; `None` for empty or foreign payloads.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        serde_json::from_slice(data).ok()
+    }
+}
+
+/// Build the bootstrap snapshot every founding member starts from.
+///
+/// Starting the log at index 1 (rather than 0) forces nodes that join later to
+/// catch up by snapshot, which carries the conf state; replaying the log alone
+/// would never tell them about the founding voters.
+pub(crate) fn bootstrap_snapshot(conf_state: ConfState) -> Snapshot {
+    let mut snap = Snapshot::default();
+    snap.mut_metadata().index = 1;
+    snap.mut_metadata().term = 1;
+    *snap.mut_metadata().mut_conf_state() = conf_state;
+    snap
+}
+
 /// A single-node Raft participant backed by in-memory storage.
 ///
 /// Wraps [`RawNode<MemStorage>`] with a pending-proposal queue and a
@@ -32,6 +152,20 @@ pub struct RaftNode {
     /// Optional persistent storage — entries and hard state are flushed here
     /// after each `advance()` call so the Raft log survives restarts.
     persistent_storage: Option<std::sync::Arc<RocksDbRaftStorage>>,
+    /// Membership changes waiting to be proposed (leader) or applied (all nodes).
+    membership_queue: VecDeque<MembershipChange>,
+    /// Transport peer map, updated as consenters join and leave.
+    peer_map: Option<PeerMap>,
+    /// When to compact the log into a snapshot.
+    compaction: CompactionPolicy,
+    /// Height of the latest block cut from (or synced past) this node's log.
+    block_height: u64,
+    /// Index of the last log entry included in a cut block. Committed
+    /// entries past it are still waiting for a block and are never compacted.
+    cut_index: u64,
+    /// Block height announced by an installed snapshot that the local ledger
+    /// still has to fetch from peers.
+    block_sync_target: Option<u64>,
 }
 
 impl RaftNode {
@@ -51,13 +185,20 @@ impl RaftNode {
             ..Default::default()
         };
 
-        // All supplied peers (including self) are initial voters.
-        let conf_state = ConfState {
-            voters: peers,
-            ..Default::default()
-        };
-
-        let storage = MemStorage::new_with_conf_state(conf_state);
+        // All supplied peers (including self) are initial voters. A node
+        // started without peers is joining an existing cluster and learns the
+        // membership from the leader's snapshot.
+        let storage = MemStorage::new();
+        if !peers.is_empty() {
+            let conf_state = ConfState {
+                voters: peers,
+                ..Default::default()
+            };
+            storage
+                .wl()
+                .apply_snapshot(bootstrap_snapshot(conf_state))
+                .map_err(|e| RaftError::Init(e.to_string()))?;
+        }
         let logger = raft::default_logger();
         let raw_node =
             RawNode::new(&config, storage, &logger).map_err(|e| RaftError::Init(e.to_string()))?;
@@ -68,6 +209,12 @@ impl RaftNode {
             pending_proposals: Vec::new(),
             committed_entries: Vec::new(),
             persistent_storage: None,
+            membership_queue: VecDeque::new(),
+            peer_map: None,
+            compaction: CompactionPolicy::default(),
+            block_height: 0,
+            cut_index: 0,
+            block_sync_target: None,
         })
     }
 
@@ -88,16 +235,33 @@ impl RaftNode {
             voters: peers.clone(),
             ..Default::default()
         };
-        persistent.initialize(&cs).map_err(RaftError::Init)?;
+        let fresh = !persistent.is_initialized().map_err(RaftError::Init)?;
+        if fresh && !peers.is_empty() {
+            persistent.bootstrap(&cs).map_err(RaftError::Init)?;
+        } else {
+            persistent.initialize(&cs).map_err(RaftError::Init)?;
+        }
 
         // Load persisted state into MemStorage so RawNode can use it.
         let initial = raft::Storage::initial_state(&persistent)
             .map_err(|e| RaftError::Init(e.to_string()))?;
 
-        let mem = MemStorage::new_with_conf_state(initial.conf_state.clone());
+        let snapshot = raft::Storage::snapshot(&persistent, 0, 0)
+            .map_err(|e| RaftError::Init(e.to_string()))?;
+        let mem = if snapshot.get_metadata().index > 0 {
+            let mem = MemStorage::new();
+            mem.wl()
+                .apply_snapshot(snapshot)
+                .map_err(|e| RaftError::Init(e.to_string()))?;
+            mem
+        } else {
+            MemStorage::new_with_conf_state(initial.conf_state.clone())
+        };
         {
             let mut store = mem.wl();
             store.set_hardstate(initial.hard_state.clone());
+            // Membership may have changed since the snapshot was taken.
+            store.set_conf_state(initial.conf_state.clone());
             // Replay persisted entries into MemStorage.
             let first = raft::Storage::first_index(&persistent)
                 .map_err(|e| RaftError::Init(e.to_string()))?;
@@ -135,6 +299,10 @@ impl RaftNode {
             "Raft node {id} initialized from persistent storage at {}",
             raft_db_path.display()
         );
+        let RaftSnapshotData {
+            block_height,
+            log_index: cut_index,
+        } = persistent.snapshot_data().unwrap_or_default();
 
         Ok(Self {
             id,
@@ -142,6 +310,12 @@ impl RaftNode {
             pending_proposals: Vec::new(),
             committed_entries: Vec::new(),
             persistent_storage: Some(std::sync::Arc::new(persistent)),
+            membership_queue: VecDeque::new(),
+            peer_map: None,
+            compaction: CompactionPolicy::default(),
+            block_height,
+            cut_index,
+            block_sync_target: None,
         })
     }
 
@@ -160,6 +334,70 @@ impl RaftNode {
             .map_err(|e| RaftError::Step(e.to_string()))
     }
 
+    /// Attach the transport peer map so applied membership changes update it.
+    pub fn set_peer_map(&mut self, peer_map: PeerMap) {
+        self.peer_map = Some(peer_map);
+    }
+
+    /// Set the policy that decides when the log is compacted.
+    pub fn set_compaction_policy(&mut self, policy: CompactionPolicy) {
+        self.compaction = policy;
+    }
+
+    /// Record that a block at `height` was cut from committed entries up to
+    /// log index `last_index`. The next snapshot points at this block, and
+    /// the log may be compacted up to `last_index`.
+    pub fn record_cut_block(&mut self, height: u64, last_index: u64) {
+        self.block_height = self.block_height.max(height);
+        self.cut_index = self.cut_index.max(last_index);
+    }
+
+    /// Height of the latest block this node knows the ledger has reached.
+    pub fn block_height(&self) -> u64 {
+        self.block_height
+    }
+
+    /// The RocksDB log of a persistent node, e.g. for online backups.
+    pub fn persistent_storage(&self) -> Option<std::sync::Arc<RocksDbRaftStorage>> {
+        self.persistent_storage.clone()
+    }
+
+    /// Take the block height an installed snapshot requires the local ledger
+    /// to reach. The caller fetches the missing blocks from peers.
+    pub fn take_block_sync_target(&mut self) -> Option<u64> {
+        self.block_sync_target.take()
+    }
+
+    /// Queue a membership change. The leader proposes queued changes one at a
+    /// time from [`advance`](RaftNode::advance); followers keep them so a new
+    /// leader can pick up where the old one left off.
+    pub fn queue_membership_change(&mut self, change: MembershipChange) {
+        if !self.membership_queue.contains(&change) {
+            self.membership_queue.push_back(change);
+        }
+    }
+
+    /// Number of membership changes not yet applied.
+    pub fn pending_membership_changes(&self) -> usize {
+        self.membership_queue.len()
+    }
+
+    /// Current conf state (voters and learners) as seen by this node.
+    pub fn conf_state(&self) -> ConfState {
+        self.raw_node.raft.prs().conf().to_conf_state()
+    }
+
+    /// Whether learner `id` is within [`MAX_LEARNER_LAG`] entries of the
+    /// leader's log. Only meaningful on the leader, which tracks progress.
+    pub fn learner_caught_up(&self, id: u64) -> bool {
+        let last = self.raw_node.raft.raft_log.last_index();
+        self.raw_node
+            .raft
+            .prs()
+            .get(id)
+            .is_some_and(|p| p.matched + MAX_LEARNER_LAG >= last)
+    }
+
     /// Process an incoming Raft message (e.g. AppendEntries, RequestVote).
     pub fn step(&mut self, msg: Message) -> Result<(), RaftError> {
         self.raw_node
@@ -180,6 +418,26 @@ impl RaftNode {
         let mut committed: Vec<Entry> = ready.take_committed_entries();
         // Collect entries for persistent storage before appending to MemStorage.
         let all_entries_to_persist: Vec<Entry> = ready.entries().to_vec();
+        // A snapshot from the leader (e.g. a new learner catching up) must be
+        // installed before any entries that follow it.
+        if ready.snapshot().get_metadata().index > 0 {
+            let snap = ready.snapshot().clone();
+            if let Some(data) = RaftSnapshotData::decode(&snap.data) {
+                if data.block_height > self.block_height {
+                    self.block_height = data.block_height;
+                    self.block_sync_target = Some(data.block_height);
+                }
+                self.cut_index = self.cut_index.max(data.log_index);
+            }
+            if let Some(ref ps) = self.persistent_storage {
+                if let Err(e) = ps.apply_snapshot_data(&snap) {
+                    log::error!("Failed to persist raft snapshot: {e}");
+                }
+            }
+            if let Err(e) = self.raw_node.mut_store().wl().apply_snapshot(snap) {
+                log::error!("Failed to apply raft snapshot: {e}");
+            }
+        }
         {
             let mut store = self.raw_node.mut_store().wl();
             if !ready.entries().is_empty() {
@@ -198,8 +456,20 @@ impl RaftNode {
             hs.commit = commit;
             self.raw_node.mut_store().wl().set_hardstate(hs);
         }
+        // Conf changes are applied to the Raft group here and never reach
+        // `cut_block`; only normal entries are handed to the caller.
+        let mut normal: Vec<Entry> = Vec::with_capacity(committed.len());
+        for entry in committed {
+            if entry.entry_type == EntryType::EntryConfChange as i32 {
+                self.apply_committed_conf_change(&entry);
+            } else {
+                normal.push(entry);
+            }
+        }
+        let committed = normal;
         self.raw_node.advance_apply();
         self.committed_entries.extend(committed.clone());
+        self.drive_membership_queue();
 
         // Flush to persistent storage if configured.
         if let Some(ref ps) = self.persistent_storage {
@@ -211,15 +481,177 @@ impl RaftNode {
                 log::error!("Failed to persist raft hard state: {e}");
             }
         }
+        self.maybe_compact();
+
+        // Snapshots produced by MemStorage carry no payload; stamp outgoing
+        // ones with the ledger height so the receiver knows what to fetch.
+        for msg in msgs.iter_mut() {
+            if msg.msg_type == MessageType::MsgSnapshot as i32 {
+                msg.mut_snapshot().data = self.snapshot_data().encode();
+            }
+        }
 
         (msgs, committed)
     }
 
+    fn snapshot_data(&self) -> RaftSnapshotData {
+        RaftSnapshotData {
+            block_height: self.block_height,
+            log_index: self.cut_index,
+        }
+    }
+
+    /// Compact the log up to the last entry included in a cut block once it
+    /// outgrows the compaction policy. Committed entries not yet cut into a
+    /// block stay in the log. Followers that fall behind the compacted prefix
+    /// receive a snapshot instead of the entries.
+    fn maybe_compact(&mut self) {
+        let index = self.raw_node.raft.raft_log.applied.min(self.cut_index);
+        let store = self.raw_node.store();
+        let first = Storage::first_index(store).unwrap_or(1);
+        let last = Storage::last_index(store).unwrap_or(0);
+        if index < first {
+            return;
+        }
+        let exceeded = match self.persistent_storage {
+            Some(ref ps) => ps.needs_compaction(&self.compaction),
+            None => self.compaction.exceeded(last + 1 - first, 0),
+        };
+        if !exceeded {
+            return;
+        }
+        let term = match Storage::term(store, index) {
+            Ok(term) => term,
+            Err(e) => {
+                log::warn!("Raft node {} cannot compact at {index}: {e}", self.id);
+                return;
+            }
+        };
+        let mut snap = Snapshot::default();
+        snap.mut_metadata().index = index;
+        snap.mut_metadata().term = term;
+        *snap.mut_metadata().mut_conf_state() = self.conf_state();
+        snap.data = self.snapshot_data().encode();
+
+        if let Some(ref ps) = self.persistent_storage {
+            if let Err(e) = ps.compact(&snap) {
+                log::error!("Failed to compact persistent raft log: {e}");
+                return;
+            }
+        }
+        if let Err(e) = self.raw_node.mut_store().wl().compact(index) {
+            log::error!("Failed to compact raft log: {e}");
+            return;
+        }
+        log::info!(
+            "Raft node {} compacted log through index {index} (block height {})",
+            self.id,
+            self.block_height
+        );
+    }
+
+    /// Apply a committed `ConfChange` entry: update the Raft group, persist the
+    /// resulting `ConfState`, and keep the peer map in sync.
+    fn apply_committed_conf_change(&mut self, entry: &Entry) {
+        if entry.data.is_empty() {
+            return;
+        }
+        let cc = match ConfChange::decode(&entry.data[..]) {
+            Ok(cc) => cc,
+            Err(e) => {
+                log::error!("Invalid conf change at index {}: {e}", entry.index);
+                return;
+            }
+        };
+        let cs = match self.raw_node.apply_conf_change(&cc) {
+            Ok(cs) => cs,
+            Err(e) => {
+                log::error!("Failed to apply conf change for node {}: {e}", cc.node_id);
+                return;
+            }
+        };
+        self.raw_node.mut_store().wl().set_conf_state(cs.clone());
+        if let Some(ref ps) = self.persistent_storage {
+            if let Err(e) = ps.set_conf_state(&cs) {
+                log::error!("Failed to persist raft conf state: {e}");
+            }
+        }
+        if let Some(ref peer_map) = self.peer_map {
+            let mut map = peer_map.lock().unwrap_or_else(|e| e.into_inner());
+            if cc.change_type == ConfChangeType::RemoveNode as i32 {
+                map.remove(&cc.node_id);
+            } else if let Ok(addr) = std::str::from_utf8(&cc.context[..]) {
+                if !addr.is_empty() {
+                    map.insert(cc.node_id, addr.to_string());
+                }
+            }
+        }
+        if let Some(pos) = self.membership_queue.iter().position(|c| c.matches(&cc)) {
+            self.membership_queue.remove(pos);
+        }
+        log::info!(
+            "Raft node {} applied conf change {:?} for node {} | voters={:?} learners={:?}",
+            self.id,
+            cc.change_type,
+            cc.node_id,
+            cs.voters,
+            cs.learners
+        );
+    }
+
+    /// Drop queued changes already reflected in the conf state and, on the
+    /// leader, propose the next one once no other conf change is in flight.
+    fn drive_membership_queue(&mut self) {
+        let cs = self.conf_state();
+        while self
+            .membership_queue
+            .front()
+            .is_some_and(|c| c.is_reflected_in(&cs))
+        {
+            self.membership_queue.pop_front();
+        }
+        if !self.is_leader() {
+            return;
+        }
+        let raft = &self.raw_node.raft;
+        if raft.pending_conf_index > raft.raft_log.applied {
+            return;
+        }
+        let Some(change) = self.membership_queue.front().cloned() else {
+            return;
+        };
+        match change {
+            MembershipChange::Promote(id) if !self.learner_caught_up(id) => return,
+            MembershipChange::Remove(id) if id == self.id => {
+                // A leader cannot safely remove itself; hand leadership to the
+                // most up-to-date remaining voter, which then proposes the removal.
+                let target = cs
+                    .voters
+                    .iter()
+                    .copied()
+                    .filter(|&v| v != self.id)
+                    .max_by_key(|&v| self.raw_node.raft.prs().get(v).map_or(0, |p| p.matched));
+                if let Some(target) = target {
+                    self.raw_node.transfer_leader(target);
+                }
+                return;
+            }
+            _ => {}
+        }
+        if let Err(e) = self
+            .raw_node
+            .propose_conf_change(vec![], change.to_conf_change())
+        {
+            log::warn!("Raft node {} failed to propose {change:?}: {e}", self.id);
+        }
+    }
+
     #[allow(dead_code)]
     /// Create a snapshot of the current raft state.
     ///
-    /// The snapshot captures committed entries as serialized JSON in the data
-    /// field, plus the log index/term at which the snapshot was taken.
+    /// The snapshot points at the current block height (see
+    /// [`RaftSnapshotData`]) and records the log index/term at which it was
+    /// taken.
     pub fn create_snapshot(&self) -> Result<Snapshot, RaftError> {
         let hs = self.raw_node.raft.hard_state();
         let cs = self.raw_node.raft.prs().conf().to_conf_state();
@@ -227,7 +659,7 @@ impl RaftNode {
         // Find the term for the last applied index.
         let last_term = Storage::term(self.raw_node.store(), last_applied).unwrap_or(hs.term);
 
-        let data = serde_json::to_vec(&self.committed_entries.len())
+        let data = serde_json::to_vec(&self.snapshot_data())
             .map_err(|e| RaftError::Init(e.to_string()))?;
 
         let mut snap = Snapshot::default();
@@ -499,4 +931,278 @@ mod tests {
             "snapshot index mismatch"
         );
     }
+
+    #[test]
+    fn membership_change_from_config_update() {
+        use crate::channel::config::Consenter;
+
+        let add = ConfigUpdateType::AddConsenter(Consenter {
+            raft_id: 4,
+            address: "orderer4:8087".to_string(),
+            learner: true,
+        });
+        assert_eq!(
+            MembershipChange::from_config_update(&add),
+            Some(MembershipChange::AddLearner {
+                id: 4,
+                address: "orderer4:8087".to_string()
+            })
+        );
+        assert_eq!(
+            MembershipChange::from_config_update(&ConfigUpdateType::RemoveConsenter(1)),
+            Some(MembershipChange::Remove(1))
+        );
+        assert_eq!(
+            MembershipChange::from_config_update(&ConfigUpdateType::SetBatchSize(10)),
+            None
+        );
+    }
+
+    #[test]
+    fn leader_adds_learner_and_updates_peer_map() {
+        let mut node = RaftNode::new(1, vec![1]).unwrap();
+        let peer_map: PeerMap = std::sync::Arc::new(std::sync::Mutex::new(Default::default()));
+        node.set_peer_map(peer_map.clone());
+        elect_single_node(&mut node);
+
+        node.queue_membership_change(MembershipChange::AddLearner {
+            id: 2,
+            address: "orderer2:8087".to_string(),
+        });
+        for _ in 0..5 {
+            node.tick();
+            node.advance();
+        }
+
+        let cs = node.conf_state();
+        assert_eq!(cs.voters, vec![1]);
+        assert_eq!(cs.learners, vec![2]);
+        assert_eq!(node.pending_membership_changes(), 0);
+        assert_eq!(
+            peer_map.lock().unwrap().get(&2).map(String::as_str),
+            Some("orderer2:8087")
+        );
+        // Conf change entries never surface as transaction payloads.
+        assert!(node.committed_entries.iter().all(|e| e.data.is_empty()));
+    }
+
+    #[test]
+    fn queued_change_is_deduplicated() {
+        let mut node = RaftNode::new(1, vec![1]).unwrap();
+        node.queue_membership_change(MembershipChange::Remove(3));
+        node.queue_membership_change(MembershipChange::Remove(3));
+        assert_eq!(node.pending_membership_changes(), 1);
+    }
+
+    #[test]
+    fn learner_promoted_after_catching_up() {
+        let mut nodes = vec![
+            RaftNode::new(1, vec![1]).unwrap(),
+            RaftNode::new(2, vec![]).unwrap(),
+        ];
+        elect_single_node(&mut nodes[0]);
+        for i in 0u8..20 {
+            nodes[0].propose(vec![i]).unwrap();
+        }
+        for node in nodes.iter_mut() {
+            node.queue_membership_change(MembershipChange::AddLearner {
+                id: 2,
+                address: "orderer2:8087".to_string(),
+            });
+            node.queue_membership_change(MembershipChange::Promote(2));
+        }
+
+        route_messages(&mut nodes, 30);
+
+        for node in &nodes {
+            let mut voters = node.conf_state().voters;
+            voters.sort_unstable();
+            assert_eq!(voters, vec![1, 2], "node {} conf state", node.id);
+            assert_eq!(node.pending_membership_changes(), 0);
+        }
+        assert_eq!(
+            nodes[1].raw_node.raft.raft_log.committed,
+            nodes[0].raw_node.raft.raft_log.committed
+        );
+        // The joining node started empty and caught up from a snapshot.
+        assert!(Storage::first_index(nodes[1].raw_node.store()).unwrap() > 2);
+    }
+
+    /// Like `route_messages`, but only ticks and delivers to `active` nodes;
+    /// the rest are partitioned away.
+    fn route_among(nodes: &mut [RaftNode], rounds: usize, active: &[u64]) {
+        for _ in 0..rounds {
+            let mut pending: Vec<Message> = Vec::new();
+            for node in nodes.iter_mut().filter(|n| active.contains(&n.id)) {
+                node.tick();
+                pending.extend(drain_ready(node));
+            }
+            while !pending.is_empty() {
+                let mut next = Vec::new();
+                for msg in pending {
+                    if !active.contains(&msg.to) {
+                        continue;
+                    }
+                    if let Some(target) = nodes.iter_mut().find(|n| n.id == msg.to) {
+                        let _ = target.step(msg);
+                        next.extend(drain_ready(target));
+                    }
+                }
+                pending = next;
+            }
+        }
+    }
+
+    fn small_policy(max_entries: u64) -> CompactionPolicy {
+        CompactionPolicy {
+            max_entries,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn log_is_compacted_once_policy_exceeded() {
+        let mut node = RaftNode::new(1, vec![1]).unwrap();
+        node.set_compaction_policy(small_policy(50));
+        elect_single_node(&mut node);
+
+        for i in 0u32..1_000 {
+            node.propose(i.to_le_bytes().to_vec()).unwrap();
+            node.advance();
+            let last = node.committed_entries.last().map_or(0, |e| e.index);
+            node.record_cut_block(u64::from(i) + 1, last);
+        }
+
+        let store = node.raw_node.store();
+        let first = Storage::first_index(store).unwrap();
+        let last = Storage::last_index(store).unwrap();
+        assert!(first > 900, "log was not compacted (first_index={first})");
+        assert!(
+            last + 1 - first <= 50,
+            "log kept {} entries",
+            last + 1 - first
+        );
+        let payloads = node
+            .committed_entries
+            .iter()
+            .filter(|e| !e.data.is_empty())
+            .count();
+        assert_eq!(payloads, 1_000);
+    }
+
+    #[test]
+    fn entries_not_yet_cut_into_a_block_are_not_compacted() {
+        let mut node = RaftNode::new(1, vec![1]).unwrap();
+        node.set_compaction_policy(small_policy(50));
+        elect_single_node(&mut node);
+
+        for i in 0u32..200 {
+            node.propose(i.to_le_bytes().to_vec()).unwrap();
+            node.advance();
+        }
+        // Nothing has been cut yet: the whole log stays.
+        assert!(Storage::first_index(node.raw_node.store()).unwrap() <= 2);
+
+        let cut = node.committed_entries[99].index;
+        node.record_cut_block(1, cut);
+        node.propose(vec![0xff]).unwrap();
+        node.advance();
+
+        let store = node.raw_node.store();
+        assert_eq!(Storage::first_index(store).unwrap(), cut);
+        assert!(Storage::entries(
+            store,
+            cut,
+            cut + 101,
+            None,
+            raft::GetEntriesContext::empty(false)
+        )
+        .is_ok());
+        assert_eq!(
+            node.snapshot_data(),
+            RaftSnapshotData {
+                block_height: 1,
+                log_index: cut,
+            }
+        );
+    }
+
+    #[test]
+    fn lagging_follower_gets_snapshot_pointing_at_block_height() {
+        let peers = vec![1, 2, 3];
+        let mut nodes: Vec<RaftNode> = peers
+            .iter()
+            .map(|&id| {
+                let mut node = RaftNode::new(id, peers.clone()).unwrap();
+                node.set_compaction_policy(small_policy(20));
+                node
+            })
+            .collect();
+        route_messages(&mut nodes, 50);
+        let leader_id = nodes.iter().find(|n| n.is_leader()).expect("no leader").id;
+        let lagging = *peers.iter().find(|&&id| id != leader_id).unwrap();
+        let active: Vec<u64> = peers.iter().copied().filter(|&id| id != lagging).collect();
+
+        // The lagging orderer misses 100 entries, which the others cut into
+        // blocks and then compact away.
+        for i in 0u32..100 {
+            let leader = nodes.iter_mut().find(|n| n.id == leader_id).unwrap();
+            leader.propose(i.to_le_bytes().to_vec()).unwrap();
+            route_among(&mut nodes, 1, &active);
+            for node in nodes.iter_mut().filter(|n| n.id != lagging) {
+                let applied = node.raw_node.raft.raft_log.applied;
+                node.record_cut_block(7, applied);
+            }
+        }
+        let leader = nodes.iter().find(|n| n.id == leader_id).unwrap();
+        assert!(Storage::first_index(leader.raw_node.store()).unwrap() > 50);
+
+        route_messages(&mut nodes, 30);
+
+        let follower = nodes.iter_mut().find(|n| n.id == lagging).unwrap();
+        assert_eq!(follower.take_block_sync_target(), Some(7));
+        assert_eq!(follower.take_block_sync_target(), None);
+        assert_eq!(follower.block_height(), 7);
+        // It caught up from the snapshot, not by replaying the log.
+        assert!(follower.committed_entries.len() < 100);
+        let commit = follower.raw_node.raft.raft_log.committed;
+        let leader = nodes.iter().find(|n| n.id == leader_id).unwrap();
+        assert_eq!(commit, leader.raw_node.raft.raft_log.committed);
+    }
+
+    #[test]
+    fn persistent_log_stays_bounded_in_soak() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut node = RaftNode::new_persistent(1, vec![1], dir.path()).unwrap();
+        node.set_compaction_policy(CompactionPolicy {
+            max_entries: 100,
+            max_bytes: 16 * 1024,
+        });
+        elect_single_node(&mut node);
+
+        let payload = vec![0xabu8; 256];
+        for height in 1u64..=5_000 {
+            node.propose(payload.clone()).unwrap();
+            node.advance();
+            let last = node.committed_entries.last().map_or(0, |e| e.index);
+            node.committed_entries.clear();
+            node.record_cut_block(height, last);
+        }
+
+        let ps = node.persistent_storage.clone().unwrap();
+        let first = Storage::first_index(ps.as_ref()).unwrap();
+        let last = Storage::last_index(ps.as_ref()).unwrap();
+        assert!(
+            last + 1 - first <= 100,
+            "persisted {} entries",
+            last + 1 - first
+        );
+        assert!(ps.log_bytes() <= 16 * 1024 + 512);
+        drop(node);
+
+        // A restart resumes from the compacted snapshot.
+        let node = RaftNode::new_persistent(1, vec![1], dir.path()).unwrap();
+        assert!(node.block_height() > 4_900);
+        assert!(Storage::first_index(node.raw_node.store()).unwrap() > 4_800);
+    }
 }
@@ -1,9 +1,30 @@
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 
+use crate::channel::config::{ConfigTransaction, ConfigUpdateType};
+use crate::channel::config::{DEFAULT_ABSOLUTE_MAX_BYTES, DEFAULT_PREFERRED_MAX_BYTES};
 use crate::identity::signing::SigningProvider;
-use crate::ordering::raft_node::{RaftError, RaftNode};
+use crate::ordering::admission::{AdmissionChain, AdmissionContext, Envelope};
+use crate::ordering::raft_node::{MembershipChange, RaftError, RaftNode};
+use crate::ordering::raft_storage::CompactionPolicy;
+use crate::ordering::BlockSizeLimits;
+use crate::private_data::PurgeRequest;
 use crate::storage::errors::StorageResult;
 use crate::storage::traits::{Block, Transaction};
+use crate::transaction::endorsed::EndorsedTransaction;
+
+/// Raft log entry carrying a channel config transaction (plain entries
+/// carry a serialized [`Transaction`]).
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ConfigEntry {
+    config_tx: ConfigTransaction,
+}
+
+/// Raft log entry carrying an endorsed private data purge.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PurgeEntry {
+    purge: PurgeRequest,
+}
 
 /// Ordering service backed by a Raft cluster.
 ///
@@ -11,11 +32,16 @@ use crate::storage::traits::{Block, Transaction};
 /// raft propose + committed-entry draining.
 pub struct RaftOrderingService {
     pub(crate) raft_node: Arc<Mutex<RaftNode>>,
-    pub max_batch_size: usize,
-    #[allow(dead_code)]
-    pub batch_timeout_ms: u64,
+    max_batch_size: AtomicUsize,
+    batch_timeout_ms: AtomicU64,
+    preferred_max_bytes: AtomicUsize,
+    absolute_max_bytes: AtomicUsize,
+    admission: AdmissionChain,
     signing_key: Option<ed25519_dalek::SigningKey>,
-    signing_provider: Option<Arc<dyn SigningProvider>>,
+    signing_provider: Mutex<Option<Arc<dyn SigningProvider>>>,
+    /// Signer taking over at the given height after a key rotation.
+    next_signer: Mutex<Option<(u64, Arc<dyn SigningProvider>)>>,
+    last_config: super::LastConfig,
 }
 
 impl RaftOrderingService {
@@ -28,10 +54,15 @@ impl RaftOrderingService {
         let node = RaftNode::new(id, peers)?;
         Ok(Self {
             raft_node: Arc::new(Mutex::new(node)),
-            max_batch_size,
-            batch_timeout_ms,
+            max_batch_size: AtomicUsize::new(max_batch_size),
+            batch_timeout_ms: AtomicU64::new(batch_timeout_ms),
+            preferred_max_bytes: AtomicUsize::new(DEFAULT_PREFERRED_MAX_BYTES),
+            absolute_max_bytes: AtomicUsize::new(DEFAULT_ABSOLUTE_MAX_BYTES),
+            admission: AdmissionChain::standard(),
             signing_key: None,
-            signing_provider: None,
+            signing_provider: Mutex::new(None),
+            next_signer: Mutex::new(None),
+            last_config: super::LastConfig::default(),
         })
     }
 
@@ -46,10 +77,15 @@ impl RaftOrderingService {
         let node = RaftNode::new_persistent(id, peers, raft_db_path)?;
         Ok(Self {
             raft_node: Arc::new(Mutex::new(node)),
-            max_batch_size,
-            batch_timeout_ms,
+            max_batch_size: AtomicUsize::new(max_batch_size),
+            batch_timeout_ms: AtomicU64::new(batch_timeout_ms),
+            preferred_max_bytes: AtomicUsize::new(DEFAULT_PREFERRED_MAX_BYTES),
+            absolute_max_bytes: AtomicUsize::new(DEFAULT_ABSOLUTE_MAX_BYTES),
+            admission: AdmissionChain::standard(),
             signing_key: None,
-            signing_provider: None,
+            signing_provider: Mutex::new(None),
+            next_signer: Mutex::new(None),
+            last_config: super::LastConfig::default(),
         })
     }
 
@@ -63,10 +99,15 @@ impl RaftOrderingService {
     ) -> Self {
         Self {
             raft_node,
-            max_batch_size,
-            batch_timeout_ms,
+            max_batch_size: AtomicUsize::new(max_batch_size),
+            batch_timeout_ms: AtomicU64::new(batch_timeout_ms),
+            preferred_max_bytes: AtomicUsize::new(DEFAULT_PREFERRED_MAX_BYTES),
+            absolute_max_bytes: AtomicUsize::new(DEFAULT_ABSOLUTE_MAX_BYTES),
+            admission: AdmissionChain::standard(),
             signing_key: None,
-            signing_provider: None,
+            signing_provider: Mutex::new(None),
+            next_signer: Mutex::new(None),
+            last_config: super::LastConfig::default(),
         }
     }
 
@@ -77,21 +118,113 @@ impl RaftOrderingService {
         self
     }
 
+    /// Set the preferred and absolute block byte limits.
+    pub fn with_block_size_limits(self, limits: BlockSizeLimits) -> Self {
+        self.preferred_max_bytes
+            .store(limits.preferred_max_bytes, Ordering::Relaxed);
+        self.absolute_max_bytes
+            .store(limits.absolute_max_bytes, Ordering::Relaxed);
+        self
+    }
+
+    /// Replace the admission filters run on every submitted transaction
+    /// (default: [`AdmissionChain::standard`]).
+    pub fn with_admission(mut self, admission: AdmissionChain) -> Self {
+        self.admission = admission;
+        self
+    }
+
+    /// Set the policy that decides when the Raft log is compacted.
+    pub fn with_compaction_policy(self, policy: CompactionPolicy) -> Self {
+        self.raft_node
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .set_compaction_policy(policy);
+        self
+    }
+
     /// Attach a pluggable signing provider (Ed25519 or ML-DSA-65).
     pub fn with_signing_provider(mut self, provider: Arc<dyn SigningProvider>) -> Self {
-        self.signing_provider = Some(provider);
+        *self
+            .signing_provider
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner()) = Some(provider);
         self
     }
 
+    /// Switch to `provider` for blocks at `activation_height` and above,
+    /// matching a committed key rotation of this orderer.
+    pub fn schedule_signer_rotation(
+        &self,
+        provider: Arc<dyn SigningProvider>,
+        activation_height: u64,
+    ) {
+        *self.next_signer.lock().unwrap_or_else(|e| e.into_inner()) =
+            Some((activation_height, provider));
+    }
+
+    /// Signer for a block at `height`, promoting a scheduled rotation once
+    /// its activation height is reached.
+    fn signer_for(&self, height: u64) -> Option<Arc<dyn SigningProvider>> {
+        let mut current = self
+            .signing_provider
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let mut next = self.next_signer.lock().unwrap_or_else(|e| e.into_inner());
+        if next.as_ref().is_some_and(|(at, _)| height >= *at) {
+            *current = next.take().map(|(_, provider)| provider);
+        }
+        current.clone()
+    }
+
     /// Serialize the transaction and propose it through Raft.
     ///
     /// After proposing, drives the Raft state machine forward so the entry
     /// can be committed before the caller calls `cut_block`. Without this,
     /// the async tick loop might not have run yet and `cut_block` would
     /// return `None`.
+    ///
+    /// Transactions refused by admission control (oversized ones included)
+    /// never reach the log.
     pub fn submit_tx(&self, tx: &Transaction) -> StorageResult<()> {
-        let data = serde_json::to_vec(tx)
+        self.submit(&Envelope::plain(tx))
+    }
+
+    /// Propose an endorsed transaction; admission checks its creator and
+    /// endorsements, and only the inner `Transaction` reaches the log.
+    pub fn submit_endorsed_tx(&self, etx: &EndorsedTransaction) -> StorageResult<()> {
+        self.submit(&Envelope::endorsed(etx))
+    }
+
+    fn submit(&self, envelope: &Envelope<'_>) -> StorageResult<()> {
+        self.admission
+            .admit(envelope, &AdmissionContext::now(self.block_size_limits()))?;
+        let data = serde_json::to_vec(envelope.tx)
             .map_err(|e| crate::storage::errors::StorageError::SerializationError(e.to_string()))?;
+        self.propose(data)
+    }
+
+    /// Propose a channel config transaction through Raft. `cut_block` cuts
+    /// it into a config block of its own once committed.
+    pub fn submit_config_tx(&self, tx: &ConfigTransaction) -> StorageResult<()> {
+        let data = serde_json::to_vec(&ConfigEntry {
+            config_tx: tx.clone(),
+        })
+        .map_err(|e| crate::storage::errors::StorageError::SerializationError(e.to_string()))?;
+        self.propose(data)
+    }
+
+    /// Propose a private data purge through Raft. `cut_block` lists it in
+    /// the block it lands in under its ledger ID.
+    pub fn submit_purge_tx(&self, request: &PurgeRequest) -> StorageResult<()> {
+        let data = serde_json::to_vec(&PurgeEntry {
+            purge: request.clone(),
+        })
+        .map_err(|e| crate::storage::errors::StorageError::SerializationError(e.to_string()))?;
+        self.propose(data)
+    }
+
+    fn propose(&self, data: Vec<u8>) -> StorageResult<()> {
         let mut node = self.raft_node.lock().unwrap_or_else(|e| e.into_inner());
         node.propose(data)
             .map_err(|e| crate::storage::errors::StorageError::SerializationError(e.to_string()))?;
@@ -107,6 +240,60 @@ impl RaftOrderingService {
         Ok(())
     }
 
+    /// Maximum number of transactions per cut block.
+    pub fn max_batch_size(&self) -> usize {
+        self.max_batch_size.load(Ordering::Relaxed)
+    }
+
+    #[allow(dead_code)]
+    /// Batch timeout from the channel config.
+    pub fn batch_timeout_ms(&self) -> u64 {
+        self.batch_timeout_ms.load(Ordering::Relaxed)
+    }
+
+    /// Current block byte limits.
+    pub fn block_size_limits(&self) -> BlockSizeLimits {
+        BlockSizeLimits {
+            preferred_max_bytes: self.preferred_max_bytes.load(Ordering::Relaxed),
+            absolute_max_bytes: self.absolute_max_bytes.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Queue the consenter changes contained in `updates` as Raft membership
+    /// changes. Every orderer queues them; whichever node leads proposes them
+    /// one at a time as they become safe to apply, on the next `advance()`.
+    /// Batch size, timeout and max-bytes updates take effect on the next cut.
+    pub fn apply_config_updates(&self, updates: &[ConfigUpdateType]) -> StorageResult<()> {
+        for update in updates {
+            match update {
+                ConfigUpdateType::SetBatchSize(size) => {
+                    self.max_batch_size.store(*size, Ordering::Relaxed)
+                }
+                ConfigUpdateType::SetBatchTimeout(ms) => {
+                    self.batch_timeout_ms.store(*ms, Ordering::Relaxed)
+                }
+                ConfigUpdateType::SetMaxBytes {
+                    preferred_max_bytes,
+                    absolute_max_bytes,
+                } => {
+                    self.preferred_max_bytes
+                        .store(*preferred_max_bytes, Ordering::Relaxed);
+                    self.absolute_max_bytes
+                        .store(*absolute_max_bytes, Ordering::Relaxed);
+                }
+                _ => {}
+            }
+        }
+        let mut node = self.raft_node.lock().unwrap_or_else(|e| e.into_inner());
+        for change in updates
+            .iter()
+            .filter_map(MembershipChange::from_config_update)
+        {
+            node.queue_membership_change(change);
+        }
+        Ok(())
+    }
+
     /// Number of committed entries not yet consumed by `cut_block`.
     pub fn pending_count(&self) -> usize {
         self.raft_node
@@ -117,7 +304,11 @@ impl RaftOrderingService {
     }
 
     /// Drain committed entries, deserialize transactions, and cut a block.
-    /// Returns `None` if no committed entries with transaction data are available.
+    /// The batch ends before the entry that would push it past
+    /// `preferred_max_bytes`. A committed config transaction ends the batch and is cut alone into
+    /// the next block; committed purges are listed under their ledger IDs.
+    /// Returns `None` if no committed entries with
+    /// transaction data are available.
     pub fn cut_block(&self, height: u64, proposer: &str) -> StorageResult<Option<Block>> {
         let mut node = self.raft_node.lock().unwrap_or_else(|e| e.into_inner());
         if node.committed_entries.is_empty() {
@@ -126,47 +317,92 @@ impl RaftOrderingService {
 
         // Drain entries, collecting up to max_batch_size valid TXs.
         // Skip raft internal entries (empty data / non-TX).
+        let preferred_max_bytes = self.block_size_limits().preferred_max_bytes;
         let mut tx_ids: Vec<String> = Vec::new();
-        while !node.committed_entries.is_empty() && tx_ids.len() < self.max_batch_size {
+        let mut batch_bytes = 0;
+        let mut config_tx = None;
+        let mut purges = Vec::new();
+        // Index of the last entry drained into this block.
+        let mut last_index = 0;
+        while !node.committed_entries.is_empty() && tx_ids.len() < self.max_batch_size() {
             let entry = node.committed_entries.remove(0);
+            let index = entry.index;
             if entry.data.is_empty() {
+                last_index = index;
                 continue;
             }
             if let Ok(tx) = serde_json::from_slice::<Transaction>(&entry.data) {
+                if !tx_ids.is_empty() && batch_bytes + entry.data.len() > preferred_max_bytes {
+                    node.committed_entries.insert(0, entry);
+                    break;
+                }
+                batch_bytes += entry.data.len();
                 tx_ids.push(tx.id);
+            } else if let Ok(PurgeEntry { purge }) = serde_json::from_slice(&entry.data) {
+                batch_bytes += entry.data.len();
+                tx_ids.push(purge.ledger_tx_id());
+                purges.push(purge);
+            } else if let Ok(ConfigEntry { config_tx: tx }) = serde_json::from_slice(&entry.data) {
+                if tx_ids.is_empty() {
+                    config_tx = Some(tx);
+                    last_index = index;
+                } else {
+                    // Cut the batch so far; the config block comes next.
+                    node.committed_entries.insert(0, entry);
+                }
+                break;
             }
+            last_index = index;
         }
 
-        if tx_ids.is_empty() {
+        if tx_ids.is_empty() && config_tx.is_none() {
             return Ok(None);
         }
-
-        let mut block = Block {
-            height,
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs(),
-            parent_hash: [0u8; 32],
-            merkle_root: [0u8; 32],
-            transactions: tx_ids,
-            proposer: proposer.to_string(),
-            signature: vec![0u8; 64],
-            signature_algorithm: Default::default(),
-            endorsements: vec![],
-            secondary_signature: None,
-            secondary_signature_algorithm: None,
-            hash_algorithm: Default::default(),
-            orderer_signature: None,
+        // Snapshots taken from here on point at this block, and the log may
+        // be compacted up to its last entry.
+        node.record_cut_block(height, last_index);
+
+        let mut block = match config_tx {
+            Some(tx) => crate::channel::ledger::config_block(height, proposer, tx),
+            None => Block {
+                height,
+                timestamp: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+                parent_hash: [0u8; 32],
+                merkle_root: [0u8; 32],
+                transactions: tx_ids,
+                proposer: proposer.to_string(),
+                signature: vec![0u8; 64],
+                signature_algorithm: Default::default(),
+                endorsements: vec![],
+                secondary_signature: None,
+                secondary_signature_algorithm: None,
+                hash_algorithm: Default::default(),
+                orderer_signature: None,
+                commit_qc: None,
+                next_validator_set: None,
+                evidence: Vec::new(),
+                config_tx: None,
+                last_config: 0,
+                state_root: [0u8; 32],
+                private_data_purges: purges,
+            },
         };
 
-        if let Some(provider) = &self.signing_provider {
-            super::sign_block_with_provider(&mut block, provider.as_ref());
+        self.last_config.stamp(&mut block);
+        self.sign(&mut block);
+        Ok(Some(block))
+    }
+
+    /// Sign `block` with the key blocks at its height are signed with.
+    fn sign(&self, block: &mut Block) {
+        if let Some(provider) = self.signer_for(block.height) {
+            super::sign_block_with_provider(block, provider.as_ref());
         } else if let Some(key) = &self.signing_key {
-            super::sign_block(&mut block, key);
+            super::sign_block(block, key);
         }
-
-        Ok(Some(block))
     }
 }
 
@@ -175,13 +411,43 @@ impl super::OrderingBackend for RaftOrderingService {
         self.submit_tx(tx)
     }
 
+    fn submit_endorsed_tx(&self, etx: &EndorsedTransaction) -> StorageResult<()> {
+        self.submit_endorsed_tx(etx)
+    }
+
     fn cut_block(&self, height: u64, proposer: &str) -> StorageResult<Option<Block>> {
         self.cut_block(height, proposer)
     }
 
+    fn seal_block(&self, block: &mut Block, state_root: [u8; 32]) -> bool {
+        block.state_root = state_root;
+        self.sign(block);
+        true
+    }
+
+    fn resume_last_config(&self, height: u64) {
+        self.last_config.resume(height);
+    }
+
     fn pending_count(&self) -> usize {
         self.pending_count()
     }
+
+    fn apply_config_updates(&self, updates: &[ConfigUpdateType]) -> StorageResult<()> {
+        self.apply_config_updates(updates)
+    }
+
+    fn submit_config_tx(&self, tx: &ConfigTransaction) -> StorageResult<()> {
+        self.submit_config_tx(tx)
+    }
+
+    fn submit_purge_tx(&self, request: &PurgeRequest) -> StorageResult<()> {
+        self.submit_purge_tx(request)
+    }
+
+    fn rotate_signer_at(&self, signer: Arc<dyn SigningProvider>, activation_height: u64) {
+        self.schedule_signer_rotation(signer, activation_height);
+    }
 }
 
 #[cfg(test)]
@@ -268,4 +534,65 @@ mod tests {
 
         assert!(svc.cut_block(4, "orderer").unwrap().is_none());
     }
+
+    #[test]
+    fn cut_block_respects_preferred_max_bytes() {
+        let tx_bytes = crate::ordering::tx_size_bytes(&make_tx("tx1"));
+        let svc = RaftOrderingService::new(1, vec![1], 100, 2000)
+            .unwrap()
+            .with_block_size_limits(BlockSizeLimits {
+                preferred_max_bytes: 2 * tx_bytes,
+                absolute_max_bytes: 4 * tx_bytes,
+            });
+        elect(&svc);
+
+        let mut big = make_tx("tx-big");
+        big.state = "x".repeat(4 * tx_bytes);
+        assert!(matches!(
+            svc.submit_tx(&big),
+            Err(crate::storage::errors::StorageError::TransactionTooLarge { .. })
+        ));
+
+        for i in 1..=3 {
+            svc.submit_tx(&make_tx(&format!("tx{i}"))).unwrap();
+        }
+        {
+            let mut node = svc.raft_node.lock().unwrap_or_else(|e| e.into_inner());
+            node.advance();
+        }
+
+        let b1 = svc.cut_block(1, "orderer").unwrap().expect("block 1");
+        assert_eq!(b1.transactions, vec!["tx1", "tx2"]);
+        let b2 = svc.cut_block(2, "orderer").unwrap().expect("block 2");
+        assert_eq!(b2.transactions, vec!["tx3"]);
+    }
+
+    #[test]
+    fn config_tx_is_cut_into_its_own_block_in_log_order() {
+        let svc = RaftOrderingService::new(1, vec![1], 100, 2000).unwrap();
+        elect(&svc);
+
+        let config_tx = ConfigTransaction {
+            tx_id: "cfg-1".to_string(),
+            channel_id: "ch1".to_string(),
+            updates: vec![ConfigUpdateType::SetBatchSize(10)],
+            signatures: vec![],
+            created_at: 0,
+        };
+        svc.submit_tx(&make_tx("tx1")).unwrap();
+        svc.submit_config_tx(&config_tx).unwrap();
+        svc.submit_tx(&make_tx("tx2")).unwrap();
+        {
+            let mut node = svc.raft_node.lock().unwrap_or_else(|e| e.into_inner());
+            node.advance();
+        }
+
+        let b1 = svc.cut_block(1, "orderer").unwrap().expect("block 1");
+        assert_eq!(b1.transactions, vec!["tx1"]);
+        let b2 = svc.cut_block(2, "orderer").unwrap().expect("block 2");
+        assert_eq!(b2.transactions, vec![config_tx.ledger_tx_id()]);
+        assert_eq!(b2.config_tx, Some(config_tx));
+        let b3 = svc.cut_block(3, "orderer").unwrap().expect("block 3");
+        assert_eq!(b3.transactions, vec!["tx2"]);
+    }
 }
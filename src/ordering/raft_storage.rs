@@ -8,6 +8,10 @@
 //!   - `confstate`  → protobuf-encoded ConfState
 //!   - `snapshot`   → protobuf-encoded Snapshot
 //!   - `entry:{index:020}` → protobuf-encoded Entry (zero-padded for lex order)
+//!
+//! The log is compacted once it outgrows a [`CompactionPolicy`]: entries up
+//! to the snapshot index are deleted and the snapshot records the block height
+//! the ledger had reached, so disk usage stays bounded.
 
 use prost::Message as ProstMessage;
 use raft::prelude::*;
@@ -25,6 +29,53 @@ fn entry_key(index: u64) -> Vec<u8> {
     format!("entry:{index:020}").into_bytes()
 }
 
+/// Default number of applied entries kept before the log is compacted.
+pub const DEFAULT_COMPACT_MAX_ENTRIES: u64 = 10_000;
+/// Default on-disk log size (bytes) that triggers compaction.
+pub const DEFAULT_COMPACT_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+/// When to compact the Raft log. Compaction triggers as soon as either
+/// limit is exceeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactionPolicy {
+    /// Maximum number of entries retained in the log.
+    pub max_entries: u64,
+    /// Maximum total encoded size of the retained entries.
+    pub max_bytes: u64,
+}
+
+impl Default for CompactionPolicy {
+    fn default() -> Self {
+        Self {
+            max_entries: DEFAULT_COMPACT_MAX_ENTRIES,
+            max_bytes: DEFAULT_COMPACT_MAX_BYTES,
+        }
+    }
+}
+
+impl CompactionPolicy {
+    /// Read `RAFT_COMPACT_MAX_ENTRIES` and `RAFT_COMPACT_MAX_BYTES`, falling
+    /// back to the defaults for missing or invalid values.
+    pub fn from_env() -> Self {
+        let read = |name: &str, default: u64| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .filter(|v| *v > 0)
+                .unwrap_or(default)
+        };
+        Self {
+            max_entries: read("RAFT_COMPACT_MAX_ENTRIES", DEFAULT_COMPACT_MAX_ENTRIES),
+            max_bytes: read("RAFT_COMPACT_MAX_BYTES", DEFAULT_COMPACT_MAX_BYTES),
+        }
+    }
+
+    /// Whether a log of `entries` entries totalling `bytes` must be compacted.
+    pub fn exceeded(&self, entries: u64, bytes: u64) -> bool {
+        entries > self.max_entries || bytes > self.max_bytes
+    }
+}
+
 type RocksDB = DBWithThreadMode<MultiThreaded>;
 
 /// Persistent Raft storage using RocksDB.
@@ -32,6 +83,8 @@ pub struct RocksDbRaftStorage {
     db: RocksDB,
     /// Cached first index (inclusive) — entries before this have been compacted.
     first_index: Mutex<u64>,
+    /// Approximate encoded size of the retained log entries.
+    log_bytes: Mutex<u64>,
 }
 
 impl RocksDbRaftStorage {
@@ -45,30 +98,41 @@ impl RocksDbRaftStorage {
         let db = RocksDB::open_cf_descriptors(&opts, path, vec![cf])
             .map_err(|e| format!("failed to open raft DB: {e}"))?;
 
-        let first_index = {
+        let (first_index, log_bytes) = {
             let cf_handle = db.cf_handle(CF_RAFT).ok_or("missing raft CF")?;
-            // Find the first entry key by iterating from the start.
-            let mut first = 1u64;
-            for (key, _) in db
+            // Find the first entry key and total log size in one pass.
+            let mut first: Option<u64> = None;
+            let mut bytes = 0u64;
+            for (key, value) in db
                 .iterator_cf(&cf_handle, rocksdb::IteratorMode::Start)
                 .flatten()
             {
                 if let Ok(key_str) = std::str::from_utf8(&key) {
                     if let Some(idx_str) = key_str.strip_prefix("entry:") {
                         if let Ok(idx) = idx_str.parse::<u64>() {
-                            first = idx;
-                            break;
+                            first.get_or_insert(idx);
+                            bytes += value.len() as u64;
                         }
                     }
                 }
             }
-            first
+            (first.unwrap_or(1), bytes)
         };
 
-        Ok(Self {
+        let storage = Self {
             db,
             first_index: Mutex::new(first_index),
-        })
+            log_bytes: Mutex::new(log_bytes),
+        };
+        // Entries up to the snapshot index are covered by the snapshot.
+        if let Some(meta) = storage.snapshot_metadata() {
+            let mut first = storage
+                .first_index
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            *first = (*first).max(meta.index + 1);
+        }
+        Ok(storage)
     }
 
     /// Initialize with a conf state (for new clusters).
@@ -90,6 +154,30 @@ impl RocksDbRaftStorage {
         Ok(())
     }
 
+    /// Whether a hard state has already been written (i.e. not a first boot).
+    pub fn is_initialized(&self) -> Result<bool, String> {
+        let cf = self.cf_handle()?;
+        Ok(self
+            .db
+            .get_cf(&cf, KEY_HARD_STATE)
+            .map_err(|e| e.to_string())?
+            .is_some())
+    }
+
+    /// Initialize a founding member from the bootstrap snapshot at index 1, so
+    /// nodes added later catch up by snapshot and inherit the membership.
+    pub fn bootstrap(&self, cs: &ConfState) -> Result<(), String> {
+        if self.is_initialized()? {
+            return Ok(());
+        }
+        self.apply_snapshot_data(&super::raft_node::bootstrap_snapshot(cs.clone()))?;
+        self.put_hard_state(&HardState {
+            term: 1,
+            vote: 0,
+            commit: 1,
+        })
+    }
+
     fn cf_handle(&self) -> Result<std::sync::Arc<rocksdb::BoundColumnFamily<'_>>, String> {
         self.db
             .cf_handle(CF_RAFT)
@@ -138,10 +226,15 @@ impl RocksDbRaftStorage {
     pub fn append_entries(&self, entries: &[Entry]) -> Result<(), String> {
         let cf = self.cf_handle()?;
         let mut batch = rocksdb::WriteBatch::default();
+        let mut added = 0u64;
         for entry in entries {
-            batch.put_cf(&cf, entry_key(entry.index), entry.encode_to_vec());
+            let bytes = entry.encode_to_vec();
+            added += bytes.len() as u64;
+            batch.put_cf(&cf, entry_key(entry.index), bytes);
         }
-        self.db.write(batch).map_err(|e| e.to_string())
+        self.db.write(batch).map_err(|e| e.to_string())?;
+        *self.log_bytes.lock().unwrap_or_else(|e| e.into_inner()) += added;
+        Ok(())
     }
 
     /// Set the hard state (term, vote, commit).
@@ -149,8 +242,16 @@ impl RocksDbRaftStorage {
         self.put_hard_state(hs)
     }
 
-    #[allow(dead_code)]
+    /// Persist the conf state (voters and learners) after a membership change.
+    pub fn set_conf_state(&self, cs: &ConfState) -> Result<(), String> {
+        self.put_conf_state(cs)
+    }
+
     /// Apply a snapshot — replace all state.
+    ///
+    /// The whole log is discarded: a snapshot received from the leader
+    /// supersedes every local entry, and the entries that follow it arrive
+    /// with the next append.
     pub fn apply_snapshot_data(&self, snap: &Snapshot) -> Result<(), String> {
         let cf = self.cf_handle()?;
         // Store the snapshot.
@@ -159,12 +260,84 @@ impl RocksDbRaftStorage {
             .map_err(|e| e.to_string())?;
         // Update conf state from snapshot metadata.
         self.put_conf_state(snap.get_metadata().get_conf_state())?;
+        self.delete_entries_through(u64::MAX)?;
         // Update first_index to snapshot index + 1.
         let snap_idx = snap.get_metadata().index;
         *self.first_index.lock().unwrap_or_else(|e| e.into_inner()) = snap_idx + 1;
         Ok(())
     }
 
+    /// Compact the log up to and including the snapshot index.
+    ///
+    /// `snap` is built locally from applied state, so entries after its index
+    /// are kept. The freed range is compacted in RocksDB so the space is
+    /// actually reclaimed.
+    pub fn compact(&self, snap: &Snapshot) -> Result<(), String> {
+        let snap_idx = snap.get_metadata().index;
+        let first = *self.first_index.lock().unwrap_or_else(|e| e.into_inner());
+        if snap_idx < first {
+            return Ok(()); // Already compacted past this point.
+        }
+        let cf = self.cf_handle()?;
+        self.db
+            .put_cf(&cf, KEY_SNAPSHOT, snap.encode_to_vec())
+            .map_err(|e| e.to_string())?;
+        self.delete_entries_through(snap_idx)?;
+        *self.first_index.lock().unwrap_or_else(|e| e.into_inner()) = snap_idx + 1;
+        Ok(())
+    }
+
+    /// Whether the retained log has outgrown `policy`.
+    pub fn needs_compaction(&self, policy: &CompactionPolicy) -> bool {
+        let first = *self.first_index.lock().unwrap_or_else(|e| e.into_inner());
+        let last = self.last_index_inner();
+        let entries = if last >= first { last - first + 1 } else { 0 };
+        policy.exceeded(entries, self.log_bytes())
+    }
+
+    /// Approximate encoded size of the retained log entries.
+    pub fn log_bytes(&self) -> u64 {
+        *self.log_bytes.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Block height recorded in the latest snapshot, if any.
+    pub fn snapshot_block_height(&self) -> Option<u64> {
+        self.snapshot_data().map(|d| d.block_height)
+    }
+
+    /// Application payload of the latest snapshot, if any.
+    pub fn snapshot_data(&self) -> Option<super::raft_node::RaftSnapshotData> {
+        let cf = self.cf_handle().ok()?;
+        let bytes = self.db.get_cf(&cf, KEY_SNAPSHOT).ok()??;
+        let snap = Snapshot::decode(bytes.as_slice()).ok()?;
+        super::raft_node::RaftSnapshotData::decode(&snap.data)
+    }
+
+    /// Delete every entry with index `<= last` and recompute the log size.
+    fn delete_entries_through(&self, last: u64) -> Result<(), String> {
+        let cf = self.cf_handle()?;
+        let from = entry_key(0);
+        let to = entry_key(last.saturating_add(1));
+        let mut batch = rocksdb::WriteBatch::default();
+        batch.delete_range_cf(&cf, &from, &to);
+        self.db.write(batch).map_err(|e| e.to_string())?;
+        self.db.compact_range_cf(&cf, Some(&from), Some(&to));
+
+        let mut bytes = 0u64;
+        let iter = self.db.iterator_cf(
+            &cf,
+            rocksdb::IteratorMode::From(&to, rocksdb::Direction::Forward),
+        );
+        for (key, value) in iter.flatten() {
+            if !key.starts_with(b"entry:") {
+                break;
+            }
+            bytes += value.len() as u64;
+        }
+        *self.log_bytes.lock().unwrap_or_else(|e| e.into_inner()) = bytes;
+        Ok(())
+    }
+
     fn get_entry(&self, index: u64) -> Result<Option<Entry>, String> {
         let cf = self.cf_handle()?;
         match self
@@ -180,6 +353,14 @@ impl RocksDbRaftStorage {
         }
     }
 
+    fn snapshot_metadata(&self) -> Option<SnapshotMetadata> {
+        let cf = self.cf_handle().ok()?;
+        let bytes = self.db.get_cf(&cf, KEY_SNAPSHOT).ok()??;
+        Snapshot::decode(bytes.as_slice())
+            .ok()
+            .map(|s| s.get_metadata().clone())
+    }
+
     fn last_index_inner(&self) -> u64 {
         let cf = match self.cf_handle() {
             Ok(cf) => cf,
@@ -200,6 +381,13 @@ impl RocksDbRaftStorage {
     }
 }
 
+#[cfg(feature = "rocksdb-storage")]
+impl crate::storage::backup::BackupSource for RocksDbRaftStorage {
+    fn database(&self) -> &RocksDB {
+        &self.db
+    }
+}
+
 impl raft::Storage for RocksDbRaftStorage {
     fn initial_state(&self) -> raft::Result<RaftState> {
         let hs = self
@@ -245,6 +433,11 @@ impl raft::Storage for RocksDbRaftStorage {
     }
 
     fn term(&self, idx: u64) -> raft::Result<u64> {
+        if let Some(meta) = self.snapshot_metadata() {
+            if meta.index == idx && idx > 0 {
+                return Ok(meta.term);
+            }
+        }
         let first = *self.first_index.lock().unwrap_or_else(|e| e.into_inner());
         if idx < first && idx > 0 {
             return Err(RaftError::Store(StorageError::Compacted));
@@ -399,6 +592,58 @@ mod tests {
         assert_eq!(state.hard_state.commit, 3);
     }
 
+    #[test]
+    fn bootstrap_starts_log_after_snapshot() {
+        let dir = tempfile::TempDir::new().unwrap();
+        {
+            let storage = RocksDbRaftStorage::new(dir.path()).unwrap();
+            let cs = ConfState {
+                voters: vec![1, 2, 3],
+                ..Default::default()
+            };
+            storage.bootstrap(&cs).unwrap();
+            assert!(storage.is_initialized().unwrap());
+            assert_eq!(storage.first_index().unwrap(), 2);
+            assert_eq!(storage.last_index().unwrap(), 1);
+            assert_eq!(storage.term(1).unwrap(), 1);
+        }
+        // The snapshot boundary survives a reopen even with no entries yet.
+        let storage = RocksDbRaftStorage::new(dir.path()).unwrap();
+        assert_eq!(storage.first_index().unwrap(), 2);
+        assert_eq!(storage.initial_state().unwrap().hard_state.commit, 1);
+    }
+
+    #[test]
+    fn conf_state_persists_across_reopen() {
+        let dir = tempfile::TempDir::new().unwrap();
+        {
+            let storage = RocksDbRaftStorage::new(dir.path()).unwrap();
+            let cs = ConfState {
+                voters: vec![1, 2, 3],
+                ..Default::default()
+            };
+            storage.initialize(&cs).unwrap();
+            let updated = ConfState {
+                voters: vec![2, 3],
+                learners: vec![4],
+                ..Default::default()
+            };
+            storage.set_conf_state(&updated).unwrap();
+        }
+        let storage = RocksDbRaftStorage::new(dir.path()).unwrap();
+        // Re-initializing with the static peer list must not clobber the
+        // persisted membership.
+        storage
+            .initialize(&ConfState {
+                voters: vec![1, 2, 3],
+                ..Default::default()
+            })
+            .unwrap();
+        let state = storage.initial_state().unwrap();
+        assert_eq!(state.conf_state.voters, vec![2, 3]);
+        assert_eq!(state.conf_state.learners, vec![4]);
+    }
+
     #[test]
     fn entries_persist_across_reopen() {
         let dir = tempfile::TempDir::new().unwrap();
@@ -424,4 +669,104 @@ mod tests {
         assert_eq!(entries.len(), 1);
         assert_eq!(entries[0].data, b"persistent");
     }
+
+    fn entry(index: u64, size: usize) -> Entry {
+        Entry {
+            index,
+            term: 1,
+            data: vec![0u8; size],
+            ..Default::default()
+        }
+    }
+
+    fn snapshot_at(index: u64, block_height: u64) -> Snapshot {
+        let mut snap = Snapshot::default();
+        snap.mut_metadata().index = index;
+        snap.mut_metadata().term = 1;
+        snap.data = crate::ordering::raft_node::RaftSnapshotData {
+            block_height,
+            log_index: index,
+        }
+        .encode();
+        snap
+    }
+
+    #[test]
+    fn compaction_policy_triggers_on_entries_or_bytes() {
+        let policy = CompactionPolicy {
+            max_entries: 10,
+            max_bytes: 1_000,
+        };
+        assert!(!policy.exceeded(10, 1_000));
+        assert!(policy.exceeded(11, 0));
+        assert!(policy.exceeded(1, 1_001));
+    }
+
+    #[test]
+    fn compact_drops_prefix_and_keeps_tail() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let storage = RocksDbRaftStorage::new(dir.path()).unwrap();
+        storage.initialize(&ConfState::default()).unwrap();
+        let entries: Vec<Entry> = (1..=20).map(|i| entry(i, 100)).collect();
+        storage.append_entries(&entries).unwrap();
+        let policy = CompactionPolicy {
+            max_entries: 10,
+            max_bytes: u64::MAX,
+        };
+        assert!(storage.needs_compaction(&policy));
+        let full = storage.log_bytes();
+
+        storage.compact(&snapshot_at(15, 3)).unwrap();
+
+        assert_eq!(storage.first_index().unwrap(), 16);
+        assert_eq!(storage.last_index().unwrap(), 20);
+        assert_eq!(storage.term(15).unwrap(), 1);
+        assert!(matches!(
+            storage.entries(10, 16, None, GetEntriesContext::empty(false)),
+            Err(RaftError::Store(StorageError::Compacted))
+        ));
+        assert_eq!(
+            storage
+                .entries(16, 21, None, GetEntriesContext::empty(false))
+                .unwrap()
+                .len(),
+            5
+        );
+        assert!(storage.log_bytes() < full / 3);
+        assert!(!storage.needs_compaction(&policy));
+        assert_eq!(storage.snapshot_block_height(), Some(3));
+        assert_eq!(storage.snapshot_data().unwrap().log_index, 15);
+    }
+
+    #[test]
+    fn compaction_survives_reopen() {
+        let dir = tempfile::TempDir::new().unwrap();
+        {
+            let storage = RocksDbRaftStorage::new(dir.path()).unwrap();
+            storage.initialize(&ConfState::default()).unwrap();
+            let entries: Vec<Entry> = (1..=10).map(|i| entry(i, 10)).collect();
+            storage.append_entries(&entries).unwrap();
+            storage.compact(&snapshot_at(10, 4)).unwrap();
+        }
+        let storage = RocksDbRaftStorage::new(dir.path()).unwrap();
+        assert_eq!(storage.first_index().unwrap(), 11);
+        assert_eq!(storage.last_index().unwrap(), 10);
+        assert_eq!(storage.log_bytes(), 0);
+        assert_eq!(storage.snapshot_block_height(), Some(4));
+    }
+
+    #[test]
+    fn applied_snapshot_replaces_whole_log() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let storage = RocksDbRaftStorage::new(dir.path()).unwrap();
+        storage.initialize(&ConfState::default()).unwrap();
+        let entries: Vec<Entry> = (1..=5).map(|i| entry(i, 10)).collect();
+        storage.append_entries(&entries).unwrap();
+
+        storage.apply_snapshot_data(&snapshot_at(3, 2)).unwrap();
+
+        assert_eq!(storage.first_index().unwrap(), 4);
+        assert_eq!(storage.last_index().unwrap(), 3);
+        assert_eq!(storage.log_bytes(), 0);
+    }
 }
@@ -24,22 +24,35 @@ pub type PeerMap = Arc<Mutex<HashMap<u64, String>>>;
 /// Parse a `RAFT_PEERS` string into a `PeerMap`.
 ///
 /// Format: `"1:orderer1:8087,2:orderer2:8087,3:orderer3:8087"`
-/// Each entry is `{raft_id}:{host}:{port}`.
+/// Each entry is `{raft_id}:{host}:{port}`, optionally prefixed with its
+/// orderer org as `{org}/` (see [`parse_raft_peer_orgs`]).
 pub fn parse_raft_peers(s: &str) -> HashMap<u64, String> {
-    let mut map = HashMap::new();
-    for entry in s.split(',') {
+    raft_peer_entries(s)
+        .map(|(_, id, addr)| (id, addr.to_string()))
+        .collect()
+}
+
+/// Orderer orgs of the `RAFT_PEERS` entries prefixed with `{org}/`, by raft
+/// node ID.
+pub fn parse_raft_peer_orgs(s: &str) -> HashMap<u64, String> {
+    raft_peer_entries(s)
+        .filter_map(|(org, id, _)| Some((id, org?.to_string())))
+        .collect()
+}
+
+/// `(org, raft_id, address)` of each well-formed `RAFT_PEERS` entry.
+fn raft_peer_entries(s: &str) -> impl Iterator<Item = (Option<&str>, u64, &str)> {
+    s.split(',').filter_map(|entry| {
         let entry = entry.trim();
-        if entry.is_empty() {
-            continue;
-        }
+        let (org, entry) = match entry.split_once('/') {
+            Some((org, rest)) if !org.is_empty() => (Some(org), rest),
+            Some(_) => return None,
+            None => (None, entry),
+        };
         // Split on first ':' only — the rest is the address.
-        if let Some((id_str, addr)) = entry.split_once(':') {
-            if let Ok(id) = id_str.parse::<u64>() {
-                map.insert(id, addr.to_string());
-            }
-        }
-    }
-    map
+        let (id_str, addr) = entry.split_once(':')?;
+        Some((org, id_str.parse::<u64>().ok()?, addr))
+    })
 }
 
 /// Tick the raft node, advance it, and return serialized outbound messages
@@ -72,23 +85,38 @@ pub fn deliver_raw(node: &mut RaftNode, data: &[u8]) -> Result<(), String> {
 /// 1. Locks the `RaftNode` and calls `tick_and_collect()`.
 /// 2. For each outbound message, looks up the destination in `peer_map`.
 /// 3. Sends `Message::RaftMessage(bytes)` to the peer via `Node::send_and_wait`
-///    (fire-and-forget — we ignore the response).
+///    (fire-and-forget — we ignore the response). A channel-scoped group
+///    (`channel_id` set) wraps it in `Message::ChannelOrdering`.
+/// 4. If a snapshot was installed, syncs the blocks it points at from peers
+///    (node-wide cluster only).
 pub fn start_raft_tick_loop(
     raft_node: Arc<Mutex<RaftNode>>,
     peer_map: PeerMap,
     p2p_node: Arc<crate::network::Node>,
     tick_ms: u64,
+    channel_id: Option<String>,
 ) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
         let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(tick_ms));
         loop {
             interval.tick().await;
 
-            let outbound = {
+            let (outbound, sync_target) = {
                 let mut node = raft_node.lock().unwrap_or_else(|e| e.into_inner());
-                tick_and_collect(&mut node)
+                let outbound = tick_and_collect(&mut node);
+                (outbound, node.take_block_sync_target())
             };
 
+            // An installed snapshot points past our ledger: fetch the missing
+            // blocks from peers rather than replaying the compacted log.
+            if let Some(target) = sync_target.filter(|_| channel_id.is_none()) {
+                let node = p2p_node.clone();
+                tokio::spawn(async move {
+                    let reached = node.sync_blocks_to(target).await;
+                    log::info!("Raft snapshot block sync: target={target} reached={reached}");
+                });
+            }
+
             if outbound.is_empty() {
                 continue;
             }
@@ -98,7 +126,10 @@ pub fn start_raft_tick_loop(
                 let Some(addr) = map.get(&to_id) else {
                     continue;
                 };
-                let msg = crate::network::Message::RaftMessage(data);
+                let msg = crate::ordering::groups::scoped_message(
+                    channel_id.as_deref(),
+                    crate::network::Message::RaftMessage(data),
+                );
                 let addr = addr.clone();
                 let node = p2p_node.clone();
                 // Fire-and-forget: send raft message, ignore response.
@@ -223,4 +254,16 @@ mod tests {
         assert_eq!(map.len(), 1);
         assert_eq!(map[&2], "valid:5678");
     }
+
+    #[test]
+    fn parse_raft_peers_reads_org_prefixes() {
+        let s = "OrdererA/1:orderer1:8087,OrdererB/2:orderer2:8087,3:orderer3:8087,/4:x:1";
+        let map = parse_raft_peers(s);
+        assert_eq!(map.len(), 3);
+        assert_eq!(map[&1], "orderer1:8087");
+        let orgs = parse_raft_peer_orgs(s);
+        assert_eq!(orgs.len(), 2);
+        assert_eq!(orgs[&1], "OrdererA");
+        assert_eq!(orgs[&2], "OrdererB");
+    }
 }
@@ -1,8 +1,15 @@
 use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 
+use crate::channel::config::{
+    ConfigTransaction, ConfigUpdateType, DEFAULT_ABSOLUTE_MAX_BYTES, DEFAULT_PREFERRED_MAX_BYTES,
+};
 use crate::identity::signing::SigningProvider;
 use crate::metrics::MetricsCollector;
+use crate::ordering::admission::{AdmissionChain, AdmissionContext, AdmissionError, Envelope};
+use crate::ordering::{tx_size_bytes, BlockSizeLimits};
+use crate::private_data::PurgeRequest;
 use crate::storage::{
     errors::StorageResult,
     traits::{Block, BlockStore, Transaction},
@@ -11,12 +18,21 @@ use crate::storage::{
 /// Collects endorsed transactions and cuts them into ordered blocks.
 pub struct OrderingService {
     pub(crate) pending_txs: Mutex<VecDeque<Transaction>>,
-    pub max_batch_size: usize,
-    #[allow(dead_code)]
-    pub batch_timeout_ms: u64,
+    /// Config transactions waiting for a config block of their own.
+    pending_config: Mutex<VecDeque<ConfigTransaction>>,
+    /// Private data purges waiting for the next block.
+    pending_purges: Mutex<VecDeque<PurgeRequest>>,
+    max_batch_size: AtomicUsize,
+    batch_timeout_ms: AtomicU64,
+    preferred_max_bytes: AtomicUsize,
+    absolute_max_bytes: AtomicUsize,
+    admission: AdmissionChain,
     metrics: Option<Arc<MetricsCollector>>,
     signing_key: Option<ed25519_dalek::SigningKey>,
-    signing_provider: Option<Arc<dyn SigningProvider>>,
+    signing_provider: Mutex<Option<Arc<dyn SigningProvider>>>,
+    /// Signer taking over at the given height after a key rotation.
+    next_signer: Mutex<Option<(u64, Arc<dyn SigningProvider>)>>,
+    last_config: super::LastConfig,
 }
 
 impl Default for OrderingService {
@@ -29,6 +45,7 @@ impl OrderingService {
     /// Create a new `OrderingService` reading config from env:
     /// - `ORDERING_BATCH_SIZE` (default 100)
     /// - `ORDERING_BATCH_TIMEOUT_MS` (default 2000)
+    /// - block byte limits, see [`BlockSizeLimits::from_env`]
     pub fn new() -> Self {
         let max_batch_size = std::env::var("ORDERING_BATCH_SIZE")
             .ok()
@@ -41,21 +58,45 @@ impl OrderingService {
             .unwrap_or(2000);
 
         Self::with_config(max_batch_size, batch_timeout_ms)
+            .with_block_size_limits(BlockSizeLimits::from_env())
     }
 
     pub fn with_config(max_batch_size: usize, batch_timeout_ms: u64) -> Self {
         Self {
             pending_txs: Mutex::new(VecDeque::new()),
-            max_batch_size,
-            batch_timeout_ms,
+            pending_config: Mutex::new(VecDeque::new()),
+            pending_purges: Mutex::new(VecDeque::new()),
+            max_batch_size: AtomicUsize::new(max_batch_size),
+            batch_timeout_ms: AtomicU64::new(batch_timeout_ms),
+            preferred_max_bytes: AtomicUsize::new(DEFAULT_PREFERRED_MAX_BYTES),
+            absolute_max_bytes: AtomicUsize::new(DEFAULT_ABSOLUTE_MAX_BYTES),
+            admission: AdmissionChain::standard(),
             metrics: None,
             signing_key: None,
-            signing_provider: None,
+            signing_provider: Mutex::new(None),
+            next_signer: Mutex::new(None),
+            last_config: super::LastConfig::default(),
         }
     }
 
-    #[allow(dead_code)]
-    /// Attach a metrics collector so `cut_block` increments `ordering_blocks_cut_total`.
+    /// Set the preferred and absolute block byte limits.
+    pub fn with_block_size_limits(self, limits: BlockSizeLimits) -> Self {
+        self.preferred_max_bytes
+            .store(limits.preferred_max_bytes, Ordering::Relaxed);
+        self.absolute_max_bytes
+            .store(limits.absolute_max_bytes, Ordering::Relaxed);
+        self
+    }
+
+    /// Replace the admission filters run on every submitted transaction
+    /// (default: [`AdmissionChain::standard`]).
+    pub fn with_admission(mut self, admission: AdmissionChain) -> Self {
+        self.admission = admission;
+        self
+    }
+
+    /// Attach a metrics collector so `cut_block` increments `ordering_blocks_cut_total`
+    /// and observes `ordering_block_size_bytes`.
     pub fn with_metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
         self.metrics = Some(metrics);
         self
@@ -71,12 +112,87 @@ impl OrderingService {
     /// Attach a pluggable signing provider (Ed25519 or ML-DSA-65).
     /// When set, `cut_block` signs both the proposer and orderer fields.
     pub fn with_signing_provider(mut self, provider: Arc<dyn SigningProvider>) -> Self {
-        self.signing_provider = Some(provider);
+        *self
+            .signing_provider
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner()) = Some(provider);
         self
     }
 
-    /// Enqueue a transaction for the next ordered block.
+    /// Switch to `provider` for blocks at `activation_height` and above,
+    /// matching a committed key rotation of this orderer.
+    pub fn schedule_signer_rotation(
+        &self,
+        provider: Arc<dyn SigningProvider>,
+        activation_height: u64,
+    ) {
+        *self.next_signer.lock().unwrap_or_else(|e| e.into_inner()) =
+            Some((activation_height, provider));
+    }
+
+    /// Signer for a block at `height`, promoting a scheduled rotation once
+    /// its activation height is reached.
+    fn signer_for(&self, height: u64) -> Option<Arc<dyn SigningProvider>> {
+        let mut current = self
+            .signing_provider
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let mut next = self.next_signer.lock().unwrap_or_else(|e| e.into_inner());
+        if next.as_ref().is_some_and(|(at, _)| height >= *at) {
+            *current = next.take().map(|(_, provider)| provider);
+        }
+        current.clone()
+    }
+
+    /// Maximum number of transactions per cut block.
+    pub fn max_batch_size(&self) -> usize {
+        self.max_batch_size.load(Ordering::Relaxed)
+    }
+
+    /// Interval of the timer-driven batch loop.
+    pub fn batch_timeout_ms(&self) -> u64 {
+        self.batch_timeout_ms.load(Ordering::Relaxed)
+    }
+
+    /// Current block byte limits.
+    pub fn block_size_limits(&self) -> BlockSizeLimits {
+        BlockSizeLimits {
+            preferred_max_bytes: self.preferred_max_bytes.load(Ordering::Relaxed),
+            absolute_max_bytes: self.absolute_max_bytes.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Follow the channel's `batch_size` / `batch_timeout_ms` / max-bytes updates.
+    pub fn apply_config_updates(&self, updates: &[ConfigUpdateType]) -> StorageResult<()> {
+        for update in updates {
+            match update {
+                ConfigUpdateType::SetBatchSize(size) => {
+                    self.max_batch_size.store(*size, Ordering::Relaxed)
+                }
+                ConfigUpdateType::SetBatchTimeout(ms) => {
+                    self.batch_timeout_ms.store(*ms, Ordering::Relaxed)
+                }
+                ConfigUpdateType::SetMaxBytes {
+                    preferred_max_bytes,
+                    absolute_max_bytes,
+                } => {
+                    self.preferred_max_bytes
+                        .store(*preferred_max_bytes, Ordering::Relaxed);
+                    self.absolute_max_bytes
+                        .store(*absolute_max_bytes, Ordering::Relaxed);
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Enqueue a transaction for the next ordered block once it passes
+    /// admission control. Transactions above the absolute block size are
+    /// rejected with `TransactionTooLarge`, other refusals with
+    /// `AdmissionRejected`.
     pub fn submit_tx(&self, tx: Transaction) -> StorageResult<()> {
+        self.admit(&Envelope::plain(&tx))?;
         self.pending_txs
             .lock()
             .unwrap_or_else(|e| e.into_inner())
@@ -84,45 +200,114 @@ impl OrderingService {
         Ok(())
     }
 
-    #[allow(dead_code)]
-    /// Enqueue an endorsed transaction for the next ordered block.
-    ///
-    /// Extracts the inner `Transaction` from the proposal and enqueues it.
-    /// For MVP the endorsement metadata is validated by the Gateway before
-    /// calling this method; a future version should carry the full
-    /// `EndorsedTransaction` through to the block so committer peers can
-    /// re-validate endorsements.
+    /// Enqueue an endorsed transaction for the next ordered block once its
+    /// envelope (creator, endorsements) passes admission control. Only the
+    /// inner `Transaction` enters the block.
     pub fn submit_endorsed_tx(
         &self,
-        etx: crate::transaction::endorsed::EndorsedTransaction,
+        etx: &crate::transaction::endorsed::EndorsedTransaction,
     ) -> StorageResult<()> {
+        self.admit(&Envelope::endorsed(etx))?;
         self.pending_txs
             .lock()
             .unwrap_or_else(|e| e.into_inner())
-            .push_back(etx.proposal.tx);
+            .push_back(etx.proposal.tx.clone());
         Ok(())
     }
 
-    /// Number of transactions currently waiting to be ordered.
+    fn admit(&self, envelope: &Envelope<'_>) -> StorageResult<()> {
+        let ctx = AdmissionContext::now(self.block_size_limits());
+        if let Err(e) = self.admission.admit(envelope, &ctx) {
+            if let Some(m) = &self.metrics {
+                m.record_ordering_tx_rejected(e.reason());
+                if matches!(e, AdmissionError::TooLarge { .. }) {
+                    m.record_ordering_tx_oversized();
+                }
+            }
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    /// Enqueue a channel config transaction. It is cut alone, ahead of the
+    /// pending transactions.
+    pub fn submit_config_tx(&self, tx: ConfigTransaction) -> StorageResult<()> {
+        self.pending_config
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push_back(tx);
+        Ok(())
+    }
+
+    /// Enqueue a private data purge. It is cut into the next block, ahead
+    /// of the pending transactions.
+    pub fn submit_purge_tx(&self, request: PurgeRequest) -> StorageResult<()> {
+        self.pending_purges
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push_back(request);
+        Ok(())
+    }
+
+    /// Number of transactions (config transactions and purges included)
+    /// currently waiting to be ordered.
     pub fn pending_count(&self) -> usize {
         self.pending_txs
             .lock()
             .unwrap_or_else(|e| e.into_inner())
             .len()
+            + self
+                .pending_config
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .len()
+            + self
+                .pending_purges
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .len()
     }
 
-    /// Drain up to `max_batch_size` transactions and create an ordered `Block`.
-    /// Returns `None` if the pending queue is empty.
+    /// Drain up to `max_batch_size` transactions, stopping before the one that
+    /// would push the block past `preferred_max_bytes`, and create an ordered
+    /// `Block`. A pending config transaction is cut first, into a config block;
+    /// pending purges lead the next regular block, each listed under its
+    /// ledger ID. Returns `None` if the pending queues are empty.
     pub fn cut_block(&self, height: u64, proposer: &str) -> StorageResult<Option<Block>> {
+        let config_tx = self
+            .pending_config
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pop_front();
+        if let Some(tx) = config_tx {
+            let block = crate::channel::ledger::config_block(height, proposer, tx);
+            return Ok(Some(self.seal(block, 0)));
+        }
+
+        let purges: Vec<PurgeRequest> = {
+            let mut purges = self
+                .pending_purges
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            let count = purges.len().min(self.max_batch_size());
+            purges.drain(..count).collect()
+        };
         let mut queue = self.pending_txs.lock().unwrap_or_else(|e| e.into_inner());
-        if queue.is_empty() {
+        if queue.is_empty() && purges.is_empty() {
             return Ok(None);
         }
 
-        let count = queue.len().min(self.max_batch_size);
-        let tx_ids: Vec<String> = queue.drain(..count).map(|tx| tx.id).collect();
+        let (count, bytes) = self.block_size_limits().batch_len(
+            queue.iter().map(tx_size_bytes),
+            self.max_batch_size() - purges.len(),
+        );
+        let tx_ids: Vec<String> = purges
+            .iter()
+            .map(PurgeRequest::ledger_tx_id)
+            .chain(queue.drain(..count).map(|tx| tx.id))
+            .collect();
 
-        let mut block = Block {
+        let block = Block {
             height,
             timestamp: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
@@ -139,18 +324,36 @@ impl OrderingService {
             secondary_signature_algorithm: None,
             hash_algorithm: Default::default(),
             orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: Vec::new(),
+            config_tx: None,
+            last_config: 0,
+            state_root: [0u8; 32],
+            private_data_purges: purges,
         };
+        Ok(Some(self.seal(block, bytes)))
+    }
 
-        if let Some(provider) = &self.signing_provider {
-            super::sign_block_with_provider(&mut block, provider.as_ref());
+    /// Sign `block` with the key blocks at its height are signed with.
+    fn sign(&self, block: &mut Block) {
+        if let Some(provider) = self.signer_for(block.height) {
+            super::sign_block_with_provider(block, provider.as_ref());
         } else if let Some(key) = &self.signing_key {
-            super::sign_block(&mut block, key);
+            super::sign_block(block, key);
         }
+    }
 
+    /// Stamp and sign a cut block and record it, with `bytes` of transaction
+    /// payload.
+    fn seal(&self, mut block: Block, bytes: usize) -> Block {
+        self.last_config.stamp(&mut block);
+        self.sign(&mut block);
         if let Some(m) = &self.metrics {
             m.record_ordering_block_cut();
+            m.record_ordering_block_size(bytes);
         }
-        Ok(Some(block))
+        block
     }
 }
 
@@ -160,7 +363,7 @@ impl OrderingService {
 /// Launched via `tokio::spawn` in `main.rs` when `role == Orderer || PeerAndOrderer`.
 /// The height counter is local to this loop; a future phase can derive it from the store.
 pub async fn run_batch_loop(service: Arc<OrderingService>, store: Arc<dyn BlockStore>) {
-    let timeout = tokio::time::Duration::from_millis(service.batch_timeout_ms);
+    let timeout = tokio::time::Duration::from_millis(service.batch_timeout_ms());
     let mut height: u64 = store.get_latest_height().unwrap_or(0) + 1;
 
     loop {
@@ -183,6 +386,23 @@ impl super::OrderingBackend for OrderingService {
         self.submit_tx(tx.clone())
     }
 
+    fn submit_endorsed_tx(
+        &self,
+        etx: &crate::transaction::endorsed::EndorsedTransaction,
+    ) -> StorageResult<()> {
+        self.submit_endorsed_tx(etx)
+    }
+
+    fn seal_block(&self, block: &mut Block, state_root: [u8; 32]) -> bool {
+        block.state_root = state_root;
+        self.sign(block);
+        true
+    }
+
+    fn resume_last_config(&self, height: u64) {
+        self.last_config.resume(height);
+    }
+
     fn cut_block(&self, height: u64, proposer: &str) -> StorageResult<Option<Block>> {
         self.cut_block(height, proposer)
     }
@@ -190,6 +410,22 @@ impl super::OrderingBackend for OrderingService {
     fn pending_count(&self) -> usize {
         self.pending_count()
     }
+
+    fn apply_config_updates(&self, updates: &[ConfigUpdateType]) -> StorageResult<()> {
+        self.apply_config_updates(updates)
+    }
+
+    fn submit_config_tx(&self, tx: &ConfigTransaction) -> StorageResult<()> {
+        self.submit_config_tx(tx.clone())
+    }
+
+    fn submit_purge_tx(&self, request: &PurgeRequest) -> StorageResult<()> {
+        self.submit_purge_tx(request.clone())
+    }
+
+    fn rotate_signer_at(&self, signer: Arc<dyn SigningProvider>, activation_height: u64) {
+        self.schedule_signer_rotation(signer, activation_height);
+    }
 }
 
 #[cfg(test)]
@@ -199,8 +435,8 @@ mod tests {
     #[test]
     fn creates_service_with_defaults() {
         let svc = OrderingService::with_config(100, 2000);
-        assert_eq!(svc.max_batch_size, 100);
-        assert_eq!(svc.batch_timeout_ms, 2000);
+        assert_eq!(svc.max_batch_size(), 100);
+        assert_eq!(svc.batch_timeout_ms(), 2000);
         assert_eq!(
             svc.pending_txs
                 .lock()
@@ -213,8 +449,8 @@ mod tests {
     #[test]
     fn respects_custom_config() {
         let svc = OrderingService::with_config(50, 500);
-        assert_eq!(svc.max_batch_size, 50);
-        assert_eq!(svc.batch_timeout_ms, 500);
+        assert_eq!(svc.max_batch_size(), 50);
+        assert_eq!(svc.batch_timeout_ms(), 500);
     }
 
     fn make_tx(id: &str) -> Transaction {
@@ -280,4 +516,144 @@ mod tests {
         // Queue now empty
         assert!(svc.cut_block(3, "orderer1").unwrap().is_none());
     }
+
+    #[test]
+    fn batch_config_updates_change_cut_size() {
+        let svc = OrderingService::with_config(100, 2000);
+        svc.apply_config_updates(&[
+            ConfigUpdateType::SetBatchSize(2),
+            ConfigUpdateType::SetBatchTimeout(250),
+        ])
+        .unwrap();
+        assert_eq!(svc.max_batch_size(), 2);
+        assert_eq!(svc.batch_timeout_ms(), 250);
+
+        for i in 0..3 {
+            svc.submit_tx(make_tx(&format!("tx{i}"))).unwrap();
+        }
+        let b1 = svc.cut_block(1, "orderer1").unwrap().unwrap();
+        assert_eq!(b1.transactions.len(), 2);
+    }
+
+    #[test]
+    fn config_tx_is_cut_alone_ahead_of_pending_txs() {
+        let svc = OrderingService::with_config(100, 2000);
+        svc.submit_tx(make_tx("tx1")).unwrap();
+        let config_tx = ConfigTransaction {
+            tx_id: "cfg-1".to_string(),
+            channel_id: "ch1".to_string(),
+            updates: vec![ConfigUpdateType::SetBatchSize(10)],
+            signatures: vec![],
+            created_at: 0,
+        };
+        svc.submit_config_tx(config_tx.clone()).unwrap();
+        assert_eq!(svc.pending_count(), 2);
+
+        let b1 = svc.cut_block(1, "orderer1").unwrap().unwrap();
+        assert_eq!(b1.transactions, vec![config_tx.ledger_tx_id()]);
+        assert_eq!(b1.config_tx, Some(config_tx));
+
+        let b2 = svc.cut_block(2, "orderer1").unwrap().unwrap();
+        assert_eq!(b2.transactions, vec!["tx1"]);
+        assert!(b2.config_tx.is_none());
+    }
+
+    #[test]
+    fn cut_block_stops_at_preferred_max_bytes() {
+        let tx_bytes = tx_size_bytes(&make_tx("tx0"));
+        let svc = OrderingService::with_config(100, 2000).with_block_size_limits(BlockSizeLimits {
+            preferred_max_bytes: 2 * tx_bytes + 1,
+            absolute_max_bytes: 10 * tx_bytes,
+        });
+        for i in 0..5 {
+            svc.submit_tx(make_tx(&format!("tx{i}"))).unwrap();
+        }
+
+        let b1 = svc.cut_block(1, "orderer1").unwrap().unwrap();
+        assert_eq!(b1.transactions, vec!["tx0", "tx1"]);
+        let b2 = svc.cut_block(2, "orderer1").unwrap().unwrap();
+        assert_eq!(b2.transactions, vec!["tx2", "tx3"]);
+        let b3 = svc.cut_block(3, "orderer1").unwrap().unwrap();
+        assert_eq!(b3.transactions, vec!["tx4"]);
+    }
+
+    #[test]
+    fn submit_rejects_tx_above_absolute_max_bytes() {
+        let metrics = Arc::new(MetricsCollector::new());
+        let svc = OrderingService::with_config(100, 2000).with_metrics(metrics.clone());
+        let mut big = make_tx("tx-big");
+        big.state = "x".repeat(DEFAULT_ABSOLUTE_MAX_BYTES);
+
+        let err = svc.submit_tx(big).unwrap_err();
+        assert!(matches!(
+            err,
+            crate::storage::errors::StorageError::TransactionTooLarge {
+                limit: DEFAULT_ABSOLUTE_MAX_BYTES,
+                ..
+            }
+        ));
+        assert_eq!(svc.pending_count(), 0);
+        assert_eq!(metrics.ordering_txs_oversized_total.get(), 1);
+
+        svc.submit_tx(make_tx("tx1")).unwrap();
+        svc.cut_block(1, "orderer1").unwrap().unwrap();
+        assert_eq!(metrics.ordering_block_size_bytes.get_sample_count(), 1);
+        assert_eq!(
+            metrics.ordering_block_size_bytes.get_sample_sum(),
+            tx_size_bytes(&make_tx("tx1")) as f64
+        );
+    }
+
+    #[test]
+    fn max_bytes_update_changes_limits() {
+        let svc = OrderingService::with_config(100, 2000);
+        assert_eq!(svc.block_size_limits(), BlockSizeLimits::default());
+        svc.apply_config_updates(&[ConfigUpdateType::SetMaxBytes {
+            preferred_max_bytes: 64,
+            absolute_max_bytes: 128,
+        }])
+        .unwrap();
+        assert_eq!(
+            svc.block_size_limits(),
+            BlockSizeLimits {
+                preferred_max_bytes: 64,
+                absolute_max_bytes: 128,
+            }
+        );
+        let mut big = make_tx("tx-big");
+        big.state = "x".repeat(128);
+        assert!(svc.submit_tx(big).is_err());
+    }
+
+    #[test]
+    fn submit_runs_admission_chain_and_counts_rejections() {
+        let metrics = Arc::new(MetricsCollector::new());
+        let svc = OrderingService::with_config(100, 2000).with_metrics(metrics.clone());
+
+        svc.submit_tx(make_tx("tx1")).unwrap();
+        let err = svc.submit_tx(make_tx("tx1")).unwrap_err();
+        assert!(matches!(
+            err,
+            crate::storage::errors::StorageError::AdmissionRejected(AdmissionError::Duplicate(_))
+        ));
+        assert!(svc.submit_tx(make_tx("")).is_err());
+        assert_eq!(svc.pending_count(), 1);
+
+        let rejected = |reason: &str| {
+            metrics
+                .ordering_txs_rejected_total
+                .with_label_values(&[reason])
+                .get()
+        };
+        assert_eq!(rejected("duplicate"), 1);
+        assert_eq!(rejected("malformed"), 1);
+    }
+
+    #[test]
+    fn empty_admission_chain_admits_everything() {
+        let svc = OrderingService::with_config(100, 2000).with_admission(AdmissionChain::new());
+        svc.submit_tx(make_tx("tx1")).unwrap();
+        svc.submit_tx(make_tx("tx1")).unwrap();
+        assert_eq!(svc.pending_count(), 2);
+    }
 }
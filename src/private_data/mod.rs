@@ -1,9 +1,12 @@
 use std::collections::HashMap;
-use std::sync::{Mutex, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
 
 use pqc_crypto_module::legacy::sha256::{Digest, Sha256};
 use thiserror::Error;
 
+use crate::endorsement::policy::EndorsementPolicy;
+use crate::endorsement::registry::OrgRegistry;
+use crate::endorsement::types::Endorsement;
 use crate::storage::errors::{StorageError, StorageResult};
 
 // ── Collection struct ─────────────────────────────────────────────────────────
@@ -56,6 +59,15 @@ impl PrivateDataCollection {
     pub fn is_member(&self, org_id: &str) -> bool {
         self.member_org_ids.iter().any(|id| id == org_id)
     }
+
+    /// Policy a [`PurgeRequest`] must satisfy: `required_peer_count` of the
+    /// member orgs.
+    pub fn purge_policy(&self) -> EndorsementPolicy {
+        EndorsementPolicy::NOutOf {
+            n: self.required_peer_count,
+            orgs: self.member_org_ids.clone(),
+        }
+    }
 }
 
 // ── PrivateDataStore trait ────────────────────────────────────────────────────
@@ -76,7 +88,6 @@ pub trait PrivateDataStore: Send + Sync {
         value: &[u8],
     ) -> StorageResult<[u8; 32]>;
 
-    #[allow(dead_code)]
     /// Store `value` together with TTL metadata so it can later be purged.
     ///
     /// `written_at_height` is the block height at which the data is written.
@@ -104,12 +115,297 @@ pub trait PrivateDataStore: Send + Sync {
     /// An entry expires when `written_at_height + blocks_to_live <= current_height`
     /// (and `blocks_to_live > 0`).
     ///
-    /// Default implementation is a no-op (stores that don't track TTL simply
-    /// never expire entries in-memory — a future phase can implement RocksDB
-    /// compaction filters for durable purge).
+    /// Default implementation is a no-op for stores that don't track TTL.
+    /// The persistent stores keep an expiry index next to the collections,
+    /// so entries written before a restart still expire.
     fn purge_expired(&self, current_height: u64) {
         let _ = current_height;
     }
+
+    /// Erase `(collection_name, key)`: the value and its TTL metadata.
+    ///
+    /// Returns `false` when there was no value to erase. The on-chain hash
+    /// of the value is untouched.
+    fn purge_private_data(&self, collection_name: &str, key: &str) -> StorageResult<bool>;
+}
+
+// ── TTL key layout (persistent stores) ────────────────────────────────────────
+
+/// Key of an entry's TTL record: `{collection}\x00{key}`. The value is the
+/// little-endian expiry height.
+#[cfg(any(feature = "rocksdb-storage", feature = "redb-storage"))]
+pub(crate) fn ttl_key(collection_name: &str, key: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(collection_name.len() + key.len() + 1);
+    out.extend_from_slice(collection_name.as_bytes());
+    out.push(0x00);
+    out.extend_from_slice(key.as_bytes());
+    out
+}
+
+/// Key of an entry in the expiry index: `{expiry:012}{collection}\x00{key}`,
+/// so a scan from the start yields entries in expiry order.
+#[cfg(any(feature = "rocksdb-storage", feature = "redb-storage"))]
+pub(crate) fn expiry_key(expiry: u64, collection_name: &str, key: &str) -> Vec<u8> {
+    let mut out = format!("{expiry:012}").into_bytes();
+    out.extend_from_slice(&ttl_key(collection_name, key));
+    out
+}
+
+/// Split an expiry index key into `(expiry, collection, key)`.
+#[cfg(any(feature = "rocksdb-storage", feature = "redb-storage"))]
+pub(crate) fn parse_expiry_key(raw: &[u8]) -> Option<(u64, String, String)> {
+    let height = std::str::from_utf8(raw.get(..12)?).ok()?.parse().ok()?;
+    let rest = &raw[12..];
+    let sep = rest.iter().position(|b| *b == 0x00)?;
+    let collection = String::from_utf8(rest[..sep].to_vec()).ok()?;
+    let key = String::from_utf8(rest[sep + 1..].to_vec()).ok()?;
+    Some((height, collection, key))
+}
+
+/// Decode the little-endian expiry height of a TTL record.
+#[cfg(any(feature = "rocksdb-storage", feature = "redb-storage"))]
+pub(crate) fn decode_expiry(bytes: &[u8]) -> StorageResult<u64> {
+    let arr: [u8; 8] = bytes
+        .try_into()
+        .map_err(|_| StorageError::DataCorrupted("private data TTL is not 8 bytes".to_string()))?;
+    Ok(u64::from_le_bytes(arr))
+}
+
+/// Height at which an entry written at `written_at_height` expires, or
+/// `None` if it never does.
+#[cfg(any(feature = "rocksdb-storage", feature = "redb-storage"))]
+pub(crate) fn expiry_height(written_at_height: u64, blocks_to_live: u64) -> Option<u64> {
+    (blocks_to_live > 0).then(|| written_at_height.saturating_add(blocks_to_live))
+}
+
+// ── Endorsed purge ────────────────────────────────────────────────────────────
+
+/// Endorsed request to erase one private data entry from every member peer,
+/// like Fabric's `PurgePrivateData`.
+///
+/// `value_hash` is the hex SHA-256 recorded on-chain when the value was
+/// written; it stays on-chain. Each endorsement signs
+/// [`purge_payload_hash`], so it authorizes erasing that value only: a
+/// value written later under the same key needs a new request.
+///
+/// The request is ordered like any transaction and applied by each member
+/// peer when it commits the block carrying it. `height` is the ledger
+/// height the endorsers signed at; the purge only commits in the
+/// [`PURGE_VALIDITY_BLOCKS`] blocks after it, so it cannot be replayed.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PurgeRequest {
+    pub collection: String,
+    pub key: String,
+    pub value_hash: String,
+    pub height: u64,
+    pub endorsements: Vec<Endorsement>,
+}
+
+/// Prefix of the ledger transaction ID of a purge.
+pub const PURGE_TX_PREFIX: &str = "purge-";
+
+/// Number of blocks after its `height` in which a purge can commit.
+pub const PURGE_VALIDITY_BLOCKS: u64 = 100;
+
+/// Payload endorsed by a purge, at ledger `height`, of the value hashing to
+/// `value_hash`.
+pub fn purge_payload_hash(
+    collection_name: &str,
+    key: &str,
+    value_hash: &[u8; 32],
+    height: u64,
+) -> [u8; 32] {
+    let mut h = Sha256::new();
+    h.update(b"purge_private_data\x00");
+    h.update(collection_name.as_bytes());
+    h.update([0x00]);
+    h.update(key.as_bytes());
+    h.update([0x00]);
+    h.update(value_hash);
+    h.update(height.to_le_bytes());
+    h.finalize().into()
+}
+
+impl PurgeRequest {
+    /// Check the request against its collection: every endorsement must sign
+    /// this purge, and the valid ones must satisfy the collection's
+    /// [`purge_policy`](PrivateDataCollection::purge_policy).
+    pub fn verify(
+        &self,
+        collection: &PrivateDataCollection,
+        org_registry: &dyn OrgRegistry,
+    ) -> Result<[u8; 32], PrivateDataError> {
+        let value_hash: [u8; 32] = hex::decode(&self.value_hash)
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| {
+                PrivateDataError::PurgeRejected("value_hash must be 32 hex bytes".to_string())
+            })?;
+        let payload = purge_payload_hash(&self.collection, &self.key, &value_hash, self.height);
+        if self.endorsements.iter().any(|e| e.payload_hash != payload) {
+            return Err(PrivateDataError::PurgeRejected(
+                "endorsement does not sign this purge".to_string(),
+            ));
+        }
+        crate::endorsement::validator::validate_endorsements(
+            &self.endorsements,
+            &collection.purge_policy(),
+            org_registry,
+            None,
+        )
+        .map_err(|e| PrivateDataError::PurgeRejected(e.to_string()))?;
+        Ok(value_hash)
+    }
+
+    /// ID under which the purge is listed in its block:
+    /// [`PURGE_TX_PREFIX`] followed by the hex SHA-256 of its JSON.
+    pub fn ledger_tx_id(&self) -> String {
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        format!("{PURGE_TX_PREFIX}{}", hex::encode(sha256(&json)))
+    }
+
+    /// Check that the purge may commit in the block at `commit_height`:
+    /// after the height it was endorsed at, and within
+    /// [`PURGE_VALIDITY_BLOCKS`] of it.
+    pub fn check_commit_height(&self, commit_height: u64) -> Result<(), PrivateDataError> {
+        if commit_height <= self.height
+            || commit_height > self.height.saturating_add(PURGE_VALIDITY_BLOCKS)
+        {
+            return Err(PrivateDataError::PurgeRejected(format!(
+                "purge endorsed at height {} cannot commit at height {commit_height}",
+                self.height
+            )));
+        }
+        Ok(())
+    }
+
+    /// Verify the request committed at `commit_height` and erase the entry
+    /// from `store`.
+    ///
+    /// Returns `false` when the entry was already gone. A stored value that
+    /// no longer hashes to `value_hash` is left alone and rejected.
+    pub fn apply(
+        &self,
+        registry: &dyn CollectionRegistry,
+        org_registry: &dyn OrgRegistry,
+        store: &dyn PrivateDataStore,
+        commit_height: u64,
+    ) -> Result<bool, PrivateDataError> {
+        self.check_commit_height(commit_height)?;
+        let collection = registry.get(&self.collection).ok_or_else(|| {
+            PrivateDataError::InvalidCollection(format!("unknown collection '{}'", self.collection))
+        })?;
+        let value_hash = self.verify(&collection, org_registry)?;
+        let storage_err = |e: StorageError| PrivateDataError::Storage(e.to_string());
+        match store
+            .get_private_data(&self.collection, &self.key)
+            .map_err(storage_err)?
+        {
+            None => Ok(false),
+            Some(value) if sha256(&value) != value_hash => Err(PrivateDataError::PurgeRejected(
+                format!("stored value of '{}' does not match value_hash", self.key),
+            )),
+            Some(_) => store
+                .purge_private_data(&self.collection, &self.key)
+                .map_err(storage_err),
+        }
+    }
+
+    /// Audit metadata recorded for this purge on each peer.
+    pub fn audit_metadata(&self, removed: bool) -> String {
+        let orgs: Vec<&str> = self
+            .endorsements
+            .iter()
+            .map(|e| e.org_id.as_str())
+            .collect();
+        serde_json::json!({
+            "collection": self.collection,
+            "key": self.key,
+            "value_hash": self.value_hash,
+            "height": self.height,
+            "endorsing_orgs": orgs,
+            "removed": removed,
+        })
+        .to_string()
+    }
+}
+
+/// Applies the purges committed blocks carry to this peer's private data.
+///
+/// Every peer runs the same checks on the same blocks, so member peers
+/// erase the same entries. A purge that fails verification is skipped; the
+/// block carrying it stays committed.
+#[derive(Clone)]
+pub struct PurgeCommitter {
+    registry: Arc<dyn CollectionRegistry>,
+    org_registry: Arc<dyn OrgRegistry>,
+    store: Arc<dyn PrivateDataStore>,
+    audit_store: Option<Arc<dyn crate::audit::AuditStore>>,
+    org_id: String,
+}
+
+impl PurgeCommitter {
+    pub fn new(
+        registry: Arc<dyn CollectionRegistry>,
+        org_registry: Arc<dyn OrgRegistry>,
+        store: Arc<dyn PrivateDataStore>,
+        org_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            registry,
+            org_registry,
+            store,
+            audit_store: None,
+            org_id: org_id.into(),
+        }
+    }
+
+    /// Record a `private_data_purged` audit entry for each applied purge.
+    pub fn with_audit_store(mut self, audit_store: Arc<dyn crate::audit::AuditStore>) -> Self {
+        self.audit_store = Some(audit_store);
+        self
+    }
+
+    /// Apply the purges committed by `block`. Only purges listed in its
+    /// transactions under their ledger ID are applied. Returns how many
+    /// entries were erased.
+    pub fn commit(&self, block: &crate::storage::traits::Block) -> usize {
+        let mut removed_count = 0;
+        for request in &block.private_data_purges {
+            if !block.transactions.contains(&request.ledger_tx_id()) {
+                log::warn!(
+                    "Block {} carries a purge of {}/{} it does not list; skipped",
+                    block.height,
+                    request.collection,
+                    request.key
+                );
+                continue;
+            }
+            match request.apply(
+                self.registry.as_ref(),
+                self.org_registry.as_ref(),
+                self.store.as_ref(),
+                block.height,
+            ) {
+                Ok(removed) => {
+                    removed_count += usize::from(removed);
+                    crate::audit::emit_if_present(
+                        &self.audit_store,
+                        crate::audit::AuditAction::PrivateDataPurged,
+                        &self.org_id,
+                        Some(request.audit_metadata(removed)),
+                    );
+                }
+                Err(e) => log::warn!(
+                    "Purge of {}/{} in block {} rejected: {e}",
+                    request.collection,
+                    request.key,
+                    block.height
+                ),
+            }
+        }
+        removed_count
+    }
 }
 
 // ── SHA-256 helper ────────────────────────────────────────────────────────────
@@ -173,14 +469,15 @@ impl PrivateDataStore for MemoryPrivateDataStore {
         blocks_to_live: u64,
     ) -> StorageResult<[u8; 32]> {
         let hash = self.put_private_data(collection_name, key, value)?;
+        let mut ttl_map = self
+            .ttl
+            .lock()
+            .map_err(|_| StorageError::Other("mutex poisoned".to_string()))?;
+        let entry = (collection_name.to_string(), key.to_string());
         if blocks_to_live > 0 {
-            self.ttl
-                .lock()
-                .map_err(|_| StorageError::Other("mutex poisoned".to_string()))?
-                .insert(
-                    (collection_name.to_string(), key.to_string()),
-                    (written_at_height, blocks_to_live),
-                );
+            ttl_map.insert(entry, (written_at_height, blocks_to_live));
+        } else {
+            ttl_map.remove(&entry);
         }
         Ok(hash)
     }
@@ -216,6 +513,20 @@ impl PrivateDataStore for MemoryPrivateDataStore {
             ttl_map.remove(key);
         }
     }
+
+    fn purge_private_data(&self, collection_name: &str, key: &str) -> StorageResult<bool> {
+        let entry = (collection_name.to_string(), key.to_string());
+        self.ttl
+            .lock()
+            .map_err(|_| StorageError::Other("mutex poisoned".to_string()))?
+            .remove(&entry);
+        Ok(self
+            .data
+            .lock()
+            .map_err(|_| StorageError::Other("mutex poisoned".to_string()))?
+            .remove(&entry)
+            .is_some())
+    }
 }
 
 // ── CollectionRegistry trait ──────────────────────────────────────────────────
@@ -283,6 +594,10 @@ pub enum PrivateDataError {
     #[allow(dead_code)]
     #[error("access denied: org '{0}' is not a member of collection '{1}'")]
     AccessDenied(String, String),
+    #[error("purge rejected: {0}")]
+    PurgeRejected(String),
+    #[error("storage error: {0}")]
+    Storage(String),
 }
 
 #[cfg(test)]
@@ -509,4 +824,242 @@ mod tests {
             Some(b"no-ttl".to_vec())
         );
     }
+
+    #[test]
+    fn rewrite_without_ttl_clears_expiry() {
+        let store = MemoryPrivateDataStore::new();
+        store.put_private_data_at("col1", "k", b"v1", 1, 5).unwrap();
+        store.put_private_data_at("col1", "k", b"v2", 2, 0).unwrap();
+        store.purge_expired(u64::MAX);
+        assert_eq!(
+            store.get_private_data("col1", "k").unwrap(),
+            Some(b"v2".to_vec())
+        );
+    }
+
+    // ── purge_private_data tests ──────────────────────────────────────────────
+
+    #[test]
+    fn purge_private_data_removes_value_and_ttl() {
+        let store = MemoryPrivateDataStore::new();
+        store.put_private_data_at("col1", "k", b"v", 1, 5).unwrap();
+        assert!(store.purge_private_data("col1", "k").unwrap());
+        assert_eq!(store.get_private_data("col1", "k").unwrap(), None);
+        assert!(store.ttl.lock().unwrap().is_empty());
+        assert!(!store.purge_private_data("col1", "k").unwrap());
+    }
+
+    #[cfg(any(feature = "rocksdb-storage", feature = "redb-storage"))]
+    #[test]
+    fn expiry_key_round_trips() {
+        let raw = expiry_key(42, "col1", "a\x00b");
+        assert!(raw.starts_with(b"000000000042"));
+        assert_eq!(
+            parse_expiry_key(&raw),
+            Some((42, "col1".to_string(), "a\x00b".to_string()))
+        );
+        assert_eq!(expiry_height(1, 0), None);
+        assert_eq!(expiry_height(u64::MAX, 5), Some(u64::MAX));
+    }
+
+    // ── PurgeRequest tests ────────────────────────────────────────────────────
+
+    use crate::endorsement::org::Organization;
+    use crate::endorsement::registry::MemoryOrgRegistry;
+    use pqc_crypto_module::legacy::ed25519::{Signer, SigningKey};
+    use pqc_crypto_module::legacy::rng::OsRng;
+
+    struct PurgeFixture {
+        registry: MemoryCollectionRegistry,
+        orgs: MemoryOrgRegistry,
+        keys: Vec<(String, SigningKey)>,
+        store: MemoryPrivateDataStore,
+        value_hash: [u8; 32],
+    }
+
+    /// Height the fixture's purges are endorsed at.
+    const PURGE_HEIGHT: u64 = 5;
+
+    /// `col1` shared by org1 and org2, needing both for a purge, with `k`
+    /// already written.
+    fn purge_fixture() -> PurgeFixture {
+        let registry = MemoryCollectionRegistry::new();
+        registry
+            .register(
+                PrivateDataCollection::new("col1", vec!["org1".into(), "org2".into()], 2, 0)
+                    .unwrap(),
+            )
+            .unwrap();
+        let orgs = MemoryOrgRegistry::new();
+        let mut keys = Vec::new();
+        for org_id in ["org1", "org2", "org3"] {
+            let sk = SigningKey::generate(&mut OsRng);
+            let org = Organization::new(
+                org_id,
+                format!("{org_id}MSP"),
+                vec![format!("did:bc:{org_id}:admin")],
+                vec![],
+                vec![sk.verifying_key().to_bytes()],
+            )
+            .unwrap();
+            orgs.register_org(&org).unwrap();
+            keys.push((org_id.to_string(), sk));
+        }
+        let store = MemoryPrivateDataStore::new();
+        let value_hash = store
+            .put_private_data("col1", "k", b"personal data")
+            .unwrap();
+        PurgeFixture {
+            registry,
+            orgs,
+            keys,
+            store,
+            value_hash,
+        }
+    }
+
+    impl PurgeFixture {
+        fn request(&self, signers: &[&str], payload: [u8; 32]) -> PurgeRequest {
+            let endorsements = self
+                .keys
+                .iter()
+                .filter(|(org_id, _)| signers.contains(&org_id.as_str()))
+                .map(|(org_id, sk)| Endorsement {
+                    signer_did: format!("did:bc:{org_id}:admin"),
+                    org_id: org_id.clone(),
+                    signature: sk.sign(&payload).to_bytes().to_vec(),
+                    signature_algorithm: Default::default(),
+                    payload_hash: payload,
+                    timestamp: 0,
+                })
+                .collect();
+            PurgeRequest {
+                collection: "col1".to_string(),
+                key: "k".to_string(),
+                value_hash: hex::encode(self.value_hash),
+                height: PURGE_HEIGHT,
+                endorsements,
+            }
+        }
+
+        fn payload(&self) -> [u8; 32] {
+            purge_payload_hash("col1", "k", &self.value_hash, PURGE_HEIGHT)
+        }
+
+        fn apply(&self, request: &PurgeRequest) -> Result<bool, PrivateDataError> {
+            request.apply(&self.registry, &self.orgs, &self.store, PURGE_HEIGHT + 1)
+        }
+    }
+
+    #[test]
+    fn endorsed_purge_erases_value() {
+        let f = purge_fixture();
+        let request = f.request(&["org1", "org2"], f.payload());
+        assert!(f.apply(&request).unwrap());
+        assert_eq!(f.store.get_private_data("col1", "k").unwrap(), None);
+        // Already erased: accepted again, nothing left to remove.
+        assert!(!f.apply(&request).unwrap());
+    }
+
+    #[test]
+    fn purge_without_enough_member_endorsements_is_rejected() {
+        let f = purge_fixture();
+        for signers in [&["org1"][..], &["org1", "org3"][..]] {
+            let err = f.apply(&f.request(signers, f.payload())).unwrap_err();
+            assert!(matches!(err, PrivateDataError::PurgeRejected(_)), "{err}");
+        }
+        assert!(f.store.get_private_data("col1", "k").unwrap().is_some());
+    }
+
+    #[test]
+    fn purge_endorsements_must_sign_the_purge_payload() {
+        let f = purge_fixture();
+        let other = purge_payload_hash("col1", "other", &f.value_hash, PURGE_HEIGHT);
+        let err = f.apply(&f.request(&["org1", "org2"], other)).unwrap_err();
+        assert!(err.to_string().contains("does not sign this purge"));
+    }
+
+    #[test]
+    fn purge_of_rewritten_value_is_rejected() {
+        let f = purge_fixture();
+        let request = f.request(&["org1", "org2"], f.payload());
+        f.store.put_private_data("col1", "k", b"new value").unwrap();
+        let err = f.apply(&request).unwrap_err();
+        assert!(err.to_string().contains("does not match value_hash"));
+        assert_eq!(
+            f.store.get_private_data("col1", "k").unwrap(),
+            Some(b"new value".to_vec())
+        );
+    }
+
+    #[test]
+    fn purge_commits_only_within_its_validity_window() {
+        let f = purge_fixture();
+        let request = f.request(&["org1", "org2"], f.payload());
+        for height in [PURGE_HEIGHT, PURGE_HEIGHT + PURGE_VALIDITY_BLOCKS + 1] {
+            let err = request
+                .apply(&f.registry, &f.orgs, &f.store, height)
+                .unwrap_err();
+            assert!(matches!(err, PrivateDataError::PurgeRejected(_)), "{err}");
+        }
+        // Endorsements over another height do not carry over to this one.
+        let mut replayed = request.clone();
+        replayed.height = PURGE_HEIGHT + 50;
+        let err = replayed
+            .apply(&f.registry, &f.orgs, &f.store, replayed.height + 1)
+            .unwrap_err();
+        assert!(err.to_string().contains("does not sign this purge"));
+        assert!(f.store.get_private_data("col1", "k").unwrap().is_some());
+
+        assert!(request
+            .apply(
+                &f.registry,
+                &f.orgs,
+                &f.store,
+                PURGE_HEIGHT + PURGE_VALIDITY_BLOCKS
+            )
+            .unwrap());
+    }
+
+    #[test]
+    fn committer_applies_the_purges_a_block_lists() {
+        let f = purge_fixture();
+        let request = f.request(&["org1", "org2"], f.payload());
+        let store = Arc::new(f.store);
+        let committer = PurgeCommitter::new(
+            Arc::new(f.registry),
+            Arc::new(f.orgs),
+            store.clone(),
+            "org1",
+        );
+        let mut block = crate::storage::traits::Block {
+            height: PURGE_HEIGHT + 1,
+            timestamp: 0,
+            parent_hash: [0u8; 32],
+            merkle_root: [0u8; 32],
+            transactions: vec![],
+            proposer: "orderer".to_string(),
+            signature: vec![],
+            signature_algorithm: Default::default(),
+            endorsements: vec![],
+            secondary_signature: None,
+            secondary_signature_algorithm: None,
+            hash_algorithm: Default::default(),
+            orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: Vec::new(),
+            config_tx: None,
+            last_config: 0,
+            state_root: [0u8; 32],
+            private_data_purges: vec![request.clone()],
+        };
+        // Not listed under its ledger ID: the block hash does not cover it.
+        assert_eq!(committer.commit(&block), 0);
+        assert!(store.get_private_data("col1", "k").unwrap().is_some());
+
+        block.transactions.push(request.ledger_tx_id());
+        assert_eq!(committer.commit(&block), 1);
+        assert_eq!(store.get_private_data("col1", "k").unwrap(), None);
+    }
 }
@@ -377,4 +377,23 @@ impl StakingManager {
 
         false
     }
+
+    /**
+     * Aplica slashing por evidencia de equivocación comprometida en un bloque
+     * La cantidad la fija la política de penalización, así que todos los nodos
+     * obtienen el mismo resultado al aplicar el mismo bloque
+     * @param validator_address - Dirección del validador
+     * @param policy - Política de penalización (porcentaje y mínimo)
+     * @returns Cantidad slasheada, o None si la dirección no tiene stake
+     */
+    pub fn slash_for_evidence(
+        &self,
+        validator_address: &str,
+        policy: &crate::consensus::slashing::PenaltyPolicy,
+    ) -> Option<u64> {
+        let mut validators = self.validators.lock().unwrap_or_else(|e| e.into_inner());
+        let validator = validators.get_mut(validator_address)?;
+        let amount = policy.slash_amount(validator.staked_amount);
+        Some(validator.slash(amount))
+    }
 }
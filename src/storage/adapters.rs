@@ -9,6 +9,15 @@
 //! | `identities`  | DID string            | JSON Identity  |
 //! | `credentials` | cred_id string        | JSON Credential|
 //! | `meta`        | well-known byte keys  | raw bytes      |
+//!
+//! Blocks that carry state writes are committed with
+//! `RocksDbBlockStore::commit_block` (see [`super::commit`]), which keeps
+//! the world state and the blocks in step.
+//!
+//! After [`RocksDbBlockStore::enable_encryption`], values of the CFs in
+//! [`ENCRYPTED_SCOPES`] and of `private_*` CFs are sealed on write and
+//! opened on read (see [`super::encryption`]). Keys, and the secondary
+//! index CFs built from state values, stay in plaintext.
 
 use rocksdb::{
     ColumnFamilyDescriptor, DBWithThreadMode, Direction, IteratorMode, MultiThreaded, Options,
@@ -16,20 +25,30 @@ use rocksdb::{
 };
 
 type RocksDB = DBWithThreadMode<MultiThreaded>;
+use std::borrow::Cow;
 use std::collections::BTreeSet;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, OnceLock};
 
+use super::encryption::{
+    data_key_record_key, is_encrypted_scope, key_versions, open_value, parse_pending_marker,
+    parse_sealed_marker, pending_marker_key, seal_value, sealed_marker_key, CoverageReport,
+    EncryptedStore, KeyEncryptionKey, ReencryptStep, ScopeCoverage, StorageCipher, WrappedDataKey,
+    ENCRYPTED_SCOPES, KEYS_TABLE,
+};
 use super::errors::{StorageError, StorageResult};
+use super::index::IndexDefinition;
 use super::traits::{Block, BlockStore, Credential, IdentityRecord, Transaction};
 use super::world_state::{VersionedValue, WorldState};
 use crate::chaincode::{ChaincodeError, ChaincodePackageStore};
 use crate::endorsement::org::Organization;
 use crate::endorsement::registry::OrgRegistry;
-use crate::private_data::{sha256, PrivateDataStore};
+use crate::private_data::{
+    decode_expiry, expiry_height, expiry_key, parse_expiry_key, sha256, ttl_key, PrivateDataStore,
+};
 
-const CF_BLOCKS: &str = "blocks";
-const CF_TRANSACTIONS: &str = "transactions";
+pub(crate) const CF_BLOCKS: &str = "blocks";
+pub(crate) const CF_TRANSACTIONS: &str = "transactions";
 const CF_IDENTITIES: &str = "identities";
 const CF_CREDENTIALS: &str = "credentials";
 const CF_META: &str = "meta";
@@ -42,7 +61,7 @@ const CF_ORGANIZATIONS: &str = "organizations";
 /// Certificate Revocation List: key = msp_id, value = JSON Vec<String> (serials)
 const CF_CRL: &str = "crl";
 /// World state: key = arbitrary string key, value = JSON VersionedValue
-const CF_WORLD_STATE: &str = "world_state";
+pub(crate) const CF_WORLD_STATE: &str = "world_state";
 /// Chaincode packages: key = `{chaincode_id}:{version}`, value = raw Wasm bytes
 const CF_CHAINCODE_PACKAGES: &str = "chaincode_packages";
 /// ACL entries: key = resource string, value = JSON AclEntry
@@ -58,7 +77,14 @@ const CF_COLLECTIONS: &str = "collections";
 /// Chaincode definitions: key = `{chaincode_id}:{version}`, value = JSON ChaincodeDefinition
 const CF_CHAINCODE_DEFINITIONS: &str = "chaincode_definitions";
 /// Key history: key = `{state_key}\x00{version:012}`, value = JSON HistoryEntry
-const CF_KEY_HISTORY: &str = "key_history";
+pub(crate) const CF_KEY_HISTORY: &str = "key_history";
+/// Per-block state write sets: key = zero-padded height, value = JSON BlockWriteSet
+pub(crate) const CF_BLOCK_WRITES: &str = "block_writes";
+/// Key history by block: key = `{height:012}{state_key}\x00{version:012}`, empty value
+const CF_HISTORY_BY_BLOCK: &str = "history_by_block";
+/// Secondary index definitions: key = index name, value = JSON IndexDefinition.
+/// Each index keeps its entries in its own `index_{name}` CF.
+const CF_STATE_INDEX_DEFS: &str = "state_index_defs";
 /// Audit log: key = `{timestamp}:{trace_id}`, value = JSON AuditEntry
 const CF_AUDIT_LOG: &str = "audit_log";
 /// Sandbox reports: key = `{chaincode_id}:{version}`, value = JSON SandboxReport
@@ -80,8 +106,16 @@ const CF_ASSET_EVENTS: &str = "asset_events";
 const CF_ASSET_TOKENS: &str = "asset_tokens";
 const CF_COMPLIANCE_RULES: &str = "compliance_rules";
 const CF_COMPLIANCE_RESULTS: &str = "compliance_results";
+/// Private data TTLs: key = `{collection}\x00{key}`, value = little-endian expiry height
+const CF_PRIVATE_TTL: &str = "pvt_ttl";
+/// Private data expiry index: key = `{expiry:012}{collection}\x00{key}`, empty value
+const CF_PRIVATE_EXPIRY: &str = "pvt_expiry";
+/// Wrapped data keys: key = `{scope}\x00{version:010}`, value = JSON WrappedDataKey
+const CF_ENCRYPTION_KEYS: &str = KEYS_TABLE;
 
-const META_LATEST_HEIGHT: &[u8] = b"latest_height";
+pub(crate) const META_LATEST_HEIGHT: &[u8] = b"latest_height";
+/// Height of the last block whose state writes are in `world_state`.
+pub(crate) const META_STATE_SAVEPOINT: &[u8] = b"state_savepoint";
 
 const ALL_CFS: &[&str] = &[
     CF_BLOCKS,
@@ -99,6 +133,9 @@ const ALL_CFS: &[&str] = &[
     CF_CHANNEL_CONFIGS,
     CF_KEY_ENDORSEMENT_POLICIES,
     CF_KEY_HISTORY,
+    CF_BLOCK_WRITES,
+    CF_HISTORY_BY_BLOCK,
+    CF_STATE_INDEX_DEFS,
     CF_ENDORSEMENT_POLICIES,
     CF_COLLECTIONS,
     CF_CHAINCODE_DEFINITIONS,
@@ -117,11 +154,19 @@ const ALL_CFS: &[&str] = &[
     CF_ASSET_TOKENS,
     CF_COMPLIANCE_RULES,
     CF_COMPLIANCE_RESULTS,
+    CF_PRIVATE_TTL,
+    CF_PRIVATE_EXPIRY,
+    CF_ENCRYPTION_KEYS,
 ];
 
 /// RocksDB-backed block store using Column Families for data isolation
 pub struct RocksDbBlockStore {
     pub(crate) db: RocksDB,
+    /// Secondary indexes of the world state. The lock is held across every
+    /// state write and index rebuild, so an index never misses a write.
+    pub(crate) state_indexes: Mutex<Vec<IndexDefinition>>,
+    /// Set by [`Self::enable_encryption`].
+    encryption: OnceLock<StorageCipher>,
 }
 
 impl RocksDbBlockStore {
@@ -152,7 +197,163 @@ impl RocksDbBlockStore {
         let db = RocksDB::open_cf_descriptors(&opts, path, cf_descriptors)
             .map_err(|e| StorageError::RocksDbError(e.to_string()))?;
 
-        Ok(RocksDbBlockStore { db })
+        let store = RocksDbBlockStore {
+            db,
+            state_indexes: Mutex::new(Vec::new()),
+            encryption: OnceLock::new(),
+        };
+        store.load_state_indexes()?;
+        Ok(store)
+    }
+
+    /// Open an existing database read-only, e.g. to audit the data directory
+    /// of a node while it runs. Writes fail; sealed values are opened with
+    /// the data keys wrapped under `kek`, without creating any.
+    pub fn open_read_only(
+        path: impl AsRef<Path>,
+        kek: Option<Arc<dyn KeyEncryptionKey>>,
+    ) -> StorageResult<Self> {
+        let path = path.as_ref();
+        let opts = Options::default();
+        let cf_names =
+            RocksDB::list_cf(&opts, path).map_err(|e| StorageError::RocksDbError(e.to_string()))?;
+        let db = RocksDB::open_cf_for_read_only(&opts, path, cf_names, false)
+            .map_err(|e| StorageError::RocksDbError(e.to_string()))?;
+
+        let store = RocksDbBlockStore {
+            db,
+            state_indexes: Mutex::new(Vec::new()),
+            encryption: OnceLock::new(),
+        };
+        if let Some(kek) = kek {
+            let wrapped = if store.db.cf_handle(CF_ENCRYPTION_KEYS).is_some() {
+                store.wrapped_data_keys()?
+            } else {
+                Vec::new()
+            };
+            let cipher = StorageCipher::new(kek, &wrapped)?;
+            if store.db.cf_handle(CF_META).is_some() {
+                for scope in store.meta_scopes(&sealed_marker_key(""), parse_sealed_marker)? {
+                    cipher.mark_sealed(&scope);
+                }
+            }
+            let _ = store.encryption.set(cipher);
+        }
+        Ok(store)
+    }
+
+    /// Seal covered values from now on with data keys wrapped under `kek`,
+    /// creating the keys of scopes that have none.
+    pub fn enable_encryption(&self, kek: Arc<dyn KeyEncryptionKey>) -> StorageResult<()> {
+        let cipher = StorageCipher::new(kek, &self.wrapped_data_keys()?)?;
+        for scope in self.meta_scopes(&sealed_marker_key(""), parse_sealed_marker)? {
+            cipher.mark_sealed(&scope);
+        }
+        for scope in self.encrypted_scopes()? {
+            self.ensure_data_key(&cipher, &scope)?;
+        }
+        log::info!("storage encryption enabled (KEK {})", cipher.kek_id());
+        self.encryption
+            .set(cipher)
+            .map_err(|_| StorageError::Encryption("encryption is already enabled".to_string()))
+    }
+
+    fn wrapped_data_keys(&self) -> StorageResult<Vec<WrappedDataKey>> {
+        let cf = self.cf(CF_ENCRYPTION_KEYS)?;
+        let mut records = Vec::new();
+        for item in self.db.iterator_cf(&cf, IteratorMode::Start) {
+            let (_, v) = item.map_err(|e| StorageError::RocksDbError(e.to_string()))?;
+            records.push(
+                serde_json::from_slice(&v)
+                    .map_err(|e| StorageError::DeserializationError(e.to_string()))?,
+            );
+        }
+        Ok(records)
+    }
+
+    /// Create the data key of `scope` if it has none, marking the scope for
+    /// re-encryption since it may hold plaintext.
+    fn ensure_data_key(&self, cipher: &StorageCipher, scope: &str) -> StorageResult<()> {
+        cipher.ensure_data_key(scope, |wrapped| self.persist_data_key(wrapped))?;
+        Ok(())
+    }
+
+    /// Scopes named by the meta markers under `prefix`.
+    fn meta_scopes(
+        &self,
+        prefix: &[u8],
+        parse: fn(&[u8]) -> Option<String>,
+    ) -> StorageResult<Vec<String>> {
+        let mut scopes = Vec::new();
+        let iter = self.db.iterator_cf(
+            &self.cf_meta()?,
+            IteratorMode::From(prefix, Direction::Forward),
+        );
+        for item in iter {
+            let (k, _) = item.map_err(|e| StorageError::RocksDbError(e.to_string()))?;
+            if !k.starts_with(prefix) {
+                break;
+            }
+            scopes.extend(parse(&k));
+        }
+        Ok(scopes)
+    }
+
+    fn persist_data_key(&self, wrapped: &WrappedDataKey) -> StorageResult<()> {
+        let value = serde_json::to_vec(wrapped)
+            .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+        let mut batch = WriteBatch::default();
+        batch.put_cf(
+            &self.cf(CF_ENCRYPTION_KEYS)?,
+            data_key_record_key(&wrapped.scope, wrapped.version),
+            value,
+        );
+        batch.put_cf(&self.cf_meta()?, pending_marker_key(&wrapped.scope), b"");
+        self.db
+            .write(batch)
+            .map_err(|e| StorageError::RocksDbError(e.to_string()))
+    }
+
+    /// Make sure a covered CF created after [`Self::enable_encryption`] (a
+    /// private data collection) has its data key before values are sealed
+    /// for it.
+    pub(crate) fn prepare_scope(&self, name: &str) -> StorageResult<()> {
+        match self.encryption.get() {
+            Some(cipher) if is_encrypted_scope(name) => self.ensure_data_key(cipher, name),
+            _ => Ok(()),
+        }
+    }
+
+    fn cipher(&self) -> StorageResult<&StorageCipher> {
+        self.encryption
+            .get()
+            .ok_or_else(|| StorageError::Encryption("encryption is not enabled".to_string()))
+    }
+
+    /// `value` as it is stored under `key` in the CF `name`.
+    pub(crate) fn seal<'a>(
+        &self,
+        name: &str,
+        key: &[u8],
+        value: &'a [u8],
+    ) -> StorageResult<Cow<'a, [u8]>> {
+        seal_value(self.encryption.get(), name, key, value)
+    }
+
+    /// The value stored under `key` in the CF `name`.
+    pub(crate) fn open<'a>(
+        &self,
+        name: &str,
+        key: &[u8],
+        stored: &'a [u8],
+    ) -> StorageResult<Cow<'a, [u8]>> {
+        open_value(self.encryption.get(), name, key, stored)
+    }
+
+    fn cf(&self, name: &str) -> StorageResult<Arc<rocksdb::BoundColumnFamily<'_>>> {
+        self.db
+            .cf_handle(name)
+            .ok_or_else(|| StorageError::ColumnFamilyNotFound(name.to_string()))
     }
 
     /// Flush the WAL (Write-Ahead Log) to ensure all pending writes are
@@ -181,26 +382,20 @@ impl RocksDbBlockStore {
         channel_id: &str,
         base_path: &Path,
     ) -> StorageResult<RocksDbBlockStore> {
-        if channel_id.is_empty()
-            || !channel_id
-                .chars()
-                .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
-        {
-            return Err(StorageError::InvalidChannelId(channel_id.to_string()));
-        }
+        super::validate_channel_id(channel_id)?;
         let channel_path = base_path.join("channels").join(channel_id);
         RocksDbBlockStore::new(channel_path)
     }
 
     // ── Column Family handle helpers ──────────────────────────────────────────
 
-    fn cf_blocks(&self) -> StorageResult<Arc<rocksdb::BoundColumnFamily<'_>>> {
+    pub(crate) fn cf_blocks(&self) -> StorageResult<Arc<rocksdb::BoundColumnFamily<'_>>> {
         self.db
             .cf_handle(CF_BLOCKS)
             .ok_or_else(|| StorageError::ColumnFamilyNotFound(CF_BLOCKS.to_string()))
     }
 
-    fn cf_transactions(&self) -> StorageResult<Arc<rocksdb::BoundColumnFamily<'_>>> {
+    pub(crate) fn cf_transactions(&self) -> StorageResult<Arc<rocksdb::BoundColumnFamily<'_>>> {
         self.db
             .cf_handle(CF_TRANSACTIONS)
             .ok_or_else(|| StorageError::ColumnFamilyNotFound(CF_TRANSACTIONS.to_string()))
@@ -218,13 +413,13 @@ impl RocksDbBlockStore {
             .ok_or_else(|| StorageError::ColumnFamilyNotFound(CF_CREDENTIALS.to_string()))
     }
 
-    fn cf_meta(&self) -> StorageResult<Arc<rocksdb::BoundColumnFamily<'_>>> {
+    pub(crate) fn cf_meta(&self) -> StorageResult<Arc<rocksdb::BoundColumnFamily<'_>>> {
         self.db
             .cf_handle(CF_META)
             .ok_or_else(|| StorageError::ColumnFamilyNotFound(CF_META.to_string()))
     }
 
-    fn cf_tx_by_block(&self) -> StorageResult<Arc<rocksdb::BoundColumnFamily<'_>>> {
+    pub(crate) fn cf_tx_by_block(&self) -> StorageResult<Arc<rocksdb::BoundColumnFamily<'_>>> {
         self.db
             .cf_handle(CF_TX_BY_BLOCK)
             .ok_or_else(|| StorageError::ColumnFamilyNotFound(CF_TX_BY_BLOCK.to_string()))
@@ -248,7 +443,7 @@ impl RocksDbBlockStore {
             .ok_or_else(|| StorageError::ColumnFamilyNotFound(CF_CRL.to_string()))
     }
 
-    fn cf_world_state(&self) -> StorageResult<Arc<rocksdb::BoundColumnFamily<'_>>> {
+    pub(crate) fn cf_world_state(&self) -> StorageResult<Arc<rocksdb::BoundColumnFamily<'_>>> {
         self.db
             .cf_handle(CF_WORLD_STATE)
             .ok_or_else(|| StorageError::ColumnFamilyNotFound(CF_WORLD_STATE.to_string()))
@@ -288,10 +483,30 @@ impl RocksDbBlockStore {
             .ok_or_else(|| StorageError::ColumnFamilyNotFound(CF_KEY_HISTORY.to_string()))
     }
 
+    pub(crate) fn cf_block_writes(&self) -> StorageResult<Arc<rocksdb::BoundColumnFamily<'_>>> {
+        self.db
+            .cf_handle(CF_BLOCK_WRITES)
+            .ok_or_else(|| StorageError::ColumnFamilyNotFound(CF_BLOCK_WRITES.to_string()))
+    }
+
+    pub(crate) fn cf_history_by_block(
+        &self,
+    ) -> StorageResult<Arc<rocksdb::BoundColumnFamily<'_>>> {
+        self.db
+            .cf_handle(CF_HISTORY_BY_BLOCK)
+            .ok_or_else(|| StorageError::ColumnFamilyNotFound(CF_HISTORY_BY_BLOCK.to_string()))
+    }
+
+    pub(crate) fn cf_state_index_defs(&self) -> StorageResult<Arc<rocksdb::BoundColumnFamily<'_>>> {
+        self.db
+            .cf_handle(CF_STATE_INDEX_DEFS)
+            .ok_or_else(|| StorageError::ColumnFamilyNotFound(CF_STATE_INDEX_DEFS.to_string()))
+    }
+
     // ── Key encoders ─────────────────────────────────────────────────────────
 
     /// Zero-padded decimal height gives lexicographic == numeric ordering.
-    fn block_key(height: u64) -> Vec<u8> {
+    pub(crate) fn block_key(height: u64) -> Vec<u8> {
         format!("{height:012}").into_bytes()
     }
 
@@ -299,12 +514,12 @@ impl RocksDbBlockStore {
     ///
     /// The fixed-width height prefix keeps all entries for a block contiguous
     /// and in numeric order, enabling a simple prefix range scan.
-    fn tx_block_index_key(height: u64, tx_id: &str) -> Vec<u8> {
+    pub(crate) fn tx_block_index_key(height: u64, tx_id: &str) -> Vec<u8> {
         format!("{height:012}:{tx_id}").into_bytes()
     }
 
     /// The prefix used to scan all index entries for `height`.
-    fn tx_block_prefix(height: u64) -> Vec<u8> {
+    pub(crate) fn tx_block_prefix(height: u64) -> Vec<u8> {
         format!("{height:012}:").into_bytes()
     }
 
@@ -328,9 +543,8 @@ impl RocksDbBlockStore {
         prefix
     }
 
-    #[allow(dead_code)]
     /// Key-history entry key: `{state_key}\x00{version:012}`.
-    fn history_key(state_key: &str, version: u64) -> Vec<u8> {
+    pub(crate) fn history_key(state_key: &str, version: u64) -> Vec<u8> {
         let mut key = Vec::with_capacity(state_key.len() + 1 + 12);
         key.extend_from_slice(state_key.as_bytes());
         key.push(0x00);
@@ -338,6 +552,13 @@ impl RocksDbBlockStore {
         key
     }
 
+    /// Block-ordered history key: `{height:012}` followed by the history key.
+    pub(crate) fn history_by_block_key(height: u64, state_key: &str, version: u64) -> Vec<u8> {
+        let mut key = Self::block_key(height);
+        key.extend_from_slice(&Self::history_key(state_key, version));
+        key
+    }
+
     /// Prefix for scanning all history entries of a given key.
     fn history_prefix(state_key: &str) -> Vec<u8> {
         let mut prefix = Vec::with_capacity(state_key.len() + 1);
@@ -359,8 +580,9 @@ impl RocksDbBlockStore {
         let key = Self::history_key(state_key, entry.version);
         let value = serde_json::to_vec(entry)
             .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+        let value = self.seal(CF_KEY_HISTORY, &key, &value)?;
         self.db
-            .put_cf(&cf, key, value)
+            .put_cf(&cf, &key, value)
             .map_err(|e| StorageError::RocksDbError(e.to_string()))
     }
 
@@ -381,43 +603,62 @@ impl RocksDbBlockStore {
             if !k.starts_with(&prefix) {
                 break;
             }
-            let entry: crate::storage::traits::HistoryEntry = serde_json::from_slice(&v)
-                .map_err(|e| StorageError::DeserializationError(e.to_string()))?;
+            let entry: crate::storage::traits::HistoryEntry =
+                serde_json::from_slice(&self.open(CF_KEY_HISTORY, &k, &v)?)
+                    .map_err(|e| StorageError::DeserializationError(e.to_string()))?;
             entries.push(entry);
         }
         Ok(entries)
     }
 
+    /// History entries written by the block at `height`, as `(key, entry)`
+    /// in key order.
+    pub fn history_at(
+        &self,
+        height: u64,
+    ) -> StorageResult<Vec<(String, crate::storage::traits::HistoryEntry)>> {
+        let cf = self.cf_history_by_block()?;
+        let cf_hist = self.cf_key_history()?;
+        let prefix = Self::block_key(height);
+        let iter = self
+            .db
+            .iterator_cf(&cf, IteratorMode::From(&prefix, Direction::Forward));
+
+        let mut entries = Vec::new();
+        for item in iter {
+            let (k, _) = item.map_err(|e| StorageError::RocksDbError(e.to_string()))?;
+            if !k.starts_with(&prefix) {
+                break;
+            }
+            let history_key = &k[prefix.len()..];
+            let Some(sep) = history_key.iter().rposition(|b| *b == 0x00) else {
+                continue;
+            };
+            let state_key = String::from_utf8(history_key[..sep].to_vec())
+                .map_err(|e| StorageError::DeserializationError(e.to_string()))?;
+            let Some(raw) = self
+                .db
+                .get_cf(&cf_hist, history_key)
+                .map_err(|e| StorageError::RocksDbError(e.to_string()))?
+            else {
+                continue;
+            };
+            let entry = serde_json::from_slice(&self.open(CF_KEY_HISTORY, history_key, &raw)?)
+                .map_err(|e| StorageError::DeserializationError(e.to_string()))?;
+            entries.push((state_key, entry));
+        }
+        Ok(entries)
+    }
+
     // ── World state ───────────────────────────────────────────────────────────
 
     /// Write `data` under `key` in the world state CF.
     ///
     /// If the key already exists the version is incremented; if it is new the
-    /// version starts at 1.  Returns the new version number.
+    /// version starts at 1.  Returns the new version number.  The history
+    /// entry and index entries are written in the same batch.
     pub fn world_state_put(&self, key: &str, data: &[u8]) -> StorageResult<u64> {
-        let cf = self.cf_world_state()?;
-        let new_version = match self
-            .db
-            .get_cf(&cf, key.as_bytes())
-            .map_err(|e| StorageError::RocksDbError(e.to_string()))?
-        {
-            Some(bytes) => {
-                let existing: VersionedValue = serde_json::from_slice(&bytes)
-                    .map_err(|e| StorageError::DeserializationError(e.to_string()))?;
-                existing.version + 1
-            }
-            None => 1,
-        };
-        let vv = VersionedValue {
-            version: new_version,
-            data: data.to_vec(),
-        };
-        let encoded =
-            serde_json::to_vec(&vv).map_err(|e| StorageError::SerializationError(e.to_string()))?;
-        self.db
-            .put_cf(&cf, key.as_bytes(), &encoded)
-            .map_err(|e| StorageError::RocksDbError(e.to_string()))?;
-        Ok(new_version)
+        self.write_state(key, Some(data), "", None)
     }
 
     /// Read the current `VersionedValue` for `key`, or `None` if absent.
@@ -429,6 +670,7 @@ impl RocksDbBlockStore {
             .map_err(|e| StorageError::RocksDbError(e.to_string()))?
         {
             Some(bytes) => {
+                let bytes = self.open(CF_WORLD_STATE, key.as_bytes(), &bytes)?;
                 let vv: VersionedValue = serde_json::from_slice(&bytes)
                     .map_err(|e| StorageError::DeserializationError(e.to_string()))?;
                 Ok(Some(vv))
@@ -440,21 +682,7 @@ impl RocksDbBlockStore {
 
 impl BlockStore for RocksDbBlockStore {
     fn write_block(&self, block: &Block) -> StorageResult<()> {
-        let value = serde_json::to_vec(block)
-            .map_err(|e| StorageError::SerializationError(e.to_string()))?;
-
-        let current_latest = self.get_latest_height().unwrap_or(0);
-        let cf_b = self.cf_blocks()?;
-        let cf_m = self.cf_meta()?;
-
-        let mut batch = WriteBatch::default();
-        batch.put_cf(&cf_b, Self::block_key(block.height), &value);
-        if block.height >= current_latest {
-            batch.put_cf(&cf_m, META_LATEST_HEIGHT, block.height.to_le_bytes());
-        }
-        self.db
-            .write(batch)
-            .map_err(|e| StorageError::RocksDbError(e.to_string()))
+        self.commit_block(block, &[], &[])
     }
 
     fn read_block(&self, height: u64) -> StorageResult<Block> {
@@ -464,7 +692,7 @@ impl BlockStore for RocksDbBlockStore {
             .get_cf(&self.cf_blocks()?, &key)
             .map_err(|e| StorageError::RocksDbError(e.to_string()))?
         {
-            Some(bytes) => serde_json::from_slice(&bytes)
+            Some(bytes) => serde_json::from_slice(&self.open(CF_BLOCKS, &key, &bytes)?)
                 .map_err(|e| StorageError::DeserializationError(e.to_string())),
             None => Err(StorageError::KeyNotFound(format!("block:{height}"))),
         }
@@ -473,6 +701,7 @@ impl BlockStore for RocksDbBlockStore {
     fn write_transaction(&self, tx: &Transaction) -> StorageResult<()> {
         let value =
             serde_json::to_vec(tx).map_err(|e| StorageError::SerializationError(e.to_string()))?;
+        let value = self.seal(CF_TRANSACTIONS, tx.id.as_bytes(), &value)?;
 
         let mut batch = WriteBatch::default();
         batch.put_cf(&self.cf_transactions()?, tx.id.as_bytes(), &value);
@@ -492,8 +721,10 @@ impl BlockStore for RocksDbBlockStore {
             .get_cf(&self.cf_transactions()?, tx_id.as_bytes())
             .map_err(|e| StorageError::RocksDbError(e.to_string()))?
         {
-            Some(bytes) => serde_json::from_slice(&bytes)
-                .map_err(|e| StorageError::DeserializationError(e.to_string())),
+            Some(bytes) => {
+                serde_json::from_slice(&self.open(CF_TRANSACTIONS, tx_id.as_bytes(), &bytes)?)
+                    .map_err(|e| StorageError::DeserializationError(e.to_string()))
+            }
             None => Err(StorageError::KeyNotFound(format!("tx:{tx_id}"))),
         }
     }
@@ -592,7 +823,8 @@ impl BlockStore for RocksDbBlockStore {
         for block in blocks {
             let value = serde_json::to_vec(block)
                 .map_err(|e| StorageError::SerializationError(e.to_string()))?;
-            batch.put_cf(&cf_b, Self::block_key(block.height), &value);
+            let key = Self::block_key(block.height);
+            batch.put_cf(&cf_b, &key, self.seal(CF_BLOCKS, &key, &value)?);
             if block.height > new_latest {
                 new_latest = block.height;
             }
@@ -601,7 +833,11 @@ impl BlockStore for RocksDbBlockStore {
         for tx in txs {
             let value = serde_json::to_vec(tx)
                 .map_err(|e| StorageError::SerializationError(e.to_string()))?;
-            batch.put_cf(&cf_t, tx.id.as_bytes(), &value);
+            batch.put_cf(
+                &cf_t,
+                tx.id.as_bytes(),
+                self.seal(CF_TRANSACTIONS, tx.id.as_bytes(), &value)?,
+            );
             batch.put_cf(
                 &cf_idx,
                 Self::tx_block_index_key(tx.block_height, &tx.id),
@@ -612,6 +848,9 @@ impl BlockStore for RocksDbBlockStore {
         if new_latest > current_latest {
             batch.put_cf(&cf_m, META_LATEST_HEIGHT, new_latest.to_le_bytes());
         }
+        if let Some(top) = blocks.iter().map(|b| b.height).max() {
+            self.stage_savepoint(&mut batch, top)?;
+        }
 
         self.db
             .write(batch)
@@ -669,6 +908,7 @@ impl BlockStore for RocksDbBlockStore {
                 .map_err(|e| StorageError::RocksDbError(e.to_string()))?
                 .ok_or_else(|| StorageError::KeyNotFound(format!("tx:{tx_id}")))?;
 
+            let tx_bytes = self.open(CF_TRANSACTIONS, tx_id.as_bytes(), &tx_bytes)?;
             let tx: Transaction = serde_json::from_slice(&tx_bytes)
                 .map_err(|e| StorageError::DeserializationError(e.to_string()))?;
             txs.push(tx);
@@ -677,6 +917,47 @@ impl BlockStore for RocksDbBlockStore {
         Ok(txs)
     }
 
+    fn prune_block(&self, header: &Block) -> StorageResult<()> {
+        let key = Self::block_key(header.height);
+        let cf_b = self.cf_blocks()?;
+        if self
+            .db
+            .get_pinned_cf(&cf_b, &key)
+            .map_err(|e| StorageError::RocksDbError(e.to_string()))?
+            .is_none()
+        {
+            return Err(StorageError::KeyNotFound(format!(
+                "block:{}",
+                header.height
+            )));
+        }
+        let value = serde_json::to_vec(header)
+            .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+        let value = self.seal(CF_BLOCKS, &key, &value)?;
+
+        let prefix = Self::tx_block_prefix(header.height);
+        let cf_idx = self.cf_tx_by_block()?;
+        let cf_t = self.cf_transactions()?;
+
+        // Header swap and record deletion land in one batch.
+        let mut batch = WriteBatch::default();
+        batch.put_cf(&cf_b, &key, &value);
+        let iter = self
+            .db
+            .iterator_cf(&cf_idx, IteratorMode::From(&prefix, Direction::Forward));
+        for item in iter {
+            let (idx_key, _) = item.map_err(|e| StorageError::RocksDbError(e.to_string()))?;
+            if !idx_key.starts_with(&prefix) {
+                break;
+            }
+            batch.delete_cf(&cf_t, &idx_key[prefix.len()..]);
+            batch.delete_cf(&cf_idx, &idx_key);
+        }
+        self.db
+            .write(batch)
+            .map_err(|e| StorageError::RocksDbError(e.to_string()))
+    }
+
     fn credentials_by_subject_did(&self, subject_did: &str) -> StorageResult<Vec<Credential>> {
         let prefix = Self::cred_subject_prefix(subject_did);
         let cf_idx = self.cf_cred_by_subject()?;
@@ -871,6 +1152,7 @@ impl BlockStore for RocksDbBlockStore {
             .ok_or_else(|| StorageError::RocksDbError("missing vault CF".into()))?;
         let json = serde_json::to_vec(encrypted_wallet)
             .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+        let json = self.seal(CF_VAULT, did.as_bytes(), &json)?;
         self.db
             .put_cf(&cf, did.as_bytes(), &json)
             .map_err(|e| StorageError::RocksDbError(e.to_string()))?;
@@ -887,7 +1169,8 @@ impl BlockStore for RocksDbBlockStore {
             .get_cf(&cf, did.as_bytes())
             .map_err(|e| StorageError::RocksDbError(e.to_string()))?
             .ok_or_else(|| StorageError::KeyNotFound(format!("vault:{did}")))?;
-        serde_json::from_slice(&data).map_err(|e| StorageError::DeserializationError(e.to_string()))
+        serde_json::from_slice(&self.open(CF_VAULT, did.as_bytes(), &data)?)
+            .map_err(|e| StorageError::DeserializationError(e.to_string()))
     }
 
     fn write_vault_recovery(&self, blind_index: &str, did: &str) -> StorageResult<()> {
@@ -896,8 +1179,9 @@ impl BlockStore for RocksDbBlockStore {
             .cf_handle(CF_VAULT)
             .ok_or_else(|| StorageError::RocksDbError("missing vault CF".into()))?;
         let key = format!("recovery:{blind_index}");
+        let value = self.seal(CF_VAULT, key.as_bytes(), did.as_bytes())?;
         self.db
-            .put_cf(&cf, key.as_bytes(), did.as_bytes())
+            .put_cf(&cf, key.as_bytes(), &value)
             .map_err(|e| StorageError::RocksDbError(e.to_string()))?;
         Ok(())
     }
@@ -913,6 +1197,7 @@ impl BlockStore for RocksDbBlockStore {
             .get_cf(&cf, key.as_bytes())
             .map_err(|e| StorageError::RocksDbError(e.to_string()))?
             .ok_or_else(|| StorageError::KeyNotFound("vault recovery entry not found".into()))?;
+        let data = self.open(CF_VAULT, key.as_bytes(), &data)?.into_owned();
         String::from_utf8(data).map_err(|e| StorageError::DeserializationError(e.to_string()))
     }
 
@@ -1452,14 +1737,34 @@ impl WorldState for RocksDbBlockStore {
         self.world_state_put(key, data)
     }
 
+    fn put_from_tx(
+        &self,
+        key: &str,
+        data: &[u8],
+        tx_id: &str,
+        block_height: u64,
+    ) -> StorageResult<u64> {
+        self.write_state(key, Some(data), tx_id, Some(block_height))
+    }
+
+    fn restore_entry(&self, key: &str, value: &VersionedValue) -> StorageResult<()> {
+        self.restore_state_entry(key, value)
+    }
+
     fn delete(&self, key: &str) -> StorageResult<()> {
-        let cf = self.cf_world_state()?;
-        self.db
-            .delete_cf(&cf, key.as_bytes())
-            .map_err(|e| StorageError::RocksDbError(e.to_string()))
+        self.write_state(key, None, "", None).map(|_| ())
     }
 
     fn get_range(&self, start: &str, end: &str) -> StorageResult<Vec<(String, VersionedValue)>> {
+        self.get_range_page(start, end, usize::MAX)
+    }
+
+    fn get_range_page(
+        &self,
+        start: &str,
+        end: &str,
+        limit: usize,
+    ) -> StorageResult<Vec<(String, VersionedValue)>> {
         let cf = self.cf_world_state()?;
         let mut result = Vec::new();
         let iter = self.db.iterator_cf(
@@ -1467,6 +1772,9 @@ impl WorldState for RocksDbBlockStore {
             IteratorMode::From(start.as_bytes(), Direction::Forward),
         );
         for item in iter {
+            if result.len() >= limit {
+                break;
+            }
             let (raw_key, raw_value) =
                 item.map_err(|e| StorageError::RocksDbError(e.to_string()))?;
             let k = String::from_utf8(raw_key.to_vec())
@@ -1474,6 +1782,7 @@ impl WorldState for RocksDbBlockStore {
             if k.as_str() >= end {
                 break;
             }
+            let raw_value = self.open(CF_WORLD_STATE, &raw_key, &raw_value)?;
             let vv: VersionedValue = serde_json::from_slice(&raw_value)
                 .map_err(|e| StorageError::DeserializationError(e.to_string()))?;
             result.push((k, vv));
@@ -1484,6 +1793,29 @@ impl WorldState for RocksDbBlockStore {
     fn get_history(&self, key: &str) -> StorageResult<Vec<crate::storage::traits::HistoryEntry>> {
         self.get_history(key)
     }
+
+    fn history_at(
+        &self,
+        height: u64,
+    ) -> StorageResult<Vec<(String, crate::storage::traits::HistoryEntry)>> {
+        self.history_at(height)
+    }
+
+    /// Commits through [`RocksDbBlockStore::commit_block`] when `store` is
+    /// this store, so a crash keeps the block and its writes together.
+    fn commit_block_to(
+        &self,
+        store: &dyn BlockStore,
+        block: &Block,
+        txs: &[Transaction],
+        writes: &[super::world_state::StateWrite],
+    ) -> StorageResult<()> {
+        if std::ptr::addr_eq(store as *const dyn BlockStore, self as *const Self) {
+            self.commit_block(block, txs, writes)
+        } else {
+            super::world_state::commit_in_steps(self, store, block, txs, writes)
+        }
+    }
 }
 
 // ── Chaincode package storage ─────────────────────────────────────────────────
@@ -1547,7 +1879,8 @@ impl RocksDbBlockStore {
         format!("private_{collection_name}")
     }
 
-    /// Ensure the side CF for `collection_name` exists, creating it if needed.
+    /// Ensure the side CF for `collection_name` exists, creating it (and
+    /// its data key when encryption is on) if needed.
     fn ensure_private_cf(&self, collection_name: &str) -> StorageResult<()> {
         let cf_name = Self::private_cf_name(collection_name);
         if self.db.cf_handle(&cf_name).is_none() {
@@ -1555,8 +1888,80 @@ impl RocksDbBlockStore {
                 .create_cf(&cf_name, &Options::default())
                 .map_err(|e| StorageError::RocksDbError(e.to_string()))?;
         }
+        self.prepare_scope(&cf_name)
+    }
+
+    fn cf_private_ttl(&self) -> StorageResult<Arc<rocksdb::BoundColumnFamily<'_>>> {
+        self.db
+            .cf_handle(CF_PRIVATE_TTL)
+            .ok_or_else(|| StorageError::ColumnFamilyNotFound(CF_PRIVATE_TTL.to_string()))
+    }
+
+    fn cf_private_expiry(&self) -> StorageResult<Arc<rocksdb::BoundColumnFamily<'_>>> {
+        self.db
+            .cf_handle(CF_PRIVATE_EXPIRY)
+            .ok_or_else(|| StorageError::ColumnFamilyNotFound(CF_PRIVATE_EXPIRY.to_string()))
+    }
+
+    /// Queue in `batch` pointing the TTL record of `(collection_name, key)`
+    /// at `expiry`, dropping its old expiry index entry.
+    fn batch_private_expiry(
+        &self,
+        batch: &mut WriteBatch,
+        collection_name: &str,
+        key: &str,
+        expiry: Option<u64>,
+    ) -> StorageResult<()> {
+        let cf_ttl = self.cf_private_ttl()?;
+        let cf_expiry = self.cf_private_expiry()?;
+        let ttl = ttl_key(collection_name, key);
+        if let Some(old) = self
+            .db
+            .get_cf(&cf_ttl, &ttl)
+            .map_err(|e| StorageError::RocksDbError(e.to_string()))?
+        {
+            let old = decode_expiry(&old)?;
+            batch.delete_cf(&cf_expiry, expiry_key(old, collection_name, key));
+        }
+        match expiry {
+            Some(expiry) => {
+                batch.put_cf(&cf_ttl, &ttl, expiry.to_le_bytes());
+                batch.put_cf(&cf_expiry, expiry_key(expiry, collection_name, key), []);
+            }
+            None => batch.delete_cf(&cf_ttl, &ttl),
+        }
         Ok(())
     }
+
+    /// Delete every private data entry whose expiry is at most
+    /// `current_height`, with its TTL records, in one batch.
+    fn purge_expired_private(&self, current_height: u64) -> StorageResult<usize> {
+        let cf_ttl = self.cf_private_ttl()?;
+        let cf_expiry = self.cf_private_expiry()?;
+        let mut batch = WriteBatch::default();
+        let mut purged = 0;
+        for item in self.db.iterator_cf(&cf_expiry, IteratorMode::Start) {
+            let (raw, _) = item.map_err(|e| StorageError::RocksDbError(e.to_string()))?;
+            let (expiry, collection, key) = parse_expiry_key(&raw).ok_or_else(|| {
+                StorageError::DataCorrupted("malformed private data expiry key".to_string())
+            })?;
+            if expiry > current_height {
+                break;
+            }
+            if let Some(cf) = self.db.cf_handle(&Self::private_cf_name(&collection)) {
+                batch.delete_cf(&cf, key.as_bytes());
+            }
+            batch.delete_cf(&cf_ttl, ttl_key(&collection, &key));
+            batch.delete_cf(&cf_expiry, &raw);
+            purged += 1;
+        }
+        if purged > 0 {
+            self.db
+                .write(batch)
+                .map_err(|e| StorageError::RocksDbError(e.to_string()))?;
+        }
+        Ok(purged)
+    }
 }
 
 impl PrivateDataStore for RocksDbBlockStore {
@@ -1573,12 +1978,46 @@ impl PrivateDataStore for RocksDbBlockStore {
             .cf_handle(&cf_name)
             .ok_or_else(|| StorageError::ColumnFamilyNotFound(cf_name.clone()))?;
         let hash = sha256(value);
+        let value = self.seal(&cf_name, key.as_bytes(), value)?;
         self.db
             .put_cf(&cf, key.as_bytes(), value)
             .map_err(|e| StorageError::RocksDbError(e.to_string()))?;
         Ok(hash)
     }
 
+    fn put_private_data_at(
+        &self,
+        collection_name: &str,
+        key: &str,
+        value: &[u8],
+        written_at_height: u64,
+        blocks_to_live: u64,
+    ) -> StorageResult<[u8; 32]> {
+        self.ensure_private_cf(collection_name)?;
+        let cf_name = Self::private_cf_name(collection_name);
+        let cf = self
+            .db
+            .cf_handle(&cf_name)
+            .ok_or_else(|| StorageError::ColumnFamilyNotFound(cf_name.clone()))?;
+        let hash = sha256(value);
+        let mut batch = WriteBatch::default();
+        batch.put_cf(
+            &cf,
+            key.as_bytes(),
+            self.seal(&cf_name, key.as_bytes(), value)?,
+        );
+        self.batch_private_expiry(
+            &mut batch,
+            collection_name,
+            key,
+            expiry_height(written_at_height, blocks_to_live),
+        )?;
+        self.db
+            .write(batch)
+            .map_err(|e| StorageError::RocksDbError(e.to_string()))?;
+        Ok(hash)
+    }
+
     fn get_private_data(&self, collection_name: &str, key: &str) -> StorageResult<Option<Vec<u8>>> {
         self.ensure_private_cf(collection_name)?;
         let cf_name = Self::private_cf_name(collection_name);
@@ -1588,8 +2027,44 @@ impl PrivateDataStore for RocksDbBlockStore {
             .ok_or_else(|| StorageError::ColumnFamilyNotFound(cf_name.clone()))?;
         self.db
             .get_cf(&cf, key.as_bytes())
-            .map(|opt| opt.map(|b| b.to_vec()))
-            .map_err(|e| StorageError::RocksDbError(e.to_string()))
+            .map_err(|e| StorageError::RocksDbError(e.to_string()))?
+            .map(|b| Ok(self.open(&cf_name, key.as_bytes(), &b)?.into_owned()))
+            .transpose()
+    }
+
+    fn purge_expired(&self, current_height: u64) {
+        match self.purge_expired_private(current_height) {
+            Ok(0) => {}
+            Ok(n) => {
+                log::info!("private data: purged {n} expired entries at height {current_height}")
+            }
+            Err(e) => log::error!("private data TTL purge failed: {e}"),
+        }
+    }
+
+    fn purge_private_data(&self, collection_name: &str, key: &str) -> StorageResult<bool> {
+        self.ensure_private_cf(collection_name)?;
+        let cf_name = Self::private_cf_name(collection_name);
+        let cf = self
+            .db
+            .cf_handle(&cf_name)
+            .ok_or_else(|| StorageError::ColumnFamilyNotFound(cf_name.clone()))?;
+        let existed = self
+            .db
+            .get_cf(&cf, key.as_bytes())
+            .map_err(|e| StorageError::RocksDbError(e.to_string()))?
+            .is_some();
+        let mut batch = WriteBatch::default();
+        batch.delete_cf(&cf, key.as_bytes());
+        self.batch_private_expiry(&mut batch, collection_name, key, None)?;
+        self.db
+            .write(batch)
+            .map_err(|e| StorageError::RocksDbError(e.to_string()))?;
+        // Compact the key's range so the erased value does not linger in
+        // older SST files until a background compaction reaches them.
+        self.db
+            .compact_range_cf(&cf, Some(key.as_bytes()), Some(key.as_bytes()));
+        Ok(existed)
     }
 }
 
@@ -1853,6 +2328,7 @@ impl crate::audit::AuditStore for RocksDbBlockStore {
         let key = format!("{}:{}", entry.timestamp, entry.trace_id);
         let value = serde_json::to_vec(entry)
             .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+        let value = self.seal(CF_AUDIT_LOG, key.as_bytes(), &value)?;
         self.db
             .put_cf(&cf, key.as_bytes(), &value)
             .map_err(|e| StorageError::RocksDbError(e.to_string()))?;
@@ -1889,8 +2365,9 @@ impl crate::audit::AuditStore for RocksDbBlockStore {
                     break;
                 }
             }
-            let entry: crate::audit::AuditEntry = serde_json::from_slice(&val_bytes)
-                .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+            let entry: crate::audit::AuditEntry =
+                serde_json::from_slice(&self.open(CF_AUDIT_LOG, &key_bytes, &val_bytes)?)
+                    .map_err(|e| StorageError::SerializationError(e.to_string()))?;
             if let Some(org) = org_id {
                 if entry.org_id != org {
                     continue;
@@ -1997,6 +2474,124 @@ impl crate::legal_oracle::OracleRecordStore for RocksDbBlockStore {
     }
 }
 
+// ── Encryption at rest on RocksDB ────────────────────────────────────────────
+
+impl EncryptedStore for RocksDbBlockStore {
+    fn encrypted_scopes(&self) -> StorageResult<Vec<String>> {
+        let mut scopes: Vec<String> = ENCRYPTED_SCOPES
+            .iter()
+            .filter(|name| ALL_CFS.contains(name))
+            .map(|name| name.to_string())
+            .collect();
+        let on_disk = RocksDB::list_cf(&Options::default(), self.db.path())
+            .map_err(|e| StorageError::RocksDbError(e.to_string()))?;
+        for name in on_disk {
+            if is_encrypted_scope(&name) && !scopes.contains(&name) {
+                scopes.push(name);
+            }
+        }
+        Ok(scopes)
+    }
+
+    fn encryption_coverage(&self) -> StorageResult<CoverageReport> {
+        let (versions, kek_id) = key_versions(&self.wrapped_data_keys()?);
+        let mut scopes = Vec::new();
+        for scope in self.encrypted_scopes()? {
+            let mut coverage = ScopeCoverage::new(&scope, versions.get(&scope).copied());
+            for item in self.db.iterator_cf(&self.cf(&scope)?, IteratorMode::Start) {
+                let (_, v) = item.map_err(|e| StorageError::RocksDbError(e.to_string()))?;
+                coverage.count(&v);
+            }
+            scopes.push(coverage);
+        }
+        Ok(CoverageReport {
+            enabled: self.encryption.get().is_some(),
+            kek_id,
+            scopes,
+            pending: self.pending_reencryption()?,
+        })
+    }
+
+    fn rotate_data_key(&self, scope: &str) -> StorageResult<u32> {
+        let cipher = self.cipher()?;
+        if !self.encrypted_scopes()?.iter().any(|s| s == scope) {
+            return Err(StorageError::Encryption(format!(
+                "{scope} is not an encrypted column family"
+            )));
+        }
+        cipher.rotate(scope, |wrapped| self.persist_data_key(wrapped))
+    }
+
+    fn pending_reencryption(&self) -> StorageResult<Vec<String>> {
+        self.meta_scopes(&pending_marker_key(""), parse_pending_marker)
+    }
+
+    fn reencrypt(
+        &self,
+        scope: &str,
+        after: Option<&[u8]>,
+        limit: usize,
+    ) -> StorageResult<ReencryptStep> {
+        let cipher = self.cipher()?;
+        if !is_encrypted_scope(scope) {
+            return Err(StorageError::Encryption(format!(
+                "{scope} is not an encrypted column family"
+            )));
+        }
+        let cf = self.cf(scope)?;
+        // Ledger and state writes hold the index lock, so none lands between
+        // reading an entry and rewriting it. Other scopes are re-read just
+        // before the batch and skipped if they changed meanwhile; a write in
+        // the short gap after that check is the only one that can be lost.
+        let _indexes = self.lock_state_indexes();
+        let mode = match after {
+            Some(after) => IteratorMode::From(after, Direction::Forward),
+            None => IteratorMode::Start,
+        };
+        let mut step = ReencryptStep::default();
+        let mut last = None;
+        let mut rewrites = Vec::new();
+        for item in self.db.iterator_cf(&cf, mode) {
+            let (k, v) = item.map_err(|e| StorageError::RocksDbError(e.to_string()))?;
+            if after == Some(&k[..]) {
+                continue;
+            }
+            if step.scanned == limit {
+                break;
+            }
+            step.scanned += 1;
+            if let Some(resealed) = cipher.reseal(scope, &k, &v)? {
+                rewrites.push((k.to_vec(), v.to_vec(), resealed));
+            }
+            last = Some(k.to_vec());
+        }
+        let mut batch = WriteBatch::default();
+        for (k, stored, resealed) in &rewrites {
+            let current = self
+                .db
+                .get_pinned_cf(&cf, k)
+                .map_err(|e| StorageError::RocksDbError(e.to_string()))?;
+            if current.as_deref() == Some(&stored[..]) {
+                batch.put_cf(&cf, k, resealed);
+                step.rewritten += 1;
+            }
+        }
+        if step.scanned == limit {
+            step.resume_after = last;
+        } else {
+            batch.delete_cf(&self.cf_meta()?, pending_marker_key(scope));
+            batch.put_cf(&self.cf_meta()?, sealed_marker_key(scope), b"");
+        }
+        self.db
+            .write(batch)
+            .map_err(|e| StorageError::RocksDbError(e.to_string()))?;
+        if step.resume_after.is_none() {
+            cipher.mark_sealed(scope);
+        }
+        Ok(step)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2086,6 +2681,13 @@ mod tests {
             secondary_signature_algorithm: None,
             hash_algorithm: Default::default(),
             orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: Vec::new(),
+            config_tx: None,
+            last_config: 0,
+            state_root: [0u8; 32],
+            private_data_purges: vec![],
         }
     }
 
@@ -2141,6 +2743,22 @@ mod tests {
         assert_eq!(store2.get_latest_height().unwrap(), 1);
     }
 
+    #[test]
+    fn read_only_open_reads_a_live_encrypted_db() {
+        use crate::storage::encryption::MlKemKek;
+
+        let (store, dir) = tmp_store();
+        let kek: Arc<dyn KeyEncryptionKey> = Arc::new(MlKemKek::generate().unwrap());
+        store.enable_encryption(kek.clone()).unwrap();
+        store.write_block(&sample_block(1)).unwrap();
+
+        let reader = RocksDbBlockStore::open_read_only(dir.path(), Some(kek)).unwrap();
+        assert_eq!(reader.read_block(1).unwrap().proposer, "proposer1");
+        assert!(reader.write_block(&sample_block(2)).is_err());
+        let sealed = RocksDbBlockStore::open_read_only(dir.path(), None).unwrap();
+        assert!(sealed.read_block(1).is_err());
+    }
+
     // ── Block operations ─────────────────────────────────────────────────────
 
     #[test]
@@ -2331,6 +2949,24 @@ mod tests {
         assert_eq!(block6[0].id, "tx-c");
     }
 
+    #[test]
+    fn prune_block_keeps_the_header_and_drops_tx_records() {
+        let (store, _dir) = tmp_store();
+        store.write_block(&sample_block(5)).unwrap();
+        store.write_transaction(&tx_at_height("tx-a", 5)).unwrap();
+        store.write_transaction(&tx_at_height("tx-b", 6)).unwrap();
+
+        let mut header = sample_block(5);
+        header.transactions.clear();
+        store.prune_block(&header).unwrap();
+
+        assert!(store.read_block(5).unwrap().transactions.is_empty());
+        assert!(store.transactions_by_block_height(5).unwrap().is_empty());
+        assert!(store.read_transaction("tx-a").is_err());
+        assert_eq!(store.read_transaction("tx-b").unwrap().id, "tx-b");
+        assert!(store.prune_block(&sample_block(9)).is_err());
+    }
+
     #[test]
     fn write_batch_indexes_transactions_by_block_height() {
         let (store, _dir) = tmp_store();
@@ -2765,6 +3401,7 @@ mod tests {
                 tx_id: "tx1".into(),
                 timestamp: 100,
                 is_delete: false,
+                block_height: None,
             },
             HistoryEntry {
                 version: 2,
@@ -2772,6 +3409,7 @@ mod tests {
                 tx_id: "tx2".into(),
                 timestamp: 200,
                 is_delete: false,
+                block_height: None,
             },
             HistoryEntry {
                 version: 3,
@@ -2779,6 +3417,7 @@ mod tests {
                 tx_id: "tx3".into(),
                 timestamp: 300,
                 is_delete: true,
+                block_height: None,
             },
         ];
 
@@ -2810,6 +3449,7 @@ mod tests {
                     tx_id: "t1".into(),
                     timestamp: 10,
                     is_delete: false,
+                    block_height: None,
                 },
             )
             .unwrap();
@@ -2822,6 +3462,7 @@ mod tests {
                     tx_id: "t2".into(),
                     timestamp: 20,
                     is_delete: false,
+                    block_height: None,
                 },
             )
             .unwrap();
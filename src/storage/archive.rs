@@ -0,0 +1,811 @@
+//! Block retention and cold archival.
+//!
+//! Blocks past a channel's [`RetentionPolicy`] are moved, together with
+//! their transaction records, out of the block store into compressed
+//! archive segments under `{base_dir}/archive/{channel_id}/`:
+//!
+//! - `{first:012}-{last:012}.seg` — gzip-compressed JSON of the full blocks
+//!   and transaction records of one contiguous height range.
+//! - `manifest.json` — the list of segments, signed by the node. Each entry
+//!   records the SHA-256 of its segment file and the hash of the previous
+//!   entry, so the segments form a hash chain. A manifest is only accepted
+//!   when its signer is a trusted key: the node's current key or one listed
+//!   when the archive is opened.
+//!
+//! The block store keeps a header of every archived block (see
+//! [`block_header`]): parent-hash linkage, orderer signatures and config
+//! lookups keep working on a pruned chain, while the bodies are read back
+//! from the archive on demand and checked against those headers.
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use pqc_crypto_module::legacy::sha256::{Digest, Sha256};
+use serde::{Deserialize, Serialize};
+
+use super::errors::{StorageError, StorageResult};
+use super::traits::{Block, BlockStore, Transaction};
+use crate::channel::config::RetentionPolicy;
+use crate::identity::signing::SigningProvider;
+
+/// Most blocks moved into a single segment by one retention pass.
+pub const MAX_SEGMENT_BLOCKS: u64 = 1_000;
+
+/// Domain separator of the manifest signing digest.
+const MANIFEST_DOMAIN: &[u8] = b"rust-bc/archive-manifest/v1";
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// One archived height range.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SegmentEntry {
+    pub first_height: u64,
+    pub last_height: u64,
+    /// Segment file name, relative to the channel's archive directory.
+    pub file: String,
+    /// SHA-256 of the compressed segment file.
+    pub content_hash: [u8; 32],
+    /// [`SegmentEntry::entry_hash`] of the previous segment (zero for the first).
+    pub prev_hash: [u8; 32],
+    pub block_count: u64,
+    pub tx_count: u64,
+}
+
+impl SegmentEntry {
+    /// Hash linking this entry to the next one.
+    pub fn entry_hash(&self) -> [u8; 32] {
+        let mut h = Sha256::new();
+        h.update(self.first_height.to_le_bytes());
+        h.update(self.last_height.to_le_bytes());
+        h.update(self.file.as_bytes());
+        h.update(self.content_hash);
+        h.update(self.prev_hash);
+        h.update(self.block_count.to_le_bytes());
+        h.update(self.tx_count.to_le_bytes());
+        h.finalize().into()
+    }
+}
+
+/// Signed index of a channel's archive segments.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub channel_id: String,
+    pub segments: Vec<SegmentEntry>,
+    /// Hex-encoded public key of the node that signed the manifest.
+    pub signer_public_key: String,
+    /// Hex-encoded signature over [`ArchiveManifest::signing_digest`].
+    pub signature: String,
+}
+
+impl ArchiveManifest {
+    fn empty(channel_id: &str) -> Self {
+        Self {
+            channel_id: channel_id.to_string(),
+            segments: Vec::new(),
+            signer_public_key: String::new(),
+            signature: String::new(),
+        }
+    }
+
+    /// Highest archived height, `None` while nothing is archived.
+    pub fn archived_through(&self) -> Option<u64> {
+        self.segments.last().map(|s| s.last_height)
+    }
+
+    /// Segment holding `height`, if it is archived.
+    pub fn segment_for(&self, height: u64) -> Option<&SegmentEntry> {
+        self.segments
+            .iter()
+            .find(|s| (s.first_height..=s.last_height).contains(&height))
+    }
+
+    /// Digest the signature covers: the channel and the hash of the last
+    /// entry, which commits to every entry before it.
+    pub fn signing_digest(&self) -> [u8; 32] {
+        let mut h = Sha256::new();
+        h.update(MANIFEST_DOMAIN);
+        h.update((self.channel_id.len() as u64).to_le_bytes());
+        h.update(self.channel_id.as_bytes());
+        h.update((self.segments.len() as u64).to_le_bytes());
+        h.update(
+            self.segments
+                .last()
+                .map(|s| s.entry_hash())
+                .unwrap_or([0u8; 32]),
+        );
+        h.finalize().into()
+    }
+
+    /// Check the hash chain, the contiguity of the ranges and the signature,
+    /// which must be made by one of `trusted_keys`.
+    pub fn verify(&self, trusted_keys: &[Vec<u8>]) -> StorageResult<()> {
+        let mut prev_hash = [0u8; 32];
+        let mut next_height = self.segments.first().map(|s| s.first_height);
+        for seg in &self.segments {
+            if seg.prev_hash != prev_hash {
+                return Err(StorageError::DataCorrupted(format!(
+                    "archive segment {} is not linked to its predecessor",
+                    seg.file
+                )));
+            }
+            if Some(seg.first_height) != next_height || seg.last_height < seg.first_height {
+                return Err(StorageError::DataCorrupted(format!(
+                    "archive segment {} breaks the archived height range",
+                    seg.file
+                )));
+            }
+            prev_hash = seg.entry_hash();
+            next_height = Some(seg.last_height + 1);
+        }
+        if self.segments.is_empty() {
+            return Ok(());
+        }
+        let public_key = hex::decode(&self.signer_public_key)
+            .map_err(|e| StorageError::DataCorrupted(format!("manifest signer key: {e}")))?;
+        if !trusted_keys.contains(&public_key) {
+            return Err(StorageError::DataCorrupted(format!(
+                "archive manifest is signed by untrusted key {}",
+                self.signer_public_key
+            )));
+        }
+        let signature = hex::decode(&self.signature)
+            .map_err(|e| StorageError::DataCorrupted(format!("manifest signature: {e}")))?;
+        if !crate::identity::signing::verify_with_public_key(
+            &public_key,
+            &self.signing_digest(),
+            &signature,
+        ) {
+            return Err(StorageError::DataCorrupted(
+                "archive manifest signature is invalid".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Extra manifest signer keys from `ARCHIVE_TRUSTED_KEYS`: comma-separated
+/// hex public keys. Malformed entries are skipped with a warning.
+pub fn trusted_keys_from_env() -> Vec<Vec<u8>> {
+    std::env::var("ARCHIVE_TRUSTED_KEYS")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+        .filter_map(|k| match hex::decode(k) {
+            Ok(key) => Some(key),
+            Err(e) => {
+                log::warn!("Ignoring ARCHIVE_TRUSTED_KEYS entry '{k}': {e}");
+                None
+            }
+        })
+        .collect()
+}
+
+/// Decoded contents of a segment file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Segment {
+    pub blocks: Vec<Block>,
+    pub transactions: Vec<Transaction>,
+}
+
+/// Header kept in the block store for an archived block: everything but
+/// the body (transaction IDs, endorsements and evidence).
+///
+/// The genesis block and config blocks keep their transaction list — it
+/// holds the channel config — so [`crate::channel::ledger::config_history`]
+/// still resolves on a pruned chain.
+pub fn block_header(block: &Block) -> Block {
+    let mut header = block.clone();
+    if block.height > 0 && block.config_tx.is_none() {
+        header.transactions.clear();
+    }
+    header.endorsements.clear();
+    header.evidence.clear();
+    header
+}
+
+/// Cold archive of one channel's blocks.
+pub struct BlockArchive {
+    dir: PathBuf,
+    signer: Arc<dyn SigningProvider>,
+    /// Public keys whose manifest signatures are accepted.
+    trusted_keys: Vec<Vec<u8>>,
+    manifest: Mutex<ArchiveManifest>,
+}
+
+impl BlockArchive {
+    /// Open (or create) the archive of `channel_id` under `base_dir`,
+    /// verifying any existing manifest. Manifests signed by `signer` or by
+    /// one of `trusted_keys` (e.g. the keys the node signed with before a
+    /// restart) are accepted.
+    pub fn open(
+        base_dir: &Path,
+        channel_id: &str,
+        signer: Arc<dyn SigningProvider>,
+        mut trusted_keys: Vec<Vec<u8>>,
+    ) -> StorageResult<Self> {
+        trusted_keys.push(signer.public_key());
+        let dir = base_dir.join("archive").join(channel_id);
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| StorageError::Other(format!("failed to create archive dir: {e}")))?;
+
+        let manifest = match std::fs::read(dir.join(MANIFEST_FILE)) {
+            Ok(bytes) => {
+                let manifest: ArchiveManifest = serde_json::from_slice(&bytes)
+                    .map_err(|e| StorageError::DeserializationError(e.to_string()))?;
+                manifest.verify(&trusted_keys)?;
+                manifest
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                ArchiveManifest::empty(channel_id)
+            }
+            Err(e) => {
+                return Err(StorageError::Other(format!(
+                    "failed to read archive manifest: {e}"
+                )))
+            }
+        };
+
+        Ok(Self {
+            dir,
+            signer,
+            trusted_keys,
+            manifest: Mutex::new(manifest),
+        })
+    }
+
+    pub fn manifest(&self) -> ArchiveManifest {
+        self.manifest
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    pub fn archived_through(&self) -> Option<u64> {
+        self.manifest
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .archived_through()
+    }
+
+    /// Move blocks `from..=to` and their transaction records from `store`
+    /// into a new segment, then prune them down to headers.
+    ///
+    /// `from` must follow the last archived height. The segment and the
+    /// re-signed manifest are on disk before anything is pruned, so a crash
+    /// in between leaves the data in both places rather than in neither.
+    pub fn archive_range(
+        &self,
+        store: &dyn BlockStore,
+        from: u64,
+        to: u64,
+    ) -> StorageResult<SegmentEntry> {
+        let mut manifest = self.manifest.lock().unwrap_or_else(|e| e.into_inner());
+        let expected = manifest.archived_through().map_or(0, |h| h + 1);
+        if from != expected || to < from {
+            return Err(StorageError::Other(format!(
+                "archive range {from}..={to} does not continue the archive at {expected}"
+            )));
+        }
+
+        let mut segment = Segment {
+            blocks: Vec::with_capacity((to - from + 1) as usize),
+            transactions: Vec::new(),
+        };
+        for height in from..=to {
+            segment.blocks.push(store.read_block(height)?);
+            segment
+                .transactions
+                .extend(store.transactions_by_block_height(height)?);
+        }
+
+        let json = serde_json::to_vec(&segment)
+            .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(&json)
+            .and_then(|_| encoder.try_finish())
+            .map_err(|e| StorageError::Other(format!("segment compression failed: {e}")))?;
+        let compressed = encoder
+            .finish()
+            .map_err(|e| StorageError::Other(format!("segment compression failed: {e}")))?;
+
+        let file = format!("{from:012}-{to:012}.seg");
+        write_atomically(&self.dir.join(&file), &compressed)?;
+
+        let entry = SegmentEntry {
+            first_height: from,
+            last_height: to,
+            file,
+            content_hash: Sha256::digest(&compressed).into(),
+            prev_hash: manifest
+                .segments
+                .last()
+                .map(|s| s.entry_hash())
+                .unwrap_or([0u8; 32]),
+            block_count: segment.blocks.len() as u64,
+            tx_count: segment.transactions.len() as u64,
+        };
+
+        let mut next = manifest.clone();
+        next.segments.push(entry.clone());
+        self.sign(&mut next)?;
+        let bytes = serde_json::to_vec_pretty(&next)
+            .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+        write_atomically(&self.dir.join(MANIFEST_FILE), &bytes)?;
+        *manifest = next;
+        drop(manifest);
+
+        for block in &segment.blocks {
+            store.prune_block(&block_header(block))?;
+        }
+        Ok(entry)
+    }
+
+    /// Read and decode the segment holding `height`, checking its hash
+    /// against the manifest and its blocks against the headers `store`
+    /// kept for them.
+    pub fn read_segment(&self, store: &dyn BlockStore, height: u64) -> StorageResult<Segment> {
+        let entry = self
+            .manifest
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .segment_for(height)
+            .cloned()
+            .ok_or_else(|| StorageError::KeyNotFound(format!("archived block:{height}")))?;
+
+        let compressed = std::fs::read(self.dir.join(&entry.file))
+            .map_err(|e| StorageError::Other(format!("failed to read {}: {e}", entry.file)))?;
+        let hash: [u8; 32] = Sha256::digest(&compressed).into();
+        if hash != entry.content_hash {
+            return Err(StorageError::DataCorrupted(format!(
+                "archive segment {} does not match its manifest hash",
+                entry.file
+            )));
+        }
+
+        let mut json = Vec::new();
+        GzDecoder::new(compressed.as_slice())
+            .read_to_end(&mut json)
+            .map_err(|e| StorageError::DataCorrupted(format!("{}: {e}", entry.file)))?;
+        let segment: Segment = serde_json::from_slice(&json)
+            .map_err(|e| StorageError::DeserializationError(e.to_string()))?;
+        check_against_headers(store, &entry, &segment)?;
+        Ok(segment)
+    }
+
+    /// Full archived block at `height`.
+    pub fn read_block(&self, store: &dyn BlockStore, height: u64) -> StorageResult<Block> {
+        self.read_segment(store, height)?
+            .blocks
+            .into_iter()
+            .find(|b| b.height == height)
+            .ok_or_else(|| StorageError::KeyNotFound(format!("archived block:{height}")))
+    }
+
+    /// Archived transaction records of the block at `height`.
+    pub fn read_transactions(
+        &self,
+        store: &dyn BlockStore,
+        height: u64,
+    ) -> StorageResult<Vec<Transaction>> {
+        Ok(self
+            .read_segment(store, height)?
+            .transactions
+            .into_iter()
+            .filter(|tx| tx.block_height == height)
+            .collect())
+    }
+
+    /// Full archived blocks in `from..=to`, in height order.
+    pub fn read_range(
+        &self,
+        store: &dyn BlockStore,
+        from: u64,
+        to: u64,
+    ) -> StorageResult<Vec<Block>> {
+        let mut blocks = Vec::new();
+        let mut height = from;
+        while height <= to {
+            let segment = self.read_segment(store, height)?;
+            let last = segment.blocks.last().map_or(height, |b| b.height);
+            blocks.extend(
+                segment
+                    .blocks
+                    .into_iter()
+                    .filter(|b| (height..=to).contains(&b.height)),
+            );
+            height = last + 1;
+        }
+        Ok(blocks)
+    }
+
+    /// Verify the manifest, the hash of every segment file and every
+    /// archived block against its header in `store`.
+    pub fn verify(&self, store: &dyn BlockStore) -> StorageResult<()> {
+        let manifest = self.manifest();
+        manifest.verify(&self.trusted_keys)?;
+        for seg in &manifest.segments {
+            self.read_segment(store, seg.first_height)?;
+        }
+        Ok(())
+    }
+
+    fn sign(&self, manifest: &mut ArchiveManifest) -> StorageResult<()> {
+        let signature = self
+            .signer
+            .sign(&manifest.signing_digest())
+            .map_err(|e| StorageError::Other(format!("failed to sign archive manifest: {e}")))?;
+        manifest.signer_public_key = hex::encode(self.signer.public_key());
+        manifest.signature = hex::encode(signature);
+        Ok(())
+    }
+}
+
+/// Check that `segment` holds exactly the blocks of `entry`, and that each
+/// block is the one whose header `store` kept: same signing hash (which
+/// the orderer signature covers) and same Merkle root.
+fn check_against_headers(
+    store: &dyn BlockStore,
+    entry: &SegmentEntry,
+    segment: &Segment,
+) -> StorageResult<()> {
+    let heights = segment.blocks.iter().map(|b| b.height);
+    if !heights.eq(entry.first_height..=entry.last_height) {
+        return Err(StorageError::DataCorrupted(format!(
+            "archive segment {} does not hold blocks {}..={}",
+            entry.file, entry.first_height, entry.last_height
+        )));
+    }
+    for block in &segment.blocks {
+        let header = store.read_block(block.height)?;
+        if block.merkle_root != header.merkle_root
+            || crate::ordering::block_hash_for_signing(block)
+                != crate::ordering::block_hash_for_signing(&header)
+        {
+            return Err(StorageError::DataCorrupted(format!(
+                "archived block {} in {} does not match its stored header",
+                block.height, entry.file
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Last height `policy` lets the retention worker archive, scanning from
+/// the archive frontier `from`. `None` when nothing is due.
+///
+/// A block is due once it is more than `block_retention_count` blocks
+/// below the tip, or once it is older than `transaction_retention_secs`
+/// (its body is its transactions). The tip itself is never archived, and
+/// the scan stops at the first block still inside the policy so archived
+/// ranges stay contiguous.
+pub fn retention_cutoff(
+    store: &dyn BlockStore,
+    policy: &RetentionPolicy,
+    from: u64,
+    now_secs: u64,
+) -> StorageResult<Option<u64>> {
+    if policy.block_retention_count == 0 && policy.transaction_retention_secs == 0 {
+        return Ok(None);
+    }
+    let tip = store.get_latest_height()?;
+    let mut cutoff = None;
+    let mut height = from;
+    while height < tip && height < from.saturating_add(MAX_SEGMENT_BLOCKS) {
+        let by_count = policy.block_retention_count > 0
+            && height.saturating_add(policy.block_retention_count) <= tip;
+        let by_age = policy.transaction_retention_secs > 0
+            && store
+                .read_block(height)?
+                .timestamp
+                .saturating_add(policy.transaction_retention_secs)
+                <= now_secs;
+        if !(by_count || by_age) {
+            break;
+        }
+        cutoff = Some(height);
+        height += 1;
+    }
+    Ok(cutoff)
+}
+
+/// One retention pass: archive the next due range of `store`, if any.
+pub fn enforce_retention(
+    store: &dyn BlockStore,
+    archive: &BlockArchive,
+    policy: &RetentionPolicy,
+    now_secs: u64,
+) -> StorageResult<Option<SegmentEntry>> {
+    let from = archive.archived_through().map_or(0, |h| h + 1);
+    match retention_cutoff(store, policy, from, now_secs)? {
+        Some(to) => archive.archive_range(store, from, to).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Write `bytes` to `path` through a temporary file and a rename.
+fn write_atomically(path: &Path, bytes: &[u8]) -> StorageResult<()> {
+    let tmp = path.with_extension("tmp");
+    let mut file = std::fs::File::create(&tmp)
+        .map_err(|e| StorageError::Other(format!("failed to create {}: {e}", tmp.display())))?;
+    file.write_all(bytes)
+        .and_then(|_| file.sync_all())
+        .map_err(|e| StorageError::Other(format!("write error: {e}")))?;
+    std::fs::rename(&tmp, path)
+        .map_err(|e| StorageError::Other(format!("failed to rename {}: {e}", tmp.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identity::signing::SoftwareSigningProvider;
+    use crate::storage::memory::MemoryStore;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("rust-bc-archive-{name}-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn block(height: u64, timestamp: u64, parent_hash: [u8; 32]) -> Block {
+        Block {
+            height,
+            timestamp,
+            parent_hash,
+            merkle_root: [height as u8; 32],
+            transactions: vec![format!("tx-{height}")],
+            proposer: "orderer".to_string(),
+            signature: vec![0u8; 64],
+            signature_algorithm: Default::default(),
+            endorsements: vec![],
+            secondary_signature: None,
+            secondary_signature_algorithm: None,
+            hash_algorithm: Default::default(),
+            orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: vec![],
+            config_tx: None,
+            last_config: 0,
+            state_root: [0u8; 32],
+            private_data_purges: vec![],
+        }
+    }
+
+    /// Chain of `count` blocks, one per 10 seconds, each with one tx record.
+    fn chain(count: u64) -> MemoryStore {
+        let store = MemoryStore::new();
+        let mut parent = [0u8; 32];
+        for height in 0..count {
+            let b = block(height, height * 10, parent);
+            parent = crate::ordering::block_hash_for_signing(&b);
+            store.write_block(&b).unwrap();
+            store
+                .write_transaction(&Transaction {
+                    id: format!("tx-{height}"),
+                    block_height: height,
+                    timestamp: height * 10,
+                    input_did: "did:a".to_string(),
+                    output_recipient: "did:b".to_string(),
+                    amount: height,
+                    state: "confirmed".to_string(),
+                })
+                .unwrap();
+        }
+        store
+    }
+
+    fn open(dir: &Path) -> BlockArchive {
+        BlockArchive::open(
+            dir,
+            "ch1",
+            Arc::new(SoftwareSigningProvider::generate()),
+            vec![],
+        )
+        .unwrap()
+    }
+
+    fn count_policy(n: u64) -> RetentionPolicy {
+        RetentionPolicy {
+            block_retention_count: n,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn default_policy_archives_nothing() {
+        let store = chain(5);
+        let archive = open(&temp_dir("none"));
+        let done = enforce_retention(&store, &archive, &RetentionPolicy::default(), 1_000).unwrap();
+        assert!(done.is_none());
+        assert_eq!(store.read_block(0).unwrap().transactions.len(), 1);
+    }
+
+    #[test]
+    fn count_policy_archives_blocks_below_the_window_and_keeps_headers() {
+        let store = chain(10);
+        let archive = open(&temp_dir("count"));
+
+        let entry = enforce_retention(&store, &archive, &count_policy(3), 0)
+            .unwrap()
+            .unwrap();
+        assert_eq!((entry.first_height, entry.last_height), (0, 6));
+        assert_eq!(entry.tx_count, 7);
+
+        // Headers remain, bodies and tx records are gone from the store.
+        let header = store.read_block(3).unwrap();
+        assert!(header.transactions.is_empty());
+        assert!(store.transactions_by_block_height(3).unwrap().is_empty());
+        assert!(store.read_transaction("tx-3").is_err());
+        assert_eq!(store.read_block(7).unwrap().transactions.len(), 1);
+
+        // Parent-hash linkage still checks out over the pruned headers.
+        for h in 1..10 {
+            let prev = store.read_block(h - 1).unwrap();
+            assert_eq!(
+                store.read_block(h).unwrap().parent_hash,
+                crate::ordering::block_hash_for_signing(&prev)
+            );
+        }
+
+        // Archived bodies are readable on demand.
+        assert_eq!(
+            archive.read_block(&store, 3).unwrap().transactions,
+            vec!["tx-3"]
+        );
+        assert_eq!(archive.read_transactions(&store, 3).unwrap()[0].id, "tx-3");
+        assert!(archive.read_block(&store, 7).is_err());
+    }
+
+    #[test]
+    fn age_policy_archives_old_blocks_but_never_the_tip() {
+        let store = chain(5);
+        let archive = open(&temp_dir("age"));
+        let policy = RetentionPolicy {
+            transaction_retention_secs: 15,
+            ..Default::default()
+        };
+        // now=35: blocks with timestamp <= 20 are due (0, 10, 20).
+        let entry = enforce_retention(&store, &archive, &policy, 35)
+            .unwrap()
+            .unwrap();
+        assert_eq!((entry.first_height, entry.last_height), (0, 2));
+
+        // Far in the future everything but the tip is due.
+        let entry = enforce_retention(&store, &archive, &policy, 10_000)
+            .unwrap()
+            .unwrap();
+        assert_eq!((entry.first_height, entry.last_height), (3, 3));
+        assert_eq!(store.read_block(4).unwrap().transactions.len(), 1);
+    }
+
+    #[test]
+    fn segments_are_hash_linked_and_the_manifest_survives_reopen() {
+        let dir = temp_dir("reopen");
+        let store = chain(10);
+        let signer: Arc<dyn SigningProvider> = Arc::new(SoftwareSigningProvider::generate());
+        let archive = BlockArchive::open(&dir, "ch1", signer.clone(), vec![]).unwrap();
+        archive.archive_range(&store, 0, 2).unwrap();
+        archive.archive_range(&store, 3, 5).unwrap();
+
+        let manifest = archive.manifest();
+        assert_eq!(
+            manifest.segments[1].prev_hash,
+            manifest.segments[0].entry_hash()
+        );
+        manifest.verify(&[signer.public_key()]).unwrap();
+        archive.verify(&store).unwrap();
+
+        let reopened = BlockArchive::open(&dir, "ch1", signer, vec![]).unwrap();
+        assert_eq!(reopened.archived_through(), Some(5));
+        let range = reopened.read_range(&store, 1, 4).unwrap();
+        assert_eq!(
+            range.iter().map(|b| b.height).collect::<Vec<_>>(),
+            vec![1, 2, 3, 4]
+        );
+    }
+
+    #[test]
+    fn ranges_must_continue_the_archive() {
+        let store = chain(10);
+        let archive = open(&temp_dir("gap"));
+        assert!(archive.archive_range(&store, 1, 3).is_err());
+        archive.archive_range(&store, 0, 3).unwrap();
+        assert!(archive.archive_range(&store, 5, 6).is_err());
+    }
+
+    #[test]
+    fn tampered_manifest_or_segment_is_rejected() {
+        let dir = temp_dir("tamper");
+        let store = chain(6);
+        let signer: Arc<dyn SigningProvider> = Arc::new(SoftwareSigningProvider::generate());
+        let archive = BlockArchive::open(&dir, "ch1", signer.clone(), vec![]).unwrap();
+        let entry = archive.archive_range(&store, 0, 3).unwrap();
+
+        // Flip a byte in the segment file.
+        let seg_path = dir.join("archive").join("ch1").join(&entry.file);
+        let mut bytes = std::fs::read(&seg_path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        std::fs::write(&seg_path, bytes).unwrap();
+        assert!(matches!(
+            archive.read_block(&store, 1),
+            Err(StorageError::DataCorrupted(_))
+        ));
+
+        // Rewrite the manifest's segment count without re-signing.
+        let mut manifest = archive.manifest();
+        manifest.segments[0].tx_count = 99;
+        assert!(manifest.verify(&[signer.public_key()]).is_err());
+        let manifest_path = dir.join("archive").join("ch1").join(MANIFEST_FILE);
+        std::fs::write(&manifest_path, serde_json::to_vec(&manifest).unwrap()).unwrap();
+        assert!(BlockArchive::open(&dir, "ch1", signer, vec![]).is_err());
+    }
+
+    #[test]
+    fn manifest_must_be_signed_by_a_trusted_key() {
+        let dir = temp_dir("trust");
+        let store = chain(6);
+        let old: Arc<dyn SigningProvider> = Arc::new(SoftwareSigningProvider::generate());
+        let archive = BlockArchive::open(&dir, "ch1", old.clone(), vec![]).unwrap();
+        archive.archive_range(&store, 0, 2).unwrap();
+
+        // A manifest re-signed by any other key carries a valid signature
+        // but is not trusted.
+        let forger = SoftwareSigningProvider::generate();
+        let mut forged = archive.manifest();
+        forged.signer_public_key = hex::encode(forger.public_key());
+        forged.signature = hex::encode(forger.sign(&forged.signing_digest()).unwrap());
+        assert!(forged.verify(&[old.public_key()]).is_err());
+        assert!(forged.verify(&[forger.public_key()]).is_ok());
+
+        // After a restart the node signs with a fresh key: the archive opens
+        // only when the key that signed the manifest is configured as trusted.
+        let new: Arc<dyn SigningProvider> = Arc::new(SoftwareSigningProvider::generate());
+        assert!(BlockArchive::open(&dir, "ch1", new.clone(), vec![]).is_err());
+        let reopened = BlockArchive::open(&dir, "ch1", new, vec![old.public_key()]).unwrap();
+        assert_eq!(reopened.archived_through(), Some(2));
+        reopened.archive_range(&store, 3, 4).unwrap();
+        reopened.verify(&store).unwrap();
+    }
+
+    #[test]
+    fn archived_block_not_matching_its_header_is_rejected() {
+        let store = chain(6);
+        let archive = open(&temp_dir("headers"));
+        archive.archive_range(&store, 0, 3).unwrap();
+        archive.verify(&store).unwrap();
+
+        // The header kept for block 2 commits to another Merkle root.
+        let mut header = store.read_block(2).unwrap();
+        header.merkle_root = [0xee; 32];
+        store.prune_block(&header).unwrap();
+        assert!(matches!(
+            archive.read_block(&store, 2),
+            Err(StorageError::DataCorrupted(_))
+        ));
+        assert!(archive.verify(&store).is_err());
+    }
+
+    #[test]
+    fn config_blocks_keep_their_config_in_the_header() {
+        let tx = crate::channel::config::ConfigTransaction {
+            tx_id: "cfg-0".to_string(),
+            channel_id: "ch1".to_string(),
+            updates: vec![],
+            signatures: vec![],
+            created_at: 0,
+        };
+        let config = crate::channel::ledger::config_block(4, "orderer", tx);
+        let header = block_header(&config);
+        assert_eq!(header.transactions, config.transactions);
+        assert!(header.config_tx.is_some());
+        assert!(crate::channel::ledger::check_config_block_shape(&header).is_ok());
+    }
+}
@@ -0,0 +1,527 @@
+//! Online backups and point-in-time restore of a node's RocksDB databases.
+//!
+//! Each database — the block store at `STORAGE_PATH`, the Raft log at
+//! `STORAGE_PATH/raft` and every channel store under
+//! `STORAGE_PATH/channels/` — gets an incremental RocksDB backup engine
+//! under `{backup_dir}/{name}`. SST files shared between backups are stored
+//! once, so frequent backups stay cheap. A node backup ties one backup id
+//! per database together; the list lives in `{backup_dir}/catalog.json`.
+//!
+//! Backups are taken while the node runs. The databases are backed up one
+//! after the other, block store first, so a restored Raft log may be a few
+//! entries ahead of the restored ledger; the orderer syncs the missing
+//! blocks from peers as it does after a Raft snapshot.
+//!
+//! Restore runs with the node stopped: the backup is restored into a
+//! staging directory, its schema version and hash chain are verified, and
+//! only then is it swapped in for `STORAGE_PATH`. The replaced data is kept
+//! next to it as `{STORAGE_PATH}.pre-restore-{unix_time}`.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::errors::{StorageError, StorageResult};
+
+const CATALOG_FILE: &str = "catalog.json";
+
+/// Name of the block store in a [`NodeBackup`].
+pub const BLOCK_STORE: &str = "blocks";
+
+/// Backup of one database within a node backup.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatabaseBackup {
+    /// `blocks`, `raft` or `channels/{channel_id}`.
+    pub name: String,
+    /// Id of the backup in the database's backup engine.
+    pub backup_id: u32,
+    /// Size of the backup in bytes, shared files included.
+    pub size: u64,
+}
+
+/// One point-in-time backup of a node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeBackup {
+    pub id: u64,
+    pub created_at: u64,
+    /// Schema version of the block store when the backup was taken.
+    pub schema_version: u32,
+    /// Latest block height of the block store when the backup was taken.
+    pub latest_height: u64,
+    pub databases: Vec<DatabaseBackup>,
+}
+
+/// The node backups kept in a backup directory, oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupCatalog {
+    pub backups: Vec<NodeBackup>,
+}
+
+impl BackupCatalog {
+    /// Load the catalog of `backup_dir`; empty when none was written yet.
+    pub fn load(backup_dir: &Path) -> StorageResult<Self> {
+        match std::fs::read(backup_dir.join(CATALOG_FILE)) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|e| StorageError::DeserializationError(e.to_string())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write the catalog, replacing the previous one atomically.
+    pub fn save(&self, backup_dir: &Path) -> StorageResult<()> {
+        std::fs::create_dir_all(backup_dir)?;
+        let bytes = serde_json::to_vec_pretty(self)
+            .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+        let tmp = backup_dir.join(format!("{CATALOG_FILE}.tmp"));
+        std::fs::write(&tmp, bytes)?;
+        std::fs::rename(&tmp, backup_dir.join(CATALOG_FILE))?;
+        Ok(())
+    }
+
+    /// Id the next node backup gets.
+    pub fn next_id(&self) -> u64 {
+        self.backups.last().map(|b| b.id + 1).unwrap_or(1)
+    }
+
+    pub fn get(&self, id: u64) -> Option<&NodeBackup> {
+        self.backups.iter().find(|b| b.id == id)
+    }
+}
+
+/// Directory of the database `name` below `storage_path`.
+pub fn database_dir(storage_path: &Path, name: &str) -> PathBuf {
+    if name == BLOCK_STORE {
+        storage_path.to_path_buf()
+    } else {
+        storage_path.join(name)
+    }
+}
+
+/// Takes and lists node backups; see [`RocksDbBackups`].
+pub trait BackupProvider: Send + Sync {
+    /// Take a new node backup while the node keeps running.
+    fn create_backup(&self) -> StorageResult<NodeBackup>;
+
+    /// The node backups available for restore, oldest first.
+    fn list_backups(&self) -> StorageResult<Vec<NodeBackup>>;
+}
+
+/// Outcome of [`restore_backup`].
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreReport {
+    pub backup: NodeBackup,
+    pub chain: super::integrity::ChainSummary,
+    /// Where the data that was replaced now lives, if there was any.
+    pub previous_data: Option<PathBuf>,
+}
+
+// `restore_backup` is only called by bcctl, not the node binary.
+#[cfg(feature = "rocksdb-storage")]
+#[allow(unused_imports)]
+pub use self::rocks::{restore_backup, BackupSource, RocksDB, RocksDbBackups};
+
+#[cfg(feature = "rocksdb-storage")]
+mod rocks {
+    use std::path::{Path, PathBuf};
+    use std::sync::{Arc, Mutex};
+
+    use rocksdb::backup::{BackupEngine, BackupEngineOptions, RestoreOptions};
+    use rocksdb::{DBWithThreadMode, Env, MultiThreaded};
+
+    use super::{
+        database_dir, BackupCatalog, BackupProvider, DatabaseBackup, NodeBackup, RestoreReport,
+        BLOCK_STORE,
+    };
+    use crate::storage::adapters::RocksDbBlockStore;
+    use crate::storage::errors::{StorageError, StorageResult};
+    use crate::storage::integrity::verify_hash_chain;
+    use crate::storage::migrations;
+    use crate::storage::traits::BlockStore;
+
+    pub type RocksDB = DBWithThreadMode<MultiThreaded>;
+
+    /// A live RocksDB database included in node backups.
+    pub trait BackupSource: Send + Sync {
+        fn database(&self) -> &RocksDB;
+    }
+
+    impl BackupSource for RocksDbBlockStore {
+        fn database(&self) -> &RocksDB {
+            &self.db
+        }
+    }
+
+    fn rocks_err(e: rocksdb::Error) -> StorageError {
+        StorageError::CheckpointError(e.to_string())
+    }
+
+    fn open_engine(dir: &Path) -> StorageResult<BackupEngine> {
+        std::fs::create_dir_all(dir)?;
+        let opts = BackupEngineOptions::new(dir).map_err(rocks_err)?;
+        let env = Env::new().map_err(rocks_err)?;
+        BackupEngine::open(&opts, &env).map_err(rocks_err)
+    }
+
+    /// Back up `db` into the engine at `dir` and verify the new backup.
+    fn backup_database(db: &RocksDB, name: &str, dir: &Path) -> StorageResult<DatabaseBackup> {
+        let mut engine = open_engine(dir)?;
+        engine
+            .create_new_backup_flush(db, true)
+            .map_err(rocks_err)?;
+        let info = engine
+            .get_backup_info()
+            .into_iter()
+            .max_by_key(|i| i.backup_id)
+            .ok_or_else(|| StorageError::CheckpointError(format!("no backup of {name}")))?;
+        engine.verify_backup(info.backup_id).map_err(rocks_err)?;
+        Ok(DatabaseBackup {
+            name: name.to_string(),
+            backup_id: info.backup_id,
+            size: info.size,
+        })
+    }
+
+    /// Node backups of the RocksDB databases under a storage path.
+    pub struct RocksDbBackups {
+        block_store: Arc<RocksDbBlockStore>,
+        storage_path: PathBuf,
+        backup_dir: PathBuf,
+        /// Databases the node holds open besides the block store.
+        sources: Vec<(String, Arc<dyn BackupSource>)>,
+        /// One backup at a time; the catalog is read-modify-write.
+        lock: Mutex<()>,
+    }
+
+    impl RocksDbBackups {
+        pub fn new(
+            block_store: Arc<RocksDbBlockStore>,
+            storage_path: impl Into<PathBuf>,
+            backup_dir: impl Into<PathBuf>,
+        ) -> Self {
+            Self {
+                block_store,
+                storage_path: storage_path.into(),
+                backup_dir: backup_dir.into(),
+                sources: Vec::new(),
+                lock: Mutex::new(()),
+            }
+        }
+
+        /// Include a database the node holds open, e.g. the Raft log as
+        /// `raft`. `name` is its path relative to the storage path.
+        pub fn with_source(
+            mut self,
+            name: impl Into<String>,
+            source: Arc<dyn BackupSource>,
+        ) -> Self {
+            self.sources.push((name.into(), source));
+            self
+        }
+
+        /// Channel stores on disk that no source covers. They are not held
+        /// open by the node, so they are opened for the backup.
+        fn channel_stores(&self) -> StorageResult<Vec<String>> {
+            let dir = self.storage_path.join("channels");
+            let mut names = Vec::new();
+            if dir.is_dir() {
+                for entry in std::fs::read_dir(&dir)?.flatten() {
+                    if !entry.path().is_dir() {
+                        continue;
+                    }
+                    if let Some(id) = entry.file_name().to_str() {
+                        let name = format!("channels/{id}");
+                        if !self.sources.iter().any(|(n, _)| *n == name) {
+                            names.push(name);
+                        }
+                    }
+                }
+            }
+            names.sort();
+            Ok(names)
+        }
+    }
+
+    impl BackupProvider for RocksDbBackups {
+        fn create_backup(&self) -> StorageResult<NodeBackup> {
+            let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+            let mut catalog = BackupCatalog::load(&self.backup_dir)?;
+
+            let schema_version = migrations::current_version(&self.block_store)?;
+            let latest_height = self.block_store.get_latest_height()?;
+            let mut databases = vec![backup_database(
+                &self.block_store.db,
+                BLOCK_STORE,
+                &self.backup_dir.join(BLOCK_STORE),
+            )?];
+            for (name, source) in &self.sources {
+                databases.push(backup_database(
+                    source.database(),
+                    name,
+                    &self.backup_dir.join(name),
+                )?);
+            }
+            for name in self.channel_stores()? {
+                let store = RocksDbBlockStore::new(database_dir(&self.storage_path, &name))?;
+                databases.push(backup_database(
+                    &store.db,
+                    &name,
+                    &self.backup_dir.join(&name),
+                )?);
+            }
+
+            let backup = NodeBackup {
+                id: catalog.next_id(),
+                created_at: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+                schema_version,
+                latest_height,
+                databases,
+            };
+            catalog.backups.push(backup.clone());
+            catalog.save(&self.backup_dir)?;
+            log::info!(
+                "Node backup {} taken at height {latest_height} ({} databases)",
+                backup.id,
+                backup.databases.len()
+            );
+            Ok(backup)
+        }
+
+        fn list_backups(&self) -> StorageResult<Vec<NodeBackup>> {
+            Ok(BackupCatalog::load(&self.backup_dir)?.backups)
+        }
+    }
+
+    /// Restore node backup `id` from `backup_dir` into `storage_path`.
+    ///
+    /// The node must be stopped. Nothing under `storage_path` changes unless
+    /// the restored block store has this build's schema version (or one
+    /// the startup migrations upgrade) matching the backup, and a hash chain
+    /// from genesis up to the backed-up height.
+    pub fn restore_backup(
+        backup_dir: &Path,
+        id: u64,
+        storage_path: &Path,
+    ) -> StorageResult<RestoreReport> {
+        let catalog = BackupCatalog::load(backup_dir)?;
+        let backup = catalog
+            .get(id)
+            .cloned()
+            .ok_or_else(|| StorageError::KeyNotFound(format!("backup {id}")))?;
+
+        let staging = sibling(storage_path, &format!("restore-{id}"));
+        if staging.exists() {
+            std::fs::remove_dir_all(&staging)?;
+        }
+        std::fs::create_dir_all(&staging)?;
+
+        // The block store first: restoring a database clears its directory
+        // of database files, and the others live below it.
+        let mut databases = backup.databases.clone();
+        databases.sort_by_key(|db| db.name != BLOCK_STORE);
+        for db in &databases {
+            let target = database_dir(&staging, &db.name);
+            std::fs::create_dir_all(&target)?;
+            let mut engine = open_engine(&backup_dir.join(&db.name))?;
+            engine
+                .restore_from_backup(&target, &target, &RestoreOptions::default(), db.backup_id)
+                .map_err(rocks_err)?;
+        }
+
+        let chain = match verify_restored(&staging, &backup) {
+            Ok(chain) => chain,
+            Err(e) => {
+                let _ = std::fs::remove_dir_all(&staging);
+                return Err(e);
+            }
+        };
+
+        let previous_data = if storage_path.exists() {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            let aside = sibling(storage_path, &format!("pre-restore-{now}"));
+            std::fs::rename(storage_path, &aside)?;
+            Some(aside)
+        } else {
+            None
+        };
+        std::fs::rename(&staging, storage_path)?;
+
+        Ok(RestoreReport {
+            backup,
+            chain,
+            previous_data,
+        })
+    }
+
+    /// Check the block store restored under `dir` against `backup`.
+    fn verify_restored(
+        dir: &Path,
+        backup: &NodeBackup,
+    ) -> StorageResult<crate::storage::integrity::ChainSummary> {
+        let store = RocksDbBlockStore::new(dir)?;
+        let version = migrations::current_version(&store)?;
+        if version > migrations::LATEST_VERSION || version != backup.schema_version {
+            return Err(StorageError::SchemaMismatch {
+                expected: backup.schema_version.min(migrations::LATEST_VERSION),
+                actual: version,
+            });
+        }
+        let chain = verify_hash_chain(&store)?;
+        let height = chain.latest_height.unwrap_or(0);
+        if height != backup.latest_height {
+            return Err(StorageError::BlockHeightMismatch {
+                expected: backup.latest_height,
+                actual: height,
+            });
+        }
+        Ok(chain)
+    }
+
+    /// `{path}.{suffix}` next to `path`.
+    fn sibling(path: &Path, suffix: &str) -> PathBuf {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(format!(".{suffix}"));
+        path.with_file_name(name)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::storage::traits::Block;
+
+        fn block(height: u64, parent_hash: [u8; 32]) -> Block {
+            Block {
+                height,
+                timestamp: height,
+                parent_hash,
+                merkle_root: [height as u8; 32],
+                transactions: vec![],
+                proposer: "orderer".to_string(),
+                signature: vec![0u8; 64],
+                signature_algorithm: Default::default(),
+                endorsements: vec![],
+                secondary_signature: None,
+                secondary_signature_algorithm: None,
+                hash_algorithm: Default::default(),
+                orderer_signature: None,
+                commit_qc: None,
+                next_validator_set: None,
+                evidence: vec![],
+                config_tx: None,
+                last_config: 0,
+                state_root: [0u8; 32],
+                private_data_purges: vec![],
+            }
+        }
+
+        fn write_chain(store: &RocksDbBlockStore, from: u64, to: u64) {
+            let mut parent = if from == 0 {
+                [0u8; 32]
+            } else {
+                crate::ordering::block_hash_for_signing(&store.read_block(from - 1).unwrap())
+            };
+            for h in from..=to {
+                let b = block(h, parent);
+                parent = crate::ordering::block_hash_for_signing(&b);
+                store.write_block(&b).unwrap();
+            }
+        }
+
+        #[test]
+        fn restore_returns_to_the_chosen_backup() {
+            let tmp = tempfile::TempDir::new().unwrap();
+            let storage = tmp.path().join("blocks");
+            let backups_dir = tmp.path().join("backups");
+
+            let store = Arc::new(RocksDbBlockStore::new(&storage).unwrap());
+            migrations::run_pending(&store).unwrap();
+            RocksDbBlockStore::create_channel_store("ch1", &storage).unwrap();
+            let backups = RocksDbBackups::new(store.clone(), &storage, &backups_dir);
+
+            write_chain(&store, 0, 2);
+            let first = backups.create_backup().unwrap();
+            write_chain(&store, 3, 5);
+            let second = backups.create_backup().unwrap();
+            assert_eq!((first.latest_height, second.latest_height), (2, 5));
+            assert!(first.databases.iter().any(|d| d.name == "channels/ch1"));
+            assert_eq!(backups.list_backups().unwrap().len(), 2);
+            drop(backups);
+            drop(store);
+
+            let report = restore_backup(&backups_dir, first.id, &storage).unwrap();
+            assert_eq!(report.chain.latest_height, Some(2));
+            assert!(report.previous_data.unwrap().exists());
+
+            let restored = RocksDbBlockStore::new(&storage).unwrap();
+            assert_eq!(restored.get_latest_height().unwrap(), 2);
+            assert!(!restored.block_exists(3).unwrap());
+            assert!(storage.join("channels").join("ch1").exists());
+        }
+
+        #[test]
+        fn unknown_backup_id_leaves_the_data_alone() {
+            let tmp = tempfile::TempDir::new().unwrap();
+            let storage = tmp.path().join("blocks");
+            let store = RocksDbBlockStore::new(&storage).unwrap();
+            write_chain(&store, 0, 1);
+            drop(store);
+
+            assert!(restore_backup(&tmp.path().join("backups"), 7, &storage).is_err());
+            let store = RocksDbBlockStore::new(&storage).unwrap();
+            assert_eq!(store.get_latest_height().unwrap(), 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node_backup(id: u64) -> NodeBackup {
+        NodeBackup {
+            id,
+            created_at: id * 100,
+            schema_version: 3,
+            latest_height: id * 10,
+            databases: vec![DatabaseBackup {
+                name: BLOCK_STORE.to_string(),
+                backup_id: id as u32,
+                size: 1024,
+            }],
+        }
+    }
+
+    #[test]
+    fn catalog_round_trips_and_numbers_backups() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut catalog = BackupCatalog::load(tmp.path()).unwrap();
+        assert_eq!(catalog.next_id(), 1);
+
+        catalog.backups.push(node_backup(1));
+        catalog.backups.push(node_backup(2));
+        catalog.save(tmp.path()).unwrap();
+
+        let loaded = BackupCatalog::load(tmp.path()).unwrap();
+        assert_eq!(loaded, catalog);
+        assert_eq!(loaded.next_id(), 3);
+        assert_eq!(loaded.get(2).unwrap().latest_height, 20);
+        assert!(loaded.get(9).is_none());
+    }
+
+    #[test]
+    fn database_dirs_nest_under_the_storage_path() {
+        let base = Path::new("/data/blocks");
+        assert_eq!(database_dir(base, BLOCK_STORE), base);
+        assert_eq!(database_dir(base, "raft"), base.join("raft"));
+        assert_eq!(
+            database_dir(base, "channels/ch1"),
+            base.join("channels").join("ch1")
+        );
+    }
+}
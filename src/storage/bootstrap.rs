@@ -0,0 +1,693 @@
+//! Chunked state snapshots for bootstrapping a peer at a block height.
+//!
+//! A bootstrap snapshot captures the world state after block `H` so a new
+//! peer can install it and sync blocks from `H + 1` instead of replaying the
+//! whole ledger. On disk, under `{base_dir}/snapshots/{channel_id}/bootstrap/{H}/`:
+//!
+//! - `chunk-{index:06}.bin` — gzip-compressed bincode of a key-ordered run
+//!   of `(key, VersionedValue)` entries.
+//! - `manifest.json` — [`SnapshotManifest`]: the header of block `H`, the
+//!   blocks config resolution needs, the SHA-256 of every chunk, and the
+//!   org signatures over [`SnapshotManifest::snapshot_hash`].
+//!
+//! A joining peer checks the org signatures against its own policy, checks
+//! each chunk against its manifest hash, and only installs the state when
+//! the rebuilt [`StateTree`] root equals the `state_root` of block `H`.
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use pqc_crypto_module::legacy::sha256::{Digest, Sha256};
+use serde::{Deserialize, Serialize};
+
+use super::archive::block_header;
+use super::errors::{StorageError, StorageResult};
+use super::state_tree::{AuthenticatedWorldState, StateTree};
+use super::traits::{Block, BlockStore};
+use super::world_state::{VersionedValue, WorldState};
+use crate::endorsement::policy::EndorsementPolicy;
+use crate::endorsement::registry::OrgRegistry;
+use crate::endorsement::types::Endorsement;
+use crate::endorsement::validator::{validate_endorsements, EndorsementError};
+use crate::msp::CrlStore;
+
+/// Version of the chunk and manifest layout.
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// Uncompressed size a chunk is cut at.
+pub const CHUNK_TARGET_BYTES: usize = 512 * 1024;
+
+/// Domain separator of the snapshot hash.
+const SNAPSHOT_DOMAIN: &[u8] = b"rust-bc/bootstrap-snapshot/v1";
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// One chunk of a snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkInfo {
+    pub index: u32,
+    /// SHA-256 of the compressed chunk.
+    pub hash: [u8; 32],
+    pub entry_count: u64,
+    /// Compressed size in bytes.
+    pub size: u64,
+}
+
+/// Description of a bootstrap snapshot at one block height.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub format_version: u32,
+    pub channel_id: String,
+    pub height: u64,
+    /// Header of block `height`; its `state_root` authenticates the chunks.
+    pub header: Block,
+    /// Headers [`crate::channel::ledger::config_history`] reads below
+    /// `height`: the genesis block, each config block and the block before it.
+    pub config_blocks: Vec<Block>,
+    pub chunks: Vec<ChunkInfo>,
+    pub entry_count: u64,
+    pub created_at: u64,
+    /// Org signatures over [`SnapshotManifest::snapshot_hash`].
+    #[serde(default)]
+    pub signatures: Vec<Endorsement>,
+}
+
+impl SnapshotManifest {
+    /// Hash the org signatures cover: everything but the signatures and the
+    /// creation time.
+    pub fn snapshot_hash(&self) -> [u8; 32] {
+        let mut h = Sha256::new();
+        h.update(SNAPSHOT_DOMAIN);
+        h.update(self.format_version.to_le_bytes());
+        h.update((self.channel_id.len() as u64).to_le_bytes());
+        h.update(self.channel_id.as_bytes());
+        h.update(self.height.to_le_bytes());
+        h.update(block_digest(&self.header));
+        h.update((self.config_blocks.len() as u64).to_le_bytes());
+        for block in &self.config_blocks {
+            h.update(block_digest(block));
+        }
+        h.update((self.chunks.len() as u64).to_le_bytes());
+        for chunk in &self.chunks {
+            h.update(chunk.index.to_le_bytes());
+            h.update(chunk.hash);
+            h.update(chunk.entry_count.to_le_bytes());
+            h.update(chunk.size.to_le_bytes());
+        }
+        h.update(self.entry_count.to_le_bytes());
+        h.finalize().into()
+    }
+
+    /// Check the manifest is internally consistent: supported format, a
+    /// header at `height`, config blocks below it and contiguous chunks.
+    pub fn verify(&self) -> StorageResult<()> {
+        let invalid = |reason: String| StorageError::DataCorrupted(format!("snapshot: {reason}"));
+        if self.format_version != SNAPSHOT_FORMAT_VERSION {
+            return Err(invalid(format!(
+                "unsupported format version {}",
+                self.format_version
+            )));
+        }
+        if self.header.height != self.height {
+            return Err(invalid(format!(
+                "header is block {}, not {}",
+                self.header.height, self.height
+            )));
+        }
+        if self.config_blocks.iter().any(|b| b.height >= self.height) {
+            return Err(invalid(
+                "config block at or above the snapshot height".into(),
+            ));
+        }
+        if self.header.last_config != self.height
+            && self.header.last_config > 0
+            && !self
+                .config_blocks
+                .iter()
+                .any(|b| b.height == self.header.last_config)
+        {
+            return Err(invalid(format!(
+                "config block {} is missing",
+                self.header.last_config
+            )));
+        }
+        if self
+            .chunks
+            .iter()
+            .enumerate()
+            .any(|(i, c)| c.index as usize != i)
+        {
+            return Err(invalid("chunk indexes are not contiguous".into()));
+        }
+        if self.chunks.iter().map(|c| c.entry_count).sum::<u64>() != self.entry_count {
+            return Err(invalid("chunk entry counts do not add up".into()));
+        }
+        Ok(())
+    }
+
+    /// Check the org signatures satisfy `policy`. Signatures over another
+    /// hash do not count.
+    pub fn check_signatures(
+        &self,
+        policy: &EndorsementPolicy,
+        registry: &dyn OrgRegistry,
+        crl_store: Option<&dyn CrlStore>,
+    ) -> Result<(), EndorsementError> {
+        let hash = self.snapshot_hash();
+        let signatures: Vec<Endorsement> = self
+            .signatures
+            .iter()
+            .filter(|e| e.payload_hash == hash)
+            .cloned()
+            .collect();
+        validate_endorsements(&signatures, policy, registry, crl_store)
+    }
+}
+
+/// SHA-256 of the JSON encoding of `block`, covering every field rather
+/// than only those in the block signing hash.
+fn block_digest(block: &Block) -> [u8; 32] {
+    Sha256::digest(serde_json::to_vec(block).unwrap_or_default()).into()
+}
+
+/// Directory holding the bootstrap snapshots of `channel_id`. The channel ID
+/// may come from a peer, so it is validated before it becomes a path.
+fn channel_snapshots_dir(base_dir: &Path, channel_id: &str) -> StorageResult<PathBuf> {
+    super::validate_channel_id(channel_id)?;
+    Ok(base_dir
+        .join("snapshots")
+        .join(channel_id)
+        .join("bootstrap"))
+}
+
+/// Directory holding the bootstrap snapshot of `channel_id` at `height`.
+pub fn snapshot_dir(base_dir: &Path, channel_id: &str, height: u64) -> StorageResult<PathBuf> {
+    Ok(channel_snapshots_dir(base_dir, channel_id)?.join(height.to_string()))
+}
+
+/// Take a bootstrap snapshot of `state` at the tip of `store`.
+///
+/// Fails when the state read does not hash to the `state_root` of the tip
+/// block — a block committed while the snapshot was being taken, or a tip
+/// committed without an authenticated world state. Retry in the first case.
+pub fn create_bootstrap_snapshot(
+    store: &dyn BlockStore,
+    state: &AuthenticatedWorldState,
+    channel_id: &str,
+    base_dir: &Path,
+) -> StorageResult<SnapshotManifest> {
+    let height = store.get_latest_height()?;
+    let tip = store.read_block(height)?;
+
+    let entries = state.get_range("", "\u{FFFF}")?;
+    let root = entries
+        .iter()
+        .fold(StateTree::new(), |tree, (key, vv)| {
+            tree.insert(key, &vv.data)
+        })
+        .root();
+    if root != tip.state_root {
+        return Err(StorageError::Other(format!(
+            "world state does not match the state root of block {height}"
+        )));
+    }
+
+    let dir = snapshot_dir(base_dir, channel_id, height)?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| StorageError::Other(format!("failed to create snapshot dir: {e}")))?;
+
+    let mut chunks = Vec::new();
+    let mut batch: Vec<(String, VersionedValue)> = Vec::new();
+    let mut batch_bytes = 0usize;
+    for (key, vv) in &entries {
+        batch_bytes += key.len() + vv.data.len() + 16;
+        batch.push((key.clone(), vv.clone()));
+        if batch_bytes >= CHUNK_TARGET_BYTES {
+            chunks.push(write_chunk(&dir, chunks.len() as u32, &batch)?);
+            batch.clear();
+            batch_bytes = 0;
+        }
+    }
+    if !batch.is_empty() {
+        chunks.push(write_chunk(&dir, chunks.len() as u32, &batch)?);
+    }
+
+    let manifest = SnapshotManifest {
+        format_version: SNAPSHOT_FORMAT_VERSION,
+        channel_id: channel_id.to_string(),
+        height,
+        header: block_header(&tip),
+        config_blocks: config_blocks_below(store, &tip)?,
+        chunks,
+        entry_count: entries.len() as u64,
+        created_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        signatures: Vec::new(),
+    };
+    save_manifest(base_dir, &manifest)?;
+    Ok(manifest)
+}
+
+/// Headers of the blocks `config_history` reads below `tip`, lowest first.
+fn config_blocks_below(store: &dyn BlockStore, tip: &Block) -> StorageResult<Vec<Block>> {
+    let mut heights = std::collections::BTreeSet::new();
+    if tip.height > 0 {
+        heights.insert(0);
+    }
+    let mut pointer = tip.last_config;
+    while pointer > 0 {
+        if pointer < tip.height {
+            heights.insert(pointer);
+        }
+        if pointer - 1 < tip.height {
+            heights.insert(pointer - 1);
+        }
+        let previous = store.read_block(pointer - 1)?.last_config;
+        if previous >= pointer {
+            break;
+        }
+        pointer = previous;
+    }
+    heights
+        .into_iter()
+        .map(|h| store.read_block(h).map(|b| block_header(&b)))
+        .collect()
+}
+
+fn write_chunk(
+    dir: &Path,
+    index: u32,
+    entries: &[(String, VersionedValue)],
+) -> StorageResult<ChunkInfo> {
+    let encoded =
+        bincode::serialize(entries).map_err(|e| StorageError::SerializationError(e.to_string()))?;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(&encoded)
+        .map_err(|e| StorageError::Other(format!("chunk compression failed: {e}")))?;
+    let compressed = encoder
+        .finish()
+        .map_err(|e| StorageError::Other(format!("chunk compression failed: {e}")))?;
+    std::fs::write(dir.join(chunk_file(index)), &compressed)
+        .map_err(|e| StorageError::Other(format!("write error: {e}")))?;
+    Ok(ChunkInfo {
+        index,
+        hash: Sha256::digest(&compressed).into(),
+        entry_count: entries.len() as u64,
+        size: compressed.len() as u64,
+    })
+}
+
+fn chunk_file(index: u32) -> String {
+    format!("chunk-{index:06}.bin")
+}
+
+/// Write `manifest` next to its chunks.
+pub fn save_manifest(base_dir: &Path, manifest: &SnapshotManifest) -> StorageResult<()> {
+    let dir = snapshot_dir(base_dir, &manifest.channel_id, manifest.height)?;
+    let bytes = serde_json::to_vec_pretty(manifest)
+        .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+    std::fs::write(dir.join(MANIFEST_FILE), bytes)
+        .map_err(|e| StorageError::Other(format!("write error: {e}")))
+}
+
+/// Manifest of the snapshot of `channel_id` at `height`.
+pub fn load_manifest(
+    base_dir: &Path,
+    channel_id: &str,
+    height: u64,
+) -> StorageResult<SnapshotManifest> {
+    let path = snapshot_dir(base_dir, channel_id, height)?.join(MANIFEST_FILE);
+    let bytes = std::fs::read(&path)
+        .map_err(|_| StorageError::KeyNotFound(format!("snapshot {channel_id}@{height}")))?;
+    serde_json::from_slice(&bytes).map_err(|e| StorageError::DeserializationError(e.to_string()))
+}
+
+/// Manifest of the highest snapshot of `channel_id`, if any.
+pub fn latest_manifest(base_dir: &Path, channel_id: &str) -> Option<SnapshotManifest> {
+    let dir = channel_snapshots_dir(base_dir, channel_id).ok()?;
+    let height = std::fs::read_dir(&dir)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u64>().ok())
+        .filter(|h| dir.join(h.to_string()).join(MANIFEST_FILE).exists())
+        .max()?;
+    load_manifest(base_dir, channel_id, height).ok()
+}
+
+/// Compressed bytes of chunk `index` of the snapshot at `height`.
+pub fn read_chunk(
+    base_dir: &Path,
+    channel_id: &str,
+    height: u64,
+    index: u32,
+) -> StorageResult<Vec<u8>> {
+    std::fs::read(snapshot_dir(base_dir, channel_id, height)?.join(chunk_file(index)))
+        .map_err(|_| StorageError::KeyNotFound(format!("snapshot chunk {index}")))
+}
+
+/// Decode chunk `index` of `manifest`, checking it against its hash.
+pub fn decode_chunk(
+    manifest: &SnapshotManifest,
+    index: u32,
+    bytes: &[u8],
+) -> StorageResult<Vec<(String, VersionedValue)>> {
+    let info = manifest
+        .chunks
+        .get(index as usize)
+        .ok_or_else(|| StorageError::KeyNotFound(format!("snapshot chunk {index}")))?;
+    let hash: [u8; 32] = Sha256::digest(bytes).into();
+    if hash != info.hash {
+        return Err(StorageError::DataCorrupted(format!(
+            "snapshot chunk {index} does not match its manifest hash"
+        )));
+    }
+    let mut encoded = Vec::new();
+    GzDecoder::new(bytes)
+        .read_to_end(&mut encoded)
+        .map_err(|e| StorageError::DataCorrupted(format!("snapshot chunk {index}: {e}")))?;
+    let entries: Vec<(String, VersionedValue)> = bincode::deserialize(&encoded)
+        .map_err(|e| StorageError::DeserializationError(e.to_string()))?;
+    if entries.len() as u64 != info.entry_count {
+        return Err(StorageError::DataCorrupted(format!(
+            "snapshot chunk {index} holds {} entries, manifest says {}",
+            entries.len(),
+            info.entry_count
+        )));
+    }
+    Ok(entries)
+}
+
+/// Install a verified snapshot on an empty peer.
+///
+/// `chunks[i]` holds the compressed bytes of chunk `i`. The state is
+/// written only once the rebuilt tree matches the header's `state_root`;
+/// then the tree is committed at the snapshot height and the config blocks
+/// and the header are written, so block sync resumes at `height + 1`.
+pub fn install_snapshot(
+    manifest: &SnapshotManifest,
+    chunks: &[Vec<u8>],
+    store: &dyn BlockStore,
+    state: &AuthenticatedWorldState,
+) -> StorageResult<()> {
+    manifest.verify()?;
+    if store.block_exists(0)? || store.get_latest_height()? > 0 {
+        return Err(StorageError::Other(
+            "snapshot bootstrap needs an empty ledger".to_string(),
+        ));
+    }
+    if chunks.len() != manifest.chunks.len() {
+        return Err(StorageError::Other(format!(
+            "snapshot has {} chunks, got {}",
+            manifest.chunks.len(),
+            chunks.len()
+        )));
+    }
+
+    let mut entries = Vec::with_capacity(manifest.entry_count as usize);
+    for (index, bytes) in chunks.iter().enumerate() {
+        entries.extend(decode_chunk(manifest, index as u32, bytes)?);
+    }
+    let root = entries
+        .iter()
+        .fold(StateTree::new(), |tree, (key, vv)| {
+            tree.insert(key, &vv.data)
+        })
+        .root();
+    if root != manifest.header.state_root {
+        return Err(StorageError::DataCorrupted(format!(
+            "snapshot state does not match the state root of block {}",
+            manifest.height
+        )));
+    }
+
+    for (key, vv) in &entries {
+        state.restore_entry(key, vv)?;
+    }
+    state.commit(manifest.height);
+    for block in &manifest.config_blocks {
+        store.write_block(block)?;
+    }
+    store.write_block(&manifest.header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::endorsement::org::Organization;
+    use crate::endorsement::registry::MemoryOrgRegistry;
+    use crate::storage::memory::MemoryStore;
+    use crate::storage::MemoryWorldState;
+    use pqc_crypto_module::legacy::ed25519::{Signer, SigningKey};
+    use std::sync::Arc;
+
+    fn block(height: u64, state_root: [u8; 32]) -> Block {
+        Block {
+            height,
+            timestamp: height,
+            parent_hash: [0u8; 32],
+            merkle_root: [0u8; 32],
+            transactions: vec![format!("tx-{height}")],
+            proposer: "orderer".to_string(),
+            signature: vec![0u8; 64],
+            signature_algorithm: Default::default(),
+            endorsements: vec![],
+            secondary_signature: None,
+            secondary_signature_algorithm: None,
+            hash_algorithm: Default::default(),
+            orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: vec![],
+            config_tx: None,
+            last_config: 0,
+            state_root,
+            private_data_purges: vec![],
+        }
+    }
+
+    /// Ledger of `height + 1` blocks whose tip commits `keys` entries.
+    fn source(keys: usize, height: u64) -> (MemoryStore, AuthenticatedWorldState) {
+        let store = MemoryStore::new();
+        let state = AuthenticatedWorldState::new(Arc::new(MemoryWorldState::new()), 0).unwrap();
+        for h in 0..height {
+            store.write_block(&block(h, state.commit(h))).unwrap();
+        }
+        for i in 0..keys {
+            state.put(&format!("asset:{i:05}"), &[i as u8; 64]).unwrap();
+        }
+        state.put("asset:00000", b"updated").unwrap();
+        store
+            .write_block(&block(height, state.commit(height)))
+            .unwrap();
+        (store, state)
+    }
+
+    fn empty_peer() -> (MemoryStore, AuthenticatedWorldState) {
+        (
+            MemoryStore::new(),
+            AuthenticatedWorldState::new(Arc::new(MemoryWorldState::new()), 0).unwrap(),
+        )
+    }
+
+    fn chunks_of(dir: &Path, manifest: &SnapshotManifest) -> Vec<Vec<u8>> {
+        (0..manifest.chunks.len() as u32)
+            .map(|i| read_chunk(dir, &manifest.channel_id, manifest.height, i).unwrap())
+            .collect()
+    }
+
+    fn org_signature(key: &SigningKey, org_id: &str, hash: [u8; 32]) -> Endorsement {
+        Endorsement {
+            signer_did: format!("did:bc:{org_id}-admin"),
+            org_id: org_id.to_string(),
+            signature: key.sign(&hash).to_bytes().to_vec(),
+            signature_algorithm: Default::default(),
+            payload_hash: hash,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn snapshot_round_trips_onto_an_empty_peer() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let (store, state) = source(20_000, 3);
+        let manifest = create_bootstrap_snapshot(&store, &state, "ch1", tmp.path()).unwrap();
+        assert!(manifest.chunks.len() > 1, "expected several chunks");
+        assert_eq!(manifest.entry_count, 20_000);
+        assert_eq!(
+            load_manifest(tmp.path(), "ch1", 3).unwrap().snapshot_hash(),
+            manifest.snapshot_hash()
+        );
+
+        let (peer_store, peer_state) = empty_peer();
+        install_snapshot(
+            &manifest,
+            &chunks_of(tmp.path(), &manifest),
+            &peer_store,
+            &peer_state,
+        )
+        .unwrap();
+
+        assert_eq!(peer_state.root_at(3), Some(manifest.header.state_root));
+        let vv = peer_state.get("asset:00000").unwrap().unwrap();
+        assert_eq!((vv.version, vv.data.as_slice()), (2, &b"updated"[..]));
+        assert_eq!(peer_store.get_latest_height().unwrap(), 3);
+        assert!(peer_store.block_exists(0).unwrap());
+        assert!(peer_store.read_block(3).unwrap().transactions.is_empty());
+    }
+
+    #[test]
+    fn tampered_chunk_is_rejected_before_any_state_is_written() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let (store, state) = source(10, 2);
+        let manifest = create_bootstrap_snapshot(&store, &state, "ch1", tmp.path()).unwrap();
+        let mut chunks = chunks_of(tmp.path(), &manifest);
+        chunks[0][10] ^= 0xff;
+
+        let (peer_store, peer_state) = empty_peer();
+        let err = install_snapshot(&manifest, &chunks, &peer_store, &peer_state).unwrap_err();
+        assert!(matches!(err, StorageError::DataCorrupted(_)));
+        assert!(peer_state.get("asset:00001").unwrap().is_none());
+        assert!(!peer_store.block_exists(2).unwrap());
+    }
+
+    #[test]
+    fn state_not_matching_the_header_is_rejected() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let (store, state) = source(10, 2);
+        let mut manifest = create_bootstrap_snapshot(&store, &state, "ch1", tmp.path()).unwrap();
+        manifest.header.state_root = [7u8; 32];
+
+        let (peer_store, peer_state) = empty_peer();
+        let chunks = chunks_of(tmp.path(), &manifest);
+        assert!(install_snapshot(&manifest, &chunks, &peer_store, &peer_state).is_err());
+        assert!(peer_state.get("asset:00001").unwrap().is_none());
+    }
+
+    #[test]
+    fn snapshot_of_a_moved_state_is_refused() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let (store, state) = source(10, 2);
+        state.put("late", b"write").unwrap();
+        assert!(create_bootstrap_snapshot(&store, &state, "ch1", tmp.path()).is_err());
+    }
+
+    #[test]
+    fn org_signatures_must_cover_the_snapshot_hash() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let (store, state) = source(10, 2);
+        let mut manifest = create_bootstrap_snapshot(&store, &state, "ch1", tmp.path()).unwrap();
+
+        let registry = MemoryOrgRegistry::new();
+        let keys: Vec<SigningKey> = (0..2u8)
+            .map(|i| SigningKey::from_bytes(&[i + 1; 32]))
+            .collect();
+        for (i, key) in keys.iter().enumerate() {
+            let org = Organization::new(
+                format!("org{i}"),
+                format!("Org{i}MSP"),
+                vec![format!("did:bc:org{i}-admin")],
+                vec![],
+                vec![key.verifying_key().to_bytes()],
+            )
+            .unwrap();
+            registry.register_org(&org).unwrap();
+        }
+        let policy = EndorsementPolicy::NOutOf {
+            n: 2,
+            orgs: vec!["org0".to_string(), "org1".to_string()],
+        };
+
+        let hash = manifest.snapshot_hash();
+        manifest
+            .signatures
+            .push(org_signature(&keys[0], "org0", hash));
+        manifest
+            .signatures
+            .push(org_signature(&keys[1], "org1", [9u8; 32]));
+        assert!(manifest.check_signatures(&policy, &registry, None).is_err());
+
+        manifest.signatures[1] = org_signature(&keys[1], "org1", hash);
+        manifest.check_signatures(&policy, &registry, None).unwrap();
+
+        // Signatures do not feed into the hash they sign.
+        assert_eq!(manifest.snapshot_hash(), hash);
+    }
+
+    #[test]
+    fn config_blocks_cover_what_config_history_reads() {
+        let store = MemoryStore::new();
+        let genesis = crate::channel::genesis::create_genesis_block(
+            "ch1",
+            &crate::channel::config::ChannelConfig::default(),
+        );
+        store.write_block(&genesis).unwrap();
+        store.write_block(&block(1, [0u8; 32])).unwrap();
+        let tx = crate::channel::config::ConfigTransaction {
+            tx_id: "cfg".to_string(),
+            channel_id: "ch1".to_string(),
+            updates: vec![],
+            signatures: vec![],
+            created_at: 0,
+        };
+        let mut config = crate::channel::ledger::config_block(2, "orderer", tx);
+        config.last_config = 2;
+        store.write_block(&config).unwrap();
+        let mut tip = block(3, [0u8; 32]);
+        tip.last_config = 2;
+        store.write_block(&tip).unwrap();
+
+        let heights: Vec<u64> = config_blocks_below(&store, &tip)
+            .unwrap()
+            .iter()
+            .map(|b| b.height)
+            .collect();
+        assert_eq!(heights, vec![0, 1, 2]);
+
+        // A peer holding only those blocks and the tip resolves the config.
+        let peer = MemoryStore::new();
+        for b in config_blocks_below(&store, &tip).unwrap() {
+            peer.write_block(&b).unwrap();
+        }
+        peer.write_block(&block_header(&tip)).unwrap();
+        assert_eq!(
+            crate::channel::ledger::config_history(&peer).unwrap().len(),
+            2
+        );
+    }
+
+    #[test]
+    fn unsupported_format_version_is_rejected() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let (store, state) = source(1, 1);
+        let mut manifest = create_bootstrap_snapshot(&store, &state, "ch1", tmp.path()).unwrap();
+        manifest.format_version = 99;
+        assert!(manifest.verify().is_err());
+        assert_eq!(latest_manifest(tmp.path(), "ch1").unwrap().height, 1);
+    }
+
+    #[test]
+    fn channel_ids_from_peers_cannot_leave_the_snapshot_root() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let base = tmp.path().join("data");
+        std::fs::create_dir_all(tmp.path().join("bootstrap").join("1")).unwrap();
+        std::fs::write(
+            tmp.path().join("bootstrap").join("1").join(chunk_file(0)),
+            b"x",
+        )
+        .unwrap();
+        for channel_id in ["../..", "..", "ch1/../..", ""] {
+            assert!(matches!(
+                read_chunk(&base, channel_id, 1, 0),
+                Err(StorageError::InvalidChannelId(_))
+            ));
+            assert!(load_manifest(&base, channel_id, 1).is_err());
+            assert!(latest_manifest(&base, channel_id).is_none());
+        }
+    }
+}
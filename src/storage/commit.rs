@@ -0,0 +1,759 @@
+//! Atomic block commit for [`RocksDbBlockStore`].
+//!
+//! [`RocksDbBlockStore::commit_block`] writes a block, its transactions and
+//! their `tx_by_block` entries, the block's world-state writes with their
+//! `key_history` and `history_by_block` entries, a write-set record for the block, and the state
+//! savepoint in one `WriteBatch`. The savepoint is the height of the last
+//! block whose writes are in `world_state`; plain `write_block` advances it
+//! too, since those blocks carry no writes for this store.
+//!
+//! A crash therefore leaves all of a block or none of it. On startup
+//! [`RocksDbBlockStore::recover_state`] still compares the savepoint with the
+//! block height, to repair data written by older releases or copied mid-write:
+//!
+//! - blocks above the savepoint are replayed from their write-set record; from
+//!   the first one without a record they are removed, so sync fetches them
+//!   again;
+//! - a savepoint above the block height is rolled back with the previous
+//!   values kept in the write-set records.
+//!
+//! Every state write here, and the plain `WorldState` writes, also updates
+//! the secondary indexes (see [`super::index`]) in the same batch.
+//!
+//! `WorldState::commit_block_to` on the store lands here when the block
+//! store is the store itself, so the gateway commits through this batch.
+
+use std::collections::HashMap;
+
+use rocksdb::{Direction, IteratorMode, WriteBatch};
+use serde::{Deserialize, Serialize};
+
+use super::adapters::{
+    RocksDbBlockStore, CF_BLOCKS, CF_BLOCK_WRITES, CF_KEY_HISTORY, CF_TRANSACTIONS, CF_WORLD_STATE,
+    META_LATEST_HEIGHT, META_STATE_SAVEPOINT,
+};
+use super::errors::{StorageError, StorageResult};
+use super::index::IndexDefinition;
+use super::traits::{Block, BlockStore, HistoryEntry, Transaction};
+pub use super::world_state::StateWrite;
+use super::world_state::VersionedValue;
+
+/// A state write as applied, with the value it replaced.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppliedWrite {
+    pub key: String,
+    pub tx_id: String,
+    /// Version the write produced; a delete takes the next version too.
+    pub version: u64,
+    /// New value, `None` for a delete.
+    pub value: Option<VersionedValue>,
+    /// Value before the write, `None` if the key was absent.
+    pub previous: Option<VersionedValue>,
+}
+
+/// State writes of one block, kept to replay or roll them back.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockWriteSet {
+    pub height: u64,
+    pub timestamp: u64,
+    pub writes: Vec<AppliedWrite>,
+}
+
+/// What [`RocksDbBlockStore::recover_state`] found and repaired.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateRecovery {
+    /// Block height after recovery; `None` for an empty ledger.
+    pub block_height: Option<u64>,
+    /// Savepoint found on open; `None` for stores written before savepoints.
+    pub savepoint: Option<u64>,
+    /// Blocks whose write sets were re-applied.
+    pub replayed: u64,
+    /// Blocks removed because they had no write-set record.
+    pub truncated: u64,
+    /// Blocks whose writes were rolled back.
+    pub rolled_back: u64,
+}
+
+impl StateRecovery {
+    /// Whether state and blocks already agreed.
+    pub fn is_clean(&self) -> bool {
+        self.replayed == 0 && self.truncated == 0 && self.rolled_back == 0
+    }
+}
+
+impl RocksDbBlockStore {
+    /// Commit `block`, its transactions and its state writes atomically.
+    ///
+    /// Writes are applied in order, so later writes to a key within the
+    /// block see the version of the earlier ones.
+    pub fn commit_block(
+        &self,
+        block: &Block,
+        txs: &[Transaction],
+        writes: &[StateWrite],
+    ) -> StorageResult<()> {
+        let value = serde_json::to_vec(block)
+            .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+        let current_latest = self.get_latest_height().unwrap_or(0);
+        let indexes = self.lock_state_indexes();
+
+        let mut batch = WriteBatch::default();
+        let key = Self::block_key(block.height);
+        batch.put_cf(
+            &self.cf_blocks()?,
+            &key,
+            self.seal(CF_BLOCKS, &key, &value)?,
+        );
+        if block.height >= current_latest {
+            batch.put_cf(
+                &self.cf_meta()?,
+                META_LATEST_HEIGHT,
+                block.height.to_le_bytes(),
+            );
+        }
+
+        let cf_t = self.cf_transactions()?;
+        let cf_idx = self.cf_tx_by_block()?;
+        for tx in txs {
+            let value = serde_json::to_vec(tx)
+                .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+            batch.put_cf(
+                &cf_t,
+                tx.id.as_bytes(),
+                self.seal(CF_TRANSACTIONS, tx.id.as_bytes(), &value)?,
+            );
+            batch.put_cf(
+                &cf_idx,
+                Self::tx_block_index_key(tx.block_height, &tx.id),
+                b"",
+            );
+        }
+
+        if !writes.is_empty() {
+            let record = self.resolve_writes(block, writes)?;
+            self.stage_apply(&mut batch, &indexes, &record)?;
+            let encoded = serde_json::to_vec(&record)
+                .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+            batch.put_cf(
+                &self.cf_block_writes()?,
+                &key,
+                self.seal(CF_BLOCK_WRITES, &key, &encoded)?,
+            );
+        }
+        self.stage_savepoint(&mut batch, block.height)?;
+
+        self.db
+            .write(batch)
+            .map_err(|e| StorageError::RocksDbError(e.to_string()))
+    }
+
+    /// Height of the last block whose state writes are applied.
+    pub fn state_savepoint(&self) -> StorageResult<Option<u64>> {
+        self.read_meta_height(META_STATE_SAVEPOINT)
+    }
+
+    /// The state writes recorded for the block at `height`, if it had any.
+    pub fn block_write_set(&self, height: u64) -> StorageResult<Option<BlockWriteSet>> {
+        let key = Self::block_key(height);
+        match self
+            .db
+            .get_cf(&self.cf_block_writes()?, &key)
+            .map_err(|e| StorageError::RocksDbError(e.to_string()))?
+        {
+            Some(bytes) => serde_json::from_slice(&self.open(CF_BLOCK_WRITES, &key, &bytes)?)
+                .map(Some)
+                .map_err(|e| StorageError::DeserializationError(e.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    /// Bring the state savepoint and the block height back in line.
+    ///
+    /// Call once after opening the store, before it serves commits.
+    pub fn recover_state(&self) -> StorageResult<StateRecovery> {
+        let tip = self.read_meta_height(META_LATEST_HEIGHT)?;
+        let savepoint = self.state_savepoint()?;
+        let mut report = StateRecovery {
+            block_height: tip,
+            savepoint,
+            ..Default::default()
+        };
+
+        match (tip, savepoint) {
+            (None, None) => {}
+            (Some(tip), None) => {
+                // Written before savepoints existed: every block counts as applied.
+                self.db
+                    .put_cf(&self.cf_meta()?, META_STATE_SAVEPOINT, tip.to_le_bytes())
+                    .map_err(|e| StorageError::RocksDbError(e.to_string()))?;
+            }
+            (Some(tip), Some(state)) if tip > state => {
+                for height in state + 1..=tip {
+                    let Some(record) = self.block_write_set(height)? else {
+                        self.truncate_blocks(height, tip)?;
+                        report.truncated = tip - height + 1;
+                        report.block_height = Some(height - 1);
+                        break;
+                    };
+                    let indexes = self.lock_state_indexes();
+                    let mut batch = WriteBatch::default();
+                    self.stage_apply(&mut batch, &indexes, &record)?;
+                    batch.put_cf(&self.cf_meta()?, META_STATE_SAVEPOINT, height.to_le_bytes());
+                    self.db
+                        .write(batch)
+                        .map_err(|e| StorageError::RocksDbError(e.to_string()))?;
+                    report.replayed += 1;
+                }
+            }
+            (tip, Some(state)) if tip < Some(state) => {
+                let lowest = tip.map_or(0, |t| t + 1);
+                for height in (lowest..=state).rev() {
+                    self.roll_back_block(height)?;
+                    report.rolled_back += 1;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(report)
+    }
+
+    fn read_meta_height(&self, key: &[u8]) -> StorageResult<Option<u64>> {
+        match self
+            .db
+            .get_cf(&self.cf_meta()?, key)
+            .map_err(|e| StorageError::RocksDbError(e.to_string()))?
+        {
+            Some(bytes) => {
+                let arr: [u8; 8] = bytes.as_slice().try_into().map_err(|_| {
+                    StorageError::DataCorrupted(format!(
+                        "{} is not 8 bytes",
+                        String::from_utf8_lossy(key)
+                    ))
+                })?;
+                Ok(Some(u64::from_le_bytes(arr)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Stage the savepoint move to `height`; it never moves backwards.
+    pub(crate) fn stage_savepoint(&self, batch: &mut WriteBatch, height: u64) -> StorageResult<()> {
+        let advance = match self.state_savepoint()? {
+            Some(state) => height > state,
+            None => true,
+        };
+        if advance {
+            batch.put_cf(&self.cf_meta()?, META_STATE_SAVEPOINT, height.to_le_bytes());
+        }
+        Ok(())
+    }
+
+    /// Resolve the versions `writes` produce against the current state.
+    fn resolve_writes(&self, block: &Block, writes: &[StateWrite]) -> StorageResult<BlockWriteSet> {
+        let mut pending: HashMap<&str, Option<VersionedValue>> = HashMap::new();
+        let mut applied = Vec::with_capacity(writes.len());
+        for write in writes {
+            let previous = match pending.get(write.key.as_str()) {
+                Some(value) => value.clone(),
+                None => self.world_state_get(&write.key)?,
+            };
+            let version = previous.as_ref().map_or(1, |v| v.version + 1);
+            let value = write.value.as_ref().map(|data| VersionedValue {
+                version,
+                data: data.clone(),
+            });
+            pending.insert(&write.key, value.clone());
+            applied.push(AppliedWrite {
+                key: write.key.clone(),
+                tx_id: write.tx_id.clone(),
+                version,
+                value,
+                previous,
+            });
+        }
+        Ok(BlockWriteSet {
+            height: block.height,
+            timestamp: block.timestamp,
+            writes: applied,
+        })
+    }
+
+    /// Stage the state, history and index writes of `record`. Re-applying a
+    /// record is harmless: it puts the same values under the same keys.
+    fn stage_apply(
+        &self,
+        batch: &mut WriteBatch,
+        indexes: &[IndexDefinition],
+        record: &BlockWriteSet,
+    ) -> StorageResult<()> {
+        for write in &record.writes {
+            self.stage_write(batch, indexes, write, record.timestamp, Some(record.height))?;
+        }
+        Ok(())
+    }
+
+    fn stage_write(
+        &self,
+        batch: &mut WriteBatch,
+        indexes: &[IndexDefinition],
+        write: &AppliedWrite,
+        timestamp: u64,
+        block_height: Option<u64>,
+    ) -> StorageResult<()> {
+        let cf_ws = self.cf_world_state()?;
+        match &write.value {
+            Some(value) => {
+                let encoded = serde_json::to_vec(value)
+                    .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+                let key = write.key.as_bytes();
+                batch.put_cf(&cf_ws, key, self.seal(CF_WORLD_STATE, key, &encoded)?);
+            }
+            None => batch.delete_cf(&cf_ws, write.key.as_bytes()),
+        }
+        let entry = HistoryEntry {
+            version: write.version,
+            data: write
+                .value
+                .as_ref()
+                .map(|v| v.data.clone())
+                .unwrap_or_default(),
+            tx_id: write.tx_id.clone(),
+            timestamp,
+            is_delete: write.value.is_none(),
+            block_height,
+        };
+        let encoded = serde_json::to_vec(&entry)
+            .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+        let history_key = Self::history_key(&write.key, write.version);
+        batch.put_cf(
+            &self.cf_key_history()?,
+            &history_key,
+            self.seal(CF_KEY_HISTORY, &history_key, &encoded)?,
+        );
+        if let Some(height) = block_height {
+            batch.put_cf(
+                &self.cf_history_by_block()?,
+                Self::history_by_block_key(height, &write.key, write.version),
+                b"",
+            );
+        }
+        self.stage_index_update(
+            batch,
+            indexes,
+            &write.key,
+            write.previous.as_ref(),
+            write.value.as_ref(),
+        )
+    }
+
+    /// Put (`Some`) or delete (`None`) a single key outside a block commit,
+    /// with its history and index entries. Returns the version written.
+    pub(crate) fn write_state(
+        &self,
+        key: &str,
+        data: Option<&[u8]>,
+        tx_id: &str,
+        block_height: Option<u64>,
+    ) -> StorageResult<u64> {
+        let indexes = self.lock_state_indexes();
+        let previous = self.world_state_get(key)?;
+        let version = previous.as_ref().map_or(1, |v| v.version + 1);
+        let write = AppliedWrite {
+            key: key.to_string(),
+            tx_id: tx_id.to_string(),
+            version,
+            value: data.map(|data| VersionedValue {
+                version,
+                data: data.to_vec(),
+            }),
+            previous,
+        };
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let mut batch = WriteBatch::default();
+        self.stage_write(&mut batch, &indexes, &write, timestamp, block_height)?;
+        self.db
+            .write(batch)
+            .map_err(|e| StorageError::RocksDbError(e.to_string()))?;
+        Ok(version)
+    }
+
+    /// Install `value` under `key` as-is, with its index entries but no
+    /// history entry.
+    pub(crate) fn restore_state_entry(
+        &self,
+        key: &str,
+        value: &VersionedValue,
+    ) -> StorageResult<()> {
+        let indexes = self.lock_state_indexes();
+        let previous = self.world_state_get(key)?;
+        let encoded = serde_json::to_vec(value)
+            .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+        let encoded = self.seal(CF_WORLD_STATE, key.as_bytes(), &encoded)?;
+        let mut batch = WriteBatch::default();
+        batch.put_cf(&self.cf_world_state()?, key.as_bytes(), encoded);
+        self.stage_index_update(&mut batch, &indexes, key, previous.as_ref(), Some(value))?;
+        self.db
+            .write(batch)
+            .map_err(|e| StorageError::RocksDbError(e.to_string()))
+    }
+
+    /// Stage the removal of the block at `height` and its transactions.
+    fn stage_remove_block(&self, batch: &mut WriteBatch, height: u64) -> StorageResult<()> {
+        batch.delete_cf(&self.cf_blocks()?, Self::block_key(height));
+        batch.delete_cf(&self.cf_block_writes()?, Self::block_key(height));
+
+        let prefix = Self::tx_block_prefix(height);
+        let cf_idx = self.cf_tx_by_block()?;
+        let cf_t = self.cf_transactions()?;
+        let iter = self
+            .db
+            .iterator_cf(&cf_idx, IteratorMode::From(&prefix, Direction::Forward));
+        for item in iter {
+            let (idx_key, _) = item.map_err(|e| StorageError::RocksDbError(e.to_string()))?;
+            if !idx_key.starts_with(&prefix) {
+                break;
+            }
+            batch.delete_cf(&cf_t, &idx_key[prefix.len()..]);
+            batch.delete_cf(&cf_idx, &idx_key);
+        }
+        Ok(())
+    }
+
+    /// Remove blocks `from..=to`; the tip becomes `from - 1`.
+    fn truncate_blocks(&self, from: u64, to: u64) -> StorageResult<()> {
+        let mut batch = WriteBatch::default();
+        for height in from..=to {
+            self.stage_remove_block(&mut batch, height)?;
+        }
+        let cf_m = self.cf_meta()?;
+        match from.checked_sub(1) {
+            Some(tip) => {
+                batch.put_cf(&cf_m, META_LATEST_HEIGHT, tip.to_le_bytes());
+                batch.put_cf(&cf_m, META_STATE_SAVEPOINT, tip.to_le_bytes());
+            }
+            None => {
+                batch.delete_cf(&cf_m, META_LATEST_HEIGHT);
+                batch.delete_cf(&cf_m, META_STATE_SAVEPOINT);
+            }
+        }
+        self.db
+            .write(batch)
+            .map_err(|e| StorageError::RocksDbError(e.to_string()))
+    }
+
+    /// Undo the state writes of the block at `height` and drop the block.
+    pub(crate) fn roll_back_block(&self, height: u64) -> StorageResult<()> {
+        let indexes = self.lock_state_indexes();
+        let mut batch = WriteBatch::default();
+        if let Some(record) = self.block_write_set(height)? {
+            let cf_ws = self.cf_world_state()?;
+            let cf_hist = self.cf_key_history()?;
+            let cf_by_block = self.cf_history_by_block()?;
+            for write in record.writes.iter().rev() {
+                match &write.previous {
+                    Some(previous) => {
+                        let encoded = serde_json::to_vec(previous)
+                            .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+                        let key = write.key.as_bytes();
+                        batch.put_cf(&cf_ws, key, self.seal(CF_WORLD_STATE, key, &encoded)?);
+                    }
+                    None => batch.delete_cf(&cf_ws, write.key.as_bytes()),
+                }
+                batch.delete_cf(&cf_hist, Self::history_key(&write.key, write.version));
+                batch.delete_cf(
+                    &cf_by_block,
+                    Self::history_by_block_key(height, &write.key, write.version),
+                );
+                self.stage_index_update(
+                    &mut batch,
+                    &indexes,
+                    &write.key,
+                    write.value.as_ref(),
+                    write.previous.as_ref(),
+                )?;
+            }
+        }
+        self.stage_remove_block(&mut batch, height)?;
+        let cf_m = self.cf_meta()?;
+        match height.checked_sub(1) {
+            Some(below) => batch.put_cf(&cf_m, META_STATE_SAVEPOINT, below.to_le_bytes()),
+            None => batch.delete_cf(&cf_m, META_STATE_SAVEPOINT),
+        }
+        self.db
+            .write(batch)
+            .map_err(|e| StorageError::RocksDbError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::world_state::WorldState;
+    use tempfile::TempDir;
+
+    fn tmp_store() -> (RocksDbBlockStore, TempDir) {
+        let dir = TempDir::new().unwrap();
+        let store = RocksDbBlockStore::new(dir.path()).unwrap();
+        (store, dir)
+    }
+
+    fn block(height: u64) -> Block {
+        Block {
+            height,
+            timestamp: 1_000 + height,
+            parent_hash: [0u8; 32],
+            merkle_root: [1u8; 32],
+            transactions: vec![format!("tx-{height}")],
+            proposer: "orderer".to_string(),
+            signature: vec![2u8; 64],
+            signature_algorithm: Default::default(),
+            endorsements: vec![],
+            secondary_signature: None,
+            secondary_signature_algorithm: None,
+            hash_algorithm: Default::default(),
+            orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: Vec::new(),
+            config_tx: None,
+            last_config: 0,
+            state_root: [0u8; 32],
+            private_data_purges: vec![],
+        }
+    }
+
+    fn tx(height: u64) -> Transaction {
+        Transaction {
+            id: format!("tx-{height}"),
+            block_height: height,
+            timestamp: 1_000 + height,
+            input_did: "did:bc:a".to_string(),
+            output_recipient: "did:bc:b".to_string(),
+            amount: 1,
+            state: "committed".to_string(),
+        }
+    }
+
+    fn commit(store: &RocksDbBlockStore, height: u64, writes: &[StateWrite]) {
+        store
+            .commit_block(&block(height), &[tx(height)], writes)
+            .unwrap();
+    }
+
+    fn data(store: &RocksDbBlockStore, key: &str) -> Option<Vec<u8>> {
+        store.world_state_get(key).unwrap().map(|v| v.data)
+    }
+
+    #[test]
+    fn commit_block_writes_block_transactions_state_and_history() {
+        let (store, _dir) = tmp_store();
+        commit(&store, 0, &[StateWrite::put("tx-0", "a", b"1")]);
+
+        assert_eq!(store.read_block(0).unwrap().height, 0);
+        assert_eq!(store.transactions_by_block_height(0).unwrap().len(), 1);
+        assert_eq!(data(&store, "a"), Some(b"1".to_vec()));
+        let history = store.get_history("a").unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].tx_id, "tx-0");
+        assert_eq!(history[0].timestamp, 1_000);
+        assert_eq!(history[0].block_height, Some(0));
+        assert_eq!(store.state_savepoint().unwrap(), Some(0));
+    }
+
+    #[test]
+    fn writes_in_one_block_see_earlier_versions() {
+        let (store, _dir) = tmp_store();
+        commit(&store, 0, &[StateWrite::put("tx-0", "a", b"1")]);
+        commit(
+            &store,
+            1,
+            &[
+                StateWrite::put("tx-1", "a", b"2"),
+                StateWrite::put("tx-1", "a", b"3"),
+                StateWrite::delete("tx-1", "a"),
+            ],
+        );
+
+        assert_eq!(data(&store, "a"), None);
+        let versions: Vec<(u64, bool)> = store
+            .get_history("a")
+            .unwrap()
+            .iter()
+            .map(|e| (e.version, e.is_delete))
+            .collect();
+        assert_eq!(
+            versions,
+            vec![(1, false), (2, false), (3, false), (4, true)]
+        );
+        let record = store.block_write_set(1).unwrap().unwrap();
+        assert_eq!(record.writes[0].previous.as_ref().unwrap().data, b"1");
+    }
+
+    #[test]
+    fn history_at_lists_the_writes_of_a_block_until_it_is_rolled_back() {
+        let (store, _dir) = tmp_store();
+        commit(&store, 0, &[StateWrite::put("tx-0", "a", b"1")]);
+        commit(
+            &store,
+            1,
+            &[
+                StateWrite::put("tx-1", "b", b"2"),
+                StateWrite::delete("tx-1", "a"),
+            ],
+        );
+
+        let entries: Vec<(String, u64, bool)> = store
+            .history_at(1)
+            .unwrap()
+            .into_iter()
+            .map(|(key, e)| (key, e.version, e.is_delete))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![("a".to_string(), 2, true), ("b".to_string(), 1, false)]
+        );
+
+        store.roll_back_block(1).unwrap();
+        assert!(store.history_at(1).unwrap().is_empty());
+        assert_eq!(store.history_at(0).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn write_block_advances_the_savepoint_but_never_backwards() {
+        let (store, _dir) = tmp_store();
+        store.write_block(&block(3)).unwrap();
+        store.write_block(&block(1)).unwrap();
+        assert_eq!(store.state_savepoint().unwrap(), Some(3));
+        assert!(store.block_write_set(3).unwrap().is_none());
+    }
+
+    #[test]
+    fn recover_state_on_a_consistent_store_is_clean() {
+        let (store, _dir) = tmp_store();
+        assert!(store.recover_state().unwrap().is_clean());
+        commit(&store, 0, &[StateWrite::put("tx-0", "a", b"1")]);
+        commit(&store, 1, &[]);
+        let report = store.recover_state().unwrap();
+        assert!(report.is_clean());
+        assert_eq!(report.block_height, Some(1));
+        assert_eq!(report.savepoint, Some(1));
+    }
+
+    #[test]
+    fn recover_state_adopts_the_block_height_without_a_savepoint() {
+        let (store, _dir) = tmp_store();
+        store.write_block(&block(0)).unwrap();
+        store.write_block(&block(1)).unwrap();
+        store
+            .db
+            .delete_cf(&store.cf_meta().unwrap(), META_STATE_SAVEPOINT)
+            .unwrap();
+
+        let report = store.recover_state().unwrap();
+        assert!(report.is_clean());
+        assert_eq!(report.savepoint, None);
+        assert_eq!(store.state_savepoint().unwrap(), Some(1));
+    }
+
+    #[test]
+    fn recover_state_replays_recorded_blocks_above_the_savepoint() {
+        let (store, _dir) = tmp_store();
+        commit(&store, 0, &[StateWrite::put("tx-0", "a", b"1")]);
+        commit(&store, 1, &[StateWrite::put("tx-1", "a", b"2")]);
+        // State as it was after block 0, savepoint left behind.
+        store
+            .restore_entry(
+                "a",
+                &VersionedValue {
+                    version: 1,
+                    data: b"1".to_vec(),
+                },
+            )
+            .unwrap();
+        store
+            .db
+            .put_cf(
+                &store.cf_meta().unwrap(),
+                META_STATE_SAVEPOINT,
+                0u64.to_le_bytes(),
+            )
+            .unwrap();
+
+        let report = store.recover_state().unwrap();
+        assert_eq!(report.replayed, 1);
+        assert_eq!(data(&store, "a"), Some(b"2".to_vec()));
+        assert_eq!(store.state_savepoint().unwrap(), Some(1));
+    }
+
+    #[test]
+    fn recover_state_truncates_blocks_without_a_write_set() {
+        let (store, _dir) = tmp_store();
+        commit(&store, 0, &[StateWrite::put("tx-0", "a", b"1")]);
+        // Block 1 and its transaction land without the rest of the commit.
+        let value = serde_json::to_vec(&block(1)).unwrap();
+        store
+            .db
+            .put_cf(
+                &store.cf_blocks().unwrap(),
+                RocksDbBlockStore::block_key(1),
+                value,
+            )
+            .unwrap();
+        store
+            .db
+            .put_cf(
+                &store.cf_meta().unwrap(),
+                META_LATEST_HEIGHT,
+                1u64.to_le_bytes(),
+            )
+            .unwrap();
+        store.write_transaction(&tx(1)).unwrap();
+
+        let report = store.recover_state().unwrap();
+        assert_eq!(report.truncated, 1);
+        assert_eq!(report.block_height, Some(0));
+        assert!(!store.block_exists(1).unwrap());
+        assert!(store.read_transaction("tx-1").is_err());
+        assert_eq!(store.get_latest_height().unwrap(), 0);
+        assert_eq!(store.state_savepoint().unwrap(), Some(0));
+    }
+
+    #[test]
+    fn recover_state_rolls_back_state_ahead_of_the_blocks() {
+        let (store, _dir) = tmp_store();
+        commit(&store, 0, &[StateWrite::put("tx-0", "a", b"1")]);
+        commit(
+            &store,
+            1,
+            &[
+                StateWrite::put("tx-1", "a", b"2"),
+                StateWrite::put("tx-1", "b", b"new"),
+            ],
+        );
+        // Block height written back to 0 without the state following.
+        store
+            .db
+            .put_cf(
+                &store.cf_meta().unwrap(),
+                META_LATEST_HEIGHT,
+                0u64.to_le_bytes(),
+            )
+            .unwrap();
+
+        let report = store.recover_state().unwrap();
+        assert_eq!(report.rolled_back, 1);
+        assert_eq!(data(&store, "a"), Some(b"1".to_vec()));
+        assert_eq!(data(&store, "b"), None);
+        assert_eq!(store.get_history("a").unwrap().len(), 1);
+        assert!(store.get_history("b").unwrap().is_empty());
+        assert!(!store.block_exists(1).unwrap());
+        assert!(store.block_write_set(1).unwrap().is_none());
+        assert_eq!(store.state_savepoint().unwrap(), Some(0));
+        assert!(store.recover_state().unwrap().is_clean());
+    }
+}
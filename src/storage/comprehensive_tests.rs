@@ -6,130 +6,180 @@
 //! - Edge cases (20 tests)
 //! - Schema migration (10 tests)
 //! - Performance validation (15 tests)
+//!
+//! The suite is a macro run once per persistent backend (RocksDB and redb);
+//! each run provides `open(name)`, a store under a fixed name, and
+//! `temp_store()`, a store in its own temporary directory.
 
-#[cfg(all(test, feature = "rocksdb-storage"))]
-mod comprehensive_storage_tests {
-    use crate::storage::adapters::RocksDbBlockStore;
-    use crate::storage::errors::StorageError;
-    use crate::storage::traits::{Block, BlockStore, Credential, IdentityRecord, Transaction};
-    use std::time::Instant;
-    use tempfile::TempDir;
+#[cfg(all(test, any(feature = "rocksdb-storage", feature = "redb-storage")))]
+macro_rules! storage_suite {
+    () => {
+        use crate::storage::errors::StorageError;
+        use crate::storage::traits::{Block, BlockStore, Credential, IdentityRecord, Transaction};
+        use std::time::Instant;
 
-    /// Create an isolated RocksDB store backed by a temp directory.
-    /// The directory is cleaned up when `_dir` is dropped.
-    fn temp_store() -> (RocksDbBlockStore, TempDir) {
-        let dir = TempDir::new().unwrap();
-        let store = RocksDbBlockStore::new(dir.path().to_str().unwrap()).unwrap();
-        (store, dir)
-    }
+        // ========== CRUD OPERATIONS (20 tests) ==========
 
-    // ========== CRUD OPERATIONS (20 tests) ==========
-
-    #[test]
-    fn test_create_block_basic() {
-        let store = RocksDbBlockStore::new("/tmp/test_block_basic").unwrap();
-        let block = Block {
-            height: 1,
-            timestamp: 1000,
-            parent_hash: [0u8; 32],
-            merkle_root: [1u8; 32],
-            transactions: vec!["tx1".to_string()],
-            proposer: "proposer1".to_string(),
-            signature: vec![2u8; 64],
-            signature_algorithm: Default::default(),
-            endorsements: vec![],
-            secondary_signature: None,
-            secondary_signature_algorithm: None,
-            hash_algorithm: Default::default(),
-            orderer_signature: None,
-        };
-        assert!(store.write_block(&block).is_ok());
-    }
+        #[test]
+        fn test_create_block_basic() {
+            let store = open("test_block_basic");
+            let block = Block {
+                height: 1,
+                timestamp: 1000,
+                parent_hash: [0u8; 32],
+                merkle_root: [1u8; 32],
+                transactions: vec!["tx1".to_string()],
+                proposer: "proposer1".to_string(),
+                signature: vec![2u8; 64],
+                signature_algorithm: Default::default(),
+                endorsements: vec![],
+                secondary_signature: None,
+                secondary_signature_algorithm: None,
+                hash_algorithm: Default::default(),
+                orderer_signature: None,
+                commit_qc: None,
+                next_validator_set: None,
+                evidence: Vec::new(),
+                config_tx: None,
+                last_config: 0,
+                state_root: [0u8; 32],
+                private_data_purges: vec![],
+            };
+            assert!(store.write_block(&block).is_ok());
+        }
 
-    #[test]
-    fn test_create_transaction_basic() {
-        let store = RocksDbBlockStore::new("/tmp/test_tx_basic").unwrap();
-        let tx = Transaction {
-            id: "tx1".to_string(),
-            block_height: 1,
-            timestamp: 1000,
-            input_did: "did:bc:input".to_string(),
-            output_recipient: "did:bc:output".to_string(),
-            amount: 100,
-            state: "confirmed".to_string(),
-        };
-        assert!(store.write_transaction(&tx).is_ok());
-    }
+        #[test]
+        fn test_create_transaction_basic() {
+            let store = open("test_tx_basic");
+            let tx = Transaction {
+                id: "tx1".to_string(),
+                block_height: 1,
+                timestamp: 1000,
+                input_did: "did:bc:input".to_string(),
+                output_recipient: "did:bc:output".to_string(),
+                amount: 100,
+                state: "confirmed".to_string(),
+            };
+            assert!(store.write_transaction(&tx).is_ok());
+        }
 
-    #[test]
-    fn test_create_identity_basic() {
-        let store = RocksDbBlockStore::new("/tmp/test_identity_basic").unwrap();
-        let identity = IdentityRecord {
-            did: "did:bc:1".to_string(),
-            created_at: 1000,
-            updated_at: 2000,
-            status: "active".to_string(),
-        };
-        assert!(store.write_identity(&identity).is_ok());
-    }
+        #[test]
+        fn test_create_identity_basic() {
+            let store = open("test_identity_basic");
+            let identity = IdentityRecord {
+                did: "did:bc:1".to_string(),
+                created_at: 1000,
+                updated_at: 2000,
+                status: "active".to_string(),
+            };
+            assert!(store.write_identity(&identity).is_ok());
+        }
 
-    #[test]
-    fn test_create_credential_basic() {
-        let store = RocksDbBlockStore::new("/tmp/test_cred_basic").unwrap();
-        let cred = Credential {
-            id: "cred1".to_string(),
-            issuer_did: "did:bc:issuer".to_string(),
-            subject_did: "did:bc:subject".to_string(),
-            cred_type: "eid".to_string(),
-            issued_at: 1000,
-            expires_at: 2000,
-            ..Default::default()
-        };
-        assert!(store.write_credential(&cred).is_ok());
-    }
+        #[test]
+        fn test_create_credential_basic() {
+            let store = open("test_cred_basic");
+            let cred = Credential {
+                id: "cred1".to_string(),
+                issuer_did: "did:bc:issuer".to_string(),
+                subject_did: "did:bc:subject".to_string(),
+                cred_type: "eid".to_string(),
+                issued_at: 1000,
+                expires_at: 2000,
+                ..Default::default()
+            };
+            assert!(store.write_credential(&cred).is_ok());
+        }
 
-    #[test]
-    fn test_read_block_not_found() {
-        let store = RocksDbBlockStore::new("/tmp/test_read_block_404").unwrap();
-        assert!(store.read_block(999).is_err());
-    }
+        #[test]
+        fn test_read_block_not_found() {
+            let store = open("test_read_block_404");
+            assert!(store.read_block(999).is_err());
+        }
 
-    #[test]
-    fn test_read_transaction_not_found() {
-        let store = RocksDbBlockStore::new("/tmp/test_read_tx_404").unwrap();
-        assert!(store.read_transaction("nonexistent").is_err());
-    }
+        #[test]
+        fn test_read_transaction_not_found() {
+            let store = open("test_read_tx_404");
+            assert!(store.read_transaction("nonexistent").is_err());
+        }
 
-    #[test]
-    fn test_read_identity_not_found() {
-        let store = RocksDbBlockStore::new("/tmp/test_read_id_404").unwrap();
-        assert!(store.read_identity("did:bc:notfound").is_err());
-    }
+        #[test]
+        fn test_read_identity_not_found() {
+            let store = open("test_read_id_404");
+            assert!(store.read_identity("did:bc:notfound").is_err());
+        }
 
-    #[test]
-    fn test_read_credential_not_found() {
-        let store = RocksDbBlockStore::new("/tmp/test_read_cred_404").unwrap();
-        assert!(store.read_credential("crednotfound").is_err());
-    }
+        #[test]
+        fn test_read_credential_not_found() {
+            let store = open("test_read_cred_404");
+            assert!(store.read_credential("crednotfound").is_err());
+        }
 
-    #[test]
-    fn test_block_exists_false() {
-        let store = RocksDbBlockStore::new("/tmp/test_block_exists").unwrap();
-        let exists = store.block_exists(999).unwrap();
-        assert!(!exists);
-    }
+        #[test]
+        fn test_block_exists_false() {
+            let store = open("test_block_exists");
+            let exists = store.block_exists(999).unwrap();
+            assert!(!exists);
+        }
+
+        #[test]
+        fn test_multiple_blocks_write() {
+            let store = open("test_multi_blocks");
+            for i in 1..=10 {
+                let block = Block {
+                    height: i,
+                    timestamp: 1000 + i,
+                    parent_hash: [0u8; 32],
+                    merkle_root: [1u8; 32],
+                    transactions: vec![format!("tx{}", i)],
+                    proposer: format!("proposer{i}"),
+                    signature: vec![2u8; 64],
+                    signature_algorithm: Default::default(),
+                    endorsements: vec![],
+                    secondary_signature: None,
+                    secondary_signature_algorithm: None,
+                    hash_algorithm: Default::default(),
+                    orderer_signature: None,
+                    commit_qc: None,
+                    next_validator_set: None,
+                    evidence: Vec::new(),
+                    config_tx: None,
+                    last_config: 0,
+                    state_root: [0u8; 32],
+                    private_data_purges: vec![],
+                };
+                assert!(store.write_block(&block).is_ok());
+            }
+        }
+
+        // Key format tests deferred: functions are private
+        // Test via public API in integration tests
+
+        #[test]
+        fn test_update_identity_status() {
+            let store = open("test_update_id");
+            let mut identity = IdentityRecord {
+                did: "did:bc:1".to_string(),
+                created_at: 1000,
+                updated_at: 2000,
+                status: "active".to_string(),
+            };
+            assert!(store.write_identity(&identity).is_ok());
+            identity.status = "revoked".to_string();
+            assert!(store.write_identity(&identity).is_ok());
+        }
 
-    #[test]
-    fn test_multiple_blocks_write() {
-        let store = RocksDbBlockStore::new("/tmp/test_multi_blocks").unwrap();
-        for i in 1..=10 {
+        // ========== BATCH OPERATIONS (15 tests) ==========
+
+        #[test]
+        fn test_write_batch_single_block() {
+            let (store, _dir) = temp_store();
             let block = Block {
-                height: i,
-                timestamp: 1000 + i,
+                height: 1,
+                timestamp: 1000,
                 parent_hash: [0u8; 32],
                 merkle_root: [1u8; 32],
-                transactions: vec![format!("tx{}", i)],
-                proposer: format!("proposer{i}"),
+                transactions: vec!["tx1".to_string()],
+                proposer: "proposer1".to_string(),
                 signature: vec![2u8; 64],
                 signature_algorithm: Default::default(),
                 endorsements: vec![],
@@ -137,113 +187,162 @@ mod comprehensive_storage_tests {
                 secondary_signature_algorithm: None,
                 hash_algorithm: Default::default(),
                 orderer_signature: None,
+                commit_qc: None,
+                next_validator_set: None,
+                evidence: Vec::new(),
+                config_tx: None,
+                last_config: 0,
+                state_root: [0u8; 32],
+                private_data_purges: vec![],
             };
-            assert!(store.write_block(&block).is_ok());
+            assert!(store.write_batch(&[block], &[]).is_ok());
         }
-    }
 
-    // Key format tests deferred: functions are private
-    // Test via public API in integration tests
-
-    #[test]
-    fn test_update_identity_status() {
-        let store = RocksDbBlockStore::new("/tmp/test_update_id").unwrap();
-        let mut identity = IdentityRecord {
-            did: "did:bc:1".to_string(),
-            created_at: 1000,
-            updated_at: 2000,
-            status: "active".to_string(),
-        };
-        assert!(store.write_identity(&identity).is_ok());
-        identity.status = "revoked".to_string();
-        assert!(store.write_identity(&identity).is_ok());
-    }
+        #[test]
+        fn test_write_batch_single_transaction() {
+            let (store, _dir) = temp_store();
+            let tx = Transaction {
+                id: "tx1".to_string(),
+                block_height: 1,
+                timestamp: 1000,
+                input_did: "did:bc:input".to_string(),
+                output_recipient: "did:bc:output".to_string(),
+                amount: 100,
+                state: "confirmed".to_string(),
+            };
+            assert!(store.write_batch(&[], &[tx]).is_ok());
+        }
 
-    // ========== BATCH OPERATIONS (15 tests) ==========
-
-    #[test]
-    fn test_write_batch_single_block() {
-        let (store, _dir) = temp_store();
-        let block = Block {
-            height: 1,
-            timestamp: 1000,
-            parent_hash: [0u8; 32],
-            merkle_root: [1u8; 32],
-            transactions: vec!["tx1".to_string()],
-            proposer: "proposer1".to_string(),
-            signature: vec![2u8; 64],
-            signature_algorithm: Default::default(),
-            endorsements: vec![],
-            secondary_signature: None,
-            secondary_signature_algorithm: None,
-            hash_algorithm: Default::default(),
-            orderer_signature: None,
-        };
-        assert!(store.write_batch(&[block], &[]).is_ok());
-    }
+        #[test]
+        fn test_write_batch_mixed() {
+            let (store, _dir) = temp_store();
+            let block = Block {
+                height: 1,
+                timestamp: 1000,
+                parent_hash: [0u8; 32],
+                merkle_root: [1u8; 32],
+                transactions: vec!["tx1".to_string()],
+                proposer: "proposer1".to_string(),
+                signature: vec![2u8; 64],
+                signature_algorithm: Default::default(),
+                endorsements: vec![],
+                secondary_signature: None,
+                secondary_signature_algorithm: None,
+                hash_algorithm: Default::default(),
+                orderer_signature: None,
+                commit_qc: None,
+                next_validator_set: None,
+                evidence: Vec::new(),
+                config_tx: None,
+                last_config: 0,
+                state_root: [0u8; 32],
+                private_data_purges: vec![],
+            };
+            let tx = Transaction {
+                id: "tx1".to_string(),
+                block_height: 1,
+                timestamp: 1000,
+                input_did: "did:bc:input".to_string(),
+                output_recipient: "did:bc:output".to_string(),
+                amount: 100,
+                state: "confirmed".to_string(),
+            };
+            assert!(store.write_batch(&[block], &[tx]).is_ok());
+        }
 
-    #[test]
-    fn test_write_batch_single_transaction() {
-        let (store, _dir) = temp_store();
-        let tx = Transaction {
-            id: "tx1".to_string(),
-            block_height: 1,
-            timestamp: 1000,
-            input_did: "did:bc:input".to_string(),
-            output_recipient: "did:bc:output".to_string(),
-            amount: 100,
-            state: "confirmed".to_string(),
-        };
-        assert!(store.write_batch(&[], &[tx]).is_ok());
-    }
+        #[test]
+        fn test_write_batch_empty_fails() {
+            let (store, _dir) = temp_store();
+            assert!(store.write_batch(&[], &[]).is_err());
+        }
 
-    #[test]
-    fn test_write_batch_mixed() {
-        let (store, _dir) = temp_store();
-        let block = Block {
-            height: 1,
-            timestamp: 1000,
-            parent_hash: [0u8; 32],
-            merkle_root: [1u8; 32],
-            transactions: vec!["tx1".to_string()],
-            proposer: "proposer1".to_string(),
-            signature: vec![2u8; 64],
-            signature_algorithm: Default::default(),
-            endorsements: vec![],
-            secondary_signature: None,
-            secondary_signature_algorithm: None,
-            hash_algorithm: Default::default(),
-            orderer_signature: None,
-        };
-        let tx = Transaction {
-            id: "tx1".to_string(),
-            block_height: 1,
-            timestamp: 1000,
-            input_did: "did:bc:input".to_string(),
-            output_recipient: "did:bc:output".to_string(),
-            amount: 100,
-            state: "confirmed".to_string(),
-        };
-        assert!(store.write_batch(&[block], &[tx]).is_ok());
-    }
+        #[test]
+        fn test_write_batch_multiple_blocks() {
+            let (store, _dir) = temp_store();
+            let blocks = (1..=5)
+                .map(|i| Block {
+                    height: i,
+                    timestamp: 1000 + i,
+                    parent_hash: [0u8; 32],
+                    merkle_root: [1u8; 32],
+                    transactions: vec![format!("tx{}", i)],
+                    proposer: format!("proposer{i}"),
+                    signature: vec![2u8; 64],
+                    signature_algorithm: Default::default(),
+                    endorsements: vec![],
+                    secondary_signature: None,
+                    secondary_signature_algorithm: None,
+                    hash_algorithm: Default::default(),
+                    orderer_signature: None,
+                    commit_qc: None,
+                    next_validator_set: None,
+                    evidence: Vec::new(),
+                    config_tx: None,
+                    last_config: 0,
+                    state_root: [0u8; 32],
+                    private_data_purges: vec![],
+                })
+                .collect::<Vec<_>>();
+            assert!(store.write_batch(&blocks, &[]).is_ok());
+        }
 
-    #[test]
-    fn test_write_batch_empty_fails() {
-        let (store, _dir) = temp_store();
-        assert!(store.write_batch(&[], &[]).is_err());
-    }
+        #[test]
+        fn test_write_batch_multiple_transactions() {
+            let (store, _dir) = temp_store();
+            let txs = (1..=5)
+                .map(|i| Transaction {
+                    id: format!("tx{i}"),
+                    block_height: i as u64,
+                    timestamp: 1000 + i as u64,
+                    input_did: "did:bc:input".to_string(),
+                    output_recipient: "did:bc:output".to_string(),
+                    amount: 100 * i as u64,
+                    state: "confirmed".to_string(),
+                })
+                .collect::<Vec<_>>();
+            assert!(store.write_batch(&[], &txs).is_ok());
+        }
+
+        #[test]
+        fn test_write_batch_large_blocks() {
+            let (store, _dir) = temp_store();
+            let blocks = (1..=100)
+                .map(|i| Block {
+                    height: i,
+                    timestamp: 1000 + i,
+                    parent_hash: [0u8; 32],
+                    merkle_root: [1u8; 32],
+                    transactions: (1..=10).map(|j| format!("tx{i}_{j}")).collect(),
+                    proposer: format!("proposer{i}"),
+                    signature: vec![2u8; 64],
+                    signature_algorithm: Default::default(),
+                    endorsements: vec![],
+                    secondary_signature: None,
+                    secondary_signature_algorithm: None,
+                    hash_algorithm: Default::default(),
+                    orderer_signature: None,
+                    commit_qc: None,
+                    next_validator_set: None,
+                    evidence: Vec::new(),
+                    config_tx: None,
+                    last_config: 0,
+                    state_root: [0u8; 32],
+                    private_data_purges: vec![],
+                })
+                .collect::<Vec<_>>();
+            assert!(store.write_batch(&blocks, &[]).is_ok());
+        }
 
-    #[test]
-    fn test_write_batch_multiple_blocks() {
-        let (store, _dir) = temp_store();
-        let blocks = (1..=5)
-            .map(|i| Block {
-                height: i,
-                timestamp: 1000 + i,
+        #[test]
+        fn test_batch_atomicity() {
+            let (store, _dir) = temp_store();
+            let blocks = vec![Block {
+                height: 1,
+                timestamp: 1000,
                 parent_hash: [0u8; 32],
                 merkle_root: [1u8; 32],
-                transactions: vec![format!("tx{}", i)],
-                proposer: format!("proposer{i}"),
+                transactions: vec!["tx1".to_string()],
+                proposer: "proposer1".to_string(),
                 signature: vec![2u8; 64],
                 signature_algorithm: Default::default(),
                 endorsements: vec![],
@@ -251,39 +350,91 @@ mod comprehensive_storage_tests {
                 secondary_signature_algorithm: None,
                 hash_algorithm: Default::default(),
                 orderer_signature: None,
-            })
-            .collect::<Vec<_>>();
-        assert!(store.write_batch(&blocks, &[]).is_ok());
-    }
-
-    #[test]
-    fn test_write_batch_multiple_transactions() {
-        let (store, _dir) = temp_store();
-        let txs = (1..=5)
-            .map(|i| Transaction {
-                id: format!("tx{i}"),
-                block_height: i as u64,
-                timestamp: 1000 + i as u64,
+                commit_qc: None,
+                next_validator_set: None,
+                evidence: Vec::new(),
+                config_tx: None,
+                last_config: 0,
+                state_root: [0u8; 32],
+                private_data_purges: vec![],
+            }];
+            let txs = vec![Transaction {
+                id: "tx1".to_string(),
+                block_height: 1,
+                timestamp: 1000,
                 input_did: "did:bc:input".to_string(),
                 output_recipient: "did:bc:output".to_string(),
-                amount: 100 * i as u64,
+                amount: 100,
                 state: "confirmed".to_string(),
-            })
-            .collect::<Vec<_>>();
-        assert!(store.write_batch(&[], &txs).is_ok());
-    }
+            }];
+            assert!(store.write_batch(&blocks, &txs).is_ok());
+        }
+
+        #[test]
+        fn test_batch_sequential() {
+            let (store, _dir) = temp_store();
+            for i in 1..=10 {
+                let block = Block {
+                    height: i,
+                    timestamp: 1000 + i,
+                    parent_hash: [0u8; 32],
+                    merkle_root: [1u8; 32],
+                    transactions: vec![],
+                    proposer: "proposer".to_string(),
+                    signature: vec![2u8; 64],
+                    signature_algorithm: Default::default(),
+                    endorsements: vec![],
+                    secondary_signature: None,
+                    secondary_signature_algorithm: None,
+                    hash_algorithm: Default::default(),
+                    orderer_signature: None,
+                    commit_qc: None,
+                    next_validator_set: None,
+                    evidence: Vec::new(),
+                    config_tx: None,
+                    last_config: 0,
+                    state_root: [0u8; 32],
+                    private_data_purges: vec![],
+                };
+                assert!(store.write_batch(&[block], &[]).is_ok());
+            }
+        }
 
-    #[test]
-    fn test_write_batch_large_blocks() {
-        let (store, _dir) = temp_store();
-        let blocks = (1..=100)
-            .map(|i| Block {
-                height: i,
-                timestamp: 1000 + i,
+        #[test]
+        fn test_batch_with_credentials() {
+            let (store, _dir) = temp_store();
+            let cred = Credential {
+                id: "cred1".to_string(),
+                issuer_did: "did:bc:issuer".to_string(),
+                subject_did: "did:bc:subject".to_string(),
+                cred_type: "eid".to_string(),
+                issued_at: 1000,
+                expires_at: 2000,
+                ..Default::default()
+            };
+            assert!(store.write_credential(&cred).is_ok());
+        }
+
+        #[test]
+        fn test_batch_error_handling() {
+            let (store, _dir) = temp_store();
+            let result = store.write_batch(&[], &[]);
+            assert!(result.is_err());
+            if let Err(StorageError::BatchOperationFailed(msg)) = result {
+                assert!(msg.contains("Empty"));
+            }
+        }
+
+        #[test]
+        fn test_batch_concurrent_write() {
+            let (store, _dir) = temp_store();
+            let block1 = Block {
+                height: 1,
+                timestamp: 1000,
                 parent_hash: [0u8; 32],
                 merkle_root: [1u8; 32],
-                transactions: (1..=10).map(|j| format!("tx{i}_{j}")).collect(),
-                proposer: format!("proposer{i}"),
+                transactions: vec![],
+                proposer: "p1".to_string(),
                 signature: vec![2u8; 64],
                 signature_algorithm: Default::default(),
                 endorsements: vec![],
@@ -291,48 +442,131 @@ mod comprehensive_storage_tests {
                 secondary_signature_algorithm: None,
                 hash_algorithm: Default::default(),
                 orderer_signature: None,
-            })
-            .collect::<Vec<_>>();
-        assert!(store.write_batch(&blocks, &[]).is_ok());
-    }
+                commit_qc: None,
+                next_validator_set: None,
+                evidence: Vec::new(),
+                config_tx: None,
+                last_config: 0,
+                state_root: [0u8; 32],
+                private_data_purges: vec![],
+            };
+            let block2 = Block {
+                height: 2,
+                timestamp: 2000,
+                parent_hash: [1u8; 32],
+                merkle_root: [2u8; 32],
+                transactions: vec![],
+                proposer: "p2".to_string(),
+                signature: vec![3u8; 64],
+                signature_algorithm: Default::default(),
+                endorsements: vec![],
+                secondary_signature: None,
+                secondary_signature_algorithm: None,
+                hash_algorithm: Default::default(),
+                orderer_signature: None,
+                commit_qc: None,
+                next_validator_set: None,
+                evidence: Vec::new(),
+                config_tx: None,
+                last_config: 0,
+                state_root: [0u8; 32],
+                private_data_purges: vec![],
+            };
+            assert!(store.write_batch(&[block1, block2], &[]).is_ok());
+        }
 
-    #[test]
-    fn test_batch_atomicity() {
-        let (store, _dir) = temp_store();
-        let blocks = vec![Block {
-            height: 1,
-            timestamp: 1000,
-            parent_hash: [0u8; 32],
-            merkle_root: [1u8; 32],
-            transactions: vec!["tx1".to_string()],
-            proposer: "proposer1".to_string(),
-            signature: vec![2u8; 64],
-            signature_algorithm: Default::default(),
-            endorsements: vec![],
-            secondary_signature: None,
-            secondary_signature_algorithm: None,
-            hash_algorithm: Default::default(),
-            orderer_signature: None,
-        }];
-        let txs = vec![Transaction {
-            id: "tx1".to_string(),
-            block_height: 1,
-            timestamp: 1000,
-            input_did: "did:bc:input".to_string(),
-            output_recipient: "did:bc:output".to_string(),
-            amount: 100,
-            state: "confirmed".to_string(),
-        }];
-        assert!(store.write_batch(&blocks, &txs).is_ok());
-    }
+        // ========== EDGE CASES (20 tests) ==========
 
-    #[test]
-    fn test_batch_sequential() {
-        let (store, _dir) = temp_store();
-        for i in 1..=10 {
+        #[test]
+        fn test_large_block_data() {
+            let store = open("test_large_block");
             let block = Block {
-                height: i,
-                timestamp: 1000 + i,
+                height: 1,
+                timestamp: 1000,
+                parent_hash: [0u8; 32],
+                merkle_root: [1u8; 32],
+                transactions: (1..=1000).map(|i| format!("tx{i:04}")).collect(),
+                proposer: "proposer".to_string(),
+                signature: vec![2u8; 64],
+                signature_algorithm: Default::default(),
+                endorsements: vec![],
+                secondary_signature: None,
+                secondary_signature_algorithm: None,
+                hash_algorithm: Default::default(),
+                orderer_signature: None,
+                commit_qc: None,
+                next_validator_set: None,
+                evidence: Vec::new(),
+                config_tx: None,
+                last_config: 0,
+                state_root: [0u8; 32],
+                private_data_purges: vec![],
+            };
+            assert!(store.write_block(&block).is_ok());
+        }
+
+        #[test]
+        fn test_zero_height_block() {
+            let store = open("test_zero_height");
+            let block = Block {
+                height: 0,
+                timestamp: 0,
+                parent_hash: [0u8; 32],
+                merkle_root: [0u8; 32],
+                transactions: vec![],
+                proposer: "genesis".to_string(),
+                signature: vec![0u8; 64],
+                signature_algorithm: Default::default(),
+                endorsements: vec![],
+                secondary_signature: None,
+                secondary_signature_algorithm: None,
+                hash_algorithm: Default::default(),
+                orderer_signature: None,
+                commit_qc: None,
+                next_validator_set: None,
+                evidence: Vec::new(),
+                config_tx: None,
+                last_config: 0,
+                state_root: [0u8; 32],
+                private_data_purges: vec![],
+            };
+            assert!(store.write_block(&block).is_ok());
+        }
+
+        #[test]
+        fn test_max_height_block() {
+            let store = open("test_max_height");
+            let block = Block {
+                height: u64::MAX,
+                timestamp: u64::MAX,
+                parent_hash: [255u8; 32],
+                merkle_root: [255u8; 32],
+                transactions: vec![],
+                proposer: "max".to_string(),
+                signature: vec![255u8; 64],
+                signature_algorithm: Default::default(),
+                endorsements: vec![],
+                secondary_signature: None,
+                secondary_signature_algorithm: None,
+                hash_algorithm: Default::default(),
+                orderer_signature: None,
+                commit_qc: None,
+                next_validator_set: None,
+                evidence: Vec::new(),
+                config_tx: None,
+                last_config: 0,
+                state_root: [0u8; 32],
+                private_data_purges: vec![],
+            };
+            assert!(store.write_block(&block).is_ok());
+        }
+
+        #[test]
+        fn test_empty_transaction_list() {
+            let store = open("test_empty_txs");
+            let block = Block {
+                height: 1,
+                timestamp: 1000,
                 parent_hash: [0u8; 32],
                 merkle_root: [1u8; 32],
                 transactions: vec![],
@@ -344,425 +578,422 @@ mod comprehensive_storage_tests {
                 secondary_signature_algorithm: None,
                 hash_algorithm: Default::default(),
                 orderer_signature: None,
+                commit_qc: None,
+                next_validator_set: None,
+                evidence: Vec::new(),
+                config_tx: None,
+                last_config: 0,
+                state_root: [0u8; 32],
+                private_data_purges: vec![],
             };
-            assert!(store.write_batch(&[block], &[]).is_ok());
+            assert!(store.write_block(&block).is_ok());
         }
-    }
 
-    #[test]
-    fn test_batch_with_credentials() {
-        let (store, _dir) = temp_store();
-        let cred = Credential {
-            id: "cred1".to_string(),
-            issuer_did: "did:bc:issuer".to_string(),
-            subject_did: "did:bc:subject".to_string(),
-            cred_type: "eid".to_string(),
-            issued_at: 1000,
-            expires_at: 2000,
-            ..Default::default()
-        };
-        assert!(store.write_credential(&cred).is_ok());
-    }
+        #[test]
+        fn test_long_did_string() {
+            let store = open("test_long_did");
+            let long_did = format!("did:bc:{}", "x".repeat(1000));
+            let identity = IdentityRecord {
+                did: long_did,
+                created_at: 1000,
+                updated_at: 2000,
+                status: "active".to_string(),
+            };
+            assert!(store.write_identity(&identity).is_ok());
+        }
 
-    #[test]
-    fn test_batch_error_handling() {
-        let (store, _dir) = temp_store();
-        let result = store.write_batch(&[], &[]);
-        assert!(result.is_err());
-        if let Err(StorageError::BatchOperationFailed(msg)) = result {
-            assert!(msg.contains("Empty"));
+        #[test]
+        fn test_special_chars_in_proposer() {
+            let store = open("test_special_proposer");
+            let block = Block {
+                height: 1,
+                timestamp: 1000,
+                parent_hash: [0u8; 32],
+                merkle_root: [1u8; 32],
+                transactions: vec![],
+                proposer: "proposer-!@#$%^&*()".to_string(),
+                signature: vec![2u8; 64],
+                signature_algorithm: Default::default(),
+                endorsements: vec![],
+                secondary_signature: None,
+                secondary_signature_algorithm: None,
+                hash_algorithm: Default::default(),
+                orderer_signature: None,
+                commit_qc: None,
+                next_validator_set: None,
+                evidence: Vec::new(),
+                config_tx: None,
+                last_config: 0,
+                state_root: [0u8; 32],
+                private_data_purges: vec![],
+            };
+            assert!(store.write_block(&block).is_ok());
         }
-    }
 
-    #[test]
-    fn test_batch_concurrent_write() {
-        let (store, _dir) = temp_store();
-        let block1 = Block {
-            height: 1,
-            timestamp: 1000,
-            parent_hash: [0u8; 32],
-            merkle_root: [1u8; 32],
-            transactions: vec![],
-            proposer: "p1".to_string(),
-            signature: vec![2u8; 64],
-            signature_algorithm: Default::default(),
-            endorsements: vec![],
-            secondary_signature: None,
-            secondary_signature_algorithm: None,
-            hash_algorithm: Default::default(),
-            orderer_signature: None,
-        };
-        let block2 = Block {
-            height: 2,
-            timestamp: 2000,
-            parent_hash: [1u8; 32],
-            merkle_root: [2u8; 32],
-            transactions: vec![],
-            proposer: "p2".to_string(),
-            signature: vec![3u8; 64],
-            signature_algorithm: Default::default(),
-            endorsements: vec![],
-            secondary_signature: None,
-            secondary_signature_algorithm: None,
-            hash_algorithm: Default::default(),
-            orderer_signature: None,
-        };
-        assert!(store.write_batch(&[block1, block2], &[]).is_ok());
-    }
+        #[test]
+        fn test_unicode_in_proposer() {
+            let store = open("test_unicode_proposer");
+            let block = Block {
+                height: 1,
+                timestamp: 1000,
+                parent_hash: [0u8; 32],
+                merkle_root: [1u8; 32],
+                transactions: vec![],
+                proposer: "proposer-🚀-✅".to_string(),
+                signature: vec![2u8; 64],
+                signature_algorithm: Default::default(),
+                endorsements: vec![],
+                secondary_signature: None,
+                secondary_signature_algorithm: None,
+                hash_algorithm: Default::default(),
+                orderer_signature: None,
+                commit_qc: None,
+                next_validator_set: None,
+                evidence: Vec::new(),
+                config_tx: None,
+                last_config: 0,
+                state_root: [0u8; 32],
+                private_data_purges: vec![],
+            };
+            assert!(store.write_block(&block).is_ok());
+        }
 
-    // ========== EDGE CASES (20 tests) ==========
-
-    #[test]
-    fn test_large_block_data() {
-        let store = RocksDbBlockStore::new("/tmp/test_large_block").unwrap();
-        let block = Block {
-            height: 1,
-            timestamp: 1000,
-            parent_hash: [0u8; 32],
-            merkle_root: [1u8; 32],
-            transactions: (1..=1000).map(|i| format!("tx{i:04}")).collect(),
-            proposer: "proposer".to_string(),
-            signature: vec![2u8; 64],
-            signature_algorithm: Default::default(),
-            endorsements: vec![],
-            secondary_signature: None,
-            secondary_signature_algorithm: None,
-            hash_algorithm: Default::default(),
-            orderer_signature: None,
-        };
-        assert!(store.write_block(&block).is_ok());
-    }
+        #[test]
+        fn test_credential_with_revocation() {
+            let store = open("test_revoked_cred");
+            let cred = Credential {
+                id: "cred1".to_string(),
+                issuer_did: "did:bc:issuer".to_string(),
+                subject_did: "did:bc:subject".to_string(),
+                cred_type: "eid".to_string(),
+                issued_at: 1000,
+                expires_at: 2000,
+                revoked_at: Some(1500),
+                ..Default::default()
+            };
+            assert!(store.write_credential(&cred).is_ok());
+        }
 
-    #[test]
-    fn test_zero_height_block() {
-        let store = RocksDbBlockStore::new("/tmp/test_zero_height").unwrap();
-        let block = Block {
-            height: 0,
-            timestamp: 0,
-            parent_hash: [0u8; 32],
-            merkle_root: [0u8; 32],
-            transactions: vec![],
-            proposer: "genesis".to_string(),
-            signature: vec![0u8; 64],
-            signature_algorithm: Default::default(),
-            endorsements: vec![],
-            secondary_signature: None,
-            secondary_signature_algorithm: None,
-            hash_algorithm: Default::default(),
-            orderer_signature: None,
-        };
-        assert!(store.write_block(&block).is_ok());
-    }
+        #[test]
+        fn test_expired_credential() {
+            let store = open("test_expired_cred");
+            let cred = Credential {
+                id: "cred1".to_string(),
+                issuer_did: "did:bc:issuer".to_string(),
+                subject_did: "did:bc:subject".to_string(),
+                cred_type: "passport".to_string(),
+                issued_at: 0,
+                expires_at: 1000,
+                ..Default::default()
+            };
+            assert!(store.write_credential(&cred).is_ok());
+        }
 
-    #[test]
-    fn test_max_height_block() {
-        let store = RocksDbBlockStore::new("/tmp/test_max_height").unwrap();
-        let block = Block {
-            height: u64::MAX,
-            timestamp: u64::MAX,
-            parent_hash: [255u8; 32],
-            merkle_root: [255u8; 32],
-            transactions: vec![],
-            proposer: "max".to_string(),
-            signature: vec![255u8; 64],
-            signature_algorithm: Default::default(),
-            endorsements: vec![],
-            secondary_signature: None,
-            secondary_signature_algorithm: None,
-            hash_algorithm: Default::default(),
-            orderer_signature: None,
-        };
-        assert!(store.write_block(&block).is_ok());
-    }
+        #[test]
+        fn test_transaction_zero_amount() {
+            let store = open("test_tx_zero_amount");
+            let tx = Transaction {
+                id: "tx1".to_string(),
+                block_height: 1,
+                timestamp: 1000,
+                input_did: "did:bc:input".to_string(),
+                output_recipient: "did:bc:output".to_string(),
+                amount: 0,
+                state: "confirmed".to_string(),
+            };
+            assert!(store.write_transaction(&tx).is_ok());
+        }
 
-    #[test]
-    fn test_empty_transaction_list() {
-        let store = RocksDbBlockStore::new("/tmp/test_empty_txs").unwrap();
-        let block = Block {
-            height: 1,
-            timestamp: 1000,
-            parent_hash: [0u8; 32],
-            merkle_root: [1u8; 32],
-            transactions: vec![],
-            proposer: "proposer".to_string(),
-            signature: vec![2u8; 64],
-            signature_algorithm: Default::default(),
-            endorsements: vec![],
-            secondary_signature: None,
-            secondary_signature_algorithm: None,
-            hash_algorithm: Default::default(),
-            orderer_signature: None,
-        };
-        assert!(store.write_block(&block).is_ok());
-    }
+        #[test]
+        fn test_transaction_max_amount() {
+            let store = open("test_tx_max_amount");
+            let tx = Transaction {
+                id: "tx1".to_string(),
+                block_height: 1,
+                timestamp: 1000,
+                input_did: "did:bc:input".to_string(),
+                output_recipient: "did:bc:output".to_string(),
+                amount: u64::MAX,
+                state: "confirmed".to_string(),
+            };
+            assert!(store.write_transaction(&tx).is_ok());
+        }
 
-    #[test]
-    fn test_long_did_string() {
-        let store = RocksDbBlockStore::new("/tmp/test_long_did").unwrap();
-        let long_did = format!("did:bc:{}", "x".repeat(1000));
-        let identity = IdentityRecord {
-            did: long_did,
-            created_at: 1000,
-            updated_at: 2000,
-            status: "active".to_string(),
-        };
-        assert!(store.write_identity(&identity).is_ok());
-    }
+        #[test]
+        fn test_transaction_pending_state() {
+            let store = open("test_tx_pending");
+            let tx = Transaction {
+                id: "tx1".to_string(),
+                block_height: 0,
+                timestamp: 1000,
+                input_did: "did:bc:input".to_string(),
+                output_recipient: "did:bc:output".to_string(),
+                amount: 100,
+                state: "pending".to_string(),
+            };
+            assert!(store.write_transaction(&tx).is_ok());
+        }
 
-    #[test]
-    fn test_special_chars_in_proposer() {
-        let store = RocksDbBlockStore::new("/tmp/test_special_proposer").unwrap();
-        let block = Block {
-            height: 1,
-            timestamp: 1000,
-            parent_hash: [0u8; 32],
-            merkle_root: [1u8; 32],
-            transactions: vec![],
-            proposer: "proposer-!@#$%^&*()".to_string(),
-            signature: vec![2u8; 64],
-            signature_algorithm: Default::default(),
-            endorsements: vec![],
-            secondary_signature: None,
-            secondary_signature_algorithm: None,
-            hash_algorithm: Default::default(),
-            orderer_signature: None,
-        };
-        assert!(store.write_block(&block).is_ok());
-    }
+        #[test]
+        fn test_transaction_failed_state() {
+            let store = open("test_tx_failed");
+            let tx = Transaction {
+                id: "tx1".to_string(),
+                block_height: 1,
+                timestamp: 1000,
+                input_did: "did:bc:input".to_string(),
+                output_recipient: "did:bc:output".to_string(),
+                amount: 100,
+                state: "failed".to_string(),
+            };
+            assert!(store.write_transaction(&tx).is_ok());
+        }
 
-    #[test]
-    fn test_unicode_in_proposer() {
-        let store = RocksDbBlockStore::new("/tmp/test_unicode_proposer").unwrap();
-        let block = Block {
-            height: 1,
-            timestamp: 1000,
-            parent_hash: [0u8; 32],
-            merkle_root: [1u8; 32],
-            transactions: vec![],
-            proposer: "proposer-🚀-✅".to_string(),
-            signature: vec![2u8; 64],
-            signature_algorithm: Default::default(),
-            endorsements: vec![],
-            secondary_signature: None,
-            secondary_signature_algorithm: None,
-            hash_algorithm: Default::default(),
-            orderer_signature: None,
-        };
-        assert!(store.write_block(&block).is_ok());
-    }
+        #[test]
+        fn test_identity_suspended_status() {
+            let store = open("test_id_suspended");
+            let identity = IdentityRecord {
+                did: "did:bc:1".to_string(),
+                created_at: 1000,
+                updated_at: 2000,
+                status: "suspended".to_string(),
+            };
+            assert!(store.write_identity(&identity).is_ok());
+        }
 
-    #[test]
-    fn test_credential_with_revocation() {
-        let store = RocksDbBlockStore::new("/tmp/test_revoked_cred").unwrap();
-        let cred = Credential {
-            id: "cred1".to_string(),
-            issuer_did: "did:bc:issuer".to_string(),
-            subject_did: "did:bc:subject".to_string(),
-            cred_type: "eid".to_string(),
-            issued_at: 1000,
-            expires_at: 2000,
-            revoked_at: Some(1500),
-            ..Default::default()
-        };
-        assert!(store.write_credential(&cred).is_ok());
-    }
+        #[test]
+        fn test_identity_revoked_status() {
+            let store = open("test_id_revoked");
+            let identity = IdentityRecord {
+                did: "did:bc:1".to_string(),
+                created_at: 1000,
+                updated_at: 2000,
+                status: "revoked".to_string(),
+            };
+            assert!(store.write_identity(&identity).is_ok());
+        }
 
-    #[test]
-    fn test_expired_credential() {
-        let store = RocksDbBlockStore::new("/tmp/test_expired_cred").unwrap();
-        let cred = Credential {
-            id: "cred1".to_string(),
-            issuer_did: "did:bc:issuer".to_string(),
-            subject_did: "did:bc:subject".to_string(),
-            cred_type: "passport".to_string(),
-            issued_at: 0,
-            expires_at: 1000,
-            ..Default::default()
-        };
-        assert!(store.write_credential(&cred).is_ok());
-    }
+        // ========== SCHEMA MIGRATION (10 tests) ==========
 
-    #[test]
-    fn test_transaction_zero_amount() {
-        let store = RocksDbBlockStore::new("/tmp/test_tx_zero_amount").unwrap();
-        let tx = Transaction {
-            id: "tx1".to_string(),
-            block_height: 1,
-            timestamp: 1000,
-            input_did: "did:bc:input".to_string(),
-            output_recipient: "did:bc:output".to_string(),
-            amount: 0,
-            state: "confirmed".to_string(),
-        };
-        assert!(store.write_transaction(&tx).is_ok());
-    }
+        #[test]
+        fn test_schema_version_tracking() {
+            let store = open("test_schema_v1");
+            let block = Block {
+                height: 1,
+                timestamp: 1000,
+                parent_hash: [0u8; 32],
+                merkle_root: [1u8; 32],
+                transactions: vec![],
+                proposer: "p1".to_string(),
+                signature: vec![0u8; 64],
+                signature_algorithm: Default::default(),
+                endorsements: vec![],
+                secondary_signature: None,
+                secondary_signature_algorithm: None,
+                hash_algorithm: Default::default(),
+                orderer_signature: None,
+                commit_qc: None,
+                next_validator_set: None,
+                evidence: Vec::new(),
+                config_tx: None,
+                last_config: 0,
+                state_root: [0u8; 32],
+                private_data_purges: vec![],
+            };
+            assert!(store.write_block(&block).is_ok());
+        }
 
-    #[test]
-    fn test_transaction_max_amount() {
-        let store = RocksDbBlockStore::new("/tmp/test_tx_max_amount").unwrap();
-        let tx = Transaction {
-            id: "tx1".to_string(),
-            block_height: 1,
-            timestamp: 1000,
-            input_did: "did:bc:input".to_string(),
-            output_recipient: "did:bc:output".to_string(),
-            amount: u64::MAX,
-            state: "confirmed".to_string(),
-        };
-        assert!(store.write_transaction(&tx).is_ok());
-    }
+        #[test]
+        fn test_backwards_compatibility() {
+            let store = open("test_compat");
+            let block = Block {
+                height: 1,
+                timestamp: 1000,
+                parent_hash: [0u8; 32],
+                merkle_root: [1u8; 32],
+                transactions: vec![],
+                proposer: "proposer".to_string(),
+                signature: vec![2u8; 64],
+                signature_algorithm: Default::default(),
+                endorsements: vec![],
+                secondary_signature: None,
+                secondary_signature_algorithm: None,
+                hash_algorithm: Default::default(),
+                orderer_signature: None,
+                commit_qc: None,
+                next_validator_set: None,
+                evidence: Vec::new(),
+                config_tx: None,
+                last_config: 0,
+                state_root: [0u8; 32],
+                private_data_purges: vec![],
+            };
+            assert!(store.write_block(&block).is_ok());
+        }
 
-    #[test]
-    fn test_transaction_pending_state() {
-        let store = RocksDbBlockStore::new("/tmp/test_tx_pending").unwrap();
-        let tx = Transaction {
-            id: "tx1".to_string(),
-            block_height: 0,
-            timestamp: 1000,
-            input_did: "did:bc:input".to_string(),
-            output_recipient: "did:bc:output".to_string(),
-            amount: 100,
-            state: "pending".to_string(),
-        };
-        assert!(store.write_transaction(&tx).is_ok());
-    }
+        // ... (10 more migration tests would go here)
+        #[test]
+        fn test_migration_placeholder_1() {}
+        #[test]
+        fn test_migration_placeholder_2() {}
+        #[test]
+        fn test_migration_placeholder_3() {}
+        #[test]
+        fn test_migration_placeholder_4() {}
+        #[test]
+        fn test_migration_placeholder_5() {}
+        #[test]
+        fn test_migration_placeholder_6() {}
+        #[test]
+        fn test_migration_placeholder_7() {}
+        #[test]
+        fn test_migration_placeholder_8() {}
 
-    #[test]
-    fn test_transaction_failed_state() {
-        let store = RocksDbBlockStore::new("/tmp/test_tx_failed").unwrap();
-        let tx = Transaction {
-            id: "tx1".to_string(),
-            block_height: 1,
-            timestamp: 1000,
-            input_did: "did:bc:input".to_string(),
-            output_recipient: "did:bc:output".to_string(),
-            amount: 100,
-            state: "failed".to_string(),
-        };
-        assert!(store.write_transaction(&tx).is_ok());
-    }
+        // ========== PRIVATE DATA TTL AND PURGE ==========
 
-    #[test]
-    fn test_identity_suspended_status() {
-        let store = RocksDbBlockStore::new("/tmp/test_id_suspended").unwrap();
-        let identity = IdentityRecord {
-            did: "did:bc:1".to_string(),
-            created_at: 1000,
-            updated_at: 2000,
-            status: "suspended".to_string(),
-        };
-        assert!(store.write_identity(&identity).is_ok());
-    }
+        #[test]
+        fn test_private_data_ttl_purge() {
+            use crate::private_data::PrivateDataStore;
+            let (store, _dir) = temp_store();
+            store
+                .put_private_data_at("col", "short", b"a", 1, 5)
+                .unwrap();
+            store
+                .put_private_data_at("col", "long", b"b", 1, 100)
+                .unwrap();
+            store
+                .put_private_data_at("col", "forever", b"c", 1, 0)
+                .unwrap();
+            // Rewritten with a later expiry: the first one no longer applies.
+            store
+                .put_private_data_at("col", "moved", b"d", 1, 5)
+                .unwrap();
+            store
+                .put_private_data_at("col", "moved", b"e", 3, 50)
+                .unwrap();
 
-    #[test]
-    fn test_identity_revoked_status() {
-        let store = RocksDbBlockStore::new("/tmp/test_id_revoked").unwrap();
-        let identity = IdentityRecord {
-            did: "did:bc:1".to_string(),
-            created_at: 1000,
-            updated_at: 2000,
-            status: "revoked".to_string(),
-        };
-        assert!(store.write_identity(&identity).is_ok());
-    }
+            store.purge_expired(5);
+            assert!(store.get_private_data("col", "short").unwrap().is_some());
+            store.purge_expired(6);
+            assert_eq!(store.get_private_data("col", "short").unwrap(), None);
+            assert!(store.get_private_data("col", "long").unwrap().is_some());
+            assert_eq!(
+                store.get_private_data("col", "moved").unwrap(),
+                Some(b"e".to_vec())
+            );
+            store.purge_expired(u64::MAX);
+            assert_eq!(store.get_private_data("col", "long").unwrap(), None);
+            assert_eq!(store.get_private_data("col", "moved").unwrap(), None);
+            assert!(store.get_private_data("col", "forever").unwrap().is_some());
+        }
 
-    // ========== SCHEMA MIGRATION (10 tests) ==========
-
-    #[test]
-    fn test_schema_version_tracking() {
-        let store = RocksDbBlockStore::new("/tmp/test_schema_v1").unwrap();
-        let block = Block {
-            height: 1,
-            timestamp: 1000,
-            parent_hash: [0u8; 32],
-            merkle_root: [1u8; 32],
-            transactions: vec![],
-            proposer: "p1".to_string(),
-            signature: vec![0u8; 64],
-            signature_algorithm: Default::default(),
-            endorsements: vec![],
-            secondary_signature: None,
-            secondary_signature_algorithm: None,
-            hash_algorithm: Default::default(),
-            orderer_signature: None,
-        };
-        assert!(store.write_block(&block).is_ok());
-    }
+        #[test]
+        fn test_private_data_purge_key() {
+            use crate::private_data::PrivateDataStore;
+            let (store, _dir) = temp_store();
+            store.put_private_data_at("col", "k", b"v", 1, 5).unwrap();
+            store.put_private_data("col", "other", b"w").unwrap();
+            assert!(store.purge_private_data("col", "k").unwrap());
+            assert_eq!(store.get_private_data("col", "k").unwrap(), None);
+            assert!(!store.purge_private_data("col", "k").unwrap());
+            // The dropped TTL record does not resurface on a later write.
+            store.put_private_data("col", "k", b"again").unwrap();
+            store.purge_expired(u64::MAX);
+            assert_eq!(
+                store.get_private_data("col", "k").unwrap(),
+                Some(b"again".to_vec())
+            );
+            assert!(store.get_private_data("col", "other").unwrap().is_some());
+        }
 
-    #[test]
-    fn test_backwards_compatibility() {
-        let store = RocksDbBlockStore::new("/tmp/test_compat").unwrap();
-        let block = Block {
-            height: 1,
-            timestamp: 1000,
-            parent_hash: [0u8; 32],
-            merkle_root: [1u8; 32],
-            transactions: vec![],
-            proposer: "proposer".to_string(),
-            signature: vec![2u8; 64],
-            signature_algorithm: Default::default(),
-            endorsements: vec![],
-            secondary_signature: None,
-            secondary_signature_algorithm: None,
-            hash_algorithm: Default::default(),
-            orderer_signature: None,
-        };
-        assert!(store.write_block(&block).is_ok());
-    }
+        // ========== ENCRYPTION AT REST ==========
 
-    // ... (10 more migration tests would go here)
-    #[test]
-    fn test_migration_placeholder_1() {}
-    #[test]
-    fn test_migration_placeholder_2() {}
-    #[test]
-    fn test_migration_placeholder_3() {}
-    #[test]
-    fn test_migration_placeholder_4() {}
-    #[test]
-    fn test_migration_placeholder_5() {}
-    #[test]
-    fn test_migration_placeholder_6() {}
-    #[test]
-    fn test_migration_placeholder_7() {}
-    #[test]
-    fn test_migration_placeholder_8() {}
-
-    // ========== PERFORMANCE VALIDATION (15 tests) ==========
-
-    #[test]
-    fn test_write_latency_single_block() {
-        let store = RocksDbBlockStore::new("/tmp/test_perf_write").unwrap();
-        let block = Block {
-            height: 1,
-            timestamp: 1000,
-            parent_hash: [0u8; 32],
-            merkle_root: [1u8; 32],
-            transactions: vec![],
-            proposer: "p".to_string(),
-            signature: vec![0u8; 64],
-            signature_algorithm: Default::default(),
-            endorsements: vec![],
-            secondary_signature: None,
-            secondary_signature_algorithm: None,
-            hash_algorithm: Default::default(),
-            orderer_signature: None,
-        };
-        let start = Instant::now();
-        let _ = store.write_block(&block);
-        let elapsed = start.elapsed();
-        // Just verify it completes, latency check deferred to real RocksDB impl
-        assert!(elapsed.as_millis() < 1000);
-    }
+        #[test]
+        fn test_encryption_round_trip_and_rotation() {
+            use crate::private_data::PrivateDataStore;
+            use crate::storage::encryption::{reencrypt_scope, EncryptedStore, MlKemKek};
+            use crate::storage::world_state::WorldState;
+            use std::sync::Arc;
+
+            let (store, _dir) = temp_store();
+            store
+                .enable_encryption(Arc::new(MlKemKek::generate().unwrap()))
+                .unwrap();
+            let block = crate::channel::genesis::create_genesis_block(
+                "default",
+                &crate::channel::config::ChannelConfig::default(),
+            );
+            store.write_block(&block).unwrap();
+            store.put_from_tx("k", b"v1", "tx1", 0).unwrap();
+            store.put_private_data("col", "pk", b"secret").unwrap();
+            store
+                .write_vault("did:bc:1", &serde_json::json!({"sealed": true}))
+                .unwrap();
+
+            assert_eq!(store.read_block(0).unwrap().proposer, block.proposer);
+            assert_eq!(WorldState::get(&store, "k").unwrap().unwrap().data, b"v1");
+            assert_eq!(store.get_history("k").unwrap()[0].data, b"v1");
+            assert_eq!(
+                store.get_private_data("col", "pk").unwrap(),
+                Some(b"secret".to_vec())
+            );
+            assert_eq!(store.read_vault("did:bc:1").unwrap()["sealed"], true);
+
+            let report = store.encryption_coverage().unwrap();
+            assert!(report.enabled);
+            assert!(report.scopes.iter().all(|s| s.plaintext == 0 && s.stale == 0));
+            assert!(report.scopes.iter().any(|s| s.scope == "private_col"));
+
+            assert_eq!(store.rotate_data_key("world_state").unwrap(), 2);
+            let world_state = |store: &dyn EncryptedStore| {
+                store
+                    .encryption_coverage()
+                    .unwrap()
+                    .scopes
+                    .into_iter()
+                    .find(|s| s.scope == "world_state")
+                    .unwrap()
+            };
+            assert_eq!(world_state(&store).stale, 1);
+            reencrypt_scope(&store, "world_state").unwrap();
+            let ws = world_state(&store);
+            assert_eq!((ws.key_version, ws.current, ws.stale), (Some(2), 1, 0));
+            assert_eq!(WorldState::get(&store, "k").unwrap().unwrap().data, b"v1");
+            assert!(store.rotate_data_key("identities").is_err());
+        }
 
-    #[test]
-    fn test_throughput_100_blocks() {
-        let store = RocksDbBlockStore::new("/tmp/test_perf_100").unwrap();
-        let start = Instant::now();
-        for i in 1..=100 {
+        #[test]
+        fn test_encryption_reencrypts_existing_plaintext() {
+            use crate::storage::encryption::{reencrypt_scope, EncryptedStore, MlKemKek};
+            use crate::storage::world_state::WorldState;
+            use std::sync::Arc;
+
+            let (store, _dir) = temp_store();
+            store.put_from_tx("k", b"old", "tx1", 0).unwrap();
+            assert!(!store.encryption_coverage().unwrap().enabled);
+            store
+                .enable_encryption(Arc::new(MlKemKek::generate().unwrap()))
+                .unwrap();
+
+            let report = store.encryption_coverage().unwrap();
+            assert!(!report.is_complete());
+            assert!(report.pending.iter().any(|s| s == "world_state"));
+            for scope in store.pending_reencryption().unwrap() {
+                reencrypt_scope(&store, &scope).unwrap();
+            }
+            let report = store.encryption_coverage().unwrap();
+            assert!(report.is_complete(), "{report:?}");
+            assert_eq!(WorldState::get(&store, "k").unwrap().unwrap().data, b"old");
+        }
+
+        // ========== PERFORMANCE VALIDATION (15 tests) ==========
+
+        #[test]
+        fn test_write_latency_single_block() {
+            let store = open("test_perf_write");
             let block = Block {
-                height: i,
-                timestamp: 1000 + i,
+                height: 1,
+                timestamp: 1000,
                 parent_hash: [0u8; 32],
                 merkle_root: [1u8; 32],
                 transactions: vec![],
@@ -774,56 +1005,141 @@ mod comprehensive_storage_tests {
                 secondary_signature_algorithm: None,
                 hash_algorithm: Default::default(),
                 orderer_signature: None,
+                commit_qc: None,
+                next_validator_set: None,
+                evidence: Vec::new(),
+                config_tx: None,
+                last_config: 0,
+                state_root: [0u8; 32],
+                private_data_purges: vec![],
             };
+            let start = Instant::now();
             let _ = store.write_block(&block);
+            let elapsed = start.elapsed();
+            // Just verify it completes, latency check deferred to real RocksDB impl
+            assert!(elapsed.as_millis() < 1000);
         }
-        let elapsed = start.elapsed();
-        assert!(elapsed.as_secs() < 60);
-    }
 
-    #[test]
-    fn test_throughput_1000_transactions() {
-        let store = RocksDbBlockStore::new("/tmp/test_perf_1000_tx").unwrap();
-        let start = Instant::now();
-        for i in 1..=1000 {
-            let tx = Transaction {
-                id: format!("tx{i}"),
-                block_height: i as u64,
-                timestamp: 1000 + i as u64,
-                input_did: "did:bc:input".to_string(),
-                output_recipient: "did:bc:output".to_string(),
-                amount: 100,
-                state: "confirmed".to_string(),
-            };
-            let _ = store.write_transaction(&tx);
+        #[test]
+        fn test_throughput_100_blocks() {
+            let store = open("test_perf_100");
+            let start = Instant::now();
+            for i in 1..=100 {
+                let block = Block {
+                    height: i,
+                    timestamp: 1000 + i,
+                    parent_hash: [0u8; 32],
+                    merkle_root: [1u8; 32],
+                    transactions: vec![],
+                    proposer: "p".to_string(),
+                    signature: vec![0u8; 64],
+                    signature_algorithm: Default::default(),
+                    endorsements: vec![],
+                    secondary_signature: None,
+                    secondary_signature_algorithm: None,
+                    hash_algorithm: Default::default(),
+                    orderer_signature: None,
+                    commit_qc: None,
+                    next_validator_set: None,
+                    evidence: Vec::new(),
+                    config_tx: None,
+                    last_config: 0,
+                    state_root: [0u8; 32],
+                    private_data_purges: vec![],
+                };
+                let _ = store.write_block(&block);
+            }
+            let elapsed = start.elapsed();
+            assert!(elapsed.as_secs() < 60);
         }
-        let elapsed = start.elapsed();
-        assert!(elapsed.as_secs() < 60);
+
+        #[test]
+        fn test_throughput_1000_transactions() {
+            let store = open("test_perf_1000_tx");
+            let start = Instant::now();
+            for i in 1..=1000 {
+                let tx = Transaction {
+                    id: format!("tx{i}"),
+                    block_height: i as u64,
+                    timestamp: 1000 + i as u64,
+                    input_did: "did:bc:input".to_string(),
+                    output_recipient: "did:bc:output".to_string(),
+                    amount: 100,
+                    state: "confirmed".to_string(),
+                };
+                let _ = store.write_transaction(&tx);
+            }
+            let elapsed = start.elapsed();
+            assert!(elapsed.as_secs() < 60);
+        }
+
+        // ... (12 more perf tests as placeholders)
+        #[test]
+        fn test_perf_placeholder_1() {}
+        #[test]
+        fn test_perf_placeholder_2() {}
+        #[test]
+        fn test_perf_placeholder_3() {}
+        #[test]
+        fn test_perf_placeholder_4() {}
+        #[test]
+        fn test_perf_placeholder_5() {}
+        #[test]
+        fn test_perf_placeholder_6() {}
+        #[test]
+        fn test_perf_placeholder_7() {}
+        #[test]
+        fn test_perf_placeholder_8() {}
+        #[test]
+        fn test_perf_placeholder_9() {}
+        #[test]
+        fn test_perf_placeholder_10() {}
+        #[test]
+        fn test_perf_placeholder_11() {}
+        #[test]
+        fn test_perf_placeholder_12() {}
+    };
+}
+
+#[cfg(all(test, feature = "rocksdb-storage"))]
+mod comprehensive_storage_tests {
+    use crate::storage::adapters::RocksDbBlockStore;
+    use tempfile::TempDir;
+
+    fn open(name: &str) -> RocksDbBlockStore {
+        RocksDbBlockStore::new(format!("/tmp/{name}")).unwrap()
+    }
+
+    /// Create an isolated RocksDB store backed by a temp directory.
+    /// The directory is cleaned up when `_dir` is dropped.
+    fn temp_store() -> (RocksDbBlockStore, TempDir) {
+        let dir = TempDir::new().unwrap();
+        let store = RocksDbBlockStore::new(dir.path().to_str().unwrap()).unwrap();
+        (store, dir)
+    }
+
+    storage_suite!();
+}
+
+#[cfg(all(test, feature = "redb-storage"))]
+mod redb_storage_tests {
+    use crate::storage::redb_store::RedbBlockStore;
+    use tempfile::TempDir;
+
+    /// A fresh store under `$TMPDIR/redb_suite/{name}`; a previous run's
+    /// file is removed first.
+    fn open(name: &str) -> RedbBlockStore {
+        let path = std::env::temp_dir().join("redb_suite").join(name);
+        let _ = std::fs::remove_dir_all(&path);
+        RedbBlockStore::new(path).unwrap()
+    }
+
+    /// Create an isolated redb store backed by a temp directory.
+    fn temp_store() -> (RedbBlockStore, TempDir) {
+        let dir = TempDir::new().unwrap();
+        let store = RedbBlockStore::new(dir.path()).unwrap();
+        (store, dir)
     }
 
-    // ... (12 more perf tests as placeholders)
-    #[test]
-    fn test_perf_placeholder_1() {}
-    #[test]
-    fn test_perf_placeholder_2() {}
-    #[test]
-    fn test_perf_placeholder_3() {}
-    #[test]
-    fn test_perf_placeholder_4() {}
-    #[test]
-    fn test_perf_placeholder_5() {}
-    #[test]
-    fn test_perf_placeholder_6() {}
-    #[test]
-    fn test_perf_placeholder_7() {}
-    #[test]
-    fn test_perf_placeholder_8() {}
-    #[test]
-    fn test_perf_placeholder_9() {}
-    #[test]
-    fn test_perf_placeholder_10() {}
-    #[test]
-    fn test_perf_placeholder_11() {}
-    #[test]
-    fn test_perf_placeholder_12() {}
+    storage_suite!();
 }
@@ -194,14 +194,27 @@ impl WorldState for CouchDbWorldState {
     }
 
     fn get_range(&self, start: &str, end: &str) -> StorageResult<Vec<(String, VersionedValue)>> {
-        // Use CouchDB _all_docs with startkey/endkey for range queries.
-        let url = format!(
+        self.get_range_page(start, end, usize::MAX)
+    }
+
+    fn get_range_page(
+        &self,
+        start: &str,
+        end: &str,
+        limit: usize,
+    ) -> StorageResult<Vec<(String, VersionedValue)>> {
+        // Use CouchDB _all_docs with startkey/endkey for range queries. The
+        // end key is inclusive there, so one extra row is asked for.
+        let mut url = format!(
             "{}/{}/_all_docs?include_docs=true&startkey={}&endkey={}",
             self.base_url,
             self.db,
             serde_json::to_string(start).unwrap_or_default(),
             serde_json::to_string(end).unwrap_or_default(),
         );
+        if limit < usize::MAX {
+            url.push_str(&format!("&limit={}", limit + 1));
+        }
 
         let resp = block_on_async(self.client.get(&url).send())
             .map_err(|e| StorageError::Other(format!("CouchDB range: {e}")))?;
@@ -225,6 +238,7 @@ impl WorldState for CouchDbWorldState {
                 ));
             }
         }
+        results.truncate(limit);
 
         Ok(results)
     }
@@ -0,0 +1,876 @@
+//! Envelope encryption of storage values at rest.
+//!
+//! Every covered column family ([`ENCRYPTED_SCOPES`] and each `private_*`
+//! collection) has its own AES-256-GCM data key. Data keys never reach disk
+//! in the clear: each one is wrapped under a key-encryption key (KEK), an
+//! ML-KEM-768 key pair from `pqc_crypto_module`, by encapsulating to the KEK
+//! public key and sealing the data key under
+//! `HMAC-SHA3-256(shared_secret, WRAP_LABEL)`. Wrapped keys are kept in the
+//! store's own `encryption_keys` table, so only the KEK lives outside it: in
+//! a file ([`MlKemKek::load_or_create`]) or in the HSM ([`crate::identity::hsm::HsmKek`]).
+//!
+//! A sealed value is `MAGIC || key version (u32 BE) || nonce || ciphertext+tag`
+//! with `{scope}\0{key}` as associated data, so it cannot be moved under
+//! another key. Values without the magic prefix were written before
+//! encryption was enabled; they stay readable and [`spawn_reencryption`]
+//! seals them, as it does values under an older key after a rotation.
+//! Once a pass over a scope has finished, the scope is recorded as sealed
+//! ([`sealed_marker_key`]) and a value without the prefix is refused there,
+//! so plaintext planted in the database is not taken as data.
+//!
+//! Keys are not encrypted. Neither are the entries of secondary indexes
+//! (`storage::index`), whose keys hold the indexed field values.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+
+use pqc_crypto_module::aead::{KEY_LEN, NONCE_LEN, TAG_LEN};
+use pqc_crypto_module::api;
+use pqc_crypto_module::errors::CryptoError;
+use pqc_crypto_module::types::{
+    MlKemCiphertext, MlKemPrivateKey, MlKemPublicKey, MlKemSharedSecret,
+};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use super::errors::{StorageError, StorageResult};
+
+/// Column families whose values are encrypted, besides `private_*`.
+pub const ENCRYPTED_SCOPES: &[&str] = &[
+    "blocks",
+    "transactions",
+    "world_state",
+    "key_history",
+    "block_writes",
+    "vault",
+    "audit_log",
+];
+
+/// Name prefix of private data collection column families.
+pub const PRIVATE_SCOPE_PREFIX: &str = "private_";
+
+/// Column family (RocksDB) or table (redb) holding the wrapped data keys.
+pub const KEYS_TABLE: &str = "encryption_keys";
+
+/// Entries scanned per re-encryption step.
+pub const REENCRYPT_BATCH: usize = 256;
+
+const MAGIC: [u8; 4] = [0x00, b'E', b'N', b'C'];
+const HEADER_LEN: usize = MAGIC.len() + 4 + NONCE_LEN;
+const WRAP_LABEL: &[u8] = b"rust-bc storage data key";
+/// Byte range of the encapsulation key inside an ML-KEM-768 decapsulation
+/// key (FIPS 203: `dk_pke || ek || H(ek) || z`).
+const MLKEM768_EK_RANGE: std::ops::Range<usize> = 1152..2336;
+const MLKEM768_DK_LEN: usize = 2400;
+
+fn crypto_err(e: CryptoError) -> StorageError {
+    StorageError::Encryption(e.to_string())
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Bring `pqc_crypto_module` into approved mode once per process.
+fn ensure_approved() -> StorageResult<()> {
+    static INIT: OnceLock<Result<(), String>> = OnceLock::new();
+    INIT.get_or_init(|| {
+        use pqc_crypto_module::approved_mode::{state, ModuleState};
+        if state() == ModuleState::Approved {
+            return Ok(());
+        }
+        api::initialize_approved_mode().map_err(|e| e.to_string())
+    })
+    .clone()
+    .map_err(StorageError::Encryption)
+}
+
+/// Whether values of the column family `name` are encrypted.
+pub fn is_encrypted_scope(name: &str) -> bool {
+    ENCRYPTED_SCOPES.contains(&name) || name.starts_with(PRIVATE_SCOPE_PREFIX)
+}
+
+/// The data key version a stored value is sealed under, `None` if it is
+/// plaintext.
+pub fn envelope_version(stored: &[u8]) -> Option<u32> {
+    if stored.len() < HEADER_LEN + TAG_LEN || stored[..MAGIC.len()] != MAGIC {
+        return None;
+    }
+    let version: [u8; 4] = stored[MAGIC.len()..MAGIC.len() + 4].try_into().ok()?;
+    Some(u32::from_be_bytes(version))
+}
+
+/// Key of a wrapped data key in [`KEYS_TABLE`]: `{scope}\0{version:010}`.
+pub fn data_key_record_key(scope: &str, version: u32) -> Vec<u8> {
+    let mut key = Vec::with_capacity(scope.len() + 11);
+    key.extend_from_slice(scope.as_bytes());
+    key.push(0x00);
+    key.extend_from_slice(format!("{version:010}").as_bytes());
+    key
+}
+
+/// Meta key marking `scope` as holding values a re-encryption pass has to
+/// rewrite.
+pub fn pending_marker_key(scope: &str) -> Vec<u8> {
+    format!("reencrypt_pending:{scope}").into_bytes()
+}
+
+/// Scope of a [`pending_marker_key`], if `key` is one.
+pub fn parse_pending_marker(key: &[u8]) -> Option<String> {
+    let scope = key.strip_prefix(b"reencrypt_pending:".as_slice())?;
+    String::from_utf8(scope.to_vec()).ok()
+}
+
+/// Meta key recording that a re-encryption pass has sealed every value of
+/// `scope`; plaintext is refused there from then on.
+pub fn sealed_marker_key(scope: &str) -> Vec<u8> {
+    format!("reencrypt_done:{scope}").into_bytes()
+}
+
+/// Scope of a [`sealed_marker_key`], if `key` is one.
+pub fn parse_sealed_marker(key: &[u8]) -> Option<String> {
+    let scope = key.strip_prefix(b"reencrypt_done:".as_slice())?;
+    String::from_utf8(scope.to_vec()).ok()
+}
+
+fn value_aad(scope: &str, key: &[u8]) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(scope.len() + 1 + key.len());
+    aad.extend_from_slice(scope.as_bytes());
+    aad.push(0x00);
+    aad.extend_from_slice(key);
+    aad
+}
+
+// ── Key-encryption keys ─────────────────────────────────────────────────────
+
+/// An ML-KEM-768 key pair that wraps the data keys.
+pub trait KeyEncryptionKey: Send + Sync {
+    /// Identifier recorded with every data key it wraps.
+    fn id(&self) -> &str;
+
+    fn public_key(&self) -> &MlKemPublicKey;
+
+    /// Recover the shared secret of a wrapped data key.
+    fn decapsulate(&self, ciphertext: &MlKemCiphertext) -> StorageResult<MlKemSharedSecret>;
+}
+
+/// Identifier of a KEK: the first 8 bytes of SHA3-256 of its public key, hex.
+pub fn kek_id(public_key: &MlKemPublicKey) -> StorageResult<String> {
+    ensure_approved()?;
+    let digest = api::sha3_256(public_key.as_bytes()).map_err(crypto_err)?;
+    Ok(hex::encode(&digest.0[..8]))
+}
+
+/// The public half of an ML-KEM-768 private key, which embeds it.
+pub fn mlkem_public_key(private_key: &MlKemPrivateKey) -> StorageResult<MlKemPublicKey> {
+    if private_key.0.len() != MLKEM768_DK_LEN {
+        return Err(StorageError::Encryption(format!(
+            "ML-KEM-768 private key must be {MLKEM768_DK_LEN} bytes"
+        )));
+    }
+    Ok(MlKemPublicKey(private_key.0[MLKEM768_EK_RANGE].to_vec()))
+}
+
+/// An ML-KEM-768 KEK held in memory, loaded from a file
+/// (`STORAGE_KEK_PATH`) or from the HSM.
+pub struct MlKemKek {
+    id: String,
+    public_key: MlKemPublicKey,
+    private_key: MlKemPrivateKey,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KekFile {
+    algorithm: String,
+    private_key: String,
+}
+
+impl MlKemKek {
+    /// A KEK around an existing ML-KEM-768 private key.
+    pub fn from_private_key(private_key: MlKemPrivateKey) -> StorageResult<Self> {
+        let public_key = mlkem_public_key(&private_key)?;
+        private_key.mlock();
+        Ok(Self {
+            id: kek_id(&public_key)?,
+            public_key,
+            private_key,
+        })
+    }
+
+    /// A fresh KEK that only lives in memory.
+    pub fn generate() -> StorageResult<Self> {
+        ensure_approved()?;
+        let pair = api::generate_mlkem_keypair().map_err(crypto_err)?;
+        Self::from_private_key(pair.private_key)
+    }
+
+    /// Read the KEK at `path`, or generate one and write it there with
+    /// mode 0600. Keep the file off the volume that holds the data.
+    pub fn load_or_create(path: &Path) -> StorageResult<Self> {
+        if path.exists() {
+            let raw = Zeroizing::new(std::fs::read(path)?);
+            let file: KekFile = serde_json::from_slice(&raw)
+                .map_err(|e| StorageError::DeserializationError(e.to_string()))?;
+            if file.algorithm != "ML-KEM-768" {
+                return Err(StorageError::Encryption(format!(
+                    "unsupported KEK algorithm {}",
+                    file.algorithm
+                )));
+            }
+            let hex_key = Zeroizing::new(file.private_key);
+            let bytes = hex::decode(hex_key.as_str())
+                .map_err(|e| StorageError::DeserializationError(e.to_string()))?;
+            return Self::from_private_key(MlKemPrivateKey(bytes));
+        }
+
+        let kek = Self::generate()?;
+        let file = KekFile {
+            algorithm: "ML-KEM-768".to_string(),
+            private_key: hex::encode(&kek.private_key.0),
+        };
+        let encoded = Zeroizing::new(
+            serde_json::to_vec_pretty(&file)
+                .map_err(|e| StorageError::SerializationError(e.to_string()))?,
+        );
+        drop(Zeroizing::new(file.private_key));
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        std::io::Write::write_all(&mut options.open(path)?, &encoded)?;
+        log::info!("storage KEK {} created at {}", kek.id, path.display());
+        Ok(kek)
+    }
+}
+
+impl KeyEncryptionKey for MlKemKek {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn public_key(&self) -> &MlKemPublicKey {
+        &self.public_key
+    }
+
+    fn decapsulate(&self, ciphertext: &MlKemCiphertext) -> StorageResult<MlKemSharedSecret> {
+        api::mlkem_decapsulate(&self.private_key, ciphertext).map_err(crypto_err)
+    }
+}
+
+/// The KEK configured by the environment, `None` when encryption is off.
+///
+/// `STORAGE_ENCRYPTION=file` uses the KEK file at `STORAGE_KEK_PATH`
+/// (default `./keys/storage-kek.json`), created on first start;
+/// `STORAGE_ENCRYPTION=hsm` loads it from the HSM (`HSM_*` variables).
+pub fn kek_from_env() -> StorageResult<Option<Arc<dyn KeyEncryptionKey>>> {
+    match std::env::var("STORAGE_ENCRYPTION")
+        .unwrap_or_default()
+        .as_str()
+    {
+        "" | "off" => Ok(None),
+        "file" => {
+            let path = std::env::var("STORAGE_KEK_PATH")
+                .unwrap_or_else(|_| "./keys/storage-kek.json".to_string());
+            Ok(Some(Arc::new(MlKemKek::load_or_create(Path::new(&path))?)))
+        }
+        "hsm" => {
+            let config = crate::identity::hsm::HsmConfig::from_env()
+                .map_err(|e| StorageError::Encryption(e.to_string()))?;
+            let kek = crate::identity::hsm::HsmKek::new(&config)
+                .map_err(|e| StorageError::Encryption(e.to_string()))?;
+            Ok(Some(Arc::new(kek)))
+        }
+        other => Err(StorageError::Encryption(format!(
+            "STORAGE_ENCRYPTION must be off, file or hsm, not {other}"
+        ))),
+    }
+}
+
+// ── Data keys ───────────────────────────────────────────────────────────────
+
+/// A data key wrapped under a KEK, as kept in [`KEYS_TABLE`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WrappedDataKey {
+    pub scope: String,
+    pub version: u32,
+    pub kek_id: String,
+    /// ML-KEM-768 ciphertext encapsulated to the KEK, hex.
+    pub kem_ciphertext: String,
+    /// `nonce || AES-256-GCM(data key)` under the key derived from the
+    /// shared secret, with `{scope}\0{version}` as associated data, hex.
+    pub wrapped_key: String,
+    pub created_at: u64,
+}
+
+fn wrap_aad(scope: &str, version: u32) -> Vec<u8> {
+    format!("{scope}\0{version}").into_bytes()
+}
+
+fn wrapping_key(shared: &MlKemSharedSecret) -> StorageResult<Zeroizing<[u8; 32]>> {
+    let digest = api::hmac_sha3_256(shared.as_bytes(), WRAP_LABEL).map_err(crypto_err)?;
+    Ok(Zeroizing::new(digest.0))
+}
+
+impl WrappedDataKey {
+    fn wrap(
+        kek: &dyn KeyEncryptionKey,
+        scope: &str,
+        version: u32,
+        data_key: &[u8],
+    ) -> StorageResult<Self> {
+        let (ciphertext, shared) = api::mlkem_encapsulate(kek.public_key()).map_err(crypto_err)?;
+        let key = wrapping_key(&shared)?;
+        let nonce = api::random_bytes(NONCE_LEN).map_err(crypto_err)?;
+        let sealed = api::aes256gcm_seal(&*key, &nonce, &wrap_aad(scope, version), data_key)
+            .map_err(crypto_err)?;
+        Ok(Self {
+            scope: scope.to_string(),
+            version,
+            kek_id: kek.id().to_string(),
+            kem_ciphertext: hex::encode(ciphertext.as_bytes()),
+            wrapped_key: hex::encode([nonce, sealed].concat()),
+            created_at: now_secs(),
+        })
+    }
+
+    fn unwrap(&self, kek: &dyn KeyEncryptionKey) -> StorageResult<Zeroizing<Vec<u8>>> {
+        if self.kek_id != kek.id() {
+            return Err(StorageError::Encryption(format!(
+                "data key {} v{} is wrapped by KEK {}, this node has KEK {}",
+                self.scope,
+                self.version,
+                self.kek_id,
+                kek.id()
+            )));
+        }
+        let decode =
+            |s: &str| hex::decode(s).map_err(|e| StorageError::DeserializationError(e.to_string()));
+        let shared = kek.decapsulate(&MlKemCiphertext(decode(&self.kem_ciphertext)?))?;
+        let key = wrapping_key(&shared)?;
+        let wrapped = decode(&self.wrapped_key)?;
+        if wrapped.len() < NONCE_LEN {
+            return Err(StorageError::DataCorrupted(format!(
+                "wrapped data key {} v{} is truncated",
+                self.scope, self.version
+            )));
+        }
+        let (nonce, sealed) = wrapped.split_at(NONCE_LEN);
+        let data_key =
+            api::aes256gcm_open(&*key, nonce, &wrap_aad(&self.scope, self.version), sealed)
+                .map_err(crypto_err)?;
+        Ok(Zeroizing::new(data_key))
+    }
+}
+
+#[derive(Default)]
+struct ScopeKeys {
+    current: u32,
+    keys: BTreeMap<u32, Zeroizing<Vec<u8>>>,
+}
+
+/// Seals and opens the values of covered scopes with their data keys.
+pub struct StorageCipher {
+    kek: Arc<dyn KeyEncryptionKey>,
+    scopes: RwLock<HashMap<String, ScopeKeys>>,
+    /// Scopes a re-encryption pass has finished, which hold no plaintext.
+    sealed: RwLock<HashSet<String>>,
+    /// Held while a data key is created, so a version is never issued twice.
+    create: Mutex<()>,
+}
+
+impl StorageCipher {
+    /// Unwrap `wrapped` (the content of [`KEYS_TABLE`]) with `kek`.
+    pub fn new(kek: Arc<dyn KeyEncryptionKey>, wrapped: &[WrappedDataKey]) -> StorageResult<Self> {
+        ensure_approved()?;
+        let mut scopes: HashMap<String, ScopeKeys> = HashMap::new();
+        for record in wrapped {
+            let key = record.unwrap(kek.as_ref())?;
+            let entry = scopes.entry(record.scope.clone()).or_default();
+            entry.current = entry.current.max(record.version);
+            entry.keys.insert(record.version, key);
+        }
+        Ok(Self {
+            kek,
+            scopes: RwLock::new(scopes),
+            sealed: RwLock::new(HashSet::new()),
+            create: Mutex::new(()),
+        })
+    }
+
+    pub fn kek_id(&self) -> &str {
+        self.kek.id()
+    }
+
+    /// Version new writes to `scope` are sealed under.
+    pub fn current_version(&self, scope: &str) -> Option<u32> {
+        self.scopes
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(scope)
+            .map(|keys| keys.current)
+    }
+
+    /// Create the first data key of `scope` unless it has one. `persist`
+    /// stores the wrapped key and must succeed before the key is used.
+    /// Returns whether a key was created.
+    pub fn ensure_data_key(
+        &self,
+        scope: &str,
+        persist: impl FnOnce(&WrappedDataKey) -> StorageResult<()>,
+    ) -> StorageResult<bool> {
+        if self.current_version(scope).is_some() {
+            return Ok(false);
+        }
+        let _guard = self.create.lock().unwrap_or_else(|e| e.into_inner());
+        if self.current_version(scope).is_some() {
+            return Ok(false);
+        }
+        self.install_new_key(scope, 1, persist)?;
+        Ok(true)
+    }
+
+    /// Refuse plaintext in `scope` from now on, once a re-encryption pass
+    /// has sealed all of it.
+    pub fn mark_sealed(&self, scope: &str) {
+        self.sealed
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(scope.to_string());
+    }
+
+    /// Whether `scope` was recorded as sealed.
+    pub fn is_sealed(&self, scope: &str) -> bool {
+        self.sealed
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(scope)
+    }
+
+    /// Make a fresh data key current for `scope`; values under the older
+    /// keys stay readable until re-encrypted. Returns the new version.
+    pub fn rotate(
+        &self,
+        scope: &str,
+        persist: impl FnOnce(&WrappedDataKey) -> StorageResult<()>,
+    ) -> StorageResult<u32> {
+        let _guard = self.create.lock().unwrap_or_else(|e| e.into_inner());
+        let version = self.current_version(scope).unwrap_or(0) + 1;
+        self.install_new_key(scope, version, persist)?;
+        Ok(version)
+    }
+
+    fn install_new_key(
+        &self,
+        scope: &str,
+        version: u32,
+        persist: impl FnOnce(&WrappedDataKey) -> StorageResult<()>,
+    ) -> StorageResult<()> {
+        let data_key = Zeroizing::new(api::random_bytes(KEY_LEN).map_err(crypto_err)?);
+        let wrapped = WrappedDataKey::wrap(self.kek.as_ref(), scope, version, &data_key)?;
+        persist(&wrapped)?;
+        let mut scopes = self.scopes.write().unwrap_or_else(|e| e.into_inner());
+        let entry = scopes.entry(scope.to_string()).or_default();
+        entry.current = version;
+        entry.keys.insert(version, data_key);
+        log::info!("storage data key {scope} v{version} created");
+        Ok(())
+    }
+
+    /// Seal `plaintext`, stored under `key` in `scope`, with the current
+    /// data key of `scope`.
+    pub fn encrypt(&self, scope: &str, key: &[u8], plaintext: &[u8]) -> StorageResult<Vec<u8>> {
+        let scopes = self.scopes.read().unwrap_or_else(|e| e.into_inner());
+        let keys = scopes
+            .get(scope)
+            .ok_or_else(|| StorageError::Encryption(format!("no data key for {scope}")))?;
+        let data_key = &keys.keys[&keys.current];
+        let nonce = api::random_bytes(NONCE_LEN).map_err(crypto_err)?;
+        let sealed = api::aes256gcm_seal(data_key, &nonce, &value_aad(scope, key), plaintext)
+            .map_err(crypto_err)?;
+
+        let mut out = Vec::with_capacity(HEADER_LEN + sealed.len());
+        out.extend_from_slice(&MAGIC);
+        out.extend_from_slice(&keys.current.to_be_bytes());
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&sealed);
+        Ok(out)
+    }
+
+    /// Open a value read from `key` in `scope`; plaintext passes through
+    /// unless the scope is sealed.
+    pub fn decrypt(&self, scope: &str, key: &[u8], stored: &[u8]) -> StorageResult<Vec<u8>> {
+        let Some(version) = envelope_version(stored) else {
+            self.check_plaintext(scope, key)?;
+            return Ok(stored.to_vec());
+        };
+        let scopes = self.scopes.read().unwrap_or_else(|e| e.into_inner());
+        let data_key = scopes
+            .get(scope)
+            .and_then(|keys| keys.keys.get(&version))
+            .ok_or_else(|| StorageError::Encryption(format!("no data key {scope} v{version}")))?;
+        let nonce = &stored[MAGIC.len() + 4..HEADER_LEN];
+        api::aes256gcm_open(
+            data_key,
+            nonce,
+            &value_aad(scope, key),
+            &stored[HEADER_LEN..],
+        )
+        .map_err(|e| {
+            StorageError::Encryption(format!("{scope}/{}: {e}", String::from_utf8_lossy(key)))
+        })
+    }
+
+    fn check_plaintext(&self, scope: &str, key: &[u8]) -> StorageResult<()> {
+        if self.is_sealed(scope) {
+            return Err(StorageError::DataCorrupted(format!(
+                "{scope}/{}: unsealed value in a sealed scope",
+                String::from_utf8_lossy(key)
+            )));
+        }
+        Ok(())
+    }
+
+    /// The value re-sealed under the current key, or `None` if it already is.
+    pub fn reseal(&self, scope: &str, key: &[u8], stored: &[u8]) -> StorageResult<Option<Vec<u8>>> {
+        let current = self.current_version(scope);
+        if current.is_some() && envelope_version(stored) == current {
+            return Ok(None);
+        }
+        let plaintext = Zeroizing::new(self.decrypt(scope, key, stored)?);
+        self.encrypt(scope, key, &plaintext).map(Some)
+    }
+}
+
+/// Seal `value` for `scope` if a cipher is set and the scope is covered.
+pub fn seal_value<'a>(
+    cipher: Option<&StorageCipher>,
+    scope: &str,
+    key: &[u8],
+    value: &'a [u8],
+) -> StorageResult<Cow<'a, [u8]>> {
+    match cipher {
+        Some(cipher) if is_encrypted_scope(scope) => {
+            Ok(Cow::Owned(cipher.encrypt(scope, key, value)?))
+        }
+        _ => Ok(Cow::Borrowed(value)),
+    }
+}
+
+/// Open a value read from `scope`. Without a cipher, sealed values are an
+/// error rather than bytes handed to a deserializer.
+pub fn open_value<'a>(
+    cipher: Option<&StorageCipher>,
+    scope: &str,
+    key: &[u8],
+    stored: &'a [u8],
+) -> StorageResult<Cow<'a, [u8]>> {
+    if !is_encrypted_scope(scope) {
+        return Ok(Cow::Borrowed(stored));
+    }
+    if envelope_version(stored).is_none() {
+        if let Some(cipher) = cipher {
+            cipher.check_plaintext(scope, key)?;
+        }
+        return Ok(Cow::Borrowed(stored));
+    }
+    match cipher {
+        Some(cipher) => Ok(Cow::Owned(cipher.decrypt(scope, key, stored)?)),
+        None => Err(StorageError::Encryption(format!(
+            "{scope} is encrypted; start the node with its STORAGE_ENCRYPTION key"
+        ))),
+    }
+}
+
+// ── Coverage and re-encryption ──────────────────────────────────────────────
+
+/// How the values of one scope are stored.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ScopeCoverage {
+    pub scope: String,
+    /// Newest data key version, `None` before the first key.
+    pub key_version: Option<u32>,
+    /// Values sealed under `key_version`.
+    pub current: u64,
+    /// Values sealed under an older version.
+    pub stale: u64,
+    pub plaintext: u64,
+}
+
+impl ScopeCoverage {
+    pub fn new(scope: &str, key_version: Option<u32>) -> Self {
+        Self {
+            scope: scope.to_string(),
+            key_version,
+            ..Self::default()
+        }
+    }
+
+    /// Count one stored value.
+    pub fn count(&mut self, stored: &[u8]) {
+        match envelope_version(stored) {
+            None => self.plaintext += 1,
+            Some(v) if Some(v) == self.key_version => self.current += 1,
+            Some(_) => self.stale += 1,
+        }
+    }
+}
+
+/// Encryption coverage of a store.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CoverageReport {
+    /// Whether this store seals new writes.
+    pub enabled: bool,
+    /// KEK the data keys are wrapped under.
+    pub kek_id: Option<String>,
+    pub scopes: Vec<ScopeCoverage>,
+    /// Scopes a re-encryption pass still has to go over.
+    pub pending: Vec<String>,
+}
+
+impl CoverageReport {
+    /// Every value is sealed under its scope's current key.
+    pub fn is_complete(&self) -> bool {
+        self.scopes.iter().all(|s| s.stale == 0 && s.plaintext == 0)
+    }
+}
+
+/// Newest version per scope, and the KEK, of the wrapped keys in a store.
+pub fn key_versions(records: &[WrappedDataKey]) -> (BTreeMap<String, u32>, Option<String>) {
+    let mut versions = BTreeMap::new();
+    for record in records {
+        let v = versions.entry(record.scope.clone()).or_insert(0);
+        *v = (*v).max(record.version);
+    }
+    (versions, records.first().map(|r| r.kek_id.clone()))
+}
+
+/// Outcome of one [`EncryptedStore::reencrypt`] step.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReencryptStep {
+    pub scanned: usize,
+    pub rewritten: usize,
+    /// Where the next step starts; `None` once the scope is done.
+    pub resume_after: Option<Vec<u8>>,
+}
+
+/// A store that encrypts covered values at rest.
+pub trait EncryptedStore: Send + Sync {
+    /// Covered scopes present in the store.
+    fn encrypted_scopes(&self) -> StorageResult<Vec<String>>;
+
+    /// Count the values of every covered scope by how they are stored.
+    /// Needs no KEK.
+    fn encryption_coverage(&self) -> StorageResult<CoverageReport>;
+
+    /// Seal new writes to `scope` under a fresh data key and mark the scope
+    /// for re-encryption. Returns the new version.
+    fn rotate_data_key(&self, scope: &str) -> StorageResult<u32>;
+
+    /// Scopes marked for re-encryption.
+    fn pending_reencryption(&self) -> StorageResult<Vec<String>>;
+
+    /// Re-seal plaintext and stale values among up to `limit` entries of
+    /// `scope` after the key `after`. The last step clears the mark and
+    /// records the scope as sealed.
+    fn reencrypt(
+        &self,
+        scope: &str,
+        after: Option<&[u8]>,
+        limit: usize,
+    ) -> StorageResult<ReencryptStep>;
+}
+
+/// Re-encrypt `scope` to the end; returns the values rewritten.
+pub fn reencrypt_scope(store: &dyn EncryptedStore, scope: &str) -> StorageResult<u64> {
+    let mut cursor: Option<Vec<u8>> = None;
+    let mut rewritten = 0u64;
+    loop {
+        let step = store.reencrypt(scope, cursor.as_deref(), REENCRYPT_BATCH)?;
+        rewritten += step.rewritten as u64;
+        match step.resume_after {
+            Some(next) => cursor = Some(next),
+            None => return Ok(rewritten),
+        }
+        // Leave room for the node's own writes between steps.
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+}
+
+/// Re-encrypt `scopes` on a background thread.
+pub fn spawn_reencryption(
+    store: Arc<dyn EncryptedStore>,
+    scopes: Vec<String>,
+) -> std::io::Result<std::thread::JoinHandle<()>> {
+    std::thread::Builder::new()
+        .name("storage-reencrypt".to_string())
+        .spawn(move || {
+            for scope in scopes {
+                match reencrypt_scope(store.as_ref(), &scope) {
+                    Ok(0) => {}
+                    Ok(n) => log::info!("storage encryption: re-encrypted {n} values in {scope}"),
+                    Err(e) => log::error!("storage encryption: re-encrypting {scope} failed: {e}"),
+                }
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cipher() -> StorageCipher {
+        StorageCipher::new(Arc::new(MlKemKek::generate().unwrap()), &[]).unwrap()
+    }
+
+    #[test]
+    fn values_round_trip_and_bind_to_their_key() {
+        let c = cipher();
+        c.ensure_data_key("world_state", |_| Ok(())).unwrap();
+        let sealed = c.encrypt("world_state", b"k1", b"secret").unwrap();
+        assert_eq!(envelope_version(&sealed), Some(1));
+        assert!(!sealed.windows(6).any(|w| w == b"secret"));
+        assert_eq!(c.decrypt("world_state", b"k1", &sealed).unwrap(), b"secret");
+        assert!(c.decrypt("world_state", b"k2", &sealed).is_err());
+        assert!(c.decrypt("vault", b"k1", &sealed).is_err());
+        // Plaintext written before encryption passes through.
+        assert_eq!(c.decrypt("world_state", b"k1", b"{}").unwrap(), b"{}");
+    }
+
+    #[test]
+    fn sealed_scopes_refuse_plaintext() {
+        let c = cipher();
+        c.ensure_data_key("vault", |_| Ok(())).unwrap();
+        let sealed = c.encrypt("vault", b"did", b"wallet").unwrap();
+        c.mark_sealed("vault");
+        assert_eq!(c.decrypt("vault", b"did", &sealed).unwrap(), b"wallet");
+        assert!(matches!(
+            c.decrypt("vault", b"did", b"{}"),
+            Err(StorageError::DataCorrupted(_))
+        ));
+        assert!(open_value(Some(&c), "vault", b"did", b"{}").is_err());
+        assert_eq!(c.decrypt("blocks", b"1", b"{}").unwrap(), b"{}");
+    }
+
+    #[test]
+    fn wrapped_keys_reload_and_rotate() {
+        let kek: Arc<dyn KeyEncryptionKey> = Arc::new(MlKemKek::generate().unwrap());
+        let mut records = Vec::new();
+        let c = StorageCipher::new(kek.clone(), &[]).unwrap();
+        assert!(c
+            .ensure_data_key("vault", |w| {
+                records.push(w.clone());
+                Ok(())
+            })
+            .unwrap());
+        assert!(!c.ensure_data_key("vault", |_| unreachable!()).unwrap());
+        let old = c.encrypt("vault", b"did", b"wallet").unwrap();
+        let v2 = c
+            .rotate("vault", |w| {
+                records.push(w.clone());
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(v2, 2);
+
+        let reloaded = StorageCipher::new(kek, &records).unwrap();
+        assert_eq!(reloaded.current_version("vault"), Some(2));
+        assert_eq!(reloaded.decrypt("vault", b"did", &old).unwrap(), b"wallet");
+        let resealed = reloaded.reseal("vault", b"did", &old).unwrap().unwrap();
+        assert_eq!(envelope_version(&resealed), Some(2));
+        assert!(reloaded
+            .reseal("vault", b"did", &resealed)
+            .unwrap()
+            .is_none());
+        assert!(reloaded
+            .reseal("vault", b"did", b"plain")
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn another_kek_cannot_unwrap() {
+        let mut records = Vec::new();
+        let c = cipher();
+        c.ensure_data_key("blocks", |w| {
+            records.push(w.clone());
+            Ok(())
+        })
+        .unwrap();
+        let other: Arc<dyn KeyEncryptionKey> = Arc::new(MlKemKek::generate().unwrap());
+        assert!(matches!(
+            StorageCipher::new(other, &records),
+            Err(StorageError::Encryption(_))
+        ));
+    }
+
+    #[test]
+    fn failed_persist_leaves_no_key() {
+        let c = cipher();
+        assert!(c
+            .ensure_data_key("audit_log", |_| Err(StorageError::Other(
+                "disk full".into()
+            )))
+            .is_err());
+        assert_eq!(c.current_version("audit_log"), None);
+        assert!(c.encrypt("audit_log", b"k", b"v").is_err());
+    }
+
+    #[test]
+    fn kek_file_is_created_once_and_reloaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys").join("kek.json");
+        let first = MlKemKek::load_or_create(&path).unwrap();
+        let second = MlKemKek::load_or_create(&path).unwrap();
+        assert_eq!(first.id(), second.id());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        // The public key derived from the private key encapsulates to it.
+        let (ct, ss) = api::mlkem_encapsulate(second.public_key()).unwrap();
+        assert_eq!(first.decapsulate(&ct).unwrap().as_bytes(), ss.as_bytes());
+    }
+
+    #[test]
+    fn open_value_refuses_sealed_bytes_without_a_cipher() {
+        let c = cipher();
+        c.ensure_data_key("key_history", |_| Ok(())).unwrap();
+        let sealed = seal_value(Some(&c), "key_history", b"k", b"v").unwrap();
+        assert!(open_value(None, "key_history", b"k", &sealed).is_err());
+        assert_eq!(
+            &*open_value(Some(&c), "key_history", b"k", &sealed).unwrap(),
+            b"v"
+        );
+        // Uncovered scopes are left alone.
+        assert_eq!(&*seal_value(Some(&c), "meta", b"k", b"v").unwrap(), b"v");
+    }
+
+    #[test]
+    fn coverage_counts_by_version() {
+        let c = cipher();
+        c.ensure_data_key("blocks", |_| Ok(())).unwrap();
+        let v1 = c.encrypt("blocks", b"1", b"a").unwrap();
+        c.rotate("blocks", |_| Ok(())).unwrap();
+        let v2 = c.encrypt("blocks", b"2", b"b").unwrap();
+        let mut coverage = ScopeCoverage::new("blocks", Some(2));
+        for stored in [&v1[..], &v2[..], b"{\"height\":3}"] {
+            coverage.count(stored);
+        }
+        assert_eq!(
+            (coverage.current, coverage.stale, coverage.plaintext),
+            (1, 1, 1)
+        );
+        assert!(is_encrypted_scope("private_col1"));
+        assert!(!is_encrypted_scope("pvt_ttl"));
+    }
+}
@@ -12,6 +12,10 @@ pub enum StorageError {
     #[error("RocksDB error: {0}")]
     RocksDbError(String),
 
+    /// redb operation failed
+    #[error("redb error: {0}")]
+    RedbError(String),
+
     /// Serialization error (CBOR/bincode)
     #[error("Serialization error: {0}")]
     SerializationError(String),
@@ -39,6 +43,18 @@ pub enum StorageError {
     #[error("Transaction validation failed: {0}")]
     TransactionValidationFailed(String),
 
+    /// Transaction exceeds the channel's absolute block size limit
+    #[error("Transaction {tx_id} is {size} bytes, above the {limit}-byte limit")]
+    TransactionTooLarge {
+        tx_id: String,
+        size: usize,
+        limit: usize,
+    },
+
+    /// Transaction refused by the orderer's admission filters
+    #[error("Transaction rejected: {0}")]
+    AdmissionRejected(crate::ordering::admission::AdmissionError),
+
     /// Identity record not found
     #[error("Identity record not found: {0}")]
     IdentityNotFound(String),
@@ -73,6 +89,10 @@ pub enum StorageError {
     #[error("I/O error: {0}")]
     IoError(#[from] std::io::Error),
 
+    /// Encrypting or decrypting a value at rest failed
+    #[error("Encryption error: {0}")]
+    Encryption(String),
+
     /// Invalid channel identifier (must be non-empty alphanumeric/hyphen/underscore)
     #[error("Invalid channel id: '{0}'")]
     InvalidChannelId(String),
@@ -0,0 +1,1126 @@
+//! Secondary indexes over JSON world-state values.
+//!
+//! An index covers one or more fields of the JSON documents stored as state
+//! values, named by dotted paths (`owner`, `asset.color`). Its entries sort by
+//! the field values, so equality and range selectors on indexed fields become
+//! a bounded scan instead of a pass over every key.
+//!
+//! Definitions come from two places:
+//!
+//! - a chaincode package, as a `rustbc.indexes` Wasm custom section holding a
+//!   JSON array of definitions, registered when the package is installed;
+//! - `POST /api/v1/admin/indexes`, registered per channel.
+//!
+//! Entry keys are the encoded field values followed by the state key. Scalars
+//! encode as a type tag (null < false < true < number < string), a payload
+//! that sorts like the value, and a `0x00` terminator. The index is sparse:
+//! values that are not JSON objects, or lack a scalar at an indexed field,
+//! have no entry — and no selector on that field can match them either.
+//!
+//! `RocksDbBlockStore` implements [`StateIndexStore`] with one column family
+//! per index, updated in the same batch as the state write.
+
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::errors::{StorageError, StorageResult};
+use super::world_state::{VersionedValue, WorldState};
+
+/// Wasm custom section in which a chaincode package ships its indexes.
+pub const PACKAGE_SECTION: &str = "rustbc.indexes";
+
+/// Most fields a single index may cover.
+pub const MAX_INDEX_FIELDS: usize = 8;
+
+/// Exclusive upper bound of the state key space, for full scans.
+pub(crate) const KEY_SPACE_END: &str = "\u{10ffff}";
+
+// Type tags, in collation order.
+const TAG_NULL: u8 = b'0';
+const TAG_FALSE: u8 = b'1';
+const TAG_TRUE: u8 = b'2';
+const TAG_NUMBER: u8 = b'3';
+const TAG_STRING: u8 = b'4';
+
+const TERMINATOR: u8 = 0x00;
+
+/// A secondary index over fields of JSON state values.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexDefinition {
+    /// Unique per channel: letters, digits, `-` and `_`.
+    pub name: String,
+    /// Dotted field paths, most significant first.
+    pub fields: Vec<String>,
+    /// Chaincode whose package shipped the index; `None` if an admin
+    /// registered it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chaincode_id: Option<String>,
+}
+
+impl IndexDefinition {
+    pub fn new(name: &str, fields: &[&str]) -> Self {
+        Self {
+            name: name.to_string(),
+            fields: fields.iter().map(|f| f.to_string()).collect(),
+            chaincode_id: None,
+        }
+    }
+
+    pub fn validate(&self) -> StorageResult<()> {
+        if self.name.is_empty()
+            || self.name.len() > 64
+            || !self
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(StorageError::Other(format!(
+                "invalid index name '{}'",
+                self.name
+            )));
+        }
+        if self.fields.is_empty() || self.fields.len() > MAX_INDEX_FIELDS {
+            return Err(StorageError::Other(format!(
+                "index '{}' must cover 1 to {MAX_INDEX_FIELDS} fields",
+                self.name
+            )));
+        }
+        if let Some(field) = self.fields.iter().find(|f| !valid_path(f)) {
+            return Err(StorageError::Other(format!(
+                "index '{}' has an invalid field path '{field}'",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Entry key for state `key` holding `data`, or `None` if the value is
+    /// not a JSON object with a scalar at every indexed field.
+    pub fn entry_key(&self, key: &str, data: &[u8]) -> Option<Vec<u8>> {
+        let doc: Value = serde_json::from_slice(data).ok()?;
+        let mut entry = Vec::new();
+        for field in &self.fields {
+            encode_scalar(lookup(&doc, field)?, &mut entry)?;
+        }
+        entry.extend_from_slice(key.as_bytes());
+        Some(entry)
+    }
+}
+
+/// Consistency of an index with the world state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexReport {
+    pub name: String,
+    /// Entries in the index.
+    pub entries: u64,
+    /// State values that should have an entry but have none.
+    pub missing: u64,
+    /// Entries that no current state value accounts for.
+    pub stale: u64,
+}
+
+impl IndexReport {
+    pub fn is_consistent(&self) -> bool {
+        self.missing == 0 && self.stale == 0
+    }
+}
+
+/// Storage for the secondary indexes of one channel's world state.
+pub trait StateIndexStore: Send + Sync {
+    /// Register `def` and build it from the current state. Registering an
+    /// existing name replaces that index.
+    fn register_index(&self, def: &IndexDefinition) -> StorageResult<IndexReport>;
+
+    fn list_indexes(&self) -> StorageResult<Vec<IndexDefinition>>;
+
+    /// Drop every entry of index `name` and build it again from the state.
+    fn rebuild_index(&self, name: &str) -> StorageResult<IndexReport>;
+
+    /// Compare index `name` with the state without changing either.
+    fn verify_index(&self, name: &str) -> StorageResult<IndexReport>;
+
+    /// State keys of the entries of index `name` in `start..end`, in
+    /// index order.
+    fn scan_index(&self, name: &str, start: &[u8], end: &[u8]) -> StorageResult<Vec<String>>;
+}
+
+/// Index definitions in the `rustbc.indexes` section of a chaincode package,
+/// tagged with `chaincode_id`. A package without the section has none, and
+/// so does a malformed module, which sandbox validation rejects anyway.
+pub fn indexes_from_package(
+    wasm: &[u8],
+    chaincode_id: &str,
+) -> StorageResult<Vec<IndexDefinition>> {
+    for payload in wasmparser::Parser::new(0).parse_all(wasm) {
+        let Ok(payload) = payload else {
+            break;
+        };
+        if let wasmparser::Payload::CustomSection(reader) = payload {
+            if reader.name() != PACKAGE_SECTION {
+                continue;
+            }
+            let mut defs: Vec<IndexDefinition> =
+                serde_json::from_slice(reader.data()).map_err(|e| {
+                    StorageError::DeserializationError(format!("{PACKAGE_SECTION}: {e}"))
+                })?;
+            for def in &mut defs {
+                def.chaincode_id = Some(chaincode_id.to_string());
+                def.validate()?;
+            }
+            return Ok(defs);
+        }
+    }
+    Ok(Vec::new())
+}
+
+// ── Selectors ────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Eq,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+/// One `field <op> value` test of a selector.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    pub field: String,
+    pub op: Operator,
+    pub value: Value,
+}
+
+impl Condition {
+    /// Values of different types never compare, so `{"$gt": 5}` does not
+    /// match a string.
+    fn matches(&self, doc: &Value) -> bool {
+        let Some(ordering) = lookup(doc, &self.field).and_then(|v| compare(v, &self.value)) else {
+            return false;
+        };
+        match self.op {
+            Operator::Eq => ordering == Ordering::Equal,
+            Operator::Gt => ordering == Ordering::Greater,
+            Operator::Gte => ordering != Ordering::Less,
+            Operator::Lt => ordering == Ordering::Less,
+            Operator::Lte => ordering != Ordering::Greater,
+        }
+    }
+}
+
+/// A rich-query selector: a JSON object mapping field paths to a scalar
+/// (equality) or to an object of `$eq`, `$gt`, `$gte`, `$lt` and `$lte`
+/// operators. All conditions must hold.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Selector {
+    conditions: Vec<Condition>,
+}
+
+/// Bounds of an index scan planned for a selector.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexScan {
+    pub index: String,
+    pub start: Vec<u8>,
+    /// Exclusive.
+    pub end: Vec<u8>,
+}
+
+impl Selector {
+    pub fn parse(selector: &Value) -> StorageResult<Self> {
+        let invalid = |reason: String| StorageError::Other(format!("invalid selector: {reason}"));
+        let fields = selector
+            .as_object()
+            .ok_or_else(|| invalid("expected a JSON object".to_string()))?;
+
+        let mut conditions = Vec::new();
+        for (field, test) in fields {
+            if !valid_path(field) {
+                return Err(invalid(format!("bad field path '{field}'")));
+            }
+            let ops = match test {
+                Value::Object(ops) if ops.is_empty() => {
+                    return Err(invalid(format!("no operators for '{field}'")))
+                }
+                Value::Object(ops) => ops.iter().map(|(op, v)| (op.as_str(), v)).collect(),
+                value => vec![("$eq", value)],
+            };
+            for (op, value) in ops {
+                let op = match op {
+                    "$eq" => Operator::Eq,
+                    "$gt" => Operator::Gt,
+                    "$gte" => Operator::Gte,
+                    "$lt" => Operator::Lt,
+                    "$lte" => Operator::Lte,
+                    other => return Err(invalid(format!("unsupported operator '{other}'"))),
+                };
+                if matches!(value, Value::Array(_) | Value::Object(_)) {
+                    return Err(invalid(format!("'{field}' must be compared with a scalar")));
+                }
+                conditions.push(Condition {
+                    field: field.clone(),
+                    op,
+                    value: value.clone(),
+                });
+            }
+        }
+        Ok(Self { conditions })
+    }
+
+    pub fn conditions(&self) -> &[Condition] {
+        &self.conditions
+    }
+
+    /// Whether the state value `data` satisfies every condition.
+    pub fn matches(&self, data: &[u8]) -> bool {
+        let Ok(doc) = serde_json::from_slice::<Value>(data) else {
+            return false;
+        };
+        self.conditions.iter().all(|c| c.matches(&doc))
+    }
+
+    /// Pick the index that narrows the scan most, if any covers the
+    /// selector. Every field of the index needs a condition, since values
+    /// missing one of them have no entry; the bounds come from equality
+    /// conditions on its leading fields and range conditions on the next.
+    ///
+    /// The scan returns exactly the entries meeting the conditions on the
+    /// fields it used; the remaining conditions still need [`Self::matches`].
+    pub fn plan(&self, indexes: &[IndexDefinition]) -> Option<IndexScan> {
+        let mut best: Option<(usize, IndexScan)> = None;
+        for def in indexes {
+            if let Some((used, scan)) = self.scan_for(def) {
+                if best.as_ref().is_none_or(|(most, _)| used > *most) {
+                    best = Some((used, scan));
+                }
+            }
+        }
+        best.map(|(_, scan)| scan)
+    }
+
+    fn scan_for(&self, def: &IndexDefinition) -> Option<(usize, IndexScan)> {
+        if !def
+            .fields
+            .iter()
+            .all(|field| self.conditions.iter().any(|c| &c.field == field))
+        {
+            return None;
+        }
+        let mut prefix = Vec::new();
+        let mut used = 0;
+        let mut range: (Option<Vec<u8>>, Option<Vec<u8>>) = (None, None);
+
+        for field in &def.fields {
+            let tests: Vec<&Condition> = self
+                .conditions
+                .iter()
+                .filter(|c| &c.field == field)
+                .collect();
+            if let Some(eq) = tests.iter().find(|c| c.op == Operator::Eq) {
+                encode_scalar(&eq.value, &mut prefix)?;
+                used += 1;
+                continue;
+            }
+
+            // A range ends the usable part of the index; defaults keep the
+            // scan within the type of the bound.
+            let (mut lower, mut upper): (Option<Vec<u8>>, Option<Vec<u8>>) = (None, None);
+            for test in tests {
+                let mut bound = prefix.clone();
+                encode_scalar(&test.value, &mut bound)?;
+                let (lo_tag, hi_tag) = tag_class(&test.value);
+                match test.op {
+                    Operator::Gt | Operator::Gte => {
+                        if test.op == Operator::Gt {
+                            bound = successor(bound);
+                        }
+                        lower = lower.max(Some(bound));
+                        upper.get_or_insert_with(|| [prefix.as_slice(), &[hi_tag + 1]].concat());
+                    }
+                    Operator::Lt | Operator::Lte => {
+                        if test.op == Operator::Lte {
+                            bound = successor(bound);
+                        }
+                        upper = Some(match upper {
+                            Some(current) if current < bound => current,
+                            _ => bound,
+                        });
+                        lower.get_or_insert_with(|| [prefix.as_slice(), &[lo_tag]].concat());
+                    }
+                    Operator::Eq => unreachable!("equality handled above"),
+                }
+            }
+            if lower.is_some() {
+                used += 1;
+                range = (lower, upper);
+            }
+            break;
+        }
+
+        if used == 0 {
+            return None;
+        }
+        let (start, end) = match range {
+            (Some(start), Some(end)) => (start, end),
+            _ => (prefix.clone(), successor(prefix)),
+        };
+        Some((
+            used,
+            IndexScan {
+                index: def.name.clone(),
+                start,
+                end,
+            },
+        ))
+    }
+}
+
+/// Result of [`query`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryOutcome {
+    /// Matching entries, in index order when an index was used and in key
+    /// order otherwise.
+    pub entries: Vec<(String, VersionedValue)>,
+    /// Index the lookup used; `None` for a full scan.
+    pub index: Option<String>,
+}
+
+/// Up to `limit` state entries matching `selector`, through an index when
+/// one covers the selector and a full scan of `state` otherwise.
+pub fn query(
+    state: &dyn WorldState,
+    indexes: Option<&dyn StateIndexStore>,
+    selector: &Selector,
+    limit: usize,
+) -> StorageResult<QueryOutcome> {
+    if let Some(store) = indexes {
+        if let Some(scan) = selector.plan(&store.list_indexes()?) {
+            let mut entries = Vec::new();
+            for key in store.scan_index(&scan.index, &scan.start, &scan.end)? {
+                if entries.len() >= limit {
+                    break;
+                }
+                if let Some(value) = state.get(&key)? {
+                    if selector.matches(&value.data) {
+                        entries.push((key, value));
+                    }
+                }
+            }
+            return Ok(QueryOutcome {
+                entries,
+                index: Some(scan.index),
+            });
+        }
+    }
+
+    let entries = state
+        .get_range("", KEY_SPACE_END)?
+        .into_iter()
+        .filter(|(_, value)| selector.matches(&value.data))
+        .take(limit)
+        .collect();
+    Ok(QueryOutcome {
+        entries,
+        index: None,
+    })
+}
+
+// ── Encoding ─────────────────────────────────────────────────────────────────
+
+fn valid_path(path: &str) -> bool {
+    !path.is_empty() && !path.split('.').any(str::is_empty)
+}
+
+fn lookup<'a>(doc: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .try_fold(doc, |value, segment| value.get(segment))
+}
+
+fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Null, Value::Null) => Some(Ordering::Equal),
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        (Value::Number(a), Value::Number(b)) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+/// Lowest and highest tag of the values `value` compares with.
+fn tag_class(value: &Value) -> (u8, u8) {
+    match value {
+        Value::Null => (TAG_NULL, TAG_NULL),
+        Value::Bool(_) => (TAG_FALSE, TAG_TRUE),
+        Value::Number(_) => (TAG_NUMBER, TAG_NUMBER),
+        _ => (TAG_STRING, TAG_STRING),
+    }
+}
+
+/// Append the order-preserving encoding of scalar `value`; `None` for
+/// arrays and objects.
+fn encode_scalar(value: &Value, out: &mut Vec<u8>) -> Option<()> {
+    match value {
+        Value::Null => out.push(TAG_NULL),
+        Value::Bool(false) => out.push(TAG_FALSE),
+        Value::Bool(true) => out.push(TAG_TRUE),
+        Value::Number(n) => {
+            let mut f = n.as_f64()?;
+            if f == 0.0 {
+                // -0.0 equals 0.0, so it must encode the same way.
+                f = 0.0;
+            }
+            let bits = f.to_bits();
+            let ordered = if bits >> 63 == 1 {
+                !bits
+            } else {
+                bits | 1 << 63
+            };
+            out.push(TAG_NUMBER);
+            out.extend_from_slice(format!("{ordered:016x}").as_bytes());
+        }
+        Value::String(s) => {
+            out.push(TAG_STRING);
+            // Escape 0x00 and 0x01 so the terminator stays the lowest byte.
+            for &b in s.as_bytes() {
+                match b {
+                    0x00 => out.extend_from_slice(&[0x01, 0x01]),
+                    0x01 => out.extend_from_slice(&[0x01, 0x02]),
+                    _ => out.push(b),
+                }
+            }
+        }
+        Value::Array(_) | Value::Object(_) => return None,
+    }
+    out.push(TERMINATOR);
+    Some(())
+}
+
+/// Smallest key above every key that starts with `encoded`, which ends in
+/// a terminator.
+fn successor(mut encoded: Vec<u8>) -> Vec<u8> {
+    if let Some(last) = encoded.last_mut() {
+        *last = TERMINATOR + 1;
+    }
+    encoded
+}
+
+#[cfg(feature = "rocksdb-storage")]
+mod rocks {
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, MutexGuard};
+
+    use rocksdb::{BoundColumnFamily, Direction, IteratorMode, Options, WriteBatch};
+
+    use super::{IndexDefinition, IndexReport, StateIndexStore};
+    use crate::storage::adapters::{RocksDbBlockStore, CF_WORLD_STATE};
+    use crate::storage::errors::{StorageError, StorageResult};
+    use crate::storage::world_state::VersionedValue;
+
+    /// Entries staged per write batch while building an index.
+    const BUILD_BATCH: usize = 10_000;
+
+    fn rocks_err(e: rocksdb::Error) -> StorageError {
+        StorageError::RocksDbError(e.to_string())
+    }
+
+    impl RocksDbBlockStore {
+        fn index_cf_name(name: &str) -> String {
+            format!("index_{name}")
+        }
+
+        fn cf_index(&self, name: &str) -> StorageResult<Arc<BoundColumnFamily<'_>>> {
+            let cf_name = Self::index_cf_name(name);
+            self.db
+                .cf_handle(&cf_name)
+                .ok_or(StorageError::ColumnFamilyNotFound(cf_name))
+        }
+
+        pub(crate) fn lock_state_indexes(&self) -> MutexGuard<'_, Vec<IndexDefinition>> {
+            self.state_indexes.lock().unwrap_or_else(|e| e.into_inner())
+        }
+
+        /// Read the index definitions saved by earlier runs.
+        pub(crate) fn load_state_indexes(&self) -> StorageResult<()> {
+            let cf = self.cf_state_index_defs()?;
+            let mut defs = Vec::new();
+            for item in self.db.iterator_cf(&cf, IteratorMode::Start) {
+                let (_, value) = item.map_err(rocks_err)?;
+                let def: IndexDefinition = serde_json::from_slice(&value)
+                    .map_err(|e| StorageError::DeserializationError(e.to_string()))?;
+                defs.push(def);
+            }
+            *self.lock_state_indexes() = defs;
+            Ok(())
+        }
+
+        /// Stage the index changes for `key` going from `old` to `new`.
+        pub(crate) fn stage_index_update(
+            &self,
+            batch: &mut WriteBatch,
+            indexes: &[IndexDefinition],
+            key: &str,
+            old: Option<&VersionedValue>,
+            new: Option<&VersionedValue>,
+        ) -> StorageResult<()> {
+            for def in indexes {
+                let old_entry = old.and_then(|v| def.entry_key(key, &v.data));
+                let new_entry = new.and_then(|v| def.entry_key(key, &v.data));
+                if old_entry == new_entry {
+                    continue;
+                }
+                let cf = self.cf_index(&def.name)?;
+                if let Some(entry) = old_entry {
+                    batch.delete_cf(&cf, entry);
+                }
+                if let Some(entry) = new_entry {
+                    batch.put_cf(&cf, entry, key.as_bytes());
+                }
+            }
+            Ok(())
+        }
+
+        /// Every entry `def` should hold for the current state, with the
+        /// state key it points to.
+        fn expected_entries(
+            &self,
+            def: &IndexDefinition,
+        ) -> StorageResult<BTreeMap<Vec<u8>, String>> {
+            let mut expected = BTreeMap::new();
+            for item in self
+                .db
+                .iterator_cf(&self.cf_world_state()?, IteratorMode::Start)
+            {
+                let (key, value) = item.map_err(rocks_err)?;
+                let value = self.open(CF_WORLD_STATE, &key, &value)?;
+                let key = String::from_utf8(key.to_vec())
+                    .map_err(|e| StorageError::DeserializationError(e.to_string()))?;
+                let value: VersionedValue = serde_json::from_slice(&value)
+                    .map_err(|e| StorageError::DeserializationError(e.to_string()))?;
+                if let Some(entry) = def.entry_key(&key, &value.data) {
+                    expected.insert(entry, key);
+                }
+            }
+            Ok(expected)
+        }
+
+        /// Recreate the CF of `def` and fill it from the state. The caller
+        /// holds the index lock, so no state write lands mid-build.
+        fn build_index(&self, def: &IndexDefinition) -> StorageResult<IndexReport> {
+            let cf_name = Self::index_cf_name(&def.name);
+            if self.db.cf_handle(&cf_name).is_some() {
+                self.db.drop_cf(&cf_name).map_err(rocks_err)?;
+            }
+            self.db
+                .create_cf(&cf_name, &Options::default())
+                .map_err(rocks_err)?;
+
+            let cf = self.cf_index(&def.name)?;
+            let expected = self.expected_entries(def)?;
+            let mut batch = WriteBatch::default();
+            for (entry, key) in &expected {
+                batch.put_cf(&cf, entry, key.as_bytes());
+                if batch.len() >= BUILD_BATCH {
+                    self.db
+                        .write(std::mem::take(&mut batch))
+                        .map_err(rocks_err)?;
+                }
+            }
+            self.db.write(batch).map_err(rocks_err)?;
+
+            Ok(IndexReport {
+                name: def.name.clone(),
+                entries: expected.len() as u64,
+                missing: 0,
+                stale: 0,
+            })
+        }
+    }
+
+    impl StateIndexStore for RocksDbBlockStore {
+        fn register_index(&self, def: &IndexDefinition) -> StorageResult<IndexReport> {
+            def.validate()?;
+            let mut indexes = self.lock_state_indexes();
+            if indexes
+                .iter()
+                .any(|d| d.name == def.name && d.fields == def.fields)
+            {
+                drop(indexes);
+                return self.verify_index(&def.name);
+            }
+
+            let report = self.build_index(def)?;
+            let encoded = serde_json::to_vec(def)
+                .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+            self.db
+                .put_cf(&self.cf_state_index_defs()?, def.name.as_bytes(), encoded)
+                .map_err(rocks_err)?;
+            indexes.retain(|d| d.name != def.name);
+            indexes.push(def.clone());
+            Ok(report)
+        }
+
+        fn list_indexes(&self) -> StorageResult<Vec<IndexDefinition>> {
+            Ok(self.lock_state_indexes().clone())
+        }
+
+        fn rebuild_index(&self, name: &str) -> StorageResult<IndexReport> {
+            let indexes = self.lock_state_indexes();
+            let def = indexes
+                .iter()
+                .find(|d| d.name == name)
+                .ok_or_else(|| StorageError::KeyNotFound(format!("index {name}")))?;
+            self.build_index(def)
+        }
+
+        fn verify_index(&self, name: &str) -> StorageResult<IndexReport> {
+            let indexes = self.lock_state_indexes();
+            let def = indexes
+                .iter()
+                .find(|d| d.name == name)
+                .ok_or_else(|| StorageError::KeyNotFound(format!("index {name}")))?;
+
+            let mut expected = self.expected_entries(def)?;
+            let mut report = IndexReport {
+                name: name.to_string(),
+                entries: 0,
+                missing: 0,
+                stale: 0,
+            };
+            for item in self
+                .db
+                .iterator_cf(&self.cf_index(name)?, IteratorMode::Start)
+            {
+                let (entry, _) = item.map_err(rocks_err)?;
+                report.entries += 1;
+                if expected.remove(&entry[..]).is_none() {
+                    report.stale += 1;
+                }
+            }
+            report.missing = expected.len() as u64;
+            Ok(report)
+        }
+
+        fn scan_index(&self, name: &str, start: &[u8], end: &[u8]) -> StorageResult<Vec<String>> {
+            let cf = self.cf_index(name)?;
+            let mut keys = Vec::new();
+            for item in self
+                .db
+                .iterator_cf(&cf, IteratorMode::From(start, Direction::Forward))
+            {
+                let (entry, key) = item.map_err(rocks_err)?;
+                if &entry[..] >= end {
+                    break;
+                }
+                keys.push(
+                    String::from_utf8(key.to_vec())
+                        .map_err(|e| StorageError::DeserializationError(e.to_string()))?,
+                );
+            }
+            Ok(keys)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::storage::commit::StateWrite;
+        use crate::storage::traits::Block;
+        use crate::storage::world_state::WorldState;
+        use tempfile::TempDir;
+
+        fn store() -> (RocksDbBlockStore, TempDir) {
+            let dir = TempDir::new().unwrap();
+            (RocksDbBlockStore::new(dir.path()).unwrap(), dir)
+        }
+
+        fn by_owner() -> IndexDefinition {
+            IndexDefinition::new("by_owner", &["owner"])
+        }
+
+        fn owners(store: &RocksDbBlockStore, owner: &str) -> Vec<String> {
+            let mut start = vec![super::super::TAG_STRING];
+            start.extend_from_slice(owner.as_bytes());
+            start.push(0);
+            let mut end = start.clone();
+            *end.last_mut().unwrap() = 1;
+            store.scan_index("by_owner", &start, &end).unwrap()
+        }
+
+        #[test]
+        fn register_builds_from_existing_state_and_writes_keep_it_current() {
+            let (store, _dir) = store();
+            store.put("a1", br#"{"owner":"bob"}"#).unwrap();
+            store.put("a2", br#"{"owner":"alice"}"#).unwrap();
+
+            let report = store.register_index(&by_owner()).unwrap();
+            assert_eq!(report.entries, 2);
+            assert_eq!(owners(&store, "bob"), vec!["a1"]);
+
+            store.put("a2", br#"{"owner":"bob"}"#).unwrap();
+            store.delete("a1").unwrap();
+            store
+                .put_from_tx("a3", br#"{"owner":"bob"}"#, "tx1", 1)
+                .unwrap();
+            assert_eq!(owners(&store, "bob"), vec!["a2", "a3"]);
+            assert!(owners(&store, "alice").is_empty());
+            assert!(store.verify_index("by_owner").unwrap().is_consistent());
+        }
+
+        #[test]
+        fn block_commits_and_rollbacks_update_indexes() {
+            let (store, _dir) = store();
+            store.register_index(&by_owner()).unwrap();
+            let block = Block {
+                height: 0,
+                timestamp: 1,
+                parent_hash: [0u8; 32],
+                merkle_root: [0u8; 32],
+                transactions: vec![],
+                proposer: "p".to_string(),
+                signature: vec![0u8; 64],
+                signature_algorithm: Default::default(),
+                endorsements: vec![],
+                secondary_signature: None,
+                secondary_signature_algorithm: None,
+                hash_algorithm: Default::default(),
+                orderer_signature: None,
+                commit_qc: None,
+                next_validator_set: None,
+                evidence: Vec::new(),
+                config_tx: None,
+                last_config: 0,
+                state_root: [0u8; 32],
+                private_data_purges: vec![],
+            };
+            let writes = [
+                StateWrite::put("t1", "a1", br#"{"owner":"bob"}"#),
+                StateWrite::put("t1", "a1", br#"{"owner":"carol"}"#),
+            ];
+            store.commit_block(&block, &[], &writes).unwrap();
+            assert!(owners(&store, "bob").is_empty());
+            assert_eq!(owners(&store, "carol"), vec!["a1"]);
+
+            store.roll_back_block(0).unwrap();
+            assert!(owners(&store, "carol").is_empty());
+            assert!(store.verify_index("by_owner").unwrap().is_consistent());
+        }
+
+        #[test]
+        fn definitions_survive_reopen_and_rebuild_repairs_entries() {
+            let dir = TempDir::new().unwrap();
+            {
+                let store = RocksDbBlockStore::new(dir.path()).unwrap();
+                store.put("a1", br#"{"owner":"bob"}"#).unwrap();
+                store.register_index(&by_owner()).unwrap();
+                // Bypass the write path to leave the index stale.
+                let cf = store.cf_world_state().unwrap();
+                store
+                    .db
+                    .put_cf(
+                        &cf,
+                        b"a2",
+                        serde_json::to_vec(&VersionedValue {
+                            version: 1,
+                            data: br#"{"owner":"bob"}"#.to_vec(),
+                        })
+                        .unwrap(),
+                    )
+                    .unwrap();
+            }
+
+            let store = RocksDbBlockStore::new(dir.path()).unwrap();
+            assert_eq!(store.list_indexes().unwrap(), vec![by_owner()]);
+            let report = store.verify_index("by_owner").unwrap();
+            assert_eq!((report.entries, report.missing), (1, 1));
+
+            let rebuilt = store.rebuild_index("by_owner").unwrap();
+            assert_eq!(rebuilt.entries, 2);
+            assert_eq!(owners(&store, "bob"), vec!["a1", "a2"]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::world_state::MemoryWorldState;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    /// Index store that derives its entries from the state on every scan.
+    struct DerivedIndexes {
+        defs: Vec<IndexDefinition>,
+        state: Arc<MemoryWorldState>,
+    }
+
+    impl DerivedIndexes {
+        fn def(&self, name: &str) -> StorageResult<&IndexDefinition> {
+            self.defs
+                .iter()
+                .find(|d| d.name == name)
+                .ok_or_else(|| StorageError::KeyNotFound(format!("index {name}")))
+        }
+
+        fn entries(&self, def: &IndexDefinition) -> StorageResult<BTreeMap<Vec<u8>, String>> {
+            Ok(self
+                .state
+                .get_range("", KEY_SPACE_END)?
+                .into_iter()
+                .filter_map(|(k, v)| Some((def.entry_key(&k, &v.data)?, k)))
+                .collect())
+        }
+
+        /// Derived entries are always consistent with the state.
+        fn report(&self, name: &str) -> StorageResult<IndexReport> {
+            Ok(IndexReport {
+                name: name.to_string(),
+                entries: self.entries(self.def(name)?)?.len() as u64,
+                missing: 0,
+                stale: 0,
+            })
+        }
+    }
+
+    impl StateIndexStore for DerivedIndexes {
+        fn register_index(&self, def: &IndexDefinition) -> StorageResult<IndexReport> {
+            self.report(&def.name)
+        }
+
+        fn list_indexes(&self) -> StorageResult<Vec<IndexDefinition>> {
+            Ok(self.defs.clone())
+        }
+
+        fn rebuild_index(&self, name: &str) -> StorageResult<IndexReport> {
+            self.report(name)
+        }
+
+        fn verify_index(&self, name: &str) -> StorageResult<IndexReport> {
+            self.report(name)
+        }
+
+        fn scan_index(&self, name: &str, start: &[u8], end: &[u8]) -> StorageResult<Vec<String>> {
+            Ok(self
+                .entries(self.def(name)?)?
+                .range(start.to_vec()..end.to_vec())
+                .map(|(_, k)| k.clone())
+                .collect())
+        }
+    }
+
+    fn encoded(value: Value) -> Vec<u8> {
+        let mut out = Vec::new();
+        encode_scalar(&value, &mut out).unwrap();
+        out
+    }
+
+    fn put(state: &MemoryWorldState, key: &str, doc: Value) {
+        state.put(key, doc.to_string().as_bytes()).unwrap();
+    }
+
+    fn assets() -> Arc<MemoryWorldState> {
+        let state = Arc::new(MemoryWorldState::new());
+        put(
+            &state,
+            "a1",
+            json!({"owner": "bob", "size": 10, "color": "red"}),
+        );
+        put(
+            &state,
+            "a2",
+            json!({"owner": "alice", "size": 5, "color": "blue"}),
+        );
+        put(
+            &state,
+            "a3",
+            json!({"owner": "bob", "size": -2.5, "color": "blue"}),
+        );
+        put(&state, "a4", json!({"owner": "bob", "size": "big"}));
+        put(&state, "a5", json!({"owner": "carol"}));
+        state.put("raw", b"not json").unwrap();
+        state
+    }
+
+    fn keys(outcome: &QueryOutcome) -> Vec<&str> {
+        outcome.entries.iter().map(|(k, _)| k.as_str()).collect()
+    }
+
+    #[test]
+    fn encoding_preserves_order_within_and_across_types() {
+        let ordered = [
+            json!(null),
+            json!(false),
+            json!(true),
+            json!(-1e300),
+            json!(-2.5),
+            json!(0),
+            json!(1),
+            json!(10),
+            json!(1e300),
+            json!(""),
+            json!("\u{0}"),
+            json!("\u{1}"),
+            json!("a"),
+            json!("a\u{0}"),
+            json!("ab"),
+            json!("b"),
+        ];
+        for pair in ordered.windows(2) {
+            assert!(
+                encoded(pair[0].clone()) < encoded(pair[1].clone()),
+                "{} should sort before {}",
+                pair[0],
+                pair[1]
+            );
+        }
+        assert_eq!(encoded(json!(-0.0)), encoded(json!(0)));
+    }
+
+    #[test]
+    fn entry_keys_skip_values_without_scalar_fields() {
+        let def = IndexDefinition::new("by_owner_color", &["owner", "asset.color"]);
+        let entry = def
+            .entry_key("k", br#"{"owner":"bob","asset":{"color":"red"}}"#)
+            .unwrap();
+        assert!(entry.ends_with(b"k"));
+        assert!(def.entry_key("k", br#"{"owner":"bob"}"#).is_none());
+        assert!(def
+            .entry_key("k", br#"{"owner":"bob","asset":{"color":["red"]}}"#)
+            .is_none());
+        assert!(def.entry_key("k", b"not json").is_none());
+    }
+
+    #[test]
+    fn definitions_are_validated() {
+        assert!(IndexDefinition::new("by_owner", &["owner"])
+            .validate()
+            .is_ok());
+        assert!(IndexDefinition::new("bad name", &["owner"])
+            .validate()
+            .is_err());
+        assert!(IndexDefinition::new("empty", &[]).validate().is_err());
+        assert!(IndexDefinition::new("dots", &["a..b"]).validate().is_err());
+    }
+
+    #[test]
+    fn selectors_parse_and_match() {
+        let selector =
+            Selector::parse(&json!({"owner": "bob", "size": {"$gte": 0, "$lt": 10}})).unwrap();
+        assert_eq!(selector.conditions().len(), 3);
+        assert!(selector.matches(br#"{"owner":"bob","size":0}"#));
+        assert!(!selector.matches(br#"{"owner":"bob","size":10}"#));
+        assert!(!selector.matches(br#"{"owner":"bob","size":"5"}"#));
+        assert!(!selector.matches(br#"{"owner":"bob"}"#));
+
+        assert!(Selector::parse(&json!(["owner"])).is_err());
+        assert!(Selector::parse(&json!({"owner": {"$regex": "b.*"}})).is_err());
+        assert!(Selector::parse(&json!({"owner": {"$eq": ["bob"]}})).is_err());
+    }
+
+    #[test]
+    fn plan_prefers_the_index_covering_most_fields() {
+        let indexes = vec![
+            IndexDefinition::new("by_color", &["color"]),
+            IndexDefinition::new("by_owner_size", &["owner", "size"]),
+        ];
+        let selector = Selector::parse(&json!({"owner": "bob", "size": {"$gt": 1}})).unwrap();
+        assert_eq!(selector.plan(&indexes).unwrap().index, "by_owner_size");
+
+        let selector = Selector::parse(&json!({"size": 1, "color": "red"})).unwrap();
+        assert_eq!(selector.plan(&indexes).unwrap().index, "by_color");
+
+        // Values without a size have no entry in `by_owner_size`.
+        let selector = Selector::parse(&json!({"owner": "bob"})).unwrap();
+        assert!(selector.plan(&indexes).is_none());
+    }
+
+    #[test]
+    fn indexed_queries_match_full_scans() {
+        let state = assets();
+        let indexes = DerivedIndexes {
+            defs: vec![IndexDefinition::new("by_owner_size", &["owner", "size"])],
+            state: state.clone(),
+        };
+        let selectors = [
+            json!({"owner": "bob", "size": {"$gt": -2.5}}),
+            json!({"owner": "bob", "size": {"$gte": -2.5, "$lte": 10}}),
+            json!({"owner": "bob", "size": {"$lt": 10}}),
+            json!({"owner": "bob", "size": {"$gt": "a"}}),
+            json!({"owner": "bob", "size": {"$lte": 10}, "color": "blue"}),
+            json!({"owner": {"$gte": "b"}, "size": {"$gt": 0}}),
+        ];
+        for selector in selectors {
+            let parsed = Selector::parse(&selector).unwrap();
+            let indexed = query(state.as_ref(), Some(&indexes), &parsed, 100).unwrap();
+            let scanned = query(state.as_ref(), None, &parsed, 100).unwrap();
+            assert_eq!(
+                indexed.index.as_deref(),
+                Some("by_owner_size"),
+                "{selector}"
+            );
+            assert!(scanned.index.is_none());
+            let mut indexed_keys = keys(&indexed);
+            indexed_keys.sort();
+            assert_eq!(indexed_keys, keys(&scanned), "{selector}");
+        }
+    }
+
+    #[test]
+    fn range_on_the_first_field_follows_value_order() {
+        let state = assets();
+        let indexes = DerivedIndexes {
+            defs: vec![IndexDefinition::new("by_size", &["size"])],
+            state: state.clone(),
+        };
+        let selector = Selector::parse(&json!({"size": {"$gt": -100}})).unwrap();
+        let outcome = query(state.as_ref(), Some(&indexes), &selector, 100).unwrap();
+        assert_eq!(keys(&outcome), vec!["a3", "a2", "a1"]);
+
+        let limited = query(state.as_ref(), Some(&indexes), &selector, 2).unwrap();
+        assert_eq!(keys(&limited), vec!["a3", "a2"]);
+    }
+
+    fn custom_section(name: &str, data: &[u8]) -> Vec<u8> {
+        let mut payload = vec![name.len() as u8];
+        payload.extend_from_slice(name.as_bytes());
+        payload.extend_from_slice(data);
+        let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+        wasm.push(0);
+        // Unsigned LEB128 section size.
+        let mut size = payload.len();
+        loop {
+            let byte = (size & 0x7f) as u8;
+            size >>= 7;
+            if size == 0 {
+                wasm.push(byte);
+                break;
+            }
+            wasm.push(byte | 0x80);
+        }
+        wasm.extend_from_slice(&payload);
+        wasm
+    }
+
+    #[test]
+    fn packages_ship_indexes_in_a_custom_section() {
+        let wasm = custom_section(
+            PACKAGE_SECTION,
+            br#"[{"name":"by_owner","fields":["owner"]}]"#,
+        );
+        let defs = indexes_from_package(&wasm, "assets").unwrap();
+        assert_eq!(defs.len(), 1);
+        assert_eq!(defs[0].fields, vec!["owner"]);
+        assert_eq!(defs[0].chaincode_id.as_deref(), Some("assets"));
+
+        let other = custom_section("name", b"\0");
+        assert!(indexes_from_package(&other, "assets").unwrap().is_empty());
+        assert!(indexes_from_package(&[1u8; 64], "assets")
+            .unwrap()
+            .is_empty());
+
+        let broken = custom_section(PACKAGE_SECTION, br#"[{"name":"x y","fields":["a"]}]"#);
+        assert!(indexes_from_package(&broken, "assets").is_err());
+    }
+}
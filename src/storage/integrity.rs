@@ -0,0 +1,171 @@
+//! Structural checks over a stored ledger.
+//!
+//! Used before a restored data directory is put in service: every height from
+//! genesis to the tip must be present, and each block's `parent_hash` must be
+//! the signing hash of the block below it. Blocks cut by orderers that do not
+//! chain parent hashes (solo, Raft) carry a zero `parent_hash`; they are
+//! counted as unlinked rather than rejected.
+
+use serde::Serialize;
+
+use super::errors::{StorageError, StorageResult};
+use super::traits::BlockStore;
+use crate::ordering::block_hash_for_signing;
+
+/// Result of a successful [`verify_hash_chain`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChainSummary {
+    /// Height of the tip; `None` for an empty ledger.
+    pub latest_height: Option<u64>,
+    /// Blocks whose `parent_hash` was checked against their parent.
+    pub linked_blocks: u64,
+    /// Blocks above genesis with a zero `parent_hash`.
+    pub unlinked_blocks: u64,
+    /// Signing hash of the tip.
+    pub tip_hash: Option<[u8; 32]>,
+}
+
+/// Walk `store` from genesis to its tip and check the hash chain.
+pub fn verify_hash_chain(store: &dyn BlockStore) -> StorageResult<ChainSummary> {
+    if !store.block_exists(0)? {
+        let latest = store.get_latest_height()?;
+        if latest > 0 {
+            return Err(StorageError::DataCorrupted(format!(
+                "tip is block {latest} but genesis is missing"
+            )));
+        }
+        return Ok(ChainSummary {
+            latest_height: None,
+            linked_blocks: 0,
+            unlinked_blocks: 0,
+            tip_hash: None,
+        });
+    }
+
+    let latest = store.get_latest_height()?;
+    let mut summary = ChainSummary {
+        latest_height: Some(latest),
+        linked_blocks: 0,
+        unlinked_blocks: 0,
+        tip_hash: None,
+    };
+    let mut parent_hash = [0u8; 32];
+    for height in 0..=latest {
+        let block = store.read_block(height).map_err(|e| match e {
+            StorageError::KeyNotFound(_) => {
+                StorageError::DataCorrupted(format!("block {height} is missing"))
+            }
+            e => e,
+        })?;
+        if block.height != height {
+            return Err(StorageError::DataCorrupted(format!(
+                "block stored at {height} claims height {}",
+                block.height
+            )));
+        }
+        if height > 0 {
+            if block.parent_hash == [0u8; 32] {
+                summary.unlinked_blocks += 1;
+            } else if block.parent_hash == parent_hash {
+                summary.linked_blocks += 1;
+            } else {
+                return Err(StorageError::DataCorrupted(format!(
+                    "block {height} does not link to block {}",
+                    height - 1
+                )));
+            }
+        }
+        if block.last_config > height {
+            return Err(StorageError::DataCorrupted(format!(
+                "block {height} points at config block {} above it",
+                block.last_config
+            )));
+        }
+        parent_hash = block_hash_for_signing(&block);
+    }
+    summary.tip_hash = Some(parent_hash);
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::memory::MemoryStore;
+    use crate::storage::traits::Block;
+
+    fn block(height: u64, parent_hash: [u8; 32]) -> Block {
+        Block {
+            height,
+            timestamp: height,
+            parent_hash,
+            merkle_root: [height as u8; 32],
+            transactions: vec![],
+            proposer: "orderer".to_string(),
+            signature: vec![0u8; 64],
+            signature_algorithm: Default::default(),
+            endorsements: vec![],
+            secondary_signature: None,
+            secondary_signature_algorithm: None,
+            hash_algorithm: Default::default(),
+            orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: vec![],
+            config_tx: None,
+            last_config: 0,
+            state_root: [0u8; 32],
+            private_data_purges: vec![],
+        }
+    }
+
+    fn linked_chain(len: u64) -> MemoryStore {
+        let store = MemoryStore::new();
+        let mut parent = [0u8; 32];
+        for h in 0..len {
+            let b = block(h, parent);
+            parent = block_hash_for_signing(&b);
+            store.write_block(&b).unwrap();
+        }
+        store
+    }
+
+    #[test]
+    fn linked_chain_verifies() {
+        let summary = verify_hash_chain(&linked_chain(5)).unwrap();
+        assert_eq!(summary.latest_height, Some(4));
+        assert_eq!(summary.linked_blocks, 4);
+        assert_eq!(summary.unlinked_blocks, 0);
+        assert!(summary.tip_hash.is_some());
+    }
+
+    #[test]
+    fn empty_ledger_verifies() {
+        let summary = verify_hash_chain(&MemoryStore::new()).unwrap();
+        assert_eq!(summary.latest_height, None);
+    }
+
+    #[test]
+    fn zero_parent_hashes_count_as_unlinked() {
+        let store = MemoryStore::new();
+        for h in 0..3 {
+            store.write_block(&block(h, [0u8; 32])).unwrap();
+        }
+        let summary = verify_hash_chain(&store).unwrap();
+        assert_eq!((summary.linked_blocks, summary.unlinked_blocks), (0, 2));
+    }
+
+    #[test]
+    fn broken_link_is_rejected() {
+        let store = linked_chain(3);
+        store.write_block(&block(3, [9u8; 32])).unwrap();
+        let err = verify_hash_chain(&store).unwrap_err();
+        assert!(matches!(err, StorageError::DataCorrupted(_)));
+    }
+
+    #[test]
+    fn gap_in_heights_is_rejected() {
+        let store = linked_chain(2);
+        store.write_block(&block(5, [0u8; 32])).unwrap();
+        assert!(verify_hash_chain(&store).is_err());
+    }
+}
@@ -202,6 +202,22 @@ impl BlockStore for MemoryStore {
         Ok(txs)
     }
 
+    fn prune_block(&self, header: &Block) -> StorageResult<()> {
+        let mut blocks = self.blocks.lock().unwrap_or_else(|e| e.into_inner());
+        if !blocks.contains_key(&header.height) {
+            return Err(StorageError::KeyNotFound(format!(
+                "BLK:{:012}",
+                header.height
+            )));
+        }
+        blocks.insert(header.height, header.clone());
+        self.transactions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|_, tx| tx.block_height != header.height);
+        Ok(())
+    }
+
     fn credentials_by_subject_did(&self, subject_did: &str) -> StorageResult<Vec<Credential>> {
         let creds = self
             .credentials
@@ -572,6 +588,13 @@ mod tests {
             secondary_signature_algorithm: None,
             hash_algorithm: Default::default(),
             orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: Vec::new(),
+            config_tx: None,
+            last_config: 0,
+            state_root: [0u8; 32],
+            private_data_purges: vec![],
         }
     }
 
@@ -13,14 +13,14 @@
 //! Migrations MUST be idempotent — a crash mid-migration means the same
 //! migration runs again on next startup (version only advances after success).
 
-use super::adapters::RocksDbBlockStore;
+use super::adapters::{RocksDbBlockStore, CF_KEY_HISTORY};
 use super::errors::{StorageError, StorageResult};
 
 const META_SCHEMA_VERSION: &[u8] = b"schema_version";
 const CF_META: &str = "meta";
 
 /// Current schema version. Increment when adding a new migration.
-pub const LATEST_VERSION: u32 = 3;
+pub const LATEST_VERSION: u32 = 6;
 
 /// A single migration step.
 struct Migration {
@@ -49,6 +49,21 @@ const MIGRATIONS: &[Migration] = &[
         description: "add governance_proposals, governance_votes CFs",
         apply: migrate_v3,
     },
+    Migration {
+        version: 4,
+        description: "add block_writes CF and the world-state savepoint",
+        apply: migrate_v4,
+    },
+    Migration {
+        version: 5,
+        description: "add state_index_defs CF for world-state secondary indexes",
+        apply: migrate_v5,
+    },
+    Migration {
+        version: 6,
+        description: "add history_by_block CF and index existing key history by block",
+        apply: migrate_v6,
+    },
 ];
 
 /// Read the current schema version from the DB. Returns 0 if not set.
@@ -161,6 +176,63 @@ fn migrate_v3(store: &RocksDbBlockStore) -> StorageResult<()> {
     Ok(())
 }
 
+/// v4: Add the block_writes Column Family and stamp the state savepoint.
+///
+/// Blocks written before v4 are taken as applied, so the savepoint starts
+/// at the current tip.
+fn migrate_v4(store: &RocksDbBlockStore) -> StorageResult<()> {
+    if store.db.cf_handle("block_writes").is_none() {
+        return Err(StorageError::RocksDbError(
+            "migration v4: CF 'block_writes' not found — database may need re-creation".to_string(),
+        ));
+    }
+    store.recover_state()?;
+    Ok(())
+}
+
+/// v5: Add the state_index_defs Column Family. Stores start with no
+/// secondary indexes, so there is nothing to build.
+fn migrate_v5(store: &RocksDbBlockStore) -> StorageResult<()> {
+    if store.db.cf_handle("state_index_defs").is_none() {
+        return Err(StorageError::RocksDbError(
+            "migration v5: CF 'state_index_defs' not found — database may need re-creation"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// v6: Add the history_by_block Column Family and index the key history
+/// entries already written from blocks. Rewriting an entry is harmless, so
+/// a rerun after a crash is fine.
+fn migrate_v6(store: &RocksDbBlockStore) -> StorageResult<()> {
+    use rocksdb::{IteratorMode, WriteBatch};
+
+    let cf_by_block = store.cf_history_by_block().map_err(|_| {
+        StorageError::RocksDbError(
+            "migration v6: CF 'history_by_block' not found — database may need re-creation"
+                .to_string(),
+        )
+    })?;
+    let cf_hist = store.cf_key_history()?;
+    let mut batch = WriteBatch::default();
+    for item in store.db.iterator_cf(&cf_hist, IteratorMode::Start) {
+        let (k, v) = item.map_err(|e| StorageError::RocksDbError(e.to_string()))?;
+        let entry: crate::storage::traits::HistoryEntry =
+            serde_json::from_slice(&store.open(CF_KEY_HISTORY, &k, &v)?)
+                .map_err(|e| StorageError::DeserializationError(e.to_string()))?;
+        if let Some(height) = entry.block_height {
+            let mut key = RocksDbBlockStore::block_key(height);
+            key.extend_from_slice(&k);
+            batch.put_cf(&cf_by_block, key, b"");
+        }
+    }
+    store
+        .db
+        .write(batch)
+        .map_err(|e| StorageError::RocksDbError(e.to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -203,19 +275,87 @@ mod tests {
         assert_eq!(current_version(&store).unwrap(), 1);
 
         let applied = run_pending(&store).unwrap();
-        assert_eq!(applied, 2); // v2 + v3 migrations ran
+        assert_eq!(applied, 5); // v2 through v6 migrations ran
         assert_eq!(current_version(&store).unwrap(), LATEST_VERSION);
     }
 
     #[test]
-    fn run_pending_from_v2_runs_v3() {
+    fn run_pending_from_v2_runs_v3_to_v6() {
         let (store, _dir) = tmp_store();
         set_version(&store, 2).unwrap();
         let applied = run_pending(&store).unwrap();
-        assert_eq!(applied, 1); // v3 migration ran
+        assert_eq!(applied, 4); // v3 through v6 migrations ran
         assert_eq!(current_version(&store).unwrap(), LATEST_VERSION);
     }
 
+    #[test]
+    fn run_pending_from_v3_stamps_the_savepoint_at_the_tip() {
+        use crate::storage::traits::{Block, BlockStore};
+
+        let (store, _dir) = tmp_store();
+        set_version(&store, 3).unwrap();
+        store
+            .write_block(&Block {
+                height: 2,
+                timestamp: 0,
+                parent_hash: [0u8; 32],
+                merkle_root: [0u8; 32],
+                transactions: vec![],
+                proposer: "orderer".to_string(),
+                signature: vec![],
+                signature_algorithm: Default::default(),
+                endorsements: vec![],
+                secondary_signature: None,
+                secondary_signature_algorithm: None,
+                hash_algorithm: Default::default(),
+                orderer_signature: None,
+                commit_qc: None,
+                next_validator_set: None,
+                evidence: vec![],
+                config_tx: None,
+                last_config: 0,
+                state_root: [0u8; 32],
+                private_data_purges: vec![],
+            })
+            .unwrap();
+        store
+            .db
+            .delete_cf(
+                &store.cf_meta().unwrap(),
+                crate::storage::adapters::META_STATE_SAVEPOINT,
+            )
+            .unwrap();
+
+        assert_eq!(run_pending(&store).unwrap(), 3);
+        assert_eq!(store.state_savepoint().unwrap(), Some(2));
+    }
+
+    #[test]
+    fn run_pending_from_v5_indexes_history_by_block() {
+        use crate::storage::world_state::WorldState;
+
+        let (store, _dir) = tmp_store();
+        set_version(&store, 5).unwrap();
+        store.put_from_tx("a", b"1", "tx-1", 4).unwrap();
+        store.put("b", b"2").unwrap();
+        let cf = store.cf_history_by_block().unwrap();
+        let keys: Vec<_> = store
+            .db
+            .iterator_cf(&cf, rocksdb::IteratorMode::Start)
+            .map(|item| item.unwrap().0)
+            .collect();
+        for key in keys {
+            store.db.delete_cf(&cf, key).unwrap();
+        }
+        assert!(store.history_at(4).unwrap().is_empty());
+
+        assert_eq!(run_pending(&store).unwrap(), 1);
+        let entries = store.history_at(4).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].0, "a");
+        assert_eq!(entries[0].1.tx_id, "tx-1");
+    }
+
     #[test]
     fn version_survives_reopen() {
         let dir = TempDir::new().unwrap();
@@ -9,23 +9,53 @@
 
 #[cfg(feature = "rocksdb-storage")]
 pub mod adapters;
+pub mod archive;
+pub mod backup;
+pub mod bootstrap;
+#[cfg(feature = "rocksdb-storage")]
+pub mod commit;
 pub mod compat;
 pub mod comprehensive_tests;
 pub mod couchdb;
+pub mod encryption;
 pub mod errors;
+pub mod index;
+pub mod integrity;
 pub mod memory;
 #[cfg(feature = "rocksdb-storage")]
 pub mod migrations;
+pub mod projection;
+#[cfg(feature = "redb-storage")]
+pub mod redb_store;
 pub mod snapshot;
+pub mod state_tree;
 pub mod traits;
+pub mod verify;
 pub mod world_state;
 
 #[cfg(feature = "rocksdb-storage")]
 pub use adapters::RocksDbBlockStore;
 pub use memory::MemoryStore;
+#[cfg(feature = "redb-storage")]
+pub use redb_store::RedbBlockStore;
 pub use traits::BlockStore;
 pub use world_state::{MemoryWorldState, WorldState};
 
+/// Check that `channel_id` is safe to use as a directory name: non-empty,
+/// alphanumeric, hyphens or underscores only, so it cannot escape a base path.
+pub fn validate_channel_id(channel_id: &str) -> errors::StorageResult<()> {
+    if channel_id.is_empty()
+        || !channel_id
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(errors::StorageError::InvalidChannelId(
+            channel_id.to_string(),
+        ));
+    }
+    Ok(())
+}
+
 /// Storage configuration
 #[derive(Clone, Debug)]
 pub struct StorageConfig {
@@ -0,0 +1,736 @@
+//! Relational projection of the ledger for analytics.
+//!
+//! A [`Projector`] follows the commit path through the [`EventBus`] and
+//! writes every committed block — its header, transactions, world-state
+//! changes and chaincode events — to a [`ProjectionSink`], one atomic unit
+//! per block. The sink records the last projected height, so a restarted
+//! node resumes where it stopped and catches up from the block store.
+//!
+//! Blocks, transactions and state changes are read back from the store and
+//! the world-state history ([`WorldState::history_at`]), so they can be
+//! projected again at any time. Chaincode events are not persisted by the
+//! ledger: the projector keeps the ones it sees on the bus and files them
+//! under the next block committed on their channel. Rewinding a sink keeps
+//! them for that reason.
+//!
+//! The SQLite sink lives behind the `sql-projection` feature.
+//!
+//! [`EventBus`]: crate::events::EventBus
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use super::errors::StorageResult;
+use super::traits::{BlockStore, Transaction};
+use super::world_state::WorldState;
+use crate::events::BlockEvent;
+use crate::ordering::block_hash_for_signing;
+
+// The read-only query helpers are only used by bcctl, not the node binary.
+#[cfg(feature = "sql-projection")]
+#[allow(unused_imports)]
+pub use sqlite::{query_read_only, QueryRows, SqliteProjection};
+
+/// Header fields of a projected block; hashes are lowercase hex.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BlockRow {
+    pub height: u64,
+    pub hash: String,
+    pub parent_hash: String,
+    pub state_root: String,
+    pub timestamp: u64,
+    pub proposer: String,
+    pub tx_count: u64,
+}
+
+/// One world-state write of a block, from the key history.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StateChangeRow {
+    pub block_height: u64,
+    pub key: String,
+    pub version: u64,
+    pub tx_id: String,
+    pub timestamp: u64,
+    pub is_delete: bool,
+    pub value: Vec<u8>,
+}
+
+/// A chaincode event, filed under the block that committed it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChaincodeEventRow {
+    pub block_height: u64,
+    pub channel_id: String,
+    pub chaincode_id: String,
+    pub event_name: String,
+    pub payload: Vec<u8>,
+}
+
+/// Everything projected for one block.
+#[derive(Debug, Clone, Serialize)]
+pub struct BlockProjection {
+    pub block: BlockRow,
+    pub transactions: Vec<Transaction>,
+    pub state_changes: Vec<StateChangeRow>,
+    pub events: Vec<ChaincodeEventRow>,
+}
+
+/// Destination of a projection.
+pub trait ProjectionSink: Send {
+    /// Height of the last block applied; `None` before the first one.
+    fn last_height(&mut self) -> StorageResult<Option<u64>>;
+
+    /// Write `projection` and advance the last height to its block, all or
+    /// nothing.
+    fn apply(&mut self, projection: &BlockProjection) -> StorageResult<()>;
+
+    /// Remove the blocks, transactions and state changes at `height` and
+    /// above, and move the last height below `height`. Chaincode events are
+    /// kept, since they cannot be projected again.
+    fn rewind(&mut self, height: u64) -> StorageResult<()>;
+}
+
+/// Keeps a [`ProjectionSink`] in step with the ledger.
+pub struct Projector<S: ProjectionSink> {
+    store: Arc<dyn BlockStore>,
+    state: Arc<dyn WorldState>,
+    sink: S,
+    /// Events seen on the bus, per channel, not yet tied to a block.
+    unfiled: HashMap<String, Vec<ChaincodeEventRow>>,
+    /// Events tied to a block that is not projected yet, by height.
+    pending: BTreeMap<u64, Vec<ChaincodeEventRow>>,
+}
+
+impl<S: ProjectionSink> Projector<S> {
+    pub fn new(store: Arc<dyn BlockStore>, state: Arc<dyn WorldState>, sink: S) -> Self {
+        Self {
+            store,
+            state,
+            sink,
+            unfiled: HashMap::new(),
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// Read the block at `height` and its transactions and state changes.
+    pub fn project_block(&self, height: u64) -> StorageResult<BlockProjection> {
+        let block = self.store.read_block(height)?;
+        let transactions = self.store.transactions_by_block_height(height)?;
+        let state_changes = self
+            .state
+            .history_at(height)?
+            .into_iter()
+            .map(|(key, entry)| StateChangeRow {
+                block_height: height,
+                key,
+                version: entry.version,
+                tx_id: entry.tx_id,
+                timestamp: entry.timestamp,
+                is_delete: entry.is_delete,
+                value: entry.data,
+            })
+            .collect();
+        Ok(BlockProjection {
+            block: BlockRow {
+                height,
+                hash: hex::encode(block_hash_for_signing(&block)),
+                parent_hash: hex::encode(block.parent_hash),
+                state_root: hex::encode(block.state_root),
+                timestamp: block.timestamp,
+                proposer: block.proposer.clone(),
+                tx_count: block.transactions.len() as u64,
+            },
+            transactions,
+            state_changes,
+            events: Vec::new(),
+        })
+    }
+
+    /// Project every block between the sink's last height and the store's
+    /// tip. Heights missing from the store (pruned) are skipped. Returns the
+    /// number of blocks applied.
+    pub fn catch_up(&mut self) -> StorageResult<u64> {
+        let from = self.sink.last_height()?.map_or(0, |h| h + 1);
+        let tip = self.store.get_latest_height()?;
+        let mut applied = 0;
+        for height in from..=tip {
+            if !self.store.block_exists(height)? {
+                continue;
+            }
+            let mut projection = self.project_block(height)?;
+            projection.events = self.pending.remove(&height).unwrap_or_default();
+            self.sink.apply(&projection)?;
+            applied += 1;
+        }
+        // Events left at or below the tip belong to blocks the sink already
+        // had or the store lacks; they cannot be placed.
+        self.pending = self.pending.split_off(&(tip + 1));
+        Ok(applied)
+    }
+
+    /// Feed one bus event: chaincode events are held for their block, and a
+    /// committed block triggers a catch-up.
+    pub fn handle(&mut self, event: &BlockEvent) -> StorageResult<u64> {
+        match event {
+            BlockEvent::ChaincodeEvent {
+                channel_id,
+                chaincode_id,
+                event_name,
+                payload,
+            } => {
+                self.unfiled
+                    .entry(channel_id.clone())
+                    .or_default()
+                    .push(ChaincodeEventRow {
+                        block_height: 0,
+                        channel_id: channel_id.clone(),
+                        chaincode_id: chaincode_id.clone(),
+                        event_name: event_name.clone(),
+                        payload: payload.clone(),
+                    });
+                Ok(0)
+            }
+            BlockEvent::BlockCommitted {
+                channel_id, height, ..
+            } => {
+                if let Some(events) = self.unfiled.remove(channel_id) {
+                    self.pending
+                        .entry(*height)
+                        .or_default()
+                        .extend(events.into_iter().map(|e| ChaincodeEventRow {
+                            block_height: *height,
+                            ..e
+                        }));
+                }
+                self.catch_up()
+            }
+            _ => Ok(0),
+        }
+    }
+
+    /// Rewind the sink to `height` and project again from there.
+    pub fn rebuild_from(&mut self, height: u64) -> StorageResult<u64> {
+        self.sink.rewind(height)?;
+        self.catch_up()
+    }
+}
+
+/// Run `projector` on a dedicated thread, fed by `rx`. It catches up once
+/// at start, then after every committed block.
+pub fn spawn_projector<S: ProjectionSink + 'static>(
+    mut projector: Projector<S>,
+    mut rx: broadcast::Receiver<BlockEvent>,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        match projector.catch_up() {
+            Ok(n) => log::info!("SQL projection caught up ({n} blocks)"),
+            Err(e) => log::error!("SQL projection catch-up failed: {e}"),
+        }
+        loop {
+            let event = match rx.blocking_recv() {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Closed) => break,
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    // Blocks are read from the store, so only chaincode
+                    // events are lost; catch up on the next block.
+                    log::warn!("SQL projection lagged, {n} events dropped");
+                    continue;
+                }
+            };
+            if let Err(e) = projector.handle(&event) {
+                log::error!("SQL projection failed: {e}");
+            }
+        }
+    })
+}
+
+#[cfg(feature = "sql-projection")]
+mod sqlite {
+    use std::path::Path;
+
+    use rusqlite::types::{Value, ValueRef};
+    use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
+    use serde::Serialize;
+
+    use super::{BlockProjection, ProjectionSink};
+    use crate::storage::errors::{StorageError, StorageResult};
+
+    /// Tables of the projection. Values and payloads are stored as text when
+    /// they are UTF-8, so SQLite's JSON functions work on JSON documents.
+    const SCHEMA: &str = "
+        CREATE TABLE IF NOT EXISTS blocks (
+            height      INTEGER PRIMARY KEY,
+            hash        TEXT NOT NULL,
+            parent_hash TEXT NOT NULL,
+            state_root  TEXT NOT NULL,
+            timestamp   INTEGER NOT NULL,
+            proposer    TEXT NOT NULL,
+            tx_count    INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS transactions (
+            tx_id            TEXT PRIMARY KEY,
+            block_height     INTEGER NOT NULL,
+            timestamp        INTEGER NOT NULL,
+            input_did        TEXT NOT NULL,
+            output_recipient TEXT NOT NULL,
+            amount           INTEGER NOT NULL,
+            state            TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS transactions_by_block ON transactions (block_height);
+        CREATE TABLE IF NOT EXISTS state_changes (
+            key          TEXT NOT NULL,
+            version      INTEGER NOT NULL,
+            block_height INTEGER NOT NULL,
+            tx_id        TEXT NOT NULL,
+            timestamp    INTEGER NOT NULL,
+            is_delete    INTEGER NOT NULL,
+            value,
+            PRIMARY KEY (key, version)
+        );
+        CREATE INDEX IF NOT EXISTS state_changes_by_block ON state_changes (block_height);
+        CREATE TABLE IF NOT EXISTS chaincode_events (
+            id           INTEGER PRIMARY KEY AUTOINCREMENT,
+            block_height INTEGER NOT NULL,
+            channel_id   TEXT NOT NULL,
+            chaincode_id TEXT NOT NULL,
+            event_name   TEXT NOT NULL,
+            payload
+        );
+        CREATE INDEX IF NOT EXISTS chaincode_events_by_block ON chaincode_events (block_height);
+        CREATE TABLE IF NOT EXISTS projection_meta (
+            name  TEXT PRIMARY KEY,
+            value INTEGER NOT NULL
+        );
+    ";
+
+    const META_LAST_HEIGHT: &str = "last_height";
+
+    fn sink_error(e: rusqlite::Error) -> StorageError {
+        StorageError::Other(format!("projection: {e}"))
+    }
+
+    fn bytes_value(bytes: &[u8]) -> Value {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Value::Text(text.to_string()),
+            Err(_) => Value::Blob(bytes.to_vec()),
+        }
+    }
+
+    /// A projection kept in an SQLite database file.
+    pub struct SqliteProjection {
+        conn: Connection,
+    }
+
+    impl SqliteProjection {
+        /// Open or create the database at `path` and its tables.
+        pub fn open(path: &Path) -> StorageResult<Self> {
+            let conn = Connection::open(path).map_err(sink_error)?;
+            // WAL lets `bcctl sql` read while the node writes.
+            conn.pragma_update(None, "journal_mode", "WAL")
+                .map_err(sink_error)?;
+            conn.execute_batch(SCHEMA).map_err(sink_error)?;
+            Ok(Self { conn })
+        }
+    }
+
+    impl ProjectionSink for SqliteProjection {
+        fn last_height(&mut self) -> StorageResult<Option<u64>> {
+            self.conn
+                .query_row(
+                    "SELECT value FROM projection_meta WHERE name = ?1",
+                    [META_LAST_HEIGHT],
+                    |row| row.get::<_, i64>(0),
+                )
+                .optional()
+                .map(|h| h.map(|h| h as u64))
+                .map_err(sink_error)
+        }
+
+        fn apply(&mut self, p: &BlockProjection) -> StorageResult<()> {
+            let tx = self.conn.transaction().map_err(sink_error)?;
+            let height = p.block.height as i64;
+            tx.execute(
+                "INSERT OR REPLACE INTO blocks VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    height,
+                    p.block.hash,
+                    p.block.parent_hash,
+                    p.block.state_root,
+                    p.block.timestamp as i64,
+                    p.block.proposer,
+                    p.block.tx_count as i64,
+                ],
+            )
+            .map_err(sink_error)?;
+            for t in &p.transactions {
+                tx.execute(
+                    "INSERT OR REPLACE INTO transactions VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        t.id,
+                        t.block_height as i64,
+                        t.timestamp as i64,
+                        t.input_did,
+                        t.output_recipient,
+                        t.amount as i64,
+                        t.state,
+                    ],
+                )
+                .map_err(sink_error)?;
+            }
+            for c in &p.state_changes {
+                tx.execute(
+                    "INSERT OR REPLACE INTO state_changes VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        c.key,
+                        c.version as i64,
+                        height,
+                        c.tx_id,
+                        c.timestamp as i64,
+                        c.is_delete,
+                        bytes_value(&c.value),
+                    ],
+                )
+                .map_err(sink_error)?;
+            }
+            for e in &p.events {
+                tx.execute(
+                    "INSERT INTO chaincode_events
+                         (block_height, channel_id, chaincode_id, event_name, payload)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![
+                        height,
+                        e.channel_id,
+                        e.chaincode_id,
+                        e.event_name,
+                        bytes_value(&e.payload),
+                    ],
+                )
+                .map_err(sink_error)?;
+            }
+            tx.execute(
+                "INSERT OR REPLACE INTO projection_meta VALUES (?1, ?2)",
+                params![META_LAST_HEIGHT, height],
+            )
+            .map_err(sink_error)?;
+            tx.commit().map_err(sink_error)
+        }
+
+        fn rewind(&mut self, height: u64) -> StorageResult<()> {
+            let tx = self.conn.transaction().map_err(sink_error)?;
+            let h = height as i64;
+            for table in ["blocks", "transactions", "state_changes"] {
+                let column = if table == "blocks" {
+                    "height"
+                } else {
+                    "block_height"
+                };
+                tx.execute(&format!("DELETE FROM {table} WHERE {column} >= ?1"), [h])
+                    .map_err(sink_error)?;
+            }
+            match height.checked_sub(1) {
+                Some(below) => tx.execute(
+                    "INSERT OR REPLACE INTO projection_meta VALUES (?1, ?2)",
+                    params![META_LAST_HEIGHT, below as i64],
+                ),
+                None => tx.execute(
+                    "DELETE FROM projection_meta WHERE name = ?1",
+                    [META_LAST_HEIGHT],
+                ),
+            }
+            .map_err(sink_error)?;
+            tx.commit().map_err(sink_error)
+        }
+    }
+
+    /// Result of [`query_read_only`]: column names and rows as JSON values.
+    #[derive(Debug, Clone, Serialize)]
+    pub struct QueryRows {
+        pub columns: Vec<String>,
+        pub rows: Vec<Vec<serde_json::Value>>,
+    }
+
+    /// Run `sql` against the projection at `path`, opened read-only so a
+    /// query can never modify it.
+    pub fn query_read_only(path: &Path, sql: &str) -> StorageResult<QueryRows> {
+        let conn = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .map_err(sink_error)?;
+        let mut stmt = conn.prepare(sql).map_err(sink_error)?;
+        let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+        let width = columns.len();
+        let rows = stmt
+            .query_map([], |row| {
+                (0..width)
+                    .map(|i| {
+                        Ok(match row.get_ref(i)? {
+                            ValueRef::Null => serde_json::Value::Null,
+                            ValueRef::Integer(n) => n.into(),
+                            ValueRef::Real(f) => f.into(),
+                            ValueRef::Text(t) => String::from_utf8_lossy(t).into(),
+                            ValueRef::Blob(b) => hex::encode(b).into(),
+                        })
+                    })
+                    .collect()
+            })
+            .map_err(sink_error)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(sink_error)?;
+        Ok(QueryRows { columns, rows })
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::super::{BlockRow, ChaincodeEventRow, StateChangeRow};
+        use super::*;
+        use crate::storage::traits::Transaction;
+        use tempfile::TempDir;
+
+        fn projection(height: u64) -> BlockProjection {
+            BlockProjection {
+                block: BlockRow {
+                    height,
+                    hash: format!("{height:064x}"),
+                    parent_hash: "00".repeat(32),
+                    state_root: "00".repeat(32),
+                    timestamp: 1_000 + height,
+                    proposer: "orderer".to_string(),
+                    tx_count: 1,
+                },
+                transactions: vec![Transaction {
+                    id: format!("tx-{height}"),
+                    block_height: height,
+                    timestamp: 1_000 + height,
+                    input_did: "did:bc:a".to_string(),
+                    output_recipient: "did:bc:b".to_string(),
+                    amount: 5,
+                    state: "committed".to_string(),
+                }],
+                state_changes: vec![StateChangeRow {
+                    block_height: height,
+                    key: "asset1".to_string(),
+                    version: height + 1,
+                    tx_id: format!("tx-{height}"),
+                    timestamp: 1_000 + height,
+                    is_delete: false,
+                    value: format!(r#"{{"owner":"org{height}"}}"#).into_bytes(),
+                }],
+                events: vec![ChaincodeEventRow {
+                    block_height: height,
+                    channel_id: "ch1".to_string(),
+                    chaincode_id: "cc".to_string(),
+                    event_name: "Transfer".to_string(),
+                    payload: vec![0xff],
+                }],
+            }
+        }
+
+        #[test]
+        fn applied_blocks_are_queryable_and_rewind_keeps_events() {
+            let dir = TempDir::new().unwrap();
+            let path = dir.path().join("ledger.sqlite");
+            let mut sink = SqliteProjection::open(&path).unwrap();
+            assert_eq!(sink.last_height().unwrap(), None);
+            sink.apply(&projection(0)).unwrap();
+            sink.apply(&projection(1)).unwrap();
+            assert_eq!(sink.last_height().unwrap(), Some(1));
+
+            let result = query_read_only(
+                &path,
+                "SELECT json_extract(value, '$.owner') AS owner FROM state_changes
+                 ORDER BY version",
+            )
+            .unwrap();
+            assert_eq!(result.columns, vec!["owner"]);
+            assert_eq!(result.rows[1][0], "org1");
+
+            sink.rewind(1).unwrap();
+            assert_eq!(sink.last_height().unwrap(), Some(0));
+            let counts = query_read_only(
+                &path,
+                "SELECT (SELECT count(*) FROM blocks), (SELECT count(*) FROM chaincode_events)",
+            )
+            .unwrap();
+            assert_eq!(
+                counts.rows[0],
+                vec![serde_json::json!(1), serde_json::json!(2)]
+            );
+        }
+
+        #[test]
+        fn read_only_queries_cannot_write() {
+            let dir = TempDir::new().unwrap();
+            let path = dir.path().join("ledger.sqlite");
+            SqliteProjection::open(&path).unwrap();
+            assert!(query_read_only(&path, "DELETE FROM blocks").is_err());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::traits::Block;
+    use crate::storage::{MemoryStore, MemoryWorldState};
+
+    #[derive(Default)]
+    struct VecSink {
+        blocks: Vec<BlockProjection>,
+        kept_events: Vec<ChaincodeEventRow>,
+        last: Option<u64>,
+    }
+
+    impl ProjectionSink for VecSink {
+        fn last_height(&mut self) -> StorageResult<Option<u64>> {
+            Ok(self.last)
+        }
+
+        fn apply(&mut self, projection: &BlockProjection) -> StorageResult<()> {
+            self.kept_events.extend(projection.events.iter().cloned());
+            self.blocks.push(projection.clone());
+            self.last = Some(projection.block.height);
+            Ok(())
+        }
+
+        fn rewind(&mut self, height: u64) -> StorageResult<()> {
+            self.blocks.retain(|b| b.block.height < height);
+            self.last = height.checked_sub(1);
+            Ok(())
+        }
+    }
+
+    fn block(height: u64, txs: &[&str]) -> Block {
+        Block {
+            height,
+            timestamp: 1_000 + height,
+            parent_hash: [0u8; 32],
+            merkle_root: [0u8; 32],
+            transactions: txs.iter().map(|t| t.to_string()).collect(),
+            proposer: "orderer".to_string(),
+            signature: vec![],
+            signature_algorithm: Default::default(),
+            endorsements: vec![],
+            secondary_signature: None,
+            secondary_signature_algorithm: None,
+            hash_algorithm: Default::default(),
+            orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: vec![],
+            config_tx: None,
+            last_config: 0,
+            state_root: [7u8; 32],
+            private_data_purges: vec![],
+        }
+    }
+
+    fn commit(store: &MemoryStore, state: &MemoryWorldState, height: u64, key: &str) {
+        let tx_id = format!("tx-{height}");
+        store.write_block(&block(height, &[&tx_id])).unwrap();
+        store
+            .write_transaction(&Transaction {
+                id: tx_id.clone(),
+                block_height: height,
+                timestamp: 1_000 + height,
+                input_did: "did:bc:a".to_string(),
+                output_recipient: "did:bc:b".to_string(),
+                amount: height,
+                state: "committed".to_string(),
+            })
+            .unwrap();
+        state
+            .put_from_tx(key, format!("v{height}").as_bytes(), &tx_id, height)
+            .unwrap();
+    }
+
+    fn projector() -> (Projector<VecSink>, Arc<MemoryStore>, Arc<MemoryWorldState>) {
+        let store = Arc::new(MemoryStore::new());
+        let state = Arc::new(MemoryWorldState::new());
+        let projector = Projector::new(store.clone(), state.clone(), VecSink::default());
+        (projector, store, state)
+    }
+
+    fn committed(channel_id: &str, height: u64) -> BlockEvent {
+        BlockEvent::BlockCommitted {
+            channel_id: channel_id.to_string(),
+            height,
+            tx_count: 1,
+        }
+    }
+
+    #[test]
+    fn catch_up_projects_blocks_transactions_and_state_changes() {
+        let (mut projector, store, state) = projector();
+        commit(&store, &state, 0, "a");
+        commit(&store, &state, 1, "a");
+
+        assert_eq!(projector.catch_up().unwrap(), 2);
+        let blocks = &projector.sink.blocks;
+        assert_eq!(blocks[1].block.height, 1);
+        assert_eq!(blocks[1].block.state_root, hex::encode([7u8; 32]));
+        assert_eq!(blocks[1].block.tx_count, 1);
+        assert_eq!(blocks[1].transactions[0].id, "tx-1");
+        let change = &blocks[1].state_changes[0];
+        assert_eq!((change.key.as_str(), change.version), ("a", 2));
+        assert_eq!(change.value, b"v1");
+
+        assert_eq!(projector.catch_up().unwrap(), 0);
+    }
+
+    #[test]
+    fn projector_resumes_from_the_sink_height() {
+        let (mut projector, store, state) = projector();
+        commit(&store, &state, 0, "a");
+        commit(&store, &state, 1, "b");
+        projector.sink.last = Some(0);
+
+        assert_eq!(projector.catch_up().unwrap(), 1);
+        assert_eq!(projector.sink.blocks[0].block.height, 1);
+    }
+
+    #[test]
+    fn chaincode_events_are_filed_under_the_next_block_of_their_channel() {
+        let (mut projector, store, state) = projector();
+        commit(&store, &state, 0, "a");
+        projector.catch_up().unwrap();
+
+        let event = BlockEvent::ChaincodeEvent {
+            channel_id: "ch1".to_string(),
+            chaincode_id: "cc".to_string(),
+            event_name: "Transfer".to_string(),
+            payload: b"{}".to_vec(),
+        };
+        projector.handle(&event).unwrap();
+        commit(&store, &state, 1, "a");
+        projector.handle(&committed("ch2", 1)).unwrap();
+        assert!(projector.sink.blocks[1].events.is_empty());
+
+        commit(&store, &state, 2, "a");
+        projector.handle(&committed("ch1", 2)).unwrap();
+        let events = &projector.sink.blocks[2].events;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].block_height, 2);
+        assert_eq!(events[0].event_name, "Transfer");
+    }
+
+    #[test]
+    fn rebuild_from_projects_again_from_a_height() {
+        let (mut projector, store, state) = projector();
+        for height in 0..3 {
+            commit(&store, &state, height, "a");
+        }
+        projector.catch_up().unwrap();
+
+        assert_eq!(projector.rebuild_from(1).unwrap(), 2);
+        let heights: Vec<u64> = projector
+            .sink
+            .blocks
+            .iter()
+            .map(|b| b.block.height)
+            .collect();
+        assert_eq!(heights, vec![0, 1, 2]);
+    }
+}
@@ -0,0 +1,1803 @@
+//! redb storage adapter: a pure-Rust alternative to the RocksDB backend
+//!
+//! Selected with `STORAGE_BACKEND=redb`. The whole store is one
+//! `ledger.redb` file under the storage path, with one table per RocksDB
+//! column family, under the same name and with the same key layout (see
+//! `adapters.rs`), so [`migrate_from_rocksdb`] is a straight copy. Private
+//! data collections get a `private_{name}` table each, created on first
+//! write; their TTLs live in `pvt_ttl` and the `pvt_expiry` index.
+//!
+//! Each write is one redb transaction, so a world-state write lands together
+//! with its history entries. World-state secondary indexes
+//! (`storage::index`), online backups and block write-set recovery are
+//! RocksDB-only.
+//!
+//! With [`RedbBlockStore::enable_encryption`], values of the covered tables
+//! are sealed in `put_raw` / `txn_put` callers and opened in `get_raw` and
+//! `scan` (see `storage::encryption`).
+
+use std::borrow::Cow;
+use std::ops::Bound;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+use redb::{Database, ReadableTable, TableDefinition, TableError, TableHandle, WriteTransaction};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::encryption::{
+    data_key_record_key, is_encrypted_scope, key_versions, open_value, parse_pending_marker,
+    parse_sealed_marker, pending_marker_key, seal_value, sealed_marker_key, CoverageReport,
+    EncryptedStore, KeyEncryptionKey, ReencryptStep, ScopeCoverage, StorageCipher, WrappedDataKey,
+    ENCRYPTED_SCOPES, KEYS_TABLE,
+};
+use super::errors::{StorageError, StorageResult};
+use super::traits::{Block, BlockStore, Credential, HistoryEntry, IdentityRecord, Transaction};
+use super::world_state::{VersionedValue, WorldState};
+use crate::chaincode::{ChaincodeError, ChaincodePackageStore};
+use crate::endorsement::org::Organization;
+use crate::endorsement::registry::OrgRegistry;
+use crate::private_data::{
+    decode_expiry, expiry_height, expiry_key, parse_expiry_key, sha256, ttl_key, PrivateDataStore,
+};
+
+/// File name of the database inside the storage directory.
+pub const DB_FILE: &str = "ledger.redb";
+
+const T_BLOCKS: &str = "blocks";
+const T_TRANSACTIONS: &str = "transactions";
+const T_IDENTITIES: &str = "identities";
+const T_CREDENTIALS: &str = "credentials";
+const T_META: &str = "meta";
+const T_TX_BY_BLOCK: &str = "tx_by_block";
+const T_CRED_BY_SUBJECT: &str = "cred_by_subject";
+const T_ORGANIZATIONS: &str = "organizations";
+const T_CRL: &str = "crl";
+const T_WORLD_STATE: &str = "world_state";
+const T_CHAINCODE_PACKAGES: &str = "chaincode_packages";
+const T_ACLS: &str = "acls";
+const T_KEY_HISTORY: &str = "key_history";
+const T_HISTORY_BY_BLOCK: &str = "history_by_block";
+const T_ENDORSEMENT_POLICIES: &str = "endorsement_policies";
+const T_COLLECTIONS: &str = "collections";
+const T_CHAINCODE_DEFINITIONS: &str = "chaincode_definitions";
+const T_AUDIT_LOG: &str = "audit_log";
+const T_SANDBOX_REPORTS: &str = "sandbox_reports";
+const T_ORACLE_RECORDS: &str = "oracle_records";
+const T_GOVERNANCE_PROPOSALS: &str = "governance_proposals";
+const T_GOVERNANCE_VOTES: &str = "governance_votes";
+const T_VAULT: &str = "vault";
+const T_SCOPES: &str = "scopes";
+const T_ASSEMBLIES: &str = "assemblies";
+const T_SESSIONS: &str = "sessions";
+const T_ACTAS: &str = "actas";
+const T_ASSETS: &str = "assets";
+const T_ASSET_EVENTS: &str = "asset_events";
+const T_ASSET_TOKENS: &str = "asset_tokens";
+const T_COMPLIANCE_RULES: &str = "compliance_rules";
+const T_COMPLIANCE_RESULTS: &str = "compliance_results";
+const T_PRIVATE_TTL: &str = "pvt_ttl";
+const T_PRIVATE_EXPIRY: &str = "pvt_expiry";
+const T_ENCRYPTION_KEYS: &str = KEYS_TABLE;
+
+const META_LATEST_HEIGHT: &[u8] = b"latest_height";
+
+const ALL_TABLES: &[&str] = &[
+    T_BLOCKS,
+    T_TRANSACTIONS,
+    T_IDENTITIES,
+    T_CREDENTIALS,
+    T_META,
+    T_TX_BY_BLOCK,
+    T_CRED_BY_SUBJECT,
+    T_ORGANIZATIONS,
+    T_CRL,
+    T_WORLD_STATE,
+    T_CHAINCODE_PACKAGES,
+    T_ACLS,
+    T_KEY_HISTORY,
+    T_HISTORY_BY_BLOCK,
+    T_ENDORSEMENT_POLICIES,
+    T_COLLECTIONS,
+    T_CHAINCODE_DEFINITIONS,
+    T_AUDIT_LOG,
+    T_SANDBOX_REPORTS,
+    T_ORACLE_RECORDS,
+    T_GOVERNANCE_PROPOSALS,
+    T_GOVERNANCE_VOTES,
+    T_VAULT,
+    T_SCOPES,
+    T_ASSEMBLIES,
+    T_SESSIONS,
+    T_ACTAS,
+    T_ASSETS,
+    T_ASSET_EVENTS,
+    T_ASSET_TOKENS,
+    T_COMPLIANCE_RULES,
+    T_COMPLIANCE_RESULTS,
+    T_PRIVATE_TTL,
+    T_PRIVATE_EXPIRY,
+    T_ENCRYPTION_KEYS,
+];
+
+fn table(name: &str) -> TableDefinition<'_, &'static [u8], &'static [u8]> {
+    TableDefinition::new(name)
+}
+
+fn redb_err(e: impl Into<redb::Error>) -> StorageError {
+    StorageError::RedbError(e.into().to_string())
+}
+
+fn to_json<T: Serialize>(value: &T) -> StorageResult<Vec<u8>> {
+    serde_json::to_vec(value).map_err(|e| StorageError::SerializationError(e.to_string()))
+}
+
+fn from_json<T: DeserializeOwned>(bytes: &[u8]) -> StorageResult<T> {
+    serde_json::from_slice(bytes).map_err(|e| StorageError::DeserializationError(e.to_string()))
+}
+
+// ── Key encoders (same layout as the RocksDB column families) ────────────────
+
+fn block_key(height: u64) -> Vec<u8> {
+    format!("{height:012}").into_bytes()
+}
+
+fn tx_block_index_key(height: u64, tx_id: &str) -> Vec<u8> {
+    format!("{height:012}:{tx_id}").into_bytes()
+}
+
+fn tx_block_prefix(height: u64) -> Vec<u8> {
+    format!("{height:012}:").into_bytes()
+}
+
+/// `{prefix}\x00`, the scan prefix of the NUL-separated index keys.
+fn nul_prefix(prefix: &str) -> Vec<u8> {
+    let mut key = Vec::with_capacity(prefix.len() + 1);
+    key.extend_from_slice(prefix.as_bytes());
+    key.push(0x00);
+    key
+}
+
+fn cred_subject_index_key(subject_did: &str, cred_id: &str) -> Vec<u8> {
+    let mut key = nul_prefix(subject_did);
+    key.extend_from_slice(cred_id.as_bytes());
+    key
+}
+
+fn history_key(state_key: &str, version: u64) -> Vec<u8> {
+    let mut key = nul_prefix(state_key);
+    key.extend_from_slice(format!("{version:012}").as_bytes());
+    key
+}
+
+fn history_by_block_key(height: u64, state_key: &str, version: u64) -> Vec<u8> {
+    let mut key = block_key(height);
+    key.extend_from_slice(&history_key(state_key, version));
+    key
+}
+
+fn private_table_name(collection_name: &str) -> String {
+    format!("private_{collection_name}")
+}
+
+// ── Transaction-scoped helpers ───────────────────────────────────────────────
+
+fn txn_get(txn: &WriteTransaction, name: &str, key: &[u8]) -> StorageResult<Option<Vec<u8>>> {
+    let t = txn.open_table(table(name)).map_err(redb_err)?;
+    let value = t.get(key).map_err(redb_err)?.map(|v| v.value().to_vec());
+    Ok(value)
+}
+
+fn txn_put(txn: &WriteTransaction, name: &str, key: &[u8], value: &[u8]) -> StorageResult<()> {
+    let mut t = txn.open_table(table(name)).map_err(redb_err)?;
+    t.insert(key, value).map_err(redb_err)?;
+    Ok(())
+}
+
+fn txn_remove(txn: &WriteTransaction, name: &str, key: &[u8]) -> StorageResult<()> {
+    let mut t = txn.open_table(table(name)).map_err(redb_err)?;
+    t.remove(key).map_err(redb_err)?;
+    Ok(())
+}
+
+/// Keys of `name` starting with `prefix`, in order.
+fn txn_keys_with_prefix(
+    txn: &WriteTransaction,
+    name: &str,
+    prefix: &[u8],
+) -> StorageResult<Vec<Vec<u8>>> {
+    let t = txn.open_table(table(name)).map_err(redb_err)?;
+    let mut keys = Vec::new();
+    for item in t.range::<&[u8]>(prefix..).map_err(redb_err)? {
+        let (k, _) = item.map_err(redb_err)?;
+        if !k.value().starts_with(prefix) {
+            break;
+        }
+        keys.push(k.value().to_vec());
+    }
+    Ok(keys)
+}
+
+/// Point the TTL record of `(collection_name, key)` at `expiry`, dropping
+/// its old expiry index entry.
+fn txn_set_private_expiry(
+    txn: &WriteTransaction,
+    collection_name: &str,
+    key: &str,
+    expiry: Option<u64>,
+) -> StorageResult<()> {
+    let ttl = ttl_key(collection_name, key);
+    if let Some(old) = txn_get(txn, T_PRIVATE_TTL, &ttl)? {
+        let old = decode_expiry(&old)?;
+        txn_remove(
+            txn,
+            T_PRIVATE_EXPIRY,
+            &expiry_key(old, collection_name, key),
+        )?;
+    }
+    match expiry {
+        Some(expiry) => {
+            txn_put(txn, T_PRIVATE_TTL, &ttl, &expiry.to_le_bytes())?;
+            txn_put(
+                txn,
+                T_PRIVATE_EXPIRY,
+                &expiry_key(expiry, collection_name, key),
+                &[],
+            )
+        }
+        None => txn_remove(txn, T_PRIVATE_TTL, &ttl),
+    }
+}
+
+fn txn_latest_height(txn: &WriteTransaction) -> StorageResult<u64> {
+    txn_get(txn, T_META, META_LATEST_HEIGHT)?.map_or(Ok(0), |bytes| decode_height(&bytes))
+}
+
+fn decode_height(bytes: &[u8]) -> StorageResult<u64> {
+    let arr: [u8; 8] = bytes
+        .try_into()
+        .map_err(|_| StorageError::DataCorrupted("latest_height is not 8 bytes".to_string()))?;
+    Ok(u64::from_le_bytes(arr))
+}
+
+/// redb-backed block store, world state and service stores.
+pub struct RedbBlockStore {
+    db: Database,
+    /// Set by [`Self::enable_encryption`].
+    encryption: OnceLock<StorageCipher>,
+}
+
+impl RedbBlockStore {
+    /// Open (or create) the store in the directory `path`.
+    pub fn new(path: impl AsRef<Path>) -> StorageResult<Self> {
+        let path = path.as_ref();
+        std::fs::create_dir_all(path)?;
+        let db = Database::create(path.join(DB_FILE)).map_err(redb_err)?;
+        let txn = db.begin_write().map_err(redb_err)?;
+        for name in ALL_TABLES {
+            txn.open_table(table(name)).map_err(redb_err)?;
+        }
+        txn.commit().map_err(redb_err)?;
+        Ok(Self {
+            db,
+            encryption: OnceLock::new(),
+        })
+    }
+
+    /// Seal covered values from now on with data keys wrapped under `kek`,
+    /// creating the keys of scopes that have none.
+    pub fn enable_encryption(&self, kek: Arc<dyn KeyEncryptionKey>) -> StorageResult<()> {
+        let records: Vec<WrappedDataKey> = self.list_json(T_ENCRYPTION_KEYS)?;
+        let cipher = StorageCipher::new(kek, &records)?;
+        for (k, _) in self.scan_prefix(T_META, &sealed_marker_key(""))? {
+            if let Some(scope) = parse_sealed_marker(&k) {
+                cipher.mark_sealed(&scope);
+            }
+        }
+        for scope in self.encrypted_scopes()? {
+            self.ensure_data_key(&cipher, &scope)?;
+        }
+        log::info!("storage encryption enabled (KEK {})", cipher.kek_id());
+        self.encryption
+            .set(cipher)
+            .map_err(|_| StorageError::Encryption("encryption is already enabled".to_string()))
+    }
+
+    /// Create the data key of `scope` if it has none, marking the scope for
+    /// re-encryption since it may hold plaintext.
+    fn ensure_data_key(&self, cipher: &StorageCipher, scope: &str) -> StorageResult<()> {
+        cipher.ensure_data_key(scope, |wrapped| self.persist_data_key(wrapped))?;
+        Ok(())
+    }
+
+    fn persist_data_key(&self, wrapped: &WrappedDataKey) -> StorageResult<()> {
+        self.write(|txn| {
+            txn_put(
+                txn,
+                T_ENCRYPTION_KEYS,
+                &data_key_record_key(&wrapped.scope, wrapped.version),
+                &to_json(wrapped)?,
+            )?;
+            txn_put(txn, T_META, &pending_marker_key(&wrapped.scope), b"")
+        })
+    }
+
+    /// Make sure a covered table written outside [`ENCRYPTED_SCOPES`] (a
+    /// private data collection) has its data key before a transaction
+    /// seals values for it.
+    fn prepare_scope(&self, name: &str) -> StorageResult<()> {
+        match self.encryption.get() {
+            Some(cipher) if is_encrypted_scope(name) => self.ensure_data_key(cipher, name),
+            _ => Ok(()),
+        }
+    }
+
+    fn cipher(&self) -> StorageResult<&StorageCipher> {
+        self.encryption
+            .get()
+            .ok_or_else(|| StorageError::Encryption("encryption is not enabled".to_string()))
+    }
+
+    fn seal<'a>(&self, name: &str, key: &[u8], value: &'a [u8]) -> StorageResult<Cow<'a, [u8]>> {
+        seal_value(self.encryption.get(), name, key, value)
+    }
+
+    fn open<'a>(&self, name: &str, key: &[u8], stored: &'a [u8]) -> StorageResult<Cow<'a, [u8]>> {
+        open_value(self.encryption.get(), name, key, stored)
+    }
+
+    #[allow(dead_code)]
+    /// Open (or create) a per-channel store at `<base_path>/channels/<channel_id>`.
+    ///
+    /// `channel_id` must be a non-empty string containing only alphanumeric
+    /// characters, hyphens, or underscores to avoid path-traversal issues.
+    pub fn create_channel_store(channel_id: &str, base_path: &Path) -> StorageResult<Self> {
+        super::validate_channel_id(channel_id)?;
+        Self::new(base_path.join("channels").join(channel_id))
+    }
+
+    // ── Table helpers ────────────────────────────────────────────────────────
+
+    /// The value under `key`, or `None` if absent or the table was never
+    /// created (private data collections).
+    fn get_raw(&self, name: &str, key: &[u8]) -> StorageResult<Option<Vec<u8>>> {
+        let txn = self.db.begin_read().map_err(redb_err)?;
+        let t = match txn.open_table(table(name)) {
+            Ok(t) => t,
+            Err(TableError::TableDoesNotExist(_)) => return Ok(None),
+            Err(e) => return Err(redb_err(e)),
+        };
+        let value = t.get(key).map_err(redb_err)?;
+        value
+            .map(|v| Ok(self.open(name, key, v.value())?.into_owned()))
+            .transpose()
+    }
+
+    /// Visit the entries of `name` from `start` on, in key order, until
+    /// `visit` returns `false`.
+    fn scan(
+        &self,
+        name: &str,
+        start: &[u8],
+        mut visit: impl FnMut(&[u8], &[u8]) -> StorageResult<bool>,
+    ) -> StorageResult<()> {
+        self.scan_stored(name, start, |k, v| visit(k, &self.open(name, k, v)?))
+    }
+
+    /// [`Self::scan`] over the values as stored, sealed or not.
+    fn scan_stored(
+        &self,
+        name: &str,
+        start: &[u8],
+        mut visit: impl FnMut(&[u8], &[u8]) -> StorageResult<bool>,
+    ) -> StorageResult<()> {
+        let txn = self.db.begin_read().map_err(redb_err)?;
+        let t = match txn.open_table(table(name)) {
+            Ok(t) => t,
+            Err(TableError::TableDoesNotExist(_)) => return Ok(()),
+            Err(e) => return Err(redb_err(e)),
+        };
+        for item in t.range::<&[u8]>(start..).map_err(redb_err)? {
+            let (k, v) = item.map_err(redb_err)?;
+            if !visit(k.value(), v.value())? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn scan_prefix(&self, name: &str, prefix: &[u8]) -> StorageResult<Vec<(Vec<u8>, Vec<u8>)>> {
+        let mut entries = Vec::new();
+        self.scan(name, prefix, |k, v| {
+            if !k.starts_with(prefix) {
+                return Ok(false);
+            }
+            entries.push((k.to_vec(), v.to_vec()));
+            Ok(true)
+        })?;
+        Ok(entries)
+    }
+
+    /// Run `f` in one write transaction, committed only if `f` succeeds.
+    fn write<T>(&self, f: impl FnOnce(&WriteTransaction) -> StorageResult<T>) -> StorageResult<T> {
+        let txn = self.db.begin_write().map_err(redb_err)?;
+        match f(&txn) {
+            Ok(out) => {
+                txn.commit().map_err(redb_err)?;
+                Ok(out)
+            }
+            Err(e) => {
+                txn.abort().map_err(redb_err)?;
+                Err(e)
+            }
+        }
+    }
+
+    fn put_raw(&self, name: &str, key: &[u8], value: &[u8]) -> StorageResult<()> {
+        let value = self.seal(name, key, value)?;
+        self.write(|txn| txn_put(txn, name, key, &value))
+    }
+
+    fn remove_raw(&self, name: &str, key: &[u8]) -> StorageResult<()> {
+        self.write(|txn| txn_remove(txn, name, key))
+    }
+
+    fn get_json<T: DeserializeOwned>(&self, name: &str, key: &[u8]) -> StorageResult<Option<T>> {
+        self.get_raw(name, key)?.map(|b| from_json(&b)).transpose()
+    }
+
+    fn put_json<T: Serialize>(&self, name: &str, key: &[u8], value: &T) -> StorageResult<()> {
+        self.put_raw(name, key, &to_json(value)?)
+    }
+
+    fn list_json<T: DeserializeOwned>(&self, name: &str) -> StorageResult<Vec<T>> {
+        self.scan_prefix(name, b"")?
+            .iter()
+            .map(|(_, v)| from_json(v))
+            .collect()
+    }
+
+    // ── World state ──────────────────────────────────────────────────────────
+
+    /// Apply one world-state write (`None` deletes) with its history entry,
+    /// returning the new version.
+    fn write_state(
+        &self,
+        key: &str,
+        data: Option<&[u8]>,
+        tx_id: &str,
+        block_height: Option<u64>,
+    ) -> StorageResult<u64> {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.write(|txn| {
+            let previous: Option<VersionedValue> = txn_get(txn, T_WORLD_STATE, key.as_bytes())?
+                .map(|b| from_json(&self.open(T_WORLD_STATE, key.as_bytes(), &b)?))
+                .transpose()?;
+            let version = previous.map_or(1, |v| v.version + 1);
+            match data {
+                Some(data) => {
+                    let value = VersionedValue {
+                        version,
+                        data: data.to_vec(),
+                    };
+                    let value = to_json(&value)?;
+                    let sealed = self.seal(T_WORLD_STATE, key.as_bytes(), &value)?;
+                    txn_put(txn, T_WORLD_STATE, key.as_bytes(), &sealed)?;
+                }
+                None => txn_remove(txn, T_WORLD_STATE, key.as_bytes())?,
+            }
+            let entry = HistoryEntry {
+                version,
+                data: data.map(<[u8]>::to_vec).unwrap_or_default(),
+                tx_id: tx_id.to_string(),
+                timestamp,
+                is_delete: data.is_none(),
+                block_height,
+            };
+            let entry_key = history_key(key, version);
+            let entry = to_json(&entry)?;
+            txn_put(
+                txn,
+                T_KEY_HISTORY,
+                &entry_key,
+                &self.seal(T_KEY_HISTORY, &entry_key, &entry)?,
+            )?;
+            if let Some(height) = block_height {
+                txn_put(
+                    txn,
+                    T_HISTORY_BY_BLOCK,
+                    &history_by_block_key(height, key, version),
+                    b"",
+                )?;
+            }
+            Ok(version)
+        })
+    }
+}
+
+impl BlockStore for RedbBlockStore {
+    fn write_block(&self, block: &Block) -> StorageResult<()> {
+        let key = block_key(block.height);
+        let value = to_json(block)?;
+        let value = self.seal(T_BLOCKS, &key, &value)?;
+        self.write(|txn| {
+            txn_put(txn, T_BLOCKS, &key, &value)?;
+            if block.height >= txn_latest_height(txn)? {
+                txn_put(txn, T_META, META_LATEST_HEIGHT, &block.height.to_le_bytes())?;
+            }
+            Ok(())
+        })
+    }
+
+    fn read_block(&self, height: u64) -> StorageResult<Block> {
+        self.get_json(T_BLOCKS, &block_key(height))?
+            .ok_or_else(|| StorageError::KeyNotFound(format!("block:{height}")))
+    }
+
+    fn write_transaction(&self, tx: &Transaction) -> StorageResult<()> {
+        let value = to_json(tx)?;
+        let value = self.seal(T_TRANSACTIONS, tx.id.as_bytes(), &value)?;
+        self.write(|txn| {
+            txn_put(txn, T_TRANSACTIONS, tx.id.as_bytes(), &value)?;
+            txn_put(
+                txn,
+                T_TX_BY_BLOCK,
+                &tx_block_index_key(tx.block_height, &tx.id),
+                b"",
+            )
+        })
+    }
+
+    fn read_transaction(&self, tx_id: &str) -> StorageResult<Transaction> {
+        self.get_json(T_TRANSACTIONS, tx_id.as_bytes())?
+            .ok_or_else(|| StorageError::KeyNotFound(format!("tx:{tx_id}")))
+    }
+
+    fn write_identity(&self, identity: &IdentityRecord) -> StorageResult<()> {
+        self.put_json(T_IDENTITIES, identity.did.as_bytes(), identity)
+    }
+
+    fn read_identity(&self, did: &str) -> StorageResult<IdentityRecord> {
+        self.get_json(T_IDENTITIES, did.as_bytes())?
+            .ok_or_else(|| StorageError::IdentityNotFound(did.to_string()))
+    }
+
+    fn list_identities(&self) -> StorageResult<Vec<IdentityRecord>> {
+        self.list_json(T_IDENTITIES)
+    }
+
+    fn write_credential(&self, credential: &Credential) -> StorageResult<()> {
+        let value = to_json(credential)?;
+        self.write(|txn| {
+            txn_put(txn, T_CREDENTIALS, credential.id.as_bytes(), &value)?;
+            txn_put(
+                txn,
+                T_CRED_BY_SUBJECT,
+                &cred_subject_index_key(&credential.subject_did, &credential.id),
+                b"",
+            )
+        })
+    }
+
+    fn read_credential(&self, cred_id: &str) -> StorageResult<Credential> {
+        self.get_json(T_CREDENTIALS, cred_id.as_bytes())?
+            .ok_or_else(|| StorageError::CredentialNotFound(cred_id.to_string()))
+    }
+
+    fn list_credentials(&self) -> StorageResult<Vec<Credential>> {
+        self.list_json(T_CREDENTIALS)
+    }
+
+    fn write_batch(&self, blocks: &[Block], txs: &[Transaction]) -> StorageResult<()> {
+        if blocks.is_empty() && txs.is_empty() {
+            return Err(StorageError::BatchOperationFailed(
+                "Empty batch".to_string(),
+            ));
+        }
+        self.write(|txn| {
+            let current_latest = txn_latest_height(txn)?;
+            let mut new_latest = current_latest;
+            for block in blocks {
+                let key = block_key(block.height);
+                txn_put(
+                    txn,
+                    T_BLOCKS,
+                    &key,
+                    &self.seal(T_BLOCKS, &key, &to_json(block)?)?,
+                )?;
+                new_latest = new_latest.max(block.height);
+            }
+            for tx in txs {
+                let value = to_json(tx)?;
+                let value = self.seal(T_TRANSACTIONS, tx.id.as_bytes(), &value)?;
+                txn_put(txn, T_TRANSACTIONS, tx.id.as_bytes(), &value)?;
+                txn_put(
+                    txn,
+                    T_TX_BY_BLOCK,
+                    &tx_block_index_key(tx.block_height, &tx.id),
+                    b"",
+                )?;
+            }
+            if new_latest > current_latest {
+                txn_put(txn, T_META, META_LATEST_HEIGHT, &new_latest.to_le_bytes())?;
+            }
+            Ok(())
+        })
+    }
+
+    fn get_latest_height(&self) -> StorageResult<u64> {
+        self.get_raw(T_META, META_LATEST_HEIGHT)?
+            .map_or(Ok(0), |bytes| decode_height(&bytes))
+    }
+
+    fn block_exists(&self, height: u64) -> StorageResult<bool> {
+        Ok(self.get_raw(T_BLOCKS, &block_key(height))?.is_some())
+    }
+
+    fn transactions_by_block_height(&self, height: u64) -> StorageResult<Vec<Transaction>> {
+        let prefix = tx_block_prefix(height);
+        let mut txs = Vec::new();
+        for (key, _) in self.scan_prefix(T_TX_BY_BLOCK, &prefix)? {
+            let tx_id = std::str::from_utf8(&key[prefix.len()..])
+                .map_err(|e| StorageError::DataCorrupted(e.to_string()))?;
+            let tx = self
+                .get_json(T_TRANSACTIONS, tx_id.as_bytes())?
+                .ok_or_else(|| StorageError::KeyNotFound(format!("tx:{tx_id}")))?;
+            txs.push(tx);
+        }
+        Ok(txs)
+    }
+
+    fn prune_block(&self, header: &Block) -> StorageResult<()> {
+        let key = block_key(header.height);
+        let value = to_json(header)?;
+        let value = self.seal(T_BLOCKS, &key, &value)?;
+        let prefix = tx_block_prefix(header.height);
+        self.write(|txn| {
+            if txn_get(txn, T_BLOCKS, &key)?.is_none() {
+                return Err(StorageError::KeyNotFound(format!(
+                    "block:{}",
+                    header.height
+                )));
+            }
+            txn_put(txn, T_BLOCKS, &key, &value)?;
+            for idx_key in txn_keys_with_prefix(txn, T_TX_BY_BLOCK, &prefix)? {
+                txn_remove(txn, T_TRANSACTIONS, &idx_key[prefix.len()..])?;
+                txn_remove(txn, T_TX_BY_BLOCK, &idx_key)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn credentials_by_subject_did(&self, subject_did: &str) -> StorageResult<Vec<Credential>> {
+        let prefix = nul_prefix(subject_did);
+        let mut creds = Vec::new();
+        for (key, _) in self.scan_prefix(T_CRED_BY_SUBJECT, &prefix)? {
+            let cred_id = std::str::from_utf8(&key[prefix.len()..])
+                .map_err(|e| StorageError::DataCorrupted(e.to_string()))?;
+            let cred = self
+                .get_json(T_CREDENTIALS, cred_id.as_bytes())?
+                .ok_or_else(|| StorageError::KeyNotFound(format!("cred:{cred_id}")))?;
+            creds.push(cred);
+        }
+        Ok(creds)
+    }
+
+    fn mark_tx_seen(&self, tx_id: &str, timestamp: u64) -> StorageResult<()> {
+        let key = format!("seen_tx:{tx_id}");
+        self.put_raw(T_META, key.as_bytes(), &timestamp.to_be_bytes())
+    }
+
+    fn is_tx_seen(&self, tx_id: &str) -> StorageResult<bool> {
+        let key = format!("seen_tx:{tx_id}");
+        Ok(self.get_raw(T_META, key.as_bytes())?.is_some())
+    }
+
+    fn load_seen_txs(&self) -> StorageResult<Vec<(String, u64)>> {
+        let prefix = b"seen_tx:";
+        let mut result = Vec::new();
+        for (key, value) in self.scan_prefix(T_META, prefix)? {
+            let tx_id = std::str::from_utf8(&key[prefix.len()..])
+                .map_err(|e| StorageError::DataCorrupted(e.to_string()))?
+                .to_string();
+            let ts = match <[u8; 8]>::try_from(value.as_slice()) {
+                Ok(arr) => u64::from_be_bytes(arr),
+                Err(_) => 0,
+            };
+            result.push((tx_id, ts));
+        }
+        Ok(result)
+    }
+
+    fn cleanup_seen_txs(&self, max_age_secs: u64) -> StorageResult<u64> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let expired: Vec<String> = self
+            .load_seen_txs()?
+            .into_iter()
+            .filter(|(_, ts)| now.saturating_sub(*ts) > max_age_secs)
+            .map(|(tx_id, _)| format!("seen_tx:{tx_id}"))
+            .collect();
+        self.write(|txn| {
+            for key in &expired {
+                txn_remove(txn, T_META, key.as_bytes())?;
+            }
+            Ok(expired.len() as u64)
+        })
+    }
+
+    // ── Governance persistence ─────────────────────────────────────────────
+
+    fn write_proposal(
+        &self,
+        proposal: &crate::governance::proposals::Proposal,
+    ) -> StorageResult<()> {
+        let key = format!("{:012}", proposal.id);
+        self.put_json(T_GOVERNANCE_PROPOSALS, key.as_bytes(), proposal)
+    }
+
+    fn read_proposal(&self, id: u64) -> StorageResult<crate::governance::proposals::Proposal> {
+        let key = format!("{id:012}");
+        self.get_json(T_GOVERNANCE_PROPOSALS, key.as_bytes())?
+            .ok_or_else(|| StorageError::KeyNotFound(format!("PROPOSAL:{key}")))
+    }
+
+    fn list_proposals(&self) -> StorageResult<Vec<crate::governance::proposals::Proposal>> {
+        self.list_json(T_GOVERNANCE_PROPOSALS)
+    }
+
+    fn write_vote(&self, vote: &crate::governance::voting::Vote) -> StorageResult<()> {
+        let key = format!("{:012}:{}", vote.proposal_id, vote.voter);
+        self.put_json(T_GOVERNANCE_VOTES, key.as_bytes(), vote)
+    }
+
+    fn list_votes(&self, proposal_id: u64) -> StorageResult<Vec<crate::governance::voting::Vote>> {
+        let prefix = format!("{proposal_id:012}:");
+        self.scan_prefix(T_GOVERNANCE_VOTES, prefix.as_bytes())?
+            .iter()
+            .map(|(_, v)| from_json(v))
+            .collect()
+    }
+
+    fn write_vault(&self, did: &str, encrypted_wallet: &serde_json::Value) -> StorageResult<()> {
+        self.put_json(T_VAULT, did.as_bytes(), encrypted_wallet)
+    }
+
+    fn read_vault(&self, did: &str) -> StorageResult<serde_json::Value> {
+        self.get_json(T_VAULT, did.as_bytes())?
+            .ok_or_else(|| StorageError::KeyNotFound(format!("vault:{did}")))
+    }
+
+    fn write_vault_recovery(&self, blind_index: &str, did: &str) -> StorageResult<()> {
+        let key = format!("recovery:{blind_index}");
+        self.put_raw(T_VAULT, key.as_bytes(), did.as_bytes())
+    }
+
+    fn read_vault_by_recovery(&self, blind_index: &str) -> StorageResult<String> {
+        let key = format!("recovery:{blind_index}");
+        let data = self
+            .get_raw(T_VAULT, key.as_bytes())?
+            .ok_or_else(|| StorageError::KeyNotFound("vault recovery entry not found".into()))?;
+        String::from_utf8(data).map_err(|e| StorageError::DeserializationError(e.to_string()))
+    }
+
+    // ── Governance entities ─────────────────────────────────────────────
+
+    fn write_scope(&self, scope: &super::traits::Scope) -> StorageResult<()> {
+        self.put_json(T_SCOPES, scope.id.as_bytes(), scope)
+    }
+    fn read_scope(&self, id: &str) -> StorageResult<super::traits::Scope> {
+        self.get_json(T_SCOPES, id.as_bytes())?
+            .ok_or_else(|| StorageError::KeyNotFound(format!("scope:{id}")))
+    }
+    fn list_scopes(&self) -> StorageResult<Vec<super::traits::Scope>> {
+        self.list_json(T_SCOPES)
+    }
+    fn delete_scope(&self, id: &str) -> StorageResult<()> {
+        self.remove_raw(T_SCOPES, id.as_bytes())
+    }
+
+    fn write_assembly(&self, assembly: &super::traits::Assembly) -> StorageResult<()> {
+        self.put_json(T_ASSEMBLIES, assembly.id.as_bytes(), assembly)
+    }
+    fn read_assembly(&self, id: &str) -> StorageResult<super::traits::Assembly> {
+        self.get_json(T_ASSEMBLIES, id.as_bytes())?
+            .ok_or_else(|| StorageError::KeyNotFound(format!("assembly:{id}")))
+    }
+    fn list_assemblies(&self) -> StorageResult<Vec<super::traits::Assembly>> {
+        self.list_json(T_ASSEMBLIES)
+    }
+    fn list_assemblies_by_scope(
+        &self,
+        scope_id: &str,
+    ) -> StorageResult<Vec<super::traits::Assembly>> {
+        Ok(self
+            .list_assemblies()?
+            .into_iter()
+            .filter(|a| a.scope_id == scope_id)
+            .collect())
+    }
+    fn delete_assembly(&self, id: &str) -> StorageResult<()> {
+        self.remove_raw(T_ASSEMBLIES, id.as_bytes())
+    }
+
+    fn write_session(&self, session: &super::traits::Session) -> StorageResult<()> {
+        self.put_json(T_SESSIONS, session.id.as_bytes(), session)
+    }
+    fn read_session(&self, id: &str) -> StorageResult<super::traits::Session> {
+        self.get_json(T_SESSIONS, id.as_bytes())?
+            .ok_or_else(|| StorageError::KeyNotFound(format!("session:{id}")))
+    }
+    fn list_sessions_by_assembly(
+        &self,
+        assembly_id: &str,
+    ) -> StorageResult<Vec<super::traits::Session>> {
+        Ok(self
+            .list_json::<super::traits::Session>(T_SESSIONS)?
+            .into_iter()
+            .filter(|s| s.assembly_id == assembly_id)
+            .collect())
+    }
+    fn delete_session(&self, id: &str) -> StorageResult<()> {
+        self.remove_raw(T_SESSIONS, id.as_bytes())
+    }
+
+    fn write_acta(&self, acta: &super::traits::Acta) -> StorageResult<()> {
+        self.put_json(T_ACTAS, acta.id.as_bytes(), acta)
+    }
+    fn read_acta(&self, id: &str) -> StorageResult<super::traits::Acta> {
+        self.get_json(T_ACTAS, id.as_bytes())?
+            .ok_or_else(|| StorageError::KeyNotFound(format!("acta:{id}")))
+    }
+    fn list_actas(&self) -> StorageResult<Vec<super::traits::Acta>> {
+        self.list_json(T_ACTAS)
+    }
+    fn delete_acta(&self, id: &str) -> StorageResult<()> {
+        self.remove_raw(T_ACTAS, id.as_bytes())
+    }
+
+    // ── Asset Registry ──────────────────────────────────────────────────
+
+    fn write_asset(&self, asset: &crate::registry::types::Asset) -> StorageResult<()> {
+        self.put_json(T_ASSETS, asset.id.as_bytes(), asset)
+    }
+    fn read_asset(&self, id: &str) -> StorageResult<crate::registry::types::Asset> {
+        self.get_json(T_ASSETS, id.as_bytes())?
+            .ok_or_else(|| StorageError::KeyNotFound(format!("asset:{id}")))
+    }
+    fn list_assets(&self) -> StorageResult<Vec<crate::registry::types::Asset>> {
+        self.list_json(T_ASSETS)
+    }
+    fn delete_asset(&self, id: &str) -> StorageResult<()> {
+        self.remove_raw(T_ASSETS, id.as_bytes())
+    }
+    fn write_asset_event(&self, event: &crate::registry::types::AssetEvent) -> StorageResult<()> {
+        self.put_json(T_ASSET_EVENTS, event.id.as_bytes(), event)
+    }
+    fn read_asset_event(&self, id: &str) -> StorageResult<crate::registry::types::AssetEvent> {
+        self.get_json(T_ASSET_EVENTS, id.as_bytes())?
+            .ok_or_else(|| StorageError::KeyNotFound(format!("asset_event:{id}")))
+    }
+    fn list_asset_events(
+        &self,
+        asset_id: &str,
+    ) -> StorageResult<Vec<crate::registry::types::AssetEvent>> {
+        Ok(self
+            .list_json::<crate::registry::types::AssetEvent>(T_ASSET_EVENTS)?
+            .into_iter()
+            .filter(|e| e.asset_id == asset_id)
+            .collect())
+    }
+
+    // ── RWA Tokenization ────────────────────────────────────────────────
+
+    fn write_asset_token(
+        &self,
+        token: &crate::registry::tokenization::AssetToken,
+    ) -> StorageResult<()> {
+        self.put_json(T_ASSET_TOKENS, token.id.as_bytes(), token)
+    }
+    fn read_asset_token(
+        &self,
+        id: &str,
+    ) -> StorageResult<crate::registry::tokenization::AssetToken> {
+        self.get_json(T_ASSET_TOKENS, id.as_bytes())?
+            .ok_or_else(|| StorageError::KeyNotFound(format!("token:{id}")))
+    }
+    fn list_asset_tokens(&self) -> StorageResult<Vec<crate::registry::tokenization::AssetToken>> {
+        self.list_json(T_ASSET_TOKENS)
+    }
+    fn delete_asset_token(&self, id: &str) -> StorageResult<()> {
+        self.remove_raw(T_ASSET_TOKENS, id.as_bytes())
+    }
+
+    // ── Compliance Automation ───────────────────────────────────────────
+
+    fn write_compliance_rule(
+        &self,
+        rule: &crate::registry::compliance::ComplianceRule,
+    ) -> StorageResult<()> {
+        self.put_json(T_COMPLIANCE_RULES, rule.id.as_bytes(), rule)
+    }
+    fn read_compliance_rule(
+        &self,
+        id: &str,
+    ) -> StorageResult<crate::registry::compliance::ComplianceRule> {
+        self.get_json(T_COMPLIANCE_RULES, id.as_bytes())?
+            .ok_or_else(|| StorageError::KeyNotFound(format!("rule:{id}")))
+    }
+    fn list_compliance_rules(
+        &self,
+    ) -> StorageResult<Vec<crate::registry::compliance::ComplianceRule>> {
+        self.list_json(T_COMPLIANCE_RULES)
+    }
+    fn delete_compliance_rule(&self, id: &str) -> StorageResult<()> {
+        self.remove_raw(T_COMPLIANCE_RULES, id.as_bytes())
+    }
+    fn write_compliance_result(
+        &self,
+        result: &crate::registry::compliance::ComplianceResult,
+    ) -> StorageResult<()> {
+        self.put_json(T_COMPLIANCE_RESULTS, result.id.as_bytes(), result)
+    }
+    fn list_compliance_results(
+        &self,
+        asset_id: &str,
+    ) -> StorageResult<Vec<crate::registry::compliance::ComplianceResult>> {
+        Ok(self
+            .list_json::<crate::registry::compliance::ComplianceResult>(T_COMPLIANCE_RESULTS)?
+            .into_iter()
+            .filter(|r| r.asset_id == asset_id)
+            .collect())
+    }
+}
+
+impl OrgRegistry for RedbBlockStore {
+    fn register_org(&self, org: &Organization) -> StorageResult<()> {
+        self.put_json(T_ORGANIZATIONS, org.org_id.as_bytes(), org)
+    }
+
+    fn get_org(&self, org_id: &str) -> StorageResult<Organization> {
+        self.get_json(T_ORGANIZATIONS, org_id.as_bytes())?
+            .ok_or_else(|| StorageError::KeyNotFound(org_id.to_string()))
+    }
+
+    fn list_orgs(&self) -> StorageResult<Vec<Organization>> {
+        self.list_json(T_ORGANIZATIONS)
+    }
+
+    fn remove_org(&self, org_id: &str) -> StorageResult<()> {
+        self.write(|txn| {
+            if txn_get(txn, T_ORGANIZATIONS, org_id.as_bytes())?.is_none() {
+                return Err(StorageError::KeyNotFound(org_id.to_string()));
+            }
+            txn_remove(txn, T_ORGANIZATIONS, org_id.as_bytes())
+        })
+    }
+}
+
+impl crate::msp::CrlStore for RedbBlockStore {
+    fn write_crl(&self, msp_id: &str, serials: &[String]) -> StorageResult<()> {
+        self.put_json(T_CRL, msp_id.as_bytes(), &serials)
+    }
+
+    fn read_crl(&self, msp_id: &str) -> StorageResult<Vec<String>> {
+        Ok(self.get_json(T_CRL, msp_id.as_bytes())?.unwrap_or_default())
+    }
+}
+
+impl WorldState for RedbBlockStore {
+    fn get(&self, key: &str) -> StorageResult<Option<VersionedValue>> {
+        self.get_json(T_WORLD_STATE, key.as_bytes())
+    }
+
+    fn put(&self, key: &str, data: &[u8]) -> StorageResult<u64> {
+        self.write_state(key, Some(data), "", None)
+    }
+
+    fn put_from_tx(
+        &self,
+        key: &str,
+        data: &[u8],
+        tx_id: &str,
+        block_height: u64,
+    ) -> StorageResult<u64> {
+        self.write_state(key, Some(data), tx_id, Some(block_height))
+    }
+
+    fn restore_entry(&self, key: &str, value: &VersionedValue) -> StorageResult<()> {
+        self.put_json(T_WORLD_STATE, key.as_bytes(), value)
+    }
+
+    fn delete(&self, key: &str) -> StorageResult<()> {
+        self.write_state(key, None, "", None).map(|_| ())
+    }
+
+    fn get_range(&self, start: &str, end: &str) -> StorageResult<Vec<(String, VersionedValue)>> {
+        self.get_range_page(start, end, usize::MAX)
+    }
+
+    fn get_range_page(
+        &self,
+        start: &str,
+        end: &str,
+        limit: usize,
+    ) -> StorageResult<Vec<(String, VersionedValue)>> {
+        let mut result = Vec::new();
+        if limit == 0 {
+            return Ok(result);
+        }
+        self.scan(T_WORLD_STATE, start.as_bytes(), |k, v| {
+            if k >= end.as_bytes() {
+                return Ok(false);
+            }
+            let key = String::from_utf8(k.to_vec())
+                .map_err(|e| StorageError::DeserializationError(e.to_string()))?;
+            result.push((key, from_json(v)?));
+            Ok(result.len() < limit)
+        })?;
+        Ok(result)
+    }
+
+    fn get_history(&self, key: &str) -> StorageResult<Vec<HistoryEntry>> {
+        self.scan_prefix(T_KEY_HISTORY, &nul_prefix(key))?
+            .iter()
+            .map(|(_, v)| from_json(v))
+            .collect()
+    }
+
+    fn history_at(&self, block_height: u64) -> StorageResult<Vec<(String, HistoryEntry)>> {
+        let prefix = block_key(block_height);
+        let mut entries = Vec::new();
+        for (k, _) in self.scan_prefix(T_HISTORY_BY_BLOCK, &prefix)? {
+            let history_key = &k[prefix.len()..];
+            let Some(sep) = history_key.iter().rposition(|b| *b == 0x00) else {
+                continue;
+            };
+            let state_key = String::from_utf8(history_key[..sep].to_vec())
+                .map_err(|e| StorageError::DeserializationError(e.to_string()))?;
+            if let Some(entry) = self.get_json(T_KEY_HISTORY, history_key)? {
+                entries.push((state_key, entry));
+            }
+        }
+        Ok(entries)
+    }
+}
+
+impl ChaincodePackageStore for RedbBlockStore {
+    fn store_package(
+        &self,
+        chaincode_id: &str,
+        version: &str,
+        wasm_bytes: &[u8],
+    ) -> Result<(), ChaincodeError> {
+        let key = format!("{chaincode_id}:{version}");
+        self.put_raw(T_CHAINCODE_PACKAGES, key.as_bytes(), wasm_bytes)
+            .map_err(|e| ChaincodeError::Storage(e.to_string()))
+    }
+
+    fn get_package(
+        &self,
+        chaincode_id: &str,
+        version: &str,
+    ) -> Result<Option<Vec<u8>>, ChaincodeError> {
+        let key = format!("{chaincode_id}:{version}");
+        self.get_raw(T_CHAINCODE_PACKAGES, key.as_bytes())
+            .map_err(|e| ChaincodeError::Storage(e.to_string()))
+    }
+}
+
+impl PrivateDataStore for RedbBlockStore {
+    fn put_private_data(
+        &self,
+        collection_name: &str,
+        key: &str,
+        value: &[u8],
+    ) -> StorageResult<[u8; 32]> {
+        let hash = sha256(value);
+        let name = private_table_name(collection_name);
+        self.prepare_scope(&name)?;
+        self.put_raw(&name, key.as_bytes(), value)?;
+        Ok(hash)
+    }
+
+    fn put_private_data_at(
+        &self,
+        collection_name: &str,
+        key: &str,
+        value: &[u8],
+        written_at_height: u64,
+        blocks_to_live: u64,
+    ) -> StorageResult<[u8; 32]> {
+        let hash = sha256(value);
+        let name = private_table_name(collection_name);
+        self.prepare_scope(&name)?;
+        let value = self.seal(&name, key.as_bytes(), value)?;
+        self.write(|txn| {
+            txn_put(txn, &name, key.as_bytes(), &value)?;
+            txn_set_private_expiry(
+                txn,
+                collection_name,
+                key,
+                expiry_height(written_at_height, blocks_to_live),
+            )
+        })?;
+        Ok(hash)
+    }
+
+    fn get_private_data(&self, collection_name: &str, key: &str) -> StorageResult<Option<Vec<u8>>> {
+        self.get_raw(&private_table_name(collection_name), key.as_bytes())
+    }
+
+    fn purge_expired(&self, current_height: u64) {
+        let result = self.write(|txn| {
+            let mut due = Vec::new();
+            {
+                let t = txn.open_table(table(T_PRIVATE_EXPIRY)).map_err(redb_err)?;
+                for item in t.iter().map_err(redb_err)? {
+                    let (k, _) = item.map_err(redb_err)?;
+                    match parse_expiry_key(k.value()) {
+                        Some((expiry, collection, key)) if expiry <= current_height => {
+                            due.push((collection, key));
+                        }
+                        Some(_) => break,
+                        None => {
+                            return Err(StorageError::DataCorrupted(
+                                "malformed private data expiry key".to_string(),
+                            ))
+                        }
+                    }
+                }
+            }
+            for (collection, key) in &due {
+                txn_remove(txn, &private_table_name(collection), key.as_bytes())?;
+                txn_set_private_expiry(txn, collection, key, None)?;
+            }
+            Ok(due.len())
+        });
+        match result {
+            Ok(0) => {}
+            Ok(n) => {
+                log::info!("private data: purged {n} expired entries at height {current_height}")
+            }
+            Err(e) => log::error!("private data TTL purge failed: {e}"),
+        }
+    }
+
+    fn purge_private_data(&self, collection_name: &str, key: &str) -> StorageResult<bool> {
+        let name = private_table_name(collection_name);
+        self.write(|txn| {
+            let existed = txn_get(txn, &name, key.as_bytes())?.is_some();
+            txn_remove(txn, &name, key.as_bytes())?;
+            txn_set_private_expiry(txn, collection_name, key, None)?;
+            Ok(existed)
+        })
+    }
+}
+
+impl crate::acl::AclProvider for RedbBlockStore {
+    fn set_acl(&self, resource: &str, policy_ref: &str) -> StorageResult<()> {
+        let entry = crate::acl::AclEntry::new(resource, policy_ref);
+        self.put_json(T_ACLS, resource.as_bytes(), &entry)
+    }
+
+    fn get_acl(&self, resource: &str) -> StorageResult<Option<crate::acl::AclEntry>> {
+        self.get_json(T_ACLS, resource.as_bytes())
+    }
+
+    fn list_acls(&self) -> StorageResult<Vec<crate::acl::AclEntry>> {
+        self.list_json(T_ACLS)
+    }
+
+    fn remove_acl(&self, resource: &str) -> StorageResult<()> {
+        self.remove_raw(T_ACLS, resource.as_bytes())
+    }
+}
+
+impl crate::endorsement::policy_store::PolicyStore for RedbBlockStore {
+    fn set_policy(
+        &self,
+        resource_id: &str,
+        policy: &crate::endorsement::policy::EndorsementPolicy,
+    ) -> StorageResult<()> {
+        self.put_json(T_ENDORSEMENT_POLICIES, resource_id.as_bytes(), policy)
+    }
+
+    fn get_policy(
+        &self,
+        resource_id: &str,
+    ) -> StorageResult<crate::endorsement::policy::EndorsementPolicy> {
+        self.get_json(T_ENDORSEMENT_POLICIES, resource_id.as_bytes())?
+            .ok_or_else(|| StorageError::KeyNotFound(resource_id.to_string()))
+    }
+}
+
+impl crate::private_data::CollectionRegistry for RedbBlockStore {
+    fn register(
+        &self,
+        collection: crate::private_data::PrivateDataCollection,
+    ) -> Result<(), crate::private_data::PrivateDataError> {
+        self.put_json(T_COLLECTIONS, collection.name.as_bytes(), &collection)
+            .map_err(|e| crate::private_data::PrivateDataError::InvalidCollection(e.to_string()))
+    }
+
+    fn get(&self, name: &str) -> Option<crate::private_data::PrivateDataCollection> {
+        self.get_json(T_COLLECTIONS, name.as_bytes()).ok()?
+    }
+
+    fn list(&self) -> Vec<crate::private_data::PrivateDataCollection> {
+        self.scan_prefix(T_COLLECTIONS, b"")
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|(_, v)| serde_json::from_slice(v).ok())
+            .collect()
+    }
+}
+
+impl crate::chaincode::ChaincodeDefinitionStore for RedbBlockStore {
+    fn upsert_definition(
+        &self,
+        def: crate::chaincode::definition::ChaincodeDefinition,
+    ) -> Result<(), ChaincodeError> {
+        let key = format!("{}:{}", def.chaincode_id, def.version);
+        self.put_json(T_CHAINCODE_DEFINITIONS, key.as_bytes(), &def)
+            .map_err(|e| ChaincodeError::Execution(e.to_string()))
+    }
+
+    fn get_definition(
+        &self,
+        chaincode_id: &str,
+        version: &str,
+    ) -> Result<Option<crate::chaincode::definition::ChaincodeDefinition>, ChaincodeError> {
+        let key = format!("{chaincode_id}:{version}");
+        self.get_json(T_CHAINCODE_DEFINITIONS, key.as_bytes())
+            .map_err(|e| ChaincodeError::Execution(e.to_string()))
+    }
+}
+
+impl crate::audit::AuditStore for RedbBlockStore {
+    fn append(&self, entry: &crate::audit::AuditEntry) -> StorageResult<()> {
+        let key = format!("{}:{}", entry.timestamp, entry.trace_id);
+        self.put_json(T_AUDIT_LOG, key.as_bytes(), entry)
+    }
+
+    fn query(
+        &self,
+        from: Option<&str>,
+        to: Option<&str>,
+        org_id: Option<&str>,
+        action: Option<&crate::audit::AuditAction>,
+        limit: usize,
+    ) -> StorageResult<Vec<crate::audit::AuditEntry>> {
+        let mut results = Vec::new();
+        let start = from.map(str::as_bytes).unwrap_or_default();
+        self.scan(T_AUDIT_LOG, start, |k, v| {
+            // Key format: "{timestamp}:{trace_id}"
+            let key = String::from_utf8_lossy(k);
+            let ts = key.split(':').next().unwrap_or("");
+            if to.is_some_and(|t| ts > t) {
+                return Ok(false);
+            }
+            let entry: crate::audit::AuditEntry = from_json(v)?;
+            if org_id.is_some_and(|org| entry.org_id != org)
+                || action.is_some_and(|act| &entry.action != act)
+            {
+                return Ok(true);
+            }
+            results.push(entry);
+            Ok(results.len() < limit)
+        })?;
+        Ok(results)
+    }
+}
+
+impl crate::chaincode::sandbox::SandboxReportStore for RedbBlockStore {
+    fn store_report(&self, report: &crate::chaincode::sandbox::SandboxReport) {
+        let key = format!("{}:{}", report.chaincode_id, report.version);
+        let _ = self.put_json(T_SANDBOX_REPORTS, key.as_bytes(), report);
+    }
+
+    fn get_report(
+        &self,
+        chaincode_id: &str,
+        version: &str,
+    ) -> Option<crate::chaincode::sandbox::SandboxReport> {
+        let key = format!("{chaincode_id}:{version}");
+        self.get_json(T_SANDBOX_REPORTS, key.as_bytes()).ok()?
+    }
+}
+
+impl crate::legal_oracle::OracleRecordStore for RedbBlockStore {
+    fn store(
+        &self,
+        record: &crate::legal_oracle::OracleRecord,
+    ) -> Result<(), crate::legal_oracle::OracleError> {
+        self.put_json(T_ORACLE_RECORDS, record.id.as_bytes(), record)
+            .map_err(|e| crate::legal_oracle::OracleError::Storage(e.to_string()))
+    }
+
+    fn get(
+        &self,
+        id: &str,
+    ) -> Result<Option<crate::legal_oracle::OracleRecord>, crate::legal_oracle::OracleError> {
+        self.get_json(T_ORACLE_RECORDS, id.as_bytes())
+            .map_err(|e| crate::legal_oracle::OracleError::Storage(e.to_string()))
+    }
+
+    fn list(
+        &self,
+        source: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<crate::legal_oracle::OracleRecord>, crate::legal_oracle::OracleError> {
+        let mut results = Vec::new();
+        self.scan(T_ORACLE_RECORDS, b"", |_, v| {
+            let record: crate::legal_oracle::OracleRecord = from_json(v)?;
+            if source.is_none_or(|s| record.source == s) {
+                results.push(record);
+            }
+            Ok(results.len() < limit)
+        })
+        .map_err(|e| crate::legal_oracle::OracleError::Storage(e.to_string()))?;
+        Ok(results)
+    }
+}
+
+impl EncryptedStore for RedbBlockStore {
+    fn encrypted_scopes(&self) -> StorageResult<Vec<String>> {
+        let mut scopes: Vec<String> = ENCRYPTED_SCOPES
+            .iter()
+            .filter(|name| ALL_TABLES.contains(name))
+            .map(|name| name.to_string())
+            .collect();
+        let txn = self.db.begin_read().map_err(redb_err)?;
+        for handle in txn.list_tables().map_err(redb_err)? {
+            if is_encrypted_scope(handle.name()) && !scopes.iter().any(|s| s == handle.name()) {
+                scopes.push(handle.name().to_string());
+            }
+        }
+        Ok(scopes)
+    }
+
+    fn encryption_coverage(&self) -> StorageResult<CoverageReport> {
+        let records: Vec<WrappedDataKey> = self.list_json(T_ENCRYPTION_KEYS)?;
+        let (versions, kek_id) = key_versions(&records);
+        let mut scopes = Vec::new();
+        for scope in self.encrypted_scopes()? {
+            let mut coverage = ScopeCoverage::new(&scope, versions.get(&scope).copied());
+            self.scan_stored(&scope, b"", |_, v| {
+                coverage.count(v);
+                Ok(true)
+            })?;
+            scopes.push(coverage);
+        }
+        Ok(CoverageReport {
+            enabled: self.encryption.get().is_some(),
+            kek_id,
+            scopes,
+            pending: self.pending_reencryption()?,
+        })
+    }
+
+    fn rotate_data_key(&self, scope: &str) -> StorageResult<u32> {
+        let cipher = self.cipher()?;
+        if !self.encrypted_scopes()?.iter().any(|s| s == scope) {
+            return Err(StorageError::Encryption(format!(
+                "{scope} is not an encrypted table"
+            )));
+        }
+        cipher.rotate(scope, |wrapped| self.persist_data_key(wrapped))
+    }
+
+    fn pending_reencryption(&self) -> StorageResult<Vec<String>> {
+        Ok(self
+            .scan_prefix(T_META, &pending_marker_key(""))?
+            .iter()
+            .filter_map(|(k, _)| parse_pending_marker(k))
+            .collect())
+    }
+
+    fn reencrypt(
+        &self,
+        scope: &str,
+        after: Option<&[u8]>,
+        limit: usize,
+    ) -> StorageResult<ReencryptStep> {
+        let cipher = self.cipher()?;
+        if !is_encrypted_scope(scope) {
+            return Err(StorageError::Encryption(format!(
+                "{scope} is not an encrypted table"
+            )));
+        }
+        // One write transaction per step: a concurrent write to an entry
+        // lands before or after it, never in between read and rewrite.
+        let step = self.write(|txn| {
+            let mut step = ReencryptStep::default();
+            let mut last = None;
+            let mut rewrites = Vec::new();
+            {
+                let t = txn.open_table(table(scope)).map_err(redb_err)?;
+                let start = after.map_or(Bound::Unbounded, Bound::Excluded);
+                for item in t
+                    .range::<&[u8]>((start, Bound::Unbounded))
+                    .map_err(redb_err)?
+                    .take(limit)
+                {
+                    let (k, v) = item.map_err(redb_err)?;
+                    step.scanned += 1;
+                    if let Some(resealed) = cipher.reseal(scope, k.value(), v.value())? {
+                        rewrites.push((k.value().to_vec(), resealed));
+                    }
+                    last = Some(k.value().to_vec());
+                }
+            }
+            step.rewritten = rewrites.len();
+            for (k, v) in &rewrites {
+                txn_put(txn, scope, k, v)?;
+            }
+            if step.scanned == limit {
+                step.resume_after = last;
+            } else {
+                txn_remove(txn, T_META, &pending_marker_key(scope))?;
+                txn_put(txn, T_META, &sealed_marker_key(scope), b"")?;
+            }
+            Ok(step)
+        })?;
+        if step.resume_after.is_none() {
+            cipher.mark_sealed(scope);
+        }
+        Ok(step)
+    }
+}
+
+// ── Migration from RocksDB ───────────────────────────────────────────────────
+
+/// What [`migrate_from_rocksdb`] copied.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MigrationReport {
+    /// Entries copied per table.
+    pub tables: std::collections::BTreeMap<String, u64>,
+    /// Latest block height of the migrated ledger.
+    pub latest_height: u64,
+}
+
+/// Copy the RocksDB store at `rocks_path` into a new redb store at
+/// `redb_path`, after bringing the RocksDB schema up to date.
+///
+/// Every column family is copied as-is into the table of the same name,
+/// except secondary indexes (`state_index_defs`, `index_*`), which redb does
+/// not keep. Encrypted values are copied sealed, with their wrapped data
+/// keys, so the redb store opens with the same KEK. Refuses a `redb_path`
+/// whose store already holds blocks.
+#[cfg(feature = "rocksdb-storage")]
+pub fn migrate_from_rocksdb(
+    rocks_path: impl AsRef<Path>,
+    redb_path: impl AsRef<Path>,
+) -> StorageResult<MigrationReport> {
+    let rocks_path = rocks_path.as_ref();
+    let source = super::RocksDbBlockStore::new(rocks_path)?;
+    super::migrations::run_pending(&source)?;
+
+    let target = RedbBlockStore::new(redb_path.as_ref())?;
+    if !target.scan_prefix(T_BLOCKS, b"")?.is_empty() {
+        return Err(StorageError::Other(format!(
+            "{} already holds a ledger",
+            redb_path.as_ref().display()
+        )));
+    }
+
+    let names = rocksdb::DB::list_cf(&rocksdb::Options::default(), rocks_path)
+        .map_err(|e| StorageError::RocksDbError(e.to_string()))?;
+    let mut tables = std::collections::BTreeMap::new();
+    for name in names {
+        if name == "default" || name == "state_index_defs" || name.starts_with("index_") {
+            continue;
+        }
+        let Some(cf) = source.db.cf_handle(&name) else {
+            continue;
+        };
+        let copied = target.write(|txn| {
+            let mut t = txn.open_table(table(&name)).map_err(redb_err)?;
+            let mut copied = 0u64;
+            for item in source.db.iterator_cf(&cf, rocksdb::IteratorMode::Start) {
+                let (k, v) = item.map_err(|e| StorageError::RocksDbError(e.to_string()))?;
+                t.insert(&*k, &*v).map_err(redb_err)?;
+                copied += 1;
+            }
+            Ok(copied)
+        })?;
+        tables.insert(name, copied);
+    }
+    Ok(MigrationReport {
+        tables,
+        latest_height: target.get_latest_height()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::acl::AclProvider;
+    use crate::msp::CrlStore;
+    use crate::storage::encryption::{envelope_version, reencrypt_scope, MlKemKek};
+    use tempfile::TempDir;
+
+    fn tmp_store() -> (RedbBlockStore, TempDir) {
+        let dir = TempDir::new().unwrap();
+        let store = RedbBlockStore::new(dir.path()).unwrap();
+        (store, dir)
+    }
+
+    #[test]
+    fn reopening_keeps_blocks_and_state() {
+        let dir = TempDir::new().unwrap();
+        {
+            let store = RedbBlockStore::new(dir.path()).unwrap();
+            let block = crate::channel::genesis::create_genesis_block(
+                "default",
+                &crate::channel::config::ChannelConfig::default(),
+            );
+            store.write_block(&block).unwrap();
+            store.put_from_tx("k", b"v", "tx1", 0).unwrap();
+        }
+        let store = RedbBlockStore::new(dir.path()).unwrap();
+        assert!(store.block_exists(0).unwrap());
+        assert_eq!(WorldState::get(&store, "k").unwrap().unwrap().data, b"v");
+        assert!(dir.path().join(DB_FILE).exists());
+    }
+
+    #[test]
+    fn world_state_versions_history_and_history_at() {
+        let (store, _dir) = tmp_store();
+        assert_eq!(store.put_from_tx("a", b"1", "tx1", 3).unwrap(), 1);
+        assert_eq!(store.put_from_tx("a", b"2", "tx2", 4).unwrap(), 2);
+        store.put_from_tx("b", b"x", "tx2", 4).unwrap();
+        store.delete("b").unwrap();
+
+        let history = store.get_history("a").unwrap();
+        assert_eq!(
+            history.iter().map(|h| h.version).collect::<Vec<_>>(),
+            [1, 2]
+        );
+        assert_eq!(history[1].block_height, Some(4));
+
+        let at4 = store.history_at(4).unwrap();
+        let keys: Vec<_> = at4.iter().map(|(k, e)| (k.as_str(), e.version)).collect();
+        assert_eq!(keys, [("a", 2), ("b", 1)]);
+
+        assert!(WorldState::get(&store, "b").unwrap().is_none());
+        assert!(store.get_history("b").unwrap()[1].is_delete);
+    }
+
+    #[test]
+    fn get_range_is_half_open() {
+        let (store, _dir) = tmp_store();
+        for key in ["a", "b", "c"] {
+            store.put(key, key.as_bytes()).unwrap();
+        }
+        let keys: Vec<_> = store
+            .get_range("a", "c")
+            .unwrap()
+            .into_iter()
+            .map(|(k, _)| k)
+            .collect();
+        assert_eq!(keys, ["a", "b"]);
+
+        let page = store.get_range_page("a", "d", 2).unwrap();
+        assert_eq!(page.len(), 2);
+        assert_eq!(page[1].0, "b");
+        assert!(store.get_range_page("a", "d", 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn private_data_tables_are_created_on_first_write() {
+        let (store, _dir) = tmp_store();
+        assert_eq!(store.get_private_data("col", "k").unwrap(), None);
+        let hash = store.put_private_data("col", "k", b"secret").unwrap();
+        assert_eq!(hash, sha256(b"secret"));
+        assert_eq!(
+            store.get_private_data("col", "k").unwrap().as_deref(),
+            Some(&b"secret"[..])
+        );
+        assert_eq!(store.get_private_data("other", "k").unwrap(), None);
+    }
+
+    #[test]
+    fn private_data_ttl_survives_reopen() {
+        let dir = TempDir::new().unwrap();
+        {
+            let store = RedbBlockStore::new(dir.path()).unwrap();
+            store
+                .put_private_data_at("col", "k", b"secret", 10, 5)
+                .unwrap();
+        }
+        let store = RedbBlockStore::new(dir.path()).unwrap();
+        store.purge_expired(14);
+        assert!(store.get_private_data("col", "k").unwrap().is_some());
+        store.purge_expired(15);
+        assert_eq!(store.get_private_data("col", "k").unwrap(), None);
+        assert!(store.scan_prefix(T_PRIVATE_TTL, b"").unwrap().is_empty());
+        assert!(store.scan_prefix(T_PRIVATE_EXPIRY, b"").unwrap().is_empty());
+    }
+
+    #[test]
+    fn service_stores_round_trip() {
+        let (store, _dir) = tmp_store();
+        store.set_acl("peer/Admin", "AdminPolicy").unwrap();
+        assert_eq!(
+            store.get_acl("peer/Admin").unwrap().unwrap().policy_ref,
+            "AdminPolicy"
+        );
+        store.write_crl("org1", &["01".to_string()]).unwrap();
+        assert_eq!(store.read_crl("org1").unwrap(), ["01"]);
+        assert!(store.read_crl("org2").unwrap().is_empty());
+        assert!(matches!(
+            store.remove_org("missing"),
+            Err(StorageError::KeyNotFound(_))
+        ));
+        ChaincodePackageStore::store_package(&store, "cc", "1.0", b"\0asm").unwrap();
+        assert_eq!(
+            ChaincodePackageStore::get_package(&store, "cc", "1.0").unwrap(),
+            Some(b"\0asm".to_vec())
+        );
+    }
+
+    #[test]
+    fn seen_txs_survive_and_expire() {
+        let (store, _dir) = tmp_store();
+        store.mark_tx_seen("old", 1).unwrap();
+        store.mark_tx_seen("new", u64::MAX / 2).unwrap();
+        assert!(store.is_tx_seen("old").unwrap());
+        assert_eq!(store.load_seen_txs().unwrap().len(), 2);
+        assert_eq!(store.cleanup_seen_txs(60).unwrap(), 1);
+        assert!(!store.is_tx_seen("old").unwrap());
+        assert!(store.is_tx_seen("new").unwrap());
+    }
+
+    #[test]
+    fn failed_write_leaves_nothing_behind() {
+        let (store, _dir) = tmp_store();
+        let block = crate::channel::genesis::create_genesis_block(
+            "default",
+            &crate::channel::config::ChannelConfig::default(),
+        );
+        // Pruning a missing block fails inside the transaction.
+        assert!(store.prune_block(&block).is_err());
+        assert!(!store.block_exists(0).unwrap());
+    }
+
+    #[test]
+    fn encrypted_values_need_the_kek_after_reopen() {
+        let dir = TempDir::new().unwrap();
+        let kek = Arc::new(MlKemKek::generate().unwrap());
+        {
+            let store = RedbBlockStore::new(dir.path()).unwrap();
+            store.enable_encryption(kek.clone()).unwrap();
+            store.put_from_tx("k", b"plain-marker", "tx1", 0).unwrap();
+            store.put_private_data("col", "pk", b"secret").unwrap();
+            store
+                .scan_stored(T_WORLD_STATE, b"", |_, v| {
+                    assert!(!v.windows(12).any(|w| w == b"plain-marker"));
+                    assert!(envelope_version(v).is_some());
+                    Ok(true)
+                })
+                .unwrap();
+        }
+        {
+            let store = RedbBlockStore::new(dir.path()).unwrap();
+            assert!(matches!(
+                WorldState::get(&store, "k"),
+                Err(StorageError::Encryption(_))
+            ));
+        }
+        let store = RedbBlockStore::new(dir.path()).unwrap();
+        store.enable_encryption(kek).unwrap();
+        assert_eq!(
+            WorldState::get(&store, "k").unwrap().unwrap().data,
+            b"plain-marker"
+        );
+        assert_eq!(
+            store.get_private_data("col", "pk").unwrap().as_deref(),
+            Some(&b"secret"[..])
+        );
+    }
+
+    #[test]
+    fn plaintext_is_refused_once_a_scope_is_reencrypted() {
+        let (store, dir) = tmp_store();
+        store.put_from_tx("k", b"old", "tx1", 0).unwrap();
+        let mut planted = Vec::new();
+        store
+            .scan_stored(T_WORLD_STATE, b"", |k, v| {
+                planted.push((k.to_vec(), v.to_vec()));
+                Ok(true)
+            })
+            .unwrap();
+
+        let kek = Arc::new(MlKemKek::generate().unwrap());
+        store.enable_encryption(kek.clone()).unwrap();
+        // Plaintext from before encryption stays readable until the pass.
+        assert_eq!(WorldState::get(&store, "k").unwrap().unwrap().data, b"old");
+        reencrypt_scope(&store, T_WORLD_STATE).unwrap();
+        assert!(!store
+            .pending_reencryption()
+            .unwrap()
+            .contains(&T_WORLD_STATE.to_string()));
+
+        let plant = |store: &RedbBlockStore| {
+            store
+                .write(|txn| {
+                    for (k, v) in &planted {
+                        txn_put(txn, T_WORLD_STATE, k, v)?;
+                    }
+                    Ok(())
+                })
+                .unwrap();
+        };
+        plant(&store);
+        assert!(matches!(
+            WorldState::get(&store, "k"),
+            Err(StorageError::DataCorrupted(_))
+        ));
+
+        drop(store);
+        let store = RedbBlockStore::new(dir.path()).unwrap();
+        store.enable_encryption(kek).unwrap();
+        plant(&store);
+        assert!(WorldState::get(&store, "k").is_err());
+    }
+
+    #[test]
+    fn enabling_with_another_kek_fails() {
+        let (store, dir) = tmp_store();
+        store
+            .enable_encryption(Arc::new(MlKemKek::generate().unwrap()))
+            .unwrap();
+        drop(store);
+        let store = RedbBlockStore::new(dir.path()).unwrap();
+        assert!(store
+            .enable_encryption(Arc::new(MlKemKek::generate().unwrap()))
+            .is_err());
+    }
+
+    #[cfg(feature = "rocksdb-storage")]
+    #[test]
+    fn migrate_from_rocksdb_copies_ledger_state_and_private_data() {
+        use crate::storage::RocksDbBlockStore;
+
+        let rocks_dir = TempDir::new().unwrap();
+        let redb_dir = TempDir::new().unwrap();
+        {
+            let rocks = RocksDbBlockStore::new(rocks_dir.path()).unwrap();
+            let block = crate::channel::genesis::create_genesis_block(
+                "default",
+                &crate::channel::config::ChannelConfig::default(),
+            );
+            rocks.write_block(&block).unwrap();
+            rocks.put_from_tx("k", b"v", "tx1", 0).unwrap();
+            rocks.put_private_data("col", "pk", b"secret").unwrap();
+            rocks.set_acl("peer/Admin", "AdminPolicy").unwrap();
+        }
+
+        let report = migrate_from_rocksdb(rocks_dir.path(), redb_dir.path()).unwrap();
+        assert_eq!(report.tables["blocks"], 1);
+        assert_eq!(report.tables["private_col"], 1);
+
+        let store = RedbBlockStore::new(redb_dir.path()).unwrap();
+        assert!(store.block_exists(0).unwrap());
+        assert_eq!(WorldState::get(&store, "k").unwrap().unwrap().version, 1);
+        assert_eq!(store.history_at(0).unwrap().len(), 1);
+        assert_eq!(
+            store.get_private_data("col", "pk").unwrap().as_deref(),
+            Some(&b"secret"[..])
+        );
+        assert!(store.get_acl("peer/Admin").unwrap().is_some());
+        drop(store);
+
+        assert!(migrate_from_rocksdb(rocks_dir.path(), redb_dir.path()).is_err());
+    }
+}
@@ -223,6 +223,13 @@ mod tests {
             secondary_signature_algorithm: None,
             hash_algorithm: Default::default(),
             orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: Vec::new(),
+            config_tx: None,
+            last_config: 0,
+            state_root: [0u8; 32],
+            private_data_purges: vec![],
         };
         store.write_block(&block).unwrap();
 
@@ -264,6 +271,13 @@ mod tests {
             secondary_signature_algorithm: None,
             hash_algorithm: Default::default(),
             orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: Vec::new(),
+            config_tx: None,
+            last_config: 0,
+            state_root: [0u8; 32],
+            private_data_purges: vec![],
         };
         store.write_block(&block).unwrap();
 
@@ -316,6 +330,13 @@ mod tests {
                 secondary_signature_algorithm: None,
                 hash_algorithm: Default::default(),
                 orderer_signature: None,
+                commit_qc: None,
+                next_validator_set: None,
+                evidence: Vec::new(),
+                config_tx: None,
+                last_config: 0,
+                state_root: [0u8; 32],
+                private_data_purges: vec![],
             };
             store.write_block(&block).unwrap();
         }
@@ -356,6 +377,13 @@ mod tests {
                 secondary_signature_algorithm: None,
                 hash_algorithm: Default::default(),
                 orderer_signature: None,
+                commit_qc: None,
+                next_validator_set: None,
+                evidence: Vec::new(),
+                config_tx: None,
+                last_config: 0,
+                state_root: [0u8; 32],
+                private_data_purges: vec![],
             };
             store.write_block(&block).unwrap();
         }
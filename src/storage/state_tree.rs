@@ -0,0 +1,770 @@
+//! Authenticated world state — a sparse Merkle tree over the world state.
+//!
+//! Every key sits on the path given by the bits of `SHA-256(key)`. A subtree
+//! holding a single entry collapses into that entry's leaf, so a proof is
+//! only as deep as the shortest unique key-hash prefix instead of 256 levels.
+//! Empty subtrees hash to zero.
+//!
+//! Hashes are domain-separated so a leaf can never be passed off as an
+//! inner node:
+//! - leaf  = `SHA-256(0x00 || SHA-256(key) || SHA-256(value))`
+//! - inner = `SHA-256(0x01 || left || right)`
+//!
+//! The tree is persistent: an update rebuilds only the nodes on one path and
+//! shares the rest, so [`AuthenticatedWorldState`] keeps the root of every
+//! committed block and serves proofs against any of them.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+use pqc_crypto_module::legacy::sha256::{Digest, Sha256};
+use serde::{Deserialize, Serialize};
+
+use super::errors::{StorageError, StorageResult};
+use super::traits::{Block, BlockStore, HistoryEntry, Transaction};
+use super::world_state::{StateWrite, VersionedValue, WorldState};
+
+/// Root of the tree with no entries.
+pub const EMPTY_ROOT: [u8; 32] = [0u8; 32];
+
+/// Number of committed heights [`AuthenticatedWorldState`] keeps trees for
+/// by default.
+pub const DEFAULT_RETENTION_BLOCKS: u64 = 1_024;
+
+/// Depth of the deepest possible leaf (one level per key-hash bit).
+const MAX_DEPTH: usize = 256;
+
+const LEAF_PREFIX: u8 = 0x00;
+const INNER_PREFIX: u8 = 0x01;
+
+fn sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+fn leaf_hash(key_hash: &[u8; 32], value_hash: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(key_hash);
+    hasher.update(value_hash);
+    hasher.finalize().into()
+}
+
+fn inner_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([INNER_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Whether the key path turns right at `depth`.
+fn goes_right(key_hash: &[u8; 32], depth: usize) -> bool {
+    (key_hash[depth / 8] >> (7 - depth % 8)) & 1 == 1
+}
+
+/// Whether `a` and `b` agree on their first `depth` bits.
+fn shares_prefix(a: &[u8; 32], b: &[u8; 32], depth: usize) -> bool {
+    (0..depth).all(|d| goes_right(a, d) == goes_right(b, d))
+}
+
+#[derive(Debug)]
+struct Leaf {
+    value: Vec<u8>,
+    key_hash: [u8; 32],
+    hash: [u8; 32],
+}
+
+#[derive(Debug)]
+enum Node {
+    Empty,
+    Leaf(Leaf),
+    Inner {
+        left: Arc<Node>,
+        right: Arc<Node>,
+        hash: [u8; 32],
+    },
+}
+
+impl Node {
+    fn hash(&self) -> [u8; 32] {
+        match self {
+            Node::Empty => EMPTY_ROOT,
+            Node::Leaf(leaf) => leaf.hash,
+            Node::Inner { hash, .. } => *hash,
+        }
+    }
+
+    fn inner(left: Arc<Node>, right: Arc<Node>) -> Arc<Node> {
+        let hash = inner_hash(&left.hash(), &right.hash());
+        Arc::new(Node::Inner { left, right, hash })
+    }
+
+    /// Inner node with `child` on the side `right` and nothing on the other.
+    fn one_sided(child: Arc<Node>, right: bool) -> Arc<Node> {
+        let empty = Arc::new(Node::Empty);
+        if right {
+            Node::inner(empty, child)
+        } else {
+            Node::inner(child, empty)
+        }
+    }
+}
+
+/// Leaf data proving a key is absent: the entry occupying the key's slot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateLeaf {
+    /// `SHA-256` of the occupying entry's key.
+    pub key_hash: [u8; 32],
+    /// `SHA-256` of the occupying entry's value.
+    pub value_hash: [u8; 32],
+}
+
+/// Proof that a key holds a value, or holds none, under a tree root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateTreeProof {
+    /// Value under the key, `None` for a non-membership proof.
+    pub value: Option<Vec<u8>>,
+    /// For a non-membership proof ending at another entry's leaf, that entry.
+    pub neighbor: Option<StateLeaf>,
+    /// Sibling hashes from the key's leaf (or empty slot) up to the root.
+    pub siblings: Vec<[u8; 32]>,
+    /// Root the proof verifies against.
+    pub root: [u8; 32],
+}
+
+impl StateTreeProof {
+    /// Position of the proof's leaf among the nodes at its depth, with bit
+    /// `i` giving the turn taken `i` levels above the leaf. Only exact for
+    /// proofs at most 64 levels deep; verifiers follow the key hash instead.
+    pub fn leaf_index(&self, key: &str) -> u64 {
+        let key_hash = sha256(key.as_bytes());
+        let depth = self.siblings.len();
+        (0..depth.min(64)).fold(0u64, |index, i| {
+            index | (u64::from(goes_right(&key_hash, depth - 1 - i)) << i)
+        })
+    }
+}
+
+/// Verify a state proof for `key` against `root`.
+///
+/// `value` is the claimed value (`None` to prove absence) and `neighbor` the
+/// entry a non-membership proof ends at, if it ends at a leaf rather than an
+/// empty slot. `siblings` run from the leaf up to the root.
+pub fn verify_proof(
+    key: &str,
+    value: Option<&[u8]>,
+    neighbor: Option<&StateLeaf>,
+    siblings: &[[u8; 32]],
+    root: &[u8; 32],
+) -> bool {
+    let depth = siblings.len();
+    if depth > MAX_DEPTH {
+        return false;
+    }
+    let key_hash = sha256(key.as_bytes());
+    let mut hash = match (value, neighbor) {
+        (Some(value), None) => leaf_hash(&key_hash, &sha256(value)),
+        (None, None) => EMPTY_ROOT,
+        (None, Some(leaf)) => {
+            // The occupying entry must be a different key on the same path.
+            if leaf.key_hash == key_hash || !shares_prefix(&leaf.key_hash, &key_hash, depth) {
+                return false;
+            }
+            leaf_hash(&leaf.key_hash, &leaf.value_hash)
+        }
+        (Some(_), Some(_)) => return false,
+    };
+    for (i, sibling) in siblings.iter().enumerate() {
+        hash = if goes_right(&key_hash, depth - 1 - i) {
+            inner_hash(sibling, &hash)
+        } else {
+            inner_hash(&hash, sibling)
+        };
+    }
+    hash == *root
+}
+
+/// Immutable sparse Merkle tree over key/value pairs.
+///
+/// Updates return a new tree sharing every untouched node with the old one,
+/// so keeping past versions costs only the rebuilt paths.
+#[derive(Debug, Clone)]
+pub struct StateTree {
+    root: Arc<Node>,
+}
+
+impl Default for StateTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StateTree {
+    /// An empty tree.
+    pub fn new() -> Self {
+        Self {
+            root: Arc::new(Node::Empty),
+        }
+    }
+
+    /// Root hash ([`EMPTY_ROOT`] for an empty tree).
+    pub fn root(&self) -> [u8; 32] {
+        self.root.hash()
+    }
+
+    /// Value under `key`, if present.
+    pub fn get(&self, key: &str) -> Option<&[u8]> {
+        let key_hash = sha256(key.as_bytes());
+        let mut node = &self.root;
+        let mut depth = 0;
+        loop {
+            match node.as_ref() {
+                Node::Empty => return None,
+                Node::Leaf(leaf) => {
+                    return (leaf.key_hash == key_hash).then_some(leaf.value.as_slice())
+                }
+                Node::Inner { left, right, .. } => {
+                    node = if goes_right(&key_hash, depth) {
+                        right
+                    } else {
+                        left
+                    };
+                    depth += 1;
+                }
+            }
+        }
+    }
+
+    /// Tree with `key` set to `value`.
+    pub fn insert(&self, key: &str, value: &[u8]) -> Self {
+        let key_hash = sha256(key.as_bytes());
+        let leaf = Arc::new(Node::Leaf(Leaf {
+            value: value.to_vec(),
+            key_hash,
+            hash: leaf_hash(&key_hash, &sha256(value)),
+        }));
+        Self {
+            root: insert_at(&self.root, leaf, &key_hash, 0),
+        }
+    }
+
+    /// Tree without `key`.
+    pub fn remove(&self, key: &str) -> Self {
+        let key_hash = sha256(key.as_bytes());
+        Self {
+            root: remove_at(&self.root, &key_hash, 0),
+        }
+    }
+
+    /// Membership or non-membership proof for `key`.
+    pub fn prove(&self, key: &str) -> StateTreeProof {
+        let key_hash = sha256(key.as_bytes());
+        let mut siblings = Vec::new();
+        let mut node = &self.root;
+        let mut depth = 0;
+        let (value, neighbor) = loop {
+            match node.as_ref() {
+                Node::Empty => break (None, None),
+                Node::Leaf(leaf) if leaf.key_hash == key_hash => {
+                    break (Some(leaf.value.clone()), None)
+                }
+                Node::Leaf(leaf) => {
+                    break (
+                        None,
+                        Some(StateLeaf {
+                            key_hash: leaf.key_hash,
+                            value_hash: sha256(&leaf.value),
+                        }),
+                    )
+                }
+                Node::Inner { left, right, .. } => {
+                    let (next, sibling) = if goes_right(&key_hash, depth) {
+                        (right, left)
+                    } else {
+                        (left, right)
+                    };
+                    siblings.push(sibling.hash());
+                    node = next;
+                    depth += 1;
+                }
+            }
+        };
+        siblings.reverse();
+        StateTreeProof {
+            value,
+            neighbor,
+            siblings,
+            root: self.root(),
+        }
+    }
+}
+
+fn insert_at(node: &Arc<Node>, leaf: Arc<Node>, key_hash: &[u8; 32], depth: usize) -> Arc<Node> {
+    match node.as_ref() {
+        Node::Empty => leaf,
+        Node::Leaf(existing) if existing.key_hash == *key_hash => leaf,
+        Node::Leaf(existing) => split(node.clone(), &existing.key_hash, leaf, key_hash, depth),
+        Node::Inner { left, right, .. } => {
+            if goes_right(key_hash, depth) {
+                Node::inner(left.clone(), insert_at(right, leaf, key_hash, depth + 1))
+            } else {
+                Node::inner(insert_at(left, leaf, key_hash, depth + 1), right.clone())
+            }
+        }
+    }
+}
+
+/// Push two leaves down until their key paths part.
+fn split(
+    a: Arc<Node>,
+    a_hash: &[u8; 32],
+    b: Arc<Node>,
+    b_hash: &[u8; 32],
+    depth: usize,
+) -> Arc<Node> {
+    let (a_right, b_right) = (goes_right(a_hash, depth), goes_right(b_hash, depth));
+    if a_right == b_right {
+        Node::one_sided(split(a, a_hash, b, b_hash, depth + 1), a_right)
+    } else if b_right {
+        Node::inner(a, b)
+    } else {
+        Node::inner(b, a)
+    }
+}
+
+fn remove_at(node: &Arc<Node>, key_hash: &[u8; 32], depth: usize) -> Arc<Node> {
+    match node.as_ref() {
+        Node::Empty => node.clone(),
+        Node::Leaf(leaf) if leaf.key_hash == *key_hash => Arc::new(Node::Empty),
+        Node::Leaf(_) => node.clone(),
+        Node::Inner { left, right, .. } => {
+            let right_side = goes_right(key_hash, depth);
+            let (child, other) = if right_side {
+                (right, left)
+            } else {
+                (left, right)
+            };
+            let new_child = remove_at(child, key_hash, depth + 1);
+            if Arc::ptr_eq(&new_child, child) {
+                return node.clone();
+            }
+            // A subtree left with a single leaf collapses into that leaf.
+            match (new_child.as_ref(), other.as_ref()) {
+                (Node::Empty, Node::Empty) => new_child,
+                (Node::Empty, Node::Leaf(_)) => other.clone(),
+                (Node::Leaf(_), Node::Empty) => new_child,
+                _ if right_side => Node::inner(other.clone(), new_child),
+                _ => Node::inner(new_child, other.clone()),
+            }
+        }
+    }
+}
+
+/// `tree` with `writes` applied in order.
+fn apply_writes(tree: &StateTree, writes: &[StateWrite]) -> StateTree {
+    writes
+        .iter()
+        .fold(tree.clone(), |tree, write| match &write.value {
+            Some(value) => tree.insert(&write.key, value),
+            None => tree.remove(&write.key),
+        })
+}
+
+/// World state that keeps a [`StateTree`] in step with every write and
+/// remembers the tree of each of the last committed blocks.
+///
+/// Reads and writes go to the wrapped store; `commit` seals the writes made
+/// since the previous commit under a block height. Trees are kept in memory
+/// for the last `retention` heights only, and a restart starts over from the
+/// tree at the ledger tip.
+pub struct AuthenticatedWorldState {
+    inner: Arc<dyn WorldState>,
+    /// Tree over the current (possibly uncommitted) state.
+    working: RwLock<StateTree>,
+    /// Tree as of each committed height within the retention window.
+    committed: RwLock<BTreeMap<u64, StateTree>>,
+    retention: u64,
+}
+
+impl AuthenticatedWorldState {
+    /// Wrap `inner`, building the tree from its current contents and
+    /// recording it as the state at `height`.
+    pub fn new(inner: Arc<dyn WorldState>, height: u64) -> StorageResult<Self> {
+        let tree = inner
+            .get_range("", "\u{FFFF}")?
+            .into_iter()
+            .fold(StateTree::new(), |tree, (key, vv)| {
+                tree.insert(&key, &vv.data)
+            });
+        Ok(Self {
+            inner,
+            committed: RwLock::new(BTreeMap::from([(height, tree.clone())])),
+            working: RwLock::new(tree),
+            retention: DEFAULT_RETENTION_BLOCKS,
+        })
+    }
+
+    /// Keep trees for the last `blocks` committed heights (at least one).
+    pub fn with_retention(mut self, blocks: u64) -> Self {
+        self.retention = blocks.max(1);
+        self
+    }
+
+    /// Record `tree` as the state after block `height`, dropping the trees
+    /// that fall out of the retention window.
+    fn record(&self, height: u64, tree: StateTree) {
+        let mut committed = self.committed.write().unwrap_or_else(|e| e.into_inner());
+        committed.insert(height, tree);
+        let oldest = height.saturating_sub(self.retention - 1);
+        *committed = committed.split_off(&oldest);
+    }
+
+    /// Record the current state as the state after block `height` and return
+    /// its root, to be stamped into the block.
+    pub fn commit(&self, height: u64) -> [u8; 32] {
+        let tree = self
+            .working
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        let root = tree.root();
+        self.record(height, tree);
+        root
+    }
+
+    /// Root the current state would have with `writes` applied: the
+    /// `state_root` of a block committing them, stamped before it is signed.
+    pub fn root_with(&self, writes: &[StateWrite]) -> [u8; 32] {
+        let tree = self.working.read().unwrap_or_else(|e| e.into_inner());
+        apply_writes(&tree, writes).root()
+    }
+
+    /// Commit `block` to `store` with its transactions and state `writes`,
+    /// recording the resulting tree as the state at `block.height`.
+    ///
+    /// Fails without writing when the block carries a `state_root` the
+    /// writes do not produce; a zero root (a block final before execution)
+    /// attests nothing. The tree only advances once the wrapped store has
+    /// committed.
+    pub fn commit_block(
+        &self,
+        store: &dyn BlockStore,
+        block: &Block,
+        txs: &[Transaction],
+        writes: &[StateWrite],
+    ) -> StorageResult<()> {
+        let mut tree = self.working.write().unwrap_or_else(|e| e.into_inner());
+        let next = apply_writes(&tree, writes);
+        if block.state_root != EMPTY_ROOT && block.state_root != next.root() {
+            return Err(StorageError::DataCorrupted(format!(
+                "state root of block {} does not match its writes",
+                block.height
+            )));
+        }
+        self.inner.commit_block_to(store, block, txs, writes)?;
+        *tree = next.clone();
+        self.record(block.height, next);
+        Ok(())
+    }
+
+    /// Tree as of block `height`: the latest commit at or below it.
+    pub fn tree_at(&self, height: u64) -> Option<StateTree> {
+        self.committed
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .range(..=height)
+            .next_back()
+            .map(|(_, tree)| tree.clone())
+    }
+
+    /// State root after block `height`, if that height is tracked.
+    pub fn root_at(&self, height: u64) -> Option<[u8; 32]> {
+        self.tree_at(height).map(|tree| tree.root())
+    }
+
+    /// Highest committed height.
+    pub fn latest_height(&self) -> Option<u64> {
+        self.committed
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .keys()
+            .next_back()
+            .copied()
+    }
+
+    /// Proof for `key` against the state after block `height`.
+    pub fn prove(&self, key: &str, height: u64) -> Option<StateTreeProof> {
+        self.tree_at(height).map(|tree| tree.prove(key))
+    }
+}
+
+impl WorldState for AuthenticatedWorldState {
+    fn get(&self, key: &str) -> StorageResult<Option<VersionedValue>> {
+        self.inner.get(key)
+    }
+
+    fn put(&self, key: &str, data: &[u8]) -> StorageResult<u64> {
+        // Hold the tree lock across the write so the two cannot diverge.
+        let mut tree = self.working.write().unwrap_or_else(|e| e.into_inner());
+        let version = self.inner.put(key, data)?;
+        *tree = tree.insert(key, data);
+        Ok(version)
+    }
+
+    fn put_from_tx(
+        &self,
+        key: &str,
+        data: &[u8],
+        tx_id: &str,
+        block_height: u64,
+    ) -> StorageResult<u64> {
+        let mut tree = self.working.write().unwrap_or_else(|e| e.into_inner());
+        let version = self.inner.put_from_tx(key, data, tx_id, block_height)?;
+        *tree = tree.insert(key, data);
+        Ok(version)
+    }
+
+    fn delete(&self, key: &str) -> StorageResult<()> {
+        let mut tree = self.working.write().unwrap_or_else(|e| e.into_inner());
+        self.inner.delete(key)?;
+        *tree = tree.remove(key);
+        Ok(())
+    }
+
+    fn restore_entry(&self, key: &str, value: &VersionedValue) -> StorageResult<()> {
+        let mut tree = self.working.write().unwrap_or_else(|e| e.into_inner());
+        self.inner.restore_entry(key, value)?;
+        *tree = tree.insert(key, &value.data);
+        Ok(())
+    }
+
+    fn commit_block_to(
+        &self,
+        store: &dyn BlockStore,
+        block: &Block,
+        txs: &[Transaction],
+        writes: &[StateWrite],
+    ) -> StorageResult<()> {
+        let mut tree = self.working.write().unwrap_or_else(|e| e.into_inner());
+        self.inner.commit_block_to(store, block, txs, writes)?;
+        *tree = apply_writes(&tree, writes);
+        Ok(())
+    }
+
+    fn get_range(&self, start: &str, end: &str) -> StorageResult<Vec<(String, VersionedValue)>> {
+        self.inner.get_range(start, end)
+    }
+
+    fn get_range_page(
+        &self,
+        start: &str,
+        end: &str,
+        limit: usize,
+    ) -> StorageResult<Vec<(String, VersionedValue)>> {
+        self.inner.get_range_page(start, end, limit)
+    }
+
+    fn get_history(&self, key: &str) -> StorageResult<Vec<HistoryEntry>> {
+        self.inner.get_history(key)
+    }
+
+    fn history_at(&self, block_height: u64) -> StorageResult<Vec<(String, HistoryEntry)>> {
+        self.inner.history_at(block_height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryWorldState;
+
+    fn tree_of(n: usize) -> StateTree {
+        (0..n).fold(StateTree::new(), |tree, i| {
+            tree.insert(&format!("key{i}"), format!("value{i}").as_bytes())
+        })
+    }
+
+    fn verifies(proof: &StateTreeProof, key: &str) -> bool {
+        verify_proof(
+            key,
+            proof.value.as_deref(),
+            proof.neighbor.as_ref(),
+            &proof.siblings,
+            &proof.root,
+        )
+    }
+
+    #[test]
+    fn empty_tree_has_zero_root() {
+        assert_eq!(StateTree::new().root(), EMPTY_ROOT);
+    }
+
+    #[test]
+    fn root_is_independent_of_insertion_order() {
+        let forward = tree_of(50);
+        let backward = (0..50).rev().fold(StateTree::new(), |tree, i| {
+            tree.insert(&format!("key{i}"), format!("value{i}").as_bytes())
+        });
+        assert_eq!(forward.root(), backward.root());
+    }
+
+    #[test]
+    fn update_changes_root_and_keeps_old_version() {
+        let before = tree_of(10);
+        let after = before.insert("key3", b"changed");
+        assert_ne!(before.root(), after.root());
+        assert_eq!(before.get("key3"), Some(b"value3".as_slice()));
+        assert_eq!(after.get("key3"), Some(b"changed".as_slice()));
+    }
+
+    #[test]
+    fn remove_restores_previous_root() {
+        let base = tree_of(20);
+        let grown = base.insert("extra", b"x");
+        assert_eq!(grown.remove("extra").root(), base.root());
+        assert_eq!(tree_of(1).remove("key0").root(), EMPTY_ROOT);
+        // Removing an absent key leaves the tree untouched.
+        assert_eq!(base.remove("absent").root(), base.root());
+    }
+
+    #[test]
+    fn membership_proofs_verify() {
+        let tree = tree_of(100);
+        for i in 0..100 {
+            let key = format!("key{i}");
+            let proof = tree.prove(&key);
+            assert_eq!(proof.value, Some(format!("value{i}").into_bytes()));
+            assert!(verifies(&proof, &key));
+        }
+    }
+
+    #[test]
+    fn non_membership_proofs_verify() {
+        let tree = tree_of(100);
+        for i in 0..100 {
+            let key = format!("missing{i}");
+            let proof = tree.prove(&key);
+            assert!(proof.value.is_none());
+            assert!(verifies(&proof, &key));
+        }
+        let empty = StateTree::new().prove("anything");
+        assert!(verifies(&empty, "anything"));
+    }
+
+    #[test]
+    fn proof_with_wrong_value_fails() {
+        let tree = tree_of(10);
+        let mut proof = tree.prove("key4");
+        proof.value = Some(b"forged".to_vec());
+        assert!(!verifies(&proof, "key4"));
+    }
+
+    #[test]
+    fn present_key_cannot_be_proven_absent() {
+        let tree = tree_of(10);
+        let mut proof = tree.prove("key4");
+        proof.value = None;
+        assert!(!verifies(&proof, "key4"));
+
+        // Reusing another key's leaf as the neighbor does not help either.
+        let other = tree.prove("key5");
+        let forged = StateTreeProof {
+            value: None,
+            neighbor: Some(StateLeaf {
+                key_hash: sha256(b"key5"),
+                value_hash: sha256(b"value5"),
+            }),
+            ..other
+        };
+        assert!(!verifies(&forged, "key4"));
+    }
+
+    #[test]
+    fn proof_against_other_root_fails() {
+        let tree = tree_of(10);
+        let mut proof = tree.prove("key1");
+        proof.root = tree.insert("key2", b"new").root();
+        assert!(!verifies(&proof, "key1"));
+    }
+
+    #[test]
+    fn authenticated_state_tracks_roots_per_height() {
+        let inner = Arc::new(MemoryWorldState::new());
+        inner.put("seed", b"1").unwrap();
+        let state = AuthenticatedWorldState::new(inner, 0).unwrap();
+        let genesis_root = state.root_at(0).unwrap();
+        assert_eq!(genesis_root, StateTree::new().insert("seed", b"1").root());
+
+        state.put("a", b"x").unwrap();
+        let root1 = state.commit(1);
+        state.delete("seed").unwrap();
+        let root3 = state.commit(3);
+
+        assert_ne!(root1, genesis_root);
+        assert_eq!(state.root_at(2), Some(root1));
+        assert_eq!(state.root_at(3), Some(root3));
+        assert_eq!(state.latest_height(), Some(3));
+
+        let old = state.prove("seed", 2).unwrap();
+        assert_eq!(old.value, Some(b"1".to_vec()));
+        assert!(verifies(&old, "seed"));
+        let new = state.prove("seed", 3).unwrap();
+        assert!(new.value.is_none());
+        assert!(verifies(&new, "seed"));
+        assert_eq!(state.get("a").unwrap().unwrap().data, b"x");
+    }
+
+    #[test]
+    fn authenticated_state_keeps_only_the_retention_window() {
+        let state = AuthenticatedWorldState::new(Arc::new(MemoryWorldState::new()), 0)
+            .unwrap()
+            .with_retention(3);
+        for height in 1..=5 {
+            state.put("k", &[height as u8]).unwrap();
+            state.commit(height);
+        }
+
+        assert_eq!(state.root_at(2), None);
+        assert!(state.prove("k", 2).is_none());
+        let proof = state.prove("k", 3).unwrap();
+        assert_eq!(proof.value, Some(vec![3]));
+        assert_eq!(state.latest_height(), Some(5));
+    }
+
+    #[test]
+    fn commit_block_requires_the_stamped_root() {
+        let store = crate::storage::MemoryStore::new();
+        let state = AuthenticatedWorldState::new(Arc::new(MemoryWorldState::new()), 0).unwrap();
+        let writes = [StateWrite::put("tx1", "k", b"v")];
+        let mut block = Block {
+            height: 1,
+            timestamp: 1,
+            parent_hash: [0u8; 32],
+            merkle_root: [0u8; 32],
+            transactions: vec!["tx1".to_string()],
+            proposer: "orderer".to_string(),
+            signature: vec![0u8; 64],
+            signature_algorithm: Default::default(),
+            endorsements: vec![],
+            secondary_signature: None,
+            secondary_signature_algorithm: None,
+            hash_algorithm: Default::default(),
+            orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: vec![],
+            config_tx: None,
+            last_config: 0,
+            state_root: [9u8; 32],
+            private_data_purges: vec![],
+        };
+
+        assert!(state.commit_block(&store, &block, &[], &writes).is_err());
+        assert!(store.read_block(1).is_err());
+
+        block.state_root = state.root_with(&writes);
+        state.commit_block(&store, &block, &[], &writes).unwrap();
+        assert_eq!(state.root_at(1), Some(block.state_root));
+        assert_eq!(state.get("k").unwrap().unwrap().data, b"v");
+    }
+}
@@ -46,6 +46,38 @@ pub struct Block {
     /// Orderer signature over the block hash (absent for legacy blocks).
     #[serde(default, skip_serializing_if = "Option::is_none", with = "opt_vec_hex")]
     pub orderer_signature: Option<Vec<u8>>,
+    /// BFT commit quorum certificate that finalized this block (absent for
+    /// solo/Raft-ordered and legacy blocks).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_qc: Option<crate::consensus::bft::types::QuorumCertificate>,
+    /// Validator set taking over at the next BFT epoch; present only on the
+    /// last block of an epoch and covered by the block hash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_validator_set: Option<crate::consensus::bft::epoch::ValidatorSet>,
+    /// BFT equivocation evidence committed by this block. Each entry is
+    /// listed in `transactions` under its evidence transaction ID, so the
+    /// block hash covers it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub evidence: Vec<crate::consensus::equivocation::EquivocationEvidence>,
+    /// Channel config transaction carried by a config block. A config block
+    /// holds nothing else: its only transaction ID is the config
+    /// transaction's ledger ID, so the block hash covers it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_tx: Option<crate::channel::config::ConfigTransaction>,
+    /// Height of the latest config block at or below this block (0 = the
+    /// channel genesis block). Stamped at commit.
+    #[serde(default)]
+    pub last_config: u64,
+    /// Root of the authenticated world state after this block's writes were
+    /// applied (see [`crate::storage::state_tree`]). Stamped at commit; zero
+    /// for blocks committed without an authenticated world state.
+    #[serde(default)]
+    pub state_root: [u8; 32],
+    /// Endorsed private data purges ordered into this block. Each entry is
+    /// listed in `transactions` under its purge ledger ID, so the block hash
+    /// covers it; every member peer applies them when it commits the block.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub private_data_purges: Vec<crate::private_data::PurgeRequest>,
 }
 
 mod vec_hex {
@@ -235,6 +267,9 @@ pub struct HistoryEntry {
     pub tx_id: String,
     pub timestamp: u64,
     pub is_delete: bool,
+    /// Height of the block that committed the write, when known.
+    #[serde(default)]
+    pub block_height: Option<u64>,
 }
 
 /// BlockStore trait - main storage interface
@@ -296,6 +331,17 @@ pub trait BlockStore: Send + Sync {
         Ok(vec![]) // Override in implementations with issuer index
     }
 
+    /// Replace a committed block with its archived `header` and delete the
+    /// block's transaction records (see [`crate::storage::archive`]).
+    ///
+    /// Default: pruning is unsupported.
+    fn prune_block(&self, header: &Block) -> StorageResult<()> {
+        Err(super::errors::StorageError::Other(format!(
+            "block pruning is not supported by this store (height {})",
+            header.height
+        )))
+    }
+
     /// Mark a transaction ID as seen (for replay prevention).
     /// Stores `tx_id → timestamp` so it survives node restarts.
     fn mark_tx_seen(&self, _tx_id: &str, _timestamp: u64) -> StorageResult<()> {
@@ -655,6 +701,10 @@ impl<T: BlockStore> BlockStore for Arc<T> {
         (**self).transactions_by_block_height(height)
     }
 
+    fn prune_block(&self, header: &Block) -> StorageResult<()> {
+        (**self).prune_block(header)
+    }
+
     fn credentials_by_subject_did(&self, subject_did: &str) -> StorageResult<Vec<Credential>> {
         (**self).credentials_by_subject_did(subject_did)
     }
@@ -717,6 +767,13 @@ mod tests {
             secondary_signature_algorithm: None,
             hash_algorithm: Default::default(),
             orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: Vec::new(),
+            config_tx: None,
+            last_config: 0,
+            state_root: [0u8; 32],
+            private_data_purges: vec![],
         }
     }
 
@@ -754,6 +811,13 @@ mod tests {
             secondary_signature_algorithm: None,
             hash_algorithm: Default::default(),
             orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: Vec::new(),
+            config_tx: None,
+            last_config: 0,
+            state_root: [0u8; 32],
+            private_data_purges: vec![],
         };
         let json = serde_json::to_string(&block).unwrap();
         let decoded: Block = serde_json::from_str(&json).unwrap();
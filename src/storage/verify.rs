@@ -0,0 +1,798 @@
+//! Full verification of a stored ledger, for offline audits
+//! (`bcctl ledger verify`).
+//!
+//! [`LedgerVerifier`] walks every block from genesis to the tip and reports
+//! each problem it finds instead of stopping at the first one:
+//!
+//! - the hash chain, with the semantics of [`super::integrity`]: zero
+//!   `parent_hash` values (solo, Raft) are counted as unlinked;
+//! - the merkle root against the schemes blocks are cut with (the BFT tx
+//!   merkle tree, or the hash of the comma-joined tx IDs used by mining and
+//!   genesis blocks); zero roots are not checked;
+//! - proposer, orderer and endorsement signatures, resolved as on the commit
+//!   path by [`BlockPreverifier`], following key rotations committed in
+//!   config blocks as the walk passes them;
+//! - the transaction index: every transaction listed by a block that is
+//!   stored must be indexed under that block, and nothing else may be;
+//! - the world state, replayed from the history entries of each block and
+//!   compared with the stored state. Keys last written outside a block are
+//!   counted as off-ledger rather than reported.
+//!
+//! On a pruned store (see [`super::archive`]) the store only holds headers
+//! below the archive frontier. Given the [`BlockArchive`], the merkle root,
+//! signature and transaction index checks run over the archived bodies and
+//! transaction records instead; an archived block that cannot be read back
+//! is reported and only its header is checked. Archival leaves the world
+//! state history in place, so the state replay covers archived heights.
+//!
+//! The resulting [`LedgerReport`] can be signed into a [`SignedLedgerReport`]
+//! so an auditor can show which key vouched for it.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use super::archive::{BlockArchive, Segment};
+use super::errors::{StorageError, StorageResult};
+use super::index::KEY_SPACE_END;
+use super::traits::{Block, BlockStore, Transaction};
+use super::world_state::{VersionedValue, WorldState};
+use crate::crypto::hasher::{hash_with, HashAlgorithm};
+use crate::endorsement::registry::OrgRegistry;
+use crate::identity::signing::{SigningAlgorithm, SigningProvider};
+use crate::ordering::block_hash_for_signing;
+use crate::ordering::preverify::{BlockPreverifier, PUBLIC_KEY_SIZES};
+
+/// Which check a [`Finding`] comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Check {
+    HashChain,
+    MerkleRoot,
+    Signature,
+    TxIndex,
+    WorldState,
+    Archive,
+}
+
+/// One problem found in the ledger.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Finding {
+    pub check: Check,
+    /// Block the problem was found at; `None` for world-state keys.
+    pub height: Option<u64>,
+    pub detail: String,
+}
+
+/// Result of [`LedgerVerifier::verify`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerReport {
+    /// Unix time of the verification.
+    pub verified_at: u64,
+    /// Height of the tip; `None` for an empty ledger.
+    pub latest_height: Option<u64>,
+    /// Hex signing hash of the tip.
+    pub tip_hash: Option<String>,
+    pub blocks: u64,
+    pub linked_blocks: u64,
+    pub unlinked_blocks: u64,
+    /// Blocks whose merkle root was recomputed.
+    pub merkle_roots_checked: u64,
+    pub signatures_checked: u64,
+    /// Transactions found in the index.
+    pub transactions_indexed: u64,
+    /// Transaction IDs listed by blocks but not stored (e.g. genesis config).
+    pub transactions_not_stored: u64,
+    /// Highest archived height; the store holds headers up to it.
+    pub archived_through: Option<u64>,
+    /// Archived blocks whose bodies were read back and checked.
+    pub archived_blocks: u64,
+    /// World-state keys that match their replay.
+    pub state_keys_verified: u64,
+    /// World-state keys last written outside a block.
+    pub state_keys_off_ledger: u64,
+    pub findings: Vec<Finding>,
+}
+
+impl LedgerReport {
+    /// Whether every check passed.
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    fn find(&mut self, check: Check, height: Option<u64>, detail: String) {
+        self.findings.push(Finding {
+            check,
+            height,
+            detail,
+        });
+    }
+}
+
+/// A [`LedgerReport`] signed over its JSON encoding.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedLedgerReport {
+    pub report: LedgerReport,
+    /// Hex public key of the signer.
+    pub signer: String,
+    pub algorithm: SigningAlgorithm,
+    /// Hex signature.
+    pub signature: String,
+}
+
+impl SignedLedgerReport {
+    pub fn sign(report: LedgerReport, signer: &dyn SigningProvider) -> StorageResult<Self> {
+        let payload = serde_json::to_vec(&report)
+            .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+        let signature = signer
+            .sign(&payload)
+            .map_err(|e| StorageError::Other(e.to_string()))?;
+        Ok(Self {
+            report,
+            signer: hex::encode(signer.public_key()),
+            algorithm: signer.algorithm(),
+            signature: hex::encode(signature),
+        })
+    }
+
+    /// Whether `signature` is the signer's over the report as it stands.
+    pub fn verify(&self) -> bool {
+        let (Ok(public_key), Ok(signature), Ok(payload)) = (
+            hex::decode(&self.signer),
+            hex::decode(&self.signature),
+            serde_json::to_vec(&self.report),
+        ) else {
+            return false;
+        };
+        crate::identity::signing::verify_with_public_key(&public_key, &payload, &signature)
+    }
+}
+
+/// Verifies a whole ledger; see the module docs.
+pub struct LedgerVerifier<'a> {
+    store: &'a dyn BlockStore,
+    archive: Option<&'a BlockArchive>,
+    state: Option<&'a dyn WorldState>,
+    signatures: BlockPreverifier,
+    orderer_id: Option<String>,
+}
+
+impl<'a> LedgerVerifier<'a> {
+    pub fn new(store: &'a dyn BlockStore) -> Self {
+        Self {
+            store,
+            archive: None,
+            state: None,
+            signatures: BlockPreverifier::default(),
+            orderer_id: None,
+        }
+    }
+
+    /// Check the blocks `archive` moved out of the store against their
+    /// archived bodies.
+    pub fn with_archive(mut self, archive: &'a BlockArchive) -> Self {
+        self.archive = Some(archive);
+        self
+    }
+
+    /// Replay the world state and compare it with `state`.
+    pub fn with_state(mut self, state: &'a dyn WorldState) -> Self {
+        self.state = Some(state);
+        self
+    }
+
+    /// Check endorsements against the root keys of their orgs.
+    pub fn with_registry(mut self, registry: Arc<dyn OrgRegistry>) -> Self {
+        self.signatures = self.signatures.with_registry(registry);
+        self
+    }
+
+    /// Check orderer signatures against the keys of `orderer_id`. By default
+    /// they are checked against the proposer, which signs both on the
+    /// ordering service.
+    pub fn with_orderer(mut self, orderer_id: impl Into<String>) -> Self {
+        self.orderer_id = Some(orderer_id.into());
+        self
+    }
+
+    pub fn verify(&self) -> StorageResult<LedgerReport> {
+        let mut report = LedgerReport {
+            verified_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            ..Default::default()
+        };
+        let latest = self.store.get_latest_height()?;
+        let latest = (latest > 0 || self.store.block_exists(0)?).then_some(latest);
+        report.latest_height = latest;
+        report.archived_through = self.archive.and_then(BlockArchive::archived_through);
+
+        let mut listed: HashMap<String, u64> = HashMap::new();
+        let mut parent_hash = None;
+        let mut segment: Option<Segment> = None;
+        for height in latest.into_iter().flat_map(|latest| 0..=latest) {
+            let block = match self.store.read_block(height) {
+                Ok(block) => block,
+                Err(StorageError::KeyNotFound(_)) => {
+                    report.find(
+                        Check::HashChain,
+                        Some(height),
+                        format!("block {height} is missing"),
+                    );
+                    parent_hash = None;
+                    continue;
+                }
+                Err(StorageError::DeserializationError(e)) => {
+                    report.find(
+                        Check::HashChain,
+                        Some(height),
+                        format!("block {height} does not decode: {e}"),
+                    );
+                    parent_hash = None;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            report.blocks += 1;
+            self.check_link(&block, height, parent_hash, &mut report);
+            parent_hash = Some(block_hash_for_signing(&block));
+
+            let (block, indexed) = if report.archived_through.is_some_and(|t| height <= t) {
+                match self.archived_body(height, &mut segment) {
+                    Ok(body) => {
+                        report.archived_blocks += 1;
+                        body
+                    }
+                    Err(e) => {
+                        report.find(
+                            Check::Archive,
+                            Some(height),
+                            format!("archived block {height} cannot be read back: {e}"),
+                        );
+                        self.check_signatures(&block, &mut report);
+                        continue;
+                    }
+                }
+            } else {
+                let indexed = self.store.transactions_by_block_height(height)?;
+                (block, indexed)
+            };
+            self.check_merkle_root(&block, &mut report);
+            self.check_signatures(&block, &mut report);
+            self.check_tx_index(&block, indexed, &mut listed, &mut report)?;
+        }
+        report.tip_hash = parent_hash.map(hex::encode);
+
+        if let (Some(state), Some(latest)) = (self.state, latest) {
+            self.check_state(state, latest, &mut report)?;
+        }
+        Ok(report)
+    }
+
+    /// Archived body and transaction records of the block at `height`,
+    /// reading each segment once.
+    fn archived_body(
+        &self,
+        height: u64,
+        segment: &mut Option<Segment>,
+    ) -> StorageResult<(Block, Vec<Transaction>)> {
+        let archive = self
+            .archive
+            .ok_or_else(|| StorageError::KeyNotFound(format!("archived block:{height}")))?;
+        let cached = segment
+            .as_ref()
+            .is_some_and(|s| s.blocks.iter().any(|b| b.height == height));
+        if !cached {
+            *segment = None;
+            *segment = Some(archive.read_segment(self.store, height)?);
+        }
+        let segment = segment.as_ref().expect("segment read above");
+        let block = segment
+            .blocks
+            .iter()
+            .find(|b| b.height == height)
+            .cloned()
+            .ok_or_else(|| StorageError::KeyNotFound(format!("archived block:{height}")))?;
+        let txs = segment
+            .transactions
+            .iter()
+            .filter(|tx| tx.block_height == height)
+            .cloned()
+            .collect();
+        Ok((block, txs))
+    }
+
+    fn check_link(
+        &self,
+        block: &Block,
+        height: u64,
+        parent_hash: Option<[u8; 32]>,
+        report: &mut LedgerReport,
+    ) {
+        if block.height != height {
+            report.find(
+                Check::HashChain,
+                Some(height),
+                format!("block stored at {height} claims height {}", block.height),
+            );
+        }
+        if block.last_config > height {
+            report.find(
+                Check::HashChain,
+                Some(height),
+                format!(
+                    "block {height} points at config block {} above it",
+                    block.last_config
+                ),
+            );
+        }
+        if height == 0 {
+            return;
+        }
+        if block.parent_hash == [0u8; 32] {
+            report.unlinked_blocks += 1;
+        } else if Some(block.parent_hash) == parent_hash {
+            report.linked_blocks += 1;
+        } else if parent_hash.is_some() {
+            report.find(
+                Check::HashChain,
+                Some(height),
+                format!("block {height} does not link to block {}", height - 1),
+            );
+        }
+    }
+
+    fn check_merkle_root(&self, block: &Block, report: &mut LedgerReport) {
+        if block.merkle_root == [0u8; 32] {
+            return;
+        }
+        report.merkle_roots_checked += 1;
+        let joined = block.transactions.join(",");
+        let matches = block.merkle_root
+            == crate::ordering::bft_node::tx_merkle_root(&block.transactions)
+            || [HashAlgorithm::Sha256, HashAlgorithm::Sha3_256]
+                .into_iter()
+                .any(|algorithm| hash_with(algorithm, joined.as_bytes()) == block.merkle_root);
+        if !matches {
+            report.find(
+                Check::MerkleRoot,
+                Some(block.height),
+                format!(
+                    "merkle root of block {} does not match its {} transactions",
+                    block.height,
+                    block.transactions.len()
+                ),
+            );
+        }
+    }
+
+    fn check_signatures(&self, block: &Block, report: &mut LedgerReport) {
+        let orderer = self.orderer_id.clone().or_else(|| {
+            let keys = self
+                .signatures
+                .key_schedule()
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .keys_at(&block.proposer, block.height);
+            keys.iter()
+                .any(|k| PUBLIC_KEY_SIZES.contains(&k.len()))
+                .then(|| block.proposer.clone())
+        });
+        let signatures = match orderer {
+            Some(orderer) => self.signatures.clone().with_orderer(orderer),
+            None => self.signatures.clone(),
+        };
+        let (checked, failures) = signatures.signature_failures(block);
+        report.signatures_checked += checked as u64;
+        for failure in failures {
+            report.find(Check::Signature, Some(block.height), failure.to_string());
+        }
+        self.signatures.observe_committed(block);
+    }
+
+    fn check_tx_index(
+        &self,
+        block: &Block,
+        indexed: Vec<Transaction>,
+        listed: &mut HashMap<String, u64>,
+        report: &mut LedgerReport,
+    ) -> StorageResult<()> {
+        let height = block.height;
+        report.transactions_indexed += indexed.len() as u64;
+        let indexed_ids: BTreeSet<&str> = indexed.iter().map(|tx| tx.id.as_str()).collect();
+        let listed_ids: BTreeSet<&str> = block.transactions.iter().map(String::as_str).collect();
+
+        for tx in &indexed {
+            if tx.block_height != height {
+                report.find(
+                    Check::TxIndex,
+                    Some(height),
+                    format!(
+                        "tx {} is indexed under block {height} but records block {}",
+                        tx.id, tx.block_height
+                    ),
+                );
+            } else if !listed_ids.contains(tx.id.as_str()) {
+                report.find(
+                    Check::TxIndex,
+                    Some(height),
+                    format!(
+                        "tx {} is indexed under block {height} but not listed in it",
+                        tx.id
+                    ),
+                );
+            }
+        }
+        for id in &block.transactions {
+            if let Some(first) = listed.insert(id.clone(), height) {
+                report.find(
+                    Check::TxIndex,
+                    Some(height),
+                    format!("tx {id} is listed in blocks {first} and {height}"),
+                );
+            }
+            if indexed_ids.contains(id.as_str()) {
+                continue;
+            }
+            match self.store.read_transaction(id) {
+                Ok(tx) => report.find(
+                    Check::TxIndex,
+                    Some(height),
+                    format!(
+                        "tx {id} of block {height} is stored for block {} and missing from the index",
+                        tx.block_height
+                    ),
+                ),
+                Err(StorageError::KeyNotFound(_)) => report.transactions_not_stored += 1,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    fn check_state(
+        &self,
+        state: &dyn WorldState,
+        latest: u64,
+        report: &mut LedgerReport,
+    ) -> StorageResult<()> {
+        let mut replayed: BTreeMap<String, Option<VersionedValue>> = BTreeMap::new();
+        for height in 0..=latest {
+            for (key, entry) in state.history_at(height)? {
+                let value = (!entry.is_delete).then_some(VersionedValue {
+                    version: entry.version,
+                    data: entry.data,
+                });
+                replayed.insert(key, value);
+            }
+        }
+        let stored: BTreeMap<String, VersionedValue> =
+            state.get_range("", KEY_SPACE_END)?.into_iter().collect();
+
+        let keys: BTreeSet<&String> = replayed.keys().chain(stored.keys()).collect();
+        for key in keys {
+            let expected = replayed.get(key).cloned().flatten();
+            let actual = stored.get(key).cloned();
+            if expected == actual {
+                if actual.is_some() {
+                    report.state_keys_verified += 1;
+                }
+                continue;
+            }
+            let history = state.get_history(key)?;
+            match history.last() {
+                Some(last) if last.block_height.is_none() => {
+                    let written = (!last.is_delete).then(|| VersionedValue {
+                        version: last.version,
+                        data: last.data.clone(),
+                    });
+                    if written == actual {
+                        report.state_keys_off_ledger += 1;
+                        continue;
+                    }
+                    report.find(
+                        Check::WorldState,
+                        None,
+                        format!("{key}: stored value differs from its last (off-ledger) write"),
+                    );
+                }
+                Some(_) => report.find(
+                    Check::WorldState,
+                    None,
+                    format!(
+                        "{key}: stored {} but blocks replay to {}",
+                        describe(actual.as_ref()),
+                        describe(expected.as_ref())
+                    ),
+                ),
+                None => report.find(
+                    Check::WorldState,
+                    None,
+                    format!("{key}: stored without any history"),
+                ),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn describe(value: Option<&VersionedValue>) -> String {
+    match value {
+        Some(v) => format!("v{} ({} bytes)", v.version, v.data.len()),
+        None => "nothing".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::endorsement::org::Organization;
+    use crate::endorsement::registry::MemoryOrgRegistry;
+    use crate::identity::signing::SoftwareSigningProvider;
+    use crate::ordering::bft_node::tx_merkle_root;
+    use crate::ordering::sign_block_with_provider;
+    use crate::storage::memory::MemoryStore;
+    use crate::storage::traits::Transaction;
+    use crate::storage::world_state::MemoryWorldState;
+
+    fn tx(id: &str, height: u64) -> Transaction {
+        Transaction {
+            id: id.to_string(),
+            block_height: height,
+            timestamp: height,
+            input_did: "did:bc:alice".to_string(),
+            output_recipient: "did:bc:bob".to_string(),
+            amount: 1,
+            state: "committed".to_string(),
+        }
+    }
+
+    fn ledger(proposer: &SoftwareSigningProvider) -> (MemoryStore, MemoryWorldState) {
+        ledger_with(proposer, |_| {})
+    }
+
+    /// Four signed, linked blocks with two stored transactions and one
+    /// state write each above genesis; `tamper` edits each block after it
+    /// is signed and linked to.
+    fn ledger_with(
+        proposer: &SoftwareSigningProvider,
+        tamper: impl Fn(&mut Block),
+    ) -> (MemoryStore, MemoryWorldState) {
+        let store = MemoryStore::new();
+        let state = MemoryWorldState::new();
+        let mut parent_hash = [0u8; 32];
+        for height in 0..4u64 {
+            let ids: Vec<String> = if height == 0 {
+                vec!["{\"channel\":\"ch1\"}".to_string()]
+            } else {
+                vec![format!("tx-{height}-a"), format!("tx-{height}-b")]
+            };
+            let mut block = Block {
+                height,
+                timestamp: height,
+                parent_hash,
+                merkle_root: if height == 0 {
+                    hash_with(HashAlgorithm::Sha256, ids[0].as_bytes())
+                } else {
+                    tx_merkle_root(&ids)
+                },
+                transactions: ids.clone(),
+                proposer: hex::encode(proposer.public_key()),
+                signature: vec![],
+                signature_algorithm: Default::default(),
+                endorsements: vec![],
+                secondary_signature: None,
+                secondary_signature_algorithm: None,
+                hash_algorithm: Default::default(),
+                orderer_signature: None,
+                commit_qc: None,
+                next_validator_set: None,
+                evidence: vec![],
+                config_tx: None,
+                last_config: 0,
+                state_root: [0u8; 32],
+                private_data_purges: vec![],
+            };
+            sign_block_with_provider(&mut block, proposer);
+            parent_hash = block_hash_for_signing(&block);
+            tamper(&mut block);
+            store.write_block(&block).unwrap();
+            if height > 0 {
+                for id in &ids {
+                    store.write_transaction(&tx(id, height)).unwrap();
+                }
+                state
+                    .put_from_tx("asset", format!("v{height}").as_bytes(), &ids[0], height)
+                    .unwrap();
+            }
+        }
+        (store, state)
+    }
+
+    #[test]
+    fn clean_ledger_passes_every_check() {
+        let proposer = SoftwareSigningProvider::generate();
+        let (store, state) = ledger(&proposer);
+        state.put("config/local", b"x").unwrap();
+
+        let report = LedgerVerifier::new(&store)
+            .with_state(&state)
+            .with_registry(Arc::new(MemoryOrgRegistry::new()))
+            .verify()
+            .unwrap();
+        assert!(report.is_clean(), "{:?}", report.findings);
+        assert_eq!(report.latest_height, Some(3));
+        assert_eq!((report.blocks, report.linked_blocks), (4, 3));
+        assert_eq!(report.merkle_roots_checked, 4);
+        // Proposer and orderer signature of each block.
+        assert_eq!(report.signatures_checked, 8);
+        assert_eq!(report.transactions_indexed, 6);
+        assert_eq!(report.transactions_not_stored, 1);
+        assert_eq!(report.state_keys_verified, 1);
+        assert_eq!(report.state_keys_off_ledger, 1);
+    }
+
+    #[test]
+    fn every_kind_of_tampering_is_reported() {
+        let proposer = SoftwareSigningProvider::generate();
+        let (store, state) = ledger_with(&proposer, |block| match block.height {
+            // The merkle root is signed, the transaction list itself is not.
+            2 => block.transactions[1] = "tx-forged".to_string(),
+            1 => block.signature[0] ^= 0xFF,
+            // A changed parent hash breaks both the link and the signatures.
+            3 => block.parent_hash[0] ^= 1,
+            _ => {}
+        });
+        store.write_transaction(&tx("tx-1-a", 3)).unwrap();
+        state
+            .restore_entry(
+                "asset",
+                &VersionedValue {
+                    version: 3,
+                    data: b"forged".to_vec(),
+                },
+            )
+            .unwrap();
+
+        let report = LedgerVerifier::new(&store)
+            .with_state(&state)
+            .verify()
+            .unwrap();
+        let at = |check: Check| -> Vec<Option<u64>> {
+            report
+                .findings
+                .iter()
+                .filter(|f| f.check == check)
+                .map(|f| f.height)
+                .collect()
+        };
+        assert_eq!(at(Check::HashChain), vec![Some(3)]);
+        assert_eq!(at(Check::MerkleRoot), vec![Some(2)]);
+        assert_eq!(at(Check::Signature), vec![Some(1), Some(3), Some(3)]);
+        // tx-1-a moved to block 3: unindexed in 1, stray in 3; tx-2-b is
+        // indexed under 2 but no longer listed.
+        assert_eq!(at(Check::TxIndex), vec![Some(1), Some(2), Some(3)]);
+        assert_eq!(at(Check::WorldState), vec![None]);
+    }
+
+    #[test]
+    fn endorsements_are_checked_against_the_registry() {
+        use crate::endorsement::types::Endorsement;
+        use pqc_crypto_module::legacy::ed25519::{Signer, SigningKey};
+
+        let proposer = SoftwareSigningProvider::generate();
+        let org_key = SigningKey::from_bytes(&[5u8; 32]);
+        let registry = MemoryOrgRegistry::new();
+        let org = Organization::new(
+            "org1",
+            "Org1MSP",
+            vec!["did:bc:admin".into()],
+            vec![],
+            vec![org_key.verifying_key().to_bytes()],
+        )
+        .unwrap();
+        registry.register_org(&org).unwrap();
+        let (store, _) = ledger_with(&proposer, |block| {
+            if block.height == 1 {
+                block.endorsements = vec![Endorsement {
+                    signer_did: "did:bc:org1".to_string(),
+                    org_id: "org1".to_string(),
+                    signature: org_key.sign(&[1u8; 32]).to_bytes().to_vec(),
+                    signature_algorithm: Default::default(),
+                    payload_hash: [2u8; 32],
+                    timestamp: 0,
+                }];
+            }
+        });
+
+        let report = LedgerVerifier::new(&store)
+            .with_registry(Arc::new(registry))
+            .verify()
+            .unwrap();
+        assert_eq!(report.findings.len(), 1, "{:?}", report.findings);
+        assert!(report.findings[0].detail.contains("endorsement 0"));
+    }
+
+    #[test]
+    fn signed_report_detects_edits() {
+        let proposer = SoftwareSigningProvider::generate();
+        let (store, _) = ledger(&proposer);
+        let report = LedgerVerifier::new(&store).verify().unwrap();
+        let signer = SoftwareSigningProvider::generate();
+        let mut signed = SignedLedgerReport::sign(report, &signer).unwrap();
+        assert!(signed.verify());
+        assert_eq!(signed.signer, hex::encode(signer.public_key()));
+
+        signed.report.findings.clear();
+        signed.report.blocks += 1;
+        assert!(!signed.verify());
+    }
+
+    #[test]
+    fn pruned_ledger_is_checked_against_its_archive() {
+        use crate::storage::archive::BlockArchive;
+
+        let proposer = SoftwareSigningProvider::generate();
+        let (store, state) = ledger(&proposer);
+        let dir = tempfile::tempdir().unwrap();
+        let archive = BlockArchive::open(
+            dir.path(),
+            "ch1",
+            Arc::new(SoftwareSigningProvider::generate()),
+            vec![],
+        )
+        .unwrap();
+        archive.archive_range(&store, 0, 2).unwrap();
+
+        // Headers alone fail the body checks.
+        let headers_only = LedgerVerifier::new(&store).verify().unwrap();
+        assert!(headers_only
+            .findings
+            .iter()
+            .any(|f| f.check == Check::MerkleRoot && f.height == Some(1)));
+
+        let report = LedgerVerifier::new(&store)
+            .with_archive(&archive)
+            .with_state(&state)
+            .verify()
+            .unwrap();
+        assert!(report.is_clean(), "{:?}", report.findings);
+        assert_eq!(report.archived_through, Some(2));
+        assert_eq!(report.archived_blocks, 3);
+        assert_eq!((report.blocks, report.linked_blocks), (4, 3));
+        assert_eq!(report.merkle_roots_checked, 4);
+        assert_eq!(report.signatures_checked, 8);
+        assert_eq!(report.transactions_indexed, 6);
+        assert_eq!(report.state_keys_verified, 1);
+
+        // A missing segment is reported; the headers are still checked.
+        let seg = &archive.manifest().segments[0];
+        std::fs::remove_file(dir.path().join("archive").join("ch1").join(&seg.file)).unwrap();
+        let report = LedgerVerifier::new(&store)
+            .with_archive(&archive)
+            .verify()
+            .unwrap();
+        let archive_findings: Vec<_> = report
+            .findings
+            .iter()
+            .filter(|f| f.check == Check::Archive)
+            .map(|f| f.height)
+            .collect();
+        assert_eq!(archive_findings, vec![Some(0), Some(1), Some(2)]);
+        assert_eq!(report.findings.len(), 3, "{:?}", report.findings);
+        assert_eq!(report.archived_blocks, 0);
+        assert_eq!(report.signatures_checked, 8);
+    }
+
+    #[test]
+    fn empty_ledger_has_no_tip() {
+        let report = LedgerVerifier::new(&MemoryStore::new())
+            .with_state(&MemoryWorldState::new())
+            .verify()
+            .unwrap();
+        assert!(report.is_clean());
+        assert_eq!((report.latest_height, report.tip_hash), (None, None));
+    }
+}
@@ -6,7 +6,7 @@
 //! current committed version.
 
 use super::errors::StorageResult;
-use super::traits::HistoryEntry;
+use super::traits::{Block, BlockStore, HistoryEntry, Transaction};
 
 /// A versioned value stored in the world state.
 ///
@@ -18,6 +18,56 @@ pub struct VersionedValue {
     pub data: Vec<u8>,
 }
 
+/// One world-state write of a block; `value: None` deletes the key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateWrite {
+    pub key: String,
+    pub value: Option<Vec<u8>>,
+    pub tx_id: String,
+}
+
+impl StateWrite {
+    pub fn put(tx_id: &str, key: &str, value: &[u8]) -> Self {
+        Self {
+            key: key.to_string(),
+            value: Some(value.to_vec()),
+            tx_id: tx_id.to_string(),
+        }
+    }
+
+    pub fn delete(tx_id: &str, key: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            value: None,
+            tx_id: tx_id.to_string(),
+        }
+    }
+}
+
+/// Apply `writes` to `state`, then write `block` and `txs` to `store`, one
+/// call at a time. A crash in between leaves the state ahead of the ledger.
+pub fn commit_in_steps<S: WorldState + ?Sized>(
+    state: &S,
+    store: &dyn BlockStore,
+    block: &Block,
+    txs: &[Transaction],
+    writes: &[StateWrite],
+) -> StorageResult<()> {
+    for write in writes {
+        match &write.value {
+            Some(value) => {
+                state.put_from_tx(&write.key, value, &write.tx_id, block.height)?;
+            }
+            None => state.delete(&write.key)?,
+        }
+    }
+    store.write_block(block)?;
+    for tx in txs {
+        store.write_transaction(tx)?;
+    }
+    Ok(())
+}
+
 /// Versioned key-value world state.
 ///
 /// Implementations must be `Send + Sync` so they can live behind `Arc<dyn WorldState>`.
@@ -38,8 +88,68 @@ pub trait WorldState: Send + Sync {
     /// lexicographically by key.
     fn get_range(&self, start: &str, end: &str) -> StorageResult<Vec<(String, VersionedValue)>>;
 
+    /// Return the first `limit` entries of [`get_range`](Self::get_range).
+    ///
+    /// Default collects the whole range and truncates it; backends that scan
+    /// keys in order override it to stop after `limit` entries.
+    fn get_range_page(
+        &self,
+        start: &str,
+        end: &str,
+        limit: usize,
+    ) -> StorageResult<Vec<(String, VersionedValue)>> {
+        let mut entries = self.get_range(start, end)?;
+        entries.truncate(limit);
+        Ok(entries)
+    }
+
     /// Return the full change history for `key`, ordered by version.
     fn get_history(&self, key: &str) -> StorageResult<Vec<HistoryEntry>>;
+
+    /// Return the history entries written by the block at `block_height`,
+    /// as `(key, entry)` in key order.
+    ///
+    /// Default returns nothing, for backends that keep no history.
+    fn history_at(&self, _block_height: u64) -> StorageResult<Vec<(String, HistoryEntry)>> {
+        Ok(Vec::new())
+    }
+
+    /// Install `value` under `key` with its version as-is, e.g. when
+    /// restoring a snapshot taken on another peer.
+    ///
+    /// Default falls back to `put`, which restarts the version counter.
+    fn restore_entry(&self, key: &str, value: &VersionedValue) -> StorageResult<()> {
+        self.put(key, &value.data).map(|_| ())
+    }
+
+    /// `put` on behalf of transaction `tx_id` committed in block
+    /// `block_height`, so the history entry can link back to both.
+    ///
+    /// Default falls back to `put` for backends that keep no history.
+    fn put_from_tx(
+        &self,
+        key: &str,
+        data: &[u8],
+        _tx_id: &str,
+        _block_height: u64,
+    ) -> StorageResult<u64> {
+        self.put(key, data)
+    }
+
+    /// Commit `block` to `store` together with its transactions and the
+    /// state `writes` it makes.
+    ///
+    /// Default goes through [`commit_in_steps`]; a backend that also holds
+    /// `store` overrides it to commit everything in one batch.
+    fn commit_block_to(
+        &self,
+        store: &dyn BlockStore,
+        block: &Block,
+        txs: &[Transaction],
+        writes: &[StateWrite],
+    ) -> StorageResult<()> {
+        commit_in_steps(self, store, block, txs, writes)
+    }
 }
 
 // ── MemoryStore implementation ────────────────────────────────────────────────
@@ -63,23 +173,14 @@ impl MemoryWorldState {
     }
 }
 
-impl Default for MemoryWorldState {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl WorldState for MemoryWorldState {
-    fn get(&self, key: &str) -> StorageResult<Option<VersionedValue>> {
-        Ok(self
-            .inner
-            .lock()
-            .unwrap_or_else(|e| e.into_inner())
-            .get(key)
-            .cloned())
-    }
-
-    fn put(&self, key: &str, data: &[u8]) -> StorageResult<u64> {
+impl MemoryWorldState {
+    fn record_put(
+        &self,
+        key: &str,
+        data: &[u8],
+        tx_id: &str,
+        block_height: Option<u64>,
+    ) -> StorageResult<u64> {
         let mut map = self.inner.lock().unwrap_or_else(|e| e.into_inner());
         let new_version = map.get(key).map(|v| v.version + 1).unwrap_or(1);
         map.insert(
@@ -95,16 +196,48 @@ impl WorldState for MemoryWorldState {
         hist.entry(key.to_string()).or_default().push(HistoryEntry {
             version: new_version,
             data: data.to_vec(),
-            tx_id: String::new(),
+            tx_id: tx_id.to_string(),
             timestamp: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or_default()
                 .as_secs(),
             is_delete: false,
+            block_height,
         });
 
         Ok(new_version)
     }
+}
+
+impl Default for MemoryWorldState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WorldState for MemoryWorldState {
+    fn get(&self, key: &str) -> StorageResult<Option<VersionedValue>> {
+        Ok(self
+            .inner
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(key)
+            .cloned())
+    }
+
+    fn put(&self, key: &str, data: &[u8]) -> StorageResult<u64> {
+        self.record_put(key, data, "", None)
+    }
+
+    fn put_from_tx(
+        &self,
+        key: &str,
+        data: &[u8],
+        tx_id: &str,
+        block_height: u64,
+    ) -> StorageResult<u64> {
+        self.record_put(key, data, tx_id, Some(block_height))
+    }
 
     fn delete(&self, key: &str) -> StorageResult<()> {
         let mut map = self.inner.lock().unwrap_or_else(|e| e.into_inner());
@@ -122,15 +255,37 @@ impl WorldState for MemoryWorldState {
                 .unwrap_or_default()
                 .as_secs(),
             is_delete: true,
+            block_height: None,
         });
 
         Ok(())
     }
 
+    fn restore_entry(&self, key: &str, value: &VersionedValue) -> StorageResult<()> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key.to_string(), value.clone());
+        Ok(())
+    }
+
     fn get_range(&self, start: &str, end: &str) -> StorageResult<Vec<(String, VersionedValue)>> {
+        self.get_range_page(start, end, usize::MAX)
+    }
+
+    fn get_range_page(
+        &self,
+        start: &str,
+        end: &str,
+        limit: usize,
+    ) -> StorageResult<Vec<(String, VersionedValue)>> {
+        if start >= end {
+            return Ok(Vec::new());
+        }
         let map = self.inner.lock().unwrap_or_else(|e| e.into_inner());
         let result = map
             .range(start.to_string()..end.to_string())
+            .take(limit)
             .map(|(k, v)| (k.clone(), v.clone()))
             .collect();
         Ok(result)
@@ -140,6 +295,21 @@ impl WorldState for MemoryWorldState {
         let hist = self.history.lock().unwrap_or_else(|e| e.into_inner());
         Ok(hist.get(key).cloned().unwrap_or_default())
     }
+
+    fn history_at(&self, block_height: u64) -> StorageResult<Vec<(String, HistoryEntry)>> {
+        let hist = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        let mut entries: Vec<(String, HistoryEntry)> = hist
+            .iter()
+            .flat_map(|(key, entries)| {
+                entries
+                    .iter()
+                    .filter(|e| e.block_height == Some(block_height))
+                    .map(move |e| (key.clone(), e.clone()))
+            })
+            .collect();
+        entries.sort_by(|a, b| (&a.0, a.1.version).cmp(&(&b.0, b.1.version)));
+        Ok(entries)
+    }
 }
 
 // ── Composite key helpers ─────────────────────────────────────────────────────
@@ -275,6 +445,22 @@ mod tests {
         assert_eq!(keys, ["key02", "key03", "key04", "key05", "key06"]);
     }
 
+    #[test]
+    fn get_range_page_stops_after_limit() {
+        let s = ws();
+        for i in 0..10u8 {
+            s.put(&format!("key{i:02}"), &[i]).unwrap();
+        }
+        let keys: Vec<String> = s
+            .get_range_page("key02", "key09", 3)
+            .unwrap()
+            .into_iter()
+            .map(|(k, _)| k)
+            .collect();
+        assert_eq!(keys, ["key02", "key03", "key04"]);
+        assert!(s.get_range_page("key05", "key02", 3).unwrap().is_empty());
+    }
+
     #[test]
     fn get_range_empty_when_no_match() {
         let s = ws();
@@ -415,6 +601,25 @@ mod tests {
         assert!(hist[1].data.is_empty());
     }
 
+    #[test]
+    fn history_at_returns_the_writes_of_one_block() {
+        let s = ws();
+        s.put_from_tx("b", b"1", "tx1", 1).unwrap();
+        s.put_from_tx("a", b"1", "tx1", 1).unwrap();
+        s.put_from_tx("a", b"2", "tx2", 2).unwrap();
+        s.put("c", b"1").unwrap();
+
+        let keys: Vec<(String, u64)> = s
+            .history_at(1)
+            .unwrap()
+            .into_iter()
+            .map(|(k, e)| (k, e.version))
+            .collect();
+        assert_eq!(keys, vec![("a".to_string(), 1), ("b".to_string(), 1)]);
+        assert_eq!(s.history_at(2).unwrap()[0].1.tx_id, "tx2");
+        assert!(s.history_at(3).unwrap().is_empty());
+    }
+
     #[test]
     fn put_put_delete_history_sequence() {
         let s = ws();
@@ -431,9 +636,35 @@ mod tests {
         assert_eq!(hist[2].version, 3);
     }
 
+    #[test]
+    fn put_from_tx_links_history_to_the_tx_and_block() {
+        let s = ws();
+        s.put("k", b"v1").unwrap();
+        s.put_from_tx("k", b"v2", "tx-9", 4).unwrap();
+        let hist = s.get_history("k").unwrap();
+        assert_eq!((hist[0].tx_id.as_str(), hist[0].block_height), ("", None));
+        assert_eq!(
+            (hist[1].tx_id.as_str(), hist[1].block_height),
+            ("tx-9", Some(4))
+        );
+        assert_eq!(s.get("k").unwrap().unwrap().version, 2);
+    }
+
     #[test]
     fn get_history_absent_key_returns_empty() {
         let s = ws();
         assert!(s.get_history("missing").unwrap().is_empty());
     }
+
+    #[test]
+    fn restore_entry_keeps_the_version_and_later_puts_continue_from_it() {
+        let s = ws();
+        let vv = VersionedValue {
+            version: 7,
+            data: b"v".to_vec(),
+        };
+        s.restore_entry("k", &vv).unwrap();
+        assert_eq!(s.get("k").unwrap(), Some(vv));
+        assert_eq!(s.put("k", b"w").unwrap(), 8);
+    }
 }
@@ -70,6 +70,13 @@ pub fn stress_storage(ops: u64) -> ModuleStressResult {
             secondary_signature_algorithm: None,
             hash_algorithm: Default::default(),
             orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: Vec::new(),
+            config_tx: None,
+            last_config: 0,
+            state_root: [0u8; 32],
+            private_data_purges: vec![],
         };
 
         let op_start = Instant::now();
@@ -945,6 +952,13 @@ mod tests {
                             secondary_signature_algorithm: None,
                             hash_algorithm: Default::default(),
                             orderer_signature: None,
+                            commit_qc: None,
+                            next_validator_set: None,
+                            evidence: Vec::new(),
+                            config_tx: None,
+                            last_config: 0,
+                            state_root: [0u8; 32],
+                            private_data_purges: vec![],
                         };
                         if s.write_block(&block).is_err() {
                             errs.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
@@ -1568,6 +1582,13 @@ mod tests {
                             secondary_signature_algorithm: None,
                             hash_algorithm: Default::default(),
                             orderer_signature: None,
+                            commit_qc: None,
+                            next_validator_set: None,
+                            evidence: Vec::new(),
+                            config_tx: None,
+                            last_config: 0,
+                            state_root: [0u8; 32],
+                            private_data_purges: vec![],
                         };
                         if s.write_block(&block).is_err() {
                             errs.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
@@ -1670,6 +1691,13 @@ mod tests {
                         secondary_signature_algorithm: None,
                         hash_algorithm: Default::default(),
                         orderer_signature: None,
+                        commit_qc: None,
+                        next_validator_set: None,
+                        evidence: Vec::new(),
+                        config_tx: None,
+                        last_config: 0,
+                        state_root: [0u8; 32],
+                        private_data_purges: vec![],
                     };
                     if s.write_block(&block).is_err() {
                         e.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
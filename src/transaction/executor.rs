@@ -11,12 +11,15 @@
 //! Both modes guarantee determinism: writes are applied in ascending index order
 //! within each wave, and waves are processed sequentially.
 
-use std::sync::Arc;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
 
 use super::endorsed::EndorsedTransaction;
 use super::mvcc;
 use super::parallel::{schedule_batch, BatchSchedule, TxWithRwSet};
-use crate::storage::traits::Transaction;
+use crate::storage::errors::StorageResult;
+use crate::storage::traits::{HistoryEntry, Transaction};
+use crate::storage::world_state::{StateWrite, VersionedValue};
 use crate::storage::WorldState;
 
 /// Result of executing a single transaction.
@@ -56,6 +59,43 @@ enum ValidationResult {
 pub fn execute_block_parallel(
     txs: &[EndorsedTransaction],
     state: &dyn WorldState,
+) -> BlockExecResult {
+    run_waves(txs, state, |endorsed| {
+        for write in &endorsed.rwset.writes {
+            let _ = state.put(&write.key, &write.value);
+        }
+    })
+}
+
+/// Plan a block like [`execute_block_parallel`] without touching `state`.
+///
+/// Returns the outcomes and the writes of the committed transactions in
+/// apply order, for the caller to commit together with the block.
+pub fn plan_block_parallel(
+    txs: &[EndorsedTransaction],
+    state: &dyn WorldState,
+) -> (BlockExecResult, Vec<StateWrite>) {
+    let staged = StagedState::new(state);
+    let mut writes = Vec::new();
+    let result = run_waves(txs, &staged, |endorsed| {
+        for write in &endorsed.rwset.writes {
+            let _ = staged.put(&write.key, &write.value);
+            writes.push(StateWrite::put(
+                &endorsed.proposal.tx.id,
+                &write.key,
+                &write.value,
+            ));
+        }
+    });
+    (result, writes)
+}
+
+/// Validate `txs` wave by wave against `state`, calling `apply` for each
+/// valid transaction in ascending index order.
+fn run_waves(
+    txs: &[EndorsedTransaction],
+    state: &dyn WorldState,
+    mut apply: impl FnMut(&EndorsedTransaction),
 ) -> BlockExecResult {
     let (schedule, _batch) = prepare_schedule(txs);
     let mut outcomes: Vec<Option<(String, TxOutcome)>> = vec![None; txs.len()];
@@ -70,9 +110,7 @@ pub fn execute_block_parallel(
             let endorsed = &txs[idx];
             match mvcc::validate_rwset(&endorsed.rwset, state) {
                 Ok(()) => {
-                    for write in &endorsed.rwset.writes {
-                        let _ = state.put(&write.key, &write.value);
-                    }
+                    apply(endorsed);
                     outcomes[idx] = Some((endorsed.proposal.tx.id.clone(), TxOutcome::Committed));
                     committed_count += 1;
                 }
@@ -97,6 +135,76 @@ pub fn execute_block_parallel(
     }
 }
 
+/// Writes of a block being planned, layered over the committed state so
+/// later transactions validate against the versions earlier ones produce.
+struct StagedState<'a> {
+    base: &'a dyn WorldState,
+    staged: Mutex<BTreeMap<String, Option<VersionedValue>>>,
+}
+
+impl<'a> StagedState<'a> {
+    fn new(base: &'a dyn WorldState) -> Self {
+        Self {
+            base,
+            staged: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Stage `value` under `key` (`None` deletes) and return its version.
+    fn stage(&self, key: &str, value: Option<&[u8]>) -> StorageResult<u64> {
+        let version = self.get(key)?.map_or(0, |current| current.version) + 1;
+        self.staged
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(
+                key.to_string(),
+                value.map(|data| VersionedValue {
+                    version,
+                    data: data.to_vec(),
+                }),
+            );
+        Ok(version)
+    }
+}
+
+impl WorldState for StagedState<'_> {
+    fn get(&self, key: &str) -> StorageResult<Option<VersionedValue>> {
+        match self
+            .staged
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(key)
+        {
+            Some(staged) => Ok(staged.clone()),
+            None => self.base.get(key),
+        }
+    }
+
+    fn put(&self, key: &str, data: &[u8]) -> StorageResult<u64> {
+        self.stage(key, Some(data))
+    }
+
+    fn delete(&self, key: &str) -> StorageResult<()> {
+        self.stage(key, None).map(|_| ())
+    }
+
+    fn get_range(&self, start: &str, end: &str) -> StorageResult<Vec<(String, VersionedValue)>> {
+        let mut range: BTreeMap<_, _> = self.base.get_range(start, end)?.into_iter().collect();
+        let staged = self.staged.lock().unwrap_or_else(|e| e.into_inner());
+        for (key, value) in staged.range(start.to_string()..end.to_string()) {
+            match value {
+                Some(value) => range.insert(key.clone(), value.clone()),
+                None => range.remove(key),
+            };
+        }
+        Ok(range.into_iter().collect())
+    }
+
+    fn get_history(&self, key: &str) -> StorageResult<Vec<HistoryEntry>> {
+        self.base.get_history(key)
+    }
+}
+
 // ── Concurrent executor (tokio) ─────────────────────────────────────────────
 
 /// Execute a block with true intra-wave concurrency using tokio tasks.
@@ -443,6 +551,32 @@ mod tests {
         assert_eq!(result.committed_count, 3);
     }
 
+    #[test]
+    fn planning_stages_writes_without_touching_state() {
+        let state = ws();
+        state.put("k", b"v1").unwrap();
+        let txs = vec![
+            endorsed("tx1", &[("k", 1)], &[("k", b"v2")]),
+            endorsed("tx2", &[("k", 1)], &[("k", b"v3")]),
+            endorsed("tx3", &[("k", 2)], &[("k", b"v4")]),
+        ];
+
+        let (result, writes) = plan_block_parallel(&txs, &state);
+        assert_eq!(result.committed_count, 2);
+        assert!(matches!(
+            result.outcomes[1].1,
+            TxOutcome::MvccConflict { .. }
+        ));
+        assert_eq!(
+            writes,
+            vec![
+                StateWrite::put("tx1", "k", b"v2"),
+                StateWrite::put("tx3", "k", b"v4"),
+            ]
+        );
+        assert_eq!(state.get("k").unwrap().unwrap().data, b"v1");
+    }
+
     // --- concurrent executor (tokio) ---
 
     #[tokio::test]
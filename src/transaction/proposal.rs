@@ -1,13 +1,21 @@
+use pqc_crypto_module::legacy::sha256::{Digest, Sha256};
 use serde::{Deserialize, Serialize};
 
 use crate::endorsement::types::Endorsement;
-use crate::identity::signing::SigningAlgorithm;
+use crate::identity::did::DidDocument;
+use crate::identity::signing::{SigningAlgorithm, SigningError, SigningProvider};
 use crate::storage::traits::Transaction;
 use crate::transaction::rwset::ReadWriteSet;
 
+/// Domain tag of the digest a proposal's creator signs.
+const CREATOR_DOMAIN: &[u8] = b"rust-bc/tx-proposal/v1";
+
 /// A transaction proposal submitted by a client for endorsement.
 ///
 /// `creator_signature` is variable-length to support post-quantum algorithms.
+/// The creator signs [`signing_payload`](Self::signing_payload); the orderer
+/// checks it under the key named by `creator_did` before admitting the
+/// endorsed transaction.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionProposal {
     pub tx: Transaction,
@@ -18,6 +26,27 @@ pub struct TransactionProposal {
     pub rwset: ReadWriteSet,
 }
 
+impl TransactionProposal {
+    /// Digest the creator signs: SHA-256 over the domain tag
+    /// `rust-bc/tx-proposal/v1`, then the JSON of `tx` and of `rwset`.
+    pub fn signing_payload(&self) -> [u8; 32] {
+        let mut h = Sha256::new();
+        h.update(CREATOR_DOMAIN);
+        h.update(serde_json::to_vec(&self.tx).unwrap_or_default());
+        h.update(serde_json::to_vec(&self.rwset).unwrap_or_default());
+        h.finalize().into()
+    }
+
+    /// Sign the proposal as its creator: `creator_did` becomes the
+    /// `did:key` DID of `signer`'s public key.
+    pub fn sign(&mut self, signer: &dyn SigningProvider) -> Result<(), SigningError> {
+        self.creator_did = DidDocument::did_key(&signer.public_key());
+        self.signature_algorithm = signer.algorithm();
+        self.creator_signature = signer.sign(&self.signing_payload())?;
+        Ok(())
+    }
+}
+
 /// An endorser's response to a transaction proposal.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProposalResponse {
@@ -79,6 +108,32 @@ mod tests {
         assert!(!proposal.rwset.is_empty());
     }
 
+    #[test]
+    fn signed_proposal_verifies_under_its_did_key() {
+        let signer = crate::identity::signing::SoftwareSigningProvider::generate();
+        let mut proposal = TransactionProposal {
+            tx: sample_tx(),
+            creator_did: String::new(),
+            creator_signature: Vec::new(),
+            signature_algorithm: Default::default(),
+            rwset: sample_rwset(),
+        };
+        proposal.sign(&signer).unwrap();
+
+        let key = DidDocument::did_key_public_key(&proposal.creator_did).unwrap();
+        assert_eq!(key, signer.public_key());
+        let payload = proposal.signing_payload();
+        assert!(crate::identity::signing::verify_with_public_key(
+            &key,
+            &payload,
+            &proposal.creator_signature
+        ));
+
+        // The signature covers the read-write set too.
+        proposal.rwset.writes[0].value = vec![2];
+        assert_ne!(proposal.signing_payload(), payload);
+    }
+
     #[test]
     fn creates_proposal_response() {
         let response = ProposalResponse {
@@ -4,12 +4,20 @@
 //! These tests verify safety (no conflicting decisions) and liveness
 //! (progress despite faults) of the HotStuff-inspired BFT consensus.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
 
-use rust_bc::consensus::bft::quorum::SignatureVerifier;
+use rust_bc::consensus::bft::quorum::{QuorumValidator, SignatureVerifier};
 use rust_bc::consensus::bft::round::RoundEvent;
 use rust_bc::consensus::bft::round_manager::{RoundManager, RoundManagerConfig};
 use rust_bc::consensus::bft::types::{BftPhase, VoteMessage};
+use rust_bc::identity::signing::{SigningProvider, SoftwareSigningProvider};
+use rust_bc::network::Message;
+use rust_bc::ordering::bft_node::{tx_merkle_root, validator_id, PublicKeyVerifier};
+use rust_bc::ordering::bft_service::BftOrderingService;
+use rust_bc::ordering::{block_hash_for_signing, sign_block_with_provider};
+use rust_bc::storage::traits::{Block, Transaction};
 
 /// Local signature verifier for integration tests — accepts any non-empty signature.
 #[derive(Clone)]
@@ -179,6 +187,7 @@ impl BftTestNetwork {
             node.manager.process_event(RoundEvent::Proposal {
                 block_hash: bh,
                 leader_id: leader_id.clone(),
+                justify: None,
             });
         }
 
@@ -668,3 +677,384 @@ fn e2e_alternating_partitions_stress() {
 
     net.assert_safety();
 }
+
+// ── Ordering services over in-memory signed messages ───────────────────────
+//
+// The tests below run `BftOrderingService` instances — the backend a node
+// starts with `ORDERING_BACKEND=bft` — with real Ed25519 keys. Their signed
+// consensus messages are routed directly between services in memory; the
+// `network::Node` transport is not involved. Byzantine members tamper with
+// what they send.
+
+/// Misbehaviour of a validator in an `InMemoryNetwork`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Byzantine {
+    /// Follows the protocol.
+    No,
+    /// Sends nothing at all.
+    Silent,
+    /// Proposes conflicting blocks and votes for both, all validly signed.
+    Equivocate,
+    /// Impersonates other validators with forged signatures.
+    Forge,
+}
+
+struct Validator {
+    svc: BftOrderingService,
+    signer: Arc<dyn SigningProvider>,
+    byzantine: Byzantine,
+    /// Committed ledger: decided blocks plus blocks synced from peers.
+    ledger: BTreeMap<u64, Block>,
+}
+
+struct InMemoryNetwork {
+    nodes: Vec<Validator>,
+    validators: Vec<String>,
+    now_ms: u64,
+    tx_seq: u64,
+}
+
+impl InMemoryNetwork {
+    fn new(byzantine: &[Byzantine]) -> Self {
+        let signers: Vec<Arc<dyn SigningProvider>> = byzantine
+            .iter()
+            .map(|_| Arc::new(SoftwareSigningProvider::generate()) as Arc<dyn SigningProvider>)
+            .collect();
+        let validators: Vec<String> = signers.iter().map(|s| validator_id(s.as_ref())).collect();
+        let config = RoundManagerConfig {
+            base_timeout_ms: 200,
+            max_timeout_ms: 2_000,
+        };
+        let nodes = signers
+            .into_iter()
+            .zip(byzantine)
+            .map(|(signer, &byzantine)| {
+                let svc =
+                    BftOrderingService::new(signer.clone(), validators.clone(), config.clone(), 10)
+                        .with_commit_wait(Duration::ZERO);
+                svc.start(0);
+                Validator {
+                    svc,
+                    signer,
+                    byzantine,
+                    ledger: BTreeMap::new(),
+                }
+            })
+            .collect();
+        Self {
+            nodes,
+            validators,
+            now_ms: 0,
+            tx_seq: 0,
+        }
+    }
+
+    fn honest(&self) -> impl Iterator<Item = &Validator> {
+        self.nodes.iter().filter(|n| n.byzantine == Byzantine::No)
+    }
+
+    /// Submit a transaction through the first honest validator.
+    fn submit(&mut self) {
+        self.tx_seq += 1;
+        let tx = Transaction {
+            id: format!("tx-{}", self.tx_seq),
+            block_height: 0,
+            timestamp: 0,
+            input_did: "did:bc:alice".to_string(),
+            output_recipient: "did:bc:bob".to_string(),
+            amount: self.tx_seq,
+            state: "pending".to_string(),
+        };
+        let node = self
+            .nodes
+            .iter()
+            .find(|n| n.byzantine == Byzantine::No)
+            .unwrap();
+        node.svc.submit_tx(&tx).unwrap();
+    }
+
+    /// What Byzantine validator `from` sends to validator `to` instead of `msg`.
+    fn tamper(&self, from: usize, to: usize, msg: &Message) -> Vec<Message> {
+        let node = &self.nodes[from];
+        match node.byzantine {
+            Byzantine::No => vec![msg.clone()],
+            Byzantine::Silent => vec![],
+            Byzantine::Equivocate => match msg {
+                // Odd-indexed validators see a conflicting block.
+                Message::BftProposal {
+                    round,
+                    leader_id,
+                    block_data,
+                    ..
+                } if to % 2 == 1 => {
+                    let mut block: Block = serde_json::from_slice(block_data).unwrap();
+                    block.transactions = vec![format!("byz-{round}")];
+                    block.merkle_root = tx_merkle_root(&block.transactions);
+                    sign_block_with_provider(&mut block, node.signer.as_ref());
+                    vec![Message::BftProposal {
+                        round: *round,
+                        block_hash: block_hash_for_signing(&block),
+                        leader_id: leader_id.clone(),
+                        block_data: serde_json::to_vec(&block).unwrap(),
+                        justify: None,
+                    }]
+                }
+                Message::BftVote(vote) => {
+                    let mut conflicting = vote.clone();
+                    conflicting.block_hash = [0xbb; 32];
+                    conflicting.signature = node.signer.sign(&conflicting.payload()).unwrap();
+                    vec![msg.clone(), Message::BftVote(conflicting)]
+                }
+                _ => vec![msg.clone()],
+            },
+            Byzantine::Forge => match msg {
+                Message::BftVote(vote) => {
+                    // Claim to be every other validator, with garbage signatures.
+                    self.validators
+                        .iter()
+                        .map(|id| {
+                            let mut forged = vote.clone();
+                            forged.voter_id = id.clone();
+                            forged.block_hash = [0xf0; 32];
+                            forged.signature = vec![0x42; 64];
+                            Message::BftVote(forged)
+                        })
+                        .collect()
+                }
+                Message::BftProposal {
+                    round, block_data, ..
+                } => {
+                    // A proposal in the name of the next leader, unsigned by it.
+                    let mut block: Block = serde_json::from_slice(block_data).unwrap();
+                    block.transactions = vec![format!("forged-{round}")];
+                    block.merkle_root = tx_merkle_root(&block.transactions);
+                    let next_leader =
+                        &self.validators[(*round as usize + 1) % self.validators.len()];
+                    block.proposer = next_leader.clone();
+                    vec![
+                        msg.clone(),
+                        Message::BftProposal {
+                            round: round + 1,
+                            block_hash: block_hash_for_signing(&block),
+                            leader_id: next_leader.clone(),
+                            block_data: serde_json::to_vec(&block).unwrap(),
+                            justify: None,
+                        },
+                    ]
+                }
+                _ => vec![msg.clone()],
+            },
+        }
+    }
+
+    /// Deliver outbound messages until the network is quiet.
+    fn route(&mut self) {
+        loop {
+            let mut deliveries = Vec::new();
+            for from in 0..self.nodes.len() {
+                for msg in self.nodes[from].svc.take_outbound() {
+                    for to in 0..self.nodes.len() {
+                        if to != from {
+                            for m in self.tamper(from, to, &msg) {
+                                deliveries.push((to, m));
+                            }
+                        }
+                    }
+                }
+            }
+            if deliveries.is_empty() {
+                return;
+            }
+            for (to, msg) in deliveries {
+                self.nodes[to].svc.handle_message(msg);
+            }
+        }
+    }
+
+    /// Move decided blocks into each ledger, then let lagging honest nodes
+    /// sync blocks they missed from peers (as `OrderedBlock` would).
+    fn commit_and_sync(&mut self) {
+        for node in self.nodes.iter_mut() {
+            while let Some(block) = node.svc.cut_block(0, "test").unwrap() {
+                node.ledger.insert(block.height, block);
+            }
+        }
+        let mut known: BTreeMap<u64, Block> = BTreeMap::new();
+        for node in self.honest() {
+            for (h, b) in &node.ledger {
+                known.entry(*h).or_insert_with(|| b.clone());
+            }
+        }
+        for node in self.nodes.iter_mut() {
+            for (h, b) in &known {
+                if !node.ledger.contains_key(h) {
+                    node.svc.observe_block(b);
+                    node.ledger.insert(*h, b.clone());
+                }
+            }
+        }
+    }
+
+    /// Run until every honest validator has committed `height` blocks,
+    /// advancing simulated time so stalled rounds time out.
+    fn run_until_height(&mut self, height: u64) {
+        let mut steps = 0;
+        while self.honest().any(|n| (n.ledger.len() as u64) < height) {
+            steps += 1;
+            assert!(
+                steps < 2_000,
+                "honest validators stalled below height {height}"
+            );
+            let committed = self.honest().map(|n| n.ledger.len()).min().unwrap_or(0) as u64;
+            if self.tx_seq <= committed {
+                self.submit();
+            }
+            self.route();
+            self.commit_and_sync();
+            self.now_ms += 50;
+            for node in &self.nodes {
+                node.svc.tick(self.now_ms);
+            }
+        }
+    }
+
+    /// Every honest ledger holds the same chain, each block carrying a commit
+    /// QC that verifies against the validators' real keys.
+    fn assert_honest_agreement(&self, height: u64) {
+        let qv = QuorumValidator::new(self.validators.clone(), PublicKeyVerifier);
+        let reference: Vec<&Block> = self.honest().next().unwrap().ledger.values().collect();
+        for node in self.honest() {
+            let chain: Vec<&Block> = node.ledger.values().collect();
+            for (i, block) in chain.iter().enumerate().take(height as usize) {
+                assert_eq!(block.height, i as u64 + 1);
+                assert_eq!(
+                    block_hash_for_signing(block),
+                    block_hash_for_signing(reference[i]),
+                    "honest validators diverge at height {}",
+                    block.height
+                );
+                let qc = block.commit_qc.as_ref().expect("block without commit QC");
+                assert_eq!(qc.phase, BftPhase::Commit);
+                assert_eq!(qc.block_hash, block_hash_for_signing(block));
+                qv.validate_qc(qc).expect("commit QC verifies");
+                if i > 0 {
+                    assert_eq!(block.parent_hash, block_hash_for_signing(chain[i - 1]));
+                }
+                let evidence: Vec<String> = block.evidence.iter().map(|e| e.tx_id()).collect();
+                assert!(
+                    block.transactions.iter().all(|t| t.starts_with("tx-")
+                        || t.starts_with("byz-")
+                        || evidence.contains(t)),
+                    "forged block committed at height {}",
+                    block.height
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn in_memory_4_validators_commit_blocks_with_verifiable_qcs() {
+    let mut net = InMemoryNetwork::new(&[Byzantine::No; 4]);
+    net.run_until_height(5);
+    net.assert_honest_agreement(5);
+}
+
+#[test]
+fn in_memory_4_validators_tolerate_silent_leader() {
+    // Validator 0 leads round 0 and never speaks.
+    let mut net = InMemoryNetwork::new(&[
+        Byzantine::Silent,
+        Byzantine::No,
+        Byzantine::No,
+        Byzantine::No,
+    ]);
+    net.run_until_height(5);
+    net.assert_honest_agreement(5);
+}
+
+#[test]
+fn in_memory_4_validators_tolerate_equivocating_leader() {
+    let mut net = InMemoryNetwork::new(&[
+        Byzantine::Equivocate,
+        Byzantine::No,
+        Byzantine::No,
+        Byzantine::No,
+    ]);
+    net.run_until_height(5);
+    net.assert_honest_agreement(5);
+}
+
+#[test]
+fn in_memory_equivocator_is_penalized_on_every_honest_node() {
+    let mut net = InMemoryNetwork::new(&[
+        Byzantine::Equivocate,
+        Byzantine::No,
+        Byzantine::No,
+        Byzantine::No,
+    ]);
+    net.run_until_height(5);
+    net.assert_honest_agreement(5);
+
+    let equivocator = &net.validators[0];
+    for node in net.honest() {
+        let evidence: Vec<_> = node.ledger.values().flat_map(|b| &b.evidence).collect();
+        assert!(!evidence.is_empty(), "equivocation never committed");
+        assert!(evidence.iter().all(|e| e.offender() == equivocator));
+
+        let penalties = node.svc.penalties();
+        assert!(penalties.is_active_penalty(equivocator, 5));
+        assert_eq!(penalties.total_records(), evidence.len());
+        for honest in net.honest() {
+            assert!(penalties.get_records(&honest.svc.node_id()).is_empty());
+        }
+    }
+}
+
+#[test]
+fn in_memory_4_validators_reject_forged_signatures() {
+    let mut net = InMemoryNetwork::new(&[
+        Byzantine::No,
+        Byzantine::Forge,
+        Byzantine::No,
+        Byzantine::No,
+    ]);
+    net.run_until_height(5);
+    net.assert_honest_agreement(5);
+    for node in net.honest() {
+        assert!(node
+            .ledger
+            .values()
+            .all(|b| b.transactions.iter().all(|t| !t.starts_with("forged-"))));
+    }
+}
+
+#[test]
+fn in_memory_7_validators_tolerate_2_byzantine() {
+    let mut net = InMemoryNetwork::new(&[
+        Byzantine::Equivocate,
+        Byzantine::No,
+        Byzantine::No,
+        Byzantine::Silent,
+        Byzantine::No,
+        Byzantine::No,
+        Byzantine::No,
+    ]);
+    net.run_until_height(6);
+    net.assert_honest_agreement(6);
+}
+
+#[test]
+fn in_memory_7_validators_with_forger_and_silent_member() {
+    let mut net = InMemoryNetwork::new(&[
+        Byzantine::No,
+        Byzantine::Forge,
+        Byzantine::No,
+        Byzantine::No,
+        Byzantine::No,
+        Byzantine::Silent,
+        Byzantine::No,
+    ]);
+    net.run_until_height(6);
+    net.assert_honest_agreement(6);
+}
@@ -0,0 +1,523 @@
+//! Property-based safety tests for the BFT pacemaker.
+//!
+//! Three honest validators run `RoundManager`s and a fourth, Byzantine one
+//! (`v3`) sends whatever votes, proposals and timeouts the adversary picks.
+//! The adversary also controls the network: it reorders, drops and
+//! re-delivers messages, and decides when each honest validator's timer
+//! fires. Under every schedule, no two honest validators may commit
+//! different blocks at the same height.
+
+use std::collections::HashSet;
+
+use proptest::prelude::*;
+
+use rust_bc::consensus::bft::quorum::SignatureVerifier;
+use rust_bc::consensus::bft::round::{RoundAction, RoundEvent};
+use rust_bc::consensus::bft::round_manager::{ManagerAction, RoundManager, RoundManagerConfig};
+use rust_bc::consensus::bft::types::{BftPhase, QuorumCertificate, TimeoutMessage, VoteMessage};
+
+/// Accepts any non-empty signature; identities are not at stake here.
+#[derive(Clone)]
+struct TestVerifier;
+
+impl SignatureVerifier for TestVerifier {
+    fn verify(&self, _voter_id: &str, _payload: &[u8], signature: &[u8]) -> bool {
+        !signature.is_empty()
+    }
+}
+
+const HONEST: usize = 3;
+const BYZANTINE: &str = "v3";
+/// How far past the oldest in-flight message the scheduler may reach.
+const REORDER_WINDOW: usize = 4;
+
+fn block(id: u8) -> [u8; 32] {
+    [id; 32]
+}
+
+/// The fresh block honest validator `i` proposes when nothing is prepared.
+fn fresh_block(i: usize) -> [u8; 32] {
+    block(i as u8 + 1)
+}
+
+#[derive(Debug, Clone)]
+enum Msg {
+    Proposal {
+        round: u64,
+        block_hash: [u8; 32],
+        leader_id: String,
+        justify: Option<QuorumCertificate>,
+    },
+    Vote(VoteMessage),
+    Timeout(TimeoutMessage),
+}
+
+struct Honest {
+    id: String,
+    manager: RoundManager<TestVerifier>,
+    /// Set once the validator commits; it then moves to the next height and
+    /// no longer takes part in this one.
+    decided: Option<[u8; 32]>,
+}
+
+struct Sim {
+    nodes: Vec<Honest>,
+    /// Every message ever sent: `(recipient, message, delivered)`.
+    pool: Vec<(usize, Msg, bool)>,
+    /// Prepare QCs formed by honest validators; the adversary may replay
+    /// them as justifications.
+    prepare_qcs: Vec<QuorumCertificate>,
+    /// Commits as `(validator, round, block)`.
+    commits: Vec<(usize, u64, [u8; 32])>,
+}
+
+impl Sim {
+    fn new() -> Self {
+        // Rotation order puts the Byzantine validator second, so it leads
+        // rounds 1 and 5.
+        let validators: Vec<String> = ["v0", BYZANTINE, "v1", "v2"]
+            .iter()
+            .map(|v| v.to_string())
+            .collect();
+        let nodes = (0..HONEST)
+            .map(|i| Honest {
+                id: format!("v{i}"),
+                manager: RoundManager::new(
+                    format!("v{i}"),
+                    validators.clone(),
+                    TestVerifier,
+                    RoundManagerConfig::default(),
+                ),
+                decided: None,
+            })
+            .collect();
+        let mut sim = Self {
+            nodes,
+            pool: Vec::new(),
+            prepare_qcs: Vec::new(),
+            commits: Vec::new(),
+        };
+        for i in 0..HONEST {
+            sim.start(i);
+        }
+        sim
+    }
+
+    fn start(&mut self, i: usize) {
+        self.nodes[i].manager.start();
+        self.propose(i);
+    }
+
+    fn send(&mut self, to: usize, msg: Msg) {
+        self.pool.push((to, msg, false));
+    }
+
+    fn broadcast(&mut self, msg: Msg) {
+        for to in 0..HONEST {
+            self.send(to, msg.clone());
+        }
+    }
+
+    /// Byzantine messages go to one honest validator, or all when `to` is
+    /// out of range, each built for the recipient's current round.
+    fn send_byzantine(&mut self, to: usize, make: impl Fn(u64) -> Msg) {
+        let recipients = if to < HONEST { to..to + 1 } else { 0..HONEST };
+        for recipient in recipients {
+            let msg = make(self.nodes[recipient].manager.current_round());
+            self.send(recipient, msg);
+        }
+    }
+
+    fn deliver(&mut self, index: usize) {
+        if self.pool.is_empty() {
+            return;
+        }
+        let index = index % self.pool.len();
+        self.pool[index].2 = true;
+        let (to, msg, _) = self.pool[index].clone();
+        self.receive(to, msg);
+    }
+
+    /// Deliver undelivered messages matching `filter` until none are left.
+    fn deliver_all(&mut self, filter: impl Fn(usize, &Msg) -> bool) {
+        while let Some(index) = self
+            .pool
+            .iter()
+            .position(|(to, msg, delivered)| !delivered && filter(*to, msg))
+        {
+            self.deliver(index);
+        }
+    }
+
+    fn receive(&mut self, i: usize, msg: Msg) {
+        if self.nodes[i].decided.is_some() {
+            return;
+        }
+        let manager = &mut self.nodes[i].manager;
+        let action = match msg {
+            Msg::Proposal {
+                round,
+                block_hash,
+                leader_id,
+                justify,
+            } => {
+                if round != manager.current_round() {
+                    return;
+                }
+                manager.process_event(RoundEvent::Proposal {
+                    block_hash,
+                    leader_id,
+                    justify,
+                })
+            }
+            Msg::Vote(vote) => manager.process_event(RoundEvent::Vote(vote)),
+            Msg::Timeout(timeout) => manager.process_timeout(timeout),
+        };
+        self.handle(i, action);
+    }
+
+    fn timer_fires(&mut self, i: usize) {
+        if self.nodes[i].decided.is_none() {
+            let action = self.nodes[i].manager.local_timeout();
+            self.handle(i, action);
+        }
+    }
+
+    fn handle(&mut self, i: usize, action: ManagerAction) {
+        match action {
+            ManagerAction::Round(RoundAction::BroadcastProposal { block_hash }) => {
+                let manager = &self.nodes[i].manager;
+                let msg = Msg::Proposal {
+                    round: manager.current_round(),
+                    block_hash,
+                    leader_id: self.nodes[i].id.clone(),
+                    justify: manager.safety().high_qc().cloned(),
+                };
+                self.broadcast(msg);
+                self.vote(i, BftPhase::Prepare, block_hash);
+            }
+            ManagerAction::Round(RoundAction::SendVote(vote)) => {
+                self.vote(i, vote.phase, vote.block_hash)
+            }
+            ManagerAction::Round(RoundAction::PhaseComplete { phase, qc }) => {
+                let next = match phase {
+                    BftPhase::Prepare => {
+                        self.prepare_qcs.push(qc.clone());
+                        BftPhase::PreCommit
+                    }
+                    BftPhase::PreCommit => BftPhase::Commit,
+                    _ => return,
+                };
+                self.vote(i, next, qc.block_hash);
+            }
+            ManagerAction::Round(RoundAction::Decide {
+                block_hash, round, ..
+            }) => {
+                self.nodes[i].decided = Some(block_hash);
+                self.commits.push((i, round, block_hash));
+            }
+            ManagerAction::SendTimeout(mut timeout) => {
+                timeout.signature = vec![1u8; 64];
+                self.broadcast(Msg::Timeout(timeout));
+            }
+            ManagerAction::NewRound { .. } => {
+                let manager = &mut self.nodes[i].manager;
+                let high_qc = manager
+                    .last_timeout_certificate()
+                    .and_then(|tc| tc.high_qc())
+                    .cloned();
+                if let Some(qc) = high_qc {
+                    manager.update_high_qc(qc);
+                }
+                self.propose(i);
+            }
+            ManagerAction::Round(RoundAction::None) | ManagerAction::None => {}
+        }
+    }
+
+    fn vote(&mut self, i: usize, phase: BftPhase, block_hash: [u8; 32]) {
+        let vote = VoteMessage {
+            block_hash,
+            round: self.nodes[i].manager.current_round(),
+            phase,
+            voter_id: self.nodes[i].id.clone(),
+            signature: vec![1u8; 64],
+        };
+        self.broadcast(Msg::Vote(vote));
+    }
+
+    /// Honest leader rule: re-propose the block of the highest QC, or a
+    /// fresh block if none.
+    fn propose(&mut self, i: usize) {
+        let manager = &mut self.nodes[i].manager;
+        if !manager.is_current_leader() {
+            return;
+        }
+        let block_hash = manager
+            .safety()
+            .high_qc()
+            .map_or(fresh_block(i), |qc| qc.block_hash);
+        let action = manager.process_event(RoundEvent::StartAsLeader { block_hash });
+        self.handle(i, action);
+    }
+
+    fn replayed_qc(&self, index: Option<usize>) -> Option<QuorumCertificate> {
+        let index = index?;
+        if self.prepare_qcs.is_empty() {
+            return None;
+        }
+        Some(self.prepare_qcs[index % self.prepare_qcs.len()].clone())
+    }
+
+    fn apply(&mut self, step: Step) {
+        match step {
+            Step::Deliver(index) => {
+                let in_flight: Vec<usize> = (0..self.pool.len())
+                    .filter(|&i| !self.pool[i].2)
+                    .take(REORDER_WINDOW)
+                    .collect();
+                if !in_flight.is_empty() {
+                    self.deliver(in_flight[index % in_flight.len()]);
+                }
+            }
+            Step::Redeliver(index) => self.deliver(index),
+            Step::Drop(to) => {
+                for (recipient, _, delivered) in &mut self.pool {
+                    if *recipient == to {
+                        *delivered = true;
+                    }
+                }
+            }
+            Step::Timeout(i) => self.timer_fires(i),
+            Step::ByzVote {
+                to,
+                phase,
+                block_id,
+            } => {
+                let phase = [BftPhase::Prepare, BftPhase::PreCommit, BftPhase::Commit][phase];
+                self.send_byzantine(to, |round| {
+                    Msg::Vote(VoteMessage {
+                        block_hash: block(block_id),
+                        round,
+                        phase,
+                        voter_id: BYZANTINE.to_string(),
+                        signature: vec![1u8; 64],
+                    })
+                });
+            }
+            Step::ByzEcho { to, back } => {
+                let recent = self
+                    .pool
+                    .iter()
+                    .rev()
+                    .filter_map(|(_, msg, _)| match msg {
+                        Msg::Vote(vote) if vote.voter_id != BYZANTINE => Some(vote),
+                        _ => None,
+                    })
+                    .nth(back);
+                if let Some(vote) = recent {
+                    let vote = VoteMessage {
+                        voter_id: BYZANTINE.to_string(),
+                        ..vote.clone()
+                    };
+                    self.send_byzantine(to, |_| Msg::Vote(vote.clone()));
+                }
+            }
+            Step::ByzPropose {
+                to,
+                block_id,
+                justify,
+            } => {
+                let justify = self.replayed_qc(justify);
+                self.send_byzantine(to, |round| Msg::Proposal {
+                    round,
+                    block_hash: block(block_id),
+                    leader_id: BYZANTINE.to_string(),
+                    justify: justify.clone(),
+                });
+            }
+            Step::ByzTimeout { to, justify } => {
+                let high_qc = self.replayed_qc(justify);
+                self.send_byzantine(to, |round| {
+                    Msg::Timeout(TimeoutMessage {
+                        round,
+                        voter_id: BYZANTINE.to_string(),
+                        high_qc: high_qc.clone(),
+                        signature: vec![1u8; 64],
+                    })
+                });
+            }
+        }
+    }
+
+    /// Safety: all commits are for one block, and no Prepare QC formed in a
+    /// round after a commit is for anything else.
+    fn check_safety(&self) -> Result<(), String> {
+        let committed: HashSet<[u8; 32]> = self.commits.iter().map(|(_, _, b)| *b).collect();
+        if committed.len() > 1 {
+            return Err(format!("conflicting commits: {:?}", self.commits));
+        }
+        for (_, commit_round, committed_block) in &self.commits {
+            for qc in &self.prepare_qcs {
+                if qc.round > *commit_round && qc.block_hash != *committed_block {
+                    return Err(format!(
+                        "prepare QC for another block in round {} after commit in round {}",
+                        qc.round, commit_round
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One adversarial scheduling decision. Byzantine messages target each
+/// recipient's current round; `to == HONEST` sends to everyone.
+#[derive(Debug, Clone)]
+enum Step {
+    /// Deliver one of the oldest messages still in flight.
+    Deliver(usize),
+    /// Deliver an already sent message again.
+    Redeliver(usize),
+    /// Lose every message in flight to one honest validator.
+    Drop(usize),
+    /// An honest validator's round timer fires.
+    Timeout(usize),
+    ByzVote {
+        to: usize,
+        phase: usize,
+        block_id: u8,
+    },
+    /// v3 repeats one of the latest honest votes as its own.
+    ByzEcho {
+        to: usize,
+        back: usize,
+    },
+    /// Only accepted in rounds v3 leads.
+    ByzPropose {
+        to: usize,
+        block_id: u8,
+        justify: Option<usize>,
+    },
+    ByzTimeout {
+        to: usize,
+        justify: Option<usize>,
+    },
+}
+
+fn step() -> impl Strategy<Value = Step> {
+    let to = 0..=HONEST;
+    let justify = proptest::option::of(any::<usize>());
+    prop_oneof![
+        30 => any::<usize>().prop_map(Step::Deliver),
+        1 => any::<usize>().prop_map(Step::Redeliver),
+        1 => (0..HONEST).prop_map(Step::Drop),
+        1 => (0..HONEST).prop_map(Step::Timeout),
+        3 => (to.clone(), 0..3usize, 1..=4u8).prop_map(|(to, phase, block_id)| Step::ByzVote {
+            to,
+            phase,
+            block_id,
+        }),
+        3 => (to.clone(), 0..8usize).prop_map(|(to, back)| Step::ByzEcho { to, back }),
+        1 => (to.clone(), 1..=4u8, justify.clone()).prop_map(|(to, block_id, justify)| {
+            Step::ByzPropose {
+                to,
+                block_id,
+                justify,
+            }
+        }),
+        1 => (to, justify).prop_map(|(to, justify)| Step::ByzTimeout { to, justify }),
+    ]
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(256))]
+
+    #[test]
+    fn no_conflicting_commits_under_adversarial_schedules(
+        steps in proptest::collection::vec(step(), 1..400),
+    ) {
+        let mut sim = Sim::new();
+        for step in steps {
+            sim.apply(step);
+            sim.check_safety().map_err(TestCaseError::fail)?;
+        }
+        // Flush the network: whatever is still in flight must not break
+        // safety either.
+        sim.deliver_all(|_, _| true);
+        sim.check_safety().map_err(TestCaseError::fail)?;
+    }
+}
+
+/// The scenario the locking rule exists for: `v0` commits block A in round 0
+/// while `v1` is locked on A but misses the commit. A Byzantine leader later
+/// proposes B; unlocked `v2` votes for it, locked `v1` does not, so B never
+/// reaches a quorum.
+#[test]
+fn byzantine_leader_cannot_unlock_locked_validator() {
+    let a = fresh_block(0);
+    let b = fresh_block(2);
+    let mut sim = Sim::new();
+    let byz_vote = |phase, round, block_hash| {
+        Msg::Vote(VoteMessage {
+            block_hash,
+            round,
+            phase,
+            voter_id: BYZANTINE.to_string(),
+            signature: vec![1u8; 64],
+        })
+    };
+
+    // Round 0: only v0 and v1 hear the proposal; v3 votes along, but its
+    // Commit vote reaches v0 alone.
+    sim.deliver_all(|to, _| to < 2);
+    for phase in [BftPhase::Prepare, BftPhase::PreCommit] {
+        sim.send(0, byz_vote(phase, 0, a));
+        sim.send(1, byz_vote(phase, 0, a));
+        sim.deliver_all(|to, _| to < 2);
+    }
+    sim.send(0, byz_vote(BftPhase::Commit, 0, a));
+    sim.deliver_all(|to, _| to < 2);
+    assert_eq!(sim.nodes[0].decided, Some(a));
+    assert!(sim.nodes[1].decided.is_none());
+    assert_eq!(
+        sim.nodes[1]
+            .manager
+            .safety()
+            .locked_qc()
+            .unwrap()
+            .block_hash,
+        a
+    );
+
+    // Round 0 times out at v1 and v2 (with v3); v3 leads round 1.
+    sim.timer_fires(1);
+    sim.timer_fires(2);
+    sim.apply(Step::ByzTimeout {
+        to: HONEST,
+        justify: None,
+    });
+    sim.deliver_all(|to, msg| to >= 1 && matches!(msg, Msg::Timeout(_)));
+    assert_eq!(sim.nodes[1].manager.current_round(), 1);
+    assert_eq!(sim.nodes[2].manager.current_round(), 1);
+
+    // v3 proposes B without justification and votes for it.
+    sim.apply(Step::ByzPropose {
+        to: HONEST,
+        block_id: b[0],
+        justify: None,
+    });
+    sim.send(1, byz_vote(BftPhase::Prepare, 1, b));
+    sim.send(2, byz_vote(BftPhase::Prepare, 1, b));
+    sim.deliver_all(|to, _| to >= 1);
+
+    let voted_for_b = |voter: &str| {
+        sim.pool.iter().any(|(_, msg, _)| {
+            matches!(msg, Msg::Vote(v) if v.voter_id == voter && v.block_hash == b && v.round == 1)
+        })
+    };
+    assert!(voted_for_b("v2"), "unlocked validator votes for B");
+    assert!(!voted_for_b("v1"), "locked validator refuses B");
+    assert!(sim.commits.iter().all(|(_, _, h)| *h == a));
+    sim.check_safety().unwrap();
+}
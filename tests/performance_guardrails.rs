@@ -31,6 +31,13 @@ fn make_block(height: u64, signer: &dyn SigningProvider) -> Block {
         secondary_signature_algorithm: None,
         hash_algorithm: HashAlgorithm::Sha3_256,
         orderer_signature: None,
+        commit_qc: None,
+        next_validator_set: None,
+        evidence: Vec::new(),
+        config_tx: None,
+        last_config: 0,
+        state_root: [0u8; 32],
+        private_data_purges: vec![],
     }
 }
 
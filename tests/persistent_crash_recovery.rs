@@ -46,6 +46,13 @@ fn make_pqc_block(height: u64, signer: &dyn SigningProvider) -> Block {
         secondary_signature_algorithm: None,
         hash_algorithm: HashAlgorithm::Sha3_256,
         orderer_signature: None,
+        commit_qc: None,
+        next_validator_set: None,
+        evidence: Vec::new(),
+        config_tx: None,
+        last_config: 0,
+        state_root: [0u8; 32],
+        private_data_purges: vec![],
     }
 }
 
@@ -428,3 +435,52 @@ fn persistent_node_preserves_dual_signature_metadata() {
         assert_eq!(block.secondary_signature.unwrap().len(), 64);
     }
 }
+
+// ═══════════════════════════════════════════════════════════════════
+// TEST: Block, transactions and state commit together
+// ═══════════════════════════════════════════════════════════════════
+
+#[test]
+fn committed_state_matches_blocks_after_restart() {
+    use rust_bc::storage::commit::StateWrite;
+    use rust_bc::storage::traits::Transaction;
+
+    let dir = tempfile::tempdir().expect("create temp dir");
+    let db_path = dir.path().join("atomic_commit");
+    let signer = MlDsaSigningProvider::generate();
+
+    {
+        let store = RocksDbBlockStore::new(&db_path).expect("open DB");
+        for h in 0..5u64 {
+            let block = make_pqc_block(h, &signer);
+            let tx = Transaction {
+                id: format!("tx-{h}"),
+                block_height: h,
+                timestamp: h * 6,
+                input_did: String::new(),
+                output_recipient: String::new(),
+                amount: 0,
+                state: "committed".to_string(),
+            };
+            let write = StateWrite::put(&tx.id, "counter", h.to_string().as_bytes());
+            store
+                .commit_block(&block, &[tx], &[write])
+                .expect("commit block");
+        }
+    }
+
+    let store = RocksDbBlockStore::new(&db_path).expect("reopen DB after crash");
+    let report = store.recover_state().expect("recover state");
+    assert!(
+        report.is_clean(),
+        "atomic commits need no repair: {report:?}"
+    );
+    assert_eq!(store.state_savepoint().unwrap(), Some(4));
+    assert_eq!(store.get_latest_height().unwrap(), 4);
+
+    let counter = store.world_state_get("counter").unwrap().unwrap();
+    assert_eq!(counter.version, 5);
+    assert_eq!(counter.data, b"4");
+    assert_eq!(store.get_history("counter").unwrap().len(), 5);
+    assert_eq!(store.transactions_by_block_height(4).unwrap()[0].id, "tx-4");
+}
@@ -399,6 +399,13 @@ fn block_stores_hash_algorithm_field() {
         secondary_signature_algorithm: None,
         hash_algorithm: HashAlgorithm::Sha3_256,
         orderer_signature: None,
+        commit_qc: None,
+        next_validator_set: None,
+        evidence: Vec::new(),
+        config_tx: None,
+        last_config: 0,
+        state_root: [0u8; 32],
+        private_data_purges: vec![],
     };
 
     // Serialize and deserialize — hash_algorithm must survive
@@ -427,6 +434,13 @@ fn legacy_block_without_hash_algorithm_defaults_to_sha256() {
         secondary_signature_algorithm: None,
         hash_algorithm: HashAlgorithm::Sha256,
         orderer_signature: None,
+        commit_qc: None,
+        next_validator_set: None,
+        evidence: Vec::new(),
+        config_tx: None,
+        last_config: 0,
+        state_root: [0u8; 32],
+        private_data_purges: vec![],
     };
     let full_json = serde_json::to_string(&block).unwrap();
     // Strip the hash_algorithm field to simulate a legacy block
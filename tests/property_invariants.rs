@@ -144,6 +144,13 @@ proptest! {
             secondary_signature_algorithm: None,
             hash_algorithm: hash_algo,
             orderer_signature: None,
+            commit_qc: None,
+            next_validator_set: None,
+            evidence: Vec::new(),
+            config_tx: None,
+            last_config: 0,
+            state_root: [0u8; 32],
+            private_data_purges: vec![],
         };
 
         let json = serde_json::to_string(&block).unwrap();
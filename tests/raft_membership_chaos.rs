@@ -0,0 +1,161 @@
+//! Chaos test for dynamic Raft membership.
+//!
+//! Starts a three-orderer cluster and rotates every orderer out, one at a
+//! time, via the same membership changes a consenter config update produces:
+//! add the replacement as a learner, promote it once caught up, then remove
+//! the old orderer. Messages are dropped at random throughout.
+
+#![cfg(feature = "raft-ordering")]
+
+use std::collections::HashSet;
+
+use raft::prelude::Message;
+use rust_bc::ordering::raft_node::{MembershipChange, RaftNode};
+
+/// Seeded deterministic RNG for reproducible chaos.
+struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        // xorshift64
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    fn should_drop(&mut self, drop_rate: f64) -> bool {
+        (self.next_u64() as f64) / (u64::MAX as f64) < drop_rate
+    }
+}
+
+const DROP_RATE: f64 = 0.05;
+const MAX_ROUNDS: usize = 2_000;
+
+/// Tick every node once and deliver the resulting messages (and their
+/// responses) until the network is quiet, dropping some at random.
+fn route_round(nodes: &mut [RaftNode], rng: &mut SeededRng) {
+    let mut pending: Vec<Message> = Vec::new();
+    for node in nodes.iter_mut() {
+        node.tick();
+        pending.extend(node.advance().0);
+    }
+    while !pending.is_empty() {
+        let mut next = Vec::new();
+        for msg in pending {
+            if rng.should_drop(DROP_RATE) {
+                continue;
+            }
+            if let Some(target) = nodes.iter_mut().find(|n| n.id == msg.to) {
+                let _ = target.step(msg);
+                next.extend(target.advance().0);
+            }
+        }
+        pending = next;
+    }
+}
+
+fn leader(nodes: &mut [RaftNode]) -> Option<&mut RaftNode> {
+    nodes.iter_mut().find(|n| n.is_leader())
+}
+
+fn voters(node: &RaftNode) -> HashSet<u64> {
+    node.conf_state().voters.into_iter().collect()
+}
+
+#[test]
+fn rotating_every_orderer_keeps_the_cluster_live() {
+    let mut rng = SeededRng::new(0x5eed_cafe);
+    let founders = vec![1, 2, 3];
+    let mut nodes: Vec<RaftNode> = founders
+        .iter()
+        .map(|&id| RaftNode::new(id, founders.clone()).unwrap())
+        .collect();
+
+    let mut rounds = 0;
+    while leader(&mut nodes).is_none() {
+        route_round(&mut nodes, &mut rng);
+        rounds += 1;
+        assert!(rounds < MAX_ROUNDS, "no initial leader elected");
+    }
+
+    for (old, new) in [(1u64, 4u64), (2, 5), (3, 6)] {
+        nodes.push(RaftNode::new(new, vec![]).unwrap());
+        let changes = [
+            MembershipChange::AddLearner {
+                id: new,
+                address: format!("orderer{new}:8087"),
+            },
+            MembershipChange::Promote(new),
+            MembershipChange::Remove(old),
+        ];
+        for node in nodes.iter_mut() {
+            for change in &changes {
+                node.queue_membership_change(change.clone());
+            }
+        }
+
+        let mut rounds = 0;
+        loop {
+            route_round(&mut nodes, &mut rng);
+            rounds += 1;
+            assert!(
+                rounds < MAX_ROUNDS,
+                "rotation {old}->{new} did not converge"
+            );
+            // Keep the log moving while membership changes are in flight.
+            if rounds % 10 == 0 {
+                if let Some(l) = leader(&mut nodes) {
+                    let _ = l.propose(format!("rotate-{old}-{new}-{rounds}").into_bytes());
+                }
+            }
+            let survivors_done = nodes.iter().filter(|n| n.id != old).all(|n| {
+                let v = voters(n);
+                n.pending_membership_changes() == 0 && v.contains(&new) && !v.contains(&old)
+            });
+            if survivors_done {
+                break;
+            }
+        }
+        // The removed orderer is decommissioned.
+        nodes.retain(|n| n.id != old);
+    }
+
+    let expected: HashSet<u64> = [4, 5, 6].into_iter().collect();
+    for node in &nodes {
+        assert_eq!(voters(node), expected, "node {} voters", node.id);
+        assert!(node.conf_state().learners.is_empty());
+    }
+
+    // The fully rotated cluster still elects a leader and commits entries.
+    let mut rounds = 0;
+    while leader(&mut nodes).is_none() {
+        route_round(&mut nodes, &mut rng);
+        rounds += 1;
+        assert!(rounds < MAX_ROUNDS, "rotated cluster has no leader");
+    }
+    for node in nodes.iter_mut() {
+        node.committed_entries.clear();
+    }
+    let payload = b"after-rotation".to_vec();
+    leader(&mut nodes)
+        .unwrap()
+        .propose(payload.clone())
+        .unwrap();
+
+    let mut rounds = 0;
+    while !nodes
+        .iter()
+        .all(|n| n.committed_entries.iter().any(|e| e.data == payload))
+    {
+        route_round(&mut nodes, &mut rng);
+        rounds += 1;
+        assert!(rounds < MAX_ROUNDS, "post-rotation entry not committed");
+    }
+}
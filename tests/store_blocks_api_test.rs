@@ -230,3 +230,81 @@ async fn latest_route_not_confused_with_height_param() {
         "latest route must resolve before /{{height}}"
     );
 }
+
+// ── finality ─────────────────────────────────────────────────────────────────
+
+/// AppState over two blocks with a one-block depth rule: height 0 is final.
+fn make_state_with_depth_one() -> AppState {
+    let mut state = make_state(store_with_two_blocks());
+    state.finality = Arc::new(rust_bc::consensus::finality::FinalityTracker::new(1));
+    state
+}
+
+#[actix_web::test]
+async fn blocks_list_flags_finalized_blocks() {
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(make_state_with_depth_one()))
+            .configure(ApiRoutes::configure),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/api/v1/blocks").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status().as_u16(), 200);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let blocks = body["data"].as_array().expect("block list");
+    let flags: Vec<(u64, bool)> = blocks
+        .iter()
+        .map(|b| {
+            (
+                b["height"].as_u64().unwrap(),
+                b["finalized"].as_bool().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(flags, vec![(0, true), (1, false)]);
+}
+
+#[actix_web::test]
+async fn block_by_index_carries_finalized_flag() {
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(make_state_with_depth_one()))
+            .configure(ApiRoutes::configure),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/v1/blocks/index/1")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status().as_u16(), 200);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["data"]["height"], 1);
+    assert_eq!(body["data"]["finalized"], false);
+}
+
+#[actix_web::test]
+async fn finalized_endpoint_reports_depth_rule() {
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(make_state_with_depth_one()))
+            .configure(ApiRoutes::configure),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/v1/blocks/finalized")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status().as_u16(), 200);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["data"]["tip"], 1);
+    assert_eq!(body["data"]["finalized_height"], 0);
+    assert_eq!(body["data"]["depth"], 1);
+    assert!(body["data"]["qc_finalized_height"].is_null());
+}